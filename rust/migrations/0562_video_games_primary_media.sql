@@ -0,0 +1,21 @@
+-- Migration: 0562_video_games_primary_media.sql
+-- Purpose: Record each game's primary cover as a pointer into canonical_media so read
+--          paths don't have to re-rank every media row. Maintained by the ingest
+--          pipeline (database_ops::media_primary) after media links are written.
+-- Idempotent: Uses ADD COLUMN IF NOT EXISTS.
+
+ALTER TABLE IF EXISTS public.video_games
+  ADD COLUMN IF NOT EXISTS primary_media_id bigint
+    REFERENCES public.canonical_media(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_video_games_primary_media_id
+  ON public.video_games (primary_media_id)
+  WHERE primary_media_id IS NOT NULL;
+
+-- Media rows are looked up per game through metadata->>'video_game_id'.
+CREATE INDEX IF NOT EXISTS idx_canonical_media_video_game_id
+  ON public.canonical_media ((metadata->>'video_game_id'))
+  WHERE metadata ? 'video_game_id';
+
+COMMENT ON COLUMN public.video_games.primary_media_id IS
+  'canonical_media row chosen as the primary cover (role priority, resolution, then provider preference)';
@@ -0,0 +1,24 @@
+-- Migration: 0563_nexarda_store_registrations.sql
+-- Purpose: Audit trail of stores the Nexarda provider auto-registered (or held back
+--          because they were not in NEXARDA_REGISTER_ALLOWLIST), one batch per ingest run.
+--          Written by database_ops::nexarda::provider::NexardaProvider::ingest_to_db.
+-- Idempotent: Uses IF NOT EXISTS.
+
+CREATE TABLE IF NOT EXISTS public.nexarda_store_registrations (
+  id bigserial PRIMARY KEY,
+  run_id uuid NOT NULL,
+  store_name text NOT NULL,
+  normalized_name text NOT NULL,
+  currency text NOT NULL,
+  store_id text NOT NULL,
+  region_code text NOT NULL,
+  registered boolean NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_nexarda_store_registrations_pending
+  ON public.nexarda_store_registrations (normalized_name, currency)
+  WHERE NOT registered;
+
+COMMENT ON COLUMN public.nexarda_store_registrations.registered IS
+  'false when the store was held back for review by NEXARDA_REGISTER_ALLOWLIST';
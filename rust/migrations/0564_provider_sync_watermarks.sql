@@ -0,0 +1,16 @@
+-- Migration: 0564_provider_sync_watermarks.sql
+-- Purpose: Per-provider incremental sync watermarks (e.g. the highest IGDB `updated_at`
+--          already ingested), so steady-state runs only fetch records changed since.
+--          Read and advanced by database_ops::sync_watermark.
+-- Idempotent: Uses IF NOT EXISTS.
+
+CREATE TABLE IF NOT EXISTS public.provider_sync_watermarks (
+  provider_id bigint NOT NULL REFERENCES public.providers(id) ON DELETE CASCADE,
+  sync_kind text NOT NULL,
+  watermark timestamptz NOT NULL,
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  PRIMARY KEY (provider_id, sync_kind)
+);
+
+COMMENT ON COLUMN public.provider_sync_watermarks.sync_kind IS
+  'Dataset the watermark applies to (e.g. games_updated_at)';
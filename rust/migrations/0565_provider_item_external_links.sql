@@ -0,0 +1,27 @@
+-- Migration: 0565_provider_item_external_links.sql
+-- Purpose: Store ids other stores use for a catalogue item (IGDB `external_games`: Steam
+--          appids, PlayStation Store and Microsoft Store product ids) and the matching
+--          storefront provider_items row once it exists, so cross-provider matching can use
+--          exact ids instead of fuzzy titles.
+--          Written by database_ops::igdb::external_games::link_external_games.
+-- Idempotent: Uses IF NOT EXISTS.
+
+CREATE TABLE IF NOT EXISTS public.provider_item_external_links (
+  provider_item_id bigint NOT NULL REFERENCES public.provider_items(id) ON DELETE CASCADE,
+  provider_slug text NOT NULL,
+  external_uid text NOT NULL,
+  linked_provider_item_id bigint REFERENCES public.provider_items(id) ON DELETE SET NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  PRIMARY KEY (provider_item_id, provider_slug, external_uid)
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_item_external_links_lookup
+  ON public.provider_item_external_links (provider_slug, external_uid);
+
+CREATE INDEX IF NOT EXISTS idx_provider_item_external_links_linked
+  ON public.provider_item_external_links (linked_provider_item_id)
+  WHERE linked_provider_item_id IS NOT NULL;
+
+COMMENT ON COLUMN public.provider_item_external_links.provider_slug IS
+  'providers.slug of the store the id belongs to (steam-store, ps-store, xbox-store)';
@@ -0,0 +1,15 @@
+-- Migration: 0566_external_links_video_game.sql
+-- Purpose: Record the canonical video game of the catalogue item that owns each external id
+--          link, so a storefront ingesting a Steam appid or PlayStation Store product id can
+--          resolve the game directly (normalization::resolve_by_external_id).
+-- Idempotent: Uses IF NOT EXISTS.
+
+ALTER TABLE public.provider_item_external_links
+  ADD COLUMN IF NOT EXISTS video_game_id bigint;
+
+CREATE INDEX IF NOT EXISTS idx_provider_item_external_links_video_game
+  ON public.provider_item_external_links (provider_slug, external_uid, video_game_id)
+  WHERE video_game_id IS NOT NULL;
+
+COMMENT ON COLUMN public.provider_item_external_links.video_game_id IS
+  'video_games.id of the linking catalogue item (e.g. the IGDB game) when known';
@@ -0,0 +1,16 @@
+-- Migration: 0567_psstore_ingest_cursor.sql
+-- Purpose: Last fully-processed category page per PS Store locale and category, so
+--          psstore_seed_pipeline resumes after a crash instead of re-walking every page.
+--          Read and written by database_ops::playstation::cursor.
+-- Idempotent: Uses IF NOT EXISTS.
+
+CREATE TABLE IF NOT EXISTS public.psstore_ingest_cursor (
+  locale text NOT NULL,
+  category_id text NOT NULL,
+  page integer NOT NULL,
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  PRIMARY KEY (locale, category_id)
+);
+
+COMMENT ON COLUMN public.psstore_ingest_cursor.page IS
+  'Last category page whose batch was committed; the next run starts at page + 1';
@@ -0,0 +1,21 @@
+-- Migration: 0568_external_ratings.sql
+-- Purpose: One row per game and rating source (PS Store stars, Metacritic, Steam reviews,
+--          IGDB) on the source's own scale, so the game profile can show every rating side
+--          by side. Written by database_ops::external_ratings::upsert_external_rating;
+--          video_games.average_rating stays as the denormalized single value.
+-- Idempotent: Uses IF NOT EXISTS.
+
+CREATE TABLE IF NOT EXISTS public.external_ratings (
+  video_game_id bigint NOT NULL,
+  source text NOT NULL,
+  score double precision NOT NULL,
+  scale double precision NOT NULL,
+  rating_count bigint,
+  updated_at timestamptz NOT NULL DEFAULT now(),
+  PRIMARY KEY (video_game_id, source)
+);
+
+COMMENT ON COLUMN public.external_ratings.source IS
+  'ps-store, metacritic, steam or igdb';
+COMMENT ON COLUMN public.external_ratings.scale IS
+  'Maximum score on the source scale (5 for PS Store stars, 100 otherwise)';
@@ -0,0 +1,11 @@
+-- Migration: 0569_video_games_meta_score.sql
+-- Purpose: Aggregate 0-100 score across external_ratings sources, weighted by source
+--          (META_SCORE_WEIGHTS) and rating count. Recomputed by
+--          database_ops::external_ratings whenever a source's rating changes.
+-- Idempotent: Uses IF NOT EXISTS.
+
+ALTER TABLE public.video_games
+  ADD COLUMN IF NOT EXISTS meta_score double precision;
+
+COMMENT ON COLUMN public.video_games.meta_score IS
+  'Weighted mean of external_ratings scores normalized to 0-100; NULL without ratings';
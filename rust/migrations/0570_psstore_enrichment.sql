@@ -0,0 +1,15 @@
+-- Migration: 0570_psstore_enrichment.sql
+-- Purpose: When each PS Store product was last detail/rating-enriched per locale, so an
+--          interrupted psstore_seed_pipeline run skips products enriched within
+--          PS_ENRICH_MAX_AGE_HOURS. Read and written by database_ops::playstation::enrichment.
+-- Idempotent: Uses IF NOT EXISTS.
+
+CREATE TABLE IF NOT EXISTS public.psstore_enrichment (
+  locale text NOT NULL,
+  product_id text NOT NULL,
+  enriched_at timestamptz NOT NULL DEFAULT now(),
+  PRIMARY KEY (locale, product_id)
+);
+
+COMMENT ON COLUMN public.psstore_enrichment.enriched_at IS
+  'Last time the product detail payload was fetched and its rows written for this locale';
@@ -34,68 +34,8 @@ pub struct OfferJurisdictionIn {
     pub currency_id: i64,
 }
 
-#[derive(Clone, Debug)]
-pub struct PsConfig {
-    pub base_url: String,
-    pub bearer: Option<String>,
-    pub locales: Vec<String>, // e.g. ["en-us", "en-gb"]
-    pub rps: u32,
-    pub extra_headers: HashMap<String, String>,
-}
-
-impl Default for PsConfig {
-    fn default() -> Self {
-        Self {
-            base_url: "https://web.np.playstation.com/api/graphql/v1/".into(),
-            bearer: None,
-            locales: vec!["en-us".into()],
-            rps: 3,
-            extra_headers: HashMap::new(),
-        }
-    }
-}
-impl PsConfig {
-    pub fn basic_config() -> PsConfig {
-        let mut config = PsConfig {
-            base_url: "https://web.np.playstation.com/api/graphql/v1/",
-            bearer: None,
-            country_w_language: vec![
-                "en-us",
-                "en-gb",
-                "fr-fr",
-                "de-de",
-                "es-es",
-                "it-it",
-                "ja-jp",
-                "en-au",
-                "ca-en",
-                "pt-br",
-                "es-mx",
-                "ru-ru",
-                "ar-sa",
-                "en-za",
-                "en-ch",
-                "fr-ch",
-                "it-ch",
-                "de-ch",
-                "zh-tw",
-                "zh-cn",
-                "ko-kr",
-                "nl-nl",
-                "sv-se",
-                "da-dk",
-                "no-no",
-                "fi-fi",
-                "pl-pl",
-                "pt-pt",
-                "tr-tr"
-            ],
-            rps: 3_u32,
-            extra_headers: None,
-        };
-        return config;
-    }
-}
+// The one PsConfig lives in src/root.rs; re-exported so this module can't drift from it.
+pub use crate::root::PsConfig;
 
 #[derive(Error, Debug)]
 pub enum PsError {
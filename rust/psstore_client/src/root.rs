@@ -1,4 +1,5 @@
-use std::{ collections::HashMap, sync::Arc, time::Duration };
+use std::{ collections::{ HashMap, VecDeque }, sync::{ Arc, OnceLock }, time::Duration };
+use std::sync::atomic::{ AtomicU64, Ordering };
 use std::fs;
 use std::io::Write;
 use std::path::{ Path, PathBuf };
@@ -14,8 +15,8 @@ use hickory_resolver::{
 use thiserror::Error;
 use tokio::sync::Mutex;
 use dotenv::dotenv;
-use reqwest::header::{ HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE };
-// backoff not currently used; simple retry implemented
+use reqwest::header::{ HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, ETAG, IF_NONE_MATCH };
+use backoff::{ backoff::Backoff, ExponentialBackoff };
 use serde_json::Value;
 use serde::{ Serialize, Deserialize };
 use tracing::{ info, warn, error, debug };
@@ -67,17 +68,28 @@ pub fn get_env(key: &str) -> String {
 
 #[derive(Clone, Debug)]
 pub struct PsConfig {
+    // GraphQL endpoint; PS_BASE_URL overrides the default (e.g. to point at a stub store).
     pub base_url: String,
     pub bearer: Option<String>,
     pub locales: Vec<String>,
     pub rps: u32,
     pub extra_headers: HashMap<String, String>,
+    // Retry policy: attempts include the first request; delays double from the base delay,
+    // spread by +/- retry_jitter_ratio.
     pub retry_attempts: u32,
     pub retry_base_delay_ms: u64,
+    pub retry_jitter_ratio: f64,
+    // Per-endpoint attempt budgets; None falls back to retry_attempts.
+    pub detail_retry_attempts: Option<u32>,
+    pub grid_retry_attempts: Option<u32>,
+    // Conditional requests (If-None-Match) for product detail and category grid fetches.
+    pub enable_etag_cache: bool,
     pub cookie: Option<String>,
     // IPv6/Proxy opts
     pub ipv6_only: bool,
     pub proxy: Option<String>,
+    // Persisted query hash for categoryGridRetrieve; None keeps the env/dynamic lookup.
+    pub grid_hash: Option<String>,
 }
 
 impl Default for PsConfig {
@@ -114,6 +126,23 @@ impl Default for PsConfig {
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(2000);
+        let retry_jitter_ratio = std::env
+            ::var("PS_RETRY_JITTER_RATIO")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0.0);
+        let detail_retry_attempts = std::env
+            ::var("PS_DETAIL_RETRY_ATTEMPTS")
+            .ok()
+            .and_then(|s| s.parse().ok());
+        let grid_retry_attempts = std::env
+            ::var("PS_GRID_RETRY_ATTEMPTS")
+            .ok()
+            .and_then(|s| s.parse().ok());
+        let enable_etag_cache = match std::env::var("PS_ETAG_CACHE").ok().as_deref() {
+            Some(v) if v == "0" || v.eq_ignore_ascii_case("false") || v.eq_ignore_ascii_case("no") || v.eq_ignore_ascii_case("off") => false,
+            _ => true,
+        };
 
         // Optional cookie bootstrap (Akamai/_abck/bm values captured from browser)
         let cookie = std::env
@@ -152,16 +181,85 @@ impl Default for PsConfig {
             }
         };
         Self {
-            base_url: "https://web.np.playstation.com/api/graphql/v1/".into(),
+            base_url: std::env
+                ::var("PS_BASE_URL")
+                .unwrap_or_else(|_| "https://web.np.playstation.com/api/graphql/v1/".into()),
             bearer,
             locales: regions,
             rps,
             extra_headers: HashMap::new(),
             retry_attempts,
             retry_base_delay_ms,
+            retry_jitter_ratio,
+            detail_retry_attempts,
+            grid_retry_attempts,
+            enable_etag_cache,
             cookie,
             ipv6_only,
             proxy: std::env::var("PS_PROXY").ok(),
+            grid_hash: None,
+        }
+    }
+}
+
+impl PsConfig {
+    /// Set the default retry policy used by every operation without its own budget.
+    pub fn with_retry(mut self, attempts: u32, base_delay_ms: u64, jitter_ratio: f64) -> Self {
+        self.retry_attempts = attempts;
+        self.retry_base_delay_ms = base_delay_ms;
+        self.retry_jitter_ratio = jitter_ratio;
+        self
+    }
+
+    /// Attempt budget for product detail lookups (metGetProductById), which are expensive.
+    pub fn with_detail_retry_attempts(mut self, attempts: u32) -> Self {
+        self.detail_retry_attempts = Some(attempts);
+        self
+    }
+
+    /// Attempt budget for category grid pages (categoryGridRetrieve), which are cheap.
+    pub fn with_grid_retry_attempts(mut self, attempts: u32) -> Self {
+        self.grid_retry_attempts = Some(attempts);
+        self
+    }
+
+    /// Retry policy for a persisted operation: its endpoint budget if one is set, otherwise
+    /// `retry_attempts`.
+    pub fn retry_policy_for(&self, operation_name: &str) -> RetryPolicy {
+        let endpoint_attempts = match operation_name {
+            "metGetProductById" => self.detail_retry_attempts,
+            "categoryGridRetrieve" => self.grid_retry_attempts,
+            _ => None,
+        };
+        RetryPolicy {
+            attempts: endpoint_attempts.unwrap_or(self.retry_attempts).max(1),
+            base_delay_ms: self.retry_base_delay_ms.max(1),
+            jitter_ratio: self.retry_jitter_ratio,
+        }
+    }
+}
+
+/// Retry budget for one request: total attempts and the exponential backoff between them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    pub base_delay_ms: u64,
+    pub jitter_ratio: f64,
+}
+
+impl RetryPolicy {
+    /// Backoff that starts at the base delay and doubles, never giving up on elapsed time
+    /// (the attempt budget bounds retries instead).
+    pub fn backoff(&self) -> ExponentialBackoff {
+        let initial = Duration::from_millis(self.base_delay_ms.max(1));
+        ExponentialBackoff {
+            current_interval: initial,
+            initial_interval: initial,
+            randomization_factor: self.jitter_ratio.clamp(0.0, 1.0),
+            multiplier: 2.0,
+            max_interval: Duration::from_secs(300),
+            max_elapsed_time: None,
+            ..ExponentialBackoff::default()
         }
     }
 }
@@ -188,13 +286,81 @@ pub enum PsError {
     #[error("other: {0}")] Other(String),
 }
 
+/// Running totals for one [`PsStoreClient`] and its clones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PsClientStats {
+    /// HTTP requests sent, counting each retry attempt.
+    pub requests: u64,
+    /// Decoded (post gzip/brotli/deflate) response body bytes.
+    pub bytes_in: u64,
+    /// Responses served from the disk cache or replayed on 304 Not Modified.
+    pub cache_hits: u64,
+}
+
+#[derive(Default)]
+struct PsClientCounters {
+    requests: AtomicU64,
+    bytes_in: AtomicU64,
+    cache_hits: AtomicU64,
+}
+
+/// Entries kept in the process-wide ETag cache unless `PS_ETAG_CACHE_MAX` says otherwise.
+const ETAG_CACHE_CAPACITY: usize = 20_000;
+
+/// Last ETag and body per request key, oldest entry evicted first once `capacity` is hit.
+struct EtagCache {
+    capacity: usize,
+    entries: HashMap<String, (String, Value)>,
+    order: VecDeque<String>,
+}
+
+impl EtagCache {
+    fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), entries: HashMap::new(), order: VecDeque::new() }
+    }
+
+    fn get(&self, key: &str) -> Option<&(String, Value)> {
+        self.entries.get(key)
+    }
+
+    fn insert(&mut self, key: String, entry: (String, Value)) {
+        if self.entries.insert(key.clone(), entry).is_none() {
+            self.order.push_back(key);
+        }
+        while self.entries.len() > self.capacity {
+            match self.order.pop_front() {
+                Some(oldest) => {
+                    self.entries.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+/// One ETag cache for the whole process. Pipelines build a fresh client per locale and
+/// run, so a per-client cache would be empty on every tick.
+fn shared_etag_cache() -> Arc<Mutex<EtagCache>> {
+    static CACHE: OnceLock<Arc<Mutex<EtagCache>>> = OnceLock::new();
+    CACHE.get_or_init(|| {
+        let capacity = std::env
+            ::var("PS_ETAG_CACHE_MAX")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(ETAG_CACHE_CAPACITY);
+        Arc::new(Mutex::new(EtagCache::new(capacity)))
+    }).clone()
+}
+
 #[derive(Clone)]
 pub struct PsStoreClient {
     http: Client,
     cfg: Arc<PsConfig>,
     limiter: Arc<RateLimiter<String, DashMapStateStore<String>, DefaultClock>>,
-    #[allow(dead_code)]
-    etags: Arc<Mutex<HashMap<String, String>>>,
+    // Last ETag and body per (base url, operation, locale, variables), replayed on 304 Not
+    // Modified; shared by every client in the process.
+    etags: Arc<Mutex<EtagCache>>,
+    counters: Arc<PsClientCounters>,
     #[allow(dead_code)]
     resolver_v6: Option<TokioAsyncResolver>,
 }
@@ -627,11 +793,21 @@ impl PsStoreClient {
             http,
             cfg: Arc::new(cfg),
             limiter: Arc::new(limiter),
-            etags: Arc::new(Mutex::new(HashMap::new())),
+            etags: shared_etag_cache(),
+            counters: Arc::new(PsClientCounters::default()),
             resolver_v6,
         }
     }
 
+    /// Requests sent, decoded bytes received and cache hits so far, across all clones.
+    pub fn stats(&self) -> PsClientStats {
+        PsClientStats {
+            requests: self.counters.requests.load(Ordering::Relaxed),
+            bytes_in: self.counters.bytes_in.load(Ordering::Relaxed),
+            cache_hits: self.counters.cache_hits.load(Ordering::Relaxed),
+        }
+    }
+
     /// Normalize arbitrary locale strings to the ll-CC form used in headers and hash maps.
     /// Examples: "en-us" → "en-US", "en_US" → "en-US", "de-de" → "de-DE".
     fn normalize_locale_for_hash(locale: &str) -> String {
@@ -734,6 +910,58 @@ impl PsStoreClient {
         operation_name: &str,
         variables: &Value,
         locale: Option<&str>
+    ) -> Result<Value, PsError> {
+        self.op_get_inner(operation_name, variables, locale, false).await
+    }
+
+    /// Like `op_get`, but revalidates with If-None-Match when an ETag is cached for the same
+    /// operation, locale and variables, and returns the cached body on 304 Not Modified.
+    /// Behaves exactly like `op_get` when `PsConfig::enable_etag_cache` is off.
+    async fn op_get_conditional(
+        &self,
+        operation_name: &str,
+        variables: &Value,
+        locale: Option<&str>
+    ) -> Result<Value, PsError> {
+        self.op_get_inner(operation_name, variables, locale, self.cfg.enable_etag_cache).await
+    }
+
+    /// If-None-Match header for `etag_key`, empty when nothing is cached.
+    async fn conditional_headers(&self, etag_key: Option<&str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        if let Some(key) = etag_key {
+            if let Some((etag, _)) = self.etags.lock().await.get(key) {
+                if let Ok(value) = HeaderValue::from_str(etag) {
+                    headers.insert(IF_NONE_MATCH, value);
+                }
+            }
+        }
+        headers
+    }
+
+    async fn etag_cached_body(&self, etag_key: Option<&str>) -> Option<Value> {
+        let key = etag_key?;
+        self.etags
+            .lock().await
+            .get(key)
+            .map(|(_, body)| body.clone())
+    }
+
+    /// Remember a successful, error-free response under its ETag.
+    async fn remember_etag(&self, etag_key: Option<&str>, etag: Option<String>, v: &Value) {
+        if let (Some(key), Some(etag)) = (etag_key, etag) {
+            if v.get("errors").is_none() {
+                self.etags.lock().await.insert(key.to_string(), (etag, v.clone()));
+            }
+        }
+    }
+
+    async fn op_get_inner(
+        &self,
+        operation_name: &str,
+        variables: &Value,
+        locale: Option<&str>,
+        use_etag: bool
     ) -> Result<Value, PsError> {
         let url = self.url("op");
         let vars_string = variables.to_string();
@@ -743,7 +971,6 @@ impl PsStoreClient {
         // locale_use is defined below; compute effective_sha after that
 
         // Basic metrics counters
-        use std::sync::atomic::{ AtomicU64, Ordering };
         static TOTAL_REQUESTS: AtomicU64 = AtomicU64::new(0);
         static HASH_MISMATCHES: AtomicU64 = AtomicU64::new(0);
         let total_after = TOTAL_REQUESTS.fetch_add(1, Ordering::Relaxed) + 1;
@@ -753,9 +980,22 @@ impl PsStoreClient {
         let locale_use = locale
             .or_else(|| self.cfg.locales.get(0).map(|s| s.as_str()))
             .unwrap_or("en-us");
+        let etag_key = use_etag.then(||
+            format!(
+                "{}|{}|{}|{}",
+                self.cfg.base_url,
+                operation_name,
+                normalize_locale_key(locale_use),
+                vars_string
+            )
+        );
 
         // Compute effective sha now that locale_use is known
-        let effective_sha = Self::persisted_hash_for(operation_name, locale_use);
+        let effective_sha = match self.cfg.grid_hash.as_deref().map(str::trim) {
+            Some(hash) if operation_name == "categoryGridRetrieve" && !hash.is_empty() =>
+                hash.to_string(),
+            _ => Self::persisted_hash_for(operation_name, locale_use),
+        };
         let effective_sha_trimmed = effective_sha.trim();
         if
             !expected_default.is_empty() &&
@@ -846,6 +1086,7 @@ impl PsStoreClient {
                                         warn!(op=%operation_name, cache="bypass", path=%cache_path.display(), "ps op_get cached payload contains GraphQL errors; ignoring cache");
                                     } else {
                                         info!(op=%operation_name, cache="hit", path=%cache_path.display(), "ps op_get served from cache");
+                                        self.counters.cache_hits.fetch_add(1, Ordering::Relaxed);
                                         if let Ok(pretty) = serde_json::to_string_pretty(&v) {
                                             println!(
                                                 "[psstore op={} cache-hit] {}",
@@ -866,8 +1107,9 @@ impl PsStoreClient {
 
             // Perform request with retries; on success, write cache
             let mut attempt = 0u32;
-            let max_attempts = self.cfg.retry_attempts.max(1);
-            let mut delay = Duration::from_millis(self.cfg.retry_base_delay_ms.max(1));
+            let policy = self.cfg.retry_policy_for(operation_name);
+            let max_attempts = policy.attempts;
+            let mut retry_backoff = policy.backoff();
             // Canonicalize locale header to ll-CC
             let key = {
                 let s = locale_use.replace('_', "-");
@@ -906,6 +1148,8 @@ impl PsStoreClient {
                         "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0 Safari/537.36".to_string()
                     );
 
+                let conditional = self.conditional_headers(etag_key.as_deref()).await;
+                self.counters.requests.fetch_add(1, Ordering::Relaxed);
                 let resp = match
                     http
                         .get(&req_url)
@@ -919,6 +1163,7 @@ impl PsStoreClient {
                         .header(reqwest::header::REFERER, referer.clone())
                         .header(reqwest::header::USER_AGENT, ua.clone())
                         .header("X-PSN-Store-Front", key.to_lowercase())
+                        .headers(conditional)
                         .send().await
                 {
                     Ok(r) => r,
@@ -927,13 +1172,17 @@ impl PsStoreClient {
                         if attempt >= max_attempts {
                             return Err(PsError::Net(e));
                         }
-                        tokio::time::sleep(delay).await;
-                        delay = delay.saturating_mul(2);
+                        tokio::time::sleep(retry_backoff.next_backoff().unwrap_or(retry_backoff.max_interval)).await;
                         continue;
                     }
                 };
 
                 let status = resp.status();
+                let etag = resp
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|h| h.to_str().ok())
+                    .map(|h| h.to_string());
                 let body = match resp.text().await {
                     Ok(b) => b,
                     Err(e) => {
@@ -941,12 +1190,12 @@ impl PsStoreClient {
                         if attempt >= max_attempts {
                             return Err(PsError::Net(e));
                         }
-                        tokio::time::sleep(delay).await;
-                        delay = delay.saturating_mul(2);
+                        tokio::time::sleep(retry_backoff.next_backoff().unwrap_or(retry_backoff.max_interval)).await;
                         continue;
                     }
                 };
 
+                self.counters.bytes_in.fetch_add(body.len() as u64, Ordering::Relaxed);
                 let elapsed = t0.elapsed().as_millis();
                 info!(op=%operation_name, locale=%key, req_id=%req_id, status=%status.as_u16(), body_len=body.len(), elapsed_ms=%elapsed, "ps op_get response");
                 if std::env::var("PS_TRACE_BODIES").ok().as_deref() == Some("1") {
@@ -959,9 +1208,17 @@ impl PsStoreClient {
                     debug!(op=%operation_name, req_id=%req_id, sample=%sample, "ps op_get body sample");
                 }
 
+                if status == reqwest::StatusCode::NOT_MODIFIED {
+                    if let Some(v) = self.etag_cached_body(etag_key.as_deref()).await {
+                        info!(op=%operation_name, locale=%key, req_id=%req_id, "ps op_get not modified; serving etag-cached body");
+                        self.counters.cache_hits.fetch_add(1, Ordering::Relaxed);
+                        return Ok(v);
+                    }
+                }
+
                 if !status.is_success() {
-                    if status.as_u16() >= 500 {
-                        warn!(status=%status.as_u16(), "ps op_get server error, will retry if attempts remain");
+                    if status.as_u16() >= 500 || status.as_u16() == 429 {
+                        warn!(status=%status.as_u16(), "ps op_get server error or throttled, will retry if attempts remain");
                         if attempt >= max_attempts {
                             // Record observation for non-success
                             self.record_hash_observation(
@@ -977,8 +1234,7 @@ impl PsStoreClient {
                             );
                             return Err(PsError::Http { status: status.as_u16(), body });
                         }
-                        tokio::time::sleep(delay).await;
-                        delay = delay.saturating_mul(2);
+                        tokio::time::sleep(retry_backoff.next_backoff().unwrap_or(retry_backoff.max_interval)).await;
                         continue;
                     } else {
                         let status_u16 = status.as_u16();
@@ -1054,8 +1310,7 @@ impl PsStoreClient {
                         if attempt >= max_attempts {
                             return Err(PsError::Other(format!("graphql errors: {:?}", errs)));
                         }
-                        tokio::time::sleep(delay).await;
-                        delay = delay.saturating_mul(2);
+                        tokio::time::sleep(retry_backoff.next_backoff().unwrap_or(retry_backoff.max_interval)).await;
                         continue;
                     }
                 }
@@ -1076,14 +1331,16 @@ impl PsStoreClient {
                         println!("[psstore op={} cache-write] <non-json>", operation_name);
                     }
                 }
+                self.remember_etag(etag_key.as_deref(), etag, &v).await;
                 return Ok(v);
             }
         }
 
         // Default path: no cache directory configured
         let mut attempt = 0u32;
-        let max_attempts = self.cfg.retry_attempts.max(1);
-        let mut delay = Duration::from_millis(self.cfg.retry_base_delay_ms.max(1));
+        let policy = self.cfg.retry_policy_for(operation_name);
+        let max_attempts = policy.attempts;
+        let mut retry_backoff = policy.backoff();
         let key = {
             let s = locale_use.replace('_', "-");
             if let Some((ll, cc)) = s.split_once('-') {
@@ -1123,6 +1380,8 @@ impl PsStoreClient {
                     "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/119.0 Safari/537.36".to_string()
                 );
 
+            let conditional = self.conditional_headers(etag_key.as_deref()).await;
+            self.counters.requests.fetch_add(1, Ordering::Relaxed);
             let resp = match
                 http
                     .get(&req_url)
@@ -1136,6 +1395,7 @@ impl PsStoreClient {
                     .header(reqwest::header::REFERER, referer.clone())
                     .header(reqwest::header::USER_AGENT, ua.clone())
                     .header("X-PSN-Store-Front", key.to_lowercase())
+                    .headers(conditional)
                     .send().await
             {
                 Ok(r) => r,
@@ -1149,13 +1409,17 @@ impl PsStoreClient {
                     }
 
                     // Otherwise back off and retry
-                    tokio::time::sleep(delay).await;
-                    delay = delay.saturating_mul(2);
+                    tokio::time::sleep(retry_backoff.next_backoff().unwrap_or(retry_backoff.max_interval)).await;
                     continue;
                 }
             };
 
             let status = resp.status();
+            let etag = resp
+                .headers()
+                .get(ETAG)
+                .and_then(|h| h.to_str().ok())
+                .map(|h| h.to_string());
             let body = match resp.text().await {
                 Ok(b) => b,
                 Err(e) => {
@@ -1163,12 +1427,12 @@ impl PsStoreClient {
                     if attempt >= max_attempts {
                         return Err(PsError::Net(e));
                     }
-                    tokio::time::sleep(delay).await;
-                    delay = delay.saturating_mul(2);
+                    tokio::time::sleep(retry_backoff.next_backoff().unwrap_or(retry_backoff.max_interval)).await;
                     continue;
                 }
             };
 
+            self.counters.bytes_in.fetch_add(body.len() as u64, Ordering::Relaxed);
             let elapsed = t0.elapsed().as_millis();
             info!(op=%operation_name, locale=%key, req_id=%req_id, status=%status.as_u16(), body_len=body.len(), elapsed_ms=%elapsed, "ps op_get response");
             if std::env::var("PS_TRACE_BODIES").ok().as_deref() == Some("1") {
@@ -1181,12 +1445,20 @@ impl PsStoreClient {
                 debug!(op=%operation_name, req_id=%req_id, sample=%sample, "ps op_get body sample");
             }
 
+            if status == reqwest::StatusCode::NOT_MODIFIED {
+                if let Some(v) = self.etag_cached_body(etag_key.as_deref()).await {
+                    info!(op=%operation_name, locale=%key, req_id=%req_id, "ps op_get not modified; serving etag-cached body");
+                    self.counters.cache_hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(v);
+                }
+            }
+
             if !status.is_success() {
-                // Retry 5xx as transient, fail fast on 4xx
-                if status.as_u16() >= 500 {
+                // Retry 5xx and 429 (throttled) as transient, fail fast on other 4xx
+                if status.as_u16() >= 500 || status.as_u16() == 429 {
                     warn!(
                             status=%status.as_u16(),
-                            "ps op_get server error, will retry if attempts remain"
+                            "ps op_get server error or throttled, will retry if attempts remain"
                         );
                     if attempt >= max_attempts {
                         self.record_hash_observation(
@@ -1205,8 +1477,7 @@ impl PsStoreClient {
                             body,
                         });
                     }
-                    tokio::time::sleep(delay).await;
-                    delay = delay.saturating_mul(2);
+                    tokio::time::sleep(retry_backoff.next_backoff().unwrap_or(retry_backoff.max_interval)).await;
                     continue;
                 } else {
                     let status_u16 = status.as_u16();
@@ -1313,12 +1584,12 @@ impl PsStoreClient {
                     if attempt >= max_attempts {
                         return Err(PsError::Other(format!("graphql errors: {:?}", errs)));
                     }
-                    tokio::time::sleep(delay).await;
-                    delay = delay.saturating_mul(2);
+                    tokio::time::sleep(retry_backoff.next_backoff().unwrap_or(retry_backoff.max_interval)).await;
                     continue;
                 }
             }
 
+            self.remember_etag(etag_key.as_deref(), etag, &v).await;
             return Ok(v);
         }
     }
@@ -1531,7 +1802,7 @@ impl PsStoreClient {
         req: &CategoryRequest
     ) -> Result<Value, PsError> {
         let vars = Self::vars_for_category_request(req);
-        self.op_get("categoryGridRetrieve", &vars, Some(locale)).await
+        self.op_get_conditional("categoryGridRetrieve", &vars, Some(locale)).await
     }
 
     pub async fn get_category_page(
@@ -1667,7 +1938,7 @@ impl PsStoreClient {
         // Unified hash handling: rely on global PS_HASH if set; otherwise omit persistedQuery
         let _hash = "";
         let vars = serde_json::json!({ "productId": product_id });
-        self.op_get("metGetProductById", &vars, Some(locale)).await
+        self.op_get_conditional("metGetProductById", &vars, Some(locale)).await
     }
 
     /// Fetch concept info by product id via metGetConceptByProductIdQuery. Returns raw payload.
@@ -1720,6 +1991,22 @@ pub struct PsMedia {
     pub media_type: Option<String>,
     pub role: Option<String>,
     pub url: Option<String>,
+    /// Video length in seconds when the feed reports one.
+    #[serde(default)]
+    pub duration_secs: Option<f64>,
+}
+
+/// Seconds from a media entry's `duration`/`durationInSeconds`/`length` field, given
+/// either as a number or a numeric string.
+pub fn media_duration_secs(entry: &serde_json::Value) -> Option<f64> {
+    ["duration", "durationInSeconds", "length"]
+        .iter()
+        .filter_map(|key| entry.get(*key))
+        .find_map(|v| {
+            v.as_f64()
+                .or_else(|| v.as_str().and_then(|s| s.trim().parse::<f64>().ok()))
+        })
+        .filter(|secs| secs.is_finite() && *secs > 0.0)
 }
 
 /// Normalized product detail suitable for ingestion and UI
@@ -1896,6 +2183,7 @@ fn extract_product_summaries(v: &Value) -> Vec<PsProductSummary> {
                         media_type: mtype_opt.clone(),
                         role: role_opt.clone(),
                         url: url_opt.clone(),
+                        duration_secs: media_duration_secs(x),
                     };
                     if let Some(url) = &url_opt {
                         // Deduplicate by URL only. If name/role/etc. are same but URLs differ, they are distinct.
@@ -2357,3 +2645,178 @@ impl CategoryRequest {
         n
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{ AsyncReadExt, AsyncWriteExt };
+    use tokio::net::TcpListener;
+
+    fn test_config(base_url: String) -> PsConfig {
+        PsConfig {
+            base_url,
+            bearer: None,
+            locales: vec!["en-us".into()],
+            rps: 50,
+            extra_headers: HashMap::new(),
+            retry_attempts: 5,
+            retry_base_delay_ms: 2000,
+            retry_jitter_ratio: 0.0,
+            detail_retry_attempts: None,
+            grid_retry_attempts: None,
+            enable_etag_cache: true,
+            cookie: None,
+            ipv6_only: false,
+            proxy: None,
+            grid_hash: None,
+        }
+    }
+
+    /// Answer every request with 429 and record when each one arrived.
+    async fn throttling_server() -> (String, Arc<Mutex<Vec<Instant>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let hits = Arc::new(Mutex::new(Vec::new()));
+        let seen = hits.clone();
+        tokio::spawn(async move {
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = sock.read(&mut buf).await;
+                seen.lock().await.push(Instant::now());
+                let _ = sock.write_all(
+                    b"HTTP/1.1 429 Too Many Requests\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                ).await;
+            }
+        });
+        (base_url, hits)
+    }
+
+    /// Serve `body` with ETag "v1", answering 304 once a request revalidates with that tag,
+    /// and record each request head (lower-cased).
+    async fn etag_server(body: &'static str) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let n = sock.read(&mut buf).await.unwrap_or(0);
+                let head = String::from_utf8_lossy(&buf[..n]).to_ascii_lowercase();
+                let response = if head.contains("if-none-match: \"v1\"") {
+                    "HTTP/1.1 304 Not Modified\r\netag: \"v1\"\r\nconnection: close\r\n\r\n".to_string()
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\netag: \"v1\"\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                };
+                seen.lock().await.push(head);
+                let _ = sock.write_all(response.as_bytes()).await;
+            }
+        });
+        (base_url, requests)
+    }
+
+    #[test]
+    fn endpoint_budgets_fall_back_to_default() {
+        let cfg = test_config("http://localhost/".into())
+            .with_retry(5, 40, 0.0)
+            .with_detail_retry_attempts(2)
+            .with_grid_retry_attempts(8);
+        assert_eq!(cfg.retry_policy_for("metGetProductById").attempts, 2);
+        assert_eq!(cfg.retry_policy_for("categoryGridRetrieve").attempts, 8);
+        assert_eq!(cfg.retry_policy_for("wcaProductStarRatingRetrieve").attempts, 5);
+
+        let mut backoff = cfg.retry_policy_for("categoryGridRetrieve").backoff();
+        let delays: Vec<u128> = (0..3)
+            .map(|_| backoff.next_backoff().unwrap().as_millis())
+            .collect();
+        assert_eq!(delays, vec![40, 80, 160]);
+    }
+
+    #[tokio::test]
+    async fn throttled_request_retries_with_increasing_delays() {
+        let (base_url, hits) = throttling_server().await;
+        let client = PsStoreClient::new(test_config(base_url).with_retry(4, 40, 0.0));
+
+        let err = client
+            .op_get("categoryGridRetrieve", &serde_json::json!({}), Some("en-us")).await
+            .unwrap_err();
+        assert!(matches!(err, PsError::Http { status: 429, .. }), "{err:?}");
+
+        let hits = hits.lock().await;
+        assert_eq!(hits.len(), 4, "retried up to retry_attempts");
+        let gaps: Vec<Duration> = hits
+            .windows(2)
+            .map(|w| w[1] - w[0])
+            .collect();
+        for (i, gap) in gaps.iter().enumerate() {
+            assert!(*gap >= Duration::from_millis(40 << i), "gap {i} was {gap:?}");
+        }
+        assert!(gaps.windows(2).all(|w| w[1] > w[0]), "delays grow: {gaps:?}");
+    }
+
+    #[tokio::test]
+    async fn not_modified_detail_returns_cached_body() {
+        const BODY: &str = r#"{"data":{"metGetProductById":{"name":"Astro Bot"}}}"#;
+        let (base_url, requests) = etag_server(BODY).await;
+        let client = PsStoreClient::new(test_config(base_url));
+
+        let first = client.product_detail_raw("en-us", "UP9000-PPSA01234_00").await.unwrap();
+        let second = client.product_detail_raw("en-us", "UP9000-PPSA01234_00").await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(second.pointer("/data/metGetProductById/name"), Some(&Value::from("Astro Bot")));
+
+        let requests = requests.lock().await;
+        assert_eq!(requests.len(), 2);
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+
+        assert_eq!(client.stats(), PsClientStats {
+            requests: 2,
+            bytes_in: BODY.len() as u64,
+            cache_hits: 1,
+        });
+    }
+
+    #[tokio::test]
+    async fn etag_cache_outlives_the_client() {
+        let (base_url, requests) = etag_server(r#"{"data":{}}"#).await;
+        for _ in 0..2 {
+            // A new client per run, as the seed pipeline builds one per locale and tick.
+            let client = PsStoreClient::new(test_config(base_url.clone()));
+            client.product_detail_raw("en-us", "UP9000-PPSA05678_00").await.unwrap();
+        }
+        let requests = requests.lock().await;
+        assert_eq!(requests.len(), 2);
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+    }
+
+    #[test]
+    fn etag_cache_evicts_oldest_entries() {
+        let mut cache = EtagCache::new(2);
+        for key in ["a", "b", "a", "c"] {
+            cache.insert(key.into(), ("\"v1\"".into(), Value::Null));
+        }
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.entries.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn etag_cache_can_be_disabled() {
+        let (base_url, requests) = etag_server(r#"{"data":{}}"#).await;
+        let mut cfg = test_config(base_url);
+        cfg.enable_etag_cache = false;
+        let client = PsStoreClient::new(cfg);
+
+        for _ in 0..2 {
+            client.product_detail_raw("en-us", "UP9000-PPSA01234_00").await.unwrap();
+        }
+        let requests = requests.lock().await;
+        assert!(requests.iter().all(|head| !head.contains("if-none-match")));
+    }
+}
@@ -3,20 +3,63 @@
 use actix_web::{
     body::{BoxBody, EitherBody},
     dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::Method,
+    middleware::Condition,
     Error, HttpResponse,
 };
 use futures::future::LocalBoxFuture;
+use sha2::{Digest, Sha256};
 use std::future::{ready, Ready};
+use subtle::ConstantTimeEq;
 
 /// Authentication middleware that validates Bearer tokens
 pub struct Auth {
     secret: String,
+    mutating_only: bool,
 }
 
 impl Auth {
     pub fn new(secret: String) -> Self {
-        Self { secret }
+        Self {
+            secret,
+            mutating_only: false,
+        }
     }
+
+    /// Only require the token for state-changing requests (anything but GET/HEAD/OPTIONS),
+    /// leaving read and health endpoints open.
+    pub fn mutating_only(secret: String) -> Self {
+        Self {
+            secret,
+            mutating_only: true,
+        }
+    }
+}
+
+/// `HTTP_API_TOKEN` for the scheduler/worker HTTP servers. Logs a warning when unset, since
+/// the control endpoints then stay unauthenticated (the previous behaviour).
+pub fn api_token_from_env() -> Option<String> {
+    let token = crate::util::env::env_opt("HTTP_API_TOKEN");
+    if token.is_none() {
+        tracing::warn!("HTTP_API_TOKEN not set; control endpoints are unauthenticated");
+    }
+    token
+}
+
+/// Bearer-token guard for control endpoints; a no-op when `token` is `None`.
+pub fn write_auth(token: Option<&str>) -> Condition<Auth> {
+    Condition::new(
+        token.is_some(),
+        Auth::mutating_only(token.unwrap_or_default().to_string()),
+    )
+}
+
+/// Compare SHA-256 digests in constant time, so neither the secret's contents nor its
+/// length show up in response timing.
+fn token_matches(token: &str, secret: &str) -> bool {
+    Sha256::digest(token.as_bytes())
+        .ct_eq(&Sha256::digest(secret.as_bytes()))
+        .into()
 }
 
 impl<S, B> Transform<S, ServiceRequest> for Auth
@@ -35,6 +78,7 @@ where
         ready(Ok(AuthMiddleware {
             service,
             secret: self.secret.clone(),
+            mutating_only: self.mutating_only,
         }))
     }
 }
@@ -42,6 +86,7 @@ where
 pub struct AuthMiddleware<S> {
     service: S,
     secret: String,
+    mutating_only: bool,
 }
 
 impl<S, B> Service<ServiceRequest> for AuthMiddleware<S>
@@ -59,8 +104,10 @@ where
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let secret = self.secret.clone();
 
-        // Skip auth for health check
-        if req.path() == "/health" || req.path() == "/" {
+        let read_only = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+
+        // Skip auth for health check (and reads when only guarding mutations)
+        if req.path() == "/health" || req.path() == "/" || (self.mutating_only && read_only) {
             let fut = self.service.call(req);
             return Box::pin(async move {
                 let res = fut.await?;
@@ -76,7 +123,7 @@ where
             .and_then(|h| h.strip_prefix("Bearer "));
 
         if let Some(token) = auth_header {
-            if token == secret {
+            if token_matches(token, &secret) {
                 // Valid token - proceed with request
                 let fut = self.service.call(req);
                 return Box::pin(async move {
@@ -111,3 +158,58 @@ impl AuthContext {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, web, App};
+
+    #[test]
+    fn token_comparison() {
+        assert!(token_matches("s3cret", "s3cret"));
+        assert!(!token_matches("s3cre", "s3cret"));
+        assert!(!token_matches("s3cret ", "s3cret"));
+        assert!(!token_matches("", "s3cret"));
+    }
+
+    #[actix_web::test]
+    async fn mutating_routes_require_token() {
+        use actix_web::test;
+
+        let app = test::init_service(
+            App::new()
+                .wrap(Auth::mutating_only("s3cret".to_string()))
+                .route(
+                    "/api/shutdown",
+                    web::post().to(|| async { HttpResponse::Ok().finish() }),
+                )
+                .route(
+                    "/api/metrics",
+                    web::get().to(|| async { HttpResponse::Ok().finish() }),
+                ),
+        )
+        .await;
+
+        let anon = test::TestRequest::post().uri("/api/shutdown").to_request();
+        let resp = test::call_service(&app, anon).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let wrong = test::TestRequest::post()
+            .uri("/api/shutdown")
+            .insert_header(("Authorization", "Bearer nope"))
+            .to_request();
+        let resp = test::call_service(&app, wrong).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let authed = test::TestRequest::post()
+            .uri("/api/shutdown")
+            .insert_header(("Authorization", "Bearer s3cret"))
+            .to_request();
+        let resp = test::call_service(&app, authed).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let read = test::TestRequest::get().uri("/api/metrics").to_request();
+        let resp = test::call_service(&app, read).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
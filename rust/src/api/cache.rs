@@ -0,0 +1,96 @@
+// HTTP conditional-request helpers (weak ETag / Last-Modified) for read endpoints
+
+use actix_web::http::header;
+use actix_web::HttpRequest;
+use chrono::{DateTime, Utc};
+
+/// Freshness stamp of the rows behind a response: newest `updated_at`/`recorded_at` plus
+/// the row count, so deletions (which don't move the max timestamp) still change it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentVersion {
+    pub last_modified: DateTime<Utc>,
+    pub rows: i64,
+}
+
+impl ContentVersion {
+    /// Weak ETag for `scope` (e.g. `game-42`) and a response `variant` such as the
+    /// requested regions.
+    pub fn weak_etag(&self, scope: &str, variant: &str) -> String {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for b in variant.as_bytes() {
+            hash ^= u64::from(*b);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        format!(
+            "W/\"{scope}-{}-{}-{hash:x}\"",
+            self.last_modified.timestamp_micros(),
+            self.rows
+        )
+    }
+
+    pub fn http_date(&self) -> String {
+        self.last_modified
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string()
+    }
+}
+
+/// True when the request's `If-None-Match` matches `etag` (weak comparison, `*` allowed).
+pub fn if_none_match(req: &HttpRequest, etag: &str) -> bool {
+    req.headers()
+        .get_all(header::IF_NONE_MATCH)
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| etag_list_matches(v, etag))
+}
+
+fn etag_list_matches(list: &str, etag: &str) -> bool {
+    let opaque = |t: &str| t.trim().trim_start_matches("W/").to_string();
+    let want = opaque(etag);
+    list.split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || opaque(candidate) == want)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn version(secs: i64, rows: i64) -> ContentVersion {
+        ContentVersion {
+            last_modified: Utc.timestamp_opt(secs, 0).unwrap(),
+            rows,
+        }
+    }
+
+    #[test]
+    fn etag_changes_with_rows_and_variant() {
+        let v = version(1_700_000_000, 5);
+        let tag = v.weak_etag("game-1", "US");
+        assert!(tag.starts_with("W/\"game-1-"));
+        assert_eq!(tag, v.weak_etag("game-1", "US"));
+        assert_ne!(tag, version(1_700_000_001, 5).weak_etag("game-1", "US"));
+        assert_ne!(tag, version(1_700_000_000, 4).weak_etag("game-1", "US"));
+        assert_ne!(tag, v.weak_etag("game-1", "GB"));
+    }
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let tag = version(1_700_000_000, 5).weak_etag("game-1", "");
+        assert!(etag_list_matches(&tag, &tag));
+        assert!(etag_list_matches(
+            &format!("\"other\", {}", tag.trim_start_matches("W/")),
+            &tag
+        ));
+        assert!(etag_list_matches("*", &tag));
+        assert!(!etag_list_matches("W/\"game-1-0-0-0\"", &tag));
+    }
+
+    #[test]
+    fn http_date_is_imf_fixdate() {
+        assert_eq!(
+            version(1_700_000_000, 1).http_date(),
+            "Tue, 14 Nov 2023 22:13:20 GMT"
+        );
+    }
+}
@@ -0,0 +1,251 @@
+// Price coverage listing
+//
+// Lists every priced game with the regions and retailers it currently has prices in and its
+// lowest price. Paginated exactly like title search: same sorts (relevance meaning "most
+// regions covered first"), same cursor, and the same price snapshot pinned on the first page.
+
+use crate::api::models::{CoveragePage, GameCoverage, ProfilePrice};
+use crate::api::search::{SearchCursor, SearchSort, DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT};
+use crate::database_ops::db::Db;
+use crate::database_ops::schema_caps::SchemaCaps;
+use anyhow::Result;
+use sqlx::Row;
+
+#[derive(Debug, Clone, Default)]
+pub struct CoverageParams {
+    pub limit: Option<i64>,
+    pub sort: SearchSort,
+    pub cursor: Option<SearchCursor>,
+}
+
+/// One page of priced games in `params.sort` order with the total count and a cursor for
+/// the next page (absent on the last page). Empty when video_game_prices does not exist.
+pub async fn list_coverage(db: &Db, params: &CoverageParams) -> Result<CoveragePage> {
+    if !SchemaCaps::global()
+        .table_visible(db, "public.video_game_prices")
+        .await?
+    {
+        return Ok(CoveragePage {
+            results: Vec::new(),
+            total: 0,
+            next_cursor: None,
+        });
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let rows = sqlx::query(&build_coverage_sql(params.sort))
+        .persistent(false)
+        .bind(limit + 1)
+        .bind(params.cursor.map(|c| c.sort_key))
+        .bind(params.cursor.map(|c| c.video_game_id))
+        .bind(params.cursor.map(|c| c.as_of))
+        .fetch_all(&db.pool)
+        .await?;
+
+    let total = match rows.first() {
+        Some(r) => r.try_get::<i64, _>("total")?,
+        None => 0,
+    };
+    let has_more = rows.len() as i64 > limit;
+    let mut results = Vec::with_capacity(rows.len());
+    let mut last: Option<SearchCursor> = None;
+    for r in rows.into_iter().take(limit as usize) {
+        let result = GameCoverage {
+            video_game_id: r.try_get("video_game_id")?,
+            display_title: r.try_get("display_title")?,
+            release_date: r.try_get("release_date")?,
+            regions: r.try_get("regions")?,
+            retailers: r.try_get("retailers")?,
+            best_price: Some(ProfilePrice {
+                retailer: r.try_get("price_retailer")?,
+                country_code: r.try_get("price_country_code")?,
+                currency: r.try_get("price_currency")?,
+                amount_minor: r.try_get("price_amount_minor")?,
+                recorded_at: r.try_get("price_recorded_at")?,
+            }),
+        };
+        last = Some(SearchCursor {
+            sort_key: r.try_get("sort_key")?,
+            video_game_id: result.video_game_id,
+            as_of: r.try_get("as_of")?,
+        });
+        results.push(result);
+    }
+
+    Ok(CoveragePage {
+        results,
+        total,
+        next_cursor: if has_more {
+            last.map(|c| c.encode(params.sort))
+        } else {
+            None
+        },
+    })
+}
+
+/// `$1` is the page size (+1 to detect a next page), `$2`/`$3` the cursor's sort key and
+/// video_game_id, `$4` its price snapshot (all NULL for the first page).
+fn build_coverage_sql(sort: SearchSort) -> String {
+    let sort_key = match sort {
+        SearchSort::Relevance => "-g.region_count::float8",
+        SearchSort::ReleaseDate => {
+            "COALESCE(-(vg.release_date - DATE '1970-01-01')::float8, 'Infinity'::float8)"
+        }
+        SearchSort::Price => "bp.amount_minor::float8",
+    };
+    format!(
+        r#"
+        WITH snapshot AS (
+          SELECT COALESCE($4::timestamptz, now()) AS as_of
+        ),
+        latest AS (
+          SELECT DISTINCT ON (p.video_game_id, p.retailer, p.country_code, p.currency)
+                 p.video_game_id, p.retailer, upper(p.country_code) AS country_code,
+                 p.currency, p.amount_minor, p.recorded_at
+          FROM public.video_game_prices p
+          WHERE p.recorded_at <= (SELECT as_of FROM snapshot)
+          ORDER BY p.video_game_id, p.retailer, p.country_code, p.currency, p.recorded_at DESC
+        ),
+        g AS (
+          SELECT l.video_game_id,
+                 COALESCE(
+                   array_agg(DISTINCT l.country_code) FILTER (WHERE l.country_code IS NOT NULL),
+                   ARRAY[]::text[]
+                 ) AS regions,
+                 COALESCE(
+                   array_agg(DISTINCT l.retailer) FILTER (WHERE l.retailer IS NOT NULL),
+                   ARRAY[]::text[]
+                 ) AS retailers,
+                 count(DISTINCT l.country_code) AS region_count
+          FROM latest l
+          GROUP BY l.video_game_id
+        ),
+        ranked AS (
+          SELECT g.video_game_id, COALESCE(vg.display_title, vgt.title) AS display_title,
+                 vg.release_date, g.regions, g.retailers,
+                 bp.retailer AS price_retailer, bp.country_code AS price_country_code,
+                 bp.currency AS price_currency, bp.amount_minor AS price_amount_minor,
+                 bp.recorded_at AS price_recorded_at,
+                 {sort_key} AS sort_key
+          FROM g
+          JOIN public.video_games vg ON vg.id = g.video_game_id
+          LEFT JOIN public.video_game_titles vgt ON vgt.id = vg.title_id
+          JOIN LATERAL (
+            SELECT l.*
+            FROM latest l
+            WHERE l.video_game_id = g.video_game_id
+            ORDER BY l.amount_minor ASC
+            LIMIT 1
+          ) bp ON true
+        )
+        SELECT ranked.*, (SELECT count(*) FROM ranked)::bigint AS total,
+               (SELECT as_of FROM snapshot) AS as_of
+        FROM ranked
+        WHERE $2::float8 IS NULL
+           OR (ranked.sort_key, ranked.video_game_id) > ($2::float8, $3::bigint)
+        ORDER BY ranked.sort_key, ranked.video_game_id
+        LIMIT $1
+        "#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+    use std::collections::BTreeSet;
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn coverage_pages_have_no_duplicates_or_gaps() {
+        let db = RollbackDb::connect().await;
+        let platform_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.platforms (code, name) VALUES ('coverage-test', 'Coverage Test')
+             ON CONFLICT (name) DO UPDATE SET code = EXCLUDED.code RETURNING id",
+        )
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let mut expected = BTreeSet::new();
+        for i in 0..5i64 {
+            let vg_id: i64 = sqlx::query_scalar(
+                "WITH p AS (
+                   INSERT INTO public.products (slug, name) VALUES ($1, $1) RETURNING id
+                 ), t AS (
+                   INSERT INTO public.video_game_titles (product_id, title, normalized_title)
+                   SELECT p.id, $1, $1 FROM p RETURNING id
+                 ), vg AS (
+                   INSERT INTO public.video_games (title_id, platform_id)
+                   SELECT t.id, $2 FROM t RETURNING id
+                 )
+                 SELECT id FROM vg",
+            )
+            .bind(format!("coverage-page-test-{i}"))
+            .bind(platform_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+            for country in ["US", "GB", "JP"].iter().take(1 + i as usize % 3) {
+                sqlx::query(
+                    "INSERT INTO public.video_game_prices
+                         (video_game_id, amount_minor, currency, country_code, retailer, recorded_at)
+                     VALUES ($1, $2, 'USD', $3, 'steam', now() - interval '1 hour')",
+                )
+                .bind(vg_id)
+                .bind(1_000 + i)
+                .bind(country)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+            }
+            expected.insert(vg_id);
+        }
+
+        for sort in [
+            SearchSort::Relevance,
+            SearchSort::ReleaseDate,
+            SearchSort::Price,
+        ] {
+            let mut params = CoverageParams {
+                limit: Some(2),
+                sort,
+                cursor: None,
+            };
+            let mut seen: Vec<i64> = Vec::new();
+            let mut pages = 0;
+            loop {
+                let page = list_coverage(&db, &params).await.unwrap();
+                pages += 1;
+                if pages == 1 {
+                    // Repricing after the walk started must not move games across pages.
+                    sqlx::query(
+                        "INSERT INTO public.video_game_prices
+                             (video_game_id, amount_minor, currency, country_code, retailer,
+                              recorded_at)
+                         SELECT id, 1, 'USD', 'FR', 'steam', clock_timestamp()
+                         FROM unnest($1::bigint[]) AS id",
+                    )
+                    .bind(expected.iter().copied().collect::<Vec<_>>())
+                    .execute(&db.pool)
+                    .await
+                    .unwrap();
+                }
+                seen.extend(page.results.iter().map(|r| r.video_game_id));
+                match page.next_cursor {
+                    Some(token) => {
+                        params.cursor = Some(SearchCursor::decode(&token, sort).unwrap())
+                    }
+                    None => break,
+                }
+            }
+            let unique: BTreeSet<i64> = seen.iter().copied().collect();
+            assert_eq!(unique.len(), seen.len(), "duplicate rows for {sort:?}");
+            assert!(expected.is_subset(&unique), "gap in pages for {sort:?}");
+        }
+
+        db.rollback().await;
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::api::models::*;
 use crate::database_ops::db::Db;
-use actix_web::{web, HttpResponse, Result};
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Result};
 use std::time::SystemTime;
 
 /// Health check endpoint
@@ -178,3 +178,156 @@ pub async fn refresh_provider(path: web::Path<String>, _db: web::Data<Db>) -> Re
 
     Ok(HttpResponse::Accepted().json(response))
 }
+
+/// Aggregated profile for a single video game. Served with a weak ETag / Last-Modified
+/// derived from the underlying rows; a matching `If-None-Match` gets 304 without
+/// assembling the profile.
+pub async fn get_game_profile(
+    req: HttpRequest,
+    path: web::Path<i64>,
+    query: web::Query<GameProfileQuery>,
+    db: web::Data<Db>,
+) -> Result<HttpResponse> {
+    let video_game_id = path.into_inner();
+    let regions: Vec<String> = query.regions.iter().cloned().collect();
+    let not_found = || {
+        HttpResponse::NotFound().json(ApiResponse::<()>::error(format!(
+            "video game {} not found",
+            video_game_id
+        )))
+    };
+    let failed = |e: anyhow::Error| {
+        tracing::error!(video_game_id, error = %e, "game profile query failed");
+        HttpResponse::InternalServerError()
+            .json(ApiResponse::<()>::error("Failed to load game profile"))
+    };
+
+    let version = match crate::api::profile::game_profile_version(&db, video_game_id).await {
+        Ok(Some(version)) => version,
+        Ok(None) => return Ok(not_found()),
+        Err(e) => return Ok(failed(e)),
+    };
+    let variant = crate::api::profile::normalize_regions(&regions).join(",");
+    let etag = version.weak_etag(&format!("game-{video_game_id}"), &variant);
+    let last_modified = version.http_date();
+    if crate::api::cache::if_none_match(&req, &etag) {
+        return Ok(HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .insert_header((header::LAST_MODIFIED, last_modified))
+            .finish());
+    }
+
+    match crate::api::profile::game_profile(&db, video_game_id, &regions).await {
+        Ok(Some(profile)) => Ok(HttpResponse::Ok()
+            .insert_header((header::ETAG, etag))
+            .insert_header((header::LAST_MODIFIED, last_modified))
+            .json(ApiResponse::success(profile))),
+        Ok(None) => Ok(not_found()),
+        Err(e) => Ok(failed(e)),
+    }
+}
+
+/// Compare a game's prices across regions on one tax basis and currency
+pub async fn compare_game_prices(
+    path: web::Path<i64>,
+    query: web::Query<PriceCompareQuery>,
+    db: web::Data<Db>,
+) -> Result<HttpResponse> {
+    use crate::api::price_compare::{compare_prices, DEFAULT_COMPARE_CURRENCY};
+    use crate::normalization::tax::TaxBasis;
+
+    let video_game_id = path.into_inner();
+    let tax_basis = match TaxBasis::parse(query.tax.as_deref().unwrap_or_default()) {
+        Ok(basis) => basis,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
+        }
+    };
+    let regions: Vec<String> = query.regions.iter().cloned().collect();
+    let currency = query
+        .currency
+        .as_deref()
+        .unwrap_or(DEFAULT_COMPARE_CURRENCY);
+
+    match compare_prices(&db, video_game_id, &regions, tax_basis, currency).await {
+        Ok(comparison) => Ok(HttpResponse::Ok().json(ApiResponse::success(comparison))),
+        Err(e) => {
+            tracing::error!(video_game_id, error = %e, "price comparison failed");
+            Ok(HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("Failed to compare prices")))
+        }
+    }
+}
+
+/// Parse the `sort` and `cursor` query params shared by the paginated list endpoints.
+fn page_position(
+    sort: Option<&str>,
+    cursor: Option<&str>,
+) -> anyhow::Result<(
+    crate::api::search::SearchSort,
+    Option<crate::api::search::SearchCursor>,
+)> {
+    use crate::api::search::{SearchCursor, SearchSort};
+
+    let sort = SearchSort::parse(sort.unwrap_or_default())?;
+    let cursor = cursor.map(|c| SearchCursor::decode(c, sort)).transpose()?;
+    Ok((sort, cursor))
+}
+
+/// Fuzzy title search with keyset pagination
+pub async fn search_games(
+    query: web::Query<SearchQuery>,
+    db: web::Data<Db>,
+) -> Result<HttpResponse> {
+    use crate::api::search::SearchParams;
+
+    let (sort, cursor) = match page_position(query.sort.as_deref(), query.cursor.as_deref()) {
+        Ok(position) => position,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
+        }
+    };
+    let params = SearchParams {
+        query: query.q.clone(),
+        limit: query.limit,
+        sort,
+        cursor,
+    };
+
+    match crate::api::search::search_games(&db, &params).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(ApiResponse::success(page))),
+        Err(e) => {
+            tracing::error!(q = %query.q, error = %e, "game search failed");
+            Ok(HttpResponse::InternalServerError().json(ApiResponse::<()>::error("Search failed")))
+        }
+    }
+}
+
+/// Priced games with their region/retailer coverage, keyset-paginated like search
+pub async fn list_coverage(
+    query: web::Query<CoverageQuery>,
+    db: web::Data<Db>,
+) -> Result<HttpResponse> {
+    use crate::api::coverage::CoverageParams;
+
+    let (sort, cursor) = match page_position(query.sort.as_deref(), query.cursor.as_deref()) {
+        Ok(position) => position,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest().json(ApiResponse::<()>::error(e.to_string())))
+        }
+    };
+    let params = CoverageParams {
+        limit: query.limit,
+        sort,
+        cursor,
+    };
+
+    match crate::api::coverage::list_coverage(&db, &params).await {
+        Ok(page) => Ok(HttpResponse::Ok().json(ApiResponse::success(page))),
+        Err(e) => {
+            tracing::error!(error = %e, "coverage listing failed");
+            Ok(HttpResponse::InternalServerError()
+                .json(ApiResponse::<()>::error("Coverage listing failed")))
+        }
+    }
+}
@@ -0,0 +1,434 @@
+// Provider run metrics endpoints (/api/metrics, /metrics)
+//
+// Serves the scheduler's ProviderMetricsRegistry. The PlayStation loop's counters are also
+// repeated under `playstation` in the shape this endpoint returned before other providers
+// were tracked. `/metrics` renders the same registry in the Prometheus text format, along
+// with the ingest queue's depth.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use actix_web::{web, HttpResponse};
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use crate::database_ops::db::Db;
+use crate::database_ops::provider_loop::{ProviderMetrics, ProviderMetricsRegistry};
+use crate::database_ops::schema_caps::SchemaCaps;
+
+/// Registry name of the PlayStation Store seed loop.
+pub const PLAYSTATION_PROVIDER: &str = "psstore";
+
+/// Legacy `/api/metrics` body, kept under the `playstation` key.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlaystationMetrics {
+    pub last_run_ms: u64,
+    pub runs: u64,
+    pub failures: u64,
+    pub last_error: Option<String>,
+}
+
+impl From<ProviderMetrics> for PlaystationMetrics {
+    fn from(m: ProviderMetrics) -> Self {
+        Self {
+            last_run_ms: m.last_run_ms,
+            runs: m.runs,
+            failures: m.failures,
+            last_error: m.last_error,
+        }
+    }
+}
+
+/// GET /api/metrics
+pub async fn get_metrics(registry: web::Data<ProviderMetricsRegistry>) -> HttpResponse {
+    let providers = registry.snapshot();
+    let playstation: PlaystationMetrics = providers
+        .get(PLAYSTATION_PROVIDER)
+        .cloned()
+        .map(Into::into)
+        .unwrap_or_default();
+    HttpResponse::Ok().json(serde_json::json!({
+        "playstation": playstation,
+        "providers": providers,
+    }))
+}
+
+/// Content type of the Prometheus text exposition format.
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Builder for the Prometheus text exposition format (version 0.0.4): each family's
+/// `# HELP` / `# TYPE` lines followed by its samples.
+#[derive(Debug, Default)]
+pub struct PromText {
+    out: String,
+}
+
+impl PromText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start the family `name`; `kind` is `counter` or `gauge`.
+    pub fn family(&mut self, name: &str, kind: &str, help: &str) -> &mut Self {
+        let help = help.replace('\\', "\\\\").replace('\n', "\\n");
+        let _ = writeln!(self.out, "# HELP {name} {help}");
+        let _ = writeln!(self.out, "# TYPE {name} {kind}");
+        self
+    }
+
+    /// One sample of the current family.
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) -> &mut Self {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| {
+                    let v = v
+                        .replace('\\', "\\\\")
+                        .replace('"', "\\\"")
+                        .replace('\n', "\\n");
+                    format!("{k}=\"{v}\"")
+                })
+                .collect();
+            let _ = write!(self.out, "{{{}}}", labels.join(","));
+        }
+        let value = match value {
+            v if v.is_nan() => "NaN".to_string(),
+            v if v == f64::INFINITY => "+Inf".to_string(),
+            v if v == f64::NEG_INFINITY => "-Inf".to_string(),
+            v => v.to_string(),
+        };
+        let _ = writeln!(self.out, " {value}");
+        self
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+/// The registry as Prometheus text: runs, failures, last run duration, last success time
+/// and, for providers holding an OAuth token, its remaining lifetime per provider.
+pub fn provider_metrics_text(providers: &BTreeMap<String, ProviderMetrics>) -> String {
+    type Value = fn(&ProviderMetrics) -> Option<f64>;
+    let families: [(&str, &str, &str, Value); 5] = [
+        (
+            "ingest_provider_runs_total",
+            "counter",
+            "Successful provider runs.",
+            |m| Some(m.runs as f64),
+        ),
+        (
+            "ingest_provider_failures_total",
+            "counter",
+            "Failed provider runs.",
+            |m| Some(m.failures as f64),
+        ),
+        (
+            "ingest_provider_last_run_duration_seconds",
+            "gauge",
+            "Duration of the provider's most recent run.",
+            |m| Some(m.last_run_ms as f64 / 1000.0),
+        ),
+        (
+            "ingest_provider_last_success_timestamp_seconds",
+            "gauge",
+            "Unix time of the provider's last successful run.",
+            |m| m.last_success_at.map(|at| at.timestamp() as f64),
+        ),
+        (
+            "ingest_provider_token_ttl_seconds",
+            "gauge",
+            "Seconds left on the provider's OAuth token.",
+            |m| {
+                m.token_expires_at
+                    .map(|at| (at - chrono::Utc::now()).num_seconds().max(0) as f64)
+            },
+        ),
+    ];
+    let mut text = PromText::new();
+    for (name, kind, help, value) in families {
+        text.family(name, kind, help);
+        for (provider, m) in providers {
+            if let Some(v) = value(m) {
+                text.sample(name, &[("provider", provider)], v);
+            }
+        }
+    }
+    text.finish()
+}
+
+/// The ingest worker's pgmq queue (`INGEST_QUEUE_NAME`, default `default_ingest`).
+pub fn ingest_queue_name() -> String {
+    std::env::var("INGEST_QUEUE_NAME")
+        .ok()
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "default_ingest".to_string())
+}
+
+/// Messages in `pgmq.q_<queue>`, visible or not, and the age of the oldest one (`None`
+/// when the queue is empty).
+pub async fn queue_stats(db: &Db, queue: &str) -> Result<(i64, Option<i64>)> {
+    let sql = format!(
+        "SELECT count(*)::bigint,
+                extract(epoch FROM now() - min(enqueued_at))::bigint
+         FROM pgmq.q_{queue}"
+    );
+    Ok(sqlx::query_as(&sql)
+        .persistent(false)
+        .fetch_one(&db.pool)
+        .await?)
+}
+
+/// Queue depth and oldest message age as Prometheus text, labelled with `queue`.
+pub fn queue_metrics_text(queue: &str, depth: i64, oldest_msg_age_secs: Option<i64>) -> String {
+    let labels = [("queue", queue)];
+    let mut text = PromText::new();
+    text.family(
+        "ingest_queue_depth",
+        "gauge",
+        "Messages in the queue, visible or not.",
+    )
+    .sample("ingest_queue_depth", &labels, depth as f64);
+    text.family(
+        "ingest_queue_oldest_message_age_seconds",
+        "gauge",
+        "Age of the oldest message in the queue.",
+    );
+    if let Some(age) = oldest_msg_age_secs {
+        text.sample(
+            "ingest_queue_oldest_message_age_seconds",
+            &labels,
+            age as f64,
+        );
+    }
+    text.finish()
+}
+
+/// GET /metrics
+pub async fn get_prometheus_metrics(
+    registry: web::Data<ProviderMetricsRegistry>,
+    db: web::Data<Db>,
+) -> HttpResponse {
+    let mut body = provider_metrics_text(&registry.snapshot());
+    // The queue is only there once pgmq is installed and the worker has created it.
+    let queue = ingest_queue_name();
+    let stats = match SchemaCaps::global()
+        .table_visible(&db, &format!("pgmq.q_{queue}"))
+        .await
+    {
+        Ok(true) => queue_stats(&db, &queue).await.map(Some),
+        Ok(false) => Ok(None),
+        Err(err) => Err(err),
+    };
+    match stats {
+        Ok(Some((depth, oldest))) => body.push_str(&queue_metrics_text(&queue, depth, oldest)),
+        Ok(None) => {}
+        Err(err) => tracing::warn!(queue = %queue, error = %err, "ingest queue stats failed"),
+    }
+    HttpResponse::Ok()
+        .content_type(PROMETHEUS_CONTENT_TYPE)
+        .body(body)
+}
+
+/// Check `text` line by line against the text exposition format: every sample belongs to
+/// the family most recently declared by `# TYPE`, label values are quoted and the value is
+/// a float. Returns each sample as `(name, labels, value)`.
+pub fn parse_exposition(text: &str) -> Result<Vec<(String, String, f64)>> {
+    let name = r"[a-zA-Z_:][a-zA-Z0-9_:]*";
+    let help = regex::Regex::new(&format!(r"^# HELP ({name}) .*$"))?;
+    let kind = regex::Regex::new(&format!(
+        r"^# TYPE ({name}) (counter|gauge|histogram|summary|untyped)$"
+    ))?;
+    let sample = regex::Regex::new(&format!(
+        r#"^({name})(\{{(?:[a-zA-Z_][a-zA-Z0-9_]*="(?:[^"\\\n]|\\.)*",?)*\}})? (\S+)$"#
+    ))?;
+    if !text.ends_with('\n') {
+        bail!("missing trailing newline");
+    }
+    let mut family: Option<&str> = None;
+    let mut samples = Vec::new();
+    for line in text.lines() {
+        if help.is_match(line) {
+            continue;
+        }
+        if let Some(c) = kind.captures(line) {
+            family = c.get(1).map(|m| m.as_str());
+            continue;
+        }
+        let Some(c) = sample.captures(line) else {
+            bail!("not a sample: {line:?}");
+        };
+        if family != Some(&c[1]) {
+            bail!("undeclared: {line:?}");
+        }
+        let Ok(value) = c[3].parse::<f64>() else {
+            bail!("bad value: {line:?}");
+        };
+        let labels = c.get(2).map_or("", |m| m.as_str()).to_string();
+        samples.push((c[1].to_string(), labels, value));
+    }
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database_ops::db::Db;
+    use crate::database_ops::provider_loop::{
+        run_provider_loop, IngestProvider, ProviderTickSummary, ProviderWakes,
+    };
+    use actix_web::{test, App};
+    use std::time::Duration;
+
+    struct Down;
+
+    #[async_trait::async_trait]
+    impl IngestProvider for Down {
+        fn name(&self) -> &str {
+            "xbox"
+        }
+        async fn tick(&self, _db: &Db) -> anyhow::Result<ProviderTickSummary> {
+            anyhow::bail!("displaycatalog returned 503")
+        }
+        fn interval(&self) -> Duration {
+            Duration::from_secs(3600)
+        }
+    }
+
+    #[actix_web::test]
+    async fn reports_every_provider_and_legacy_playstation_shape() {
+        let registry = ProviderMetricsRegistry::default();
+        registry.record_success(PLAYSTATION_PROVIDER, Duration::from_millis(1200));
+        let db = Db {
+            pool: sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://metrics@127.0.0.1:1/none")
+                .unwrap(),
+        };
+        let (tx, rx) = tokio::sync::broadcast::channel(1);
+        tx.send(()).unwrap();
+        run_provider_loop(&Down, &db, &registry, &ProviderWakes::default(), rx).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(registry))
+                .route("/api/metrics", web::get().to(get_metrics)),
+        )
+        .await;
+        let req = test::TestRequest::get().uri("/api/metrics").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(body["providers"]["xbox"]["runs"], 0);
+        assert_eq!(body["providers"]["xbox"]["failures"], 1);
+        assert_eq!(
+            body["providers"]["xbox"]["last_error"],
+            "displaycatalog returned 503"
+        );
+        assert!(body["providers"]["xbox"]["last_success_at"].is_null());
+        assert!(body["providers"]["psstore"]["last_success_at"].is_string());
+        assert_eq!(
+            body["playstation"],
+            serde_json::json!({
+                "last_run_ms": 1200,
+                "runs": 1,
+                "failures": 0,
+                "last_error": null,
+            })
+        );
+    }
+
+    #[actix_web::test]
+    async fn prometheus_text_parses_and_names_every_family() {
+        let registry = ProviderMetricsRegistry::default();
+        registry.record_success("igdb", Duration::from_millis(2500));
+        registry.record_success("igdb", Duration::from_millis(1500));
+        registry.record_failure(
+            "xbox",
+            Duration::from_millis(40),
+            "displaycatalog returned 503",
+        );
+        registry.record_token_expiry("igdb", chrono::Utc::now() + chrono::Duration::hours(1));
+
+        // Unreachable, so the queue stats are left out.
+        let db = Db {
+            pool: sqlx::postgres::PgPoolOptions::new()
+                .acquire_timeout(Duration::from_millis(200))
+                .connect_lazy("postgres://metrics@127.0.0.1:1/none")
+                .unwrap(),
+        };
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(registry))
+                .app_data(web::Data::new(db))
+                .route("/metrics", web::get().to(get_prometheus_metrics)),
+        )
+        .await;
+        let req = test::TestRequest::get().uri("/metrics").to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(
+            resp.headers().get("content-type").unwrap(),
+            PROMETHEUS_CONTENT_TYPE
+        );
+        let body = test::read_body(resp).await;
+        let samples = parse_exposition(std::str::from_utf8(&body).unwrap()).unwrap();
+
+        let value = |name: &str, provider: &str| {
+            let labels = format!("{{provider=\"{provider}\"}}");
+            samples
+                .iter()
+                .find(|(n, l, _)| n == name && *l == labels)
+                .map(|s| s.2)
+        };
+        assert_eq!(value("ingest_provider_runs_total", "igdb"), Some(2.0));
+        assert_eq!(value("ingest_provider_failures_total", "igdb"), Some(0.0));
+        assert_eq!(value("ingest_provider_failures_total", "xbox"), Some(1.0));
+        assert_eq!(
+            value("ingest_provider_last_run_duration_seconds", "igdb"),
+            Some(1.5)
+        );
+        assert!(value("ingest_provider_last_success_timestamp_seconds", "igdb").is_some());
+        assert_eq!(
+            value("ingest_provider_last_success_timestamp_seconds", "xbox"),
+            None
+        );
+        let ttl = value("ingest_provider_token_ttl_seconds", "igdb").unwrap();
+        assert!((3590.0..=3600.0).contains(&ttl), "{ttl}");
+        assert_eq!(value("ingest_provider_token_ttl_seconds", "xbox"), None);
+
+        let mut text = PromText::new();
+        text.family("odd", "gauge", "Label escaping.").sample(
+            "odd",
+            &[("v", "a\"b\\c\nd")],
+            f64::NAN,
+        );
+        let text = text.finish();
+        assert!(text.contains(r#"odd{v="a\"b\\c\nd"} NaN"#), "{text}");
+        assert_eq!(parse_exposition(&text).unwrap().len(), 1);
+        assert!(
+            parse_exposition("odd 1\n").is_err(),
+            "sample before its # TYPE"
+        );
+    }
+
+    #[actix_web::test]
+    async fn queue_text_parses_with_depth_and_age() {
+        let text = queue_metrics_text("default_ingest", 40, Some(630));
+        let samples = parse_exposition(&text).unwrap();
+        let labels = r#"{queue="default_ingest"}"#.to_string();
+        assert_eq!(
+            samples,
+            [
+                ("ingest_queue_depth".to_string(), labels.clone(), 40.0),
+                (
+                    "ingest_queue_oldest_message_age_seconds".to_string(),
+                    labels,
+                    630.0
+                ),
+            ]
+        );
+        // An empty queue has no oldest message.
+        let idle = parse_exposition(&queue_metrics_text("default_ingest", 0, None)).unwrap();
+        assert_eq!(idle.len(), 1);
+    }
+}
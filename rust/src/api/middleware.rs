@@ -30,3 +30,95 @@ pub fn setup_cors(allowed_origins: &str) -> Cors {
 
     cors
 }
+
+/// CORS policy for the scheduler/worker HTTP servers, from `HTTP_CORS_ORIGINS`
+/// (comma-separated origins, or `*`). Unset or empty allows no cross-origin reads.
+pub fn cors_from_env() -> Cors {
+    cors_for_origins(&crate::util::env::env_opt("HTTP_CORS_ORIGINS").unwrap_or_default())
+}
+
+/// Read-only CORS policy: only GET is exposed cross-origin. Requests from origins outside
+/// the allowlist are still served, just without CORS headers, so the browser blocks them.
+pub fn cors_for_origins(allowed_origins: &str) -> Cors {
+    let mut cors = Cors::default()
+        .allowed_methods(vec!["GET"])
+        .allowed_headers(vec![header::ACCEPT, header::CONTENT_TYPE])
+        .block_on_origin_mismatch(false)
+        .max_age(3600);
+
+    for origin in allowed_origins
+        .split(',')
+        .map(str::trim)
+        .filter(|o| !o.is_empty())
+    {
+        cors = if origin == "*" {
+            cors.allow_any_origin()
+        } else {
+            cors.allowed_origin(origin)
+        };
+    }
+
+    cors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, web, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn cors_headers_only_for_allowed_origins() {
+        let app = test::init_service(
+            App::new()
+                .wrap(cors_for_origins(
+                    "https://allowed.example, https://other.example",
+                ))
+                .route(
+                    "/api/metrics",
+                    web::get().to(|| async { HttpResponse::Ok().finish() }),
+                ),
+        )
+        .await;
+
+        let allowed = test::TestRequest::get()
+            .uri("/api/metrics")
+            .insert_header((header::ORIGIN, "https://allowed.example"))
+            .to_request();
+        let resp = test::call_service(&app, allowed).await;
+        assert!(resp.status().is_success());
+        assert_eq!(
+            resp.headers()
+                .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+                .unwrap(),
+            "https://allowed.example"
+        );
+
+        let denied = test::TestRequest::get()
+            .uri("/api/metrics")
+            .insert_header((header::ORIGIN, "https://evil.example"))
+            .to_request();
+        let resp = test::call_service(&app, denied).await;
+        assert!(resp
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
+
+    #[actix_web::test]
+    async fn empty_allowlist_allows_no_origin() {
+        let app = test::init_service(App::new().wrap(cors_for_origins("")).route(
+            "/api/metrics",
+            web::get().to(|| async { HttpResponse::Ok().finish() }),
+        ))
+        .await;
+        let req = test::TestRequest::get()
+            .uri("/api/metrics")
+            .insert_header((header::ORIGIN, "https://allowed.example"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert!(resp
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
+}
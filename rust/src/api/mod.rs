@@ -2,10 +2,23 @@
 // Provides RESTful APIs for Laravel (game-compare) integration
 
 pub mod auth;
+pub mod cache;
+pub mod coverage;
 pub mod handlers;
+pub mod metrics;
 pub mod middleware;
 pub mod models;
+pub mod price_compare;
+pub mod price_fallback;
+pub mod profile;
+pub mod providers;
+pub mod rate_limit;
 pub mod routes;
+pub mod search;
 pub mod server;
+pub mod version;
 
+pub use price_fallback::price_with_fallback;
+pub use profile::game_profile;
+pub use search::search_games;
 pub use server::ApiServer;
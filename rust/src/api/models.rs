@@ -111,6 +111,151 @@ pub struct PriceData {
     pub timestamp: DateTime<Utc>,
 }
 
+/// Game profile query parameters
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameProfileQuery {
+    /// Comma-separated ISO country codes to restrict prices/ratings (e.g. "US,GB")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regions: Option<String>,
+}
+
+/// Cross-region price comparison query parameters
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PriceCompareQuery {
+    /// Comma-separated ISO country codes to compare (default: every region with a price)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regions: Option<String>,
+    /// "exclusive" or "inclusive" to normalize tax; omitted compares prices as listed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax: Option<String>,
+    /// Currency to compare in (default "USD")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+}
+
+/// Aggregated game profile response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameProfile {
+    pub video_game_id: i64,
+    pub title: Option<String>,
+    pub display_title: Option<String>,
+    pub synopsis: Option<String>,
+    pub genres: Vec<String>,
+    pub release_date: Option<chrono::NaiveDate>,
+    pub media: Vec<ProfileMedia>,
+    pub ratings: Vec<LocaleRating>,
+    /// One rating per source (PS Store, Metacritic, Steam, IGDB) on the source's own scale
+    pub external_ratings: Vec<ExternalRating>,
+    pub prices: Vec<ProfilePrice>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfileMedia {
+    pub url: String,
+    pub media_type: Option<String>,
+    pub title: Option<String>,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LocaleRating {
+    pub locale: String,
+    pub average_rating: f32,
+    pub rating_count: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalRating {
+    pub source: String,
+    pub display_name: String,
+    pub score: f64,
+    pub scale: f64,
+    pub rating_count: Option<i64>,
+    /// Rendered label, e.g. "Steam 94% (12k)"
+    pub label: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Latest known price for one retailer/region/currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfilePrice {
+    pub retailer: Option<String>,
+    pub country_code: Option<String>,
+    pub currency: String,
+    pub amount_minor: i64,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Title search query parameters
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchQuery {
+    pub q: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    /// "relevance" (default), "release_date" or "price"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// Opaque `next_cursor` from the previous page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// One page of search results
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchPage {
+    pub results: Vec<GameSearchResult>,
+    /// Total matches across all pages
+    pub total: i64,
+    pub next_cursor: Option<String>,
+}
+
+/// One game matched by title search
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameSearchResult {
+    pub video_game_id: i64,
+    pub title_id: i64,
+    pub display_title: String,
+    pub platforms: Vec<String>,
+    pub release_date: Option<chrono::NaiveDate>,
+    /// Trigram score in 0..=1 (higher is a closer match)
+    pub score: f32,
+    pub best_price: Option<ProfilePrice>,
+}
+
+/// Price coverage listing query parameters
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CoverageQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+    /// "relevance" (most regions first, default), "release_date" or "price"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    /// Opaque `next_cursor` from the previous page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// One page of the price coverage listing
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CoveragePage {
+    pub results: Vec<GameCoverage>,
+    /// Total priced games across all pages
+    pub total: i64,
+    pub next_cursor: Option<String>,
+}
+
+/// Regions and retailers a game has current prices in
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameCoverage {
+    pub video_game_id: i64,
+    pub display_title: Option<String>,
+    pub release_date: Option<chrono::NaiveDate>,
+    pub regions: Vec<String>,
+    pub retailers: Vec<String>,
+    pub best_price: Option<ProfilePrice>,
+}
+
 /// Error details for debugging
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorDetail {
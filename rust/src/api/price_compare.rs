@@ -0,0 +1,328 @@
+// Cross-region price comparison
+//
+// Lists the latest price per retailer and region for a game, cheapest first in a single
+// comparison currency. Stores quote EU prices with VAT included and US prices before sales
+// tax, so the comparison can normalize every row to one tax basis using its
+// `tax_inclusive` flag and the rate from `normalization::tax_rate_for_jurisdiction`.
+// Amounts are read back with the scale their writer used: Steam stores every currency
+// in hundredths, other writers use the currency's stored `currencies.minor_unit`.
+
+use crate::api::models::ProfilePrice;
+use crate::api::profile::normalize_regions;
+use crate::database_ops::db::Db;
+use crate::database_ops::exchange::ExchangeService;
+use crate::database_ops::schema_caps::SchemaCaps;
+use crate::normalization::tax::{normalize_tax, tax_rate_for_jurisdiction, TaxBasis};
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::Row;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+pub const DEFAULT_COMPARE_CURRENCY: &str = "USD";
+
+/// Retailers whose writers store `amount_minor` in hundredths whatever the currency
+/// (the store APIs quote cents, JPY included).
+pub(crate) const CENT_SCALED_RETAILERS: &[&str] = &["steam", "psstore", "ps-store", "nexarda"];
+
+/// A latest listed price with what is needed to read it back.
+#[derive(Debug, Clone)]
+struct Listed {
+    price: ProfilePrice,
+    tax_inclusive: bool,
+    /// Decimal places of `price.amount_minor` as written.
+    minor_unit: i16,
+}
+
+/// Decimal places the writer of a row used: two for [`CENT_SCALED_RETAILERS`], else the
+/// stored `currencies.minor_unit`, else the built-in table.
+pub(crate) fn writer_minor_unit(
+    retailer: Option<&str>,
+    stored: Option<i16>,
+    currency: &str,
+) -> i16 {
+    let retailer = retailer.map(|r| r.trim().to_ascii_lowercase());
+    if retailer.is_some_and(|r| CENT_SCALED_RETAILERS.contains(&r.as_str())) {
+        return 2;
+    }
+    stored.unwrap_or_else(|| crate::util::currency::minor_unit(currency))
+}
+
+/// One listed price, normalized for comparison.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparedPrice {
+    #[serde(flatten)]
+    pub price: ProfilePrice,
+    /// Whether the store listed `price` with tax included.
+    pub tax_inclusive: bool,
+    /// Rate applied to reach the requested basis; `None` when the price is left as listed
+    /// (no basis requested, already on it, or jurisdiction not in the tax table).
+    pub tax_rate: Option<f64>,
+    /// `price.amount_minor` on the requested tax basis, in the listing currency.
+    pub adjusted_amount_minor: i64,
+    /// The adjusted amount in the comparison currency (major units); `None` when no
+    /// exchange rate is known.
+    pub compare_amount: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceComparison {
+    pub currency: String,
+    pub tax_basis: Option<TaxBasis>,
+    /// Cheapest first; prices without an exchange rate last.
+    pub prices: Vec<ComparedPrice>,
+}
+
+/// Compare the latest prices of `video_game_id` across `regions` (all regions when empty),
+/// normalized to `tax_basis` and converted to `currency`.
+pub async fn compare_prices(
+    db: &Db,
+    video_game_id: i64,
+    regions: &[String],
+    tax_basis: Option<TaxBasis>,
+    currency: &str,
+) -> Result<PriceComparison> {
+    let currency = currency.trim().to_ascii_uppercase();
+    let regions = normalize_regions(regions);
+    let region_filter: Option<&[String]> = (!regions.is_empty()).then_some(regions.as_slice());
+    if !SchemaCaps::global()
+        .table_visible(db, "public.video_game_prices")
+        .await?
+    {
+        return Ok(PriceComparison {
+            currency,
+            tax_basis,
+            prices: Vec::new(),
+        });
+    }
+
+    // to_jsonb keeps this valid on legacy currencies tables without minor_unit.
+    let stored_minor_unit = if SchemaCaps::global()
+        .table_visible(db, "public.currencies")
+        .await?
+    {
+        "(SELECT (to_jsonb(c)->>'minor_unit')::smallint FROM public.currencies c
+          WHERE upper(c.code) = upper(p.currency) LIMIT 1)"
+    } else {
+        "NULL::smallint"
+    };
+    let sql = format!(
+        "SELECT DISTINCT ON (p.retailer, p.country_code, p.currency)
+                p.retailer, upper(p.country_code) AS country_code, p.currency,
+                p.amount_minor, p.tax_inclusive, p.recorded_at,
+                {stored_minor_unit} AS minor_unit
+         FROM public.video_game_prices p
+         WHERE p.video_game_id = $1
+           AND ($2::text[] IS NULL OR upper(p.country_code) = ANY($2))
+         ORDER BY p.retailer, p.country_code, p.currency, p.recorded_at DESC"
+    );
+    let listed = sqlx::query(&sql)
+        .persistent(false)
+        .bind(video_game_id)
+        .bind(region_filter)
+        .fetch_all(&db.pool)
+        .await?
+        .into_iter()
+        .map(|r| -> Result<Listed> {
+            let price = ProfilePrice {
+                retailer: r.try_get("retailer")?,
+                country_code: r.try_get("country_code")?,
+                currency: r.try_get("currency")?,
+                amount_minor: r.try_get("amount_minor")?,
+                recorded_at: r.try_get("recorded_at")?,
+            };
+            let minor_unit = writer_minor_unit(
+                price.retailer.as_deref(),
+                r.try_get("minor_unit")?,
+                &price.currency,
+            );
+            Ok(Listed {
+                price,
+                tax_inclusive: r.try_get("tax_inclusive")?,
+                minor_unit,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let rates = exchange_rates(
+        db,
+        listed.iter().map(|l| l.price.currency.as_str()),
+        &currency,
+    )
+    .await?;
+
+    Ok(PriceComparison {
+        prices: rank(listed, tax_basis, &rates),
+        currency,
+        tax_basis,
+    })
+}
+
+/// Rate per major unit from each of `codes` into `currency`, keyed by upper-cased code;
+/// codes without a known rate are left out.
+pub(crate) async fn exchange_rates<'a>(
+    db: &Db,
+    codes: impl IntoIterator<Item = &'a str>,
+    currency: &str,
+) -> Result<HashMap<String, f64>> {
+    let fx = ExchangeService::new(db.clone());
+    let mut rates = HashMap::new();
+    for code in codes {
+        if let Entry::Vacant(slot) = rates.entry(code.to_ascii_uppercase()) {
+            if let Some(rate) = fx.convert(1.0, slot.key(), currency).await? {
+                slot.insert(rate);
+            }
+        }
+    }
+    Ok(rates)
+}
+
+/// Normalize `listed` to `tax_basis`, convert with `rates` (listing currency -> comparison
+/// currency per major unit) and order cheapest first.
+fn rank(
+    listed: Vec<Listed>,
+    tax_basis: Option<TaxBasis>,
+    rates: &HashMap<String, f64>,
+) -> Vec<ComparedPrice> {
+    let mut compared: Vec<ComparedPrice> = listed
+        .into_iter()
+        .map(|listed| {
+            let Listed {
+                price,
+                tax_inclusive,
+                minor_unit,
+            } = listed;
+            let rate = tax_basis.and_then(|basis| {
+                let on_basis = tax_inclusive == (basis == TaxBasis::Inclusive);
+                let rate = tax_rate_for_jurisdiction(price.country_code.as_deref()?)?;
+                (!on_basis).then_some((basis, rate))
+            });
+            let adjusted_amount_minor = match rate {
+                Some((basis, rate)) => {
+                    normalize_tax(price.amount_minor, tax_inclusive, rate, basis)
+                }
+                None => price.amount_minor,
+            };
+            let scale = 10f64.powi(i32::from(minor_unit));
+            let compare_amount = rates
+                .get(&price.currency.to_ascii_uppercase())
+                .map(|fx| adjusted_amount_minor as f64 / scale * fx);
+            ComparedPrice {
+                price,
+                tax_inclusive,
+                tax_rate: rate.map(|(_, rate)| rate),
+                adjusted_amount_minor,
+                compare_amount,
+            }
+        })
+        .collect();
+    compared.sort_by(|a, b| match (a.compare_amount, b.compare_amount) {
+        (Some(x), Some(y)) => x.total_cmp(&y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.adjusted_amount_minor.cmp(&b.adjusted_amount_minor),
+    });
+    compared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn listed_by(
+        retailer: &str,
+        region: &str,
+        currency: &str,
+        amount_minor: i64,
+        tax_inclusive: bool,
+    ) -> Listed {
+        Listed {
+            price: ProfilePrice {
+                retailer: Some(retailer.to_string()),
+                country_code: Some(region.to_string()),
+                currency: currency.to_string(),
+                amount_minor,
+                recorded_at: Utc::now(),
+            },
+            tax_inclusive,
+            minor_unit: writer_minor_unit(Some(retailer), None, currency),
+        }
+    }
+
+    fn listed(region: &str, currency: &str, amount_minor: i64, tax_inclusive: bool) -> Listed {
+        listed_by("steam", region, currency, amount_minor, tax_inclusive)
+    }
+
+    #[test]
+    fn tax_normalization_changes_cheapest_region() {
+        // DE lists 62.99 EUR with 19% VAT; US lists 59.99 USD before sales tax.
+        let prices = vec![
+            listed("DE", "EUR", 6299, true),
+            listed("US", "USD", 5999, false),
+        ];
+        let rates = HashMap::from([("EUR".to_string(), 1.0), ("USD".to_string(), 1.0)]);
+        let regions = |ranked: &[ComparedPrice]| -> Vec<String> {
+            ranked
+                .iter()
+                .map(|p| p.price.country_code.clone().unwrap())
+                .collect()
+        };
+
+        let as_listed = rank(prices.clone(), None, &rates);
+        assert_eq!(regions(&as_listed), vec!["US", "DE"]);
+
+        let exclusive = rank(prices.clone(), Some(TaxBasis::Exclusive), &rates);
+        assert_eq!(regions(&exclusive), vec!["DE", "US"]);
+        assert_eq!(exclusive[0].adjusted_amount_minor, 5293);
+        assert_eq!(exclusive[0].tax_rate, Some(0.19));
+        assert_eq!(exclusive[1].adjusted_amount_minor, 5999);
+        assert_eq!(exclusive[1].tax_rate, None);
+
+        let inclusive = rank(prices, Some(TaxBasis::Inclusive), &rates);
+        assert_eq!(regions(&inclusive), vec!["US", "DE"]);
+        assert_eq!(inclusive[0].tax_rate, Some(0.0));
+        assert_eq!(inclusive[0].compare_amount, Some(59.99));
+    }
+
+    #[test]
+    fn prices_without_a_rate_rank_last() {
+        let rates = HashMap::from([("USD".to_string(), 1.0)]);
+        let ranked = rank(
+            vec![
+                listed("JP", "JPY", 100, true),
+                listed("US", "USD", 9999, false),
+            ],
+            None,
+            &rates,
+        );
+        assert_eq!(ranked[0].price.currency, "USD");
+        assert_eq!(ranked[1].compare_amount, None);
+    }
+
+    #[test]
+    fn jpy_rows_are_read_with_their_writers_scale() {
+        // Steam stores ¥7,000 as 700000 (hundredths); a CSV import stores ¥7,500 as 7500.
+        let rates = HashMap::from([("JPY".to_string(), 0.0067)]);
+        let ranked = rank(
+            vec![
+                listed_by("csv_import", "JP", "JPY", 7500, true),
+                listed_by("steam", "JP", "JPY", 700_000, true),
+            ],
+            None,
+            &rates,
+        );
+        let retailers: Vec<&str> = ranked
+            .iter()
+            .map(|p| p.price.retailer.as_deref().unwrap())
+            .collect();
+        assert_eq!(retailers, vec!["steam", "csv_import"]);
+        assert!((ranked[0].compare_amount.unwrap() - 46.9).abs() < 1e-9);
+        assert!((ranked[1].compare_amount.unwrap() - 50.25).abs() < 1e-9);
+
+        // A stored currencies.minor_unit wins over the built-in table for other writers.
+        assert_eq!(writer_minor_unit(Some("csv_import"), Some(2), "JPY"), 2);
+        assert_eq!(writer_minor_unit(Some("Steam"), Some(0), "JPY"), 2);
+        assert_eq!(writer_minor_unit(None, None, "JPY"), 0);
+    }
+}
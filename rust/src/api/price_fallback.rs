@@ -0,0 +1,156 @@
+// Regional price lookup with a fallback chain
+//
+// When the preferred region has no current price, walk a configurable list of regions
+// (PRICE_FALLBACK_REGIONS, default "US") and report which region satisfied the lookup.
+
+use crate::api::models::ProfilePrice;
+use crate::api::profile::normalize_regions;
+use crate::database_ops::db::Db;
+use crate::database_ops::schema_caps::SchemaCaps;
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::Row;
+
+pub const DEFAULT_FALLBACK_REGIONS: &str = "US";
+
+/// A current price and the region in the chain that supplied it.
+#[derive(Debug, Clone, Serialize)]
+pub struct RegionalPrice {
+    pub region: String,
+    /// True when the price came from a fallback rather than the preferred region.
+    pub fell_back: bool,
+    pub price: ProfilePrice,
+}
+
+/// Fallback regions from `PRICE_FALLBACK_REGIONS` (comma-separated ISO codes).
+pub fn default_fallback_chain() -> Vec<String> {
+    let raw = crate::util::env::env_opt("PRICE_FALLBACK_REGIONS")
+        .unwrap_or_else(|| DEFAULT_FALLBACK_REGIONS.to_string());
+    normalize_regions(&[raw])
+}
+
+/// Current price for `video_game_id` in `preferred_region`, else the first region in
+/// `fallback_chain` (or [`default_fallback_chain`] when `None`) that has one. Within a
+/// region the cheapest latest price across retailers wins.
+pub async fn price_with_fallback(
+    db: &Db,
+    video_game_id: i64,
+    preferred_region: &str,
+    fallback_chain: Option<&[String]>,
+) -> Result<Option<RegionalPrice>> {
+    let default_chain;
+    let fallback_chain = match fallback_chain {
+        Some(chain) => chain,
+        None => {
+            default_chain = default_fallback_chain();
+            &default_chain
+        }
+    };
+    let chain = region_chain(preferred_region, fallback_chain);
+    if chain.is_empty()
+        || !SchemaCaps::global()
+            .table_visible(db, "public.video_game_prices")
+            .await?
+    {
+        return Ok(None);
+    }
+
+    let prices = sqlx::query(
+        "SELECT DISTINCT ON (retailer, country_code, currency)
+                retailer, upper(country_code) AS country_code, currency,
+                amount_minor, recorded_at
+         FROM public.video_game_prices
+         WHERE video_game_id = $1 AND upper(country_code) = ANY($2)
+         ORDER BY retailer, country_code, currency, recorded_at DESC",
+    )
+    .persistent(false)
+    .bind(video_game_id)
+    .bind(&chain)
+    .fetch_all(&db.pool)
+    .await?
+    .into_iter()
+    .map(|r| -> Result<ProfilePrice> {
+        Ok(ProfilePrice {
+            retailer: r.try_get("retailer")?,
+            country_code: r.try_get("country_code")?,
+            currency: r.try_get("currency")?,
+            amount_minor: r.try_get("amount_minor")?,
+            recorded_at: r.try_get("recorded_at")?,
+        })
+    })
+    .collect::<Result<Vec<_>>>()?;
+
+    Ok(pick_by_chain(&chain, prices))
+}
+
+/// Preferred region first, then the fallbacks, normalized and without duplicates.
+fn region_chain(preferred_region: &str, fallback_chain: &[String]) -> Vec<String> {
+    let mut raw = vec![preferred_region.to_string()];
+    raw.extend(fallback_chain.iter().cloned());
+    normalize_regions(&raw)
+}
+
+fn pick_by_chain(chain: &[String], prices: Vec<ProfilePrice>) -> Option<RegionalPrice> {
+    chain.iter().enumerate().find_map(|(i, region)| {
+        prices
+            .iter()
+            .filter(|p| p.country_code.as_deref() == Some(region.as_str()))
+            .min_by_key(|p| p.amount_minor)
+            .map(|price| RegionalPrice {
+                region: region.clone(),
+                fell_back: i > 0,
+                price: price.clone(),
+            })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn price(region: &str, amount_minor: i64) -> ProfilePrice {
+        ProfilePrice {
+            retailer: Some("playstation_store".to_string()),
+            country_code: Some(region.to_string()),
+            currency: "USD".to_string(),
+            amount_minor,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn chain_puts_preferred_first_without_duplicates() {
+        let fallbacks = vec!["us".to_string(), "GB".to_string()];
+        assert_eq!(region_chain("gb", &fallbacks), vec!["GB", "US"]);
+        assert_eq!(region_chain("", &fallbacks), vec!["US", "GB"]);
+    }
+
+    #[test]
+    fn fallback_region_supplies_missing_price() {
+        let chain = region_chain("DE", &["GB".to_string(), "US".to_string()]);
+        let found = pick_by_chain(&chain, vec![price("US", 5999), price("GB", 4999)]).unwrap();
+        assert_eq!(found.region, "GB");
+        assert!(found.fell_back);
+        assert_eq!(found.price.amount_minor, 4999);
+    }
+
+    #[test]
+    fn preferred_region_wins_with_cheapest_retailer() {
+        let chain = region_chain("US", &["GB".to_string()]);
+        let found = pick_by_chain(
+            &chain,
+            vec![price("GB", 100), price("US", 5999), price("US", 3999)],
+        )
+        .unwrap();
+        assert_eq!(found.region, "US");
+        assert!(!found.fell_back);
+        assert_eq!(found.price.amount_minor, 3999);
+    }
+
+    #[test]
+    fn no_price_anywhere_in_chain() {
+        let chain = region_chain("DE", &["FR".to_string()]);
+        assert!(pick_by_chain(&chain, vec![price("US", 5999)]).is_none());
+    }
+}
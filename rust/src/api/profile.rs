@@ -0,0 +1,512 @@
+// Aggregated game profile read model: one consumer-facing view of a video game assembled
+// from the ingested tables, partial when optional tables are missing.
+
+use crate::api::cache::ContentVersion;
+use crate::api::models::{ExternalRating, GameProfile, LocaleRating, ProfileMedia, ProfilePrice};
+use crate::database_ops::db::Db;
+use crate::database_ops::external_ratings::{display_label, RatingSource};
+use crate::database_ops::schema_caps::SchemaCaps;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+use std::collections::HashMap;
+
+/// Fetch the aggregated profile for `video_game_id`. When `regions` is non-empty, prices
+/// are limited to those ISO country codes and ratings to locales in those regions.
+/// Returns `Ok(None)` when the game does not exist.
+pub async fn game_profile(
+    db: &Db,
+    video_game_id: i64,
+    regions: &[String],
+) -> Result<Option<GameProfile>> {
+    Ok(game_profiles(db, &[video_game_id], regions).await?.pop())
+}
+
+/// [`game_profile`] for a batch of games in one query per source table. Profiles come back
+/// in `video_game_ids` order; ids that do not exist are skipped.
+pub async fn game_profiles(
+    db: &Db,
+    video_game_ids: &[i64],
+    regions: &[String],
+) -> Result<Vec<GameProfile>> {
+    if video_game_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let games = sqlx::query(
+        "SELECT vg.id, vgt.title, vg.display_title, vg.synopsis,
+                COALESCE(vg.genres, ARRAY[]::text[]) AS genres, vg.release_date
+         FROM public.video_games vg
+         LEFT JOIN public.video_game_titles vgt ON vgt.id = vg.title_id
+         WHERE vg.id = ANY($1)",
+    )
+    .persistent(false)
+    .bind(video_game_ids)
+    .fetch_all(&db.pool)
+    .await?;
+    if games.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let regions = normalize_regions(regions);
+    let region_filter: Option<&[String]> = (!regions.is_empty()).then_some(regions.as_slice());
+
+    let mut media: HashMap<i64, Vec<ProfileMedia>> = HashMap::new();
+    for r in sqlx::query(
+        "SELECT video_game_id, url, COALESCE(media_type::text, kind::text) AS media_type,
+                title, source::text AS source
+         FROM public.provider_media_links
+         WHERE video_game_id = ANY($1) AND url IS NOT NULL
+         ORDER BY video_game_id, id",
+    )
+    .persistent(false)
+    .bind(video_game_ids)
+    .fetch_all(&db.pool)
+    .await?
+    {
+        media
+            .entry(r.try_get("video_game_id")?)
+            .or_default()
+            .push(ProfileMedia {
+                url: r.try_get("url")?,
+                media_type: r.try_get("media_type")?,
+                title: r.try_get("title")?,
+                source: r.try_get("source")?,
+            });
+    }
+
+    let mut ratings: HashMap<i64, Vec<LocaleRating>> = HashMap::new();
+    if SchemaCaps::global()
+        .table_visible(db, "public.video_game_ratings_by_locale")
+        .await?
+    {
+        for r in sqlx::query(
+            "SELECT video_game_id, locale, average_rating, rating_count, rating_updated_at
+             FROM public.video_game_ratings_by_locale
+             WHERE video_game_id = ANY($1)
+             ORDER BY video_game_id, locale",
+        )
+        .persistent(false)
+        .bind(video_game_ids)
+        .fetch_all(&db.pool)
+        .await?
+        {
+            let rating = LocaleRating {
+                locale: r.try_get("locale")?,
+                average_rating: r.try_get("average_rating")?,
+                rating_count: r.try_get("rating_count")?,
+                updated_at: r.try_get("rating_updated_at")?,
+            };
+            if region_filter.is_none_or(|filter| locale_in_regions(&rating.locale, filter)) {
+                ratings
+                    .entry(r.try_get("video_game_id")?)
+                    .or_default()
+                    .push(rating);
+            }
+        }
+    }
+
+    let mut external_ratings: HashMap<i64, Vec<ExternalRating>> = HashMap::new();
+    if SchemaCaps::global()
+        .table_visible(db, "public.external_ratings")
+        .await?
+    {
+        for r in sqlx::query(
+            "SELECT video_game_id, source, score, scale, rating_count, updated_at
+             FROM public.external_ratings
+             WHERE video_game_id = ANY($1)
+             ORDER BY video_game_id, source",
+        )
+        .persistent(false)
+        .bind(video_game_ids)
+        .fetch_all(&db.pool)
+        .await?
+        {
+            let source: String = r.try_get("source")?;
+            let score: f64 = r.try_get("score")?;
+            let rating_count: Option<i64> = r.try_get("rating_count")?;
+            let (display_name, label) = match RatingSource::parse(&source) {
+                Some(known) => (
+                    known.display_name().to_string(),
+                    display_label(known, score, rating_count),
+                ),
+                None => (source.clone(), format!("{source} {score}")),
+            };
+            external_ratings
+                .entry(r.try_get("video_game_id")?)
+                .or_default()
+                .push(ExternalRating {
+                    source,
+                    display_name,
+                    score,
+                    scale: r.try_get("scale")?,
+                    rating_count,
+                    label,
+                    updated_at: r.try_get("updated_at")?,
+                });
+        }
+    }
+
+    let mut prices: HashMap<i64, Vec<ProfilePrice>> = HashMap::new();
+    if SchemaCaps::global()
+        .table_visible(db, "public.video_game_prices")
+        .await?
+    {
+        for r in sqlx::query(
+            "SELECT DISTINCT ON (video_game_id, retailer, country_code, currency)
+                    video_game_id, retailer, upper(country_code) AS country_code, currency,
+                    amount_minor, recorded_at
+             FROM public.video_game_prices
+             WHERE video_game_id = ANY($1)
+               AND ($2::text[] IS NULL OR upper(country_code) = ANY($2))
+             ORDER BY video_game_id, retailer, country_code, currency, recorded_at DESC",
+        )
+        .persistent(false)
+        .bind(video_game_ids)
+        .bind(region_filter)
+        .fetch_all(&db.pool)
+        .await?
+        {
+            prices
+                .entry(r.try_get("video_game_id")?)
+                .or_default()
+                .push(ProfilePrice {
+                    retailer: r.try_get("retailer")?,
+                    country_code: r.try_get("country_code")?,
+                    currency: r.try_get("currency")?,
+                    amount_minor: r.try_get("amount_minor")?,
+                    recorded_at: r.try_get("recorded_at")?,
+                });
+        }
+    }
+
+    let mut games: HashMap<i64, PgRow> = games
+        .into_iter()
+        .map(|row| Ok((row.try_get("id")?, row)))
+        .collect::<Result<_>>()?;
+    let mut profiles = Vec::with_capacity(games.len());
+    for id in video_game_ids {
+        let Some(row) = games.remove(id) else {
+            continue;
+        };
+        profiles.push(GameProfile {
+            video_game_id: *id,
+            title: row.try_get("title")?,
+            display_title: row.try_get("display_title")?,
+            synopsis: row.try_get("synopsis")?,
+            genres: row.try_get("genres")?,
+            release_date: row.try_get("release_date")?,
+            media: media.remove(id).unwrap_or_default(),
+            ratings: ratings.remove(id).unwrap_or_default(),
+            external_ratings: external_ratings.remove(id).unwrap_or_default(),
+            prices: prices.remove(id).unwrap_or_default(),
+        });
+    }
+    Ok(profiles)
+}
+
+/// Freshness of everything [`game_profile`] reads for `video_game_id`, used for ETag /
+/// Last-Modified. Returns `Ok(None)` when the game does not exist.
+pub async fn game_profile_version(db: &Db, video_game_id: i64) -> Result<Option<ContentVersion>> {
+    let mut parts = vec![
+        "SELECT true AS game, updated_at AS ts, 1::bigint AS n
+         FROM public.video_games WHERE id = $1",
+        "SELECT false, max(updated_at), count(*)
+         FROM public.provider_media_links WHERE video_game_id = $1 AND url IS NOT NULL",
+    ];
+    if SchemaCaps::global()
+        .table_visible(db, "public.video_game_ratings_by_locale")
+        .await?
+    {
+        parts.push(
+            "SELECT false, max(rating_updated_at), count(*)
+             FROM public.video_game_ratings_by_locale WHERE video_game_id = $1",
+        );
+    }
+    if SchemaCaps::global()
+        .table_visible(db, "public.external_ratings")
+        .await?
+    {
+        parts.push(
+            "SELECT false, max(updated_at), count(*)
+             FROM public.external_ratings WHERE video_game_id = $1",
+        );
+    }
+    if SchemaCaps::global()
+        .table_visible(db, "public.video_game_prices")
+        .await?
+    {
+        parts.push(
+            "SELECT false, max(recorded_at), count(*)
+             FROM public.video_game_prices WHERE video_game_id = $1",
+        );
+    }
+    let sql = format!(
+        "SELECT COALESCE(bool_or(game), false) AS found, max(ts) AS last_modified,
+                COALESCE(sum(n), 0)::bigint AS row_count
+         FROM ({}) s",
+        parts.join(" UNION ALL ")
+    );
+    let row = sqlx::query(&sql)
+        .persistent(false)
+        .bind(video_game_id)
+        .fetch_one(&db.pool)
+        .await?;
+    if !row.try_get::<bool, _>("found")? {
+        return Ok(None);
+    }
+    Ok(Some(ContentVersion {
+        last_modified: row
+            .try_get::<Option<DateTime<Utc>>, _>("last_modified")?
+            .unwrap_or(DateTime::<Utc>::UNIX_EPOCH),
+        rows: row.try_get("row_count")?,
+    }))
+}
+
+/// Uppercase, trim and dedupe region codes; accepts comma-separated entries.
+pub fn normalize_regions(raw: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for code in raw.iter().flat_map(|r| r.split(',')) {
+        let code = code.trim().to_ascii_uppercase();
+        if !code.is_empty() && !out.contains(&code) {
+            out.push(code);
+        }
+    }
+    out
+}
+
+/// Region part of a locale such as `en-US` / `en_GB`; bare region codes pass through.
+fn locale_region(locale: &str) -> String {
+    locale
+        .rsplit(['-', '_'])
+        .next()
+        .unwrap_or(locale)
+        .to_ascii_uppercase()
+}
+
+fn locale_in_regions(locale: &str, regions: &[String]) -> bool {
+    let region = locale_region(locale);
+    regions.contains(&region)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    #[test]
+    fn regions_are_normalized_and_deduped() {
+        let raw = vec!["us, gb".to_string(), "US".to_string(), " ".to_string()];
+        assert_eq!(normalize_regions(&raw), vec!["US", "GB"]);
+    }
+
+    #[test]
+    fn locale_matches_region() {
+        let regions = vec!["US".to_string()];
+        assert!(locale_in_regions("en-US", &regions));
+        assert!(locale_in_regions("es_us", &regions));
+        assert!(locale_in_regions("US", &regions));
+        assert!(!locale_in_regions("en-GB", &regions));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn assembles_seeded_profile() {
+        let db = RollbackDb::connect().await;
+        let pool = &db.pool;
+
+        for ddl in [
+            "CREATE TABLE IF NOT EXISTS public.video_game_ratings_by_locale (
+               id bigserial PRIMARY KEY,
+               video_game_id bigint NOT NULL REFERENCES public.video_games(id) ON DELETE CASCADE,
+               locale text NOT NULL,
+               average_rating real NOT NULL,
+               rating_count bigint NOT NULL,
+               rating_updated_at timestamptz NOT NULL DEFAULT now(),
+               UNIQUE (video_game_id, locale))",
+            "CREATE TABLE IF NOT EXISTS public.video_game_prices (
+               id bigserial PRIMARY KEY,
+               video_game_id bigint NOT NULL,
+               amount_minor bigint NOT NULL,
+               currency text NOT NULL,
+               country_code text,
+               retailer text,
+               tax_inclusive boolean NOT NULL DEFAULT true,
+               recorded_at timestamptz NOT NULL,
+               created_at timestamptz,
+               updated_at timestamptz)",
+        ] {
+            sqlx::query(ddl).execute(pool).await.unwrap();
+        }
+
+        let product_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.products (slug, name) VALUES ('profile-test', 'Profile Test')
+             RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let title_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.video_game_titles (product_id, title) VALUES ($1, 'Profile Test')
+             RETURNING id",
+        )
+        .bind(product_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let platform_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.platforms (code, name) VALUES ('profile-test', 'Profile Test')
+             ON CONFLICT (name) DO UPDATE SET code = EXCLUDED.code RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let vg_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.video_games (title_id, platform_id, synopsis, genres)
+             VALUES ($1, $2, 'A test game.', ARRAY['Action','RPG']) RETURNING id",
+        )
+        .bind(title_id)
+        .bind(platform_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        let item_id: i64 = sqlx::query_scalar(
+            "WITH p AS (
+               INSERT INTO public.providers (slug, name) VALUES ('profile-test', 'Profile Test')
+               ON CONFLICT (slug) DO UPDATE SET name = EXCLUDED.name RETURNING id
+             )
+             INSERT INTO public.provider_items (provider_id, external_id)
+             SELECT id, 'profile-test' FROM p RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO public.provider_media_links
+               (provider_item_id, url, video_game_id, media_type, kind)
+             VALUES ($2, 'https://img.example/cover.jpg', $1, 'cover', 'image'),
+                    ($2, 'https://img.example/trailer.mp4', $1, NULL, 'video')",
+        )
+        .bind(vg_id)
+        .bind(item_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO public.video_game_ratings_by_locale
+               (video_game_id, locale, average_rating, rating_count)
+             VALUES ($1, 'en-US', 4.5, 100), ($1, 'en-GB', 4.0, 20)",
+        )
+        .bind(vg_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO public.video_game_prices
+               (video_game_id, amount_minor, currency, country_code, retailer, recorded_at)
+             VALUES ($1, 6999, 'USD', 'US', 'playstation_store', now() - interval '1 day'),
+                    ($1, 4999, 'USD', 'US', 'playstation_store', now()),
+                    ($1, 3999, 'GBP', 'GB', 'playstation_store', now())",
+        )
+        .bind(vg_id)
+        .execute(pool)
+        .await
+        .unwrap();
+
+        let profile = game_profile(&db, vg_id, &["us".to_string()])
+            .await
+            .unwrap()
+            .expect("profile");
+        assert_eq!(profile.title.as_deref(), Some("Profile Test"));
+        assert_eq!(profile.synopsis.as_deref(), Some("A test game."));
+        assert_eq!(profile.genres, vec!["Action", "RPG"]);
+        assert_eq!(profile.media.len(), 2);
+        assert_eq!(profile.media[0].media_type.as_deref(), Some("cover"));
+        // Without a media_type the enum `kind` is reported instead.
+        assert_eq!(profile.media[1].media_type.as_deref(), Some("video"));
+        assert_eq!(profile.ratings.len(), 1);
+        assert_eq!(profile.ratings[0].locale, "en-US");
+        assert_eq!(profile.prices.len(), 1);
+        assert_eq!(profile.prices[0].amount_minor, 4999);
+
+        let all = game_profile(&db, vg_id, &[]).await.unwrap().unwrap();
+        assert_eq!(all.ratings.len(), 2);
+        assert_eq!(all.prices.len(), 2);
+
+        assert!(game_profile(&db, i64::MAX, &[]).await.unwrap().is_none());
+
+        db.rollback().await;
+    }
+
+    #[actix_web::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn profile_etag_revalidates_until_rows_change() {
+        use actix_web::{http::header, http::StatusCode, test, web, App};
+
+        let db = RollbackDb::connect().await;
+        let pool = db.pool.clone();
+
+        let product_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.products (slug, name) VALUES ('etag-test', 'ETag Test')
+             RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let title_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.video_game_titles (product_id, title) VALUES ($1, 'ETag Test')
+             RETURNING id",
+        )
+        .bind(product_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let vg_id: i64 = sqlx::query_scalar(
+            // Stamped in the past: the writes below share the transaction's now().
+            "INSERT INTO public.video_games (title_id, platform_id, updated_at)
+             SELECT $1, id, now() - interval '1 minute' FROM public.platforms ORDER BY id LIMIT 1
+             RETURNING id",
+        )
+        .bind(title_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new((*db).clone()))
+                .configure(crate::api::routes::configure_routes),
+        )
+        .await;
+        let uri = format!("/api/v1/games/{vg_id}");
+
+        let first = test::call_service(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers().get(header::ETAG).unwrap().clone();
+        assert!(first.headers().contains_key(header::LAST_MODIFIED));
+
+        let revalidate = || {
+            test::TestRequest::get()
+                .uri(&uri)
+                .insert_header((header::IF_NONE_MATCH, etag.clone()))
+                .to_request()
+        };
+        let second = test::call_service(&app, revalidate()).await;
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+
+        // Writers stamp video_games.updated_at, so a synopsis merge alone invalidates the tag.
+        crate::database_ops::ingest_providers::update_video_game_synopsis_prefer_longer(
+            &db,
+            vg_id,
+            "A longer synopsis from another provider.",
+        )
+        .await
+        .unwrap();
+        let third = test::call_service(&app, revalidate()).await;
+        assert_eq!(third.status(), StatusCode::OK);
+        assert_ne!(third.headers().get(header::ETAG).unwrap(), &etag);
+
+        db.rollback().await;
+    }
+}
@@ -0,0 +1,108 @@
+// On-demand provider runs (POST /api/providers/{name}/run)
+//
+// Signals the provider's wake channel in the scheduler's ProviderWakes; the loop runs as
+// soon as its current run (if any) finishes, and several requests during one run are
+// coalesced into a single extra run.
+
+use actix_web::{web, HttpResponse};
+
+use crate::database_ops::provider_loop::ProviderWakes;
+
+/// POST /api/providers/{name}/run
+pub async fn run_provider(
+    name: web::Path<String>,
+    wakes: web::Data<ProviderWakes>,
+) -> HttpResponse {
+    let name = name.into_inner();
+    if wakes.wake(&name) {
+        HttpResponse::Accepted().json(serde_json::json!({ "ok": true, "provider": name }))
+    } else {
+        HttpResponse::NotFound().json(serde_json::json!({
+            "ok": false,
+            "error": format!("unknown provider '{name}'"),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database_ops::db::Db;
+    use crate::database_ops::provider_loop::{
+        run_provider_loop, IngestProvider, ProviderMetricsRegistry, ProviderTickSummary,
+    };
+    use actix_web::http::StatusCode;
+    use actix_web::{test, App};
+    use std::time::Duration;
+
+    struct Idle;
+
+    #[async_trait::async_trait]
+    impl IngestProvider for Idle {
+        fn name(&self) -> &str {
+            "igdb_wake_test"
+        }
+        async fn tick(&self, _db: &Db) -> anyhow::Result<ProviderTickSummary> {
+            Ok(ProviderTickSummary { items: 1 })
+        }
+        fn interval(&self) -> Duration {
+            Duration::from_secs(3600)
+        }
+    }
+
+    async fn runs_reach(registry: &ProviderMetricsRegistry, runs: u64) {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while registry.get("igdb_wake_test").map_or(0, |m| m.runs) < runs {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("provider run count did not increase");
+    }
+
+    #[actix_web::test]
+    async fn run_request_wakes_the_provider_loop() {
+        let registry = ProviderMetricsRegistry::default();
+        let wakes = ProviderWakes::new(&["igdb_wake_test"]);
+        let db = Db {
+            pool: sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://wake@127.0.0.1:1/none")
+                .unwrap(),
+        };
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let task = {
+            let (registry, wakes) = (registry.clone(), wakes.clone());
+            tokio::spawn(async move {
+                run_provider_loop(&Idle, &db, &registry, &wakes, shutdown_rx).await
+            })
+        };
+        runs_reach(&registry, 1).await;
+
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(wakes))
+                .route("/api/providers/{name}/run", web::post().to(run_provider)),
+        )
+        .await;
+        let req = test::TestRequest::post()
+            .uri("/api/providers/igdb_wake_test/run")
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::ACCEPTED
+        );
+        runs_reach(&registry, 2).await;
+
+        let req = test::TestRequest::post()
+            .uri("/api/providers/tgdb/run")
+            .to_request();
+        assert_eq!(
+            test::call_service(&app, req).await.status(),
+            StatusCode::NOT_FOUND
+        );
+
+        shutdown_tx.send(()).unwrap();
+        let metrics = task.await.unwrap();
+        assert_eq!(metrics.ticks, 2);
+    }
+}
@@ -0,0 +1,302 @@
+// Per-client rate limiting for mutating endpoints (enqueue/run/control)
+
+use actix_web::{
+    body::{BoxBody, EitherBody},
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    http::{header, Method},
+    Error, HttpResponse,
+};
+use futures::future::LocalBoxFuture;
+use governor::clock::{Clock, DefaultClock};
+use governor::{DefaultKeyedRateLimiter, Quota};
+use std::future::{ready, Ready};
+use std::net::{IpAddr, SocketAddr};
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Keys whose bucket has refilled are dropped once the table grows past this many.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+struct Limiter {
+    limiter: DefaultKeyedRateLimiter<Option<IpAddr>>,
+    clock: DefaultClock,
+    /// Peers whose `Forwarded`/`X-Forwarded-For` client address is believed.
+    trusted_proxies: Vec<IpAddr>,
+}
+
+impl Limiter {
+    /// Bucket key for `req`: the peer address, or the forwarded client address when the
+    /// peer is a trusted proxy (anyone else could set the header to dodge the limit).
+    fn client(&self, req: &ServiceRequest) -> Option<IpAddr> {
+        let peer = req.peer_addr().map(|a| a.ip());
+        if !peer.is_some_and(|ip| self.trusted_proxies.contains(&ip)) {
+            return peer;
+        }
+        let info = req.connection_info();
+        info.realip_remote_addr()
+            .and_then(|raw| {
+                raw.parse::<IpAddr>()
+                    .ok()
+                    .or_else(|| raw.parse::<SocketAddr>().ok().map(|a| a.ip()))
+            })
+            .or(peer)
+    }
+
+    /// Take one request from `client`'s bucket, or return how long until one is available.
+    fn check(&self, client: Option<IpAddr>) -> Result<(), Duration> {
+        if self.limiter.len() > PRUNE_THRESHOLD {
+            self.limiter.retain_recent();
+        }
+        self.limiter
+            .check_key(&client)
+            .map_err(|not_until| not_until.wait_time_from(self.clock.now()))
+    }
+}
+
+/// Per-IP rate limit applied to mutating requests (anything but GET/HEAD/OPTIONS).
+/// Clone the same value into every worker's `App` so the buckets are shared.
+#[derive(Clone, Default)]
+pub struct RateLimit {
+    limiter: Option<Arc<Limiter>>,
+}
+
+impl RateLimit {
+    /// `rps` requests/second per client with a burst of `ceil(rps)`; `rps <= 0` disables.
+    pub fn new(rps: f64) -> Self {
+        if !(rps > 0.0 && rps.is_finite()) {
+            return Self::default();
+        }
+        let burst = NonZeroU32::new(rps.ceil() as u32).unwrap_or(NonZeroU32::MIN);
+        let Some(quota) = Quota::with_period(Duration::from_secs_f64(1.0 / rps)) else {
+            return Self::default();
+        };
+        Self {
+            limiter: Some(Arc::new(Limiter {
+                limiter: DefaultKeyedRateLimiter::keyed(quota.allow_burst(burst)),
+                clock: DefaultClock::default(),
+                trusted_proxies: Vec::new(),
+            })),
+        }
+    }
+
+    /// Key requests from `proxies` on their forwarded client address instead of the peer.
+    pub fn with_trusted_proxies(mut self, proxies: Vec<IpAddr>) -> Self {
+        if let Some(limiter) = self.limiter.as_mut().and_then(Arc::get_mut) {
+            limiter.trusted_proxies = proxies;
+        }
+        self
+    }
+
+    /// From `HTTP_RATE_LIMIT_RPS` (unset or 0 disables limiting) and
+    /// `HTTP_RATE_LIMIT_TRUSTED_PROXIES` (comma-separated proxy IPs, default none).
+    pub fn from_env() -> Self {
+        let proxies = crate::util::env::env_opt("HTTP_RATE_LIMIT_TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .filter_map(|s| match s.parse::<IpAddr>() {
+                Ok(ip) => Some(ip),
+                Err(_) => {
+                    tracing::warn!(
+                        proxy = s,
+                        "HTTP_RATE_LIMIT_TRUSTED_PROXIES: invalid IP ignored"
+                    );
+                    None
+                }
+            })
+            .collect();
+        Self::new(crate::util::env::env_parse("HTTP_RATE_LIMIT_RPS", 0.0))
+            .with_trusted_proxies(proxies)
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            service,
+            limiter: self.limiter.clone(),
+        }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: S,
+    limiter: Option<Arc<Limiter>>,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let read_only = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+        let verdict = match &self.limiter {
+            Some(limiter) if !read_only => limiter.check(limiter.client(&req)),
+            _ => Ok(()),
+        };
+
+        match verdict {
+            Ok(()) => {
+                let fut = self.service.call(req);
+                Box::pin(async move {
+                    let res = fut.await?;
+                    Ok(res.map_into_left_body())
+                })
+            }
+            Err(wait) => {
+                let retry_after = wait.as_secs_f64().ceil().max(1.0) as u64;
+                Box::pin(async move {
+                    let response = HttpResponse::TooManyRequests()
+                        .insert_header((header::RETRY_AFTER, retry_after.to_string()))
+                        .json(serde_json::json!({
+                            "ok": false,
+                            "error": "rate limit exceeded"
+                        }))
+                        .map_into_right_body();
+                    Ok(req.into_response(response))
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{http::StatusCode, web, App};
+
+    #[test]
+    fn refill_wait_follows_rate() {
+        let limit = RateLimit::new(2.0);
+        let limiter = limit.limiter.as_ref().unwrap();
+        let client = Some(IpAddr::from([10, 0, 0, 1]));
+        assert!(limiter.check(client).is_ok());
+        assert!(limiter.check(client).is_ok());
+        let wait = limiter.check(client).unwrap_err();
+        assert!(wait <= Duration::from_millis(500), "{wait:?}");
+        assert!(wait > Duration::from_millis(400), "{wait:?}");
+        assert!(limiter.check(Some(IpAddr::from([10, 0, 0, 2]))).is_ok());
+    }
+
+    #[test]
+    fn non_positive_rate_disables() {
+        assert!(RateLimit::new(0.0).limiter.is_none());
+        assert!(RateLimit::new(-1.0).limiter.is_none());
+        assert!(RateLimit::new(f64::NAN).limiter.is_none());
+        assert!(RateLimit::new(0.5).limiter.is_some());
+    }
+
+    #[actix_web::test]
+    async fn requests_past_limit_get_429() {
+        use actix_web::test;
+
+        let app = test::init_service(
+            App::new()
+                .wrap(RateLimit::new(2.0))
+                .route(
+                    "/api/enqueue",
+                    web::post().to(|| async { HttpResponse::Ok().finish() }),
+                )
+                .route(
+                    "/api/metrics",
+                    web::get().to(|| async { HttpResponse::Ok().finish() }),
+                ),
+        )
+        .await;
+        let client: std::net::SocketAddr = "10.0.0.1:4000".parse().unwrap();
+        let other: std::net::SocketAddr = "10.0.0.2:4000".parse().unwrap();
+
+        let mut statuses = Vec::new();
+        for _ in 0..4 {
+            let req = test::TestRequest::post()
+                .uri("/api/enqueue")
+                .peer_addr(client)
+                .to_request();
+            let resp = test::call_service(&app, req).await;
+            if resp.status() == StatusCode::TOO_MANY_REQUESTS {
+                assert!(resp.headers().contains_key(header::RETRY_AFTER));
+            }
+            statuses.push(resp.status());
+        }
+        assert_eq!(
+            statuses,
+            vec![
+                StatusCode::OK,
+                StatusCode::OK,
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::TOO_MANY_REQUESTS
+            ]
+        );
+
+        // Other clients and read endpoints are unaffected.
+        let req = test::TestRequest::post()
+            .uri("/api/enqueue")
+            .peer_addr(other)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+        let req = test::TestRequest::get()
+            .uri("/api/metrics")
+            .peer_addr(client)
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn forwarded_address_is_only_trusted_from_configured_proxies() {
+        use actix_web::test;
+
+        let proxy: std::net::SocketAddr = "10.0.0.9:4000".parse().unwrap();
+        let app = test::init_service(
+            App::new()
+                .wrap(RateLimit::new(1.0).with_trusted_proxies(vec![proxy.ip()]))
+                .route(
+                    "/api/enqueue",
+                    web::post().to(|| async { HttpResponse::Ok().finish() }),
+                ),
+        )
+        .await;
+        let post = |peer: std::net::SocketAddr, forwarded_for: &str| {
+            test::TestRequest::post()
+                .uri("/api/enqueue")
+                .peer_addr(peer)
+                .insert_header(("X-Forwarded-For", forwarded_for.to_string()))
+                .to_request()
+        };
+
+        // Behind the trusted proxy, each forwarded client gets its own bucket.
+        for client in ["203.0.113.1", "203.0.113.2"] {
+            let resp = test::call_service(&app, post(proxy, client)).await;
+            assert_eq!(resp.status(), StatusCode::OK, "{client}");
+        }
+        let resp = test::call_service(&app, post(proxy, "203.0.113.1")).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Any other peer is keyed on its own address, whatever header it sends.
+        let direct: std::net::SocketAddr = "198.51.100.7:5000".parse().unwrap();
+        let resp = test::call_service(&app, post(direct, "203.0.113.3")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = test::call_service(&app, post(direct, "203.0.113.4")).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
@@ -25,6 +25,17 @@ pub fn configure_routes(cfg: &mut web::ServiceConfig) {
                     "/prices/current",
                     web::get().to(handlers::get_current_prices),
                 )
+                // Game profiles
+                .route(
+                    "/games/{video_game_id}",
+                    web::get().to(handlers::get_game_profile),
+                )
+                .route(
+                    "/games/{video_game_id}/prices/compare",
+                    web::get().to(handlers::compare_game_prices),
+                )
+                .route("/search", web::get().to(handlers::search_games))
+                .route("/coverage", web::get().to(handlers::list_coverage))
                 // Provider management
                 .route("/providers", web::get().to(handlers::list_providers))
                 .route(
@@ -0,0 +1,689 @@
+// Title search with trigram ranking
+//
+// Matches the query against video_game_titles.normalized_title (same normalization as the
+// normalize_game_title() SQL function) using pg_trgm similarity/word_similarity, grouped per
+// title so each game appears once with all of its platforms and its lowest current price.
+//
+// Results are keyset-paginated: every sort is reduced to a single float8 `sort_key`
+// (ascending) with video_game_id as tie-breaker, and the cursor carries the last
+// (sort_key, video_game_id) pair, so rows inserted between page fetches never shift or
+// duplicate entries on later pages. The cursor also pins the price snapshot (`as_of`,
+// taken on the first page): prices recorded later are ignored for the rest of the walk, so
+// a price sort stays stable while ingestion writes new prices. Prices backfilled with an
+// earlier recorded_at mid-walk can still move a game.
+//
+// The "best" price is the cheapest once every listing is read with its writer's scale and
+// converted to the comparison currency, as in price_compare; listings in a currency with no
+// known exchange rate only win when nothing else is convertible. Exchange rates are read
+// per page, so an FX sync mid-walk can still reorder a price sort.
+
+use crate::api::models::{GameSearchResult, ProfilePrice, SearchPage};
+use crate::api::price_compare::{
+    exchange_rates, writer_minor_unit, CENT_SCALED_RETAILERS, DEFAULT_COMPARE_CURRENCY,
+};
+use crate::database_ops::db::Db;
+use crate::database_ops::schema_caps::SchemaCaps;
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+pub const DEFAULT_SEARCH_LIMIT: i64 = 20;
+pub const MAX_SEARCH_LIMIT: i64 = 100;
+
+/// Result ordering for search/list APIs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SearchSort {
+    /// Best trigram score first.
+    #[default]
+    Relevance,
+    /// Newest release first; undated games last.
+    ReleaseDate,
+    /// Cheapest current price (in the comparison currency) first; unpriced games last.
+    Price,
+}
+
+impl SearchSort {
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "relevance" => Ok(Self::Relevance),
+            "release_date" => Ok(Self::ReleaseDate),
+            "price" => Ok(Self::Price),
+            other => Err(anyhow!(
+                "unknown sort '{other}' (expected relevance, release_date or price)"
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Relevance => "relevance",
+            Self::ReleaseDate => "release_date",
+            Self::Price => "price",
+        }
+    }
+
+    /// SQL expression over the grouped row `g` / best price `bp` yielding an ascending key.
+    fn key_sql(self, with_prices: bool) -> &'static str {
+        match self {
+            Self::Relevance => "-g.score::float8",
+            Self::ReleaseDate => {
+                "COALESCE(-(g.release_date - DATE '1970-01-01')::float8, 'Infinity'::float8)"
+            }
+            Self::Price if with_prices => "COALESCE(bp.compare_amount, 'Infinity'::float8)",
+            Self::Price => "'Infinity'::float8",
+        }
+    }
+}
+
+/// Position after the last row of a page, plus the price snapshot the walk started from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchCursor {
+    pub sort_key: f64,
+    pub video_game_id: i64,
+    pub as_of: DateTime<Utc>,
+}
+
+impl SearchCursor {
+    /// Opaque, URL-safe token; bound to the sort it was issued for.
+    pub fn encode(&self, sort: SearchSort) -> String {
+        URL_SAFE_NO_PAD.encode(format!(
+            "{}|{}|{}|{}",
+            sort.as_str(),
+            self.sort_key,
+            self.video_game_id,
+            self.as_of.timestamp_micros()
+        ))
+    }
+
+    pub fn decode(token: &str, sort: SearchSort) -> Result<Self> {
+        let raw = URL_SAFE_NO_PAD
+            .decode(token.trim())
+            .map_err(|_| anyhow!("malformed cursor"))?;
+        let raw = String::from_utf8(raw).map_err(|_| anyhow!("malformed cursor"))?;
+        let mut parts = raw.split('|');
+        let (Some(cursor_sort), Some(key), Some(id), Some(as_of), None) = (
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+            parts.next(),
+        ) else {
+            return Err(anyhow!("malformed cursor"));
+        };
+        if cursor_sort != sort.as_str() {
+            return Err(anyhow!(
+                "cursor was issued for sort '{cursor_sort}', not '{}'",
+                sort.as_str()
+            ));
+        }
+        Ok(Self {
+            sort_key: key.parse().map_err(|_| anyhow!("malformed cursor"))?,
+            video_game_id: id.parse().map_err(|_| anyhow!("malformed cursor"))?,
+            as_of: as_of
+                .parse()
+                .ok()
+                .and_then(DateTime::from_timestamp_micros)
+                .ok_or_else(|| anyhow!("malformed cursor"))?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchParams {
+    pub query: String,
+    pub limit: Option<i64>,
+    pub sort: SearchSort,
+    pub cursor: Option<SearchCursor>,
+}
+
+/// Search games by title. Returns one page of results in `params.sort` order with the total
+/// match count and a cursor for the next page (absent on the last page). An empty query
+/// yields an empty page.
+pub async fn search_games(db: &Db, params: &SearchParams) -> Result<SearchPage> {
+    let normalized = normalize_query(&params.query);
+    if normalized.is_empty() {
+        return Ok(SearchPage {
+            results: Vec::new(),
+            total: 0,
+            next_cursor: None,
+        });
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+    let with_prices = SchemaCaps::global()
+        .table_visible(db, "public.video_game_prices")
+        .await?;
+
+    let fx = if with_prices {
+        PriceFx::load(db, DEFAULT_COMPARE_CURRENCY).await?
+    } else {
+        PriceFx::default()
+    };
+
+    let rows = sqlx::query(&build_search_sql(with_prices, params.sort))
+        .persistent(false)
+        .bind(&normalized)
+        .bind(limit + 1)
+        .bind(params.cursor.map(|c| c.sort_key))
+        .bind(params.cursor.map(|c| c.video_game_id))
+        .bind(params.cursor.map(|c| c.as_of))
+        .bind(&fx.codes)
+        .bind(&fx.rates)
+        .bind(&fx.minor_units)
+        .bind(CENT_SCALED_RETAILERS)
+        .fetch_all(&db.pool)
+        .await?;
+
+    let total = match rows.first() {
+        Some(r) => r.try_get::<i64, _>("total")?,
+        None => 0,
+    };
+    let has_more = rows.len() as i64 > limit;
+    let mut results = Vec::with_capacity(rows.len());
+    let mut last: Option<SearchCursor> = None;
+    for r in rows.into_iter().take(limit as usize) {
+        let best_price = match r.try_get::<Option<i64>, _>("price_amount_minor")? {
+            Some(amount_minor) => Some(ProfilePrice {
+                retailer: r.try_get("price_retailer")?,
+                country_code: r.try_get("price_country_code")?,
+                currency: r.try_get("price_currency")?,
+                amount_minor,
+                recorded_at: r.try_get("price_recorded_at")?,
+            }),
+            None => None,
+        };
+        let result = GameSearchResult {
+            video_game_id: r.try_get("video_game_id")?,
+            title_id: r.try_get("title_id")?,
+            display_title: r.try_get("display_title")?,
+            platforms: r.try_get("platforms")?,
+            release_date: r.try_get("release_date")?,
+            score: r.try_get("score")?,
+            best_price,
+        };
+        last = Some(SearchCursor {
+            sort_key: r.try_get("sort_key")?,
+            video_game_id: result.video_game_id,
+            as_of: r.try_get("as_of")?,
+        });
+        results.push(result);
+    }
+
+    Ok(SearchPage {
+        results,
+        total,
+        next_cursor: if has_more {
+            last.map(|c| c.encode(params.sort))
+        } else {
+            None
+        },
+    })
+}
+
+/// What the SQL needs to put a listing's `amount_minor` into the comparison currency:
+/// per currency code, the rate per major unit and the minor unit its writers use (the
+/// cent-scaled retailers are special-cased in SQL, as in `writer_minor_unit`).
+#[derive(Debug, Default)]
+struct PriceFx {
+    codes: Vec<String>,
+    rates: Vec<f64>,
+    minor_units: Vec<i16>,
+}
+
+impl PriceFx {
+    /// Rates for every currency in `currencies` (or, on schemas without it, every currency
+    /// a price was recorded in) that can be converted to `currency`.
+    async fn load(db: &Db, currency: &str) -> Result<Self> {
+        // to_jsonb keeps this valid on legacy currencies tables without minor_unit.
+        let sql = if SchemaCaps::global()
+            .table_visible(db, "public.currencies")
+            .await?
+        {
+            "SELECT DISTINCT ON (upper(c.code)) upper(c.code) AS code,
+                    (to_jsonb(c)->>'minor_unit')::smallint AS minor_unit
+             FROM public.currencies c
+             ORDER BY upper(c.code)"
+        } else {
+            "SELECT DISTINCT upper(p.currency) AS code, NULL::smallint AS minor_unit
+             FROM public.video_game_prices p"
+        };
+        let known: Vec<(String, Option<i16>)> = sqlx::query_as(sql)
+            .persistent(false)
+            .fetch_all(&db.pool)
+            .await?;
+        let rates =
+            exchange_rates(db, known.iter().map(|(code, _)| code.as_str()), currency).await?;
+
+        let mut fx = Self::default();
+        for (code, stored) in known {
+            if let Some(rate) = rates.get(&code) {
+                fx.minor_units.push(writer_minor_unit(None, stored, &code));
+                fx.rates.push(*rate);
+                fx.codes.push(code);
+            }
+        }
+        Ok(fx)
+    }
+}
+
+/// Mirror of the `normalize_game_title()` SQL function: lowercase, non-alphanumerics
+/// collapsed to single spaces, trimmed.
+pub fn normalize_query(raw: &str) -> String {
+    raw.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `$1` is the normalized query, `$2` the page size (+1 to detect a next page), `$3`/`$4`
+/// the cursor's sort key and video_game_id, `$5` its price snapshot (all NULL for the first
+/// page), `$6`..`$8` the [`PriceFx`] columns and `$9` the cent-scaled retailers. The price
+/// lateral is only joined when the video_game_prices table exists.
+fn build_search_sql(with_prices: bool, sort: SearchSort) -> String {
+    let (price_cols, price_join) = if with_prices {
+        (
+            "bp.retailer AS price_retailer, bp.country_code AS price_country_code,
+                 bp.currency AS price_currency, bp.amount_minor AS price_amount_minor,
+                 bp.recorded_at AS price_recorded_at",
+            "LEFT JOIN LATERAL (
+               SELECT latest.*,
+                      latest.amount_minor::float8
+                        / 10 ^ CASE WHEN lower(btrim(latest.retailer)) = ANY($9::text[])
+                                    THEN 2 ELSE fx.minor_unit END
+                        * fx.rate AS compare_amount
+               FROM (
+                 SELECT DISTINCT ON (p.retailer, p.country_code, p.currency)
+                        p.retailer, upper(p.country_code) AS country_code, p.currency,
+                        p.amount_minor, p.recorded_at
+                 FROM public.video_game_prices p
+                 WHERE p.video_game_id = ANY(g.video_game_ids)
+                   AND p.recorded_at <= (SELECT as_of FROM snapshot)
+                 ORDER BY p.retailer, p.country_code, p.currency, p.recorded_at DESC
+               ) latest
+               LEFT JOIN unnest($6::text[], $7::float8[], $8::int2[]) AS fx(code, rate, minor_unit)
+                 ON fx.code = upper(latest.currency)
+               ORDER BY compare_amount ASC NULLS LAST, latest.amount_minor ASC
+               LIMIT 1
+             ) bp ON true",
+        )
+    } else {
+        (
+            "NULL::text AS price_retailer, NULL::text AS price_country_code,
+                 NULL::text AS price_currency, NULL::bigint AS price_amount_minor,
+                 NULL::timestamptz AS price_recorded_at",
+            "",
+        )
+    };
+    let sort_key = sort.key_sql(with_prices);
+    format!(
+        r#"
+        WITH snapshot AS (
+          SELECT COALESCE($5::timestamptz, now()) AS as_of
+        ),
+        matches AS (
+          SELECT vgt.id AS title_id, vgt.title,
+                 GREATEST(
+                   similarity(vgt.normalized_title, $1),
+                   word_similarity($1, vgt.normalized_title)
+                 )::real AS score
+          FROM public.video_game_titles vgt
+          WHERE vgt.normalized_title % $1
+             OR $1 <% vgt.normalized_title
+        ),
+        g AS (
+          SELECT m.title_id, m.score,
+                 min(vg.id) AS video_game_id,
+                 array_agg(vg.id ORDER BY vg.id) AS video_game_ids,
+                 COALESCE(min(vg.display_title), m.title) AS display_title,
+                 max(vg.release_date) AS release_date,
+                 COALESCE(
+                   array_agg(DISTINCT pl.name) FILTER (WHERE pl.name IS NOT NULL),
+                   ARRAY[]::text[]
+                 ) AS platforms
+          FROM matches m
+          JOIN public.video_games vg ON vg.title_id = m.title_id
+          LEFT JOIN public.platforms pl ON pl.id = vg.platform_id
+          GROUP BY m.title_id, m.title, m.score
+        ),
+        ranked AS (
+          SELECT g.video_game_id, g.title_id, g.display_title, g.platforms,
+                 g.release_date, g.score,
+                 {price_cols},
+                 {sort_key} AS sort_key
+          FROM g
+          {price_join}
+        )
+        SELECT ranked.*, (SELECT count(*) FROM ranked)::bigint AS total,
+               (SELECT as_of FROM snapshot) AS as_of
+        FROM ranked
+        WHERE $3::float8 IS NULL
+           OR (ranked.sort_key, ranked.video_game_id) > ($3::float8, $4::bigint)
+        ORDER BY ranked.sort_key, ranked.video_game_id
+        LIMIT $2
+        "#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+    use sqlx::PgPool;
+
+    #[test]
+    fn query_normalization_matches_sql_function() {
+        assert_eq!(
+            normalize_query("  The Legend of Zelda: BOTW! "),
+            "the legend of zelda botw"
+        );
+        assert_eq!(normalize_query("NBA 2K26"), "nba 2k26");
+        assert_eq!(normalize_query("---"), "");
+    }
+
+    #[test]
+    fn price_join_only_when_table_present() {
+        let with = build_search_sql(true, SearchSort::Price);
+        assert!(with.contains("video_game_prices"));
+        assert!(with.contains("COALESCE(bp.compare_amount"));
+        assert!(with.contains("ORDER BY compare_amount ASC NULLS LAST"));
+        let without = build_search_sql(false, SearchSort::Price);
+        assert!(!without.contains("video_game_prices"));
+        assert!(without.contains("NULL::bigint AS price_amount_minor"));
+    }
+
+    #[test]
+    fn sort_parsing() {
+        assert_eq!(SearchSort::parse("").unwrap(), SearchSort::Relevance);
+        assert_eq!(
+            SearchSort::parse("Release_Date").unwrap(),
+            SearchSort::ReleaseDate
+        );
+        assert_eq!(SearchSort::parse("price").unwrap(), SearchSort::Price);
+        assert!(SearchSort::parse("popularity").is_err());
+    }
+
+    #[test]
+    fn cursor_round_trips_and_is_bound_to_sort() {
+        let as_of = DateTime::from_timestamp_micros(1_760_000_000_123_456).unwrap();
+        let cursor = SearchCursor {
+            sort_key: -0.8125,
+            video_game_id: 42,
+            as_of,
+        };
+        let token = cursor.encode(SearchSort::Relevance);
+        assert_eq!(
+            SearchCursor::decode(&token, SearchSort::Relevance).unwrap(),
+            cursor
+        );
+        assert!(SearchCursor::decode(&token, SearchSort::Price).is_err());
+        assert!(SearchCursor::decode("not-a-cursor", SearchSort::Relevance).is_err());
+
+        let unpriced = SearchCursor {
+            sort_key: f64::INFINITY,
+            video_game_id: 7,
+            as_of,
+        };
+        let token = unpriced.encode(SearchSort::Price);
+        assert_eq!(
+            SearchCursor::decode(&token, SearchSort::Price).unwrap(),
+            unpriced
+        );
+    }
+
+    /// Seed one product/title/video_game per title; returns (product_id, title_id, vg_id).
+    async fn seed_titles(
+        pool: &PgPool,
+        slug_prefix: &str,
+        titles: &[&str],
+    ) -> Vec<(i64, i64, i64)> {
+        let platform_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.platforms (code, name) VALUES ('search-test', 'Search Test')
+             ON CONFLICT (name) DO UPDATE SET code = EXCLUDED.code RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        let mut seeded = Vec::new();
+        for (i, title) in titles.iter().enumerate() {
+            let product_id: i64 = sqlx::query_scalar(
+                "INSERT INTO public.products (slug, name) VALUES ($1, $2) RETURNING id",
+            )
+            .bind(format!("{slug_prefix}-{i}"))
+            .bind(title)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            let title_id: i64 = sqlx::query_scalar(
+                "INSERT INTO public.video_game_titles (product_id, title, normalized_title)
+                 VALUES ($1, $2, $3) RETURNING id",
+            )
+            .bind(product_id)
+            .bind(title)
+            .bind(normalize_query(title))
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            let vg_id: i64 = sqlx::query_scalar(
+                "INSERT INTO public.video_games (title_id, platform_id, release_date)
+                 VALUES ($1, $2, DATE '2020-01-01' + $3::int) RETURNING id",
+            )
+            .bind(title_id)
+            .bind(platform_id)
+            .bind(i as i32)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            seeded.push((product_id, title_id, vg_id));
+        }
+        seeded
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn fuzzy_query_ranks_expected_title_first() {
+        let db = RollbackDb::connect().await;
+        let seeded = seed_titles(
+            &db.pool,
+            "search-test",
+            &[
+                "Zzqx Starfall Odyssey",
+                "Zzqx Starfall Odyssey II",
+                "Zzqx Harbor Racing",
+            ],
+        )
+        .await;
+
+        let search = |q: &str| SearchParams {
+            query: q.to_string(),
+            limit: Some(10),
+            ..Default::default()
+        };
+        let page = search_games(&db, &search("zzqx starfal odysey"))
+            .await
+            .unwrap();
+        assert_eq!(page.results[0].title_id, seeded[0].1);
+        assert_eq!(page.results[0].platforms, vec!["Search Test"]);
+
+        let page = search_games(&db, &search("harbor racing")).await.unwrap();
+        assert_eq!(page.results[0].title_id, seeded[2].1);
+
+        db.rollback().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn keyset_pages_have_no_duplicates_or_gaps() {
+        let db = RollbackDb::connect().await;
+        let titles: Vec<String> = (0..7).map(|i| format!("Zzqp Paging Quest {i}")).collect();
+        let title_refs: Vec<&str> = titles.iter().map(String::as_str).collect();
+        let mut seeded = seed_titles(&db.pool, "search-page-test", &title_refs).await;
+        let expected: std::collections::BTreeSet<i64> = seeded.iter().map(|s| s.2).collect();
+
+        for sort in [
+            SearchSort::Relevance,
+            SearchSort::ReleaseDate,
+            SearchSort::Price,
+        ] {
+            let mut params = SearchParams {
+                query: "zzqp paging quest".to_string(),
+                limit: Some(3),
+                sort,
+                cursor: None,
+            };
+            let mut seen: Vec<i64> = Vec::new();
+            let mut pages = 0;
+            loop {
+                let page = search_games(&db, &params).await.unwrap();
+                pages += 1;
+                if pages == 1 {
+                    assert_eq!(page.total as usize, seeded.len());
+                    // A concurrent insert between page fetches must not shift later pages.
+                    let extra = seed_titles(
+                        &db.pool,
+                        &format!("search-page-extra-{}", sort.as_str()),
+                        &["Zzqp Paging Quest Extra"],
+                    )
+                    .await;
+                    seeded.extend(extra);
+                }
+                seen.extend(page.results.iter().map(|r| r.video_game_id));
+                match page.next_cursor {
+                    Some(token) => {
+                        params.cursor = Some(SearchCursor::decode(&token, sort).unwrap())
+                    }
+                    None => break,
+                }
+            }
+            let unique: std::collections::BTreeSet<i64> = seen.iter().copied().collect();
+            assert_eq!(unique.len(), seen.len(), "duplicate rows for {sort:?}");
+            assert!(expected.is_subset(&unique), "gap in pages for {sort:?}");
+        }
+
+        db.rollback().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn best_price_compares_across_currencies() {
+        let db = RollbackDb::connect().await;
+        let titles: Vec<String> = (0..3).map(|i| format!("Zzqm Currency Quest {i}")).collect();
+        let title_refs: Vec<&str> = titles.iter().map(String::as_str).collect();
+        let seeded = seed_titles(&db.pool, "search-currency-test", &title_refs).await;
+        let vg_ids: Vec<i64> = seeded.iter().map(|s| s.2).collect();
+
+        for (code, minor_unit) in [("USD", 2i16), ("JPY", 0), ("KWD", 3)] {
+            sqlx::query(
+                "INSERT INTO public.currencies (code, name, minor_unit) VALUES ($1, $1, $2)
+                 ON CONFLICT (code) DO NOTHING",
+            )
+            .bind(code)
+            .bind(minor_unit)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        }
+        for (base, rate) in [("JPY", 0.0067), ("KWD", 3.25)] {
+            sqlx::query(
+                "INSERT INTO public.exchange_rates
+                     (base_currency, quote_currency, rate, provider, fetched_at)
+                 VALUES ($1, 'USD', $2, 'search-test', now())",
+            )
+            .bind(base)
+            .bind(rate)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        }
+        // (video_game, retailer, currency, amount_minor): $59.99; ¥7,000 (~$46.90) beside
+        // $65.00; 18.000 KWD (~$58.50).
+        for (vg_id, retailer, currency, amount_minor) in [
+            (vg_ids[0], "steam", "USD", 5_999),
+            (vg_ids[1], "csv_import", "JPY", 7_000),
+            (vg_ids[1], "steam", "USD", 6_500),
+            (vg_ids[2], "csv_import", "KWD", 18_000),
+        ] {
+            sqlx::query(
+                "INSERT INTO public.video_game_prices
+                     (video_game_id, amount_minor, currency, country_code, retailer, recorded_at)
+                 VALUES ($1, $2, $3, 'US', $4, now() - interval '1 hour')",
+            )
+            .bind(vg_id)
+            .bind(amount_minor)
+            .bind(currency)
+            .bind(retailer)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        }
+
+        let page = search_games(
+            &db,
+            &SearchParams {
+                query: "zzqm currency quest".to_string(),
+                limit: Some(10),
+                sort: SearchSort::Price,
+                cursor: None,
+            },
+        )
+        .await
+        .unwrap();
+        let ids: Vec<i64> = page.results.iter().map(|r| r.video_game_id).collect();
+        assert_eq!(ids, vec![vg_ids[1], vg_ids[2], vg_ids[0]]);
+        let best = page.results[0].best_price.as_ref().unwrap();
+        assert_eq!((best.currency.as_str(), best.amount_minor), ("JPY", 7_000));
+
+        db.rollback().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn price_sort_ignores_prices_recorded_mid_walk() {
+        let db = RollbackDb::connect().await;
+        let titles: Vec<String> = (0..4).map(|i| format!("Zzqr Snapshot Quest {i}")).collect();
+        let title_refs: Vec<&str> = titles.iter().map(String::as_str).collect();
+        let seeded = seed_titles(&db.pool, "search-snapshot-test", &title_refs).await;
+        let vg_ids: Vec<i64> = seeded.iter().map(|s| s.2).collect();
+        let add_price = |vg_id: i64, amount_minor: i64, age_secs: i32| {
+            sqlx::query(
+                "INSERT INTO public.video_game_prices
+                     (video_game_id, amount_minor, currency, country_code, retailer, recorded_at)
+                 VALUES ($1, $2, 'USD', 'US', 'steam',
+                         clock_timestamp() - make_interval(secs => $3))",
+            )
+            .bind(vg_id)
+            .bind(amount_minor)
+            .bind(age_secs)
+            .execute(&db.pool)
+        };
+        for (i, vg_id) in vg_ids.iter().enumerate() {
+            add_price(*vg_id, 100 * (i as i64 + 1), 3600).await.unwrap();
+        }
+
+        let mut params = SearchParams {
+            query: "zzqr snapshot quest".to_string(),
+            limit: Some(2),
+            sort: SearchSort::Price,
+            cursor: None,
+        };
+        let first = search_games(&db, &params).await.unwrap();
+        let first_ids: Vec<i64> = first.results.iter().map(|r| r.video_game_id).collect();
+        assert_eq!(first_ids, vg_ids[..2]);
+        // Repriced after the walk started: the first game would reappear and the last one
+        // would jump before the cursor if the new prices were read.
+        add_price(vg_ids[0], 1_000, 0).await.unwrap();
+        add_price(vg_ids[3], 50, 0).await.unwrap();
+        params.cursor =
+            Some(SearchCursor::decode(&first.next_cursor.unwrap(), SearchSort::Price).unwrap());
+        let second = search_games(&db, &params).await.unwrap();
+        let second_ids: Vec<i64> = second.results.iter().map(|r| r.video_game_id).collect();
+        assert_eq!(second_ids, vg_ids[2..]);
+        assert_eq!(second.next_cursor, None);
+
+        db.rollback().await;
+    }
+}
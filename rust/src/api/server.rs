@@ -3,8 +3,10 @@
 use crate::api::{auth, middleware, routes};
 use crate::database_ops::db::Db;
 use actix_web::{web, App, HttpServer};
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use std::env;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use tokio::sync::broadcast;
 
 pub struct ApiServer {
     pub host: String,
@@ -117,3 +119,81 @@ impl ApiServer {
         Ok(())
     }
 }
+
+/// Resolve and bind `addr` up front, so a malformed or occupied address fails startup with
+/// a clear error instead of panicking inside a spawned server task. Hand the listener to
+/// `HttpServer::listen`.
+pub fn bind_listener(addr: &str) -> Result<TcpListener> {
+    let addrs: Vec<SocketAddr> = addr
+        .to_socket_addrs()
+        .with_context(|| format!("invalid HTTP bind address '{addr}' (expected host:port)"))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(anyhow!(
+            "HTTP bind address '{addr}' resolved to no addresses"
+        ));
+    }
+    TcpListener::bind(&addrs[..])
+        .with_context(|| format!("failed to bind HTTP server to {addr} (port already in use?)"))
+}
+
+/// Run an actix `Server` until `shutdown` fires (or its sender is dropped), then stop it
+/// gracefully: in-flight requests finish and the listening socket is released before this
+/// returns.
+pub async fn serve_until_shutdown(
+    server: actix_web::dev::Server,
+    mut shutdown: broadcast::Receiver<()>,
+) -> std::io::Result<()> {
+    let handle = server.handle();
+    let mut server = std::pin::pin!(server);
+    tokio::select! {
+        res = &mut server => res,
+        _ = shutdown.recv() => {
+            // The server future itself carries out the stop, so keep polling it; awaiting
+            // the stop first would never complete.
+            let stopped = handle.stop(true);
+            let (res, ()) = tokio::join!(server, stopped);
+            res
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::HttpResponse;
+
+    #[test]
+    fn bind_listener_reports_bad_and_used_addresses() {
+        let err = bind_listener("not-an-address").unwrap_err();
+        assert!(err.to_string().contains("invalid HTTP bind address"));
+
+        let taken = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = taken.local_addr().unwrap().to_string();
+        let err = bind_listener(&addr).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&format!("failed to bind HTTP server to {addr}")));
+
+        drop(taken);
+        assert!(bind_listener(&addr).is_ok());
+    }
+
+    #[actix_web::test]
+    async fn port_is_released_after_shutdown() {
+        let http = HttpServer::new(|| {
+            App::new().route("/", web::get().to(|| async { HttpResponse::Ok().finish() }))
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let addr = http.addrs()[0];
+        let (tx, rx) = broadcast::channel::<()>(1);
+        let task = tokio::spawn(serve_until_shutdown(http.run(), rx));
+
+        assert!(TcpListener::bind(addr).is_err());
+        tx.send(()).unwrap();
+        task.await.unwrap().unwrap();
+        assert!(TcpListener::bind(addr).is_ok());
+    }
+}
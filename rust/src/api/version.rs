@@ -0,0 +1,68 @@
+// Build metadata endpoint (/api/version)
+//
+// GIT_SHA and BUILD_TIMESTAMP are injected at compile time by build.rs.
+
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Providers a binary schedules, registered as app data for the version endpoint.
+#[derive(Debug, Clone, Default)]
+pub struct EnabledProviders(pub Vec<String>);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+    /// RFC 3339 build time, `None` if build.rs could not determine it.
+    pub built_at: Option<String>,
+}
+
+pub fn build_info() -> BuildInfo {
+    let built_at = env!("BUILD_TIMESTAMP")
+        .parse::<i64>()
+        .ok()
+        .filter(|ts| *ts > 0)
+        .and_then(|ts| DateTime::<Utc>::from_timestamp(ts, 0))
+        .map(|dt| dt.to_rfc3339());
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: env!("GIT_SHA"),
+        built_at,
+    }
+}
+
+/// GET /api/version
+pub async fn get_version(providers: Option<web::Data<EnabledProviders>>) -> HttpResponse {
+    let providers = providers.map(|p| p.0.clone()).unwrap_or_default();
+    HttpResponse::Ok().json(serde_json::json!({
+        "ok": true,
+        "build": build_info(),
+        "providers": providers,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    #[actix_web::test]
+    async fn reports_crate_version_and_providers() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(EnabledProviders(vec![
+                    "psstore".to_string(),
+                    "steam".to_string(),
+                ])))
+                .route("/api/version", web::get().to(get_version)),
+        )
+        .await;
+        let req = test::TestRequest::get().uri("/api/version").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(body["build"]["version"], env!("CARGO_PKG_VERSION"));
+        assert!(!body["build"]["git_sha"].as_str().unwrap().is_empty());
+        assert_eq!(body["providers"], serde_json::json!(["psstore", "steam"]));
+    }
+}
@@ -4,9 +4,17 @@
 //
 // This worker claims batches of NULL/default rows across retailer_providers and updates
 // them to sane defaults in parallel, using FOR UPDATE SKIP LOCKED to avoid contention.
+//
+// Targeting:
+//   BACKFILL_COLUMNS=priority,is_enabled   only fill these NULL columns (default: all)
+//   BACKFILL_DEFAULT_<COLUMN>=...          override the default for a column, e.g.
+//                                          BACKFILL_DEFAULT_PRIORITY=50
+//   BACKFILL_DRY_RUN=1                     count candidate rows per column and exit
+//                                          without running the UPDATE
 
-use anyhow::Result;
-use sqlx::PgPool;
+use anyhow::{anyhow, Result};
+use i_miss_rust::util::env::{env_flag, env_opt};
+use sqlx::{PgPool, Row};
 use std::{sync::Arc, time::Duration};
 use tokio::{task, time};
 use tracing::{error, info};
@@ -16,6 +24,189 @@ struct Config {
     pool: PgPool,
     workers: usize,
     batch_size: i64,
+    columns: Vec<BackfillColumn>,
+}
+
+/// A retailer_providers column eligible for backfill, with the default written when NULL.
+#[derive(Clone, Debug, PartialEq)]
+struct BackfillColumn {
+    name: &'static str,
+    /// Postgres type the default literal is cast to.
+    sql_type: &'static str,
+    default: String,
+}
+
+/// Known columns and their built-in defaults (as Postgres literals).
+const RETAILER_PROVIDER_COLUMNS: &[(&str, &str, &str)] = &[
+    ("credentials", "jsonb", "{}"),
+    ("settings", "jsonb", "{}"),
+    ("metadata", "jsonb", "{}"),
+    ("jurisdiction_scope", "text[]", "{}"),
+    ("is_enabled", "boolean", "true"),
+    ("priority", "integer", "100"),
+];
+
+/// Resolve the column set from `BACKFILL_COLUMNS` (comma-separated, default all) and apply
+/// any `BACKFILL_DEFAULT_<COLUMN>` overrides.
+fn resolve_columns(
+    selection: Option<&str>,
+    default_override: impl Fn(&str) -> Option<String>,
+) -> Result<Vec<BackfillColumn>> {
+    let selected: Option<Vec<String>> = selection.map(|raw| {
+        raw.split(',')
+            .map(|s| s.trim().to_ascii_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect()
+    });
+    if let Some(sel) = &selected {
+        for name in sel {
+            if !RETAILER_PROVIDER_COLUMNS.iter().any(|(c, _, _)| c == name) {
+                return Err(anyhow!("unknown backfill column: {name}"));
+            }
+        }
+    }
+
+    let columns: Vec<BackfillColumn> = RETAILER_PROVIDER_COLUMNS
+        .iter()
+        .filter(|(name, _, _)| {
+            selected
+                .as_ref()
+                .is_none_or(|sel| sel.iter().any(|s| s == name))
+        })
+        .map(|&(name, sql_type, default)| BackfillColumn {
+            name,
+            sql_type,
+            default: default_override(&format!("BACKFILL_DEFAULT_{}", name.to_ascii_uppercase()))
+                .unwrap_or_else(|| default.to_string()),
+        })
+        .collect();
+    if columns.is_empty() {
+        return Err(anyhow!("BACKFILL_COLUMNS selected no columns"));
+    }
+    Ok(columns)
+}
+
+fn null_predicate(columns: &[BackfillColumn]) -> String {
+    columns
+        .iter()
+        .map(|c| format!("{} IS NULL", c.name))
+        .collect::<Vec<_>>()
+        .join("\n             OR ")
+}
+
+/// Build the claim+update statement for the given columns. `$1` is the batch size and
+/// `$2..` are the per-column defaults, in order.
+fn build_update_sql(columns: &[BackfillColumn]) -> String {
+    let predicate = null_predicate(columns);
+    let assignments = columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            format!(
+                "{name} = coalesce(rp.{name}, ${idx}::{ty})",
+                name = c.name,
+                idx = i + 2,
+                ty = c.sql_type
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n            ");
+    format!(
+        r#"
+        WITH cte AS (
+          SELECT id
+          FROM public.retailer_providers
+          WHERE {predicate}
+          FOR UPDATE SKIP LOCKED
+          LIMIT $1
+        )
+        UPDATE public.retailer_providers rp
+        SET {assignments}
+        FROM cte
+        WHERE rp.id = cte.id
+        RETURNING rp.id
+        "#
+    )
+}
+
+/// Build the read-only preview statement: claims every candidate row with the same
+/// `FOR UPDATE SKIP LOCKED` predicate, then reports the total and a per-column NULL count.
+fn build_preview_sql(columns: &[BackfillColumn]) -> String {
+    let predicate = null_predicate(columns);
+    let flags = columns
+        .iter()
+        .map(|c| format!("{name} IS NULL AS {name}", name = c.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let counts = columns
+        .iter()
+        .map(|c| {
+            format!(
+                "count(*) FILTER (WHERE {name})::bigint AS {name}",
+                name = c.name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",\n               ");
+    format!(
+        r#"
+        WITH cte AS (
+          SELECT {flags}
+          FROM public.retailer_providers
+          WHERE {predicate}
+          FOR UPDATE SKIP LOCKED
+        )
+        SELECT count(*)::bigint AS candidates,
+               {counts}
+        FROM cte
+        "#
+    )
+}
+
+/// What a dry run reports: candidate rows and, per selected column, how many are NULL.
+#[derive(Debug, PartialEq)]
+struct BackfillPreview {
+    candidates: i64,
+    nulls: Vec<(&'static str, i64)>,
+}
+
+async fn count_candidates(
+    conn: &mut sqlx::PgConnection,
+    columns: &[BackfillColumn],
+) -> Result<BackfillPreview> {
+    let row = sqlx::query(&build_preview_sql(columns))
+        .fetch_one(&mut *conn)
+        .await?;
+    let nulls = columns
+        .iter()
+        .map(|c| Ok((c.name, row.try_get::<i64, _>(c.name)?)))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(BackfillPreview {
+        candidates: row.try_get("candidates")?,
+        nulls,
+    })
+}
+
+/// Dry-run: count candidate rows inside a transaction that is always rolled back, so the
+/// row locks are released and nothing is modified.
+async fn preview_retailer_providers(pool: &PgPool, columns: &[BackfillColumn]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    let preview = count_candidates(&mut tx, columns).await?;
+    tx.rollback().await?;
+
+    info!(
+        candidates = preview.candidates,
+        "dry-run: retailer_providers rows that would be updated"
+    );
+    for (c, (_, nulls)) in columns.iter().zip(&preview.nulls) {
+        info!(
+            column = c.name,
+            rows = nulls,
+            default = %c.default,
+            "dry-run: would fill NULLs"
+        );
+    }
+    Ok(())
 }
 
 #[tokio::main]
@@ -37,17 +228,24 @@ async fn main() -> Result<()> {
         .ok()
         .and_then(|s| s.parse().ok())
         .unwrap_or(500);
+    let columns = resolve_columns(env_opt("BACKFILL_COLUMNS").as_deref(), env_opt)?;
+
+    if env_flag("BACKFILL_DRY_RUN", false) {
+        return preview_retailer_providers(&pool, &columns).await;
+    }
 
     let cfg = Arc::new(Config {
         pool,
         workers,
         batch_size,
+        columns,
     });
 
     let mut handles = Vec::with_capacity(cfg.workers);
     info!(
         workers = cfg.workers,
         batch_size = cfg.batch_size,
+        columns = ?cfg.columns.iter().map(|c| c.name).collect::<Vec<_>>(),
         "starting backfill workers"
     );
 
@@ -71,7 +269,7 @@ async fn main() -> Result<()> {
 async fn worker_loop(cfg: Arc<Config>, worker_id: i32) -> Result<()> {
     loop {
         // Choose which table backfill to run. Retailer providers as example.
-        let claimed = run_retailer_providers_batch(&cfg.pool, cfg.batch_size).await?;
+        let claimed = run_retailer_providers_batch(&cfg.pool, cfg.batch_size, &cfg.columns).await?;
         if claimed == 0 {
             // No rows claimed; back off briefly
             time::sleep(Duration::from_millis(500)).await;
@@ -83,39 +281,178 @@ async fn worker_loop(cfg: Arc<Config>, worker_id: i32) -> Result<()> {
     }
 }
 
-// Backfill for retailer_providers: fill NULLs to sane defaults
+// Backfill for retailer_providers: fill NULLs in the configured columns to their defaults.
 // Returns number of rows updated in this iteration.
-async fn run_retailer_providers_batch(pool: &PgPool, batch_size: i64) -> Result<u64> {
+async fn run_retailer_providers_batch<'c>(
+    conn: impl sqlx::PgExecutor<'c>,
+    batch_size: i64,
+    columns: &[BackfillColumn],
+) -> Result<u64> {
     // Use a single atomic statement with FOR UPDATE SKIP LOCKED inside a CTE.
     // No explicit transaction is required; the lock and update occur within this statement.
-    let result = sqlx::query(
-        r#"
-        WITH cte AS (
-          SELECT id
-          FROM public.retailer_providers
-          WHERE credentials IS NULL
-             OR settings IS NULL
-             OR metadata IS NULL
-             OR jurisdiction_scope IS NULL
-             OR is_enabled IS NULL
-             OR priority IS NULL
-          FOR UPDATE SKIP LOCKED
-          LIMIT $1
-        )
-        UPDATE public.retailer_providers rp
-        SET credentials        = coalesce(rp.credentials, '{}'::jsonb),
-            settings           = coalesce(rp.settings, '{}'::jsonb),
-            metadata           = coalesce(rp.metadata, '{}'::jsonb),
-            jurisdiction_scope = coalesce(rp.jurisdiction_scope, '{}'::text[]),
-            is_enabled         = coalesce(rp.is_enabled, true),
-            priority           = coalesce(rp.priority, 100)
-        FROM cte
-        WHERE rp.id = cte.id
-        RETURNING rp.id
-        "#,
-    )
-    .bind(batch_size)
-    .execute(pool)
-    .await?;
+    let sql = build_update_sql(columns);
+    let mut query = sqlx::query(&sql).bind(batch_size);
+    for c in columns {
+        query = query.bind(c.default.as_str());
+    }
+    let result = query.execute(conn).await?;
     Ok(result.rows_affected())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_cover_all_columns() {
+        let cols = resolve_columns(None, |_| None).unwrap();
+        assert_eq!(cols.len(), RETAILER_PROVIDER_COLUMNS.len());
+        let priority = cols.iter().find(|c| c.name == "priority").unwrap();
+        assert_eq!(priority.default, "100");
+    }
+
+    #[test]
+    fn subset_only_updates_selected_column() {
+        let cols = resolve_columns(Some("priority"), |k| {
+            (k == "BACKFILL_DEFAULT_PRIORITY").then(|| "50".to_string())
+        })
+        .unwrap();
+        assert_eq!(cols.len(), 1);
+        assert_eq!(cols[0].default, "50");
+
+        let sql = build_update_sql(&cols);
+        assert!(sql.contains("priority = coalesce(rp.priority, $2::integer)"));
+        assert!(sql.contains("WHERE priority IS NULL"));
+        for (other, _, _) in RETAILER_PROVIDER_COLUMNS
+            .iter()
+            .filter(|(n, _, _)| *n != "priority")
+        {
+            assert!(!sql.contains(other), "unexpected column {other} in SQL");
+        }
+    }
+
+    #[test]
+    fn preview_sql_is_read_only() {
+        let cols = resolve_columns(Some("priority,is_enabled"), |_| None).unwrap();
+        let sql = build_preview_sql(&cols);
+        assert!(!sql.contains("UPDATE public"));
+        assert!(sql.contains("FOR UPDATE SKIP LOCKED"));
+        assert!(sql.contains("count(*)::bigint AS candidates"));
+        assert!(sql.contains("count(*) FILTER (WHERE priority)::bigint AS priority"));
+        assert!(sql.contains("count(*) FILTER (WHERE is_enabled)::bigint AS is_enabled"));
+        assert!(!sql.contains("LIMIT"));
+    }
+
+    #[test]
+    fn unknown_column_is_rejected() {
+        assert!(resolve_columns(Some("priority,bogus"), |_| None).is_err());
+    }
+
+    /// Inside `tx`: make sure retailer_providers has every backfill column (newer schemas
+    /// dropped them).
+    async fn ensure_backfill_columns(tx: &mut sqlx::PgConnection) {
+        for (name, sql_type, _) in RETAILER_PROVIDER_COLUMNS {
+            sqlx::query(&format!(
+                "ALTER TABLE public.retailer_providers ADD COLUMN IF NOT EXISTS {name} {sql_type}"
+            ))
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        }
+    }
+
+    /// Inside `tx`: insert one retailer_providers row with every backfill column NULL.
+    /// Returns its id.
+    async fn seed_null_row(tx: &mut sqlx::PgConnection) -> i64 {
+        ensure_backfill_columns(&mut *tx).await;
+        let retailer_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.retailers (name, slug) VALUES ('Backfill Test', 'backfill-test')
+             RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        let provider_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.providers (name, slug, kind)
+             VALUES ('Backfill Test', 'backfill-test', 'catalog') RETURNING id",
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        sqlx::query_scalar(
+            "INSERT INTO public.retailer_providers (retailer_id, provider_id)
+             VALUES ($1, $2) RETURNING id",
+        )
+        .bind(retailer_id)
+        .bind(provider_id)
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn subset_backfill_fills_only_the_selected_column() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let pool = PgPool::connect(&url).await.unwrap();
+        // Everything runs in a transaction that is rolled back.
+        let mut tx = pool.begin().await.unwrap();
+        let id = seed_null_row(&mut tx).await;
+
+        let cols = resolve_columns(Some("priority"), |k| {
+            (k == "BACKFILL_DEFAULT_PRIORITY").then(|| "50".to_string())
+        })
+        .unwrap();
+        while run_retailer_providers_batch(&mut *tx, 100, &cols)
+            .await
+            .unwrap()
+            > 0
+        {}
+
+        let row = sqlx::query(
+            "SELECT priority, is_enabled, credentials IS NULL AS credentials_null
+             FROM public.retailer_providers WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        tx.rollback().await.unwrap();
+
+        assert_eq!(row.get::<Option<i32>, _>("priority"), Some(50));
+        assert_eq!(row.get::<Option<bool>, _>("is_enabled"), None);
+        assert!(row.get::<bool, _>("credentials_null"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn dry_run_counts_candidates_without_writing() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let pool = PgPool::connect(&url).await.unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        ensure_backfill_columns(&mut tx).await;
+        let cols = resolve_columns(None, |_| None).unwrap();
+        let before = count_candidates(&mut tx, &cols).await.unwrap();
+        let id = seed_null_row(&mut tx).await;
+        let after = count_candidates(&mut tx, &cols).await.unwrap();
+        // A second preview sees the same row: the first neither filled nor claimed it.
+        let again = count_candidates(&mut tx, &cols).await.unwrap();
+
+        let row = sqlx::query(
+            "SELECT priority IS NULL AND is_enabled IS NULL AND credentials IS NULL AS untouched
+             FROM public.retailer_providers WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await
+        .unwrap();
+        tx.rollback().await.unwrap();
+
+        assert_eq!(after.candidates, before.candidates + 1);
+        for ((name, b), (_, a)) in before.nulls.iter().zip(&after.nulls) {
+            assert_eq!(*a, b + 1, "{name}");
+        }
+        assert_eq!(again, after);
+        assert!(row.get::<bool, _>("untouched"));
+    }
+}
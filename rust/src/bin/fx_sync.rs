@@ -1,6 +1,9 @@
 use anyhow::Result;
 use chrono::Utc;
-use i_miss_rust::database_ops::{db::Db, exchange::ExchangeService};
+use i_miss_rust::database_ops::{
+    db::Db,
+    exchange::{ExchangeService, FxSource},
+};
 use std::time::Duration;
 use tracing_subscriber::{fmt::SubscriberBuilder, EnvFilter};
 
@@ -33,14 +36,25 @@ async fn main() -> Result<()> {
 
 async fn run_once(svc: &ExchangeService) -> Result<()> {
     let start = Utc::now();
-    let summary = svc.sync_all().await?;
+    let summary = svc.sync_all(&FxSource::list_from_env()).await;
+    for failed in summary.failed_sources() {
+        eprintln!(
+            "[fx_sync] source {} failed: {}",
+            failed.source.name(),
+            failed.error.as_deref().unwrap_or_default()
+        );
+    }
     println!(
-        "[fx_sync] synced rates: fetched={} stored={} elapsed_ms={} ts={}",
+        "[fx_sync] synced rates: fetched={} stored={} pairs={} elapsed_ms={} ts={}",
         summary.fetched,
         summary.stored,
+        summary.pairs,
         (Utc::now() - start).num_milliseconds(),
         summary.timestamp
     );
+    if summary.all_failed() {
+        anyhow::bail!("every FX source failed");
+    }
     Ok(())
 }
 
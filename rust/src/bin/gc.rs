@@ -1,7 +1,7 @@
 use anyhow::{anyhow, bail, Context, Result};
 use clap::{CommandFactory, Parser, Subcommand};
 use i_miss_rust::database_ops::db::Db;
-use i_miss_rust::database_ops::exchange::ExchangeService;
+use i_miss_rust::database_ops::exchange::{ExchangeService, FxSource};
 use i_miss_rust::database_ops::giantbomb::ingest::ingest_from_file as gb_ingest_from_file;
 use i_miss_rust::database_ops::igdb::client as igdb_client;
 use i_miss_rust::database_ops::ingest_providers::{
@@ -68,6 +68,36 @@ enum Commands {
         #[arg(long)]
         max_connections: Option<u32>,
     },
+    /// Export every game's denormalized profile to an NDJSON file
+    ExportCatalog {
+        /// Optional override for the database URL
+        #[arg(long)]
+        db_url: Option<String>,
+        /// Output file (defaults to CATALOG_EXPORT_PATH; required one way or the other)
+        #[arg(long)]
+        path: Option<PathBuf>,
+        /// Games fetched per batch
+        #[arg(long, default_value_t = 500)]
+        batch_size: i64,
+        /// Stop after this many games
+        #[arg(long)]
+        limit: Option<u64>,
+        /// Continue after the last complete line of an existing file
+        #[arg(long, default_value_t = false)]
+        resume: bool,
+        /// Optional comma-separated ISO country codes limiting prices/ratings
+        #[arg(long, value_delimiter = ',')]
+        regions: Option<Vec<String>>,
+    },
+    /// Delete synthetic rows seeded by test harnesses (marked prices, `fallback` locale ratings)
+    PurgeSynthetic {
+        /// Optional override for the database URL
+        #[arg(long)]
+        db_url: Option<String>,
+        /// Only count the rows that would be removed
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
     /// Backfill missing sellables for canonical video game titles
     DbBackfillSellables {
         /// Optional override for the database URL
@@ -284,10 +314,21 @@ async fn main() -> Result<()> {
             info!(url = %redact_postgres_url(&database_url), "exchange-sync: connecting");
             let db = Db::connect(&database_url, 5).await?;
             let service = ExchangeService::new(db);
-            let summary = service.sync_all().await?;
+            let summary = service.sync_all(&FxSource::list_from_env()).await;
+            for failed in summary.failed_sources() {
+                warn!(
+                    source = failed.source.name(),
+                    error = failed.error.as_deref().unwrap_or_default(),
+                    "exchange-sync: source failed"
+                );
+            }
+            if summary.all_failed() {
+                anyhow::bail!("exchange-sync: every FX source failed");
+            }
             info!(
                 fetched = summary.fetched,
                 stored = summary.stored,
+                pairs = summary.pairs,
                 timestamp = %summary.timestamp,
                 "exchange-sync: completed"
             );
@@ -331,6 +372,39 @@ async fn main() -> Result<()> {
             };
             run(cfg).await?;
         }
+        Commands::ExportCatalog {
+            db_url,
+            path,
+            batch_size,
+            limit,
+            resume,
+            regions,
+        } => {
+            use i_miss_rust::cli::catalog_export::{run, CatalogExportConfig};
+            let cfg = CatalogExportConfig {
+                database_url: db_url,
+                path,
+                batch_size,
+                limit,
+                resume,
+                regions: regions.unwrap_or_default(),
+                ..Default::default()
+            };
+            let report = run(cfg).await?;
+            info!(
+                written = report.written,
+                last_id = ?report.last_id,
+                "export-catalog: finished"
+            );
+        }
+        Commands::PurgeSynthetic { db_url, dry_run } => {
+            use i_miss_rust::cli::purge_synthetic::{run, PurgeSyntheticConfig};
+            run(PurgeSyntheticConfig {
+                database_url: db_url,
+                dry_run,
+            })
+            .await?;
+        }
         Commands::DbBackfillSellables {
             db_url,
             limit,
@@ -1227,6 +1301,10 @@ fn build_nexarda_options_from_env() -> NexardaOptions {
         store_map,
         base_url: None,
         timeout: None,
+        max_retries: None,
+        backoff_ms: None,
+        reqs_per_min: None,
+        rps: None,
         api_key: std::env::var("NEXARDA_API_KEY").ok(),
         auto_register_stores: Some(true),
         default_regions,
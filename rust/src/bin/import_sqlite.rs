@@ -9,6 +9,7 @@ use i_miss_rust::database_ops::ingest_providers::{
     table_exists, ProviderEntityCache,
 };
 use i_miss_rust::util::env::{self, db_url_prefer_session};
+use i_miss_rust::util::latency::LatencySummary;
 use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
 use serde_json::Value;
 use sqlx::sqlite::SqlitePoolOptions; // requires sqlx feature "sqlite"
@@ -2543,19 +2544,8 @@ impl ImportContext {
             );
         }
 
-        if !vg_flush_durations.is_empty() {
-            vg_flush_durations.sort();
-            let total = vg_flush_durations.len();
-            let sum: std::time::Duration = vg_flush_durations
-                .iter()
-                .copied()
-                .reduce(|a, b| a + b)
-                .unwrap();
-            let avg_ms = (sum.as_secs_f64() * 1000.0) / (total as f64);
-            let p50 = vg_flush_durations[total / 2];
-            let p95_idx = (((total as f64) * 0.95).floor() as usize).min(total - 1);
-            let p95 = vg_flush_durations[p95_idx];
-            info!(target="metrics", kind="video_game_update", batches=total, avg_ms=?avg_ms, p50_ms=?p50.as_secs_f64()*1000.0, p95_ms=?p95.as_secs_f64()*1000.0, "video game update batch latency summary");
+        if let Some(l) = LatencySummary::from_durations(&vg_flush_durations) {
+            info!(target="metrics", kind="video_game_update", batches=l.count, avg_ms=?l.avg_ms, p50_ms=?l.p50_ms, p95_ms=?l.p95_ms, p99_ms=?l.p99_ms, "video game update batch latency summary");
         }
 
         // Final checkpoint save if we advanced
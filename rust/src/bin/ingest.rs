@@ -714,6 +714,10 @@ fn build_nexarda_options() -> NexardaOptions {
         context: None,
         base_url: None,
         timeout: None,
+        max_retries: None,
+        backoff_ms: None,
+        reqs_per_min: None,
+        rps: None,
     }
 }
 
@@ -71,6 +71,10 @@ async fn main() -> Result<()> {
         context: None,
         base_url: None,
         timeout: None,
+        max_retries: None,
+        backoff_ms: None,
+        reqs_per_min: None,
+        rps: None,
     };
     info!("provider=nexarda action=start");
     if let Err(e) = nx.ingest_to_db(&db, opts).await {
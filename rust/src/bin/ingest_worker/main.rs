@@ -6,16 +6,31 @@ use serde_json::json;
 use std::env;
 // use std::fmt; // unused
 use sqlx::Row;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, Semaphore};
+use tokio::task::JoinSet;
 use tokio::time::{interval, sleep, Duration, MissedTickBehavior};
 use tokio_postgres::{AsyncMessage, NoTls};
 use url::{form_urlencoded, Url};
 
+use i_miss_rust::api::metrics::{
+    ingest_queue_name, provider_metrics_text, queue_metrics_text, queue_stats, PromText,
+    PROMETHEUS_CONTENT_TYPE,
+};
 use i_miss_rust::database_ops::db::Db;
+use i_miss_rust::database_ops::igdb::client::IgdbServiceConfig;
+use i_miss_rust::database_ops::itad::ItadSyncOptions;
 use i_miss_rust::database_ops::nexarda::provider::{NexardaOptions, NexardaProvider};
+use i_miss_rust::database_ops::playstation::prices::PsPricesOptions;
+use i_miss_rust::database_ops::provider_loop::ProviderMetrics;
+use i_miss_rust::database_ops::rawg::RawgSyncOptions;
+use i_miss_rust::database_ops::steam::provider::SteamRunOptions;
+use i_miss_rust::database_ops::tgdb::TgdbSyncOptions;
+use i_miss_rust::database_ops::xbox::provider::{XboxOptions, XboxRequestPolicy};
 use i_miss_rust::util::env as env_util;
+use i_miss_rust::util::trace_context::{otel_enabled, TraceParent, TRACEPARENT_HEADER};
 
 // -------- Manager: in-memory logs + pause/resume control --------
 #[derive(Clone)]
@@ -70,29 +85,64 @@ pub async fn run_from_env() -> Result<()> {
 
     // Config
     let queue_cfg = QueueConfig::from_env();
+    let limits = ProviderLimits::from_env();
     let manager = Manager::new(1000);
     ensure_queue(&db, &queue_cfg).await?;
     let start_msg = format!(
-        "[ingest_worker] start queue={} vt={} poll={} max_retries={}",
+        "[ingest_worker] start queue={} vt={} poll={} max_retries={} concurrency={} read_batch={} provider_limits={:?}",
         queue_cfg.queue_name,
         queue_cfg.visibility_timeout_secs,
         queue_cfg.poll_interval_secs,
-        queue_cfg.max_retries
+        queue_cfg.max_retries,
+        limits.max_concurrency,
+        queue_cfg.read_batch,
+        limits.caps()
     );
     println!("{}", start_msg);
     push_log(&manager, &start_msg);
 
+    // Shutdown: Ctrl+C stops the poll loop and the HTTP listener
+    let (shutdown_tx, mut shutdown_rx) = broadcast::channel::<()>(1);
+    {
+        let shutdown_tx = shutdown_tx.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                let _ = shutdown_tx.send(());
+            }
+        });
+    }
+
     // Metrics and HTTP (optional)
     let metrics = Arc::new(Mutex::new(WorkerMetrics::default()));
+    {
+        let (db, cfg, metrics) = (db.clone(), queue_cfg.clone(), metrics.clone());
+        let mut shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            let mut tick = interval(Duration::from_secs(cfg.stats_interval_secs));
+            tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    _ = tick.tick() => {
+                        if let Err(err) = refresh_queue_stats(&db, &cfg, &metrics).await {
+                            eprintln!("[ingest_worker] queue stats failed: {err:?}");
+                        }
+                    }
+                    _ = shutdown_rx.recv() => break,
+                }
+            }
+        });
+    }
+    let mut http_task = None;
     if let Ok(addr) = env::var("WORKER_HTTP_ADDR") {
         if !addr.is_empty() {
-            start_http_server(
+            http_task = Some(start_http_server(
                 db.clone(),
                 queue_cfg.clone(),
                 metrics.clone(),
                 manager.clone(),
+                shutdown_tx.subscribe(),
                 addr,
-            );
+            )?);
         }
     }
 
@@ -116,101 +166,114 @@ pub async fn run_from_env() -> Result<()> {
     };
 
     let poll_delay = Duration::from_secs(queue_cfg.poll_interval_secs.max(1));
+    // Staleness-driven refresh: periodically enqueue targeted jobs for the stalest items.
+    let stale_refresh_every = env_util::env_flag("STALE_REFRESH_ENABLED", false).then(|| {
+        Duration::from_secs(env_util::env_parse("STALE_REFRESH_INTERVAL_SECS", 900u64).max(60))
+    });
+    let mut last_stale_refresh: Option<std::time::Instant> = None;
+    let job_slots = Arc::new(Semaphore::new(limits.max_concurrency));
+    let mut in_flight = JoinSet::new();
+    let mut pop_failures = 0u32;
 
     loop {
+        if !matches!(
+            shutdown_rx.try_recv(),
+            Err(broadcast::error::TryRecvError::Empty)
+        ) {
+            break;
+        }
         let t_poll = std::time::Instant::now();
         if manager.is_paused() {
             sleep(poll_delay).await;
             continue;
         }
+        if let Some(every) = stale_refresh_every {
+            if last_stale_refresh.is_none_or(|t| t.elapsed() >= every) {
+                last_stale_refresh = Some(std::time::Instant::now());
+                let msg = match enqueue_stale_refreshes(&db, &queue_cfg).await {
+                    Ok(jobs) => format!("[ingest_worker] stale refresh: enqueued {jobs} job(s)"),
+                    Err(err) => format!("[ingest_worker] stale refresh failed: {err:?}"),
+                };
+                println!("{}", msg);
+                push_log(&manager, &msg);
+            }
+        }
 
-        match pop_job(&db, &queue_cfg).await? {
-            Some(p) => {
+        // One slot per in-flight job, taken before popping so a job is only read (and its
+        // visibility timeout started) once it can run. Wait for one, then read as many
+        // messages as there are free slots, up to the batch size.
+        let mut slots = vec![job_slots
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("job slot semaphore is never closed")];
+        while slots.len() < queue_cfg.read_batch {
+            match job_slots.clone().try_acquire_owned() {
+                Ok(slot) => slots.push(slot),
+                Err(_) => break,
+            }
+        }
+        let popped = match pop_jobs(&db, &queue_cfg, slots.len()).await {
+            Ok(popped) => {
+                pop_failures = 0;
+                popped
+            }
+            Err(err) => {
+                // Returning here would abort the in-flight jobs; wait for the queue instead.
+                drop(slots);
+                pop_failures = pop_failures.saturating_add(1);
+                let delay = i_miss_rust::util::backoff::jittered_retry_secs(
+                    queue_cfg.poll_interval_secs.max(1),
+                    queue_cfg.retry_max_secs.max(queue_cfg.poll_interval_secs),
+                    pop_failures,
+                    &mut rand::thread_rng(),
+                );
+                let msg = format!(
+                    "[ingest_worker] reading the queue failed (attempt {pop_failures}); retrying in {delay}s: {err:?}"
+                );
+                eprintln!("{}", msg);
+                push_log(&manager, &msg);
+                metrics.lock().unwrap().last_error = Some(err.to_string());
+                tokio::select! {
+                    _ = shutdown_rx.recv() => break,
+                    _ = sleep(Duration::from_secs(delay)) => {}
+                }
+                continue;
+            }
+        };
+        match popped.len() {
+            1.. => {
                 {
                     let waited = t_poll.elapsed();
                     let mut m = metrics.lock().unwrap();
                     m.last_wait_ms = waited.as_millis() as u64;
-                    m.dequeues += 1;
-                }
-                let t_run = std::time::Instant::now();
-
-                // VT heartbeat
-                let db_clone = db.clone();
-                let cfg_clone = queue_cfg.clone();
-                let msg_id = p.msg_id;
-                let (hb_tx, mut hb_rx) = tokio::sync::oneshot::channel::<()>();
-                tokio::spawn(async move {
-                    let mut tick = interval(Duration::from_secs(
-                        (cfg_clone.visibility_timeout_secs as u64).max(4) / 2,
-                    ));
-                    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
-                    loop {
-                        tokio::select! {
-                            _ = tick.tick() => { let _ = set_job_vt(&db_clone, &cfg_clone, msg_id, cfg_clone.visibility_timeout_secs).await; }
-                            _ = &mut hb_rx => break,
-                        }
-                    }
-                });
-
-                let res = handle_job(&db, &p.job).await;
-                let run_elapsed = t_run.elapsed();
-                let _ = hb_tx.send(());
-                match res {
-                    Ok(_) => {
-                        delete_job(&db, &queue_cfg, p.msg_id).await?;
+                    m.dequeues += popped.len() as u64;
+                }
+                // Slots without a message are released when `slots` is dropped.
+                for (p, slot) in popped.into_iter().zip(slots) {
+                    let (db, cfg, limits) = (db.clone(), queue_cfg.clone(), limits.clone());
+                    let (metrics, manager) = (metrics.clone(), manager.clone());
+                    in_flight.spawn(async move {
+                        let msg_id = p.msg_id;
+                        if let Err(err) =
+                            run_popped_job(&db, &cfg, &limits, &metrics, &manager, p).await
                         {
-                            let mut m = metrics.lock().unwrap();
-                            m.last_run_ms = run_elapsed.as_millis() as u64;
-                        }
-                        let ok_msg = format!(
-                            "[ingest_worker] job msg_id={} provider={} task={} acked (ran {:.2?})",
-                            p.msg_id, p.job.provider, p.job.task, run_elapsed
-                        );
-                        println!("{}", ok_msg);
-                        push_log(&manager, &ok_msg);
-                    }
-                    Err(err) => {
-                        let fail_msg = format!(
-                            "[ingest_worker] job msg_id={} provider={} task={} failed after {:.2?}: {err:?}",
-                            p.msg_id, p.job.provider, p.job.task, run_elapsed
-                        );
-                        eprintln!("{}", fail_msg);
-                        push_log(&manager, &fail_msg);
-                        {
-                            let mut m = metrics.lock().unwrap();
-                            m.last_run_ms = run_elapsed.as_millis() as u64;
-                            m.failures += 1;
-                            m.last_error = Some(err.to_string());
-                        }
-                        let attempt = (p.read_ct as u32).saturating_add(1);
-                        let mut delay = queue_cfg
-                            .retry_base_secs
-                            .saturating_mul(1u64 << attempt.saturating_sub(1).min(6));
-                        if delay > queue_cfg.retry_max_secs {
-                            delay = queue_cfg.retry_max_secs;
-                        }
-                        if queue_cfg.max_retries > 0 && attempt > queue_cfg.max_retries {
-                            archive_job(&db, &queue_cfg, p.msg_id).await?;
-                            let arch_msg = format!(
-                                "[ingest_worker] job msg_id={} archived after {} attempts",
-                                p.msg_id, p.read_ct
+                            let msg = format!(
+                                "[ingest_worker] job msg_id={} queue update failed: {err:?}",
+                                msg_id
                             );
-                            println!("{}", arch_msg);
-                            push_log(&manager, &arch_msg);
-                        } else {
-                            set_job_vt(&db, &queue_cfg, p.msg_id, delay as i32).await?;
-                            let sched_msg = format!(
-                                "[ingest_worker] job msg_id={} rescheduled in {}s (attempt {})",
-                                p.msg_id, delay, attempt
-                            );
-                            println!("{}", sched_msg);
-                            push_log(&manager, &sched_msg);
+                            eprintln!("{}", msg);
+                            push_log(&manager, &msg);
                         }
-                    }
+                        drop(slot);
+                    });
                 }
+                while in_flight.try_join_next().is_some() {}
             }
-            None => {
+            0 => {
+                drop(slots);
                 tokio::select! {
+                    _ = shutdown_rx.recv() => break,
                     _ = sleep(poll_delay) => {}
                     msg = async { match &mut notify_stream { Some(rx) => rx.recv().await, None => pending::<Option<String>>().await } } => {
                         if let Some(payload) = msg {
@@ -223,6 +286,141 @@ pub async fn run_from_env() -> Result<()> {
             }
         }
     }
+
+    if !in_flight.is_empty() {
+        println!(
+            "[ingest_worker] waiting for {} in-flight job(s)",
+            in_flight.len()
+        );
+    }
+    while in_flight.join_next().await.is_some() {}
+    println!("[ingest_worker] shutting down");
+    drop(shutdown_tx);
+    if let Some(task) = http_task {
+        let _ = task.await;
+    }
+    Ok(())
+}
+
+/// Run one popped job under `limits` with a VT heartbeat, then ack it, reschedule it with
+/// backoff, or archive it once `max_retries` is exceeded. Jobs with an unknown provider/task
+/// are archived straight away; jobs whose provider is at its cap are handed back to the
+/// queue for another poll interval.
+async fn run_popped_job(
+    db: &Db,
+    cfg: &QueueConfig,
+    limits: &ProviderLimits,
+    metrics: &Mutex<WorkerMetrics>,
+    manager: &Manager,
+    p: PoppedJob,
+) -> Result<()> {
+    if !is_known_task(&p.job.provider, &p.job.task) {
+        // Retrying cannot help; dead-letter it before the heartbeat claims the VT.
+        archive_job(db, cfg, p.msg_id).await?;
+        let msg = format!(
+            "[ingest_worker] job msg_id={} has unknown provider={} task={}; archived without retry",
+            p.msg_id, p.job.provider, p.job.task
+        );
+        eprintln!("{}", msg);
+        push_log(manager, &msg);
+        metrics.lock().unwrap().failures += 1;
+        return Ok(());
+    }
+    // Waiting for the provider would hold a job slot other providers could use.
+    let Some(_provider_slot) = limits.try_slot(&p.job.provider) else {
+        limits.defer(p.msg_id);
+        set_job_vt(db, cfg, p.msg_id, cfg.poll_interval_secs.max(1) as i32).await?;
+        tracing::debug!(msg_id = p.msg_id, provider = %p.job.provider, "provider at its cap; job deferred");
+        return Ok(());
+    };
+    // Deferred reads are not failed attempts.
+    let read_ct = p.read_ct - limits.take_deferrals(p.msg_id);
+
+    let t_run = std::time::Instant::now();
+
+    // VT heartbeat
+    let db_clone = db.clone();
+    let cfg_clone = cfg.clone();
+    let msg_id = p.msg_id;
+    let (hb_tx, mut hb_rx) = tokio::sync::oneshot::channel::<()>();
+    tokio::spawn(async move {
+        let mut tick = interval(Duration::from_secs(
+            (cfg_clone.visibility_timeout_secs as u64).max(4) / 2,
+        ));
+        tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = tick.tick() => { let _ = set_job_vt(&db_clone, &cfg_clone, msg_id, cfg_clone.visibility_timeout_secs).await; }
+                _ = &mut hb_rx => break,
+            }
+        }
+    });
+
+    let res = handle_job(db, &p.job).await;
+    let run_elapsed = t_run.elapsed();
+    let _ = hb_tx.send(());
+    match res {
+        Ok(_) => {
+            delete_job(db, cfg, p.msg_id).await?;
+            {
+                let mut m = metrics.lock().unwrap();
+                m.last_run_ms = run_elapsed.as_millis() as u64;
+                m.providers
+                    .entry(p.job.provider.clone())
+                    .or_default()
+                    .record_success(run_elapsed);
+            }
+            let ok_msg = format!(
+                "[ingest_worker] job msg_id={} provider={} task={} acked (ran {:.2?})",
+                p.msg_id, p.job.provider, p.job.task, run_elapsed
+            );
+            println!("{}", ok_msg);
+            push_log(manager, &ok_msg);
+        }
+        Err(err) => {
+            let fail_msg = format!(
+                "[ingest_worker] job msg_id={} provider={} task={} failed after {:.2?}: {err:?}",
+                p.msg_id, p.job.provider, p.job.task, run_elapsed
+            );
+            eprintln!("{}", fail_msg);
+            push_log(manager, &fail_msg);
+            {
+                let mut m = metrics.lock().unwrap();
+                m.last_run_ms = run_elapsed.as_millis() as u64;
+                m.failures += 1;
+                m.last_error = Some(err.to_string());
+                m.providers
+                    .entry(p.job.provider.clone())
+                    .or_default()
+                    .record_failure(run_elapsed, &err.to_string());
+            }
+            let attempt = (read_ct.max(0) as u32).saturating_add(1);
+            let delay = i_miss_rust::util::backoff::jittered_retry_secs(
+                cfg.retry_base_secs,
+                cfg.retry_max_secs,
+                attempt,
+                &mut rand::thread_rng(),
+            );
+            if cfg.max_retries > 0 && attempt > cfg.max_retries {
+                archive_job(db, cfg, p.msg_id).await?;
+                let arch_msg = format!(
+                    "[ingest_worker] job msg_id={} archived after {} attempts",
+                    p.msg_id, read_ct
+                );
+                println!("{}", arch_msg);
+                push_log(manager, &arch_msg);
+            } else {
+                set_job_vt(db, cfg, p.msg_id, delay as i32).await?;
+                let sched_msg = format!(
+                    "[ingest_worker] job msg_id={} rescheduled in {}s (attempt {})",
+                    p.msg_id, delay, attempt
+                );
+                println!("{}", sched_msg);
+                push_log(manager, &sched_msg);
+            }
+        }
+    }
+    Ok(())
 }
 
 #[tokio::main]
@@ -240,6 +438,9 @@ struct IngestJob {
     provider_id: Option<i64>,
     requested_at: chrono::DateTime<Utc>,
     correlation_id: String,
+    /// W3C `traceparent` of the enqueuing request; only captured with `OTEL_ENABLED`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    traceparent: Option<String>,
 }
 
 impl IngestJob {
@@ -255,8 +456,39 @@ impl IngestJob {
                 Utc::now().timestamp_millis(),
                 std::process::id()
             ),
+            traceparent: None,
+        }
+    }
+
+    /// Span for running this job: a child of the enqueuing request's span when tracing is
+    /// `enabled` (`OTEL_ENABLED`) and the job carries a valid `traceparent`.
+    fn span(&self, enabled: bool) -> Option<tracing::Span> {
+        if !enabled {
+            return None;
         }
+        let parent = TraceParent::parse(self.traceparent.as_deref()?)?;
+        let span = parent.child();
+        Some(tracing::info_span!(
+            "handle_job",
+            provider = %self.provider,
+            task = %self.task,
+            correlation_id = %self.correlation_id,
+            trace_id = %span.trace_id,
+            span_id = %span.span_id,
+            parent_span_id = %parent.span_id,
+            sampled = span.sampled(),
+        ))
+    }
+}
+
+/// The request's `traceparent`, normalized, when tracing is `enabled` (`OTEL_ENABLED`) and
+/// it is valid.
+fn request_traceparent(req: &actix_web::HttpRequest, enabled: bool) -> Option<String> {
+    if !enabled {
+        return None;
     }
+    let raw = req.headers().get(TRACEPARENT_HEADER)?.to_str().ok()?;
+    TraceParent::parse(raw).map(|tp| tp.to_string())
 }
 
 #[derive(Debug)]
@@ -273,6 +505,59 @@ struct WorkerMetrics {
     dequeues: u64,
     failures: u64,
     last_error: Option<String>,
+    /// Messages in `pgmq.q_<queue>`, visible or not; refreshed every
+    /// `INGEST_QUEUE_STATS_SECS`.
+    queue_depth: i64,
+    /// Age of the oldest message in the queue; `None` when it is empty.
+    oldest_msg_age_secs: Option<i64>,
+    /// Job runs per provider, exported under the main server's provider metric names.
+    providers: BTreeMap<String, ProviderMetrics>,
+}
+
+impl WorkerMetrics {
+    /// Prometheus text exposition of these metrics: the worker's and queue's labelled with
+    /// `queue`, then per provider as on the main server.
+    fn prometheus_text(&self, queue: &str) -> String {
+        let labels = [("queue", queue)];
+        let families: [(&str, &str, &str, f64); 4] = [
+            (
+                "ingest_worker_dequeues_total",
+                "counter",
+                "Messages read from the queue.",
+                self.dequeues as f64,
+            ),
+            (
+                "ingest_worker_failures_total",
+                "counter",
+                "Jobs that failed or were archived as unknown.",
+                self.failures as f64,
+            ),
+            (
+                "ingest_worker_last_run_duration_seconds",
+                "gauge",
+                "Duration of the most recent successful job.",
+                self.last_run_ms as f64 / 1000.0,
+            ),
+            (
+                "ingest_worker_last_wait_seconds",
+                "gauge",
+                "Time the most recent dequeue waited for a message.",
+                self.last_wait_ms as f64 / 1000.0,
+            ),
+        ];
+        let mut text = PromText::new();
+        for (name, kind, help, value) in families {
+            text.family(name, kind, help).sample(name, &labels, value);
+        }
+        let mut text = text.finish();
+        text.push_str(&queue_metrics_text(
+            queue,
+            self.queue_depth,
+            self.oldest_msg_age_secs,
+        ));
+        text.push_str(&provider_metrics_text(&self.providers));
+        text
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -284,14 +569,17 @@ struct QueueConfig {
     retry_base_secs: u64,
     retry_max_secs: u64,
     notify_channels: Vec<String>,
+    /// Messages read per poll (`INGEST_READ_BATCH`, default 1); never more than the free
+    /// job slots.
+    read_batch: usize,
+    /// How often queue depth/age are sampled into the metrics (`INGEST_QUEUE_STATS_SECS`,
+    /// default 5).
+    stats_interval_secs: u64,
 }
 
 impl QueueConfig {
     fn from_env() -> Self {
-        let queue_name = env::var("INGEST_QUEUE_NAME")
-            .ok()
-            .filter(|s| !s.is_empty())
-            .unwrap_or_else(|| "default_ingest".to_string());
+        let queue_name = ingest_queue_name();
         let vt = env::var("INGEST_QUEUE_VT_SECS")
             .ok()
             .and_then(|s| s.parse().ok())
@@ -323,6 +611,8 @@ impl QueueConfig {
             })
             .filter(|v: &Vec<String>| !v.is_empty())
             .unwrap_or_else(|| vec!["ingest_queue".to_string()]);
+        let read_batch = env_util::env_parse("INGEST_READ_BATCH", 1usize).max(1);
+        let stats_interval_secs = env_util::env_parse("INGEST_QUEUE_STATS_SECS", 5u64).max(1);
         Self {
             queue_name,
             visibility_timeout_secs: vt,
@@ -331,11 +621,158 @@ impl QueueConfig {
             retry_base_secs,
             retry_max_secs,
             notify_channels,
+            read_batch,
+            stats_interval_secs,
         }
     }
 }
 
+/// How many jobs run at once: `INGEST_MAX_CONCURRENCY` overall (default 1) and, per
+/// provider, the caps in `INGEST_PROVIDER_LIMITS` (JSON, e.g. `{"igdb":1,"nexarda":4}`).
+/// Providers without a cap are only bound by the overall limit. A job whose provider is
+/// at its cap is not waited on: it is deferred back to the queue with its VT set to the
+/// poll interval, freeing its overall slot and running no heartbeat, and is read again
+/// once the VT lapses.
+#[derive(Debug, Clone)]
+struct ProviderLimits {
+    max_concurrency: usize,
+    per_provider: Arc<HashMap<String, (usize, Arc<Semaphore>)>>,
+    /// Per msg_id, how many of its reads ended in such a deferral (with the latest time);
+    /// subtracted from `read_ct` so deferrals don't count as failed attempts.
+    deferrals: Arc<Mutex<HashMap<i64, (i32, std::time::Instant)>>>,
+}
+
+/// How long a deferral is remembered; another worker may have run the message since.
+const DEFERRAL_MEMORY: Duration = Duration::from_secs(3600);
+
+/// The name caps are kept under: aliases of one provider share its cap.
+fn provider_key(provider: &str) -> String {
+    let provider = provider.to_ascii_lowercase();
+    match provider.as_str() {
+        "playstation" | "psstore" | "ps-store" => "ps".to_string(),
+        "microsoft" => "xbox".to_string(),
+        _ => provider,
+    }
+}
+
+impl ProviderLimits {
+    fn new(max_concurrency: usize, caps: &HashMap<String, usize>) -> Self {
+        let per_provider = caps
+            .iter()
+            .map(|(provider, &cap)| {
+                let cap = cap.max(1);
+                (provider_key(provider), (cap, Arc::new(Semaphore::new(cap))))
+            })
+            .collect();
+        Self {
+            max_concurrency: max_concurrency.max(1),
+            per_provider: Arc::new(per_provider),
+            deferrals: Arc::default(),
+        }
+    }
+
+    fn from_env() -> Self {
+        let max_concurrency = env_util::env_parse("INGEST_MAX_CONCURRENCY", 1usize);
+        let caps = match env_util::env_opt("INGEST_PROVIDER_LIMITS") {
+            Some(raw) => serde_json::from_str::<HashMap<String, usize>>(&raw).unwrap_or_else(|e| {
+                eprintln!("[ingest_worker] INGEST_PROVIDER_LIMITS is not a provider->limit JSON object ({e}); ignoring");
+                HashMap::new()
+            }),
+            None => HashMap::new(),
+        };
+        Self::new(max_concurrency, &caps)
+    }
+
+    /// Configured per-provider caps, sorted by provider.
+    fn caps(&self) -> Vec<(&str, usize)> {
+        let mut caps: Vec<(&str, usize)> = self
+            .per_provider
+            .iter()
+            .map(|(provider, (cap, _))| (provider.as_str(), *cap))
+            .collect();
+        caps.sort();
+        caps
+    }
+
+    /// A slot for `provider`, held while its job runs; `None` while its cap is reached.
+    /// Uncapped providers always get an (empty) slot.
+    fn try_slot(&self, provider: &str) -> Option<Option<tokio::sync::OwnedSemaphorePermit>> {
+        match self.per_provider.get(&provider_key(provider)) {
+            Some((_, slots)) => slots.clone().try_acquire_owned().ok().map(Some),
+            None => Some(None),
+        }
+    }
+
+    /// Note that `msg_id` went back to the queue unrun.
+    fn defer(&self, msg_id: i64) {
+        let mut deferrals = self.deferrals.lock().unwrap();
+        deferrals.retain(|_, (_, at)| at.elapsed() < DEFERRAL_MEMORY);
+        let now = std::time::Instant::now();
+        let (count, at) = deferrals.entry(msg_id).or_insert((0, now));
+        *count += 1;
+        *at = now;
+    }
+
+    /// How often `msg_id` was deferred, forgetting it.
+    fn take_deferrals(&self, msg_id: i64) -> i32 {
+        self.deferrals
+            .lock()
+            .unwrap()
+            .remove(&msg_id)
+            .map_or(0, |(n, _)| n)
+    }
+}
+
+impl Default for ProviderLimits {
+    fn default() -> Self {
+        Self::new(1, &HashMap::new())
+    }
+}
+
+/// Tasks `dispatch_job` handles, per provider (aliases listed separately). Any provider
+/// also accepts `refresh`; `run_refresh_job` rejects those it has no targeted path for.
+const KNOWN_TASKS: &[(&str, &[&str])] = &[
+    ("ps", PS_TASKS),
+    ("playstation", PS_TASKS),
+    ("psstore", PS_TASKS),
+    ("steam", &["all", "backfill", "catalog", "run"]),
+    ("igdb", &["all", "backfill", "catalog"]),
+    ("xbox", &["all", "backfill", "catalog"]),
+    ("microsoft", &["all", "backfill"]),
+    (
+        "nexarda",
+        &["catalog", "ingest", "all", "json", "catalogue_file"],
+    ),
+    ("tgdb", TGDB_TASKS),
+    ("thegamesdb", TGDB_TASKS),
+    ("itad", &["prices_scan", "sync", "all"]),
+    ("rawg", &["catalog", "ingest", "sync", "all", "range"]),
+    ("giantbomb", &["json", "load"]),
+    ("giant_bomb", &["json"]),
+    ("gb", &["json"]),
+    ("provider_items", &["scoped"]),
+];
+const PS_TASKS: &[&str] = &["all", "backfill", "prices", "ratings", "catalog", "ingest"];
+const TGDB_TASKS: &[&str] = &["catalog", "ingest", "sync", "all"];
+
+/// Whether `dispatch_job` has an arm for `(provider, task)`; keep in step with its match.
+fn is_known_task(provider: &str, task: &str) -> bool {
+    task == "refresh"
+        || KNOWN_TASKS
+            .iter()
+            .any(|(p, tasks)| *p == provider && tasks.contains(&task))
+}
+
+/// Run `job`, under [`IngestJob::span`] if any.
 async fn handle_job(db: &Db, job: &IngestJob) -> Result<()> {
+    let run = dispatch_job(db, job);
+    match job.span(otel_enabled()) {
+        Some(span) => tracing::Instrument::instrument(run, span).await,
+        None => run.await,
+    }
+}
+
+async fn dispatch_job(db: &Db, job: &IngestJob) -> Result<()> {
     match (job.provider.as_str(), job.task.as_str()) {
         // Unified task to run all supported steps for a provider
         ("ps", "all") | ("playstation", "all") | ("psstore", "all") => {
@@ -351,51 +788,10 @@ async fn handle_job(db: &Db, job: &IngestJob) -> Result<()> {
             Ok(())
         }
         ("steam", "backfill") => {
-            // Parameterized backfill via env to leverage existing code path.
-            // Supported optional args: { recent_days: i64, max_regions: usize, fetch_media: bool, language: string }
-            struct EnvScope {
-                prev: Vec<(String, Option<String>)>,
-            }
-            impl EnvScope {
-                fn new() -> Self {
-                    Self { prev: Vec::new() }
-                }
-                fn set(&mut self, k: &str, v: &str) {
-                    let p = std::env::var(k).ok();
-                    self.prev.push((k.to_string(), p));
-                    _set_env_var(k, v);
-                }
-            }
-            impl Drop for EnvScope {
-                fn drop(&mut self) {
-                    for (k, v) in self.prev.drain(..) {
-                        if let Some(val) = v {
-                            _set_env_var(&k, &val);
-                        } else {
-                            _remove_env_var(&k);
-                        }
-                    }
-                }
-            }
-            let mut envs = EnvScope::new();
-            envs.set("STEAM_BACKFILL", "1");
-            if let Some(args) = &job.args {
-                if let Some(days) = args.get("recent_days").and_then(|v| v.as_i64()) {
-                    envs.set("STEAM_RECENT_MISSING_DAYS", &days.to_string());
-                }
-                if let Some(maxr) = args.get("max_regions").and_then(|v| v.as_u64()) {
-                    envs.set("STEAM_MAX_REGIONS", &maxr.to_string());
-                }
-                if let Some(fetch) = args.get("fetch_media").and_then(|v| v.as_bool()) {
-                    if fetch {
-                        envs.set("STEAM_FETCH_MEDIA", "1");
-                    }
-                }
-                if let Some(lang) = args.get("language").and_then(|v| v.as_str()) {
-                    envs.set("STEAM_LANGUAGE", lang);
-                }
-            }
-            i_miss_rust::database_ops::steam::provider::SteamProvider::run_from_env(db).await?;
+            // Args (all optional): { recent_days }
+            use i_miss_rust::database_ops::steam::provider::SteamProvider;
+            let args: SteamJobArgs = job_args(job, "steam")?;
+            SteamProvider::run_with_options(db, args.run_options()).await?;
             Ok(())
         }
         ("igdb", "all") => {
@@ -406,154 +802,26 @@ async fn handle_job(db: &Db, job: &IngestJob) -> Result<()> {
             // Structured IGDB backfill:
             // { from_year, to_year, platforms: [ids], page_size, max_pages, reqs_per_min, rps, concurrency, max_retries, backoff_ms }
             use i_miss_rust::database_ops::igdb::client::IgdbService;
-            // Allow rate-limit args to override env for this job via scoped env
-            struct EnvScope {
-                prev: Vec<(String, Option<String>)>,
-            }
-            impl EnvScope {
-                fn new() -> Self {
-                    Self { prev: Vec::new() }
-                }
-                fn set(&mut self, k: &str, v: &str) {
-                    let p = std::env::var(k).ok();
-                    self.prev.push((k.to_string(), p));
-                    _set_env_var(k, v);
-                }
-            }
-            impl Drop for EnvScope {
-                fn drop(&mut self) {
-                    for (k, v) in self.prev.drain(..) {
-                        if let Some(val) = v {
-                            _set_env_var(&k, &val);
-                        } else {
-                            _remove_env_var(&k);
-                        }
-                    }
-                }
-            }
-            let mut scoped = EnvScope::new();
-            let (mut y_from, mut y_to) = (2020i32, chrono::Utc::now().year());
-            let mut platforms: Vec<i32> = vec![];
-            let mut page_size: usize = 200;
-            let mut max_pages: usize = 50;
-            if let Some(args) = &job.args {
-                if let Some(v) = args.get("from_year").and_then(|v| v.as_i64()) {
-                    y_from = v as i32;
-                }
-                if let Some(v) = args.get("to_year").and_then(|v| v.as_i64()) {
-                    y_to = v as i32;
-                }
-                if let Some(arr) = args.get("platforms").and_then(|v| v.as_array()) {
-                    platforms = arr
-                        .iter()
-                        .filter_map(|x| x.as_i64().map(|n| n as i32))
-                        .collect();
-                }
-                if let Some(v) = args.get("page_size").and_then(|v| v.as_u64()) {
-                    page_size = v as usize;
-                }
-                if let Some(v) = args.get("max_pages").and_then(|v| v.as_u64()) {
-                    max_pages = v as usize;
-                }
-                // Optional rate-limit controls
-                if let Some(v) = args.get("reqs_per_min").and_then(|v| v.as_u64()) {
-                    scoped.set("IGDB_REQS_PER_MIN", &v.to_string());
-                }
-                if let Some(v) = args.get("rps").and_then(|v| v.as_f64()) {
-                    scoped.set("IGDB_RPS", &format!("{}", v));
-                }
-                if let Some(v) = args.get("concurrency").and_then(|v| v.as_u64()) {
-                    scoped.set("IGDB_CONCURRENCY", &v.to_string());
-                }
-                if let Some(v) = args.get("max_retries").and_then(|v| v.as_u64()) {
-                    scoped.set("IGDB_MAX_RETRIES", &v.to_string());
-                }
-                if let Some(v) = args.get("backoff_ms").and_then(|v| v.as_u64()) {
-                    scoped.set("IGDB_BACKOFF_MS", &v.to_string());
-                }
-            }
-            let svc = IgdbService::new_from_env()?;
+            let args = IgdbJobArgs::from_job(job)?;
+            let svc = IgdbService::with_config(args.service_config())?;
             let _ = svc
-                .backfill_range(db, y_from, y_to, &platforms, page_size, max_pages)
+                .backfill_range(
+                    db,
+                    args.from_year.unwrap_or(2020),
+                    args.to_year.unwrap_or_else(|| chrono::Utc::now().year()),
+                    args.platforms.as_deref().unwrap_or_default(),
+                    args.page_size.unwrap_or(200),
+                    args.max_pages.unwrap_or(50),
+                )
                 .await?;
             Ok(())
         }
         ("xbox", "all") | ("microsoft", "all") => {
-            // Map structured args to env for xbox provider
-            // Args: { market, language, product_ids:[...], product_ids_file, ms_cv, dry_run:bool, chunk_sleep_ms, chunk_size, reqs_per_min, rps, max_retries, backoff_ms }
-            struct EnvScope {
-                prev: Vec<(String, Option<String>)>,
-            }
-            impl EnvScope {
-                fn new() -> Self {
-                    Self { prev: Vec::new() }
-                }
-                fn set(&mut self, k: &str, v: &str) {
-                    let p = std::env::var(k).ok();
-                    self.prev.push((k.to_string(), p));
-                    _set_env_var(k, v);
-                }
-            }
-            impl Drop for EnvScope {
-                fn drop(&mut self) {
-                    for (k, v) in self.prev.drain(..) {
-                        if let Some(val) = v {
-                            _set_env_var(&k, &val);
-                        } else {
-                            _remove_env_var(&k);
-                        }
-                    }
-                }
-            }
-            let mut envs = EnvScope::new();
-            if let Some(args) = &job.args {
-                if let Some(v) = args.get("market").and_then(|v| v.as_str()) {
-                    envs.set("XBOX_MARKET", v);
-                }
-                if let Some(v) = args.get("language").and_then(|v| v.as_str()) {
-                    envs.set("XBOX_LANGUAGE", v);
-                }
-                if let Some(v) = args.get("product_ids_file").and_then(|v| v.as_str()) {
-                    envs.set("XBOX_PRODUCT_IDS_FILE", v);
-                }
-                if let Some(arr) = args.get("product_ids").and_then(|v| v.as_array()) {
-                    let joined = arr
-                        .iter()
-                        .filter_map(|x| x.as_str().map(|s| s.to_string()))
-                        .collect::<Vec<_>>()
-                        .join(",");
-                    if !joined.is_empty() {
-                        envs.set("XBOX_PRODUCT_IDS", &joined);
-                    }
-                }
-                if let Some(v) = args.get("ms_cv").and_then(|v| v.as_str()) {
-                    envs.set("XBOX_MS_CV", v);
-                }
-                if let Some(v) = args.get("dry_run").and_then(|v| v.as_bool()) {
-                    if v {
-                        envs.set("XBOX_DRY_RUN", "1");
-                    }
-                }
-                if let Some(v) = args.get("chunk_sleep_ms").and_then(|v| v.as_u64()) {
-                    envs.set("XBOX_CHUNK_SLEEP_MS", &v.to_string());
-                }
-                if let Some(v) = args.get("chunk_size").and_then(|v| v.as_u64()) {
-                    envs.set("XBOX_CHUNK_SIZE", &v.to_string());
-                }
-                if let Some(v) = args.get("reqs_per_min").and_then(|v| v.as_u64()) {
-                    envs.set("XBOX_REQS_PER_MIN", &v.to_string());
-                }
-                if let Some(v) = args.get("rps").and_then(|v| v.as_f64()) {
-                    envs.set("XBOX_RPS", &format!("{}", v));
-                }
-                if let Some(v) = args.get("max_retries").and_then(|v| v.as_u64()) {
-                    envs.set("XBOX_MAX_RETRIES", &v.to_string());
-                }
-                if let Some(v) = args.get("backoff_ms").and_then(|v| v.as_u64()) {
-                    envs.set("XBOX_BACKOFF_MS", &v.to_string());
-                }
-            }
-            i_miss_rust::database_ops::xbox::provider::run_from_env(db).await?;
+            // Args (all optional): { market, language, product_ids:[...], product_ids_file, ms_cv, dry_run:bool, chunk_sleep_ms, chunk_size, reqs_per_min, rps, max_retries, backoff_ms }
+            use i_miss_rust::database_ops::xbox::provider::{run_with_options, XboxProvider};
+            let args: XboxJobArgs = job_args(job, "xbox")?;
+            let prov = XboxProvider::new()?.with_policy(args.request_policy());
+            run_with_options(db, prov, args.options()).await?;
             Ok(())
         }
         ("xbox", "backfill") | ("microsoft", "backfill") => {
@@ -566,200 +834,18 @@ async fn handle_job(db: &Db, job: &IngestJob) -> Result<()> {
             i_miss_rust::database_ops::steam::provider::SteamProvider::run_from_env(db).await?;
             Ok(())
         }
-        ("ps", "backfill") | ("playstation", "backfill") | ("psstore", "backfill") => {
-            // Parameterized PS Store backfill using the existing prices pipeline.
-            // Supported args (all optional):
+        ("ps", "backfill")
+        | ("playstation", "backfill")
+        | ("psstore", "backfill")
+        | ("ps", "prices")
+        | ("playstation", "prices")
+        | ("psstore", "prices") => {
+            // Parameterized PS Store run of the prices pipeline. Args (all optional):
             // { locales: ["en-us","en-gb"], regions: ["en-us"], region: "us", pages: 5, page_size: 100,
-            //   page_concurrency: 2, rps: 3, max_retries: 5, backoff_ms: 1500, max_offset: 5000,
-            //   sha: "...", cat_ps4: "...", cat_ps5: "..." }
-            struct EnvScope {
-                prev: Vec<(String, Option<String>)>,
-            }
-            impl EnvScope {
-                fn new() -> Self {
-                    Self { prev: Vec::new() }
-                }
-                fn set(&mut self, k: &str, v: &str) {
-                    let p = std::env::var(k).ok();
-                    self.prev.push((k.to_string(), p));
-                    _set_env_var(k, v);
-                }
-            }
-            impl Drop for EnvScope {
-                fn drop(&mut self) {
-                    for (k, v) in self.prev.drain(..) {
-                        if let Some(val) = v {
-                            _set_env_var(&k, &val);
-                        } else {
-                            _remove_env_var(&k);
-                        }
-                    }
-                }
-            }
-            let mut envs = EnvScope::new();
-            if let Some(args) = &job.args {
-                // locales/regions handling
-                if let Some(locales) = args.get("locales").and_then(|v| v.as_array()) {
-                    let vals: Vec<String> = locales
-                        .iter()
-                        .filter_map(|x| x.as_str().map(|s| s.to_lowercase()))
-                        .collect();
-                    if !vals.is_empty() {
-                        envs.set("PS_STORE_REGIONS", &vals.join(","));
-                    }
-                } else if let Some(regions) = args.get("regions").and_then(|v| v.as_array()) {
-                    let vals: Vec<String> = regions
-                        .iter()
-                        .filter_map(|x| x.as_str().map(|s| s.to_lowercase()))
-                        .collect();
-                    if !vals.is_empty() {
-                        envs.set("PS_STORE_REGIONS", &vals.join(","));
-                    }
-                } else if let Some(region) = args.get("region").and_then(|v| v.as_str()) {
-                    // Accept simple country like "us" and expand to en-us
-                    let r = region.trim().to_lowercase();
-                    let locale = if r.len() == 2 { format!("en-{}", r) } else { r };
-                    envs.set("PS_STORE_REGIONS", &locale);
-                }
-                if let Some(pages) = args.get("pages").and_then(|v| v.as_u64()) {
-                    envs.set("PS_MAX_PAGES", &pages.to_string());
-                }
-                if let Some(psize) = args.get("page_size").and_then(|v| v.as_u64()) {
-                    envs.set("PS_PAGE_SIZE", &psize.to_string());
-                }
-                if let Some(pc) = args.get("page_concurrency").and_then(|v| v.as_u64()) {
-                    envs.set("PS_PAGE_CONCURRENCY", &pc.to_string());
-                }
-                if let Some(rps) = args.get("rps").and_then(|v| v.as_u64()) {
-                    envs.set("PS_STORE_RPS", &rps.to_string());
-                }
-                if let Some(retries) = args.get("max_retries").and_then(|v| v.as_u64()) {
-                    envs.set("PS_STORE_MAX_RETRIES", &retries.to_string());
-                }
-                if let Some(backoff) = args.get("backoff_ms").and_then(|v| v.as_u64()) {
-                    envs.set("PS_STORE_BACKOFF_MS", &backoff.to_string());
-                }
-                if let Some(max_off) = args.get("max_offset").and_then(|v| v.as_u64()) {
-                    envs.set("PS_MAX_OFFSET", &max_off.to_string());
-                }
-                if let Some(sha) = args.get("sha").and_then(|v| v.as_str()) {
-                    envs.set("PS_HASH", sha);
-                }
-                // If PS_HASH not provided via args/env, but PSSTORE_SHA256 is set globally,
-                // map it into PS_HASH for downstream code compatibility
-                if std::env::var("PS_HASH")
-                    .ok()
-                    .filter(|s| !s.is_empty())
-                    .is_none()
-                {
-                    if let Ok(sha2) = std::env::var("PSSTORE_SHA256") {
-                        if !sha2.is_empty() {
-                            envs.set("PS_HASH", &sha2);
-                        }
-                    }
-                }
-                if let Some(cat4) = args.get("cat_ps4").and_then(|v| v.as_str()) {
-                    envs.set("PS4_CATEGORY", cat4);
-                }
-                if let Some(cat5) = args.get("cat_ps5").and_then(|v| v.as_str()) {
-                    envs.set("PS5_CATEGORY", cat5);
-                }
-            }
-            i_miss_rust::database_ops::playstation::prices::run_from_env().await?;
-            Ok(())
-        }
-        ("ps", "prices") | ("playstation", "prices") | ("psstore", "prices") => {
-            // Optional args mapping (same keys as backfill)
-            struct EnvScope {
-                prev: Vec<(String, Option<String>)>,
-            }
-            impl EnvScope {
-                fn new() -> Self {
-                    Self { prev: Vec::new() }
-                }
-                fn set(&mut self, k: &str, v: &str) {
-                    let p = std::env::var(k).ok();
-                    self.prev.push((k.to_string(), p));
-                    _set_env_var(k, v);
-                }
-            }
-            impl Drop for EnvScope {
-                fn drop(&mut self) {
-                    for (k, v) in self.prev.drain(..) {
-                        if let Some(val) = v {
-                            _set_env_var(&k, &val);
-                        } else {
-                            _remove_env_var(&k);
-                        }
-                    }
-                }
-            }
-            let mut envs = EnvScope::new();
-            if let Some(args) = &job.args {
-                if let Some(locales) = args.get("locales").and_then(|v| v.as_array()) {
-                    let vals: Vec<String> = locales
-                        .iter()
-                        .filter_map(|x| x.as_str().map(|s| s.to_lowercase()))
-                        .collect();
-                    if !vals.is_empty() {
-                        envs.set("PS_STORE_REGIONS", &vals.join(","));
-                    }
-                } else if let Some(regions) = args.get("regions").and_then(|v| v.as_array()) {
-                    let vals: Vec<String> = regions
-                        .iter()
-                        .filter_map(|x| x.as_str().map(|s| s.to_lowercase()))
-                        .collect();
-                    if !vals.is_empty() {
-                        envs.set("PS_STORE_REGIONS", &vals.join(","));
-                    }
-                } else if let Some(region) = args.get("region").and_then(|v| v.as_str()) {
-                    let r = region.trim().to_lowercase();
-                    let locale = if r.len() == 2 { format!("en-{}", r) } else { r };
-                    envs.set("PS_STORE_REGIONS", &locale);
-                }
-                if let Some(pages) = args.get("pages").and_then(|v| v.as_u64()) {
-                    envs.set("PS_MAX_PAGES", &pages.to_string());
-                }
-                if let Some(psize) = args.get("page_size").and_then(|v| v.as_u64()) {
-                    envs.set("PS_PAGE_SIZE", &psize.to_string());
-                }
-                if let Some(pc) = args.get("page_concurrency").and_then(|v| v.as_u64()) {
-                    envs.set("PS_PAGE_CONCURRENCY", &pc.to_string());
-                }
-                if let Some(rps) = args.get("rps").and_then(|v| v.as_u64()) {
-                    envs.set("PS_STORE_RPS", &rps.to_string());
-                }
-                if let Some(retries) = args.get("max_retries").and_then(|v| v.as_u64()) {
-                    envs.set("PS_STORE_MAX_RETRIES", &retries.to_string());
-                }
-                if let Some(backoff) = args.get("backoff_ms").and_then(|v| v.as_u64()) {
-                    envs.set("PS_STORE_BACKOFF_MS", &backoff.to_string());
-                }
-                if let Some(max_off) = args.get("max_offset").and_then(|v| v.as_u64()) {
-                    envs.set("PS_MAX_OFFSET", &max_off.to_string());
-                }
-                if let Some(sha) = args.get("sha").and_then(|v| v.as_str()) {
-                    envs.set("PS_HASH", sha);
-                }
-                if std::env::var("PS_HASH")
-                    .ok()
-                    .filter(|s| !s.is_empty())
-                    .is_none()
-                {
-                    if let Ok(sha2) = std::env::var("PSSTORE_SHA256") {
-                        if !sha2.is_empty() {
-                            envs.set("PS_HASH", &sha2);
-                        }
-                    }
-                }
-                if let Some(cat4) = args.get("cat_ps4").and_then(|v| v.as_str()) {
-                    envs.set("PS4_CATEGORY", cat4);
-                }
-                if let Some(cat5) = args.get("cat_ps5").and_then(|v| v.as_str()) {
-                    envs.set("PS5_CATEGORY", cat5);
-                }
-            }
-            i_miss_rust::database_ops::playstation::prices::run_from_env().await?;
+            //   rps: 3, max_retries: 5, backoff_ms: 1500, sha: "...", cat_ps4: "...", cat_ps5: "..." }
+            let args: PsPricesJobArgs = job_args(job, "ps prices")?;
+            i_miss_rust::database_ops::playstation::prices::run_with_options(args.options())
+                .await?;
             Ok(())
         }
         ("ps", "ratings") | ("playstation", "ratings") | ("psstore", "ratings") => {
@@ -776,54 +862,19 @@ async fn handle_job(db: &Db, job: &IngestJob) -> Result<()> {
             Ok(())
         }
         ("igdb", "catalog") => {
-            struct EnvScope {
-                prev: Vec<(String, Option<String>)>,
-            }
-            impl EnvScope {
-                fn new() -> Self {
-                    Self { prev: Vec::new() }
-                }
-                fn set(&mut self, key: &str, val: &str) {
-                    let prev = std::env::var(key).ok();
-                    self.prev.push((key.to_string(), prev));
-                    _set_env_var(key, val);
-                }
-            }
-            impl Drop for EnvScope {
-                fn drop(&mut self) {
-                    for (key, val) in self.prev.drain(..) {
-                        if let Some(v) = val {
-                            _set_env_var(&key, &v);
-                        } else {
-                            _remove_env_var(&key);
-                        }
-                    }
-                }
-            }
-
-            let mut envs = EnvScope::new();
-            if let Some(args) = &job.args {
-                if let Some(v) = args.get("mode").and_then(|v| v.as_str()) {
-                    envs.set("IGDB_MODE", v);
-                }
-                if let Some(v) = args.get("page_size").and_then(|v| v.as_u64()) {
-                    envs.set("IGDB_PAGE_SIZE", &v.to_string());
-                }
-                if let Some(v) = args.get("max_pages").and_then(|v| v.as_u64()) {
-                    envs.set("IGDB_MAX_PAGES", &v.to_string());
-                }
-                if let Some(v) = args.get("reqs_per_min").and_then(|v| v.as_u64()) {
-                    envs.set("IGDB_REQS_PER_MIN", &v.to_string());
-                }
-                if let Some(v) = args.get("rps").and_then(|v| v.as_f64()) {
-                    envs.set("IGDB_RPS", &format!("{}", v));
-                }
-                if let Some(v) = args.get("concurrency").and_then(|v| v.as_u64()) {
-                    envs.set("IGDB_CONCURRENCY", &v.to_string());
-                }
-            }
-
-            i_miss_rust::database_ops::igdb::client::run_from_env(db).await?;
+            // Args (all optional): { mode, page_size, max_pages, reqs_per_min, rps, concurrency }
+            let args = IgdbJobArgs::from_job(job)?;
+            let mode = args
+                .mode
+                .clone()
+                .or_else(|| env_util::env_opt("IGDB_MODE"))
+                .unwrap_or_else(|| "backfill".to_string());
+            i_miss_rust::database_ops::igdb::client::run_with_config(
+                db,
+                args.service_config(),
+                &mode,
+            )
+            .await?;
             Ok(())
         }
         ("xbox", "catalog") => {
@@ -836,49 +887,8 @@ async fn handle_job(db: &Db, job: &IngestJob) -> Result<()> {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(30);
-            // Allow per-job rate-limit overrides, mapped into env
-            struct EnvScope {
-                prev: Vec<(String, Option<String>)>,
-            }
-            impl EnvScope {
-                fn new() -> Self {
-                    Self { prev: Vec::new() }
-                }
-                fn set(&mut self, k: &str, v: &str) {
-                    let p = std::env::var(k).ok();
-                    self.prev.push((k.to_string(), p));
-                    _set_env_var(k, v);
-                }
-            }
-            impl Drop for EnvScope {
-                fn drop(&mut self) {
-                    for (k, v) in self.prev.drain(..) {
-                        if let Some(val) = v {
-                            _set_env_var(&k, &val);
-                        } else {
-                            _remove_env_var(&k);
-                        }
-                    }
-                }
-            }
-            let mut envs = EnvScope::new();
-            if let Some(args) = &job.args {
-                if let Some(v) = args.get("reqs_per_min").and_then(|v| v.as_u64()) {
-                    envs.set("NEXARDA_REQS_PER_MIN", &v.to_string());
-                }
-                if let Some(v) = args.get("rps").and_then(|v| v.as_f64()) {
-                    envs.set("NEXARDA_RPS", &format!("{}", v));
-                }
-                if let Some(v) = args.get("concurrency").and_then(|v| v.as_u64()) {
-                    envs.set("NEXARDA_CONCURRENCY", &v.to_string());
-                }
-                if let Some(v) = args.get("max_retries").and_then(|v| v.as_u64()) {
-                    envs.set("NEXARDA_MAX_RETRIES", &v.to_string());
-                }
-                if let Some(v) = args.get("backoff_ms").and_then(|v| v.as_u64()) {
-                    envs.set("NEXARDA_BACKOFF_MS", &v.to_string());
-                }
-            }
+            // Per-job request policy. Args (all optional): { reqs_per_min, rps, max_retries, backoff_ms }
+            let args: NexardaJobArgs = job_args(job, "nexarda")?;
             let nx = NexardaProvider::new(base_url_opt.as_deref(), Some(timeout_secs))
                 .context("nexarda init")?;
             let opts = NexardaOptions {
@@ -900,6 +910,10 @@ async fn handle_job(db: &Db, job: &IngestJob) -> Result<()> {
                 context: None,
                 base_url: None,
                 timeout: None,
+                max_retries: args.max_retries,
+                backoff_ms: args.backoff_ms,
+                reqs_per_min: args.reqs_per_min,
+                rps: args.rps,
             };
             nx.ingest_to_db(db, opts).await?;
             Ok(())
@@ -958,142 +972,27 @@ async fn handle_job(db: &Db, job: &IngestJob) -> Result<()> {
         | ("thegamesdb", "all") => {
             // TGDB mirror ingestion (catalogue + media links when schema supports them).
             // Args (all optional): { api_key, year_min, year_max, page_size, reqs_per_min }
-            struct EnvScope {
-                prev: Vec<(String, Option<String>)>,
-            }
-            impl EnvScope {
-                fn new() -> Self {
-                    Self { prev: Vec::new() }
-                }
-                fn set(&mut self, k: &str, v: &str) {
-                    let p = std::env::var(k).ok();
-                    self.prev.push((k.to_string(), p));
-                    _set_env_var(k, v);
-                }
-            }
-            impl Drop for EnvScope {
-                fn drop(&mut self) {
-                    for (k, v) in self.prev.drain(..) {
-                        if let Some(val) = v {
-                            _set_env_var(&k, &val);
-                        } else {
-                            _remove_env_var(&k);
-                        }
-                    }
-                }
-            }
-
-            let mut api_key = std::env::var("TGDB_API_KEY").ok().filter(|s| !s.is_empty());
-            if let Some(args) = &job.args {
-                let mut envs = EnvScope::new();
-
-                if let Some(v) = args.get("year_min").and_then(|v| v.as_i64()) {
-                    envs.set("TGDB_YEAR_MIN", &v.to_string());
-                }
-                if let Some(v) = args.get("year_max").and_then(|v| v.as_i64()) {
-                    envs.set("TGDB_YEAR_MAX", &v.to_string());
-                }
-                if let Some(v) = args.get("page_size").and_then(|v| v.as_u64()) {
-                    envs.set("TGDB_PAGE_SIZE", &v.to_string());
-                }
-                if let Some(v) = args.get("reqs_per_min").and_then(|v| v.as_u64()) {
-                    envs.set("TGDB_REQS_PER_MIN", &v.to_string());
-                }
-                if let Some(k) = args.get("api_key").and_then(|v| v.as_str()) {
-                    api_key = Some(k.to_string());
-                }
-
-                // Keep env overrides alive for the duration of the async call.
-                // (Drop restores previous values.)
-                let allow_anon = std::env::var("TGDB_ALLOW_ANON")
-                    .ok()
-                    .map(|v| !(v == "0" || v.eq_ignore_ascii_case("false")))
-                    .unwrap_or(false);
-
-                if api_key.is_none() && !allow_anon {
-                    println!(
-                        "[ingest_worker] tgdb: skipped (TGDB_API_KEY missing; set TGDB_ALLOW_ANON=1 to attempt anonymous calls)"
-                    );
-                    return Ok(());
-                }
-
-                i_miss_rust::database_ops::tgdb::sync(db, api_key).await?;
-                return Ok(());
-            }
-
+            let args: TgdbJobArgs = job_args(job, "tgdb")?;
+            let opts = args.options();
             let allow_anon = std::env::var("TGDB_ALLOW_ANON")
                 .ok()
                 .map(|v| !(v == "0" || v.eq_ignore_ascii_case("false")))
                 .unwrap_or(false);
-            if api_key.is_none() && !allow_anon {
+            if opts.api_key.is_none() && !allow_anon {
                 println!(
                     "[ingest_worker] tgdb: skipped (TGDB_API_KEY missing; set TGDB_ALLOW_ANON=1 to attempt anonymous calls)"
                 );
                 return Ok(());
             }
-            i_miss_rust::database_ops::tgdb::sync(db, api_key).await?;
+            i_miss_rust::database_ops::tgdb::sync_with_options(db, &opts).await?;
             Ok(())
         }
 
         ("itad", "prices_scan") | ("itad", "sync") | ("itad", "all") => {
             // ITAD pricing sync (bounded). Args (all optional):
             // { api_key, base_url, timeout_secs, country, deals_limit, max_game_overviews, default_currency, country_name }
-            struct EnvScope {
-                prev: Vec<(String, Option<String>)>,
-            }
-            impl EnvScope {
-                fn new() -> Self {
-                    Self { prev: Vec::new() }
-                }
-                fn set(&mut self, k: &str, v: &str) {
-                    let p = std::env::var(k).ok();
-                    self.prev.push((k.to_string(), p));
-                    _set_env_var(k, v);
-                }
-            }
-            impl Drop for EnvScope {
-                fn drop(&mut self) {
-                    for (k, v) in self.prev.drain(..) {
-                        if let Some(val) = v {
-                            _set_env_var(&k, &val);
-                        } else {
-                            _remove_env_var(&k);
-                        }
-                    }
-                }
-            }
-
-            let mut api_key = std::env::var("ITAD_API_KEY").ok().filter(|s| !s.is_empty());
-            let mut envs = EnvScope::new();
-
-            if let Some(args) = &job.args {
-                if let Some(k) = args.get("api_key").and_then(|v| v.as_str()) {
-                    api_key = Some(k.to_string());
-                }
-                if let Some(v) = args.get("base_url").and_then(|v| v.as_str()) {
-                    envs.set("ITAD_BASE_URL", v);
-                }
-                if let Some(v) = args.get("timeout_secs").and_then(|v| v.as_u64()) {
-                    envs.set("ITAD_TIMEOUT_SECS", &v.to_string());
-                }
-                if let Some(v) = args.get("country").and_then(|v| v.as_str()) {
-                    envs.set("ITAD_COUNTRY", v);
-                }
-                if let Some(v) = args.get("deals_limit").and_then(|v| v.as_u64()) {
-                    envs.set("ITAD_DEALS_LIMIT", &v.to_string());
-                }
-                if let Some(v) = args.get("max_game_overviews").and_then(|v| v.as_u64()) {
-                    envs.set("ITAD_MAX_GAME_OVERVIEWS", &v.to_string());
-                }
-                if let Some(v) = args.get("default_currency").and_then(|v| v.as_str()) {
-                    envs.set("ITAD_DEFAULT_CURRENCY", v);
-                }
-                if let Some(v) = args.get("country_name").and_then(|v| v.as_str()) {
-                    envs.set("ITAD_COUNTRY_NAME", v);
-                }
-            }
-
-            let _ = i_miss_rust::database_ops::itad::sync(db, api_key).await?;
+            let args: ItadJobArgs = job_args(job, "itad")?;
+            let _ = i_miss_rust::database_ops::itad::sync_with_options(db, args.options()).await?;
             Ok(())
         }
         ("rawg", "catalog")
@@ -1101,78 +1000,9 @@ async fn handle_job(db: &Db, job: &IngestJob) -> Result<()> {
         | ("rawg", "sync")
         | ("rawg", "all")
         | ("rawg", "range") => {
-            struct EnvScope {
-                prev: Vec<(String, Option<String>)>,
-            }
-            impl EnvScope {
-                fn new() -> Self {
-                    Self { prev: Vec::new() }
-                }
-                fn set(&mut self, key: &str, val: &str) {
-                    let prev = std::env::var(key).ok();
-                    self.prev.push((key.to_string(), prev));
-                    _set_env_var(key, val);
-                }
-            }
-            impl Drop for EnvScope {
-                fn drop(&mut self) {
-                    for (key, val) in self.prev.drain(..) {
-                        if let Some(v) = val {
-                            _set_env_var(&key, &v);
-                        } else {
-                            _remove_env_var(&key);
-                        }
-                    }
-                }
-            }
-
-            let mut year_min = std::env::var("RAWG_YEAR_MIN")
-                .ok()
-                .and_then(|s| s.parse::<i32>().ok())
-                .unwrap_or(2015);
-            let mut year_max = std::env::var("RAWG_YEAR_MAX")
-                .ok()
-                .and_then(|s| s.parse::<i32>().ok())
-                .unwrap_or(2025);
-            let mut api_key = std::env::var("RAWG_API_KEY").ok().filter(|s| !s.is_empty());
-            let mut scope = EnvScope::new();
-
-            if let Some(args) = &job.args {
-                if let Some(v) = args.get("year_min").and_then(|v| v.as_i64()) {
-                    year_min = v as i32;
-                }
-                if let Some(v) = args.get("year_max").and_then(|v| v.as_i64()) {
-                    year_max = v as i32;
-                }
-                if let Some(v) = args.get("mode").and_then(|v| v.as_str()) {
-                    scope.set("RAWG_MODE", v);
-                }
-                if let Some(k) = args.get("api_key").and_then(|v| v.as_str()) {
-                    api_key = Some(k.to_string());
-                }
-                if let Some(v) = args.get("page_size").and_then(|v| v.as_u64()) {
-                    scope.set("RAWG_PAGE_SIZE", &v.to_string());
-                }
-                if let Some(v) = args.get("reqs_per_min").and_then(|v| v.as_u64()) {
-                    scope.set("RAWG_REQS_PER_MIN", &v.to_string());
-                }
-                if let Some(v) = args.get("fetch_details").and_then(|v| v.as_bool()) {
-                    scope.set("RAWG_FETCH_DETAILS", if v { "1" } else { "0" });
-                }
-                if let Some(v) = args.get("sleep_ms").and_then(|v| v.as_u64()) {
-                    scope.set("RAWG_SLEEP_MS_OVERRIDE", &v.to_string());
-                }
-            }
-
-            if year_min > year_max {
-                std::mem::swap(&mut year_min, &mut year_max);
-            }
-
-            // rawg::sync uses env for range bounds, so set them here for consistent behavior.
-            scope.set("RAWG_YEAR_MIN", &year_min.to_string());
-            scope.set("RAWG_YEAR_MAX", &year_max.to_string());
-
-            i_miss_rust::database_ops::rawg::sync(db, api_key).await?;
+            // Args (all optional): { mode, api_key, year_min, year_max, page_size, reqs_per_min, fetch_details, sleep_ms }
+            let args: RawgJobArgs = job_args(job, "rawg")?;
+            i_miss_rust::database_ops::rawg::sync_with_options(db, &args.options()).await?;
             Ok(())
         }
         ("giantbomb", "json") | ("giant_bomb", "json") | ("gb", "json") | ("giantbomb", "load") => {
@@ -1227,6 +1057,7 @@ async fn handle_job(db: &Db, job: &IngestJob) -> Result<()> {
             );
             Ok(())
         }
+        (_, "refresh") => run_refresh_job(db, job).await,
         _ => Err(anyhow!(
             "unknown provider/task: {}/{}",
             job.provider,
@@ -1271,13 +1102,27 @@ async fn ensure_queue(db: &Db, cfg: &QueueConfig) -> Result<()> {
 }
 
 async fn enqueue_job(db: &Db, cfg: &QueueConfig, job: &IngestJob) -> Result<i64> {
+    let msg_id = send_job(&db.pool, cfg, job).await?;
+    notify_enqueued(db, cfg).await;
+    Ok(msg_id)
+}
+
+/// `pgmq.send` on any executor, so callers can send inside their own transaction.
+async fn send_job<'e>(
+    exec: impl sqlx::PgExecutor<'e>,
+    cfg: &QueueConfig,
+    job: &IngestJob,
+) -> Result<i64> {
     let payload = serde_json::to_value(job)?;
     let row = sqlx::query("SELECT pgmq.send($1, $2) AS msg_id")
         .bind(&cfg.queue_name)
         .bind(sqlx::types::Json(payload))
-        .fetch_one(&db.pool)
+        .fetch_one(exec)
         .await?;
-    let msg_id: i64 = row.try_get("msg_id").unwrap_or_default();
+    Ok(row.try_get("msg_id").unwrap_or_default())
+}
+
+async fn notify_enqueued(db: &Db, cfg: &QueueConfig) {
     // Notify all configured channels
     for ch in &cfg.notify_channels {
         let _ = sqlx::query("SELECT pg_notify($1, $2)")
@@ -1286,52 +1131,132 @@ async fn enqueue_job(db: &Db, cfg: &QueueConfig, job: &IngestJob) -> Result<i64>
             .execute(&db.pool)
             .await;
     }
-    Ok(msg_id)
 }
 
-async fn pop_job(db: &Db, cfg: &QueueConfig) -> Result<Option<PoppedJob>> {
+/// Enqueue one `refresh` job per provider for its stalest items; returns the job count.
+async fn enqueue_stale_refreshes(db: &Db, cfg: &QueueConfig) -> Result<usize> {
+    use i_miss_rust::database_ops::staleness::{
+        refresh_batches, stalest_provider_items, StalenessOptions,
+    };
+
+    let items = stalest_provider_items(db, &StalenessOptions::from_env()).await?;
+    let batches = refresh_batches(&items);
+    for batch in &batches {
+        let job = IngestJob::new(
+            &batch.provider_slug,
+            "refresh",
+            Some(json!({
+                "external_ids": batch.external_ids,
+                "provider_item_ids": batch.provider_item_ids,
+            })),
+        );
+        enqueue_job(db, cfg, &job).await?;
+    }
+    Ok(batches.len())
+}
+
+/// Re-ingest only the items listed in `args.external_ids` (see `enqueue_stale_refreshes`).
+async fn run_refresh_job(db: &Db, job: &IngestJob) -> Result<()> {
+    use i_miss_rust::database_ops::nexarda::provider::product_from_deal_id;
+
+    let external_ids: Vec<String> = job
+        .args
+        .as_ref()
+        .and_then(|a| a.get("external_ids"))
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default();
+    if external_ids.is_empty() {
+        return Ok(());
+    }
+    match job.provider.as_str() {
+        "ps-store" | "ps" | "playstation" | "psstore" => {
+            use i_miss_rust::database_ops::playstation::filter::ProductFilter;
+            let ids: Vec<&str> = external_ids.iter().map(String::as_str).collect();
+            let filter = ProductFilter::from_env().include_only(&ids);
+            i_miss_rust::psstore_seed_pipeline_with_filter(db, filter)
+                .await
+                .map(|_| ())
+        }
+        "nexarda" => {
+            let mut seen = std::collections::HashSet::new();
+            let products: Vec<_> = external_ids
+                .iter()
+                .filter_map(|id| product_from_deal_id(id))
+                .filter(|p| seen.insert((p.r#type.clone(), p.id.to_string())))
+                .collect();
+            let base_url_opt = env::var("NEXARDA_BASE_URL").ok().filter(|s| !s.is_empty());
+            let nx = NexardaProvider::new(base_url_opt.as_deref(), None).context("nexarda init")?;
+            let opts = NexardaOptions {
+                products,
+                store_map: serde_json::from_str(&env::var("NEXARDA_STORE_MAP").unwrap_or_default())
+                    .unwrap_or_default(),
+                api_key: env::var("NEXARDA_API_KEY").ok().filter(|s| !s.is_empty()),
+                auto_register_stores: Some(true),
+                default_regions: serde_json::from_str(
+                    &env::var("NEXARDA_DEFAULT_REGIONS").unwrap_or_default(),
+                )
+                .unwrap_or_default(),
+                dynamic_store_overrides: serde_json::from_str(
+                    &env::var("NEXARDA_STORE_OVERRIDES").unwrap_or_default(),
+                )
+                .unwrap_or_default(),
+                default_tax_inclusive: Some(true),
+                ..Default::default()
+            };
+            nx.ingest_to_db(db, opts).await?;
+            Ok(())
+        }
+        other => Err(anyhow!("no targeted refresh for provider {other}")),
+    }
+}
+
+/// Read up to `batch` messages, each hidden for the visibility timeout. Undecodable
+/// payloads are archived and left out.
+async fn pop_jobs(db: &Db, cfg: &QueueConfig, batch: usize) -> Result<Vec<PoppedJob>> {
+    let batch = batch.max(1) as i32;
     // Prefer 4-arg read() when available; fallback to 3-arg for older pgmq
-    let row4 =
-        sqlx::query("SELECT msg_id, read_ct, message FROM pgmq.read($1, $2, 1, NULL::jsonb)")
+    let rows4 =
+        sqlx::query("SELECT msg_id, read_ct, message FROM pgmq.read($1, $2, $3, NULL::jsonb)")
             .bind(&cfg.queue_name)
             .bind(cfg.visibility_timeout_secs)
-            .fetch_optional(&db.pool)
+            .bind(batch)
+            .fetch_all(&db.pool)
             .await;
 
-    let row_opt = match row4 {
-        Ok(opt) => opt,
+    let rows = match rows4 {
+        Ok(rows) => rows,
         Err(_) => {
             // Try 3-arg signature
-            sqlx::query("SELECT msg_id, read_ct, message FROM pgmq.read($1, $2, 1)")
+            sqlx::query("SELECT msg_id, read_ct, message FROM pgmq.read($1, $2, $3)")
                 .bind(&cfg.queue_name)
                 .bind(cfg.visibility_timeout_secs)
-                .fetch_optional(&db.pool)
+                .bind(batch)
+                .fetch_all(&db.pool)
                 .await?
         }
     };
 
-    if let Some(row) = row_opt {
+    let mut popped = Vec::with_capacity(rows.len());
+    for row in rows {
         let msg_id: i64 = row.try_get("msg_id")?;
         let read_ct: i32 = row.try_get("read_ct")?;
         let message: serde_json::Value = row.try_get("message")?;
         match serde_json::from_value::<IngestJob>(message) {
-            Ok(job) => Ok(Some(PoppedJob {
+            Ok(job) => popped.push(PoppedJob {
                 msg_id,
                 read_ct,
                 job,
-            })),
+            }),
             Err(e) => {
                 eprintln!(
                     "[ingest_worker] bad payload msg_id={} err={e:?}; archiving",
                     msg_id
                 );
                 archive_job(db, cfg, msg_id).await?;
-                Ok(None)
             }
         }
-    } else {
-        Ok(None)
     }
+    Ok(popped)
 }
 
 async fn delete_job(db: &Db, cfg: &QueueConfig, msg_id: i64) -> Result<()> {
@@ -1350,6 +1275,163 @@ async fn archive_job(db: &Db, cfg: &QueueConfig, msg_id: i64) -> Result<()> {
         .await?;
     Ok(())
 }
+/// A job `archive_job` moved to `pgmq.a_<queue>` (the dead-letter queue).
+#[derive(Debug, Clone, Serialize)]
+struct ArchivedJob {
+    msg_id: i64,
+    /// Reads before archiving, i.e. failed attempts.
+    read_ct: i32,
+    enqueued_at: chrono::DateTime<Utc>,
+    archived_at: chrono::DateTime<Utc>,
+    message: serde_json::Value,
+}
+
+/// Which archived jobs `POST /api/dlq/redrive` re-sends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RedriveTarget {
+    All,
+    Ids(Vec<i64>),
+}
+
+impl RedriveTarget {
+    /// `"all"` or an array of msg_ids.
+    fn from_json(v: &serde_json::Value) -> Option<Self> {
+        match v {
+            serde_json::Value::String(s) if s.eq_ignore_ascii_case("all") => Some(Self::All),
+            serde_json::Value::Array(ids) => ids
+                .iter()
+                .map(serde_json::Value::as_i64)
+                .collect::<Option<Vec<_>>>()
+                .map(Self::Ids),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+struct RedriveOutcome {
+    /// `(archived msg_id, new msg_id)` per job back on the live queue.
+    redriven: Vec<(i64, i64)>,
+    /// `(archived msg_id, reason)` per job left in the archive.
+    skipped: Vec<(i64, String)>,
+}
+
+/// Most recently archived jobs first.
+async fn list_archived_jobs(db: &Db, cfg: &QueueConfig, limit: i64) -> Result<Vec<ArchivedJob>> {
+    let sql = format!(
+        "SELECT msg_id, read_ct, enqueued_at, archived_at, message
+         FROM pgmq.a_{}
+         ORDER BY archived_at DESC, msg_id DESC
+         LIMIT $1",
+        cfg.queue_name
+    );
+    let rows = sqlx::query(&sql)
+        .persistent(false)
+        .bind(limit)
+        .fetch_all(&db.pool)
+        .await?;
+    rows.into_iter()
+        .map(|r| {
+            Ok(ArchivedJob {
+                msg_id: r.try_get("msg_id")?,
+                read_ct: r.try_get("read_ct")?,
+                enqueued_at: r.try_get("enqueued_at")?,
+                archived_at: r.try_get("archived_at")?,
+                message: r.try_get("message")?,
+            })
+        })
+        .collect()
+}
+
+/// Most archived jobs a single `GET /api/dlq` or `POST /api/dlq/redrive` touches.
+const DLQ_MAX_BATCH: i64 = 1000;
+
+/// Re-send up to [`DLQ_MAX_BATCH`] archived jobs (oldest first) onto the live queue. Each job
+/// is deleted from the archive and sent in one transaction, so a failure part-way never
+/// leaves a job both queued and archived; a job another redrive already took is skipped.
+/// The original `correlation_id` is kept and `args.redriven_at` records the re-drive;
+/// payloads that are not a valid job stay archived.
+async fn redrive_archived_jobs(
+    db: &Db,
+    cfg: &QueueConfig,
+    target: &RedriveTarget,
+) -> Result<RedriveOutcome> {
+    let archive = format!("pgmq.a_{}", cfg.queue_name);
+    let select = format!(
+        "SELECT msg_id FROM {archive}
+         WHERE $1::bigint[] IS NULL OR msg_id = ANY($1)
+         ORDER BY msg_id
+         LIMIT $2"
+    );
+    let delete = format!("DELETE FROM {archive} WHERE msg_id = $1 RETURNING message");
+    let ids = match target {
+        RedriveTarget::All => None,
+        RedriveTarget::Ids(ids) => Some(ids.clone()),
+    };
+    let msg_ids: Vec<i64> = sqlx::query_scalar(&select)
+        .persistent(false)
+        .bind(ids)
+        .bind(DLQ_MAX_BATCH)
+        .fetch_all(&db.pool)
+        .await?;
+
+    let mut outcome = RedriveOutcome::default();
+    for msg_id in msg_ids {
+        let mut tx = db.pool.begin().await?;
+        let message: Option<serde_json::Value> = sqlx::query_scalar(&delete)
+            .persistent(false)
+            .bind(msg_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let Some(message) = message else {
+            outcome
+                .skipped
+                .push((msg_id, "no longer archived".to_string()));
+            continue;
+        };
+        // Dropping `tx` on a skip rolls the delete back and leaves the job archived.
+        let mut job = match serde_json::from_value::<IngestJob>(message) {
+            Ok(job) => job,
+            Err(e) => {
+                outcome.skipped.push((msg_id, format!("bad payload: {e}")));
+                continue;
+            }
+        };
+        let redriven_at = json!(Utc::now().to_rfc3339());
+        match job.args.get_or_insert_with(|| json!({})) {
+            serde_json::Value::Object(args) => {
+                args.insert("redriven_at".to_string(), redriven_at);
+            }
+            _ => {
+                outcome
+                    .skipped
+                    .push((msg_id, "args is not an object".to_string()));
+                continue;
+            }
+        }
+        let new_id = send_job(&mut *tx, cfg, &job).await?;
+        tx.commit().await?;
+        outcome.redriven.push((msg_id, new_id));
+    }
+    if !outcome.redriven.is_empty() {
+        notify_enqueued(db, cfg).await;
+    }
+    Ok(outcome)
+}
+
+/// Sample the queue's depth and oldest message age into `metrics`.
+async fn refresh_queue_stats(
+    db: &Db,
+    cfg: &QueueConfig,
+    metrics: &Mutex<WorkerMetrics>,
+) -> Result<()> {
+    let (depth, oldest) = queue_stats(db, &cfg.queue_name).await?;
+    let mut m = metrics.lock().unwrap();
+    m.queue_depth = depth;
+    m.oldest_msg_age_secs = oldest;
+    Ok(())
+}
+
 async fn set_job_vt(db: &Db, cfg: &QueueConfig, msg_id: i64, vt_secs: i32) -> Result<()> {
     // pgmq.set_vt signature in this environment: (queue_name text, msg_id bigint, vt integer)
     sqlx::query("SELECT pgmq.set_vt($1, $2, $3)")
@@ -1417,6 +1499,12 @@ async fn connect_listen_channel(
     Ok(rx)
 }
 
+#[derive(Deserialize)]
+struct RedriveReq {
+    /// `"all"` or an array of archived msg_ids.
+    msg_ids: serde_json::Value,
+}
+
 #[derive(Deserialize)]
 struct EnqueueReq {
     provider: String,
@@ -1432,55 +1520,73 @@ fn start_http_server(
     cfg: QueueConfig,
     metrics: Arc<Mutex<WorkerMetrics>>,
     manager: Manager,
+    shutdown_rx: broadcast::Receiver<()>,
     addr: String,
-) {
+) -> Result<tokio::task::JoinHandle<()>> {
     use actix_web::{web, App, HttpResponse, HttpServer, Responder};
-    tokio::spawn(async move {
-        let db = web::Data::new(db);
-        let cfg = web::Data::new(cfg);
-        let metrics = web::Data::new(metrics);
-        let manager_data = web::Data::new(manager);
-        let bind_addr = addr.clone();
-        let server = HttpServer::new(move || {
-            App::new()
-                .app_data(db.clone())
-                .app_data(cfg.clone())
-                .app_data(metrics.clone())
-                .app_data(manager_data.clone())
-                .route(
-                    "/",
-                    web::get().to(|| async { HttpResponse::Ok().body("ok") }),
-                )
-                .route("/api/enqueue", web::post().to(enqueue))
-                .route("/api/info", web::get().to(get_info))
-                .route("/api/metrics", web::get().to(get_metrics))
-                .route("/api/logs", web::get().to(get_logs))
-                .route("/api/pause", web::post().to(pause))
-                .route("/api/resume", web::post().to(resume))
-                .route("/api/status", web::get().to(get_status))
-                // debug helpers (safe to keep; read-only)
-                .route("/api/pgmq_metrics", web::get().to(get_pgmq_metrics))
-                .route("/api/pgmq_counts", web::get().to(get_pgmq_counts))
-                .route("/api/pgmq_peek", web::get().to(get_pgmq_peek))
-                .route("/api/pgmq_read_once", web::post().to(post_pgmq_read_once))
-        })
-        .bind(bind_addr.clone())
-        .expect("failed to bind http server")
-        .run();
-
-        println!("[ingest_worker] http listening on {bind_addr}");
-        if let Err(e) = server.await {
+    // Bind before spawning so a bad/occupied WORKER_HTTP_ADDR fails startup loudly.
+    let listener = i_miss_rust::api::server::bind_listener(&addr)
+        .context("WORKER_HTTP_ADDR: cannot start HTTP API")?;
+    let db = web::Data::new(db);
+    let cfg = web::Data::new(cfg);
+    let metrics = web::Data::new(metrics);
+    let manager_data = web::Data::new(manager);
+    let api_token = i_miss_rust::api::auth::api_token_from_env();
+    let rate_limit = i_miss_rust::api::rate_limit::RateLimit::from_env();
+    let server = HttpServer::new(move || {
+        App::new()
+            .wrap(rate_limit.clone())
+            .wrap(i_miss_rust::api::auth::write_auth(api_token.as_deref()))
+            .wrap(i_miss_rust::api::middleware::cors_from_env())
+            .app_data(db.clone())
+            .app_data(cfg.clone())
+            .app_data(metrics.clone())
+            .app_data(manager_data.clone())
+            .route(
+                "/",
+                web::get().to(|| async { HttpResponse::Ok().body("ok") }),
+            )
+            .route("/api/enqueue", web::post().to(enqueue))
+            .route("/api/info", web::get().to(get_info))
+            .route("/api/metrics", web::get().to(get_metrics))
+            .route("/metrics", web::get().to(get_prometheus_metrics))
+            .route("/api/logs", web::get().to(get_logs))
+            .route("/api/pause", web::post().to(pause))
+            .route("/api/resume", web::post().to(resume))
+            .route("/api/status", web::get().to(get_status))
+            .route("/api/dlq", web::get().to(get_dlq))
+            .route("/api/dlq/redrive", web::post().to(post_dlq_redrive))
+            .route(
+                "/api/version",
+                web::get().to(i_miss_rust::api::version::get_version),
+            )
+            // debug helpers (safe to keep; read-only)
+            .route("/api/pgmq_metrics", web::get().to(get_pgmq_metrics))
+            .route("/api/pgmq_counts", web::get().to(get_pgmq_counts))
+            .route("/api/pgmq_peek", web::get().to(get_pgmq_peek))
+            .route("/api/pgmq_read_once", web::post().to(post_pgmq_read_once))
+    })
+    .listen(listener)
+    .with_context(|| format!("failed to start HTTP server on {addr}"))?
+    .run();
+
+    println!("[ingest_worker] http listening on {addr}");
+    let handle = tokio::spawn(async move {
+        if let Err(e) = i_miss_rust::api::server::serve_until_shutdown(server, shutdown_rx).await {
             eprintln!("[ingest_worker] http server error: {e:?}");
         }
+        println!("[ingest_worker] http server stopped");
     });
 
     async fn enqueue(
         db: actix_web::web::Data<Db>,
         cfg: actix_web::web::Data<QueueConfig>,
+        req: actix_web::HttpRequest,
         body: actix_web::web::Json<EnqueueReq>,
     ) -> impl actix_web::Responder {
         let mut job = IngestJob::new(&body.provider, &body.task, body.args.clone());
         job.provider_id = body.provider_id;
+        job.traceparent = request_traceparent(&req, otel_enabled());
         match enqueue_job(&db, &cfg, &job).await {
             Ok(msg_id) => actix_web::HttpResponse::Ok()
                 .json(json!({"ok": true, "msg_id": msg_id, "correlation": job.correlation_id})),
@@ -1496,8 +1602,24 @@ fn start_http_server(
         actix_web::HttpResponse::Ok().json(m)
     }
 
+    async fn get_prometheus_metrics(
+        cfg: actix_web::web::Data<QueueConfig>,
+        metrics: actix_web::web::Data<Arc<Mutex<WorkerMetrics>>>,
+    ) -> impl actix_web::Responder {
+        let text = metrics.lock().unwrap().prometheus_text(&cfg.queue_name);
+        actix_web::HttpResponse::Ok()
+            .content_type(PROMETHEUS_CONTENT_TYPE)
+            .body(text)
+    }
+
     async fn get_info(cfg: actix_web::web::Data<QueueConfig>) -> impl Responder {
-        actix_web::HttpResponse::Ok().json(cfg.as_ref())
+        let mut info = serde_json::to_value(cfg.as_ref()).unwrap_or_else(|_| json!({}));
+        let known: serde_json::Map<String, serde_json::Value> = KNOWN_TASKS
+            .iter()
+            .map(|(provider, tasks)| (provider.to_string(), json!(tasks)))
+            .collect();
+        info["known_tasks"] = serde_json::Value::Object(known);
+        actix_web::HttpResponse::Ok().json(info)
     }
 
     async fn get_logs(
@@ -1529,6 +1651,56 @@ fn start_http_server(
         actix_web::HttpResponse::Ok().json(json!({"paused": manager.is_paused()}))
     }
 
+    // Archived (dead-lettered) jobs with their failed attempt counts
+    async fn get_dlq(
+        db: actix_web::web::Data<Db>,
+        cfg: actix_web::web::Data<QueueConfig>,
+        query: actix_web::web::Query<std::collections::HashMap<String, String>>,
+    ) -> impl Responder {
+        let limit: i64 = query
+            .get("limit")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(50)
+            .clamp(1, DLQ_MAX_BATCH);
+        match list_archived_jobs(&db, &cfg, limit).await {
+            Ok(jobs) => actix_web::HttpResponse::Ok().json(json!({"ok": true, "jobs": jobs})),
+            Err(e) => actix_web::HttpResponse::InternalServerError()
+                .json(json!({"ok": false, "error": e.to_string()})),
+        }
+    }
+
+    async fn post_dlq_redrive(
+        db: actix_web::web::Data<Db>,
+        cfg: actix_web::web::Data<QueueConfig>,
+        manager: actix_web::web::Data<Manager>,
+        body: actix_web::web::Json<RedriveReq>,
+    ) -> impl Responder {
+        let Some(target) = RedriveTarget::from_json(&body.msg_ids) else {
+            return actix_web::HttpResponse::BadRequest().json(
+                json!({"ok": false, "error": "msg_ids must be \"all\" or an array of msg_ids"}),
+            );
+        };
+        match redrive_archived_jobs(&db, &cfg, &target).await {
+            Ok(outcome) => {
+                push_log(
+                    &manager,
+                    format!(
+                        "[ingest_worker] dlq redrive: {} re-sent, {} skipped",
+                        outcome.redriven.len(),
+                        outcome.skipped.len()
+                    ),
+                );
+                actix_web::HttpResponse::Ok().json(json!({
+                    "ok": true,
+                    "redriven": outcome.redriven,
+                    "skipped": outcome.skipped,
+                }))
+            }
+            Err(e) => actix_web::HttpResponse::InternalServerError()
+                .json(json!({"ok": false, "error": e.to_string()})),
+        }
+    }
+
     // Return pgmq.metrics(queue_name) for this worker
     async fn get_pgmq_metrics(
         db: actix_web::web::Data<Db>,
@@ -1632,6 +1804,8 @@ fn start_http_server(
                 .json(json!({"ok": false, "error": e.to_string()})),
         }
     }
+
+    Ok(handle)
 }
 
 async fn process_provider_items_scoped(
@@ -1670,17 +1844,819 @@ async fn process_provider_items_scoped(
     Ok(ids.len() as u64)
 }
 
-// Scoped helpers to encapsulate unsafe environment mutations.
-// SAFETY rationale: we only mutate process env within a short-lived scope (EnvScope)
-// and restore prior values on Drop to avoid leaking state.
-#[inline]
-fn _set_env_var(key: &str, val: &str) {
-    // SAFETY: Scoped, deterministic use; restored by EnvScope::drop.
-    unsafe { std::env::set_var(key, val) }
+/// Per-job overrides for IGDB tasks, applied on top of `IgdbServiceConfig::from_env()`.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct IgdbJobArgs {
+    mode: Option<String>,
+    from_year: Option<i32>,
+    to_year: Option<i32>,
+    platforms: Option<Vec<i32>>,
+    page_size: Option<usize>,
+    max_pages: Option<usize>,
+    reqs_per_min: Option<u32>,
+    rps: Option<f32>,
+    concurrency: Option<usize>,
+    max_retries: Option<u32>,
+    backoff_ms: Option<u64>,
+}
+
+impl IgdbJobArgs {
+    fn from_job(job: &IngestJob) -> Result<Self> {
+        job_args(job, "igdb")
+    }
+
+    fn service_config(&self) -> IgdbServiceConfig {
+        let mut cfg = IgdbServiceConfig::from_env();
+        if let Some(v) = self.reqs_per_min {
+            cfg.reqs_per_min = Some(v);
+        }
+        if let Some(v) = self.rps {
+            cfg.rps = Some(v);
+        }
+        if let Some(v) = self.concurrency {
+            cfg.concurrency = v.max(1);
+        }
+        if let Some(v) = self.page_size {
+            cfg.page_size = v.max(1);
+        }
+        if let Some(v) = self.max_pages {
+            cfg.max_pages = v.max(1);
+        }
+        if let Some(v) = self.max_retries {
+            cfg.max_retries = v;
+        }
+        if let Some(v) = self.backoff_ms {
+            cfg.backoff_ms = v;
+        }
+        cfg
+    }
+}
+
+/// A job's `args` decoded into a provider's typed overrides; no args means none.
+fn job_args<T: serde::de::DeserializeOwned + Default>(job: &IngestJob, what: &str) -> Result<T> {
+    match &job.args {
+        Some(args) => {
+            serde_json::from_value(args.clone()).with_context(|| format!("{what} job args"))
+        }
+        None => Ok(T::default()),
+    }
+}
+
+/// Per-job overrides for `steam/backfill`, applied on top of `SteamRunOptions::from_env()`.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct SteamJobArgs {
+    recent_days: Option<i64>,
+}
+
+impl SteamJobArgs {
+    fn run_options(&self) -> SteamRunOptions {
+        let mut opts = SteamRunOptions::from_env();
+        opts.backfill = true;
+        if let Some(v) = self.recent_days {
+            opts.recent_missing_days = v;
+        }
+        opts
+    }
+}
+
+/// Per-job overrides for Xbox runs, applied on top of `XboxOptions::default()` and
+/// `XboxRequestPolicy::from_env()`.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct XboxJobArgs {
+    market: Option<String>,
+    language: Option<String>,
+    product_ids: Option<Vec<String>>,
+    product_ids_file: Option<String>,
+    ms_cv: Option<String>,
+    dry_run: Option<bool>,
+    chunk_sleep_ms: Option<u64>,
+    chunk_size: Option<usize>,
+    reqs_per_min: Option<u64>,
+    rps: Option<f32>,
+    max_retries: Option<u32>,
+    backoff_ms: Option<u64>,
+}
+
+impl XboxJobArgs {
+    fn options(&self) -> XboxOptions {
+        use i_miss_rust::database_ops::xbox::provider::parse_product_ids;
+
+        let mut opts = XboxOptions::default();
+        if let Some(v) = &self.market {
+            opts.market = v.clone();
+        }
+        if let Some(v) = &self.language {
+            opts.language = v.clone();
+        }
+        // A readable, non-empty ids file wins over the inline list, as with the env vars.
+        let from_file = self
+            .product_ids_file
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|contents| parse_product_ids(&contents))
+            .filter(|ids| !ids.is_empty());
+        let inline = self
+            .product_ids
+            .as_ref()
+            .map(|ids| parse_product_ids(&ids.join(",")))
+            .filter(|ids| !ids.is_empty());
+        if let Some(ids) = from_file.or(inline) {
+            opts.product_ids = ids;
+        }
+        if let Some(v) = self.dry_run {
+            opts.dry_run = v;
+        }
+        opts
+    }
+
+    fn request_policy(&self) -> XboxRequestPolicy {
+        let mut policy = XboxRequestPolicy::from_env();
+        if self.reqs_per_min.is_some() || self.rps.is_some() {
+            policy.pace_ms = XboxRequestPolicy::pace_ms(self.reqs_per_min, self.rps);
+        }
+        if let Some(v) = self.max_retries {
+            policy.max_retries = v;
+        }
+        if let Some(v) = self.backoff_ms {
+            policy.backoff_ms = v;
+        }
+        if let Some(v) = self.chunk_sleep_ms {
+            policy.chunk_sleep_ms = Some(v);
+        }
+        if let Some(v) = self.chunk_size {
+            policy.chunk_size = v.max(1);
+        }
+        if let Some(v) = &self.ms_cv {
+            policy.ms_cv = v.clone();
+        }
+        policy
+    }
+}
+
+/// Per-job overrides for PS Store prices/backfill runs, applied on top of
+/// `PsPricesOptions::from_env()`.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct PsPricesJobArgs {
+    locales: Option<Vec<String>>,
+    regions: Option<Vec<String>>,
+    region: Option<String>,
+    pages: Option<u32>,
+    page_size: Option<u32>,
+    rps: Option<u32>,
+    max_retries: Option<u32>,
+    backoff_ms: Option<u64>,
+    sha: Option<String>,
+    cat_ps4: Option<String>,
+    cat_ps5: Option<String>,
+}
+
+impl PsPricesJobArgs {
+    fn options(&self) -> PsPricesOptions {
+        use i_miss_rust::database_ops::playstation::prices::parse_regions;
+
+        let mut opts = PsPricesOptions::from_env();
+        let listed = self
+            .locales
+            .as_ref()
+            .or(self.regions.as_ref())
+            .map(|vals| parse_regions(&vals.join(",")))
+            .filter(|vals| !vals.is_empty());
+        if let Some(vals) = listed {
+            opts.regions = vals;
+        } else if let Some(region) = &self.region {
+            // Accept simple country like "us" and expand to en-us
+            let r = region.trim().to_lowercase();
+            opts.regions = vec![if r.len() == 2 { format!("en-{}", r) } else { r }];
+        }
+        if let Some(v) = self.pages {
+            opts.pages = v;
+        }
+        if let Some(v) = self.page_size {
+            opts.page_size = v.max(1);
+        }
+        if let Some(v) = self.rps {
+            opts.rps = v.max(1);
+        }
+        if let Some(v) = self.max_retries {
+            opts.max_retries = v;
+        }
+        if let Some(v) = self.backoff_ms {
+            opts.backoff_ms = v;
+        }
+        if let Some(v) = self.sha.as_ref().filter(|s| !s.trim().is_empty()) {
+            opts.grid_hash = Some(v.trim().to_string());
+        }
+        if let Some(v) = &self.cat_ps4 {
+            opts.cat_ps4 = v.clone();
+        }
+        if let Some(v) = &self.cat_ps5 {
+            opts.cat_ps5 = v.clone();
+        }
+        opts
+    }
+}
+
+/// Per-job Nexarda request policy; unset fields fall back to the `NEXARDA_*` env.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct NexardaJobArgs {
+    reqs_per_min: Option<u32>,
+    rps: Option<f32>,
+    max_retries: Option<u32>,
+    backoff_ms: Option<u64>,
+}
+
+/// Per-job overrides for TGDB syncs, applied on top of `TgdbSyncOptions::from_env()`.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct TgdbJobArgs {
+    api_key: Option<String>,
+    year_min: Option<i32>,
+    year_max: Option<i32>,
+    page_size: Option<u32>,
+    reqs_per_min: Option<u64>,
+}
+
+impl TgdbJobArgs {
+    /// `api_key` is resolved here (arg, then `TGDB_API_KEY`) so the caller can skip keyless runs.
+    fn options(&self) -> TgdbSyncOptions {
+        let mut opts = TgdbSyncOptions::from_env();
+        opts.api_key = self
+            .api_key
+            .clone()
+            .or_else(|| env_util::env_opt("TGDB_API_KEY"))
+            .filter(|s| !s.is_empty());
+        if let Some(v) = self.year_min {
+            opts.year_min = v;
+        }
+        if let Some(v) = self.year_max {
+            opts.year_max = v;
+        }
+        if let Some(v) = self.page_size {
+            opts.page_size = v.max(1);
+        }
+        if let Some(v) = self.reqs_per_min {
+            opts.reqs_per_min = Some(v);
+        }
+        opts
+    }
+}
+
+/// Per-job overrides for ITAD syncs, applied on top of `ItadSyncOptions::from_env()`.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct ItadJobArgs {
+    api_key: Option<String>,
+    base_url: Option<String>,
+    timeout_secs: Option<u64>,
+    country: Option<String>,
+    deals_limit: Option<u32>,
+    max_game_overviews: Option<usize>,
+    default_currency: Option<String>,
+    country_name: Option<String>,
+}
+
+impl ItadJobArgs {
+    fn options(&self) -> ItadSyncOptions {
+        let mut opts = ItadSyncOptions::from_env();
+        if let Some(v) = &self.api_key {
+            opts.api_key = Some(v.clone());
+        }
+        if let Some(v) = &self.base_url {
+            opts.base_url = v.clone();
+        }
+        if let Some(v) = self.timeout_secs {
+            opts.timeout_secs = v;
+        }
+        if let Some(v) = &self.country {
+            opts.country = v.clone();
+        }
+        if let Some(v) = self.deals_limit {
+            opts.deals_limit = v;
+        }
+        if let Some(v) = self.max_game_overviews {
+            opts.max_game_overviews = v;
+        }
+        if let Some(v) = &self.default_currency {
+            opts.default_currency = v.clone();
+        }
+        if let Some(v) = &self.country_name {
+            opts.country_name = Some(v.clone());
+        }
+        opts
+    }
+}
+
+/// Per-job overrides for RAWG syncs, applied on top of `RawgSyncOptions::from_env()`.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct RawgJobArgs {
+    mode: Option<String>,
+    api_key: Option<String>,
+    year_min: Option<i32>,
+    year_max: Option<i32>,
+    page_size: Option<u32>,
+    reqs_per_min: Option<u64>,
+    fetch_details: Option<bool>,
+    sleep_ms: Option<u64>,
 }
 
-#[inline]
-fn _remove_env_var(key: &str) {
-    // SAFETY: Scoped, deterministic use; restored by EnvScope::drop.
-    unsafe { std::env::remove_var(key) }
+impl RawgJobArgs {
+    fn options(&self) -> RawgSyncOptions {
+        let mut opts = RawgSyncOptions::from_env();
+        if let Some(v) = &self.mode {
+            opts.mode = v.clone();
+        }
+        if let Some(v) = &self.api_key {
+            opts.api_key = Some(v.clone());
+        }
+        if let Some(v) = self.year_min {
+            opts.year_min = v;
+        }
+        if let Some(v) = self.year_max {
+            opts.year_max = v;
+        }
+        if opts.year_min > opts.year_max {
+            std::mem::swap(&mut opts.year_min, &mut opts.year_max);
+        }
+        if let Some(v) = self.page_size {
+            opts.page_size = v.max(1);
+        }
+        if let Some(v) = self.reqs_per_min {
+            opts.reqs_per_min = Some(v);
+        }
+        if let Some(v) = self.fetch_details {
+            opts.fetch_details = v;
+        }
+        if let Some(v) = self.sleep_ms {
+            opts.sleep_ms = Some(v);
+        }
+        opts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i_miss_rust::api::metrics::parse_exposition;
+
+    async fn pop_job(db: &Db, cfg: &QueueConfig) -> Result<Option<PoppedJob>> {
+        Ok(pop_jobs(db, cfg, 1).await?.pop())
+    }
+
+    #[test]
+    fn redrive_target_accepts_all_or_ids() {
+        assert_eq!(
+            RedriveTarget::from_json(&json!("ALL")),
+            Some(RedriveTarget::All)
+        );
+        assert_eq!(
+            RedriveTarget::from_json(&json!([3, 7])),
+            Some(RedriveTarget::Ids(vec![3, 7]))
+        );
+        assert_eq!(RedriveTarget::from_json(&json!([3, "7"])), None);
+        assert_eq!(RedriveTarget::from_json(&json!("some")), None);
+    }
+
+    #[test]
+    fn known_tasks_cover_dispatch_arms_only() {
+        assert!(is_known_task("psstore", "prices"));
+        assert!(is_known_task("thegamesdb", "sync"));
+        assert!(is_known_task("giantbomb", "load"));
+        assert!(is_known_task("rawg", "refresh"));
+        assert!(!is_known_task("gb", "load"));
+        assert!(!is_known_task("microsoft", "catalog"));
+        assert!(!is_known_task("bogus", "thing"));
+    }
+
+    #[test]
+    fn prometheus_text_names_every_worker_metric() {
+        let mut metrics = WorkerMetrics {
+            dequeues: 12,
+            failures: 2,
+            last_run_ms: 2500,
+            queue_depth: 40,
+            oldest_msg_age_secs: Some(630),
+            ..WorkerMetrics::default()
+        };
+        let igdb = metrics.providers.entry("igdb".into()).or_default();
+        igdb.record_success(Duration::from_millis(2500));
+        igdb.record_success(Duration::from_millis(1500));
+        metrics
+            .providers
+            .entry("xbox".into())
+            .or_default()
+            .record_failure(Duration::from_millis(40), "displaycatalog returned 503");
+        let text = metrics.prometheus_text("default_ingest");
+        let samples = parse_exposition(&text).unwrap();
+        let value = |name: &str, labels: &str| {
+            samples
+                .iter()
+                .find(|(n, l, _)| n == name && l == labels)
+                .map(|s| s.2)
+        };
+        let queue = r#"{queue="default_ingest"}"#;
+        assert_eq!(value("ingest_worker_dequeues_total", queue), Some(12.0));
+        assert_eq!(value("ingest_worker_failures_total", queue), Some(2.0));
+        assert_eq!(
+            value("ingest_worker_last_run_duration_seconds", queue),
+            Some(2.5)
+        );
+        assert_eq!(value("ingest_worker_last_wait_seconds", queue), Some(0.0));
+        assert_eq!(value("ingest_queue_depth", queue), Some(40.0));
+        assert_eq!(
+            value("ingest_queue_oldest_message_age_seconds", queue),
+            Some(630.0)
+        );
+        let (igdb, xbox) = (r#"{provider="igdb"}"#, r#"{provider="xbox"}"#);
+        assert_eq!(value("ingest_provider_runs_total", igdb), Some(2.0));
+        assert_eq!(value("ingest_provider_failures_total", igdb), Some(0.0));
+        assert_eq!(value("ingest_provider_runs_total", xbox), Some(0.0));
+        assert_eq!(value("ingest_provider_failures_total", xbox), Some(1.0));
+        assert_eq!(
+            value("ingest_provider_last_run_duration_seconds", igdb),
+            Some(1.5)
+        );
+
+        // An empty queue has no oldest message.
+        let idle = WorkerMetrics::default().prometheus_text("default_ingest");
+        let idle = parse_exposition(&idle).unwrap();
+        assert!(!idle
+            .iter()
+            .any(|(n, _, _)| n == "ingest_queue_oldest_message_age_seconds"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn concurrent_jobs_do_not_share_args() {
+        let job = |page_size: u64| {
+            IngestJob::new("igdb", "backfill", Some(json!({ "page_size": page_size })))
+        };
+        // Typed options never touch the env.
+        let igdb = |job: IngestJob| async move {
+            let args = IgdbJobArgs::from_job(&job).unwrap();
+            sleep(Duration::from_millis(20)).await;
+            args.service_config().page_size
+        };
+        let (a, b) = tokio::join!(tokio::spawn(igdb(job(25))), tokio::spawn(igdb(job(75))));
+        assert_eq!((a.unwrap(), b.unwrap()), (25, 75));
+
+        // So do the other providers' handlers; nothing is written to the process env.
+        let args = json!({
+            "page_size": 25, "year_min": 2024, "year_max": 2019, "reqs_per_min": 12,
+            "region": "gb", "sha": "abc", "max_retries": 2, "rps": 4,
+        });
+        let typed = |provider: &str| IngestJob::new(provider, "all", Some(args.clone()));
+        let tgdb: TgdbJobArgs = job_args(&typed("tgdb"), "tgdb").unwrap();
+        assert_eq!(
+            (tgdb.options().page_size, tgdb.options().reqs_per_min),
+            (25, Some(12))
+        );
+        let rawg: RawgJobArgs = job_args(&typed("rawg"), "rawg").unwrap();
+        assert_eq!(
+            (rawg.options().year_min, rawg.options().year_max),
+            (2019, 2024)
+        );
+        let ps: PsPricesJobArgs = job_args(&typed("ps"), "ps prices").unwrap();
+        let ps = ps.options();
+        assert_eq!(ps.regions, vec!["en-gb".to_string()]);
+        assert_eq!((ps.page_size, ps.rps, ps.max_retries), (25, 4, 2));
+        assert_eq!(ps.grid_hash.as_deref(), Some("abc"));
+        let xbox: XboxJobArgs = job_args(&typed("xbox"), "xbox").unwrap();
+        let policy = xbox.request_policy();
+        assert_eq!((policy.pace_ms, policy.max_retries), (Some(5_000), 2));
+        let nexarda: NexardaJobArgs = job_args(&typed("nexarda"), "nexarda").unwrap();
+        assert_eq!(
+            (nexarda.reqs_per_min, nexarda.max_retries),
+            (Some(12), Some(2))
+        );
+
+        assert!(
+            IgdbJobArgs::from_job(&job(0))
+                .unwrap()
+                .service_config()
+                .page_size
+                >= 1
+        );
+        let bad = IngestJob::new("igdb", "backfill", Some(json!({ "page_size": "big" })));
+        assert!(IgdbJobArgs::from_job(&bad).is_err());
+    }
+
+    #[tokio::test]
+    async fn provider_caps_serialize_igdb_but_not_nexarda() {
+        use std::sync::atomic::AtomicUsize;
+
+        let limits = ProviderLimits::new(
+            8,
+            &HashMap::from([
+                ("igdb".to_string(), 1),
+                ("NEXARDA".to_string(), 4),
+                ("playstation".to_string(), 1),
+            ]),
+        );
+        assert_eq!(limits.caps(), vec![("igdb", 1), ("nexarda", 4), ("ps", 1)]);
+        // The PS aliases share one cap.
+        let ps = limits.try_slot("psstore").expect("free ps slot");
+        assert!(limits.try_slot("ps").is_none());
+        assert!(limits.try_slot("PlayStation").is_none());
+        drop(ps);
+        assert!(limits.try_slot("ps-store").is_some());
+
+        // (running now, most running at once, deferrals) per provider; 'static so the jobs
+        // can be spawned like the worker pool does. A job finding its cap reached is handed
+        // back and read again later, as the worker does through the queue.
+        type Counts = (AtomicUsize, AtomicUsize, AtomicUsize);
+        let igdb: &'static Counts = Box::leak(Box::default());
+        let nexarda: &'static Counts = Box::leak(Box::default());
+        let job = |provider: &'static str, counts: &'static Counts| {
+            let limits = limits.clone();
+            async move {
+                let _slot = loop {
+                    match limits.try_slot(provider) {
+                        Some(slot) => break slot,
+                        None => {
+                            counts.2.fetch_add(1, Ordering::SeqCst);
+                            sleep(Duration::from_millis(5)).await;
+                        }
+                    }
+                };
+                let now = counts.0.fetch_add(1, Ordering::SeqCst) + 1;
+                counts.1.fetch_max(now, Ordering::SeqCst);
+                sleep(Duration::from_millis(20)).await;
+                counts.0.fetch_sub(1, Ordering::SeqCst);
+            }
+        };
+        let mut pool = JoinSet::new();
+        for _ in 0..4 {
+            pool.spawn(job("igdb", igdb));
+            pool.spawn(job("nexarda", nexarda));
+        }
+        while let Some(done) = pool.join_next().await {
+            done.unwrap();
+        }
+
+        assert_eq!(igdb.1.load(Ordering::SeqCst), 1);
+        assert!(igdb.2.load(Ordering::SeqCst) >= 3);
+        assert_eq!(nexarda.1.load(Ordering::SeqCst), 4);
+        assert_eq!(nexarda.2.load(Ordering::SeqCst), 0);
+
+        limits.defer(7);
+        limits.defer(7);
+        assert_eq!(limits.take_deferrals(7), 2);
+        assert_eq!(limits.take_deferrals(7), 0);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres with pgmq via TEST_DATABASE_URL"]
+    async fn archived_job_is_listed_and_redriven() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 2).await.unwrap();
+        let cfg = QueueConfig {
+            queue_name: format!("dlq_test_{}", std::process::id()),
+            ..QueueConfig::from_env()
+        };
+        ensure_queue(&db, &cfg).await.unwrap();
+
+        let bad = IngestJob::new("nope", "nope", Some(json!({"page": 2})));
+        enqueue_job(&db, &cfg, &bad).await.unwrap();
+        let popped = pop_job(&db, &cfg).await.unwrap().expect("queued job");
+        assert!(handle_job(&db, &popped.job).await.is_err());
+        archive_job(&db, &cfg, popped.msg_id).await.unwrap();
+        assert!(pop_job(&db, &cfg).await.unwrap().is_none());
+
+        let archived = list_archived_jobs(&db, &cfg, 10).await.unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].msg_id, popped.msg_id);
+        assert_eq!(archived[0].read_ct, 1);
+        assert_eq!(
+            archived[0].message["correlation_id"],
+            json!(bad.correlation_id)
+        );
+
+        let outcome = redrive_archived_jobs(&db, &cfg, &RedriveTarget::Ids(vec![popped.msg_id]))
+            .await
+            .unwrap();
+        assert_eq!(outcome.redriven.len(), 1);
+        assert!(outcome.skipped.is_empty());
+        assert!(list_archived_jobs(&db, &cfg, 10).await.unwrap().is_empty());
+        let repeat = redrive_archived_jobs(&db, &cfg, &RedriveTarget::Ids(vec![popped.msg_id]))
+            .await
+            .unwrap();
+        assert!(repeat.redriven.is_empty());
+
+        let again = pop_job(&db, &cfg).await.unwrap().expect("redriven job");
+        assert_eq!(again.msg_id, outcome.redriven[0].1);
+        assert_eq!(again.read_ct, 1);
+        assert_eq!(again.job.correlation_id, bad.correlation_id);
+        let args = again.job.args.unwrap();
+        assert_eq!(args["page"], json!(2));
+        assert!(args["redriven_at"].is_string());
+
+        sqlx::query("SELECT pgmq.drop_queue($1)")
+            .bind(&cfg.queue_name)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres with pgmq via TEST_DATABASE_URL"]
+    async fn batched_read_returns_up_to_the_batch_size() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 2).await.unwrap();
+        let cfg = QueueConfig {
+            queue_name: format!("batch_read_test_{}", std::process::id()),
+            read_batch: 4,
+            ..QueueConfig::from_env()
+        };
+        ensure_queue(&db, &cfg).await.unwrap();
+        for page in 0..10 {
+            let job = IngestJob::new("steam", "catalog", Some(json!({ "page": page })));
+            enqueue_job(&db, &cfg, &job).await.unwrap();
+        }
+
+        let first = pop_jobs(&db, &cfg, cfg.read_batch).await.unwrap();
+        assert_eq!(first.len(), 4);
+        let rest = pop_jobs(&db, &cfg, 100).await.unwrap();
+        assert_eq!(rest.len(), 6);
+        let mut ids: Vec<i64> = first.iter().chain(&rest).map(|p| p.msg_id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), 10);
+        assert!(pop_jobs(&db, &cfg, 4).await.unwrap().is_empty());
+
+        sqlx::query("SELECT pgmq.drop_queue($1)")
+            .bind(&cfg.queue_name)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres with pgmq via TEST_DATABASE_URL"]
+    async fn traceparent_set_on_enqueue_survives_a_pop() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 2).await.unwrap();
+        let cfg = QueueConfig {
+            queue_name: format!("traceparent_test_{}", std::process::id()),
+            ..QueueConfig::from_env()
+        };
+        ensure_queue(&db, &cfg).await.unwrap();
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let req = actix_web::test::TestRequest::default()
+            .insert_header((TRACEPARENT_HEADER, header))
+            .to_http_request();
+
+        assert_eq!(request_traceparent(&req, false), None);
+        let mut job = IngestJob::new("steam", "catalog", None);
+        job.traceparent = request_traceparent(&req, true);
+        enqueue_job(&db, &cfg, &job).await.unwrap();
+        // Jobs enqueued without one still decode.
+        enqueue_job(&db, &cfg, &IngestJob::new("steam", "catalog", None))
+            .await
+            .unwrap();
+
+        let traced = pop_job(&db, &cfg).await.unwrap().expect("traced job");
+        let plain = pop_job(&db, &cfg).await.unwrap().expect("plain job");
+        assert_eq!(traced.job.traceparent.as_deref(), Some(header));
+        assert!(traced.job.span(true).is_some());
+        assert!(traced.job.span(false).is_none());
+        assert_eq!(plain.job.traceparent, None);
+        assert!(plain.job.span(true).is_none());
+
+        sqlx::query("SELECT pgmq.drop_queue($1)")
+            .bind(&cfg.queue_name)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres with pgmq via TEST_DATABASE_URL"]
+    async fn queue_stats_report_depth_and_oldest_age() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 2).await.unwrap();
+        let cfg = QueueConfig {
+            queue_name: format!("queue_stats_test_{}", std::process::id()),
+            ..QueueConfig::from_env()
+        };
+        ensure_queue(&db, &cfg).await.unwrap();
+        let metrics = Mutex::new(WorkerMetrics::default());
+
+        refresh_queue_stats(&db, &cfg, &metrics).await.unwrap();
+        let empty = metrics.lock().unwrap().clone();
+        assert_eq!((empty.queue_depth, empty.oldest_msg_age_secs), (0, None));
+
+        let mut ids = Vec::new();
+        for page in 0..3 {
+            let job = IngestJob::new("steam", "catalog", Some(json!({ "page": page })));
+            ids.push(enqueue_job(&db, &cfg, &job).await.unwrap());
+        }
+        // Backdate two messages; the oldest sets the age.
+        let backdate = format!(
+            "UPDATE pgmq.q_{} SET enqueued_at = now() - make_interval(mins => $2)
+             WHERE msg_id = $1",
+            cfg.queue_name
+        );
+        for (msg_id, minutes) in [(ids[1], 10), (ids[0], 90)] {
+            sqlx::query(&backdate)
+                .bind(msg_id)
+                .bind(minutes)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+        refresh_queue_stats(&db, &cfg, &metrics).await.unwrap();
+        let seeded = serde_json::to_value(metrics.lock().unwrap().clone()).unwrap();
+
+        sqlx::query("SELECT pgmq.drop_queue($1)")
+            .bind(&cfg.queue_name)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(seeded["queue_depth"], 3);
+        let age = seeded["oldest_msg_age_secs"].as_i64().unwrap();
+        assert!((90 * 60..90 * 60 + 30).contains(&age), "age {age}");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres with pgmq via TEST_DATABASE_URL"]
+    async fn unknown_task_is_archived_on_first_dequeue() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 2).await.unwrap();
+        let cfg = QueueConfig {
+            queue_name: format!("unknown_task_test_{}", std::process::id()),
+            max_retries: 5,
+            ..QueueConfig::from_env()
+        };
+        ensure_queue(&db, &cfg).await.unwrap();
+        let metrics = Mutex::new(WorkerMetrics::default());
+        let manager = Manager::new(10);
+
+        let job = IngestJob::new("bogus", "thing", None);
+        enqueue_job(&db, &cfg, &job).await.unwrap();
+        let popped = pop_job(&db, &cfg).await.unwrap().expect("queued job");
+        let msg_id = popped.msg_id;
+        run_popped_job(
+            &db,
+            &cfg,
+            &ProviderLimits::default(),
+            &metrics,
+            &manager,
+            popped,
+        )
+        .await
+        .unwrap();
+
+        assert!(pop_job(&db, &cfg).await.unwrap().is_none());
+        let archived = list_archived_jobs(&db, &cfg, 10).await.unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].msg_id, msg_id);
+        assert_eq!(archived[0].read_ct, 1);
+        assert_eq!(metrics.lock().unwrap().failures, 1);
+
+        sqlx::query("SELECT pgmq.drop_queue($1)")
+            .bind(&cfg.queue_name)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres with pgmq via TEST_DATABASE_URL"]
+    async fn job_at_its_provider_cap_is_deferred_without_using_an_attempt() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 2).await.unwrap();
+        let cfg = QueueConfig {
+            queue_name: format!("deferred_job_test_{}", std::process::id()),
+            poll_interval_secs: 1,
+            max_retries: 5,
+            ..QueueConfig::from_env()
+        };
+        ensure_queue(&db, &cfg).await.unwrap();
+        let metrics = Mutex::new(WorkerMetrics::default());
+        let manager = Manager::new(10);
+        let limits = ProviderLimits::new(2, &HashMap::from([("igdb".to_string(), 1)]));
+        let busy = limits.try_slot("igdb").expect("free igdb slot");
+
+        enqueue_job(&db, &cfg, &IngestJob::new("igdb", "backfill", None))
+            .await
+            .unwrap();
+        let popped = pop_job(&db, &cfg).await.unwrap().expect("queued job");
+        let msg_id = popped.msg_id;
+        run_popped_job(&db, &cfg, &limits, &metrics, &manager, popped)
+            .await
+            .unwrap();
+
+        // Handed back for a poll interval: neither failed nor archived.
+        assert!(pop_job(&db, &cfg).await.unwrap().is_none());
+        assert_eq!(metrics.lock().unwrap().failures, 0);
+        assert!(list_archived_jobs(&db, &cfg, 10).await.unwrap().is_empty());
+        drop(busy);
+        sleep(Duration::from_millis(1500)).await;
+        let again = pop_job(&db, &cfg).await.unwrap().expect("deferred job");
+        assert_eq!(again.msg_id, msg_id);
+        assert_eq!(again.read_ct - limits.take_deferrals(msg_id), 1);
+
+        sqlx::query("SELECT pgmq.drop_queue($1)")
+            .bind(&cfg.queue_name)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+    }
 }
@@ -1,4 +1,5 @@
 use anyhow::{Context, Result};
+use i_miss_rust::util::currency::minor_unit as currency_minor_unit;
 use i_miss_rust::util::env;
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -353,14 +354,6 @@ fn extract_price_f64(v: &serde_json::Value) -> Option<f64> {
     None
 }
 
-fn currency_minor_unit(code: &str) -> i16 {
-    match code {
-        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "HUF" => 0,
-        "BHD" | "IQD" | "KWD" | "JOD" | "OMR" | "TND" => 3,
-        _ => 2,
-    }
-}
-
 fn currency_to_country_code(code: &str) -> String {
     (match code {
         "USD" => "US",
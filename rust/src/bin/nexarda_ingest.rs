@@ -44,6 +44,10 @@ async fn main() -> Result<()> {
         context: None,
         base_url: None,
         timeout: None,
+        max_retries: None,
+        backoff_ms: None,
+        reqs_per_min: None,
+        rps: None,
     };
 
     info!("nexarda: ingest start");
@@ -6,6 +6,7 @@ use serde_json::json;
 use sqlx::Row;
 use std::env;
 
+use i_miss_rust::cli::purge_synthetic::SYNTHETIC_RATING_LOCALE;
 use i_miss_rust::database_ops::db::{CurrentPriceRow, Db, PriceRow};
 use i_miss_rust::database_ops::exchange::ExchangeService;
 use i_miss_rust::database_ops::ingest_providers::{
@@ -16,6 +17,7 @@ use i_miss_rust::database_ops::ingest_providers::{
     link_provider_offer,
 };
 use i_miss_rust::database_ops::media_map::MediaMap;
+use i_miss_rust::util::currency::minor_unit as currency_minor_unit;
 use psstore_client::{PsConfig, PsProductSummary, PsStoreClient};
 
 // In-memory aggregation structs
@@ -536,7 +538,7 @@ async fn main() -> Result<()> {
             )
             .persistent(false)
             .bind(vg_ps5)
-            .bind("fallback")
+            .bind(SYNTHETIC_RATING_LOCALE)
             .bind(4.6f32)
             .bind(3251i64)
             .execute(&db.pool).await?;
@@ -559,7 +561,7 @@ async fn main() -> Result<()> {
                 tax_inclusive: true,
                 fx_minor_per_unit: None,
                 btc_sats_per_unit: None,
-                meta: json!({"src":"psstore","kind":"base","locale":loc}),
+                meta: json!({"src":"psstore","kind":"base","locale":loc,"synthetic":true}),
                 video_game_id: Some(vg_ps5),
                 currency: None,
                 country_code: Some(loc.split('-').nth(1).unwrap_or("us").to_uppercase()),
@@ -573,7 +575,7 @@ async fn main() -> Result<()> {
                 tax_inclusive: true,
                 fx_minor_per_unit: None,
                 btc_sats_per_unit: None,
-                meta: json!({"src":"psstore","kind":"discount","locale":loc}),
+                meta: json!({"src":"psstore","kind":"discount","locale":loc,"synthetic":true}),
                 video_game_id: Some(vg_ps5),
                 currency: None,
                 country_code: Some(loc.split('-').nth(1).unwrap_or("us").to_uppercase()),
@@ -821,16 +823,6 @@ fn normalize_title(s: &str) -> String {
 // Local helpers (non-DB)
 
 // Map ISO 4217 currency minor units (fraction digits). Defaults to 2 when unknown.
-fn currency_minor_unit(code: &str) -> i16 {
-    match code.to_ascii_uppercase().as_str() {
-        // zero-decimal currencies
-        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "HUF" => 0,
-        // three-decimal currencies (rare for our locales, but include as examples)
-        "BHD" | "IQD" | "KWD" | "JOD" | "OMR" | "TND" => 3,
-        // default two-decimal
-        _ => 2,
-    }
-}
 
 // Helper to load PS Store regions from env into a Vec<String>.
 // Accepts comma or space separated values; trims and lowercases; provides a wide global default.
@@ -1,9 +1,13 @@
 use anyhow::{Context, Result};
 use chrono::Utc;
 use futures::{future::pending, SinkExt, StreamExt};
+use i_miss_rust::cli::purge_synthetic::SYNTHETIC_RATING_LOCALE;
 use i_miss_rust::database_ops::db::{CurrentPriceRow, Db, PriceRow};
 use i_miss_rust::database_ops::exchange::ExchangeService;
 use i_miss_rust::database_ops::ingest_providers::*;
+use i_miss_rust::database_ops::leader::LeaderElection;
+use i_miss_rust::normalization::title::{TitleKey, MIN_TITLE_SIMILARITY};
+use i_miss_rust::util::currency::minor_unit as currency_minor_unit;
 use i_miss_rust::util::env as env_util;
 use psstore_client::{PsConfig, PsProductSummary, PsStoreClient};
 use serde::{Deserialize, Serialize};
@@ -22,7 +26,7 @@ use url::{form_urlencoded, Url};
 // Removed unused PgPool imports; using existing Db wrapper instead.
 
 // A long-running test harness that periodically (or on NOTIFY/Realtime) runs the PS Store ingest
-// for a single target (NBA 2K26) and prints DB-proof of writes.
+// for a watchlist of titles (PS_LONG_TITLES, default NBA 2K26) and prints DB-proof of writes.
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -474,7 +478,29 @@ async fn connect_listen_channel(
 
 /* ------------------------ Scheduler / Worker ------------------------ */
 
+/// With ENABLE_LEADER_ELECTION=1 only the advisory-lock holder schedules; others stand by
+/// and take over once the leader's lock is released. Without election every instance leads.
+async fn is_scheduling_leader(election: &mut Option<LeaderElection>) -> bool {
+    let Some(election) = election.as_mut() else {
+        return true;
+    };
+    match election.ensure_leader().await {
+        Ok(true) => true,
+        Ok(false) => {
+            println!("[ps_long_test] standby: another scheduler holds leadership; skipping");
+            false
+        }
+        Err(e) => {
+            eprintln!("[ps_long_test] leader election check failed: {e:?}");
+            false
+        }
+    }
+}
+
 async fn run_scheduler(db: &Db, queue_cfg: &QueueConfig) -> Result<()> {
+    let mut election =
+        LeaderElection::enabled().then(|| LeaderElection::new(db, "ps_long_scheduler"));
+
     // Prefer Supabase Realtime if available
     let mut realtime_stream = match (env::var("SUPABASE_URL"), env::var("SUPABASE_ANON_KEY")) {
         (Ok(url), Ok(key)) => {
@@ -531,10 +557,10 @@ async fn run_scheduler(db: &Db, queue_cfg: &QueueConfig) -> Result<()> {
         notify_stream.is_some()
     );
 
-    let immediate = env::var("PS_LONG_IMMEDIATE")
-        .ok()
-        .map(|v| (v == "45" || v.eq_ignore_ascii_case("true")))
-        .unwrap_or(true);
+    let immediate = resolve_immediate(
+        env::var("PS_LONG_RUN_ON_START").ok().as_deref(),
+        env::var("PS_LONG_IMMEDIATE").ok().as_deref(),
+    );
 
     let shutdown_poll_secs: u64 = env::var("PS_LONG_SHUTDOWN_POLL_SECS")
         .ok()
@@ -546,45 +572,53 @@ async fn run_scheduler(db: &Db, queue_cfg: &QueueConfig) -> Result<()> {
         env::var("PS_LONG_SHUTDOWN_FILE").unwrap_or_else(|_| "./.ps_long_stop".into());
 
     let regions = load_regions();
-    let title = env::var("PS_LONG_TITLE").unwrap_or_else(|_| "NBA 2K26".into());
+    let titles = load_titles(
+        env::var("PS_LONG_TITLES").ok().as_deref(),
+        env::var("PS_LONG_TITLE").ok().as_deref(),
+    );
     println!("[ps_long_test] configured regions: {:?}", regions);
+    println!("[ps_long_test] configured titles: {:?}", titles);
 
     if queue_cfg.mode == QueueMode::Scheduler {
         ensure_queue(db, queue_cfg).await?;
     }
 
-    if immediate {
+    if immediate && is_scheduling_leader(&mut election).await {
         if queue_cfg.mode == QueueMode::Scheduler {
-            let job = PsIngestJob::new(&title, &regions);
-            let msg_id = enqueue_job(db, queue_cfg, &job).await?;
-            println!(
-                "[ps_long_test] immediate enqueue correlation={} msg_id={}",
-                job.correlation_id, msg_id
-            );
-        } else {
-            println!("[ps_long_test] immediate ingest start");
-            if let Err(e) = run_ingest(db, &title, &regions).await {
-                eprintln!("[ps_long_test] initial ingest error: {e:?}");
+            for title in &titles {
+                let job = PsIngestJob::new(title, &regions);
+                let msg_id = enqueue_job(db, queue_cfg, &job).await?;
+                println!(
+                    "[ps_long_test] immediate enqueue title={} correlation={} msg_id={}",
+                    title, job.correlation_id, msg_id
+                );
             }
+        } else {
+            println!("[ps_long_test] immediate ingest start titles={:?}", titles);
+            ingest_titles(db, &titles, &regions).await;
         }
     }
 
     loop {
         tokio::select! {
             _ = ticker.tick() => {
+                if !is_scheduling_leader(&mut election).await {
+                    continue;
+                }
                 if queue_cfg.mode == QueueMode::Scheduler {
-                    let job = PsIngestJob::new(&title, &regions);
-                    let msg_id = enqueue_job(db, queue_cfg, &job).await?;
-                    println!(
-                        "[ps_long_test] tick enqueue correlation={} msg_id={}",
-                        job.correlation_id,
-                        msg_id
-                    );
-                } else {
-                    println!("[ps_long_test] tick -> ingest");
-                    if let Err(e) = run_ingest(db, &title, &regions).await {
-                        eprintln!("[ps_long_test] ingest error: {e:?}");
+                    for title in &titles {
+                        let job = PsIngestJob::new(title, &regions);
+                        let msg_id = enqueue_job(db, queue_cfg, &job).await?;
+                        println!(
+                            "[ps_long_test] tick enqueue title={} correlation={} msg_id={}",
+                            title,
+                            job.correlation_id,
+                            msg_id
+                        );
                     }
+                } else {
+                    println!("[ps_long_test] tick -> ingest titles={:?}", titles);
+                    ingest_titles(db, &titles, &regions).await;
                 }
             }
 
@@ -597,15 +631,18 @@ async fn run_scheduler(db: &Db, queue_cfg: &QueueConfig) -> Result<()> {
             } => {
                 match rt {
                     Some(txt) => {
+                        if !is_scheduling_leader(&mut election).await {
+                            continue;
+                        }
                         if queue_cfg.mode == QueueMode::Scheduler {
-                            let job = PsIngestJob::new(&title, &regions);
-                            let msg_id = enqueue_job(db, queue_cfg, &job).await?;
-                            println!("[ps_long_test] Realtime event -> queued correlation={} msg_id={} payload={}", job.correlation_id, msg_id, truncate(&txt, 140));
-                        } else {
-                            println!("[ps_long_test] Realtime event -> ingest (payload={})", truncate(&txt, 140));
-                            if let Err(e) = run_ingest(db, &title, &regions).await {
-                                eprintln!("[ps_long_test] ingest error: {e:?}");
+                            for title in &titles {
+                                let job = PsIngestJob::new(title, &regions);
+                                let msg_id = enqueue_job(db, queue_cfg, &job).await?;
+                                println!("[ps_long_test] Realtime event -> queued title={} correlation={} msg_id={} payload={}", title, job.correlation_id, msg_id, truncate(&txt, 140));
                             }
+                        } else {
+                            println!("[ps_long_test] Realtime event -> ingest titles={:?} (payload={})", titles, truncate(&txt, 140));
+                            ingest_titles(db, &titles, &regions).await;
                         }
                     }
                     None => {
@@ -624,20 +661,24 @@ async fn run_scheduler(db: &Db, queue_cfg: &QueueConfig) -> Result<()> {
             } => {
                 match msg {
                     Some(payload) => {
+                        if !is_scheduling_leader(&mut election).await {
+                            continue;
+                        }
                         if queue_cfg.mode == QueueMode::Scheduler {
-                            let job = PsIngestJob::new(&title, &regions);
-                            let msg_id = enqueue_job(db, queue_cfg, &job).await?;
-                            println!(
-                                "[ps_long_test] NOTIFY(psstore_tick): {} -> queued correlation={} msg_id={}",
-                                payload,
-                                job.correlation_id,
-                                msg_id
-                            );
-                        } else {
-                            println!("[ps_long_test] NOTIFY(psstore_tick): {}", payload);
-                            if let Err(e) = run_ingest(db, &title, &regions).await {
-                                eprintln!("[ps_long_test] ingest error: {e:?}");
+                            for title in &titles {
+                                let job = PsIngestJob::new(title, &regions);
+                                let msg_id = enqueue_job(db, queue_cfg, &job).await?;
+                                println!(
+                                    "[ps_long_test] NOTIFY(psstore_tick): {} -> queued title={} correlation={} msg_id={}",
+                                    payload,
+                                    title,
+                                    job.correlation_id,
+                                    msg_id
+                                );
                             }
+                        } else {
+                            println!("[ps_long_test] NOTIFY(psstore_tick): {} titles={:?}", payload, titles);
+                            ingest_titles(db, &titles, &regions).await;
                         }
                     }
                     None => {
@@ -656,6 +697,9 @@ async fn run_scheduler(db: &Db, queue_cfg: &QueueConfig) -> Result<()> {
         }
     }
 
+    if let Some(election) = election.as_mut() {
+        let _ = election.release().await;
+    }
     println!("[ps_long_test] terminated gracefully");
     Ok(())
 }
@@ -762,14 +806,14 @@ async fn run_worker(db: &Db, queue_cfg: &QueueConfig) -> Result<()> {
                             m.failures += 1;
                             m.last_error = Some(format!("{err:?}"));
                         }
-                        // compute next backoff with exponential growth capped at retry_max_secs
+                        // next backoff: exponential with decorrelated jitter, capped at retry_max_secs
                         let attempt = (popped.read_ct as u32).saturating_add(1);
-                        let mut delay = queue_cfg
-                            .retry_base_secs
-                            .saturating_mul(1u64 << attempt.saturating_sub(1).min(6));
-                        if delay > queue_cfg.retry_max_secs {
-                            delay = queue_cfg.retry_max_secs;
-                        }
+                        let delay = i_miss_rust::util::backoff::jittered_retry_secs(
+                            queue_cfg.retry_base_secs,
+                            queue_cfg.retry_max_secs,
+                            attempt,
+                            &mut rand::thread_rng(),
+                        );
 
                         // If attempts exceed max_retries, archive; else set a future VT to delay re-delivery
                         if queue_cfg.max_retries > 0 && attempt > queue_cfg.max_retries {
@@ -894,6 +938,19 @@ async fn set_job_vt(db: &Db, queue_cfg: &QueueConfig, msg_id: i64, vt_secs: i32)
 
 /* ------------------------- Ingest pipeline ------------------------- */
 
+/// Run the ingest for each watchlist title in turn. A failing title is logged and the rest
+/// still run; returns the titles that failed.
+async fn ingest_titles(db: &Db, titles: &[String], regions: &[String]) -> Vec<String> {
+    let mut failed = Vec::new();
+    for title in titles {
+        if let Err(e) = run_ingest(db, title, regions).await {
+            eprintln!("[ps_long_test] ingest error title={title}: {e:?}");
+            failed.push(title.clone());
+        }
+    }
+    failed
+}
+
 async fn run_ingest(db: &Db, title: &str, regions: &[String]) -> Result<()> {
     if regions.is_empty() {
         eprintln!("[ps_long_test] no regions configured");
@@ -1047,6 +1104,11 @@ async fn find_product_for_locale(
     start_page: u32,
     page_depth: u32,
 ) -> Result<Option<PsProductSummary>> {
+    // Fuzzy match: canonical titles (no ®/™, edition or platform suffixes) compared with
+    // Jaro-Winkler; the best candidate at or above PS_LONG_MATCH_THRESHOLD wins.
+    let threshold: f64 = env_util::env_parse("PS_LONG_MATCH_THRESHOLD", MIN_TITLE_SIMILARITY);
+    let target = TitleKey::new(title);
+    let mut best: Option<(f64, PsProductSummary)> = None;
     for cat in [cat_ps5, cat_ps4] {
         for page in start_page..start_page + page_depth {
             let offset = page * page_size;
@@ -1055,15 +1117,27 @@ async fn find_product_for_locale(
                 .await
                 .unwrap_or_default();
             for it in list {
-                if let Some(name) = it.name.as_ref() {
-                    if normalize_title(name) == normalize_title(title) {
-                        return Ok(Some(it));
-                    }
+                let Some(name) = it.name.as_ref() else {
+                    continue;
+                };
+                let score = target.similarity(&TitleKey::new(name));
+                if score >= 1.0 {
+                    println!("[ps_long_test] {locale}: exact match \"{name}\" for \"{title}\"");
+                    return Ok(Some(it));
+                }
+                if score >= threshold && best.as_ref().is_none_or(|(s, _)| score > *s) {
+                    best = Some((score, it));
                 }
             }
         }
     }
-    Ok(None)
+    if let Some((score, it)) = &best {
+        println!(
+            "[ps_long_test] {locale}: fuzzy match \"{}\" for \"{title}\" score={score:.3}",
+            it.name.as_deref().unwrap_or_default()
+        );
+    }
+    Ok(best.map(|(_, it)| it))
 }
 
 async fn fetch_locale_once(
@@ -1114,6 +1188,14 @@ async fn materialize(
     results: &HashMap<String, LocaleFetchResult>,
     regions: &[String],
 ) -> Result<()> {
+    let allow_synthetic = env_util::env_flag("PS_LONG_ALLOW_SYNTHETIC", false);
+    if !should_materialize(results, allow_synthetic) {
+        println!(
+            "[ps_long_test] no product matched for \"{title}\"; skipping writes (set PS_LONG_ALLOW_SYNTHETIC=1 to seed synthetic proof rows)"
+        );
+        return Ok(());
+    }
+
     let slug = normalize_title(title);
     // Ensure base entities
     let ps5_platform_id = ensure_platform(db, "PS5", Some("ps5")).await?;
@@ -1228,8 +1310,11 @@ async fn materialize(
         }
     }
 
-    // If nothing matched, seed synthetic price rows (proof path)
-    if price_rows.is_empty() {
+    // If nothing matched, seed synthetic price rows (proof path; PS_LONG_ALLOW_SYNTHETIC=1 only)
+    if price_rows.is_empty() && allow_synthetic {
+        eprintln!(
+            "[ps_long_test] WARNING: seeding SYNTHETIC price rows and a 'fallback' rating for \"{title}\" (PS_LONG_ALLOW_SYNTHETIC=1); do not run this against production"
+        );
         let now = Utc::now();
         for (oj_id, loc) in offer_juris_to_region.iter() {
             let amt = match loc.as_str() {
@@ -1245,7 +1330,7 @@ async fn materialize(
                 tax_inclusive: true,
                 fx_minor_per_unit: None,
                 btc_sats_per_unit: None,
-                meta: json!({"src":"psstore","kind":"base","locale":loc}),
+                meta: json!({"src":"psstore","kind":"base","locale":loc,"synthetic":true}),
                 video_game_id: Some(vg_ps5),
                 currency: None,
                 country_code: Some(loc.split('-').nth(1).unwrap_or("us").to_uppercase()),
@@ -1259,7 +1344,7 @@ async fn materialize(
                 tax_inclusive: true,
                 fx_minor_per_unit: None,
                 btc_sats_per_unit: None,
-                meta: json!({"src":"psstore","kind":"discount","locale":loc}),
+                meta: json!({"src":"psstore","kind":"discount","locale":loc,"synthetic":true}),
                 video_game_id: Some(vg_ps5),
                 currency: None,
                 country_code: Some(loc.split('-').nth(1).unwrap_or("us").to_uppercase()),
@@ -1271,7 +1356,7 @@ async fn materialize(
                 "INSERT INTO public.video_game_ratings_by_locale (video_game_id, locale, average_rating, rating_count, rating_updated_at) VALUES ($1,$2,$3,$4, now()) ON CONFLICT (video_game_id, locale) DO UPDATE SET average_rating=EXCLUDED.average_rating, rating_count=EXCLUDED.rating_count, rating_updated_at=now()"
             )
             .bind(vg_ps5)
-            .bind("fallback")
+            .bind(SYNTHETIC_RATING_LOCALE)
             .bind(4.6f32)
             .bind(3251i64)
             .execute(&db.pool).await?;
@@ -1412,6 +1497,25 @@ async fn materialize(
 
 /* ------------------------- Misc helpers ------------------------- */
 
+/// Whether `materialize` should write anything: real matches always do; a run with no
+/// matched product only writes when synthetic proof rows are explicitly allowed.
+fn should_materialize(results: &HashMap<String, LocaleFetchResult>, allow_synthetic: bool) -> bool {
+    allow_synthetic
+        || results
+            .values()
+            .any(|r| r.product.as_ref().is_some_and(|p| p.product_id.is_some()))
+}
+
+/// Whether the scheduler runs once on startup. PS_LONG_RUN_ON_START matches the
+/// <PROVIDER>_RUN_ON_START flags of the main service; PS_LONG_IMMEDIATE is the legacy
+/// spelling. Both use the standard 1/true/yes/on parsing and default to true when unset.
+fn resolve_immediate(run_on_start: Option<&str>, legacy_immediate: Option<&str>) -> bool {
+    run_on_start
+        .or(legacy_immediate)
+        .map(env_util::parse_flag)
+        .unwrap_or(true)
+}
+
 fn normalize_title(s: &str) -> String {
     s.to_lowercase()
         .replace(|c: char| !c.is_ascii_alphanumeric(), "-")
@@ -1419,6 +1523,29 @@ fn normalize_title(s: &str) -> String {
         .to_string()
 }
 
+/// Watchlist of titles: PS_LONG_TITLES as a JSON array or comma-separated list, else the
+/// single PS_LONG_TITLE, else "NBA 2K26". Blank and duplicate entries are dropped.
+fn load_titles(titles_raw: Option<&str>, single: Option<&str>) -> Vec<String> {
+    let parsed: Vec<String> = match titles_raw.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(raw) if raw.starts_with('[') => {
+            serde_json::from_str::<Vec<String>>(raw).unwrap_or_default()
+        }
+        Some(raw) => raw.split(',').map(str::to_string).collect(),
+        None => Vec::new(),
+    };
+    let mut titles: Vec<String> = Vec::new();
+    for t in parsed.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !titles.iter().any(|seen| seen == t) {
+            titles.push(t.to_string());
+        }
+    }
+    if titles.is_empty() {
+        let single = single.map(str::trim).filter(|s| !s.is_empty());
+        titles.push(single.unwrap_or("NBA 2K26").to_string());
+    }
+    titles
+}
+
 fn load_regions() -> Vec<String> {
     let raw = env::var("PS_STORE_REGIONS").unwrap_or_else(|_| "en-us en-gb de-de".into());
     raw.split(|c: char| (c == ',' || c == ' '))
@@ -1491,10 +1618,125 @@ async fn ensure_national_jurisdiction(db: &Db, country_id: i64) -> Result<i64> {
     Ok(rec.get("id"))
 }
 
-fn currency_minor_unit(code: &str) -> i16 {
-    match code.to_ascii_uppercase().as_str() {
-        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "HUF" => 0,
-        "BHD" | "IQD" | "KWD" | "JOD" | "OMR" | "TND" => 3,
-        _ => 2,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn immediate_accepts_standard_truthy_values() {
+        assert!(resolve_immediate(None, Some("1")));
+        assert!(resolve_immediate(None, Some("yes")));
+        assert!(resolve_immediate(None, Some("TRUE")));
+        assert!(!resolve_immediate(None, Some("45")));
+        assert!(!resolve_immediate(None, Some("0")));
+        assert!(resolve_immediate(None, None));
+    }
+
+    #[test]
+    fn titles_from_list_json_or_single() {
+        assert_eq!(
+            load_titles(Some("NBA 2K26, EA SPORTS FC 26,,NBA 2K26"), Some("ignored")),
+            vec!["NBA 2K26", "EA SPORTS FC 26"]
+        );
+        assert_eq!(
+            load_titles(Some(r#"["Astro Bot", "Ghost of Yotei"]"#), None),
+            vec!["Astro Bot", "Ghost of Yotei"]
+        );
+        assert_eq!(load_titles(None, Some("Astro Bot")), vec!["Astro Bot"]);
+        assert_eq!(load_titles(Some("  "), None), vec!["NBA 2K26"]);
+    }
+
+    #[test]
+    fn no_match_writes_nothing_without_synthetic_flag() {
+        let mut results = HashMap::new();
+        results.insert(
+            "en-us".to_string(),
+            LocaleFetchResult {
+                product: None,
+                rating: None,
+            },
+        );
+        assert!(!should_materialize(&results, false));
+        assert!(!should_materialize(&HashMap::new(), false));
+        assert!(should_materialize(&results, true));
+    }
+
+    #[test]
+    fn run_on_start_overrides_legacy_flag() {
+        assert!(!resolve_immediate(Some("0"), Some("1")));
+        assert!(resolve_immediate(Some("on"), Some("0")));
+    }
+
+    #[tokio::test]
+    async fn every_watchlist_title_is_fetched_from_the_store() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        const GRID: &str = r#"{"data":{"categoryGridRetrieve":{"products":[
+            {"id":"UP9000-PPSA00001_00","name":"Astro Bot"},
+            {"id":"UP9000-PPSA00002_00","name":"Ghost of Yotei"}
+        ]}}}"#;
+
+        // Serves the grid for every category page and records each request head.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::<String>::new()));
+        let seen = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut head = Vec::new();
+                let mut buf = [0u8; 4096];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match sock.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let head = String::from_utf8_lossy(&head).to_ascii_lowercase();
+                let body = if head.contains("x-apollo-operation-name: categorygridretrieve") {
+                    GRID
+                } else {
+                    r#"{"data":{}}"#
+                };
+                seen.lock().unwrap().push(head);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = sock.write_all(response.as_bytes()).await;
+            }
+        });
+        // The only test in this binary that sets env vars.
+        for (key, value) in [
+            ("PS_BASE_URL", base_url.as_str()),
+            ("PS_STORE_REGIONS", "en-us"),
+            ("PS_IPV6_ONLY", "0"),
+            ("PS_PAGE_START", "0"),
+            ("PS_PAGE_DEPTH", "1"),
+            ("PS_STORE_MAX_RETRIES", "0"),
+        ] {
+            env::set_var(key, value);
+        }
+        // Nothing listens here, so each matched title fails at its first write.
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(Duration::from_secs(1))
+            .connect_lazy("postgres://ps-long@127.0.0.1:1/none")
+            .unwrap();
+        let db = Db { pool };
+        let titles = load_titles(Some("Astro Bot, Ghost of Yotei"), None);
+
+        let failed = ingest_titles(&db, &titles, &["en-us".to_string()]).await;
+
+        // Both titles ran: each matched its own product and looked up its rating, and the
+        // first one failing to write did not stop the second.
+        assert_eq!(failed, titles);
+        let requests = requests.lock().unwrap();
+        for product_id in ["up9000-ppsa00001_00", "up9000-ppsa00002_00"] {
+            assert!(
+                requests
+                    .iter()
+                    .any(|head| !head.contains("categorygridretrieve") && head.contains(product_id)),
+                "no rating lookup for {product_id}"
+            );
+        }
     }
 }
@@ -26,6 +26,10 @@ async fn main() -> anyhow::Result<()> {
         store_map: Default::default(),
         base_url: None,
         timeout: None,
+        max_retries: None,
+        backoff_ms: None,
+        reqs_per_min: None,
+        rps: None,
         api_key: None,
         auto_register_stores: None,
         default_regions: vec![],
@@ -127,6 +127,10 @@ async fn main() -> anyhow::Result<()> {
         store_map: Default::default(),
         base_url: None,
         timeout: Some(30),
+        max_retries: None,
+        backoff_ms: None,
+        reqs_per_min: None,
+        rps: None,
         api_key,
         auto_register_stores: Some(true),
         default_regions: vec![],
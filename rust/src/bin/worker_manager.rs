@@ -2,6 +2,7 @@ use actix_web::{web, App, HttpResponse, HttpServer, Responder};
 use anyhow::{anyhow, Context, Result};
 use dotenv::dotenv; // kept for local use, but we call through env_boot::ensure_dotenv()
 use futures::future::join_all;
+use i_miss_rust::orchestrator::{supervise, SupervisorConfig};
 use i_miss_rust::util::env as env_util;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -9,6 +10,7 @@ use serde_json::json;
 use std::collections::HashMap;
 use std::env;
 use std::process::Stdio;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use tokio::process::Command;
 use tokio::time::{sleep, Duration};
@@ -33,6 +35,46 @@ impl WorkerSpec {
     }
 }
 
+/// Parse `MANAGER_WORKERS` entries of the form `name:port` (queue = name) or
+/// `name:port:queue`. Several workers may share a queue. Entries with an invalid port or
+/// queue name are skipped with a warning; missing queues are created by each worker's
+/// `ensure_queue` on startup.
+fn parse_worker_specs(raw: &str) -> Vec<WorkerSpec> {
+    let mut specs: Vec<WorkerSpec> = Vec::new();
+    for part in raw.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let mut fields = part.splitn(3, ':').map(str::trim);
+        let (Some(name), Some(port_str)) = (fields.next(), fields.next()) else {
+            eprintln!("[manager] ignoring worker entry without port: {part}");
+            continue;
+        };
+        let queue = fields.next().filter(|q| !q.is_empty()).unwrap_or(name);
+        let port: u16 = port_str.parse().unwrap_or(0);
+        if port == 0 {
+            eprintln!("[manager] ignoring worker entry with invalid port: {part}");
+            continue;
+        }
+        if !is_valid_queue_name(queue) {
+            eprintln!("[manager] ignoring worker entry with invalid queue name: {part}");
+            continue;
+        }
+        if specs.iter().any(|s| s.name == name) {
+            eprintln!("[manager] ignoring duplicate worker name: {part}");
+            continue;
+        }
+        specs.push(WorkerSpec::from_tuple(name, queue, port));
+    }
+    specs
+}
+
+/// pgmq queue names become table suffixes (`q_<name>`), so restrict them to identifiers.
+fn is_valid_queue_name(queue: &str) -> bool {
+    !queue.is_empty()
+        && queue.len() <= 47
+        && queue
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
 #[derive(Default)]
 struct ProcHandle {
     child: Option<tokio::process::Child>,
@@ -41,18 +83,28 @@ struct ProcHandle {
 struct ManagerState {
     specs: Vec<WorkerSpec>,
     procs: Mutex<HashMap<String, ProcHandle>>, // name -> process handle
+    restarts: Mutex<HashMap<String, Arc<AtomicU32>>>, // name -> supervisor restart count
     http: Client,
 }
 
 impl ManagerState {
     fn new(specs: Vec<WorkerSpec>) -> Self {
+        // Workers guard their control endpoints with HTTP_API_TOKEN; send it on every call.
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(token) = env_util::env_opt("HTTP_API_TOKEN") {
+            if let Ok(value) = reqwest::header::HeaderValue::from_str(&format!("Bearer {token}")) {
+                headers.insert(reqwest::header::AUTHORIZATION, value);
+            }
+        }
         let http = Client::builder()
             .timeout(Duration::from_secs(5))
+            .default_headers(headers)
             .build()
             .unwrap();
         Self {
             specs,
             procs: Mutex::new(HashMap::new()),
+            restarts: Mutex::new(HashMap::new()),
             http,
         }
     }
@@ -60,6 +112,15 @@ impl ManagerState {
     fn find_spec(&self, name: &str) -> Option<WorkerSpec> {
         self.specs.iter().find(|s| s.name == name).cloned()
     }
+
+    fn restart_count(&self, name: &str) -> u32 {
+        self.restarts
+            .lock()
+            .unwrap()
+            .get(name)
+            .map(|c| c.load(Ordering::SeqCst))
+            .unwrap_or(0)
+    }
 }
 
 #[derive(Serialize)]
@@ -68,10 +129,19 @@ struct WorkerSnapshot {
     queue: String,
     addr: String,
     running: bool,
+    restarts: u32,
     info: serde_json::Value,
     metrics: serde_json::Value,
 }
 
+#[derive(Serialize)]
+struct WorkerHealth {
+    name: String,
+    queue: String,
+    addr: String,
+    alive: bool,
+}
+
 /// Library entrypoint: run the worker manager with env-configured settings.
 pub async fn run_from_env() -> Result<()> {
     i_miss_rust::env_boot::ensure_dotenv();
@@ -85,24 +155,12 @@ pub async fn run_from_env() -> Result<()> {
         .try_init();
 
     // Parse worker set: MANAGER_WORKERS="default_ingest:9025,psstore_ingest:9081,igdb_catalog:9082,gb_catalog:9083,steam_ingest:9084,itad_pricing:9085,nexarda_ingest:9086,xbox_ingest:9087"
+    // Entries may also be `name:port:queue` to point a worker at an explicit queue.
     let workers_env = env::var("MANAGER_WORKERS").unwrap_or_else(|_| {
         // Sensible defaults covering our main providers; override via env as needed.
         "default_ingest:9025,psstore_ingest:9081,igdb_catalog:9082,gb_catalog:9083,steam_ingest:9084,itad_pricing:9085,nexarda_ingest:9086,xbox_ingest:9087,rawg_ingest:9088,tgdb_ingest:9089".to_string()
     });
-    let mut specs: Vec<WorkerSpec> = Vec::new();
-    for part in workers_env
-        .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-    {
-        if let Some((queue, port_str)) = part.split_once(':') {
-            let port: u16 = port_str.parse().unwrap_or(0);
-            if port > 0 {
-                let name = queue.to_string();
-                specs.push(WorkerSpec::from_tuple(&name, queue, port));
-            }
-        }
-    }
+    let mut specs = parse_worker_specs(&workers_env);
     if specs.is_empty() {
         specs.push(WorkerSpec::from_tuple(
             "default_ingest",
@@ -130,17 +188,26 @@ pub async fn run_from_env() -> Result<()> {
                 {
                     continue;
                 }
-                // spawn worker process similar to start_worker
-                let mut cmd = Command::new("target/debug/ingest_worker");
-                cmd.env("INGEST_QUEUE_NAME", &spec.queue)
-                    .env("INGEST_NOTIFY_CHANNEL", &spec.notify_channel)
-                    .env("WORKER_HTTP_ADDR", &spec.addr)
-                    .stdout(Stdio::null())
-                    .stderr(Stdio::null())
-                    .kill_on_drop(true);
-                let _ = cmd.spawn();
-                // no insertion into procs map here to keep this fire-and-forget
-                // clients can still manage via /start which updates the handle map
+                // spawn worker process similar to start_worker, restarted with backoff on
+                // exit (WORKER_MAX_RESTARTS). Not inserted into the procs map; clients can
+                // still manage via /start which updates the handle map.
+                let worker = spec.clone();
+                let supervised =
+                    supervise(spec.name.clone(), SupervisorConfig::from_env(), move || {
+                        let mut cmd = Command::new("target/debug/ingest_worker");
+                        cmd.env("INGEST_QUEUE_NAME", &worker.queue)
+                            .env("INGEST_NOTIFY_CHANNEL", &worker.notify_channel)
+                            .env("WORKER_HTTP_ADDR", &worker.addr)
+                            .stdout(Stdio::null())
+                            .stderr(Stdio::null())
+                            .kill_on_drop(true);
+                        cmd.spawn().context("spawn ingest_worker")
+                    });
+                state_clone
+                    .restarts
+                    .lock()
+                    .unwrap()
+                    .insert(spec.name.clone(), supervised.restarts);
                 sleep(Duration::from_millis(50)).await;
             }
 
@@ -312,37 +379,13 @@ pub async fn run_from_env() -> Result<()> {
         .filter(|s| !s.is_empty())
         .unwrap_or_else(|| "127.0.0.1:9090".to_string());
     println!("[multiworker] manager listening on {}", addr);
+    // The manager forwards HTTP_API_TOKEN to the workers, so it must demand it itself.
+    let api_token = i_miss_rust::api::auth::api_token_from_env();
     HttpServer::new(move || {
         App::new()
+            .wrap(i_miss_rust::api::auth::write_auth(api_token.as_deref()))
             .app_data(state.clone())
-            .route(
-                "/",
-                web::get().to(|| async { HttpResponse::Ok().body("manager-ok") }),
-            )
-            .route("/manager/workers", web::get().to(list_workers))
-            .route(
-                "/manager/workers/{name}/start",
-                web::post().to(start_worker),
-            )
-            .route("/manager/workers/{name}/stop", web::post().to(stop_worker))
-            .route(
-                "/manager/workers/{name}/restart",
-                web::post().to(restart_worker),
-            )
-            .route(
-                "/manager/workers/{name}/logs",
-                web::get().to(get_worker_logs),
-            )
-            .route("/manager/logs", web::get().to(get_all_logs))
-            .route("/manager/enqueue", web::post().to(enqueue_via_worker))
-            .route(
-                "/manager/enqueue_by_provider",
-                web::post().to(enqueue_by_provider),
-            )
-            .route(
-                "/manager/enqueue_by_provider",
-                web::get().to(enqueue_by_provider_get),
-            )
+            .configure(manager_routes)
     })
     .bind(addr)?
     .run()
@@ -350,6 +393,43 @@ pub async fn run_from_env() -> Result<()> {
     .context("manager http")
 }
 
+fn manager_routes(cfg: &mut web::ServiceConfig) {
+    cfg.route(
+        "/",
+        web::get().to(|| async { HttpResponse::Ok().body("manager-ok") }),
+    )
+    .route("/manager/workers", web::get().to(list_workers))
+    .route("/manager/workers/health", web::get().to(list_worker_health))
+    .route(
+        "/manager/workers/{name}/pause",
+        web::post().to(pause_worker),
+    )
+    .route(
+        "/manager/workers/{name}/resume",
+        web::post().to(resume_worker),
+    )
+    .route(
+        "/manager/workers/{name}/start",
+        web::post().to(start_worker),
+    )
+    .route("/manager/workers/{name}/stop", web::post().to(stop_worker))
+    .route(
+        "/manager/workers/{name}/restart",
+        web::post().to(restart_worker),
+    )
+    .route(
+        "/manager/workers/{name}/logs",
+        web::get().to(get_worker_logs),
+    )
+    .route("/manager/logs", web::get().to(get_all_logs))
+    .route("/manager/metrics", web::get().to(get_manager_metrics))
+    .route("/manager/enqueue", web::post().to(enqueue_via_worker))
+    .route(
+        "/manager/enqueue_by_provider",
+        web::post().to(enqueue_by_provider),
+    );
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     env_util::bootstrap_cli("worker_manager");
@@ -366,6 +446,7 @@ async fn list_workers(state: web::Data<Arc<ManagerState>>) -> impl Responder {
             queue: spec.queue.clone(),
             addr: spec.addr.clone(),
             running,
+            restarts: state.restart_count(&spec.name),
             info,
             metrics,
         });
@@ -373,6 +454,89 @@ async fn list_workers(state: web::Data<Arc<ManagerState>>) -> impl Responder {
     HttpResponse::Ok().json(out)
 }
 
+// GET /manager/metrics — supervisor restart counts per configured worker
+async fn get_manager_metrics(state: web::Data<Arc<ManagerState>>) -> impl Responder {
+    let restarts: HashMap<String, u32> = state
+        .specs
+        .iter()
+        .map(|s| (s.name.clone(), state.restart_count(&s.name)))
+        .collect();
+    let total: u32 = restarts.values().sum();
+    HttpResponse::Ok().json(json!({"ok": true, "restarts": restarts, "total_restarts": total}))
+}
+
+// GET /manager/workers/health — lightweight liveness via each worker's `/` route
+async fn list_worker_health(state: web::Data<Arc<ManagerState>>) -> impl Responder {
+    HttpResponse::Ok().json(collect_worker_health(&state.http, &state.specs).await)
+}
+
+async fn collect_worker_health(http: &Client, specs: &[WorkerSpec]) -> Vec<WorkerHealth> {
+    let checks = specs.iter().map(|spec| async move {
+        WorkerHealth {
+            name: spec.name.clone(),
+            queue: spec.queue.clone(),
+            addr: spec.addr.clone(),
+            alive: ping_worker(http, &spec.addr).await,
+        }
+    });
+    join_all(checks).await
+}
+
+async fn ping_worker(http: &Client, addr: &str) -> bool {
+    match http.get(format!("http://{}/", addr)).send().await {
+        Ok(r) => r.status().is_success(),
+        Err(_) => false,
+    }
+}
+
+/// Forward a control action (`pause` / `resume`) to the worker's `/api/{action}` route.
+async fn proxy_worker_control(
+    http: &Client,
+    spec: &WorkerSpec,
+    action: &str,
+) -> Result<serde_json::Value> {
+    let resp = http
+        .post(format!("http://{}/api/{}", spec.addr, action))
+        .send()
+        .await
+        .with_context(|| format!("worker {} unreachable", spec.name))?;
+    if !resp.status().is_success() {
+        return Err(anyhow!(
+            "worker {} returned {} for {}",
+            spec.name,
+            resp.status(),
+            action
+        ));
+    }
+    Ok(resp.json::<serde_json::Value>().await?)
+}
+
+async fn control_worker(name: &str, action: &str, state: &ManagerState) -> HttpResponse {
+    let Some(spec) = state.find_spec(name) else {
+        return HttpResponse::NotFound().json(json!({"ok": false, "error":"unknown worker"}));
+    };
+    match proxy_worker_control(&state.http, &spec, action).await {
+        Ok(v) => HttpResponse::Ok().json(json!({"name": spec.name, "worker": v})),
+        Err(e) => HttpResponse::BadGateway().json(json!({"ok": false, "error": e.to_string()})),
+    }
+}
+
+// POST /manager/workers/{name}/pause
+async fn pause_worker(
+    path: web::Path<(String,)>,
+    state: web::Data<Arc<ManagerState>>,
+) -> impl Responder {
+    control_worker(&path.0, "pause", &state).await
+}
+
+// POST /manager/workers/{name}/resume
+async fn resume_worker(
+    path: web::Path<(String,)>,
+    state: web::Data<Arc<ManagerState>>,
+) -> impl Responder {
+    control_worker(&path.0, "resume", &state).await
+}
+
 async fn query_worker(http: &Client, base: &str) -> (bool, serde_json::Value, serde_json::Value) {
     let info = http.get(format!("{}/api/info", base)).send().await;
     let running = info.is_ok();
@@ -621,60 +785,6 @@ async fn enqueue_by_provider(
     }
 }
 
-// GET version for tooling symmetry: /manager/enqueue_by_provider?provider=itad&task=prices_scan&args=%7B...%7D&provider_id=123
-async fn enqueue_by_provider_get(
-    query: web::Query<HashMap<String, String>>,
-    state: web::Data<Arc<ManagerState>>,
-) -> impl Responder {
-    let provider = match query.get("provider").cloned() {
-        Some(p) => p,
-        None => {
-            return HttpResponse::BadRequest()
-                .json(json!({"ok": false, "error": "missing provider"}));
-        }
-    };
-    let task = match query.get("task").cloned() {
-        Some(t) => t,
-        None => {
-            return HttpResponse::BadRequest().json(json!({"ok": false, "error": "missing task"}));
-        }
-    };
-    let args_val = query
-        .get("args")
-        .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok());
-    let provider_id = query.get("provider_id").and_then(|s| s.parse::<i64>().ok());
-    let Some(worker_name) = provider_to_worker_name(&provider) else {
-        return HttpResponse::BadRequest()
-            .json(json!({"ok": false, "error": "unknown provider -> worker mapping"}));
-    };
-    let Some(spec) = state.find_spec(worker_name) else {
-        return HttpResponse::NotFound()
-            .json(json!({"ok": false, "error": "worker not configured for provider"}));
-    };
-    let base = format!("http://{}", spec.addr);
-    let resp = state
-        .http
-        .post(format!("{}/api/enqueue", base))
-        .json(&json!({
-            "provider": provider,
-            "task": task,
-            "args": args_val,
-            "provider_id": provider_id,
-        }))
-        .send()
-        .await;
-    match resp {
-        Ok(r) => match r.json::<serde_json::Value>().await {
-            Ok(v) => HttpResponse::Ok().json(v),
-            Err(e) => HttpResponse::InternalServerError()
-                .json(json!({"ok": false, "error": e.to_string()})),
-        },
-        Err(e) => {
-            HttpResponse::InternalServerError().json(json!({"ok": false, "error": e.to_string()}))
-        }
-    }
-}
-
 async fn get_worker_logs(
     path: web::Path<(String,)>,
     query: web::Query<HashMap<String, String>>,
@@ -787,3 +897,131 @@ async fn restart_worker(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Start a stub worker exposing `/` and `/api/pause`; returns its bound addr.
+    fn spawn_stub_worker(paused: Arc<AtomicBool>) -> String {
+        let server = HttpServer::new(move || {
+            let paused = paused.clone();
+            App::new()
+                .route(
+                    "/",
+                    web::get().to(|| async { HttpResponse::Ok().body("ok") }),
+                )
+                .route(
+                    "/api/pause",
+                    web::post().to(move || {
+                        let paused = paused.clone();
+                        async move {
+                            paused.store(true, Ordering::SeqCst);
+                            HttpResponse::Ok().json(json!({"ok": true, "paused": true}))
+                        }
+                    }),
+                )
+        })
+        .workers(1)
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let addr = server.addrs()[0].to_string();
+        actix_web::rt::spawn(server.run());
+        addr
+    }
+
+    fn spec_at(name: &str, addr: &str) -> WorkerSpec {
+        WorkerSpec {
+            name: name.to_string(),
+            queue: name.to_string(),
+            notify_channel: name.to_string(),
+            addr: addr.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_legacy_and_explicit_queue_entries() {
+        let specs = parse_worker_specs(
+            "default_ingest:9025, ps_a:9081:psstore_ingest,ps_b:9082:psstore_ingest,bad:x,Bad_Q:9083:Bad-Q",
+        );
+        let got: Vec<(&str, &str, &str)> = specs
+            .iter()
+            .map(|s| (s.name.as_str(), s.queue.as_str(), s.addr.as_str()))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                ("default_ingest", "default_ingest", "127.0.0.1:9025"),
+                ("ps_a", "psstore_ingest", "127.0.0.1:9081"),
+                ("ps_b", "psstore_ingest", "127.0.0.1:9082"),
+            ]
+        );
+        assert_eq!(specs[1].notify_channel, "psstore_ingest");
+    }
+
+    #[actix_web::test]
+    async fn lists_workers_and_proxies_pause() {
+        let paused_a = Arc::new(AtomicBool::new(false));
+        let paused_b = Arc::new(AtomicBool::new(false));
+        let addr_a = spawn_stub_worker(paused_a.clone());
+        let addr_b = spawn_stub_worker(paused_b.clone());
+        let state = ManagerState::new(vec![
+            spec_at("alpha", &addr_a),
+            spec_at("beta", &addr_b),
+            // nothing listens on port 1
+            spec_at("gamma", "127.0.0.1:1"),
+        ]);
+
+        let health = collect_worker_health(&state.http, &state.specs).await;
+        let alive: Vec<(&str, bool)> = health.iter().map(|h| (h.name.as_str(), h.alive)).collect();
+        assert_eq!(
+            alive,
+            vec![("alpha", true), ("beta", true), ("gamma", false)]
+        );
+        assert_eq!(health[0].addr, addr_a);
+
+        let resp = control_worker("beta", "pause", &state).await;
+        assert!(resp.status().is_success());
+        assert!(paused_b.load(Ordering::SeqCst));
+        assert!(!paused_a.load(Ordering::SeqCst));
+
+        let missing = control_worker("nope", "pause", &state).await;
+        assert_eq!(missing.status(), actix_web::http::StatusCode::NOT_FOUND);
+    }
+
+    #[actix_web::test]
+    async fn control_routes_require_the_api_token() {
+        let paused = Arc::new(AtomicBool::new(false));
+        let addr = spawn_stub_worker(paused.clone());
+        let state = ManagerState::new(vec![spec_at("alpha", &addr)]);
+        let app = actix_web::test::init_service(
+            App::new()
+                .wrap(i_miss_rust::api::auth::write_auth(Some("secret")))
+                .app_data(web::Data::new(Arc::new(state)))
+                .configure(manager_routes),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::post()
+            .uri("/manager/workers/alpha/pause")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), actix_web::http::StatusCode::UNAUTHORIZED);
+        assert!(!paused.load(Ordering::SeqCst));
+
+        let req = actix_web::test::TestRequest::post()
+            .uri("/manager/workers/alpha/pause")
+            .insert_header(("Authorization", "Bearer secret"))
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+        assert!(paused.load(Ordering::SeqCst));
+
+        let req = actix_web::test::TestRequest::get()
+            .uri("/manager/workers")
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert!(resp.status().is_success());
+    }
+}
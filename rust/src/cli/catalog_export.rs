@@ -0,0 +1,374 @@
+// Full-catalog NDJSON export
+//
+// Streams every video game as one JSON line: the API game profile (title, genres, media,
+// ratings, current prices) plus platforms and the primary cover. Games are read in id
+// order in bounded batches, each assembled in a fixed number of queries, and the file is
+// flushed after each batch, so an interrupted export can be resumed from the last complete
+// line (found by reading only the tail of the file).
+
+use crate::api::models::{GameProfile, ProfileMedia};
+use crate::api::profile::game_profiles;
+use crate::database_ops::db::Db;
+use crate::database_ops::media_primary::primary_media_column_present;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use tracing::info;
+
+/// One exported line.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CatalogRecord {
+    #[serde(flatten)]
+    pub profile: GameProfile,
+    /// Platform names of every video game sharing this game's title.
+    pub platforms: Vec<String>,
+    pub primary_media: Option<ProfileMedia>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CatalogExportConfig {
+    /// Optional override for the database URL.
+    pub database_url: Option<String>,
+    /// Output file; falls back to `CATALOG_EXPORT_PATH`. Nothing is written without one.
+    pub path: Option<PathBuf>,
+    /// Games fetched per batch (default 500).
+    pub batch_size: i64,
+    /// Stop after this many games.
+    pub limit: Option<u64>,
+    /// Append after the last complete line of an existing file instead of truncating it.
+    pub resume: bool,
+    /// Only export games with an id greater than this (ignored when resuming).
+    pub after_id: Option<i64>,
+    /// ISO country codes limiting prices/ratings, as for the profile endpoint.
+    pub regions: Vec<String>,
+}
+
+impl Default for CatalogExportConfig {
+    fn default() -> Self {
+        Self {
+            database_url: None,
+            path: None,
+            batch_size: 500,
+            limit: None,
+            resume: false,
+            after_id: None,
+            regions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CatalogExportReport {
+    pub written: u64,
+    pub last_id: Option<i64>,
+}
+
+pub async fn run(cfg: CatalogExportConfig) -> Result<CatalogExportReport> {
+    crate::util::env::init_env();
+    let db_url = match cfg.database_url.clone() {
+        Some(url) => url,
+        None => crate::util::env::db_url()?,
+    };
+    let db = Db::connect_no_migrate(&db_url, 5).await?;
+    export_catalog(&db, &cfg).await
+}
+
+/// Write the catalog to `cfg.path` (or `CATALOG_EXPORT_PATH`) as NDJSON.
+pub async fn export_catalog(db: &Db, cfg: &CatalogExportConfig) -> Result<CatalogExportReport> {
+    let Some(path) = cfg
+        .path
+        .clone()
+        .or_else(|| crate::util::env::env_opt("CATALOG_EXPORT_PATH").map(PathBuf::from))
+    else {
+        bail!("catalog export: no output path (pass --path or set CATALOG_EXPORT_PATH)");
+    };
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+
+    let mut cursor = cfg.after_id.unwrap_or(0);
+    if cfg.resume && path.exists() {
+        let mut existing = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        let (valid_len, last_id) = resume_point(&mut existing, RESUME_TAIL_BYTES)?;
+        // Drop a line cut short by an interrupted run before appending.
+        existing.set_len(valid_len)?;
+        cursor = last_id.unwrap_or(cursor);
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(cfg.resume)
+        .truncate(!cfg.resume)
+        .open(&path)
+        .with_context(|| format!("opening {}", path.display()))?;
+    let mut out = BufWriter::new(file);
+
+    let has_primary_media = primary_media_column_present(db).await?;
+    let batch_size = cfg.batch_size.max(1);
+    let mut report = CatalogExportReport::default();
+    loop {
+        let ids: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM public.video_games WHERE id > $1 ORDER BY id LIMIT $2",
+        )
+        .persistent(false)
+        .bind(cursor)
+        .bind(batch_size)
+        .fetch_all(&db.pool)
+        .await?;
+        let remaining = cfg.limit.map_or(ids.len(), |limit| {
+            limit.saturating_sub(report.written) as usize
+        });
+        let ids = &ids[..ids.len().min(remaining)];
+        if ids.is_empty() {
+            break;
+        }
+        let mut platforms = platforms(db, ids).await?;
+        let mut primary_media = if has_primary_media {
+            primary_media(db, ids).await?
+        } else {
+            HashMap::new()
+        };
+        for profile in game_profiles(db, ids, &cfg.regions).await? {
+            let id = profile.video_game_id;
+            let record = CatalogRecord {
+                profile,
+                platforms: platforms.remove(&id).unwrap_or_default(),
+                primary_media: primary_media.remove(&id),
+            };
+            serde_json::to_writer(&mut out, &record)?;
+            out.write_all(b"\n")?;
+            report.written += 1;
+            report.last_id = Some(id);
+        }
+        out.flush()?;
+        cursor = ids[ids.len() - 1];
+    }
+    out.flush()?;
+
+    info!(
+        path = %path.display(),
+        written = report.written,
+        last_id = ?report.last_id,
+        "catalog export: finished"
+    );
+    Ok(report)
+}
+
+/// Bytes read from the end of an export when looking for its last complete line.
+const RESUME_TAIL_BYTES: u64 = 64 * 1024;
+
+/// Byte length of an export up to its last complete, parseable line and that line's game
+/// id. Reads only the tail of the file, doubling the window (from `window` bytes) until it
+/// holds a whole line.
+fn resume_point(file: &mut File, mut window: u64) -> Result<(u64, Option<i64>)> {
+    let len = file.metadata()?.len();
+    loop {
+        let start = len.saturating_sub(window);
+        file.seek(SeekFrom::Start(start))?;
+        let mut tail = Vec::new();
+        (&*file).take(len - start).read_to_end(&mut tail)?;
+        if let Some((end, id)) = last_complete_line(&tail, start == 0) {
+            return Ok((start + end as u64, Some(id)));
+        }
+        if start == 0 {
+            return Ok((0, None));
+        }
+        window = window.saturating_mul(2);
+    }
+}
+
+/// End offset and game id of the last newline-terminated line of `tail` that parses as an
+/// export record. Unless `from_start`, the bytes before the first newline may be the cut-off
+/// end of an earlier line and are never taken as one.
+fn last_complete_line(tail: &[u8], from_start: bool) -> Option<(usize, i64)> {
+    let mut ends: Vec<usize> = tail
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| **b == b'\n')
+        .map(|(i, _)| i + 1)
+        .collect();
+    if from_start {
+        ends.insert(0, 0);
+    }
+    ends.windows(2).rev().find_map(|pair| {
+        let id = serde_json::from_slice::<serde_json::Value>(&tail[pair[0]..pair[1]])
+            .ok()?
+            .get("video_game_id")?
+            .as_i64()?;
+        Some((pair[1], id))
+    })
+}
+
+/// Platform names of every video game sharing each game's title.
+async fn platforms(db: &Db, video_game_ids: &[i64]) -> Result<HashMap<i64, Vec<String>>> {
+    let rows = sqlx::query(
+        "SELECT DISTINCT vg.id AS video_game_id, p.name
+         FROM public.video_games vg
+         JOIN public.video_games sib ON sib.title_id = vg.title_id OR sib.id = vg.id
+         JOIN public.platforms p ON p.id = sib.platform_id
+         WHERE vg.id = ANY($1)
+         ORDER BY vg.id, p.name",
+    )
+    .persistent(false)
+    .bind(video_game_ids)
+    .fetch_all(&db.pool)
+    .await?;
+    let mut out: HashMap<i64, Vec<String>> = HashMap::new();
+    for r in rows {
+        out.entry(r.try_get("video_game_id")?)
+            .or_default()
+            .push(r.try_get("name")?);
+    }
+    Ok(out)
+}
+
+async fn primary_media(db: &Db, video_game_ids: &[i64]) -> Result<HashMap<i64, ProfileMedia>> {
+    let rows = sqlx::query(
+        "SELECT vg.id AS video_game_id, cm.url, cm.metadata->>'media_type' AS media_type,
+                cm.metadata->>'title' AS title, cm.metadata->>'source' AS source
+         FROM public.video_games vg
+         JOIN public.canonical_media cm ON cm.id = vg.primary_media_id
+         WHERE vg.id = ANY($1)",
+    )
+    .persistent(false)
+    .bind(video_game_ids)
+    .fetch_all(&db.pool)
+    .await?;
+    rows.into_iter()
+        .map(|r| -> Result<(i64, ProfileMedia)> {
+            Ok((
+                r.try_get("video_game_id")?,
+                ProfileMedia {
+                    url: r.try_get("url")?,
+                    media_type: r.try_get("media_type")?,
+                    title: r.try_get("title")?,
+                    source: r.try_get("source")?,
+                },
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    #[test]
+    fn resume_point_stops_at_a_truncated_line() {
+        let contents = "{\"video_game_id\":3}\n{\"video_game_id\":7}\n{\"video_game_id\":9,\"ti";
+        let path = std::env::temp_dir().join(format!("resume-{}.ndjson", uuid::Uuid::new_v4()));
+        std::fs::write(&path, contents).unwrap();
+        let mut file = File::open(&path).unwrap();
+        // A window shorter than one line has to grow before it finds a complete line.
+        for window in [4, RESUME_TAIL_BYTES] {
+            let (len, last) = resume_point(&mut file, window).unwrap();
+            assert_eq!(last, Some(7));
+            assert_eq!(
+                &contents[..len as usize],
+                "{\"video_game_id\":3}\n{\"video_game_id\":7}\n"
+            );
+        }
+        std::fs::write(&path, "{\"video_game_id\":3").unwrap();
+        let mut file = File::open(&path).unwrap();
+        assert_eq!(resume_point(&mut file, 4).unwrap(), (0, None));
+        std::fs::remove_file(&path).ok();
+
+        // The cut-off start of a tail window is never mistaken for a line.
+        assert_eq!(last_complete_line(b"_id\":5}\n", false), None);
+        assert_eq!(
+            last_complete_line(b"{\"video_game_id\":5}\n", true),
+            Some((20, 5))
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn exports_seeded_catalog_as_profile_lines() {
+        let db = RollbackDb::connect().await;
+        let pool = &db.pool;
+
+        let product_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.products (slug, name) VALUES ('export-test', 'Export Test')
+             RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let title_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.video_game_titles (product_id, title) VALUES ($1, 'Export Test')
+             RETURNING id",
+        )
+        .bind(product_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let vg_ids: Vec<i64> = sqlx::query_scalar(
+            "INSERT INTO public.video_games (title_id, platform_id, genres)
+             SELECT $1, id, ARRAY['Puzzle'] FROM public.platforms ORDER BY id LIMIT 2
+             RETURNING id",
+        )
+        .bind(title_id)
+        .fetch_all(pool)
+        .await
+        .unwrap();
+        assert_eq!(vg_ids.len(), 2, "needs two platforms");
+
+        let path = std::env::temp_dir().join(format!("catalog-{}.ndjson", uuid::Uuid::new_v4()));
+        let cfg = CatalogExportConfig {
+            path: Some(path.clone()),
+            batch_size: 1,
+            limit: Some(2),
+            after_id: Some(vg_ids[0] - 1),
+            ..Default::default()
+        };
+        let report = export_catalog(&db, &cfg).await.unwrap();
+        assert_eq!(report.written, 2);
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let records: Vec<CatalogRecord> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            records
+                .iter()
+                .map(|r| r.profile.video_game_id)
+                .collect::<Vec<_>>(),
+            vg_ids
+        );
+        for record in &records {
+            assert_eq!(record.profile.title.as_deref(), Some("Export Test"));
+            assert_eq!(record.profile.genres, vec!["Puzzle"]);
+            assert_eq!(record.platforms.len(), 2);
+        }
+
+        // Resuming drops a line cut short by an interrupted run.
+        let mut partial = OpenOptions::new().append(true).open(&path).unwrap();
+        partial.write_all(b"{\"video_game_id\":").unwrap();
+        drop(partial);
+        let resumed = export_catalog(
+            &db,
+            &CatalogExportConfig {
+                resume: true,
+                limit: Some(0),
+                ..cfg
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(resumed.written, 0);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), contents);
+
+        std::fs::remove_file(&path).ok();
+        db.rollback().await;
+    }
+}
@@ -1,3 +1,5 @@
+pub mod catalog_export;
 pub mod db_counts;
 pub mod db_missing_stats;
 pub mod playstation;
+pub mod purge_synthetic;
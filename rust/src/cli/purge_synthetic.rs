@@ -0,0 +1,239 @@
+// Purge of synthetic test/proof rows
+//
+// `ps_long_test` (with PS_LONG_ALLOW_SYNTHETIC=1) and `ps_demo_nba` seed made-up prices and
+// a per-locale rating when no real product matched. Those price rows carry
+// `"synthetic": true` in their meta (stored as `region_prices.raw_payload`); the rating row
+// has no meta column and uses the reserved `fallback` locale instead. `gc purge-synthetic`
+// deletes exactly those rows so a shared database can be cleaned up after test runs.
+
+use crate::database_ops::db::Db;
+use anyhow::Result;
+use serde::Serialize;
+use tracing::info;
+
+/// Meta key marking a seeded price row as synthetic.
+pub const SYNTHETIC_META_KEY: &str = "synthetic";
+/// Locale of the synthetic `video_game_ratings_by_locale` row.
+pub const SYNTHETIC_RATING_LOCALE: &str = "fallback";
+
+#[derive(Debug, Clone, Default)]
+pub struct PurgeSyntheticConfig {
+    /// Optional override for the database URL.
+    pub database_url: Option<String>,
+    /// Only count the rows that would be removed.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct PurgeSyntheticReport {
+    pub region_prices: u64,
+    pub locale_ratings: u64,
+    pub dry_run: bool,
+}
+
+pub async fn run(cfg: PurgeSyntheticConfig) -> Result<PurgeSyntheticReport> {
+    crate::util::env::init_env();
+    let db_url = match cfg.database_url.clone() {
+        Some(url) => url,
+        None => crate::util::env::db_url()?,
+    };
+    let db = Db::connect_no_migrate(&db_url, 2).await?;
+    let report = purge_synthetic(&db, cfg.dry_run).await?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(report)
+}
+
+/// Delete (or with `dry_run`, count) every synthetic row. Tables missing from this
+/// database are skipped.
+pub async fn purge_synthetic(db: &Db, dry_run: bool) -> Result<PurgeSyntheticReport> {
+    let mut report = PurgeSyntheticReport {
+        dry_run,
+        ..Default::default()
+    };
+    let mut tx = db.pool.begin().await?;
+
+    let has_region_prices: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+             SELECT 1 FROM information_schema.columns
+             WHERE table_schema = 'public' AND table_name = 'region_prices'
+               AND column_name = 'raw_payload'
+         )",
+    )
+    .persistent(false)
+    .fetch_one(&mut *tx)
+    .await?;
+    if has_region_prices {
+        let filter = "FROM public.region_prices WHERE (raw_payload::jsonb ->> $1) = 'true'";
+        report.region_prices = if dry_run {
+            sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) {filter}"))
+                .persistent(false)
+                .bind(SYNTHETIC_META_KEY)
+                .fetch_one(&mut *tx)
+                .await? as u64
+        } else {
+            sqlx::query(&format!("DELETE {filter}"))
+                .persistent(false)
+                .bind(SYNTHETIC_META_KEY)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected()
+        };
+    }
+
+    let has_locale_ratings: bool =
+        sqlx::query_scalar("SELECT to_regclass('public.video_game_ratings_by_locale') IS NOT NULL")
+            .persistent(false)
+            .fetch_one(&mut *tx)
+            .await?;
+    if has_locale_ratings {
+        let filter = "FROM public.video_game_ratings_by_locale WHERE locale = $1";
+        report.locale_ratings = if dry_run {
+            sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) {filter}"))
+                .persistent(false)
+                .bind(SYNTHETIC_RATING_LOCALE)
+                .fetch_one(&mut *tx)
+                .await? as u64
+        } else {
+            sqlx::query(&format!("DELETE {filter}"))
+                .persistent(false)
+                .bind(SYNTHETIC_RATING_LOCALE)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected()
+        };
+    }
+
+    tx.commit().await?;
+    info!(
+        region_prices = report.region_prices,
+        locale_ratings = report.locale_ratings,
+        dry_run,
+        "purge-synthetic: finished"
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database_ops::db::PriceRow;
+    use crate::database_ops::ingest_providers::{
+        ensure_country, ensure_currency, ensure_national_jurisdiction, ensure_offer,
+        ensure_offer_jurisdiction, ensure_retailer, ensure_sellable, ingest_prices,
+    };
+    use crate::test_support::RollbackDb;
+    use serde_json::json;
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn purge_removes_only_marked_rows() {
+        let db = RollbackDb::connect().await;
+        let pool = &db.pool;
+
+        let product_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.products (slug, name) VALUES ('purge-synthetic-test', 'Purge Test')
+             RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let title_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.video_game_titles (product_id, title) VALUES ($1, 'Purge Test')
+             RETURNING id",
+        )
+        .bind(product_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let vg_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.video_games (title_id, platform_id)
+             SELECT $1, id FROM public.platforms ORDER BY id LIMIT 1 RETURNING id",
+        )
+        .bind(title_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        let currency_id = ensure_currency(&db, "USD", "US Dollar", 2).await.unwrap();
+        let country_id = ensure_country(&db, "US", "United States", currency_id)
+            .await
+            .unwrap();
+        let jurisdiction_id = ensure_national_jurisdiction(&db, country_id).await.unwrap();
+        let retailer_id = ensure_retailer(&db, "PlayStation", Some("playstation"))
+            .await
+            .unwrap();
+        let sellable_id = ensure_sellable(&db, "software", product_id).await.unwrap();
+        let offer_id = ensure_offer(&db, sellable_id, retailer_id, None)
+            .await
+            .unwrap();
+        let oj_id = ensure_offer_jurisdiction(&db, offer_id, jurisdiction_id, currency_id)
+            .await
+            .unwrap();
+
+        let now = chrono::Utc::now();
+        let row = |amount_minor: i64, meta: serde_json::Value| PriceRow {
+            offer_jurisdiction_id: oj_id,
+            video_game_source_id: None,
+            recorded_at: now - chrono::Duration::seconds(amount_minor),
+            amount_minor,
+            tax_inclusive: true,
+            fx_minor_per_unit: None,
+            btc_sats_per_unit: None,
+            meta,
+            video_game_id: Some(vg_id),
+            currency: Some("USD".into()),
+            country_code: Some("US".into()),
+            retailer: None,
+        };
+        ingest_prices(
+            &db,
+            vec![
+                row(
+                    5999,
+                    json!({"src": "psstore", "kind": "base", "synthetic": true}),
+                ),
+                row(6999, json!({"src": "psstore", "kind": "base"})),
+            ],
+        )
+        .await
+        .unwrap();
+        for locale in [SYNTHETIC_RATING_LOCALE, "en-us"] {
+            sqlx::query(
+                "INSERT INTO public.video_game_ratings_by_locale
+                     (video_game_id, locale, average_rating, rating_count)
+                 VALUES ($1, $2, 4.5, 10)",
+            )
+            .bind(vg_id)
+            .bind(locale)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+
+        let counted = purge_synthetic(&db, true).await.unwrap();
+        assert!(counted.region_prices >= 1 && counted.locale_ratings >= 1);
+        let purged = purge_synthetic(&db, false).await.unwrap();
+        assert_eq!(purged.region_prices, counted.region_prices);
+        assert_eq!(purged.locale_ratings, counted.locale_ratings);
+
+        let prices: Vec<f64> = sqlx::query_scalar(
+            "SELECT fiat_amount::float8 FROM public.region_prices WHERE sku_region_id = $1",
+        )
+        .bind(oj_id)
+        .fetch_all(pool)
+        .await
+        .unwrap();
+        let locales: Vec<String> = sqlx::query_scalar(
+            "SELECT locale FROM public.video_game_ratings_by_locale WHERE video_game_id = $1",
+        )
+        .bind(vg_id)
+        .fetch_all(pool)
+        .await
+        .unwrap();
+
+        db.rollback().await;
+
+        assert_eq!(prices, vec![69.99]);
+        assert_eq!(locales, vec!["en-us"]);
+    }
+}
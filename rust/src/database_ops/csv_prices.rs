@@ -0,0 +1,480 @@
+// Generic CSV price importer
+//
+// Imports arbitrary retailer price exports: a `CsvPriceMapping` names the columns holding
+// title/platform/region/amount/currency (or is derived from the header row), rows are
+// resolved to offers through the `ensure_*` helpers and written as `PriceRow`s plus the
+// current price per offer_jurisdiction, like the GiantBomb/ITAD price guide import.
+
+use crate::database_ops::db::{CurrentPriceRow, Db, PriceRow};
+use crate::database_ops::ingest_providers::{
+    ensure_country, ensure_currency, ensure_national_jurisdiction, ensure_provider,
+    ensure_retailer, link_provider_offer, ProviderEntityCache,
+};
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use tracing::{debug, info};
+
+const FLUSH_ROWS: usize = 4000;
+const CP_AGENT: &str = "csv_import";
+const CP_PRIORITY: i16 = 20;
+
+/// Which CSV columns carry which field. Column names are matched case-insensitively.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CsvPriceMapping {
+    pub title: String,
+    pub amount: String,
+    pub platform: Option<String>,
+    pub region: Option<String>,
+    pub currency: Option<String>,
+    /// Stable row id from the export; the title/platform/region is used when absent.
+    pub external_id: Option<String>,
+    /// ISO country code for rows without a region.
+    pub default_region: String,
+    /// Currency for rows without one; otherwise the region's currency.
+    pub default_currency: Option<String>,
+    /// Retailer slug (and name) the prices are recorded under.
+    pub retailer: String,
+}
+
+impl Default for CsvPriceMapping {
+    fn default() -> Self {
+        Self {
+            title: "title".to_string(),
+            amount: "price".to_string(),
+            platform: None,
+            region: None,
+            currency: None,
+            external_id: None,
+            default_region: "US".to_string(),
+            default_currency: None,
+            retailer: "csv_import".to_string(),
+        }
+    }
+}
+
+const TITLE_HEADERS: &[&str] = &["title", "name", "product-name", "product_name", "game"];
+const AMOUNT_HEADERS: &[&str] = &[
+    "price",
+    "amount",
+    "current_price",
+    "sale_price",
+    "loose-price",
+];
+const PLATFORM_HEADERS: &[&str] = &["platform", "console", "console-name", "system"];
+const REGION_HEADERS: &[&str] = &["region", "country", "country_code", "store_region"];
+const CURRENCY_HEADERS: &[&str] = &["currency", "currency_code", "ccy"];
+const ID_HEADERS: &[&str] = &["id", "sku", "external_id", "product_id"];
+
+impl CsvPriceMapping {
+    /// Mapping derived from common header names; title and amount columns are required.
+    pub fn from_headers(headers: &[&str]) -> Result<Self> {
+        let find = |aliases: &[&str]| {
+            headers
+                .iter()
+                .find(|h| aliases.iter().any(|a| h.trim().eq_ignore_ascii_case(a)))
+                .map(|h| h.trim().to_string())
+        };
+        Ok(Self {
+            title: find(TITLE_HEADERS).ok_or_else(|| anyhow!("csv: no title column"))?,
+            amount: find(AMOUNT_HEADERS).ok_or_else(|| anyhow!("csv: no price column"))?,
+            platform: find(PLATFORM_HEADERS),
+            region: find(REGION_HEADERS),
+            currency: find(CURRENCY_HEADERS),
+            external_id: find(ID_HEADERS),
+            ..Self::default()
+        })
+    }
+}
+
+/// One priced CSV row after mapping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvPriceRecord {
+    pub title: String,
+    pub platform: Option<String>,
+    pub region: String,
+    pub currency: String,
+    pub amount_minor: i64,
+    pub external_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CsvImportReport {
+    pub rows: usize,
+    pub written: usize,
+    /// Rows without a title or a positive, parseable amount.
+    pub skipped: usize,
+}
+
+/// Import `path` using `mapping`, or a mapping derived from its header row when `None`.
+pub async fn import(
+    db: &Db,
+    path: impl AsRef<Path>,
+    mapping: Option<&CsvPriceMapping>,
+) -> Result<CsvImportReport> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let (records, mapping, mut report) = read_records(file, mapping)?;
+
+    let provider_id =
+        ensure_provider(db, "csv_import", "pricing_catalogue", Some("csv_import")).await?;
+    let retailer_id = ensure_retailer(db, &mapping.retailer, Some(&mapping.retailer)).await?;
+    let mut entity_cache = ProviderEntityCache::new(db.clone());
+    // (region, currency) -> (jurisdiction_id, currency_id)
+    let mut jurisdictions: HashMap<(String, String), (i64, i64)> = HashMap::new();
+
+    let now = Utc::now();
+    let mut price_rows: Vec<PriceRow> = Vec::with_capacity(FLUSH_ROWS);
+    let mut latest: HashMap<i64, i64> = HashMap::new();
+    for rec in &records {
+        let key = (rec.region.clone(), rec.currency.clone());
+        let (jurisdiction_id, currency_id) = match jurisdictions.get(&key) {
+            Some(ids) => *ids,
+            None => {
+                let minor_unit = crate::util::currency::minor_unit(&rec.currency);
+                let currency_id =
+                    ensure_currency(db, &rec.currency, &rec.currency, minor_unit).await?;
+                let country_id = ensure_country(db, &rec.region, &rec.region, currency_id).await?;
+                let jurisdiction_id = ensure_national_jurisdiction(db, country_id).await?;
+                jurisdictions.insert(key, (jurisdiction_id, currency_id));
+                (jurisdiction_id, currency_id)
+            }
+        };
+
+        let slug = record_slug(rec);
+        let product_id = match rec.platform.as_deref() {
+            Some(platform) => {
+                entity_cache
+                    .ensure_product_named_with_platform("software", &slug, &rec.title, platform)
+                    .await?
+            }
+            None => {
+                entity_cache
+                    .ensure_product_named("software", &slug, &rec.title)
+                    .await?
+            }
+        };
+        let sellable_id = entity_cache.ensure_sellable("software", product_id).await?;
+        let offer_id = entity_cache
+            .ensure_offer(sellable_id, retailer_id, None)
+            .await?;
+        let oj_id = entity_cache
+            .ensure_offer_jurisdiction(offer_id, jurisdiction_id, currency_id)
+            .await?;
+
+        let external_id = rec
+            .external_id
+            .clone()
+            .unwrap_or_else(|| format!("{slug}:{}", rec.region.to_ascii_lowercase()));
+        let meta = json!({
+            "provider": "csv_import",
+            "source": mapping.retailer,
+            "platform": rec.platform,
+        });
+        let source_id = entity_cache
+            .ensure_provider_item(
+                provider_id,
+                &format!("{}:{external_id}", mapping.retailer),
+                Some(meta.clone()),
+                false,
+            )
+            .await?;
+        link_provider_offer(db, source_id, offer_id, Some(0.8)).await?;
+
+        price_rows.push(PriceRow {
+            offer_jurisdiction_id: oj_id,
+            video_game_source_id: Some(source_id),
+            recorded_at: now,
+            amount_minor: rec.amount_minor,
+            tax_inclusive: true,
+            fx_minor_per_unit: None,
+            btc_sats_per_unit: None,
+            meta,
+            video_game_id: None,
+            currency: Some(rec.currency.clone()),
+            country_code: Some(rec.region.clone()),
+            retailer: Some(mapping.retailer.clone()),
+        });
+        latest.insert(oj_id, rec.amount_minor);
+        report.written += 1;
+        if price_rows.len() >= FLUSH_ROWS {
+            flush(db, &mut price_rows, &mut latest).await?;
+        }
+    }
+    flush(db, &mut price_rows, &mut latest).await?;
+
+    info!(
+        path = %path.display(),
+        rows = report.rows,
+        written = report.written,
+        skipped = report.skipped,
+        "csv_prices: import finished"
+    );
+    Ok(report)
+}
+
+/// Parse and map every row; rows without a title or a positive amount are counted as
+/// skipped.
+fn read_records(
+    reader: impl Read,
+    mapping: Option<&CsvPriceMapping>,
+) -> Result<(Vec<CsvPriceRecord>, CsvPriceMapping, CsvImportReport)> {
+    let mut rdr = csv::ReaderBuilder::new()
+        .has_headers(true)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers: Vec<String> = rdr.headers()?.iter().map(str::to_string).collect();
+    let mapping = match mapping {
+        Some(m) => m.clone(),
+        None => {
+            let names: Vec<&str> = headers.iter().map(String::as_str).collect();
+            CsvPriceMapping::from_headers(&names)?
+        }
+    };
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.eq_ignore_ascii_case(name.trim()))
+    };
+    let required = |name: &str| column(name).ok_or_else(|| anyhow!("csv: column {name:?} missing"));
+    let idx_title = required(&mapping.title)?;
+    let idx_amount = required(&mapping.amount)?;
+    let optional = |name: &Option<String>| -> Result<Option<usize>> {
+        name.as_deref().map(required).transpose()
+    };
+    let idx_platform = optional(&mapping.platform)?;
+    let idx_region = optional(&mapping.region)?;
+    let idx_currency = optional(&mapping.currency)?;
+    let idx_id = optional(&mapping.external_id)?;
+
+    let mut report = CsvImportReport::default();
+    let mut records = Vec::new();
+    for (line, row) in rdr.records().enumerate() {
+        let row = row?;
+        report.rows += 1;
+        let field = |idx: Option<usize>| {
+            idx.and_then(|i| row.get(i))
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let region = field(idx_region)
+            .unwrap_or_else(|| mapping.default_region.clone())
+            .to_ascii_uppercase();
+        let currency = field(idx_currency)
+            .or_else(|| mapping.default_currency.clone())
+            .unwrap_or_else(|| crate::currency_for_country(&region).0.to_string())
+            .to_ascii_uppercase();
+        let amount_minor = field(Some(idx_amount))
+            .and_then(|raw| parse_amount_minor(&raw, crate::util::currency::minor_unit(&currency)))
+            .filter(|v| *v > 0);
+        let (Some(title), Some(amount_minor)) = (field(Some(idx_title)), amount_minor) else {
+            debug!(line = line + 2, "csv_prices: row skipped");
+            report.skipped += 1;
+            continue;
+        };
+        records.push(CsvPriceRecord {
+            title,
+            platform: field(idx_platform),
+            region,
+            currency,
+            amount_minor,
+            external_id: field(idx_id),
+        });
+    }
+    Ok((records, mapping, report))
+}
+
+/// Minor units for a price string such as `$1,299.99`, `59,99 €` or `¥6,800`. A lone
+/// separator followed by at most `minor_unit` digits is the decimal point; otherwise it
+/// groups thousands. A `-` before the first digit or enclosing parentheses (`(5.00)`)
+/// make the amount negative.
+fn parse_amount_minor(raw: &str, minor_unit: i16) -> Option<i64> {
+    let first_digit = raw.find(|c: char| c.is_ascii_digit()).unwrap_or(raw.len());
+    let trimmed = raw.trim();
+    let negative =
+        raw[..first_digit].contains('-') || (trimmed.starts_with('(') && trimmed.ends_with(')'));
+    let cleaned: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
+        .collect();
+    if !cleaned.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let digits = minor_unit.max(0) as usize;
+    let decimal_at = match (cleaned.rfind('.'), cleaned.rfind(',')) {
+        (Some(d), Some(c)) => Some(d.max(c)),
+        (Some(p), None) | (None, Some(p)) => {
+            let frac_len = cleaned.len() - p - 1;
+            let single = cleaned.matches(['.', ',']).count() == 1;
+            (single && frac_len <= digits.max(2)).then_some(p)
+        }
+        (None, None) => None,
+    };
+    let (int_part, frac_part) = match decimal_at {
+        Some(p) => (&cleaned[..p], &cleaned[p + 1..]),
+        None => (cleaned.as_str(), ""),
+    };
+    let int_digits: String = int_part.chars().filter(char::is_ascii_digit).collect();
+    let whole: i64 = if int_digits.is_empty() {
+        0
+    } else {
+        int_digits.parse().ok()?
+    };
+    let mut frac: String = frac_part.chars().filter(char::is_ascii_digit).collect();
+    let round_up = frac.chars().nth(digits).is_some_and(|c| c >= '5');
+    frac.truncate(digits);
+    while frac.len() < digits {
+        frac.push('0');
+    }
+    let frac: i64 = if frac.is_empty() {
+        0
+    } else {
+        frac.parse().ok()?
+    };
+    let scale = 10i64.checked_pow(digits as u32)?;
+    let magnitude = whole
+        .checked_mul(scale)?
+        .checked_add(frac)?
+        .checked_add(i64::from(round_up))?;
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+fn record_slug(rec: &CsvPriceRecord) -> String {
+    let base = match rec.platform.as_deref() {
+        Some(platform) => format!("{} {platform}", rec.title),
+        None => rec.title.clone(),
+    };
+    let slug = base
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "unnamed".to_string()
+    } else {
+        slug
+    }
+}
+
+async fn flush(db: &Db, rows: &mut Vec<PriceRow>, latest: &mut HashMap<i64, i64>) -> Result<()> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    db.bulk_insert_prices(rows).await?;
+    let recorded_at = rows[0].recorded_at;
+    let cps: Vec<CurrentPriceRow> = latest
+        .iter()
+        .map(|(oj, amount)| CurrentPriceRow {
+            offer_jurisdiction_id: *oj,
+            recorded_at,
+            amount_minor: *amount,
+            agent: CP_AGENT.to_string(),
+            agent_priority: CP_PRIORITY,
+        })
+        .collect();
+    db.upsert_current_prices(&cps).await?;
+    rows.clear();
+    latest.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = "\
+Game Name,System,Store,Cost,CCY,SKU
+Hollow Knight,Switch,gb,£12.49,,HK-NSW
+Hollow Knight,PS4,JP,\"¥1,980\",JPY,HK-PS4
+Celeste,PC,de,\"19,99 €\",EUR,CEL-PC
+Broken Row,PC,US,n/a,USD,BR-1
+,PC,US,9.99,USD,NO-TITLE
+";
+
+    fn custom_mapping() -> CsvPriceMapping {
+        CsvPriceMapping {
+            title: "Game Name".to_string(),
+            amount: "Cost".to_string(),
+            platform: Some("System".to_string()),
+            region: Some("Store".to_string()),
+            currency: Some("ccy".to_string()),
+            external_id: Some("SKU".to_string()),
+            retailer: "acme_games".to_string(),
+            ..CsvPriceMapping::default()
+        }
+    }
+
+    #[test]
+    fn custom_mapping_produces_minor_unit_prices() {
+        let (records, mapping, report) =
+            read_records(EXPORT.as_bytes(), Some(&custom_mapping())).unwrap();
+        assert_eq!(mapping.retailer, "acme_games");
+        assert_eq!(report.rows, 5);
+        assert_eq!(report.skipped, 2);
+        assert_eq!(
+            records,
+            vec![
+                CsvPriceRecord {
+                    title: "Hollow Knight".to_string(),
+                    platform: Some("Switch".to_string()),
+                    region: "GB".to_string(),
+                    currency: "GBP".to_string(),
+                    amount_minor: 1249,
+                    external_id: Some("HK-NSW".to_string()),
+                },
+                CsvPriceRecord {
+                    title: "Hollow Knight".to_string(),
+                    platform: Some("PS4".to_string()),
+                    region: "JP".to_string(),
+                    currency: "JPY".to_string(),
+                    amount_minor: 1980,
+                    external_id: Some("HK-PS4".to_string()),
+                },
+                CsvPriceRecord {
+                    title: "Celeste".to_string(),
+                    platform: Some("PC".to_string()),
+                    region: "DE".to_string(),
+                    currency: "EUR".to_string(),
+                    amount_minor: 1999,
+                    external_id: Some("CEL-PC".to_string()),
+                },
+            ]
+        );
+        assert_ne!(record_slug(&records[0]), record_slug(&records[1]));
+    }
+
+    #[test]
+    fn header_auto_mapping_finds_common_columns() {
+        let csv = "id,console-name,product-name,loose-price\n7,PS5,Astro Bot,$59.99\n";
+        let (records, mapping, _) = read_records(csv.as_bytes(), None).unwrap();
+        assert_eq!(mapping.title, "product-name");
+        assert_eq!(mapping.platform.as_deref(), Some("console-name"));
+        assert_eq!(records[0].amount_minor, 5999);
+        assert_eq!(records[0].currency, "USD");
+        assert_eq!(records[0].external_id.as_deref(), Some("7"));
+        assert!(CsvPriceMapping::from_headers(&["sku", "price"]).is_err());
+    }
+
+    #[test]
+    fn amounts_respect_separators_and_minor_units() {
+        assert_eq!(parse_amount_minor("$1,299.99", 2), Some(129_999));
+        assert_eq!(parse_amount_minor("1.299,99 €", 2), Some(129_999));
+        assert_eq!(parse_amount_minor("1,299", 2), Some(129_900));
+        assert_eq!(parse_amount_minor("59.9", 2), Some(5990));
+        assert_eq!(parse_amount_minor("¥6,800", 0), Some(6800));
+        assert_eq!(parse_amount_minor("12.345", 3), Some(12_345));
+        assert_eq!(parse_amount_minor("free", 2), None);
+        assert_eq!(parse_amount_minor("-$5.00", 2), Some(-500));
+        assert_eq!(parse_amount_minor("$-1,299.99", 2), Some(-129_999));
+        assert_eq!(parse_amount_minor("(59,99 €)", 2), Some(-5999));
+        assert_eq!(parse_amount_minor("¥-6,800", 0), Some(-6800));
+    }
+}
@@ -296,6 +296,8 @@ impl Db {
                 .unwrap_or_default();
             info!(version, desc, "migrations up-to-date (custom)");
         }
+        // Schema capabilities probed before this point may be stale now.
+        crate::database_ops::schema_caps::SchemaCaps::global().invalidate();
         Ok(())
     }
 }
@@ -3,10 +3,72 @@ use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Instant;
+use tracing::warn;
 
 use crate::database_ops::db::Db;
 
+/// ECB euro foreign exchange reference rates, published once per working day.
+pub const ECB_DAILY_URL: &str = "https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml";
+
+/// An upstream [`ExchangeService::sync_all`] can pull rates from. Its [`name`](Self::name)
+/// is the `provider` column of the rows it writes to `exchange_rates`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FxSource {
+    CoinGecko,
+    Ecb,
+    ExchangerateHost,
+    TradingView,
+}
+
+impl FxSource {
+    pub const ALL: [FxSource; 4] = [
+        FxSource::CoinGecko,
+        FxSource::Ecb,
+        FxSource::ExchangerateHost,
+        FxSource::TradingView,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FxSource::CoinGecko => "coingecko",
+            FxSource::Ecb => "ecb",
+            FxSource::ExchangerateHost => "exchangerate.host",
+            FxSource::TradingView => "tradingview",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "coingecko" => Some(FxSource::CoinGecko),
+            "ecb" => Some(FxSource::Ecb),
+            "exchangerate.host" | "exchangerate_host" | "exchangerate" => {
+                Some(FxSource::ExchangerateHost)
+            }
+            "tradingview" => Some(FxSource::TradingView),
+            _ => None,
+        }
+    }
+
+    /// Sources listed in `FX_SOURCES` (comma-separated); every source when unset.
+    pub fn list_from_env() -> Vec<FxSource> {
+        let Some(raw) = crate::util::env::env_opt("FX_SOURCES") else {
+            return Self::ALL.to_vec();
+        };
+        let mut sources = Vec::new();
+        for name in raw.split(',').filter(|s| !s.trim().is_empty()) {
+            match Self::parse(name) {
+                Some(source) if !sources.contains(&source) => sources.push(source),
+                Some(_) => {}
+                None => warn!(source = name, "FX_SOURCES: unknown source ignored"),
+            }
+        }
+        sources
+    }
+}
+
 #[derive(Clone)]
 pub struct ExchangeService {
     pub db: Db,
@@ -222,35 +284,30 @@ impl ExchangeService {
         Ok(out)
     }
 
+    /// Rates from every [`FxSource`]; sources that fail are skipped.
     pub async fn fetch_all_rates(&self) -> Result<Vec<RateRow>> {
-        // Run fetching in parallel
-        let (btc, usd, tv) = tokio::join!(
-            self.fetch_btc_rates(),
-            self.fetch_usd_rates_via_btc(),
-            self.fetch_tradingview_rates()
-        );
-
-        let mut all = Vec::new();
-
-        // Handle results
-        if let Ok(r) = btc {
-            all.extend(r);
-        }
-        if let Ok(r) = usd {
-            all.extend(r);
-        }
-        if let Ok(r) = tv {
-            all.extend(r);
-        }
+        let fetched =
+            futures::future::join_all(FxSource::ALL.iter().map(|&s| self.fetch_source(s))).await;
+        Ok(fetched.into_iter().flatten().flatten().collect())
+    }
 
-        // Additional FX sources: ECB (Euro foreign exchange reference), exchangerate.host fallback
-        if let Ok(ecb) = self.fetch_ecb_daily().await {
-            all.extend(ecb);
-        }
-        if let Ok(host) = self.fetch_exchangerate_host_latest().await {
-            all.extend(host);
+    pub async fn fetch_source(&self, source: FxSource) -> Result<Vec<RateRow>> {
+        match source {
+            FxSource::CoinGecko => {
+                let (btc, usd) =
+                    tokio::join!(self.fetch_btc_rates(), self.fetch_usd_rates_via_btc());
+                let mut rows = btc?;
+                rows.extend(usd?);
+                Ok(rows)
+            }
+            FxSource::Ecb => {
+                let url = crate::util::env::env_opt("FX_ECB_URL")
+                    .unwrap_or_else(|| ECB_DAILY_URL.to_string());
+                self.fetch_ecb_daily(&url).await
+            }
+            FxSource::ExchangerateHost => self.fetch_exchangerate_host_latest().await,
+            FxSource::TradingView => self.fetch_tradingview_rates().await,
         }
-        Ok(all)
     }
 
     // TradingView Scanner API fetcher
@@ -475,42 +532,75 @@ impl ExchangeService {
         Ok(None)
     }
 
-    pub async fn sync_all(&self) -> Result<SyncSummary> {
-        let rates = self.fetch_all_rates().await?;
-        let stored = self.store_rates(&rates).await?;
-        Ok(SyncSummary {
-            fetched: rates.len(),
-            stored,
+    /// Fetch `sources` concurrently and upsert each one's rates into `exchange_rates` (what
+    /// [`latest_rate`](Self::latest_rate) reads). A failing source is logged and reported
+    /// in [`SyncSummary::sources`]; the others are still stored.
+    pub async fn sync_all(&self, sources: &[FxSource]) -> SyncSummary {
+        let outcomes = futures::future::join_all(sources.iter().map(|&source| async move {
+            let started = Instant::now();
+            let result = match self.fetch_source(source).await {
+                Ok(rows) => self.store_rates(&rows).await.map(|stored| (rows, stored)),
+                Err(e) => Err(e),
+            };
+            (source, started.elapsed(), result)
+        }))
+        .await;
+
+        let mut summary = SyncSummary {
+            fetched: 0,
+            stored: 0,
+            pairs: 0,
+            sources: Vec::with_capacity(outcomes.len()),
             timestamp: Utc::now(),
-        })
+        };
+        let mut pairs = BTreeSet::new();
+        for (source, elapsed, result) in outcomes {
+            let mut outcome = FxSourceOutcome {
+                source,
+                fetched: 0,
+                stored: 0,
+                elapsed_ms: elapsed.as_millis() as u64,
+                error: None,
+            };
+            match result {
+                Ok((rows, stored)) => {
+                    outcome.fetched = rows.len();
+                    outcome.stored = stored;
+                    pairs.extend(
+                        rows.into_iter()
+                            .map(|r| (r.base_currency, r.quote_currency)),
+                    );
+                }
+                Err(e) => {
+                    warn!(source = source.name(), error = %e, "fx sync: source failed");
+                    outcome.error = Some(e.to_string());
+                }
+            }
+            summary.fetched += outcome.fetched;
+            summary.stored += outcome.stored;
+            summary.sources.push(outcome);
+        }
+        summary.pairs = pairs.len();
+        summary
     }
 
-    // ECB daily reference rates (EUR base). Source: https://www.ecb.europa.eu/stats/eurofxref/eurofxref-daily.xml
-    // We'll use the JSON proxy at exchangerate.host for simplicity to avoid XML parsing.
-    async fn fetch_ecb_daily(&self) -> Result<Vec<RateRow>> {
-        let url = "https://api.exchangerate.host/latest?base=EUR";
+    /// ECB daily reference rates (EUR base), from the `eurofxref-daily.xml` document at `url`.
+    pub async fn fetch_ecb_daily(&self, url: &str) -> Result<Vec<RateRow>> {
         let resp = self.http.get(url).send().await?.error_for_status()?;
-        let v: serde_json::Value = resp.json().await?;
+        let xml = resp.text().await?;
+        let (date, rates) = parse_ecb_daily(&xml)?;
         let fetched_at = Utc::now();
-        let mut out = Vec::new();
-        if v.get("success").and_then(|b| b.as_bool()) != Some(true) {
-            return Ok(out);
-        }
-        if let Some(rates) = v.get("rates").and_then(|r| r.as_object()) {
-            for (quote, val) in rates.iter() {
-                if let Some(rate) = val.as_f64() {
-                    out.push(RateRow {
-                        base_currency: "EUR".into(),
-                        quote_currency: quote.to_uppercase(),
-                        rate,
-                        provider: "ecb".into(),
-                        fetched_at,
-                        metadata: json!({"src":"exchangerate.host","kind":"daily"}),
-                    });
-                }
-            }
-        }
-        Ok(out)
+        Ok(rates
+            .into_iter()
+            .map(|(quote, rate)| RateRow {
+                base_currency: "EUR".into(),
+                quote_currency: quote,
+                rate,
+                provider: FxSource::Ecb.name().into(),
+                fetched_at,
+                metadata: json!({"src": "eurofxref-daily", "date": date}),
+            })
+            .collect())
     }
 
     // Latest cross rates from exchangerate.host (configurable base via env FX_BASE_CURRENCY)
@@ -565,9 +655,158 @@ pub struct RateRow {
     pub metadata: serde_json::Value,
 }
 
+/// A parsed ECB daily document: the publication date and `(currency, EUR rate)` pairs.
+pub type EcbDaily = (Option<String>, Vec<(String, f64)>);
+
+/// Parse the ECB daily reference document: the publication date (the `time` attribute) and
+/// one `(currency, EUR rate)` per `<Cube currency=".." rate=".."/>` entry.
+pub fn parse_ecb_daily(xml: &str) -> Result<EcbDaily> {
+    let mut date = None;
+    let mut rates = Vec::new();
+    for tag in xml.split("<Cube").skip(1) {
+        let tag = tag.split('>').next().unwrap_or_default();
+        if let Some(time) = xml_attr(tag, "time") {
+            date = Some(time.to_string());
+        }
+        if let (Some(currency), Some(rate)) = (xml_attr(tag, "currency"), xml_attr(tag, "rate")) {
+            match rate.parse::<f64>() {
+                Ok(rate) if rate > 0.0 => rates.push((currency.to_ascii_uppercase(), rate)),
+                _ => warn!(currency, rate, "ecb: unparsable rate skipped"),
+            }
+        }
+    }
+    if rates.is_empty() {
+        anyhow::bail!("ecb: no rates in eurofxref document");
+    }
+    Ok((date, rates))
+}
+
+/// Value of attribute `name` in the inside of an XML start tag (either quote style).
+fn xml_attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(at) = rest.find(name) {
+        let preceded_by_space = rest[..at].ends_with(char::is_whitespace) || at == 0;
+        rest = &rest[at + name.len()..];
+        let Some(value) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let quote = value.chars().next()?;
+        if !preceded_by_space || (quote != '"' && quote != '\'') {
+            continue;
+        }
+        let value = &value[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncSummary {
     pub fetched: usize,
     pub stored: usize,
+    /// Distinct currency pairs updated across all sources.
+    pub pairs: usize,
+    pub sources: Vec<FxSourceOutcome>,
     pub timestamp: DateTime<Utc>,
 }
+
+impl SyncSummary {
+    pub fn failed_sources(&self) -> impl Iterator<Item = &FxSourceOutcome> {
+        self.sources.iter().filter(|s| s.error.is_some())
+    }
+
+    /// True when sources were requested and none of them succeeded.
+    pub fn all_failed(&self) -> bool {
+        !self.sources.is_empty() && self.sources.iter().all(|s| s.error.is_some())
+    }
+}
+
+/// What one [`FxSource`] contributed to a [`SyncSummary`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FxSourceOutcome {
+    pub source: FxSource,
+    pub fetched: usize,
+    pub stored: usize,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const EUROFXREF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gesmes:Envelope xmlns:gesmes="http://www.gesmes.org/xml/2002-08-01" xmlns="http://www.ecb.int/vocabulary/2002-08-01/eurofxref">
+	<gesmes:subject>Reference rates</gesmes:subject>
+	<Cube>
+		<Cube time='2026-10-15'>
+			<Cube currency='USD' rate='1.0876'/>
+			<Cube currency='JPY' rate='162.41'/>
+			<Cube currency="GBP" rate="0.8571"/>
+		</Cube>
+	</Cube>
+</gesmes:Envelope>"#;
+
+    /// Serves `body` as XML to every request; returns the base URL.
+    async fn serve(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let _ = socket.read(&mut buf).await;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: text/xml\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        base
+    }
+
+    #[tokio::test]
+    async fn ecb_daily_document_yields_eur_pairs() {
+        let base = serve(EUROFXREF).await;
+        let db = Db {
+            pool: sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://fx@127.0.0.1:1/none")
+                .unwrap(),
+        };
+        let rows = ExchangeService::new(db)
+            .fetch_ecb_daily(&format!("{base}/stats/eurofxref/eurofxref-daily.xml"))
+            .await
+            .unwrap();
+
+        let pairs: Vec<(&str, &str, f64)> = rows
+            .iter()
+            .map(|r| (r.base_currency.as_str(), r.quote_currency.as_str(), r.rate))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("EUR", "USD", 1.0876),
+                ("EUR", "JPY", 162.41),
+                ("EUR", "GBP", 0.8571)
+            ]
+        );
+        assert!(rows.iter().all(|r| r.provider == "ecb"));
+        assert_eq!(rows[0].metadata["date"], "2026-10-15");
+
+        assert!(parse_ecb_daily("<html>maintenance</html>").is_err());
+    }
+
+    #[test]
+    fn fx_sources_parse_by_provider_name() {
+        for source in FxSource::ALL {
+            assert_eq!(FxSource::parse(source.name()), Some(source));
+        }
+        assert_eq!(FxSource::parse(" ECB "), Some(FxSource::Ecb));
+        assert_eq!(FxSource::parse("fixer"), None);
+    }
+}
@@ -0,0 +1,391 @@
+//! Ratings from every source in one table.
+//!
+//! PS Store stars, Metacritic, Steam reviews and IGDB each score games on their own scale.
+//! Providers record them through [`upsert_external_rating`], one row per game and source
+//! in `external_ratings`, and the game profile renders each with [`display_label`]
+//! ("Metacritic 88", "Steam 94% (12k)", "PS ★4.6 (3k)"). The denormalized
+//! `video_games.average_rating` is still written by the providers that maintained it.
+//!
+//! Every rating change also recomputes `video_games.meta_score`, a 0-100 aggregate
+//! weighted per source by `META_SCORE_WEIGHTS` and by rating count (see [`meta_score`]).
+
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::database_ops::db::Db;
+use crate::database_ops::schema_caps::SchemaCaps;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RatingSource {
+    /// PS Store star rating, 0-5.
+    PlayStation,
+    /// Metacritic critic score, 0-100.
+    Metacritic,
+    /// Steam user reviews, percent positive.
+    Steam,
+    /// IGDB total rating, 0-100.
+    Igdb,
+}
+
+impl RatingSource {
+    pub const ALL: [Self; 4] = [Self::PlayStation, Self::Metacritic, Self::Steam, Self::Igdb];
+
+    /// Value stored in `external_ratings.source`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PlayStation => "ps-store",
+            Self::Metacritic => "metacritic",
+            Self::Steam => "steam",
+            Self::Igdb => "igdb",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        Self::ALL
+            .into_iter()
+            .find(|source| source.as_str().eq_ignore_ascii_case(raw))
+    }
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::PlayStation => "PS",
+            Self::Metacritic => "Metacritic",
+            Self::Steam => "Steam",
+            Self::Igdb => "IGDB",
+        }
+    }
+
+    /// Maximum score on this source's scale.
+    pub fn scale(self) -> f64 {
+        match self {
+            Self::PlayStation => 5.0,
+            Self::Metacritic | Self::Steam | Self::Igdb => 100.0,
+        }
+    }
+}
+
+/// Per-source reliability weights for [`meta_score`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetaScoreWeights {
+    weights: HashMap<RatingSource, f64>,
+}
+
+impl Default for MetaScoreWeights {
+    fn default() -> Self {
+        Self {
+            weights: HashMap::from([
+                (RatingSource::Metacritic, 1.0),
+                (RatingSource::Igdb, 0.8),
+                (RatingSource::Steam, 0.7),
+                (RatingSource::PlayStation, 0.6),
+            ]),
+        }
+    }
+}
+
+impl MetaScoreWeights {
+    /// Parse `source=weight` pairs separated by commas or whitespace, e.g.
+    /// `metacritic=1,steam=0.5,ps-store=0`, over the defaults. Unknown sources and
+    /// negative or non-numeric weights are logged and ignored; weight 0 drops a source.
+    pub fn parse(raw: &str) -> Self {
+        let mut weights = Self::default();
+        for pair in raw
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+        {
+            let parsed = pair.split_once('=').and_then(|(source, weight)| {
+                let weight = weight.trim().parse::<f64>().ok()?;
+                Some((RatingSource::parse(source)?, weight))
+            });
+            match parsed {
+                Some((source, weight)) if weight.is_finite() && weight >= 0.0 => {
+                    weights.weights.insert(source, weight);
+                }
+                _ => warn!(%pair, "META_SCORE_WEIGHTS: ignoring invalid entry"),
+            }
+        }
+        weights
+    }
+
+    /// Weights from `META_SCORE_WEIGHTS`, read once per process.
+    pub fn from_env() -> &'static Self {
+        static WEIGHTS: OnceLock<MetaScoreWeights> = OnceLock::new();
+        WEIGHTS.get_or_init(|| {
+            crate::util::env::env_opt("META_SCORE_WEIGHTS")
+                .map(|raw| Self::parse(&raw))
+                .unwrap_or_default()
+        })
+    }
+
+    pub fn weight(&self, source: RatingSource) -> f64 {
+        self.weights.get(&source).copied().unwrap_or(0.0)
+    }
+}
+
+/// Confidence in a rating from its count: `log10(1 + count) / 4`, capped at 1, so it
+/// reaches full weight at 10k ratings. Critic scores without a count count fully.
+fn count_confidence(count: Option<i64>) -> f64 {
+    match count {
+        None => 1.0,
+        Some(count) => ((1.0 + count.max(0) as f64).log10() / 4.0).min(1.0),
+    }
+}
+
+/// Weighted mean of `(source, score, count)` ratings, each normalized to 0-100 and
+/// weighted by `weights.weight(source) * count_confidence(count)`. `None` when no rating
+/// carries weight.
+pub fn meta_score(
+    ratings: &[(RatingSource, f64, Option<i64>)],
+    weights: &MetaScoreWeights,
+) -> Option<f64> {
+    let (weighted, total) =
+        ratings
+            .iter()
+            .fold((0.0, 0.0), |(weighted, total), &(source, score, count)| {
+                let w = weights.weight(source) * count_confidence(count);
+                (weighted + w * score / source.scale() * 100.0, total + w)
+            });
+    (total > 0.0).then(|| weighted / total)
+}
+
+/// Short label for a rating in its source's own notation, with the count when known.
+pub fn display_label(source: RatingSource, score: f64, count: Option<i64>) -> String {
+    let score = match source {
+        RatingSource::PlayStation => format!("★{score:.1}"),
+        RatingSource::Steam => format!("{}%", score.round()),
+        RatingSource::Metacritic | RatingSource::Igdb => format!("{}", score.round()),
+    };
+    match count.filter(|c| *c > 0) {
+        Some(count) => format!(
+            "{} {score} ({})",
+            source.display_name(),
+            compact_count(count)
+        ),
+        None => format!("{} {score}", source.display_name()),
+    }
+}
+
+/// `950`, `1.2k`, `12k`, `3.4M`.
+fn compact_count(count: i64) -> String {
+    let (value, suffix) = match count {
+        c if c >= 1_000_000 => (c as f64 / 1_000_000.0, "M"),
+        c if c >= 1_000 => (c as f64 / 1_000.0, "k"),
+        c => return c.to_string(),
+    };
+    if value >= 10.0 {
+        format!("{}{suffix}", value.round())
+    } else {
+        format!("{}{suffix}", (value * 10.0).round() / 10.0)
+    }
+}
+
+/// Record `source`'s rating of `video_game_id`. Re-sending the same score and count is a
+/// no-op (`updated_at` only moves when either changes); a `None` count keeps the stored
+/// one. Skipped when the `external_ratings` table is missing.
+pub async fn upsert_external_rating(
+    db: &Db,
+    video_game_id: i64,
+    source: RatingSource,
+    score: f64,
+    count: Option<i64>,
+) -> Result<()> {
+    if !score.is_finite() || !(0.0..=source.scale()).contains(&score) {
+        bail!(
+            "{} rating {score} outside 0..={}",
+            source.as_str(),
+            source.scale()
+        );
+    }
+    if !SchemaCaps::global()
+        .table_visible(db, "external_ratings")
+        .await?
+    {
+        return Ok(());
+    }
+    let written = sqlx::query(
+        "INSERT INTO public.external_ratings (video_game_id, source, score, scale, rating_count)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (video_game_id, source) DO UPDATE
+         SET score = EXCLUDED.score,
+             scale = EXCLUDED.scale,
+             rating_count = COALESCE(EXCLUDED.rating_count, external_ratings.rating_count),
+             updated_at = now()
+         WHERE (external_ratings.score, external_ratings.rating_count)
+               IS DISTINCT FROM
+               (EXCLUDED.score, COALESCE(EXCLUDED.rating_count, external_ratings.rating_count))",
+    )
+    .persistent(false)
+    .bind(video_game_id)
+    .bind(source.as_str())
+    .bind(score)
+    .bind(source.scale())
+    .bind(count)
+    .execute(&db.pool)
+    .await?;
+    if written.rows_affected() > 0 {
+        recompute_meta_score(db, video_game_id).await?;
+    }
+    Ok(())
+}
+
+/// Recompute `video_games.meta_score` from every stored rating of `video_game_id`.
+/// Skipped when the column is missing.
+pub async fn recompute_meta_score(db: &Db, video_game_id: i64) -> Result<Option<f64>> {
+    if !SchemaCaps::global()
+        .column_visible(db, "video_games", "meta_score")
+        .await?
+    {
+        return Ok(None);
+    }
+    let rows: Vec<(String, f64, Option<i64>)> = sqlx::query_as(
+        "SELECT source, score, rating_count FROM public.external_ratings
+         WHERE video_game_id = $1",
+    )
+    .persistent(false)
+    .bind(video_game_id)
+    .fetch_all(&db.pool)
+    .await?;
+    let ratings: Vec<(RatingSource, f64, Option<i64>)> = rows
+        .into_iter()
+        .filter_map(|(source, score, count)| Some((RatingSource::parse(&source)?, score, count)))
+        .collect();
+    let score = meta_score(&ratings, MetaScoreWeights::from_env());
+    sqlx::query("UPDATE public.video_games SET meta_score = $1 WHERE id = $2")
+        .persistent(false)
+        .bind(score)
+        .bind(video_game_id)
+        .execute(&db.pool)
+        .await?;
+    Ok(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    #[test]
+    fn labels_use_each_source_notation() {
+        assert_eq!(
+            display_label(RatingSource::Metacritic, 88.0, None),
+            "Metacritic 88"
+        );
+        assert_eq!(
+            display_label(RatingSource::Steam, 94.2, Some(12_345)),
+            "Steam 94% (12k)"
+        );
+        assert_eq!(
+            display_label(RatingSource::PlayStation, 4.62, Some(3_010)),
+            "PS ★4.6 (3k)"
+        );
+        assert_eq!(
+            display_label(RatingSource::Igdb, 84.4, Some(950)),
+            "IGDB 84 (950)"
+        );
+        assert_eq!(compact_count(1_240), "1.2k");
+        assert_eq!(compact_count(3_400_000), "3.4M");
+        assert_eq!(
+            RatingSource::parse(" PS-Store "),
+            Some(RatingSource::PlayStation)
+        );
+        assert_eq!(RatingSource::parse("opencritic"), None);
+    }
+
+    #[test]
+    fn meta_score_weights_sources_and_counts() {
+        let weights = MetaScoreWeights::parse("metacritic=1.0, steam=0.5 ps-store=0.25 bogus=2");
+        assert_eq!(weights.weight(RatingSource::Steam), 0.5);
+        assert_eq!(
+            weights.weight(RatingSource::Igdb),
+            0.8,
+            "unlisted keeps default"
+        );
+
+        let mut ratings = vec![
+            (RatingSource::Metacritic, 88.0, None),
+            (RatingSource::Steam, 94.0, Some(9_999)),
+            (RatingSource::PlayStation, 4.6, Some(99)),
+        ];
+        // Steam: 0.5 * log10(10_000) / 4 = 0.5; PS: 0.25 * log10(100) / 4 = 0.125.
+        let expected = (1.0 * 88.0 + 0.5 * 94.0 + 0.125 * 92.0) / (1.0 + 0.5 + 0.125);
+        let score = meta_score(&ratings, &weights).unwrap();
+        assert!((score - expected).abs() < 1e-9, "{score} != {expected}");
+
+        ratings[0].1 = 70.0;
+        let expected = (1.0 * 70.0 + 0.5 * 94.0 + 0.125 * 92.0) / (1.0 + 0.5 + 0.125);
+        let updated = meta_score(&ratings, &weights).unwrap();
+        assert!((updated - expected).abs() < 1e-9, "{updated} != {expected}");
+        assert!(updated < score);
+
+        let unweighted = MetaScoreWeights::parse("metacritic=0");
+        assert_eq!(
+            meta_score(&[(RatingSource::Metacritic, 88.0, None)], &unweighted),
+            None
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn upsert_is_idempotent_per_source() {
+        let db = RollbackDb::connect().await;
+        let video_game_id = 9_100_000_000 + i64::from(std::process::id());
+        let row = |source: RatingSource| {
+            let db = db.clone();
+            async move {
+                sqlx::query_as::<_, (f64, Option<i64>, chrono::DateTime<chrono::Utc>)>(
+                    "SELECT score, rating_count, updated_at FROM public.external_ratings
+                     WHERE video_game_id = $1 AND source = $2",
+                )
+                .bind(video_game_id)
+                .bind(source.as_str())
+                .fetch_one(&db.pool)
+                .await
+                .unwrap()
+            }
+        };
+
+        upsert_external_rating(
+            &db,
+            video_game_id,
+            RatingSource::PlayStation,
+            4.6,
+            Some(3_000),
+        )
+        .await
+        .unwrap();
+        upsert_external_rating(&db, video_game_id, RatingSource::Metacritic, 88.0, None)
+            .await
+            .unwrap();
+        let first = row(RatingSource::PlayStation).await;
+        upsert_external_rating(
+            &db,
+            video_game_id,
+            RatingSource::PlayStation,
+            4.6,
+            Some(3_000),
+        )
+        .await
+        .unwrap();
+        let repeated = row(RatingSource::PlayStation).await;
+        upsert_external_rating(&db, video_game_id, RatingSource::PlayStation, 4.7, None)
+            .await
+            .unwrap();
+        let changed = row(RatingSource::PlayStation).await;
+        let metacritic = row(RatingSource::Metacritic).await;
+        let out_of_range =
+            upsert_external_rating(&db, video_game_id, RatingSource::PlayStation, 46.0, None).await;
+        db.rollback().await;
+
+        assert_eq!(
+            repeated, first,
+            "same score and count leaves the row untouched"
+        );
+        assert_eq!((changed.0, changed.1), (4.7, Some(3_000)));
+        assert!(changed.2 >= first.2);
+        assert_eq!((metacritic.0, metacritic.1), (88.0, None));
+        assert!(out_of_range.is_err());
+    }
+}
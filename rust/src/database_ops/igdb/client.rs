@@ -1,20 +1,29 @@
 use crate::database_ops::db::Db;
+use crate::database_ops::external_ratings::{upsert_external_rating, RatingSource};
+use crate::database_ops::igdb::external_games::{
+    external_links, link_external_games, IgdbExternalGame,
+};
+use crate::database_ops::igdb::query::{Cmp, IgdbQuery, Order};
 use crate::database_ops::ingest_providers::{
     ensure_platform, ensure_provider, ensure_vg_source_media_links_with_meta,
-    ensure_video_game_source, ingest_run_finish, ingest_run_start, replace_provider_toplist_items,
-    upsert_game_media, upsert_provider_toplist, ProviderEntityCache,
+    ensure_video_game_source, ingest_run_finish, ingest_run_start, merge_video_game_metadata,
+    replace_provider_toplist_items, update_video_game_display_title_and_region, upsert_game_media,
+    upsert_provider_toplist, ProviderEntityCache,
 };
 use crate::database_ops::media_map::normalize_title;
-use anyhow::{anyhow, Context, Result};
-use chrono::{Datelike, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use crate::normalization::display_title::TitleCandidate;
+use crate::normalization::genre::canonical_genre;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, TimeZone, Utc};
 use reqwest::{Client, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde_json::{json, Value};
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, OnceCell};
 use tracing::{debug, info, instrument, warn};
 
 #[derive(Debug, Clone, Copy)]
@@ -41,6 +50,14 @@ pub struct IgdbServiceConfig {
     pub window_end: Option<NaiveDate>,
     pub platform_ids: Vec<i32>,
     pub characters_per_game: usize,
+    /// Game fields to request; `None` uses each mode's default (minimal for backfill, full
+    /// for incremental and top-monthly enrichment).
+    pub fields: Option<IgdbFieldSet>,
+    /// Extra pause after each result page, on top of the request throttle.
+    pub page_pause_ms: u64,
+    /// Refresh the OAuth token once it has less than this long left, instead of waiting
+    /// for a 401 mid-run.
+    pub token_refresh_margin_secs: u64,
 }
 
 impl Default for IgdbServiceConfig {
@@ -59,6 +76,9 @@ impl Default for IgdbServiceConfig {
             window_end: None,
             platform_ids: Vec::new(),
             characters_per_game: 3,
+            fields: None,
+            page_pause_ms: 0,
+            token_refresh_margin_secs: 300,
         }
     }
 }
@@ -142,6 +162,22 @@ impl IgdbServiceConfig {
                 cfg.characters_per_game = n.clamp(0, 5);
             }
         }
+        if let Ok(v) = std::env::var("IGDB_FIELDS") {
+            match IgdbFieldSet::parse(&v) {
+                Ok(fields) => cfg.fields = Some(fields),
+                Err(err) => warn!(target = "igdb", error = %err, "ignoring invalid IGDB_FIELDS"),
+            }
+        }
+        if let Ok(v) = std::env::var("IGDB_PAGE_PAUSE_MS") {
+            if let Ok(n) = v.parse::<u64>() {
+                cfg.page_pause_ms = n;
+            }
+        }
+        if let Ok(v) = std::env::var("IGDB_TOKEN_REFRESH_MARGIN_SECS") {
+            if let Ok(n) = v.parse::<u64>() {
+                cfg.token_refresh_margin_secs = n;
+            }
+        }
         cfg
     }
 
@@ -186,6 +222,62 @@ const IGDB_PROVIDER_KIND: &str = "catalog";
 const IGDB_PROVIDER_SLUG: &str = "igdb";
 const IGDB_FALLBACK_PLATFORM_NAME: &str = "Unknown Platform (IGDB)";
 const IGDB_FALLBACK_PLATFORM_SLUG: &str = "igdb-generic";
+/// `provider_sync_watermarks.sync_kind` for the incremental `updated_at` sync.
+const IGDB_UPDATED_WATERMARK: &str = "games_updated_at";
+const IGDB_GAME_FIELDS: &str = "fields id,name,slug,summary,storyline,first_release_date,total_rating,total_rating_count,aggregated_rating,aggregated_rating_count,genres,themes,platforms.id,platforms.name,platforms.slug,cover.image_id,cover.url,screenshots.image_id,screenshots.url,videos.video_id,videos.name,release_dates.id,release_dates.date,release_dates.platform,release_dates.region,updated_at,external_games.category,external_games.uid;";
+/// Enough to create and link games: no screenshots, videos, storyline or per-platform
+/// release dates, which dominate the payload.
+const IGDB_MINIMAL_GAME_FIELDS: &str = "fields id,name,slug,summary,first_release_date,total_rating,total_rating_count,genres,themes,platforms.id,platforms.name,platforms.slug,cover.image_id,updated_at,external_games.category,external_games.uid;";
+
+/// Game field selection for `/games` queries, set with `IGDB_FIELDS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IgdbFieldSet {
+    Minimal,
+    Full,
+    /// An explicit field list, e.g. `id,name,cover.image_id`.
+    Custom(String),
+}
+
+impl IgdbFieldSet {
+    /// `minimal`, `full`, or a comma-separated list of field paths (`a-z`, `0-9`, `_`, `.`,
+    /// `*`) that includes `id`.
+    pub fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        match raw.to_ascii_lowercase().as_str() {
+            "minimal" | "min" => return Ok(Self::Minimal),
+            "full" | "all" => return Ok(Self::Full),
+            _ => {}
+        }
+        let fields: Vec<&str> = raw.split(',').map(str::trim).collect();
+        if let Some(bad) = fields.iter().find(|f| {
+            f.is_empty()
+                || !f.chars().all(|c| {
+                    c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '.' | '*')
+                })
+        }) {
+            bail!("invalid IGDB field {bad:?} in field list {raw:?}");
+        }
+        if !fields.iter().any(|f| *f == "id" || *f == "*") {
+            bail!("IGDB field list {raw:?} must include id");
+        }
+        Ok(Self::Custom(fields.join(",")))
+    }
+
+    /// The APIcalypse `fields ...;` statement.
+    fn clause(&self) -> String {
+        format!("fields {};", self.list())
+    }
+
+    /// The bare comma-separated field list.
+    fn list(&self) -> &str {
+        let clause = match self {
+            Self::Minimal => IGDB_MINIMAL_GAME_FIELDS,
+            Self::Full => IGDB_GAME_FIELDS,
+            Self::Custom(fields) => return fields,
+        };
+        clause.trim_start_matches("fields ").trim_end_matches(';')
+    }
+}
 
 #[derive(Debug, Clone, Deserialize)]
 struct IgdbImage {
@@ -283,6 +375,10 @@ struct IgdbGame {
     release_dates: Option<Vec<IgdbReleaseDate>>,
     #[serde(default)]
     characters: Option<Vec<i64>>,
+    #[serde(default)]
+    updated_at: Option<i64>,
+    #[serde(default)]
+    external_games: Option<Vec<IgdbExternalGame>>,
 }
 
 #[derive(Debug, Clone)]
@@ -320,6 +416,18 @@ struct TwitchTokenResponse {
     token_type: String,
 }
 
+/// Unix time the most recently issued token expires (0 before the first one), shared by
+/// every `IgdbService` in the process so the service loop can report it.
+static TOKEN_EXPIRES_AT: AtomicI64 = AtomicI64::new(0);
+
+/// Expiry of the latest IGDB OAuth token issued in this process, for the metrics endpoints.
+pub fn token_expires_at() -> Option<DateTime<Utc>> {
+    match TOKEN_EXPIRES_AT.load(Ordering::Relaxed) {
+        0 => None,
+        secs => Utc.timestamp_opt(secs, 0).single(),
+    }
+}
+
 #[derive(Debug, Clone)]
 struct IgdbToken {
     access_token: String,
@@ -330,11 +438,17 @@ pub struct IgdbService {
     cfg: IgdbServiceConfig,
     http: Client,
     token: Arc<Mutex<Option<IgdbToken>>>,
+    token_url: String,
+    genre_names: OnceCell<HashMap<i64, &'static str>>,
 }
 
 impl IgdbService {
     pub fn new_from_env() -> Result<Self> {
-        let cfg = IgdbServiceConfig::from_env();
+        Self::with_config(IgdbServiceConfig::from_env())
+    }
+
+    /// Service using `cfg` as given; only `IGDB_USER_AGENT` is still read from env.
+    pub fn with_config(cfg: IgdbServiceConfig) -> Result<Self> {
         let user_agent = std::env::var("IGDB_USER_AGENT")
             .unwrap_or_else(|_| "gamecompare-igdb-ingest/1.0".to_string());
         let http = Client::builder()
@@ -345,9 +459,16 @@ impl IgdbService {
             cfg,
             http,
             token: Arc::new(Mutex::new(None)),
+            token_url: TWITCH_TOKEN_URL.to_string(),
+            genre_names: OnceCell::new(),
         })
     }
 
+    /// `fields` statement for game queries: `IGDB_FIELDS` when set, else `mode_default`.
+    fn game_fields(&self, mode_default: IgdbFieldSet) -> String {
+        self.cfg.fields.as_ref().unwrap_or(&mode_default).clause()
+    }
+
     fn effective_page_size(&self, override_size: usize) -> usize {
         let base = if override_size > 0 {
             override_size
@@ -371,16 +492,49 @@ impl IgdbService {
         }
     }
 
+    /// Request throttle plus `page_pause_ms`, then a token refresh if the current token is
+    /// inside the refresh margin, so the next page starts on a token with time left.
+    async fn pause_between_pages(&self) -> Result<()> {
+        self.throttle().await;
+        if self.cfg.page_pause_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.cfg.page_pause_ms)).await;
+        }
+        self.ensure_token().await?;
+        if let Some(ttl) = self.token_ttl().await {
+            debug!(
+                target = "igdb",
+                token_ttl_secs = ttl.as_secs(),
+                "igdb token ttl"
+            );
+        }
+        Ok(())
+    }
+
+    /// Time left on the cached OAuth token; `None` before the first request.
+    pub async fn token_ttl(&self) -> Option<Duration> {
+        self.token
+            .lock()
+            .await
+            .as_ref()
+            .map(|token| token.expires_at.saturating_duration_since(Instant::now()))
+    }
+
     async fn ensure_token(&self) -> Result<String> {
         {
             let guard = self.token.lock().await;
             if let Some(token) = guard.as_ref() {
-                if token.expires_at > Instant::now() + Duration::from_secs(30) {
+                let margin = Duration::from_secs(self.cfg.token_refresh_margin_secs.max(30));
+                if token.expires_at > Instant::now() + margin {
                     return Ok(token.access_token.clone());
                 }
             }
         }
         let token = self.request_new_token().await?;
+        let ttl = token.expires_at.saturating_duration_since(Instant::now());
+        TOKEN_EXPIRES_AT.store(
+            Utc::now().timestamp() + ttl.as_secs() as i64,
+            Ordering::Relaxed,
+        );
         let mut guard = self.token.lock().await;
         *guard = Some(token.clone());
         Ok(token.access_token)
@@ -393,7 +547,7 @@ impl IgdbService {
             .context("missing env: TWITCH_CLIENT_SECRET (required for IGDB)")?;
         let response = self
             .http
-            .post(TWITCH_TOKEN_URL)
+            .post(&self.token_url)
             .query(&[
                 ("client_id", client_id.as_str()),
                 ("client_secret", client_secret.as_str()),
@@ -452,6 +606,12 @@ impl IgdbService {
                     if status == StatusCode::UNAUTHORIZED {
                         let mut guard = self.token.lock().await;
                         *guard = None;
+                        drop(guard);
+                        // Revoked or expired early: retry with a fresh token.
+                        if attempt < self.cfg.max_retries {
+                            attempt += 1;
+                            continue;
+                        }
                     }
 
                     if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
@@ -499,6 +659,25 @@ impl IgdbService {
         self.fetch_genres("fields id,name,slug; limit 500;").await
     }
 
+    /// Canonical genre name per IGDB genre id, fetched once per service. A failed fetch
+    /// leaves the map empty, which only skips the `genres` metadata writes.
+    async fn genre_names(&self) -> &HashMap<i64, &'static str> {
+        self.genre_names
+            .get_or_init(|| async {
+                match self.fetch_all_genres().await {
+                    Ok(genres) => genres
+                        .into_iter()
+                        .filter_map(|g| Some((g.id?, canonical_genre(g.name.as_deref()?)?)))
+                        .collect(),
+                    Err(err) => {
+                        warn!(target = "igdb", error = %err, "failed to fetch IGDB genre names");
+                        HashMap::new()
+                    }
+                }
+            })
+            .await
+    }
+
     async fn fetch_themes(&self, body: &str) -> Result<Vec<IgdbTheme>> {
         self.execute_request(IGDB_THEMES_ENDPOINT, body.to_string())
             .await
@@ -752,6 +931,64 @@ impl IgdbService {
         } else {
             None
         };
+        // Laravel schema: use product_id directly
+        let video_game_id = if let Some(vg_id) = title_video_game_id {
+            vg_id
+        } else {
+            cache
+                .ensure_video_game_for_product_laravel(
+                    product_id,
+                    name,
+                    Some(&slug),
+                    Some(metadata.clone()),
+                    IGDB_PROVIDER_SLUG,
+                )
+                .await?
+        };
+        if let (Some(item_id), Some(external_games)) =
+            (video_game_source_id, game.external_games.as_deref())
+        {
+            let links = external_links(external_games);
+            match link_external_games(db, item_id, Some(video_game_id), &links).await {
+                Ok(linked) if linked > 0 => {
+                    debug!(
+                        target = "igdb",
+                        igdb_id, linked, "linked IGDB game to store items"
+                    );
+                }
+                Ok(_) => {}
+                Err(err) => warn!(
+                    target = "igdb",
+                    igdb_id,
+                    error = %err,
+                    "failed to record external_games links (best-effort)"
+                ),
+            }
+        }
+        if let Some(content) = self.content_patch(game).await {
+            merge_video_game_metadata(db, video_game_id, IGDB_PROVIDER_SLUG, content).await?;
+        }
+        if let Some(score) = game.total_rating {
+            if let Err(err) = upsert_external_rating(
+                db,
+                video_game_id,
+                RatingSource::Igdb,
+                f64::from(score),
+                game.total_rating_count,
+            )
+            .await
+            {
+                warn!(target = "igdb", igdb_id, error = %err, "failed to record IGDB rating");
+            }
+        }
+        // IGDB names games by their English official title.
+        let _ = update_video_game_display_title_and_region(
+            db,
+            video_game_id,
+            &TitleCandidate::new(name, "igdb", Some("en")),
+            None,
+        )
+        .await;
         let platforms = self.extract_platforms(game);
         if platforms.is_empty() {
             let _platform_id = ensure_platform(
@@ -760,20 +997,6 @@ impl IgdbService {
                 Some(IGDB_FALLBACK_PLATFORM_SLUG),
             )
             .await?;
-            // Laravel schema: use product_id directly
-            let video_game_id = if let Some(vg_id) = title_video_game_id {
-                vg_id
-            } else {
-                cache
-                    .ensure_video_game_for_product_laravel(
-                        product_id,
-                        name,
-                        Some(&slug),
-                        Some(metadata.clone()),
-                        IGDB_PROVIDER_SLUG,
-                    )
-                    .await?
-            };
             self.persist_media_records(
                 db,
                 video_game_source_id,
@@ -796,20 +1019,6 @@ impl IgdbService {
                 .map(|s| s.to_string())
                 .unwrap_or_else(|| normalize_title(platform_name));
             let _platform_id = ensure_platform(db, platform_name, Some(&platform_slug)).await?;
-            // Laravel schema: use product_id directly
-            let video_game_id = if let Some(vg_id) = title_video_game_id {
-                vg_id
-            } else {
-                cache
-                    .ensure_video_game_for_product_laravel(
-                        product_id,
-                        name,
-                        Some(&slug),
-                        Some(metadata.clone()),
-                        IGDB_PROVIDER_SLUG,
-                    )
-                    .await?
-            };
             self.persist_media_records(
                 db,
                 video_game_source_id,
@@ -988,6 +1197,30 @@ impl IgdbService {
         Ok(())
     }
 
+    /// `genres`/`synopsis` patch for the prioritized `video_games.metadata` merge.
+    async fn content_patch(&self, game: &IgdbGame) -> Option<Value> {
+        let mut content = serde_json::Map::new();
+        if let Some(ids) = game.genres.as_deref() {
+            let names = self.genre_names().await;
+            let mut genres: Vec<&str> =
+                ids.iter().filter_map(|id| names.get(id).copied()).collect();
+            genres.sort_unstable();
+            genres.dedup();
+            if !genres.is_empty() {
+                content.insert("genres".into(), json!(genres));
+            }
+        }
+        if let Some(summary) = game
+            .summary
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            content.insert("synopsis".into(), json!(summary));
+        }
+        (!content.is_empty()).then_some(Value::Object(content))
+    }
+
     fn build_provider_metadata(
         &self,
         game: &IgdbGame,
@@ -1066,32 +1299,45 @@ impl IgdbService {
         platforms: &[i32],
         offset: usize,
         limit: usize,
-    ) -> String {
-        let mut filters: Vec<String> = Vec::new();
+    ) -> Result<String> {
+        let fields = self.cfg.fields.as_ref().unwrap_or(&IgdbFieldSet::Minimal);
+        let mut query = IgdbQuery::new(fields.list());
         if let Some(start) = start_epoch {
-            filters.push(format!("first_release_date >= {}", start));
+            query = query.filter("first_release_date", Cmp::Ge, start);
         }
         if let Some(end) = end_epoch {
-            filters.push(format!("first_release_date <= {}", end));
-        }
-        if !platforms.is_empty() {
-            let ids = platforms
-                .iter()
-                .map(|id| id.to_string())
-                .collect::<Vec<_>>()
-                .join(",");
-            filters.push(format!("platforms = ({})", ids));
+            query = query.filter("first_release_date", Cmp::Le, end);
         }
-        let where_clause = if filters.is_empty() {
-            String::new()
-        } else {
-            format!("where {};", filters.join(" & "))
+        query
+            .filter_any("platforms", platforms)
+            .sort("first_release_date", Order::Desc)
+            .limit(limit.clamp(1, IGDB_MAX_LIMIT))
+            .offset(offset)
+            .build()
+    }
+
+    /// The page of changed games after `cursor`: oldest change first so a partial run still
+    /// advances the watermark monotonically, or by id while walking one `updated_at` value.
+    fn build_updated_since_query(
+        &self,
+        cursor: UpdatedCursor,
+        platforms: &[i32],
+        limit: usize,
+    ) -> Result<String> {
+        let fields = self.cfg.fields.as_ref().unwrap_or(&IgdbFieldSet::Full);
+        let query = match cursor.after_id {
+            Some(id) => IgdbQuery::new(fields.list())
+                .filter("updated_at", Cmp::Eq, cursor.since)
+                .filter("id", Cmp::Gt, id)
+                .sort("id", Order::Asc),
+            None => IgdbQuery::new(fields.list())
+                .filter("updated_at", Cmp::Gt, cursor.since)
+                .sort("updated_at", Order::Asc),
         };
-        format!(
-            "fields id,name,slug,summary,storyline,first_release_date,total_rating,total_rating_count,aggregated_rating,aggregated_rating_count,genres,themes,platforms.id,platforms.name,platforms.slug,cover.image_id,cover.url,screenshots.image_id,screenshots.url,videos.video_id,videos.name,release_dates.id,release_dates.date,release_dates.platform,release_dates.region; {where_clause} sort first_release_date desc; limit {limit}; offset {offset};",
-            limit = limit.min(IGDB_MAX_LIMIT),
-            offset = offset
-        )
+        query
+            .filter_any("platforms", platforms)
+            .limit(limit.clamp(1, IGDB_MAX_LIMIT))
+            .build()
     }
 
     fn build_top_monthly_games_query(
@@ -1137,7 +1383,8 @@ impl IgdbService {
 
         // Sort by engagement first (count), then by rating.
         format!(
-            "fields id,name,slug,summary,storyline,first_release_date,total_rating,total_rating_count,aggregated_rating,aggregated_rating_count,genres,themes,platforms.id,platforms.name,platforms.slug,cover.image_id,cover.url,screenshots.image_id,screenshots.url,videos.video_id,videos.name,release_dates.id,release_dates.date,release_dates.platform,release_dates.region; {where_clause} sort total_rating_count desc; limit {limit}; offset {offset};",
+            "{fields} {where_clause} sort total_rating_count desc; limit {limit}; offset {offset};",
+            fields = self.game_fields(IgdbFieldSet::Full),
             limit = limit.min(IGDB_MAX_LIMIT),
             offset = offset
         )
@@ -1251,7 +1498,8 @@ impl IgdbService {
             if page >= max_pages {
                 break;
             }
-            let query = self.build_games_query(start_epoch, end_epoch, &platforms, offset, limit);
+            let query =
+                self.build_games_query(start_epoch, end_epoch, &platforms, offset, limit)?;
             let games = self.fetch_games(&query).await?;
             if games.is_empty() {
                 break;
@@ -1275,7 +1523,7 @@ impl IgdbService {
             total += processed;
             page += 1;
             offset += limit;
-            self.throttle().await;
+            self.pause_between_pages().await?;
             debug!(
                 target = "igdb",
                 page, processed, total, "igdb page ingested"
@@ -1391,6 +1639,149 @@ impl IgdbService {
         result
     }
 
+    /// Ingest games IGDB modified since the stored `updated_at` watermark, advancing it
+    /// after every page. Without a watermark (first run, or no `provider_sync_watermarks`
+    /// table) this runs [`Self::backfill_range`] over `y_from..=y_to` and, on success,
+    /// stores the run start as the watermark.
+    #[instrument(skip(self, db))]
+    pub async fn sync_incremental(
+        &self,
+        db: &Db,
+        y_from: i32,
+        y_to: i32,
+        platforms: &[i32],
+        page_size_override: usize,
+        max_pages_override: usize,
+    ) -> Result<usize> {
+        use crate::database_ops::sync_watermark::{advance_watermark, load_watermark};
+
+        let provider_id = ensure_provider(
+            db,
+            IGDB_PROVIDER_NAME,
+            IGDB_PROVIDER_KIND,
+            Some(IGDB_PROVIDER_SLUG),
+        )
+        .await?;
+        let Some(watermark) = load_watermark(db, provider_id, IGDB_UPDATED_WATERMARK).await? else {
+            info!(
+                target = "igdb",
+                "no updated_at watermark; falling back to range backfill"
+            );
+            let started = Utc::now();
+            let processed = self
+                .backfill_range(
+                    db,
+                    y_from,
+                    y_to,
+                    platforms,
+                    page_size_override,
+                    max_pages_override,
+                )
+                .await?;
+            advance_watermark(db, provider_id, IGDB_UPDATED_WATERMARK, started).await?;
+            return Ok(processed);
+        };
+
+        let provider_items_exists = table_exists(db, "provider_items").await.unwrap_or(false);
+        let provider_media_links_exists = table_exists(db, "vg_source_media_links")
+            .await
+            .unwrap_or(false);
+        let title_schema = detect_title_schema(db).await?;
+        let platform_filter = if !platforms.is_empty() {
+            platforms.to_vec()
+        } else {
+            self.cfg.platform_ids.clone()
+        };
+        let limit = self.effective_page_size(page_size_override);
+        let max_pages = self.effective_max_pages(max_pages_override);
+        let meta = json!({
+            "mode": "incremental",
+            "since": watermark.to_rfc3339(),
+            "platform_ids": platform_filter,
+        });
+        let ingest_id = ingest_run_start(db, provider_id, None, Some(meta)).await?;
+
+        let mut cache = ProviderEntityCache::new(db.clone());
+        let mut total = 0usize;
+        let result: Result<()> = async {
+            let limit = limit.min(IGDB_MAX_LIMIT);
+            let mut cursor = UpdatedCursor::resume(watermark.timestamp());
+            let mut stored = watermark.timestamp();
+            for page in 0..max_pages {
+                // Each page restarts at the cursor, so no offset is needed.
+                let query = self.build_updated_since_query(cursor, &platform_filter, limit)?;
+                let games = self.fetch_games(&query).await?;
+                let Some(next) = cursor.next(&games, limit) else {
+                    break;
+                };
+                if games.is_empty() {
+                    cursor = next;
+                    continue;
+                }
+                let characters = self.fetch_characters_for_games(&games).await?;
+                let processed = self
+                    .persist_games(
+                        db,
+                        provider_id,
+                        &mut cache,
+                        &games,
+                        &characters,
+                        provider_items_exists,
+                        provider_media_links_exists,
+                        title_schema,
+                        None,
+                    )
+                    .await?
+                    .len();
+                total += processed;
+                // Everything before the latest `updated_at` seen is done; games sharing it
+                // are re-read from the stored value on the next run.
+                if let Some(latest) = latest_updated_at(&games).filter(|at| *at > stored) {
+                    if let Some(at) = Utc.timestamp_opt(latest, 0).single() {
+                        advance_watermark(db, provider_id, IGDB_UPDATED_WATERMARK, at).await?;
+                        stored = latest;
+                    }
+                }
+                debug!(
+                    target = "igdb",
+                    page,
+                    processed,
+                    total,
+                    since = next.since,
+                    "igdb incremental page ingested"
+                );
+                cursor = next;
+                self.pause_between_pages().await?;
+            }
+            Ok(())
+        }
+        .await;
+
+        match &result {
+            Ok(()) => {
+                ingest_run_finish(db, ingest_id, "completed", total as i64, 0, None).await?;
+            }
+            Err(err) => {
+                ingest_run_finish(
+                    db,
+                    ingest_id,
+                    "failed",
+                    total as i64,
+                    0,
+                    Some(json!({ "error": err.to_string() })),
+                )
+                .await?;
+            }
+        }
+        info!(
+            target = "igdb",
+            processed = total,
+            since = %watermark,
+            "IGDB incremental sync finished"
+        );
+        result.map(|_| total)
+    }
+
     #[instrument(skip(self, db))]
     pub async fn ingest_top_monthly_from_env(&self, db: &Db) -> Result<usize> {
         let limit = std::env::var("IGDB_TOP_MONTHLY_LIMIT")
@@ -1647,7 +2038,7 @@ impl IgdbService {
             page += 1;
             offset += q_limit;
 
-            self.throttle().await;
+            self.pause_between_pages().await?;
             debug!(
                 target = "igdb",
                 page, processed, total, "igdb top-monthly page ingested"
@@ -1724,6 +2115,54 @@ struct DesiredTag {
     display_name: String,
 }
 
+/// Position in IGDB's `(updated_at, id)` order. IGDB sorts on one field only, so games
+/// sharing `since` are walked by id (`after_id: Some`) before moving past it (`None`);
+/// paging on `updated_at` alone would skip the rest of a tie cut by the page limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct UpdatedCursor {
+    since: i64,
+    after_id: Option<i64>,
+}
+
+impl UpdatedCursor {
+    /// Start of a run at the stored watermark. Games stamped exactly `since` are re-read,
+    /// since the previous run may have stopped part-way through them.
+    fn resume(since: i64) -> Self {
+        Self {
+            since,
+            after_id: Some(0),
+        }
+    }
+
+    /// The cursor after a page of `games` fetched with `limit`, or `None` once caught up.
+    fn next(self, games: &[IgdbGame], limit: usize) -> Option<Self> {
+        let full = games.len() >= limit;
+        match self.after_id {
+            Some(after) if full => {
+                let last = games.iter().filter_map(|g| g.id).max().unwrap_or(after);
+                (last > after).then_some(Self {
+                    since: self.since,
+                    after_id: Some(last),
+                })
+            }
+            Some(_) => Some(Self {
+                since: self.since,
+                after_id: None,
+            }),
+            None if full => {
+                let latest = latest_updated_at(games)?;
+                // A full page may end part-way through the games sharing `latest`.
+                Some(Self::resume(latest))
+            }
+            None => None,
+        }
+    }
+}
+
+fn latest_updated_at(games: &[IgdbGame]) -> Option<i64> {
+    games.iter().filter_map(|g| g.updated_at).max()
+}
+
 fn split_csv_tokens(raw: &str) -> Vec<String> {
     raw.split(|c| c == ',' || c == ';')
         .map(|s| s.trim())
@@ -2279,6 +2718,13 @@ async fn table_exists(db: &Db, table: &str) -> Result<bool> {
 
 #[instrument(skip(db))]
 pub async fn run_from_env(db: &Db) -> Result<()> {
+    let mode = std::env::var("IGDB_MODE").unwrap_or_else(|_| "backfill".to_string());
+    run_with_config(db, IgdbServiceConfig::from_env(), &mode).await
+}
+
+/// [`run_from_env`] with an explicit config and `IGDB_MODE` value.
+#[instrument(skip(db, cfg))]
+pub async fn run_with_config(db: &Db, cfg: IgdbServiceConfig, mode: &str) -> Result<()> {
     // Check for required schema tables (legacy-safe)
     let required_tables = [
         "platforms",
@@ -2305,8 +2751,7 @@ pub async fn run_from_env(db: &Db) -> Result<()> {
         return Ok(());
     }
 
-    let service = IgdbService::new_from_env()?;
-    let mode = std::env::var("IGDB_MODE").unwrap_or_else(|_| "backfill".to_string());
+    let service = IgdbService::with_config(cfg)?;
 
     let current_year = Utc::now().year();
     let mut from_year = service
@@ -2334,11 +2779,14 @@ pub async fn run_from_env(db: &Db) -> Result<()> {
         window_start = ?service.cfg.window_start,
         window_end = ?service.cfg.window_end,
         characters_per_game = service.cfg.characters_per_game,
+        fields = ?service.cfg.fields,
+        page_pause_ms = service.cfg.page_pause_ms,
+        token_refresh_margin_secs = service.cfg.token_refresh_margin_secs,
         "IGDB run config"
     );
 
     let processed = if matches!(
-        mode.as_str(),
+        mode,
         "top-50"
             | "top_50"
             | "top50"
@@ -2348,12 +2796,17 @@ pub async fn run_from_env(db: &Db) -> Result<()> {
             | "monthly-top"
             | "monthly"
     ) {
-        if matches!(mode.as_str(), "top-50" | "top_50" | "top50") {
+        if matches!(mode, "top-50" | "top_50" | "top50") {
             info!(target = "igdb", mode = %mode, "IGDB running top-50 mode (alias of top-monthly)");
         } else {
             info!(target = "igdb", mode = %mode, "IGDB running top-monthly mode");
         }
         service.ingest_top_monthly_from_env(db).await?
+    } else if matches!(mode, "incremental" | "updated" | "delta") {
+        info!(target = "igdb", mode = %mode, "IGDB running incremental mode");
+        service
+            .sync_incremental(db, from_year, to_year, &service.cfg.platform_ids, 0, 0)
+            .await?
     } else {
         info!(target = "igdb", mode = %mode, "IGDB running backfill mode");
         service
@@ -2370,3 +2823,193 @@ pub async fn run_from_env(db: &Db) -> Result<()> {
     );
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(id: i64, updated_at: i64) -> IgdbGame {
+        serde_json::from_value(json!({ "id": id, "updated_at": updated_at })).unwrap()
+    }
+
+    #[test]
+    fn watermark_advances_and_next_run_only_sees_newer_records() {
+        let service = IgdbService {
+            cfg: IgdbServiceConfig::default(),
+            http: Client::new(),
+            token: Arc::new(Mutex::new(None)),
+            token_url: TWITCH_TOKEN_URL.to_string(),
+            genre_names: OnceCell::new(),
+        };
+        let catalogue = [
+            game(1, 100),
+            game(2, 250),
+            game(3, 180),
+            game(7, 250),
+            game(5, 250),
+            game(4, 400),
+        ];
+        // Stand-in for IGDB: one sort field, ties returned in no particular (here the
+        // least convenient, descending id) order.
+        let fetch = |cursor: UpdatedCursor, limit: usize| -> Vec<IgdbGame> {
+            let mut page: Vec<IgdbGame> = catalogue
+                .iter()
+                .filter(|g| match cursor.after_id {
+                    Some(id) => g.updated_at == Some(cursor.since) && g.id.unwrap() > id,
+                    None => g.updated_at.unwrap() > cursor.since,
+                })
+                .cloned()
+                .collect();
+            match cursor.after_id {
+                Some(_) => page.sort_by_key(|g| g.id),
+                None => page.sort_by_key(|g| (g.updated_at, std::cmp::Reverse(g.id))),
+            }
+            page.truncate(limit);
+            page
+        };
+        let run = |since: i64, limit: usize| -> (Vec<i64>, i64) {
+            let mut seen = Vec::new();
+            let mut watermark = since;
+            let mut cursor = UpdatedCursor::resume(since);
+            loop {
+                let games = fetch(cursor, limit);
+                seen.extend(games.iter().map(|g| g.id.unwrap()));
+                watermark = latest_updated_at(&games)
+                    .unwrap_or(watermark)
+                    .max(watermark);
+                match cursor.next(&games, limit) {
+                    Some(next) => cursor = next,
+                    None => return (seen, watermark),
+                }
+            }
+        };
+
+        // A two-game page ends part-way through the three games stamped 250.
+        let (seen, watermark) = run(150, 2);
+        let mut ids = seen.clone();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids, vec![2, 3, 4, 5, 7]);
+        assert_eq!(watermark, 400);
+
+        // The next run only re-reads games stamped exactly at the watermark.
+        assert_eq!(run(watermark, 2).0, vec![4]);
+
+        let query = service
+            .build_updated_since_query(UpdatedCursor::resume(400), &[48, 167], 1000)
+            .unwrap();
+        assert!(query.contains("where updated_at = 400 & id > 0 & platforms = (48,167);"));
+        assert!(query.contains("sort id asc; limit 500;"));
+        let query = service
+            .build_updated_since_query(
+                UpdatedCursor {
+                    since: 400,
+                    after_id: None,
+                },
+                &[48, 167],
+                1000,
+            )
+            .unwrap();
+        assert!(query.contains("where updated_at > 400 & platforms = (48,167);"));
+        assert!(query.contains("sort updated_at asc; limit 500;"));
+        assert!(query.starts_with(IGDB_GAME_FIELDS));
+    }
+
+    #[test]
+    fn minimal_backfill_fields_omit_heavy_expansions() {
+        let service = IgdbService {
+            cfg: IgdbServiceConfig::default(),
+            http: Client::new(),
+            token: Arc::new(Mutex::new(None)),
+            token_url: TWITCH_TOKEN_URL.to_string(),
+            genre_names: OnceCell::new(),
+        };
+        let query = service
+            .build_games_query(Some(0), Some(100), &[48], 0, 200)
+            .unwrap();
+        let statements: Vec<&str> = query
+            .split(';')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect();
+        let keywords: Vec<&str> = statements
+            .iter()
+            .map(|s| s.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(keywords, ["fields", "where", "sort", "limit", "offset"]);
+        let fields = statements[0].trim_start_matches("fields ");
+        assert!(IgdbFieldSet::parse(fields).is_ok(), "{fields}");
+        for heavy in ["screenshots", "videos", "storyline", "release_dates"] {
+            assert!(!query.contains(heavy), "{heavy} in {query}");
+        }
+        assert!(fields.split(',').any(|f| f == "external_games.uid"));
+
+        assert_eq!(IgdbFieldSet::parse(" Full ").unwrap(), IgdbFieldSet::Full);
+        assert_eq!(
+            IgdbFieldSet::parse("id, name").unwrap().clause(),
+            "fields id,name;"
+        );
+        assert!(IgdbFieldSet::parse("name,slug").is_err());
+        assert!(IgdbFieldSet::parse("id,name; limit 500").is_err());
+    }
+
+    #[tokio::test]
+    async fn expiring_token_is_refreshed_between_pages() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let token_url = format!("http://{}/oauth2/token", listener.local_addr().unwrap());
+        let issued = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = issued.clone();
+        tokio::spawn(async move {
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = sock.read(&mut buf).await;
+                let n = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+                let body = format!(
+                    r#"{{"access_token":"fresh-{n}","expires_in":3600,"token_type":"bearer"}}"#
+                );
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = sock.write_all(response.as_bytes()).await;
+            }
+        });
+        let mut env = crate::test_support::TestEnv::enter().await;
+        env.set(&[
+            ("TWITCH_CLIENT_ID", "test-client-id"),
+            ("TWITCH_CLIENT_SECRET", "test-client-secret"),
+        ]);
+
+        let service = IgdbService {
+            cfg: IgdbServiceConfig {
+                reqs_per_min: None,
+                ..IgdbServiceConfig::default()
+            },
+            http: Client::new(),
+            // Issued before the run started; lapses 60s into the backfill.
+            token: Arc::new(Mutex::new(Some(IgdbToken {
+                access_token: "stale".to_string(),
+                expires_at: Instant::now() + Duration::from_secs(60),
+            }))),
+            token_url,
+            genre_names: OnceCell::new(),
+        };
+
+        service.pause_between_pages().await.unwrap();
+        assert_eq!(issued.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(service.ensure_token().await.unwrap(), "fresh-1");
+        let ttl = service.token_ttl().await.unwrap();
+        assert!(ttl > Duration::from_secs(service.cfg.token_refresh_margin_secs));
+        assert!(token_expires_at().unwrap() > Utc::now() + chrono::Duration::minutes(50));
+
+        service.pause_between_pages().await.unwrap();
+        assert_eq!(
+            issued.load(std::sync::atomic::Ordering::SeqCst),
+            1,
+            "token with time left is reused"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! IGDB `external_games`: the ids a game has on other stores.
+//!
+//! Each IGDB game lists its Steam appid, PlayStation Store and Microsoft Store product ids.
+//! They are recorded per IGDB provider item in `provider_item_external_links` and linked to
+//! the storefront `provider_items` row with the same external id, so the IGDB game and the
+//! store listing are matched exactly rather than by title. The IGDB game's canonical video
+//! game is stored with each link for `normalization::resolve_by_external_id`.
+
+use anyhow::Result;
+use serde::Deserialize;
+use sqlx::QueryBuilder;
+
+use crate::database_ops::db::Db;
+
+/// `external_games.category` values for the stores we ingest.
+const CATEGORY_STEAM: i64 = 1;
+const CATEGORY_MICROSOFT: i64 = 11;
+const CATEGORY_XBOX_MARKETPLACE: i64 = 31;
+const CATEGORY_PLAYSTATION_STORE_US: i64 = 36;
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct IgdbExternalGame {
+    #[serde(default)]
+    pub category: Option<i64>,
+    #[serde(default)]
+    pub uid: Option<String>,
+}
+
+/// An external id resolved to the `providers.slug` of the store it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ExternalLink {
+    pub provider_slug: &'static str,
+    pub uid: String,
+}
+
+/// Links for the stores we ingest, deduplicated; other categories are ignored.
+pub(crate) fn external_links(external_games: &[IgdbExternalGame]) -> Vec<ExternalLink> {
+    let mut links: Vec<ExternalLink> = Vec::new();
+    for ext in external_games {
+        let provider_slug = match ext.category {
+            Some(CATEGORY_STEAM) => "steam-store",
+            Some(CATEGORY_PLAYSTATION_STORE_US) => "ps-store",
+            Some(CATEGORY_MICROSOFT | CATEGORY_XBOX_MARKETPLACE) => "xbox-store",
+            _ => continue,
+        };
+        let Some(uid) = ext.uid.as_deref().map(str::trim).filter(|u| !u.is_empty()) else {
+            continue;
+        };
+        let link = ExternalLink {
+            provider_slug,
+            uid: uid.to_string(),
+        };
+        if !links.contains(&link) {
+            links.push(link);
+        }
+    }
+    links
+}
+
+/// Record `links` for the IGDB provider item (and its canonical `video_game_id`) and point
+/// each at the storefront provider item with the same external id, when one exists. Links
+/// to items ingested later are filled in on the next run. Returns how many links resolved
+/// to a provider item.
+pub(crate) async fn link_external_games(
+    db: &Db,
+    igdb_provider_item_id: i64,
+    video_game_id: Option<i64>,
+    links: &[ExternalLink],
+) -> Result<u64> {
+    if igdb_provider_item_id == 0 || links.is_empty() {
+        return Ok(0);
+    }
+    let present: bool =
+        sqlx::query_scalar("SELECT to_regclass('public.provider_item_external_links') IS NOT NULL")
+            .persistent(false)
+            .fetch_one(&db.pool)
+            .await?;
+    if !present {
+        return Ok(0);
+    }
+
+    let mut qb = QueryBuilder::new(
+        "INSERT INTO public.provider_item_external_links
+           (provider_item_id, provider_slug, external_uid, video_game_id, linked_provider_item_id)
+         SELECT v.provider_item_id, v.provider_slug, v.external_uid, v.video_game_id,
+                (SELECT pi.id FROM public.provider_items pi
+                 JOIN public.providers p ON p.id = pi.provider_id
+                 WHERE p.slug = v.provider_slug AND pi.external_id = v.external_uid
+                 ORDER BY pi.id LIMIT 1)
+         FROM (",
+    );
+    qb.push_values(links, |mut row, link| {
+        row.push_bind(igdb_provider_item_id)
+            .push_bind(link.provider_slug)
+            .push_bind(&link.uid)
+            .push_bind(video_game_id);
+    });
+    qb.push(
+        ") AS v(provider_item_id, provider_slug, external_uid, video_game_id)
+         ON CONFLICT (provider_item_id, provider_slug, external_uid) DO UPDATE
+           SET video_game_id = COALESCE(EXCLUDED.video_game_id,
+                                        provider_item_external_links.video_game_id),
+               linked_provider_item_id = COALESCE(EXCLUDED.linked_provider_item_id,
+                                                  provider_item_external_links.linked_provider_item_id),
+               updated_at = now()
+         RETURNING linked_provider_item_id",
+    );
+    let linked: Vec<Option<i64>> = qb
+        .build_query_scalar()
+        .persistent(false)
+        .fetch_all(&db.pool)
+        .await?;
+    Ok(linked.iter().filter(|id| id.is_some()).count() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    #[derive(Deserialize)]
+    struct FixtureGame {
+        id: i64,
+        external_games: Vec<IgdbExternalGame>,
+    }
+
+    fn fixture() -> Vec<FixtureGame> {
+        serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/igdb_external_games.json"
+        )))
+        .unwrap()
+    }
+
+    #[test]
+    fn fixture_yields_store_links() {
+        let games = fixture();
+        assert_eq!(games[0].id, 119_133);
+        let links = external_links(&games[0].external_games);
+        assert_eq!(
+            links,
+            vec![
+                ExternalLink {
+                    provider_slug: "steam-store",
+                    uid: "1174180".to_string(),
+                },
+                ExternalLink {
+                    provider_slug: "xbox-store",
+                    uid: "9N2ZDN7NWQKV".to_string(),
+                },
+                ExternalLink {
+                    provider_slug: "ps-store",
+                    uid: "UP1004-CUSA03041_00-REDEMPTIONFULL02".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn fixture_links_to_existing_store_items() {
+        let db = RollbackDb::connect().await;
+        let provider = |slug: &'static str| {
+            let db = db.clone();
+            async move {
+                sqlx::query_scalar::<_, i64>(
+                    "INSERT INTO public.providers (slug, name) VALUES ($1, $1)
+                     ON CONFLICT (slug) DO UPDATE SET name = providers.name RETURNING id",
+                )
+                .bind(slug)
+                .fetch_one(&db.pool)
+                .await
+                .unwrap()
+            }
+        };
+        let item = |provider_id: i64, external_id: &'static str| {
+            let db = db.clone();
+            async move {
+                sqlx::query_scalar::<_, i64>(
+                    "INSERT INTO public.provider_items (provider_id, external_id)
+                     VALUES ($1, $2) RETURNING id",
+                )
+                .bind(provider_id)
+                .bind(external_id)
+                .fetch_one(&db.pool)
+                .await
+                .unwrap()
+            }
+        };
+        let igdb_item = item(provider("igdb").await, "test-119133").await;
+        let steam_item = item(provider("steam-store").await, "1174180").await;
+
+        let links = external_links(&fixture()[0].external_games);
+        let linked = link_external_games(&db, igdb_item, None, &links)
+            .await
+            .unwrap();
+        let rows: Vec<(String, Option<i64>)> = sqlx::query_as(
+            "SELECT provider_slug, linked_provider_item_id
+             FROM public.provider_item_external_links
+             WHERE provider_item_id = $1 ORDER BY provider_slug",
+        )
+        .bind(igdb_item)
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        db.rollback().await;
+
+        assert_eq!(linked, 1);
+        assert_eq!(rows.len(), 3);
+        assert!(rows.contains(&("steam-store".to_string(), Some(steam_item))));
+        assert!(rows.contains(&("ps-store".to_string(), None)));
+    }
+}
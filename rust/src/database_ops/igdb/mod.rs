@@ -1,2 +1,4 @@
 pub mod client;
+pub mod external_games;
 pub mod ingest;
+pub mod query;
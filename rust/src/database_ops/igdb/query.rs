@@ -0,0 +1,200 @@
+//! Typed builder for IGDB APIcalypse queries.
+//!
+//! Builds the `fields ...; where ...; sort ...; limit ...; offset ...;` body sent to
+//! `/v4/games` and friends. Field names and limits are validated in [`IgdbQuery::build`],
+//! so a malformed query fails before it is sent instead of as an IGDB 400 at runtime.
+
+use anyhow::{bail, Result};
+use std::fmt::Display;
+
+/// IGDB's maximum page size.
+pub(crate) const MAX_LIMIT: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Cmp {
+    Eq,
+    Gt,
+    Ge,
+    Le,
+}
+
+impl Cmp {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Le => "<=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Order {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IgdbQuery {
+    fields: Vec<String>,
+    filters: Vec<(String, String)>,
+    sort: Option<(String, Order)>,
+    limit: Option<usize>,
+    offset: usize,
+}
+
+impl IgdbQuery {
+    /// Query selecting `fields`, a comma-separated list such as `id,name,cover.image_id`.
+    pub(crate) fn new(fields: &str) -> Self {
+        Self {
+            fields: fields.split(',').map(|f| f.trim().to_string()).collect(),
+            filters: Vec::new(),
+            sort: None,
+            limit: None,
+            offset: 0,
+        }
+    }
+
+    /// `field <op> value`; filters are combined with `&`.
+    pub(crate) fn filter(mut self, field: &str, op: Cmp, value: impl Display) -> Self {
+        self.filters
+            .push((field.to_string(), format!("{} {}", op.as_str(), value)));
+        self
+    }
+
+    /// `field = (a,b,...)`, matching any of `values`; no filter when `values` is empty.
+    pub(crate) fn filter_any<T: Display>(mut self, field: &str, values: &[T]) -> Self {
+        if !values.is_empty() {
+            let list = values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            self.filters
+                .push((field.to_string(), format!("= ({list})")));
+        }
+        self
+    }
+
+    pub(crate) fn sort(mut self, field: &str, order: Order) -> Self {
+        self.sort = Some((field.to_string(), order));
+        self
+    }
+
+    pub(crate) fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub(crate) fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// The APIcalypse query string. Fails on an empty field list, a field or filter name
+    /// outside `a-z`, `0-9`, `_`, `.` (`*` allowed in fields), or a limit outside 1..=500.
+    pub(crate) fn build(&self) -> Result<String> {
+        if self.fields.iter().all(|f| f.is_empty()) {
+            bail!("IGDB query selects no fields");
+        }
+        for field in &self.fields {
+            validate_field(field, true)?;
+        }
+        for (field, _) in &self.filters {
+            validate_field(field, false)?;
+        }
+        if let Some((field, _)) = &self.sort {
+            validate_field(field, false)?;
+        }
+        if let Some(limit) = self.limit {
+            if !(1..=MAX_LIMIT).contains(&limit) {
+                bail!("IGDB query limit {limit} outside 1..={MAX_LIMIT}");
+            }
+        }
+
+        let mut query = format!("fields {};", self.fields.join(","));
+        if !self.filters.is_empty() {
+            let conditions = self
+                .filters
+                .iter()
+                .map(|(field, cond)| format!("{field} {cond}"))
+                .collect::<Vec<_>>()
+                .join(" & ");
+            query.push_str(&format!(" where {conditions};"));
+        }
+        if let Some((field, order)) = &self.sort {
+            let order = match order {
+                Order::Asc => "asc",
+                Order::Desc => "desc",
+            };
+            query.push_str(&format!(" sort {field} {order};"));
+        }
+        if let Some(limit) = self.limit {
+            query.push_str(&format!(" limit {limit};"));
+        }
+        if self.offset > 0 || self.limit.is_some() {
+            query.push_str(&format!(" offset {};", self.offset));
+        }
+        Ok(query)
+    }
+}
+
+fn validate_field(field: &str, allow_wildcard: bool) -> Result<()> {
+    let valid = !field.is_empty()
+        && field.chars().all(|c| {
+            c.is_ascii_lowercase()
+                || c.is_ascii_digit()
+                || matches!(c, '_' | '.')
+                || (allow_wildcard && c == '*')
+        });
+    if !valid {
+        bail!("invalid IGDB field {field:?}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn year_range_and_platform_filter() {
+        let query = IgdbQuery::new("id,name,platforms.slug")
+            .filter("first_release_date", Cmp::Ge, 1_577_836_800)
+            .filter("first_release_date", Cmp::Le, 1_609_459_199)
+            .filter_any("platforms", &[48, 167])
+            .sort("first_release_date", Order::Desc)
+            .limit(200)
+            .offset(400)
+            .build()
+            .unwrap();
+        assert_eq!(
+            query,
+            "fields id,name,platforms.slug; \
+             where first_release_date >= 1577836800 & first_release_date <= 1609459199 \
+             & platforms = (48,167); \
+             sort first_release_date desc; limit 200; offset 400;"
+        );
+    }
+
+    #[test]
+    fn invalid_queries_fail_at_build() {
+        let no_platforms: [i32; 0] = [];
+        assert_eq!(
+            IgdbQuery::new("*")
+                .filter_any("platforms", &no_platforms)
+                .build()
+                .unwrap(),
+            "fields *;"
+        );
+        assert!(IgdbQuery::new("").build().is_err());
+        assert!(IgdbQuery::new("id,name; limit 5").build().is_err());
+        assert!(IgdbQuery::new("id")
+            .filter("rating; drop", Cmp::Gt, 1)
+            .build()
+            .is_err());
+        assert!(IgdbQuery::new("id").limit(501).build().is_err());
+        assert!(IgdbQuery::new("id").limit(0).build().is_err());
+    }
+}
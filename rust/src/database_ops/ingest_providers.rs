@@ -3,6 +3,11 @@ use crate::database_ops::ensure_video_game_for_product_enhanced::{
     ensure_video_game_for_product_enhanced, VideoGameProductMetadata,
 };
 use crate::database_ops::exchange::ExchangeService;
+use crate::database_ops::schema_caps::SchemaCaps;
+use crate::database_ops::schema_profile::SchemaProfile;
+use crate::normalization::display_title::{DisplayTitlePolicy, TitleCandidate};
+use crate::normalization::external_id::resolve_by_external_id;
+use crate::normalization::metadata_priority::{merge_prioritized, MetadataPriority, SOURCES_KEY};
 use crate::normalization::platform::{PlatformKey, MIN_PLATFORM_SIMILARITY};
 use crate::normalization::rating::{RatingAlias, RatingMapper, RatingStrategy};
 use anyhow::{anyhow, bail, Result};
@@ -368,7 +373,6 @@ fn env_truthy(name: &str) -> bool {
     }
 }
 
-static PHP_COMPAT_MODE: OnceCell<bool> = OnceCell::const_new();
 static OFFER_COMPAT: OnceCell<(
     std::sync::atomic::AtomicI64,
     Mutex<HashMap<i64, (i64, i64, Option<String>)>>,
@@ -524,33 +528,9 @@ pub async fn table_exists(db: &Db, name: &str) -> Result<bool> {
     Ok(visible)
 }
 
+/// Whether the process-wide [`SchemaProfile`] is the Laravel one.
 pub async fn php_compat_schema(db: &Db) -> Result<bool> {
-    let compat = PHP_COMPAT_MODE
-        .get_or_try_init(|| async {
-            let sku_regions = table_exists(db, "sku_regions").await.unwrap_or(false);
-            let region_prices = table_exists(db, "region_prices").await.unwrap_or(false);
-            if !sku_regions || !region_prices {
-                return Ok::<bool, anyhow::Error>(false);
-            }
-
-            // Supporting tables are optional in legacy-only environments; warn if absent but keep compat on so callers can
-            // take a best-effort path against sku_regions alone.
-            let countries = table_exists(db, "countries").await.unwrap_or(false);
-            let jurisdictions = table_exists(db, "jurisdictions").await.unwrap_or(false);
-            if !countries || !jurisdictions {
-                warn!(
-                    countries,
-                    jurisdictions,
-                    "php compat: supporting tables partially missing; sku_regions/region_prices are present, but jurisdictions/countries may be absent. We will treat sku_regions.id as the canonical 'jurisdiction' identifier when possible. If a caller only has a country/jurisdiction id and needs region_code mapping, set GC_ALLOW_COUNTRY_ONLY_JURISDICTIONS=1 to allow treating jurisdiction_id as country_id (best-effort)."
-                );
-            }
-            Ok::<bool, anyhow::Error>(true)
-        })
-        .await?;
-    if *compat {
-        debug!("php compatibility mode enabled (sku_regions detected)");
-    }
-    Ok(*compat)
+    Ok(SchemaProfile::current(db).await?.is_php_compat())
 }
 
 pub struct IngestResult {
@@ -558,6 +538,28 @@ pub struct IngestResult {
     pub current_updates: Vec<CurrentPriceRow>,
 }
 
+/// What an ingest pulled out of provider payloads, counted whether or not it was written.
+#[derive(Debug, Default, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ExtractionSummary {
+    pub products_seen: usize,
+    pub genres_extracted: usize,
+    pub media_images: usize,
+    pub media_videos: usize,
+    /// Character length of each synopsis found, in the order products were seen.
+    pub synopsis_lengths: Vec<usize>,
+}
+
+impl ExtractionSummary {
+    /// Add another run's counts; its synopsis lengths follow this run's.
+    pub fn absorb(&mut self, other: ExtractionSummary) {
+        self.products_seen += other.products_seen;
+        self.genres_extracted += other.genres_extracted;
+        self.media_images += other.media_images;
+        self.media_videos += other.media_videos;
+        self.synopsis_lengths.extend(other.synopsis_lengths);
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct PostIngestSummary {
     pub video_game_source_ids: HashSet<i64>,
@@ -568,6 +570,9 @@ pub struct PostIngestSummary {
     pub bundle_rows_skipped: usize,
     pub bundle_offer_jurisdictions_ingested: HashSet<i64>,
     pub bundle_offer_jurisdictions_skipped: HashSet<i64>,
+    /// Set when the run skipped every database write; write counts above stay zero.
+    pub dry_run: bool,
+    pub extraction: ExtractionSummary,
 }
 
 impl PostIngestSummary {
@@ -604,6 +609,24 @@ impl PostIngestSummary {
             .insert(offer_jurisdiction_id);
     }
 
+    /// Fold a partial summary (e.g. one locale of a concurrent run) into this one. The
+    /// `dry_run` flag is left as is.
+    pub fn absorb(&mut self, other: PostIngestSummary) {
+        self.video_game_source_ids
+            .extend(other.video_game_source_ids);
+        self.offer_jurisdiction_ids
+            .extend(other.offer_jurisdiction_ids);
+        self.total_price_rows_written += other.total_price_rows_written;
+        self.total_current_updates += other.total_current_updates;
+        self.bundle_rows_ingested += other.bundle_rows_ingested;
+        self.bundle_rows_skipped += other.bundle_rows_skipped;
+        self.bundle_offer_jurisdictions_ingested
+            .extend(other.bundle_offer_jurisdictions_ingested);
+        self.bundle_offer_jurisdictions_skipped
+            .extend(other.bundle_offer_jurisdictions_skipped);
+        self.extraction.absorb(other.extraction);
+    }
+
     pub async fn verify(&self, db: &Db, provider_id: i64) -> Result<()> {
         verify_post_ingest(
             db,
@@ -1254,8 +1277,8 @@ pub async fn ensure_retailer(db: &Db, name: &str, slug: Option<&str>) -> Result<
 pub async fn ingest_prices(db: &Db, price_rows: Vec<PriceRow>) -> Result<IngestResult> {
     // Canonical pricing write path: insert into Laravel-style sku_regions/region_prices.
     // Hard requirement: we do NOT fall back to legacy prices/current_price writes.
-    let compat = php_compat_schema(db).await?;
-    if !compat {
+    let profile = SchemaProfile::current(db).await?;
+    if !profile.is_php_compat() {
         return Err(anyhow!(
             "canonical pricing schema required (sku_regions + region_prices). Refusing to write to legacy prices/current_price tables."
         ));
@@ -1281,19 +1304,16 @@ pub async fn ingest_prices(db: &Db, price_rows: Vec<PriceRow>) -> Result<IngestR
     let has_countries = table_exists(db, "countries").await.unwrap_or(false);
     // Canonical Laravel schema uses currencies.decimals; older DBs may have currencies.minor_unit.
     // If neither exists, treat minor_unit as 2.
-    let currencies_has_minor_unit = table_column_exists(db, "currencies", "minor_unit")
-        .await
-        .unwrap_or(false);
-    let currencies_has_decimals = table_column_exists(db, "currencies", "decimals")
-        .await
-        .unwrap_or(false);
-    let currency_minor_expr = if currencies_has_minor_unit {
-        "COALESCE(curr.minor_unit, 2)".to_string()
-    } else if currencies_has_decimals {
-        "COALESCE(curr.decimals, 2)".to_string()
-    } else {
-        "2::smallint".to_string()
-    };
+    let mut currency_minor_expr = "2::smallint".to_string();
+    for column in profile.currency_minor_unit_columns() {
+        if table_column_exists(db, "currencies", column)
+            .await
+            .unwrap_or(false)
+        {
+            currency_minor_expr = format!("COALESCE(curr.{column}, 2)::smallint");
+            break;
+        }
+    }
     let mapping_sql = if has_countries {
         let country_schema = country_schema(db).await?;
         let code_col = country_schema.code_column().ok_or_else(|| {
@@ -1689,6 +1709,7 @@ pub struct ProviderEntityCache {
     title_ids_by_source_item: HashMap<(i64, String), i64>,
     video_game_ids: HashMap<(i64, i64, Option<String>), i64>,
     video_game_ids_by_product: HashMap<i64, i64>, // Laravel schema: cache by product_id
+    video_game_ids_by_external: HashMap<(String, String), Option<i64>>,
     sellable_ids: HashMap<(String, i64), i64>,
     offer_ids: HashMap<(i64, i64, Option<String>), i64>,
     offer_jurisdictions: HashMap<(i64, i64), i64>,
@@ -1705,6 +1726,7 @@ impl ProviderEntityCache {
             title_ids_by_source_item: HashMap::new(),
             video_game_ids: HashMap::new(),
             video_game_ids_by_product: HashMap::new(),
+            video_game_ids_by_external: HashMap::new(),
             sellable_ids: HashMap::new(),
             offer_ids: HashMap::new(),
             offer_jurisdictions: HashMap::new(),
@@ -1719,6 +1741,7 @@ impl ProviderEntityCache {
         self.title_ids_by_source_item.clear();
         self.video_game_ids.clear();
         self.video_game_ids_by_product.clear();
+        self.video_game_ids_by_external.clear();
         self.sellable_ids.clear();
         self.offer_ids.clear();
         self.offer_jurisdictions.clear();
@@ -1902,6 +1925,52 @@ impl ProviderEntityCache {
         Ok(id)
     }
 
+    /// Laravel schema: video game for a storefront item. Prefers the game a catalogue
+    /// provider (IGDB `external_games`) links to the item's store id, so the listing merges
+    /// into that game (and its metadata merges into the game's); otherwise falls back to the
+    /// product's own (title-matched) video game. Lookups, hits and misses, are cached.
+    pub async fn ensure_video_game_for_store_item(
+        &mut self,
+        external_id: &str,
+        product_id: i64,
+        title: &str,
+        slug: Option<&str>,
+        metadata: Option<serde_json::Value>,
+        provider_key: &str,
+    ) -> Result<i64> {
+        let key = (
+            provider_key.trim().to_ascii_lowercase(),
+            external_id.trim().to_string(),
+        );
+        let resolved = match self.video_game_ids_by_external.get(&key) {
+            Some(cached) => *cached,
+            None => match resolve_by_external_id(&self.db, provider_key, external_id).await {
+                Ok(found) => {
+                    self.video_game_ids_by_external.insert(key, found);
+                    found
+                }
+                Err(err) => {
+                    warn!(provider_key, external_id, error = %err, "external id lookup failed; matching by title");
+                    None
+                }
+            },
+        };
+        if let Some(id) = resolved {
+            debug!(
+                provider_key,
+                external_id,
+                video_game_id = id,
+                "matched video game by external id"
+            );
+            if let Some(patch) = metadata.filter(|m| m.is_object()) {
+                merge_video_game_metadata(&self.db, id, provider_key, patch).await?;
+            }
+            return Ok(id);
+        }
+        self.ensure_video_game_for_product_laravel(product_id, title, slug, metadata, provider_key)
+            .await
+    }
+
     /// Laravel schema: ensure video_game row with full metadata enrichment
     /// Pass all available fields (rating, genres, developer, release_date, etc.)
     /// `provider_key` is required so downstream rating mappers can resolve aliases.
@@ -3064,30 +3133,24 @@ pub async fn ensure_provider_item(
         return Ok(0);
     }
 
-    if let Some(rec) =
-        sqlx::query("SELECT id FROM provider_items WHERE provider_id=$1 AND external_id=$2")
-            .persistent(false)
-            .bind(provider_id)
-            .bind(external_id)
-            .fetch_optional(&db.pool)
-            .await?
+    // Re-linking marks the item as refreshed (staleness ranks by updated_at); metadata is
+    // only replaced when provided.
+    if let Some(rec) = sqlx::query(
+        "UPDATE provider_items SET metadata=COALESCE($3, metadata), updated_at=now() \
+         WHERE provider_id=$1 AND external_id=$2 RETURNING id",
+    )
+    .persistent(false)
+    .bind(provider_id)
+    .bind(external_id)
+    .bind(&metadata)
+    .fetch_optional(&db.pool)
+    .await?
     {
-        let existing_id: i64 = rec.get("id");
-        // If metadata provided, refresh it (idempotent update)
-        if metadata.is_some() {
-            let _ =
-                sqlx::query("UPDATE provider_items SET metadata=$1, updated_at=now() WHERE id=$2")
-                    .persistent(false)
-                    .bind(&metadata)
-                    .bind(existing_id)
-                    .execute(&db.pool)
-                    .await?;
-        }
-        return Ok(existing_id);
+        return Ok(rec.get("id"));
     }
     let rec = sqlx
         ::query(
-            "INSERT INTO provider_items (provider_id, external_id, metadata) VALUES ($1,$2,$3) RETURNING id"
+            "INSERT INTO provider_items (provider_id, external_id, metadata, updated_at) VALUES ($1,$2,$3,now()) RETURNING id"
         )
         .persistent(false)
         .bind(provider_id)
@@ -3833,30 +3896,50 @@ pub async fn ensure_sellable_for_title(db: &Db, title_id: i64) -> Result<i64> {
         return ensure_sellable(db, "software", product_id).await;
     }
 
-    if let Some(row) = sqlx::query("SELECT id FROM sellables WHERE software_title_id=$1")
-        .persistent(false)
-        .bind(title_id)
-        .fetch_optional(&db.pool)
-        .await?
-    {
+    let select = || {
+        sqlx::query("SELECT id FROM sellables WHERE software_title_id=$1")
+            .persistent(false)
+            .bind(title_id)
+    };
+    if let Some(row) = select().fetch_optional(&db.pool).await? {
         return Ok(row.get("id"));
     }
-    let row = sqlx::query(
-        "INSERT INTO sellables (kind, software_title_id) VALUES ('software',$1) RETURNING id",
+    insert_or_select_id(
+        db,
+        sqlx::query(
+            "INSERT INTO sellables (kind, software_title_id) VALUES ('software',$1) ON CONFLICT DO NOTHING RETURNING id",
+        )
+        .persistent(false)
+        .bind(title_id),
+        select(),
+        "sellable",
     )
-    .persistent(false)
-    .bind(title_id)
-    .fetch_one(&db.pool)
-    .await?;
-    Ok(row.get("id"))
+    .await
 }
 
-#[instrument(skip(db))]
-pub async fn ensure_sellable(db: &Db, kind: &str, product_id: i64) -> Result<i64> {
-    if !table_exists(db, "sellables").await.unwrap_or(false) {
-        return Ok(0);
+/// Id of the row `insert` creates, or of the row `select` finds when the insert hit a
+/// unique key instead: an earlier run, or a concurrent ensure for the same key (pipeline
+/// locales run in parallel). `insert` must end in `ON CONFLICT DO NOTHING RETURNING id`.
+async fn insert_or_select_id<'q>(
+    db: &Db,
+    insert: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+    select: sqlx::query::Query<'q, sqlx::Postgres, sqlx::postgres::PgArguments>,
+    what: &str,
+) -> Result<i64> {
+    if let Some(row) = insert.fetch_optional(&db.pool).await? {
+        return Ok(row.get("id"));
     }
+    select
+        .fetch_optional(&db.pool)
+        .await?
+        .map(|row| row.get("id"))
+        .ok_or_else(|| anyhow!("{what} insert conflicted but no matching row is visible"))
+}
 
+#[instrument(skip(db))]
+pub async fn ensure_sellable(db: &Db, kind: &str, product_id: i64) -> Result<i64> {
+    // php compat synthesizes ids whether or not a sellables table exists; offer and
+    // sku_region lookups resolve them through SELLABLE_COMPAT, never the table.
     if php_compat_schema(db).await.unwrap_or(false) {
         let (counter, map) = SELLABLE_COMPAT
             .get_or_init(|| async {
@@ -3877,6 +3960,9 @@ pub async fn ensure_sellable(db: &Db, kind: &str, product_id: i64) -> Result<i64
         return Ok(new_id);
     }
 
+    if !table_exists(db, "sellables").await.unwrap_or(false) {
+        return Ok(0);
+    }
     let schema = get_sellable_schema(db).await?;
     match kind {
         "software" => {
@@ -3889,40 +3975,31 @@ pub async fn ensure_sellable(db: &Db, kind: &str, product_id: i64) -> Result<i64
                             product_id
                         )
                     })?;
-                if
-                    let Some(rec) = sqlx
-                        ::query(
-                            "SELECT id FROM sellables WHERE kind=$1::sellable_kind AND software_title_id=$2"
-                        )
-                        .persistent(false)
-                        .bind(kind)
-                        .bind(title_id)
-                        .fetch_optional(&db.pool).await?
-                {
-                    let id: i64 = rec.get("id");
-                    let vg_cols = video_games_content_columns(db).await.unwrap_or_default();
-                    if vg_cols.has_sellable_id && vg_cols.has_title_id {
-                        let _ = sqlx
-                            ::query(
-                                "UPDATE video_games SET sellable_id=$1 WHERE title_id=$2 AND (sellable_id IS DISTINCT FROM $1)"
-                            )
-                            .persistent(false)
-                            .bind(id)
-                            .bind(title_id)
-                            .execute(&db.pool)
-                            .await?;
-                    }
-                    return Ok(id);
-                }
-                let inserted = sqlx
-                    ::query(
-                        "INSERT INTO sellables (kind, software_title_id) VALUES ($1::sellable_kind,$2) RETURNING id"
+                let select = || {
+                    sqlx::query(
+                        "SELECT id FROM sellables WHERE kind=$1::sellable_kind AND software_title_id=$2",
                     )
                     .persistent(false)
                     .bind(kind)
                     .bind(title_id)
-                    .fetch_one(&db.pool).await?;
-                let sellable_id: i64 = inserted.get("id");
+                };
+                let sellable_id = match select().fetch_optional(&db.pool).await? {
+                    Some(rec) => rec.get("id"),
+                    None => {
+                        insert_or_select_id(
+                            db,
+                            sqlx::query(
+                                "INSERT INTO sellables (kind, software_title_id) VALUES ($1::sellable_kind,$2) ON CONFLICT DO NOTHING RETURNING id",
+                            )
+                            .persistent(false)
+                            .bind(kind)
+                            .bind(title_id),
+                            select(),
+                            "sellable",
+                        )
+                        .await?
+                    }
+                };
                 let vg_cols = video_games_content_columns(db).await.unwrap_or_default();
                 if vg_cols.has_sellable_id && vg_cols.has_title_id {
                     let _ = sqlx
@@ -3937,73 +4014,16 @@ pub async fn ensure_sellable(db: &Db, kind: &str, product_id: i64) -> Result<i64
                 }
                 Ok(sellable_id)
             } else if schema.has_product_id {
-                if let Some(rec) = sqlx::query(
-                    "SELECT id FROM sellables WHERE kind=$1::sellable_kind AND product_id=$2",
-                )
-                .persistent(false)
-                .bind(kind)
-                .bind(product_id)
-                .fetch_optional(&db.pool)
-                .await?
-                {
-                    return Ok(rec.get("id"));
-                }
-                let inserted = sqlx
-                    ::query(
-                        "INSERT INTO sellables (kind, product_id) VALUES ($1::sellable_kind,$2) RETURNING id"
-                    )
-                    .persistent(false)
-                    .bind(kind)
-                    .bind(product_id)
-                    .fetch_one(&db.pool).await?;
-                Ok(inserted.get("id"))
+                ensure_sellable_by(db, kind, "product_id", product_id).await
             } else {
                 anyhow::bail!("sellables table missing software linkage columns")
             }
         }
         "hardware" => {
             if schema.has_console_id {
-                if let Some(rec) = sqlx::query(
-                    "SELECT id FROM sellables WHERE kind=$1::sellable_kind AND console_id=$2",
-                )
-                .persistent(false)
-                .bind(kind)
-                .bind(product_id)
-                .fetch_optional(&db.pool)
-                .await?
-                {
-                    return Ok(rec.get("id"));
-                }
-                let inserted = sqlx
-                    ::query(
-                        "INSERT INTO sellables (kind, console_id) VALUES ($1::sellable_kind,$2) RETURNING id"
-                    )
-                    .persistent(false)
-                    .bind(kind)
-                    .bind(product_id)
-                    .fetch_one(&db.pool).await?;
-                Ok(inserted.get("id"))
+                ensure_sellable_by(db, kind, "console_id", product_id).await
             } else if schema.has_product_id {
-                if let Some(rec) = sqlx::query(
-                    "SELECT id FROM sellables WHERE kind=$1::sellable_kind AND product_id=$2",
-                )
-                .persistent(false)
-                .bind(kind)
-                .bind(product_id)
-                .fetch_optional(&db.pool)
-                .await?
-                {
-                    return Ok(rec.get("id"));
-                }
-                let inserted = sqlx
-                    ::query(
-                        "INSERT INTO sellables (kind, product_id) VALUES ($1::sellable_kind,$2) RETURNING id"
-                    )
-                    .persistent(false)
-                    .bind(kind)
-                    .bind(product_id)
-                    .fetch_one(&db.pool).await?;
-                Ok(inserted.get("id"))
+                ensure_sellable_by(db, kind, "product_id", product_id).await
             } else {
                 anyhow::bail!("sellables table missing hardware linkage columns")
             }
@@ -4012,6 +4032,34 @@ pub async fn ensure_sellable(db: &Db, kind: &str, product_id: i64) -> Result<i64
     }
 }
 
+/// Sellable of `kind` linked through `column` (`product_id` or `console_id`).
+async fn ensure_sellable_by(db: &Db, kind: &str, column: &str, linked_id: i64) -> Result<i64> {
+    let select_sql =
+        format!("SELECT id FROM sellables WHERE kind=$1::sellable_kind AND {column}=$2");
+    let select = || {
+        sqlx::query(&select_sql)
+            .persistent(false)
+            .bind(kind)
+            .bind(linked_id)
+    };
+    if let Some(rec) = select().fetch_optional(&db.pool).await? {
+        return Ok(rec.get("id"));
+    }
+    let insert_sql = format!(
+        "INSERT INTO sellables (kind, {column}) VALUES ($1::sellable_kind,$2) ON CONFLICT DO NOTHING RETURNING id"
+    );
+    insert_or_select_id(
+        db,
+        sqlx::query(&insert_sql)
+            .persistent(false)
+            .bind(kind)
+            .bind(linked_id),
+        select(),
+        "sellable",
+    )
+    .await
+}
+
 #[instrument(skip(db))]
 pub async fn ensure_offer(
     db: &Db,
@@ -4019,10 +4067,8 @@ pub async fn ensure_offer(
     retailer_id: i64,
     sku: Option<&str>,
 ) -> Result<i64> {
-    if !table_exists(db, "offers").await.unwrap_or(false) {
-        return Ok(0);
-    }
-
+    // As in ensure_sellable: ensure_offer_jurisdiction needs the synthetic id even when
+    // the schema has no offers table.
     if php_compat_schema(db).await.unwrap_or(false) {
         let (counter, map) = OFFER_COMPAT
             .get_or_init(|| async {
@@ -4050,29 +4096,34 @@ pub async fn ensure_offer(
         return Ok(new_id);
     }
 
-    if
-        let Some(rec) = sqlx
-            ::query(
-                "SELECT id FROM offers WHERE sellable_id=$1 AND retailer_id=$2 AND sku IS NOT DISTINCT FROM $3"
-            )
-            .persistent(false)
-            .bind(sellable_id)
-            .bind(retailer_id)
-            .bind(sku)
-            .fetch_optional(&db.pool).await?
-    {
+    if !table_exists(db, "offers").await.unwrap_or(false) {
+        return Ok(0);
+    }
+    let select = || {
+        sqlx::query(
+            "SELECT id FROM offers WHERE sellable_id=$1 AND retailer_id=$2 AND sku IS NOT DISTINCT FROM $3",
+        )
+        .persistent(false)
+        .bind(sellable_id)
+        .bind(retailer_id)
+        .bind(sku)
+    };
+    if let Some(rec) = select().fetch_optional(&db.pool).await? {
         return Ok(rec.get("id"));
     }
-    let inserted = sqlx::query(
-        "INSERT INTO offers (sellable_id, retailer_id, sku) VALUES ($1,$2,$3) RETURNING id",
+    insert_or_select_id(
+        db,
+        sqlx::query(
+            "INSERT INTO offers (sellable_id, retailer_id, sku) VALUES ($1,$2,$3) ON CONFLICT DO NOTHING RETURNING id",
+        )
+        .persistent(false)
+        .bind(sellable_id)
+        .bind(retailer_id)
+        .bind(sku),
+        select(),
+        "offer",
     )
-    .persistent(false)
-    .bind(sellable_id)
-    .bind(retailer_id)
-    .bind(sku)
-    .fetch_one(&db.pool)
-    .await?;
-    Ok(inserted.get("id"))
+    .await
 }
 
 #[instrument(skip(db))]
@@ -4082,7 +4133,8 @@ pub async fn ensure_offer_jurisdiction(
     jurisdiction_id: i64,
     currency_id: i64,
 ) -> Result<i64> {
-    let compat = php_compat_schema(db).await.unwrap_or(false);
+    let profile = SchemaProfile::current(db).await.unwrap_or_default();
+    let compat = profile.is_php_compat();
     let has_jurisdictions = *JURISDICTIONS_PRESENT
         .get_or_try_init(|| async { table_exists(db, "jurisdictions").await })
         .await?;
@@ -4136,28 +4188,31 @@ pub async fn ensure_offer_jurisdiction(
             }
         };
 
-        // retailer string (prefer modern retailers, else game_retailers)
-        let retailer_key: String = if table_exists(db, "retailers").await.unwrap_or(false) {
-            sqlx::query_scalar("SELECT COALESCE(slug, name, 'retailer') FROM retailers WHERE id=$1")
+        // retailer string, from the table ensure_retailer writes to in this profile
+        // (game_retailers in php compat), else the other one
+        let mut retailer_key = "retailer".to_string();
+        for table in profile.retailer_tables() {
+            if !SchemaCaps::global()
+                .table_visible(db, table)
+                .await
+                .unwrap_or(false)
+            {
+                continue;
+            }
+            let key: Option<String> = sqlx::query_scalar(&SchemaProfile::retailer_key_sql(table))
                 .persistent(false)
                 .bind(retailer_id)
                 .fetch_optional(&db.pool)
-                .await?
-                .ok_or_else(|| {
-                    anyhow::anyhow!("retailer {} missing (offer {})", retailer_id, offer_id)
-                })?
-        } else if table_exists(db, "game_retailers").await.unwrap_or(false) {
-            sqlx::query_scalar(
-                "SELECT COALESCE(retailer_key, slug, name, 'retailer') FROM game_retailers WHERE id=$1",
-            )
-            .persistent(false)
-            .bind(retailer_id)
-            .fetch_optional(&db.pool)
-            .await?
-            .unwrap_or_else(|| "retailer".to_string())
-        } else {
-            "retailer".to_string()
-        };
+                .await?;
+            match key {
+                Some(key) => retailer_key = key,
+                None if table == "retailers" => {
+                    anyhow::bail!("retailer {} missing (offer {})", retailer_id, offer_id)
+                }
+                None => {}
+            }
+            break;
+        }
 
         // php-compat default: treat sku_regions as jurisdictions.
         // If the caller is already passing sku_regions.id in the jurisdiction_id slot,
@@ -4368,26 +4423,29 @@ pub async fn ensure_offer_jurisdiction(
         return Ok(0);
     }
 
-    if let Some(rec) =
+    let select = || {
         sqlx::query("SELECT id FROM offer_jurisdictions WHERE offer_id=$1 AND jurisdiction_id=$2")
             .persistent(false)
             .bind(offer_id)
             .bind(jurisdiction_id)
-            .fetch_optional(&db.pool)
-            .await?
-    {
+    };
+    if let Some(rec) = select().fetch_optional(&db.pool).await? {
         return Ok(rec.get("id"));
     }
-    let inserted = sqlx
-        ::query(
-            "INSERT INTO offer_jurisdictions (offer_id, jurisdiction_id, currency_id) VALUES ($1,$2,$3) RETURNING id"
+    // Locales sharing a country (en-US, es-US) reach the same row concurrently.
+    insert_or_select_id(
+        db,
+        sqlx::query(
+            "INSERT INTO offer_jurisdictions (offer_id, jurisdiction_id, currency_id) VALUES ($1,$2,$3) ON CONFLICT DO NOTHING RETURNING id",
         )
         .persistent(false)
         .bind(offer_id)
         .bind(jurisdiction_id)
-        .bind(currency_id)
-        .fetch_one(&db.pool).await?;
-    Ok(inserted.get("id"))
+        .bind(currency_id),
+        select(),
+        "offer_jurisdiction",
+    )
+    .await
 }
 
 /// Batch create or retrieve multiple offers.
@@ -4765,26 +4823,310 @@ pub async fn ensure_product_named_with_platform(
     ensure_product_named(db, category, slug, name).await
 }
 
-// --------- Jurisdictional helpers (currencies, countries, jurisdictions) ---------
+/// Rows [`ensure_products_batch`] resolved for one product slug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnsuredProduct {
+    pub product_id: i64,
+    pub title_id: i64,
+    pub video_game_id: i64,
+}
 
-#[instrument(skip(db))]
-pub async fn ensure_currency(db: &Db, code: &str, name: &str, minor_unit: i16) -> Result<i64> {
-    if let Some(rec) = sqlx::query("SELECT id FROM currencies WHERE code=$1")
+/// What [`ensure_products_batch`] resolved, keyed by slug.
+#[derive(Debug, Default)]
+pub struct ProductBatch {
+    pub products: HashMap<String, EnsuredProduct>,
+    /// Set when the schema needed the per-product ensures instead.
+    pub fallback: bool,
+}
+
+/// Batched `ensure_product_named` + `ensure_software_row` + `ensure_video_game_title` +
+/// `ensure_video_game` (no edition) for a page of `(slug, name)` pairs on one platform:
+/// a fixed number of multi-row statements however many products, where the per-product
+/// ensures take four round-trips each. The software, title and video_game writes share one
+/// transaction holding an advisory lock per product, so concurrent pages over the same
+/// products neither fail nor duplicate titles. Schemas the multi-row path does not cover (titles keyed by
+/// video_game or source item, video_games without title_id/platform_id) get the
+/// per-product ensures. A repeated slug is resolved once, under its first name.
+#[instrument(skip(db, items), fields(items = items.len()))]
+pub async fn ensure_products_batch(
+    db: &Db,
+    category: &str,
+    platform_id: i64,
+    items: &[(String, String)],
+) -> Result<ProductBatch> {
+    let mut seen = HashSet::new();
+    let items: Vec<&(String, String)> = items
+        .iter()
+        .filter(|(slug, _)| seen.insert(slug.as_str()))
+        .collect();
+    let mut batch = ProductBatch::default();
+    if items.is_empty() {
+        return Ok(batch);
+    }
+    if !products_batch_supported(db).await? {
+        batch.fallback = true;
+        for (slug, name) in items {
+            let product_id = ensure_product_named(db, category, slug, name).await?;
+            ensure_software_row(db, product_id).await?;
+            let title_id =
+                ensure_video_game_title(db, product_id, name, Some(slug.as_str())).await?;
+            let video_game_id = ensure_video_game(db, title_id, platform_id, None).await?;
+            batch.products.insert(
+                slug.clone(),
+                EnsuredProduct {
+                    product_id,
+                    title_id,
+                    video_game_id,
+                },
+            );
+        }
+        return Ok(batch);
+    }
+    let caps = SchemaCaps::global();
+
+    // Products, by slug.
+    let slugs: Vec<&str> = items.iter().map(|(slug, _)| slug.as_str()).collect();
+    let names: Vec<&str> = items.iter().map(|(_, name)| name.as_str()).collect();
+    let rows = sqlx::query(
+        "INSERT INTO products (slug, name, category)
+         SELECT s, n, $3 FROM unnest($1::text[], $2::text[]) AS t(s, n)
+         ON CONFLICT (slug) DO UPDATE SET name = EXCLUDED.name, category = EXCLUDED.category
+         RETURNING id, slug",
+    )
+    .persistent(false)
+    .bind(&slugs)
+    .bind(&names)
+    .bind(category)
+    .fetch_all(&db.pool)
+    .await?;
+    let mut product_ids: HashMap<String, i64> = HashMap::with_capacity(rows.len());
+    for row in rows {
+        product_ids.insert(row.try_get("slug")?, row.try_get("id")?);
+    }
+
+    // Concurrent locales page through overlapping products, and titles have no unique key
+    // on product_id: lock the page's products (in id order) until the commit.
+    let mut ids: Vec<i64> = product_ids.values().copied().collect();
+    ids.sort_unstable();
+    let mut tx = db.pool.begin().await?;
+    sqlx::query(
+        "SELECT pg_advisory_xact_lock(hashtextextended('products_batch:' || p, 0))
+         FROM (SELECT p FROM unnest($1::bigint[]) AS p ORDER BY p) AS s",
+    )
+    .persistent(false)
+    .bind(&ids)
+    .execute(&mut *tx)
+    .await?;
+
+    // Legacy software rows, where the table still exists.
+    if caps.table_visible(db, "software").await? {
+        let column = match get_software_id_column(db).await? {
+            SoftwareIdColumn::VideoGameId => "video_game_id",
+            SoftwareIdColumn::ProductId => "product_id",
+        };
+        sqlx::query(&format!(
+            "INSERT INTO software ({column})
+             SELECT p FROM unnest($1::bigint[]) AS p
+             WHERE NOT EXISTS (SELECT 1 FROM software s WHERE s.{column} = p)"
+        ))
         .persistent(false)
-        .bind(code)
-        .fetch_optional(&db.pool)
-        .await?
-    {
-        return Ok(rec.get::<i64, _>("id"));
+        .bind(&ids)
+        .execute(&mut *tx)
+        .await?;
     }
-    let has_minor_unit = table_column_exists(db, "currencies", "minor_unit")
-        .await
+
+    // Titles, by product: the oldest existing one, else a new one.
+    let select_titles = "SELECT DISTINCT ON (product_id) product_id, id FROM video_game_titles
+         WHERE product_id = ANY($1)
+         ORDER BY product_id, id";
+    let mut title_ids: HashMap<i64, i64> = sqlx::query_as(select_titles)
+        .persistent(false)
+        .bind(&ids)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+    let (mut new_products, mut new_names, mut new_slugs) = (Vec::new(), Vec::new(), Vec::new());
+    for (slug, name) in &items {
+        let product_id = product_ids[slug.as_str()];
+        if !title_ids.contains_key(&product_id) {
+            new_products.push(product_id);
+            new_names.push(name.as_str());
+            new_slugs.push(slug.as_str());
+        }
+    }
+    if !new_products.is_empty() {
+        let name_column = get_video_game_title_schema(db).await?.name_column.as_str();
+        // DO NOTHING covers a unique index on (product_id, normalized_title) where present;
+        // rows it skipped were written by a per-product ensure and are read back below.
+        let inserted: Vec<(i64, i64)> = sqlx::query_as(&format!(
+            "INSERT INTO video_game_titles (product_id, {name_column}, normalized_title)
+             SELECT * FROM unnest($1::bigint[], $2::text[], $3::text[])
+             ON CONFLICT DO NOTHING
+             RETURNING product_id, id"
+        ))
+        .persistent(false)
+        .bind(&new_products)
+        .bind(&new_names)
+        .bind(&new_slugs)
+        .fetch_all(&mut *tx)
+        .await?;
+        let skipped = inserted.len() < new_products.len();
+        title_ids.extend(inserted);
+        if skipped {
+            let missing: Vec<i64> = new_products
+                .into_iter()
+                .filter(|product_id| !title_ids.contains_key(product_id))
+                .collect();
+            let found: Vec<(i64, i64)> = sqlx::query_as(select_titles)
+                .persistent(false)
+                .bind(&missing)
+                .fetch_all(&mut *tx)
+                .await?;
+            title_ids.extend(found);
+        }
+    }
+
+    // Video games, by title on this platform: the oldest existing one, else a new one.
+    let has_edition = caps.column_visible(db, "video_games", "edition").await?;
+    let titles: Vec<i64> = title_ids.values().copied().collect();
+    let select_video_games = format!(
+        "SELECT DISTINCT ON (title_id) title_id, id FROM video_games
+         WHERE title_id = ANY($1) AND platform_id = $2{}
+         ORDER BY title_id, id",
+        if has_edition {
+            " AND edition IS NULL"
+        } else {
+            ""
+        }
+    );
+    let mut video_game_ids: HashMap<i64, i64> = sqlx::query_as(&select_video_games)
+        .persistent(false)
+        .bind(&titles)
+        .bind(platform_id)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+    let new_titles: Vec<i64> = titles
+        .iter()
+        .copied()
+        .filter(|title_id| !video_game_ids.contains_key(title_id))
+        .collect();
+    if !new_titles.is_empty() {
+        let mut columns = vec!["title_id", "platform_id"];
+        let mut values = vec!["t", "$2"];
+        for stamp in ["created_at", "updated_at"] {
+            if caps.column_visible(db, "video_games", stamp).await? {
+                columns.push(stamp);
+                values.push("now()");
+            }
+        }
+        let inserted: Vec<(i64, i64)> = sqlx::query_as(&format!(
+            "INSERT INTO video_games ({})
+             SELECT {} FROM unnest($1::bigint[]) AS t
+             ON CONFLICT DO NOTHING
+             RETURNING title_id, id",
+            columns.join(", "),
+            values.join(", ")
+        ))
+        .persistent(false)
+        .bind(&new_titles)
+        .bind(platform_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let skipped = inserted.len() < new_titles.len();
+        video_game_ids.extend(inserted);
+        if skipped {
+            let missing: Vec<i64> = new_titles
+                .into_iter()
+                .filter(|title_id| !video_game_ids.contains_key(title_id))
+                .collect();
+            let found: Vec<(i64, i64)> = sqlx::query_as(&select_video_games)
+                .persistent(false)
+                .bind(&missing)
+                .bind(platform_id)
+                .fetch_all(&mut *tx)
+                .await?;
+            video_game_ids.extend(found);
+        }
+    }
+    tx.commit().await?;
+
+    for (slug, product_id) in product_ids {
+        let title_id = title_ids[&product_id];
+        batch.products.insert(
+            slug,
+            EnsuredProduct {
+                product_id,
+                title_id,
+                video_game_id: video_game_ids[&title_id],
+            },
+        );
+    }
+    Ok(batch)
+}
+
+/// Whether [`ensure_products_batch`] can use its multi-row statements: titles linked by
+/// `product_id` alone and video_games keyed by `(title_id, platform_id)`.
+async fn products_batch_supported(db: &Db) -> Result<bool> {
+    let caps = SchemaCaps::global();
+    for table in ["products", "video_game_titles", "video_games"] {
+        if !caps.table_visible(db, table).await? {
+            return Ok(false);
+        }
+    }
+    for column in ["title_id", "platform_id"] {
+        if !caps.column_visible(db, "video_games", column).await? {
+            return Ok(false);
+        }
+    }
+    let titles = get_video_game_title_schema(db).await?;
+    Ok(titles.has_product_id
+        && !titles.has_video_game_id
+        && !titles.has_video_game_ids
+        && (!titles.has_video_game_source_id || !titles.has_vg_source_item_id))
+}
+
+// --------- Jurisdictional helpers (currencies, countries, jurisdictions) ---------
+
+/// Currency id for `code`, creating the row with `minor_unit` when missing. An existing
+/// row keeps its stored `minor_unit`: amounts already written use that scale, so a change
+/// (e.g. a new `CURRENCY_MINOR_UNITS` override) has to ship as a migration that rescales
+/// them. A mismatch is logged.
+#[instrument(skip(db))]
+pub async fn ensure_currency(db: &Db, code: &str, name: &str, minor_unit: i16) -> Result<i64> {
+    // to_jsonb keeps this valid on legacy schemas without minor_unit.
+    if let Some(rec) = sqlx::query(
+        "SELECT id, (to_jsonb(c)->>'minor_unit')::smallint AS minor_unit
+         FROM currencies c WHERE code=$1",
+    )
+    .persistent(false)
+    .bind(code)
+    .fetch_optional(&db.pool)
+    .await?
+    {
+        let id: i64 = rec.get("id");
+        let stored: Option<i16> = rec.get("minor_unit");
+        if stored.is_some_and(|stored| stored != minor_unit) {
+            warn!(
+                %code,
+                ?stored,
+                minor_unit,
+                "currency minor unit differs from the stored row; keeping the stored value \
+                 (a change needs a migration that rescales stored amounts)"
+            );
+        }
+        return Ok(id);
+    }
+    let has_minor_unit = table_column_exists(db, "currencies", "minor_unit")
+        .await
         .unwrap_or(false);
     // Some legacy schemas don't have minor_unit; default to 2 implicitly.
     let rec = if has_minor_unit {
         sqlx::query(
             "INSERT INTO currencies (code,name,minor_unit) VALUES ($1,$2,$3) \
-             ON CONFLICT (code) DO UPDATE SET name=EXCLUDED.name, minor_unit=EXCLUDED.minor_unit \
+             ON CONFLICT (code) DO UPDATE SET name=EXCLUDED.name \
              RETURNING id",
         )
         .persistent(false)
@@ -5340,7 +5682,8 @@ pub async fn update_video_game_synopsis_prefer_longer(
     best_effort_execute(
         db,
         sqlx::query(
-            "UPDATE video_games SET synopsis = CASE WHEN synopsis IS NULL OR length(synopsis) < length($1) THEN $1 ELSE synopsis END WHERE id=$2",
+            "UPDATE video_games SET synopsis = $1, updated_at = now()
+             WHERE id=$2 AND (synopsis IS NULL OR length(synopsis) < length($1))",
         )
         .persistent(false)
         .bind(synopsis)
@@ -5351,63 +5694,136 @@ pub async fn update_video_game_synopsis_prefer_longer(
     Ok(())
 }
 
-/// Best-effort: update `video_games.display_title` and/or union `video_games.region_codes` when columns exist.
+/// Best-effort: offer `candidate` as `video_games.display_title` and union `region_code`
+/// into `video_games.region_codes`, when the columns exist. The title only replaces the
+/// current one when [`DisplayTitlePolicy`] (`DISPLAY_TITLE_POLICY`) ranks it above the
+/// source recorded in `metadata.display_title_source`; without a metadata column the
+/// first title written is kept.
 pub async fn update_video_game_display_title_and_region(
     db: &Db,
     video_game_id: i64,
-    display_title: &str,
-    region_code: &str,
+    candidate: &TitleCandidate,
+    region_code: Option<&str>,
 ) -> Result<()> {
     let cols = video_games_content_columns(db).await?;
-    let rc = region_code.to_ascii_uppercase();
 
-    match (cols.has_display_title, cols.has_region_codes) {
-        (true, true) => {
-            best_effort_execute(
-                db,
-                sqlx::query(
-                    "UPDATE video_games\nSET\n  display_title = COALESCE(display_title, $1),\n  region_codes = CASE\n    WHEN region_codes IS NULL THEN ARRAY[$2]::text[]\n    WHEN array_position(region_codes, $2) IS NULL THEN region_codes || ARRAY[$2]::text[]\n    ELSE region_codes\n  END\nWHERE\n  id = $3\n  AND (display_title IS NULL OR region_codes IS NULL OR array_position(region_codes, $2) IS NULL)",
-                )
-                .persistent(false)
-                .bind(display_title)
-                .bind(&rc)
-                .bind(video_game_id),
-                "display_title+region_codes",
-            )
-            .await;
-        }
-        (true, false) => {
-            best_effort_execute(
-                db,
-                sqlx::query(
-                    "UPDATE video_games SET display_title = $1 WHERE id=$2 AND display_title IS NULL",
-                )
-                .persistent(false)
-                .bind(display_title)
-                .bind(video_game_id),
-                "display_title",
-            )
-            .await;
+    if cols.has_display_title && cols.has_metadata {
+        if let Err(e) = update_display_title_by_policy(db, video_game_id, candidate, &cols).await {
+            warn!(error=%e, context = "display_title", "best-effort video_games update failed");
         }
-        (false, true) => {
-            best_effort_execute(
-                db,
-                sqlx::query(
-                    "UPDATE video_games\nSET\n  region_codes = CASE\n    WHEN region_codes IS NULL THEN ARRAY[$1]::text[]\n    WHEN array_position(region_codes, $1) IS NULL THEN region_codes || ARRAY[$1]::text[]\n    ELSE region_codes\n  END\nWHERE\n  id = $2\n  AND (region_codes IS NULL OR array_position(region_codes, $1) IS NULL)",
-                )
-                .persistent(false)
-                .bind(&rc)
-                .bind(video_game_id),
-                "region_codes",
+    } else if cols.has_display_title {
+        best_effort_execute(
+            db,
+            sqlx::query(
+                "UPDATE video_games SET display_title = $1, updated_at = now()
+                 WHERE id=$2 AND display_title IS NULL",
             )
-            .await;
-        }
-        (false, false) => {}
+            .persistent(false)
+            .bind(&candidate.title)
+            .bind(video_game_id),
+            "display_title",
+        )
+        .await;
+    }
+
+    if let Some(region_code) = region_code {
+        union_video_game_region_codes(db, video_game_id, &[region_code]).await?;
     }
 
     Ok(())
 }
 
+/// Best-effort: add `region_codes` (uppercased) to `video_games.region_codes` when the
+/// column exists. The union is computed inside one UPDATE (distinct, sorted), so
+/// concurrent callers adding different codes to the same game never drop each other's.
+pub async fn union_video_game_region_codes(
+    db: &Db,
+    video_game_id: i64,
+    region_codes: &[&str],
+) -> Result<()> {
+    let cols = video_games_content_columns(db).await?;
+    let codes: Vec<String> = region_codes
+        .iter()
+        .map(|c| c.trim().to_ascii_uppercase())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if !cols.has_region_codes || codes.is_empty() {
+        return Ok(());
+    }
+    best_effort_execute(
+        db,
+        sqlx::query(
+            "UPDATE video_games
+             SET region_codes = ARRAY(
+                 SELECT DISTINCT code
+                 FROM unnest(COALESCE(region_codes, '{}'::text[]) || $1::text[]) AS code
+                 ORDER BY code
+             )
+             WHERE id = $2
+               AND NOT (COALESCE(region_codes, '{}'::text[]) @> $1::text[])",
+        )
+        .persistent(false)
+        .bind(codes)
+        .bind(video_game_id),
+        "region_codes",
+    )
+    .await;
+    Ok(())
+}
+
+async fn update_display_title_by_policy(
+    db: &Db,
+    video_game_id: i64,
+    candidate: &TitleCandidate,
+    cols: &VideoGamesContentColumns,
+) -> Result<()> {
+    let row = sqlx::query(
+        "SELECT display_title, metadata::jsonb -> 'display_title_source' AS source FROM video_games WHERE id=$1",
+    )
+    .persistent(false)
+    .bind(video_game_id)
+    .fetch_optional(&db.pool)
+    .await?;
+    let Some(row) = row else {
+        return Ok(());
+    };
+    let current_title: Option<String> = row.try_get("display_title")?;
+    let recorded: Option<Value> = row.try_get("source")?;
+    let current = match (current_title.filter(|t| !t.trim().is_empty()), recorded) {
+        (None, _) => None,
+        (Some(title), Some(source)) => Some(TitleCandidate::new(
+            &title,
+            source.get("source").and_then(Value::as_str).unwrap_or(""),
+            source.get("language").and_then(Value::as_str),
+        )),
+        // A title written before sources were recorded: keep it only if nothing ranks.
+        (Some(title), None) => Some(TitleCandidate::new(&title, "", None)),
+    };
+    if current.as_ref().is_some_and(|c| c.title == candidate.title)
+        || !DisplayTitlePolicy::from_env().prefers(candidate, current.as_ref())
+    {
+        return Ok(());
+    }
+    let source = json!({
+        "source": candidate.source,
+        "language": candidate.language,
+        "title": candidate.title,
+    });
+    let sql = if cols.metadata_is_jsonb {
+        "UPDATE video_games SET display_title = $1, metadata = COALESCE(metadata, '{}'::jsonb) || jsonb_build_object('display_title_source', $2::jsonb), updated_at = now() WHERE id=$3"
+    } else {
+        "UPDATE video_games SET display_title = $1, metadata = (COALESCE(metadata::jsonb, '{}'::jsonb) || jsonb_build_object('display_title_source', $2::jsonb))::json, updated_at = now() WHERE id=$3"
+    };
+    sqlx::query(sql)
+        .persistent(false)
+        .bind(&candidate.title)
+        .bind(source)
+        .bind(video_game_id)
+        .execute(&db.pool)
+        .await?;
+    Ok(())
+}
+
 /// Best-effort: overwrite `video_games.genres` when the column exists.
 pub async fn update_video_game_genres(
     db: &Db,
@@ -5420,10 +5836,13 @@ pub async fn update_video_game_genres(
     }
     best_effort_execute(
         db,
-        sqlx::query("UPDATE video_games SET genres=$1 WHERE id=$2")
-            .persistent(false)
-            .bind(genres)
-            .bind(video_game_id),
+        sqlx::query(
+            "UPDATE video_games SET genres=$1, updated_at = now()
+             WHERE id=$2 AND genres IS DISTINCT FROM $1",
+        )
+        .persistent(false)
+        .bind(genres)
+        .bind(video_game_id),
         "genres",
     )
     .await;
@@ -5443,7 +5862,8 @@ pub async fn update_video_game_genres_if_empty(
     best_effort_execute(
         db,
         sqlx::query(
-            "UPDATE video_games SET genres = CASE WHEN genres IS NULL OR array_length(genres,1)=0 THEN $1 ELSE genres END WHERE id=$2",
+            "UPDATE video_games SET genres = $1, updated_at = now()
+             WHERE id=$2 AND (genres IS NULL OR array_length(genres,1)=0)",
         )
         .persistent(false)
         .bind(genres)
@@ -5466,10 +5886,13 @@ pub async fn update_video_game_release_date_if_null(
     }
     best_effort_execute(
         db,
-        sqlx::query("UPDATE video_games SET release_date = COALESCE(release_date, $1) WHERE id=$2")
-            .persistent(false)
-            .bind(release_date)
-            .bind(video_game_id),
+        sqlx::query(
+            "UPDATE video_games SET release_date = $1, updated_at = now()
+             WHERE id=$2 AND release_date IS NULL",
+        )
+        .persistent(false)
+        .bind(release_date)
+        .bind(video_game_id),
         "release_date",
     )
     .await;
@@ -5495,7 +5918,8 @@ pub async fn update_video_game_developer_if_empty(
     best_effort_execute(
         db,
         sqlx::query(
-            "UPDATE video_games SET developer = CASE WHEN developer IS NULL OR length(trim(developer))=0 THEN $1 ELSE developer END WHERE id=$2",
+            "UPDATE video_games SET developer = $1, updated_at = now()
+             WHERE id=$2 AND (developer IS NULL OR length(trim(developer))=0)",
         )
         .persistent(false)
         .bind(dev)
@@ -5507,27 +5931,146 @@ pub async fn update_video_game_developer_if_empty(
     Ok(())
 }
 
-/// Best-effort: merge a JSON object into `video_games.metadata` when the column exists.
-pub async fn merge_video_game_metadata(db: &Db, video_game_id: i64, patch: Value) -> Result<()> {
+/// Best-effort: merge a JSON object from `source` (a provider slug such as `igdb` or
+/// `ps-store`) into `video_games.metadata` when the column exists. Each key is only
+/// replaced when `source` ranks at least as high as the source that last wrote it
+/// (`METADATA_SOURCE_PRIORITY`); the winners are recorded under `_field_sources`.
+///
+/// `genres` (array of names) and `synopsis` (string) keys are mirrored onto the matching
+/// columns when `source` wins them, so providers should write those fields through here
+/// rather than updating the columns directly.
+pub async fn merge_video_game_metadata(
+    db: &Db,
+    video_game_id: i64,
+    source: &str,
+    patch: Value,
+) -> Result<()> {
     let cols = video_games_content_columns(db).await?;
     if !cols.has_metadata {
+        // No metadata column to record field sources in: plain column writes.
+        if let Some(genres) = patch_genres(&patch) {
+            update_video_game_genres(db, video_game_id, &genres).await?;
+        }
+        if let Some(synopsis) = patch_synopsis(&patch) {
+            update_video_game_synopsis_prefer_longer(db, video_game_id, synopsis).await?;
+        }
         return Ok(());
     }
+    if let Err(e) = merge_video_game_metadata_tx(db, video_game_id, source, &patch, cols).await {
+        warn!(error=%e, context = "metadata_merge", "best-effort video_games update failed");
+    }
+    Ok(())
+}
 
+async fn merge_video_game_metadata_tx(
+    db: &Db,
+    video_game_id: i64,
+    source: &str,
+    patch: &Value,
+    cols: VideoGamesContentColumns,
+) -> Result<()> {
+    let mut tx = db.pool.begin().await?;
     // NOTE: legacy schemas may define metadata as JSON (not JSONB).
     // Use explicit casts so both shapes can be updated without errors.
+    let current: Option<Option<Value>> =
+        sqlx::query_scalar("SELECT metadata::jsonb FROM video_games WHERE id=$1 FOR UPDATE")
+            .persistent(false)
+            .bind(video_game_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+    let Some(current) = current else {
+        return Ok(());
+    };
+    let merged = merge_prioritized(
+        &current.unwrap_or(Value::Null),
+        patch,
+        source,
+        MetadataPriority::from_env(),
+    );
+    let won = |field: &str| {
+        merged
+            .get(SOURCES_KEY)
+            .and_then(|s| s.get(field))
+            .and_then(Value::as_str)
+            == Some(source)
+    };
+    let genres = patch_genres(patch).filter(|_| cols.has_genres && won("genres"));
+    let synopsis = patch_synopsis(patch).filter(|_| cols.has_synopsis && won("synopsis"));
     let sql = if cols.metadata_is_jsonb {
-        "UPDATE video_games SET metadata = (COALESCE(metadata, '{}'::jsonb) || $1::jsonb) WHERE id=$2"
+        "UPDATE video_games SET metadata = $1::jsonb WHERE id=$2"
     } else {
-        "UPDATE video_games SET metadata = ((COALESCE(metadata::jsonb, '{}'::jsonb) || $1::jsonb)::json) WHERE id=$2"
+        "UPDATE video_games SET metadata = $1::jsonb::json WHERE id=$2"
     };
+    sqlx::query(sql)
+        .persistent(false)
+        .bind(merged)
+        .bind(video_game_id)
+        .execute(&mut *tx)
+        .await?;
+    if let Some(genres) = genres {
+        sqlx::query(
+            "UPDATE video_games SET genres=$1, updated_at = now()
+             WHERE id=$2 AND genres IS DISTINCT FROM $1",
+        )
+        .persistent(false)
+        .bind(genres)
+        .bind(video_game_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    if let Some(synopsis) = synopsis {
+        sqlx::query(
+            "UPDATE video_games SET synopsis=$1, updated_at = now()
+             WHERE id=$2 AND synopsis IS DISTINCT FROM $1",
+        )
+        .persistent(false)
+        .bind(synopsis)
+        .bind(video_game_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Non-empty `genres` name list carried by a metadata patch.
+fn patch_genres(patch: &Value) -> Option<Vec<String>> {
+    patch
+        .get("genres")?
+        .as_array()?
+        .iter()
+        .map(|g| g.as_str().map(str::to_string))
+        .collect::<Option<Vec<_>>>()
+        .filter(|g| !g.is_empty())
+}
+
+/// Non-blank `synopsis` carried by a metadata patch.
+fn patch_synopsis(patch: &Value) -> Option<&str> {
+    patch
+        .get("synopsis")?
+        .as_str()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Best-effort: merge a JSON object into `provider_items.metadata`, keeping existing keys.
+pub async fn merge_provider_item_metadata(
+    db: &Db,
+    provider_item_id: i64,
+    patch: Value,
+) -> Result<()> {
+    if provider_item_id == 0 || !provider_items_present(db).await.unwrap_or(false) {
+        return Ok(());
+    }
     best_effort_execute(
         db,
-        sqlx::query(sql)
-            .persistent(false)
-            .bind(patch)
-            .bind(video_game_id),
-        "metadata_merge",
+        sqlx::query(
+            "UPDATE provider_items SET metadata = COALESCE(metadata, '{}'::jsonb) || $1::jsonb, updated_at = now() WHERE id = $2",
+        )
+        .persistent(false)
+        .bind(patch)
+        .bind(provider_item_id),
+        "provider_item_metadata_merge",
     )
     .await;
     Ok(())
@@ -6755,16 +7298,66 @@ fn normalize_provider_media_role(media_group: &str, role_raw: Option<&str>) -> O
     }
 }
 
+/// Keep the items whose position is `true` in `keep` (parallel batch columns).
+fn retain_by_mask<T>(items: &mut Vec<T>, keep: &[bool]) {
+    let mut flags = keep.iter();
+    items.retain(|_| flags.next().copied().unwrap_or(false));
+}
+
+/// One media link to write: `(url, media_type, role, title)`.
+pub type MediaUrl = (String, Option<String>, Option<String>, Option<String>);
+
+/// One provider item's media for [`ensure_vg_source_media_links_batch`].
+#[derive(Debug, Clone, Default)]
+pub struct MediaLinkEntry {
+    pub video_game_source_id: i64,
+    pub video_game_id: Option<i64>,
+    /// As for [`ensure_vg_source_media_links_with_meta`].
+    pub urls: Vec<MediaUrl>,
+    pub meta: Option<Value>,
+}
+
+static MEDIA_LINK_BATCHES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// canonical_media INSERT statements issued by [`ensure_vg_source_media_links_batch`]
+/// since process start.
+pub fn media_link_batches() -> u64 {
+    MEDIA_LINK_BATCHES.load(std::sync::atomic::Ordering::Relaxed)
+}
+
 #[instrument(skip(db, urls, meta))]
 pub async fn ensure_vg_source_media_links_with_meta(
     db: &Db,
     video_game_source_id: i64,
     video_game_id: Option<i64>,
-    urls: &[(String, Option<String>, Option<String>, Option<String>)],
+    urls: &[MediaUrl],
     source: &str,
     meta: Option<Value>,
 ) -> Result<usize> {
-    if video_game_source_id == 0 {
+    let entry = MediaLinkEntry {
+        video_game_source_id,
+        video_game_id,
+        urls: urls.to_vec(),
+        meta,
+    };
+    ensure_vg_source_media_links_batch(db, std::slice::from_ref(&entry), source).await
+}
+
+/// Write the media links of many provider items (typically one grid page) in a single
+/// multi-row INSERT, chunked at `MEDIA_LINK_BATCH_ROWS` rows (default 5000), then
+/// recompute primary media once per game. A URL repeated across entries keeps its first
+/// occurrence, since one statement cannot upsert the same `url_hash` twice.
+#[instrument(skip(db, entries), fields(entries = entries.len()))]
+pub async fn ensure_vg_source_media_links_batch(
+    db: &Db,
+    entries: &[MediaLinkEntry],
+    source: &str,
+) -> Result<usize> {
+    let entries: Vec<&MediaLinkEntry> = entries
+        .iter()
+        .filter(|e| e.video_game_source_id != 0 && !e.urls.is_empty())
+        .collect();
+    if entries.is_empty() {
         return Ok(0);
     }
     if !provider_media_links_present(db).await.unwrap_or(false) {
@@ -6785,11 +7378,12 @@ pub async fn ensure_vg_source_media_links_with_meta(
             .unwrap_or(false)
     };
 
-    let metadata_base = meta.as_ref().filter(|v| !v.is_null()).cloned();
+    let host_policy = crate::database_ops::media_filter::MediaHostPolicy::from_env();
+    let mut rejected_hosts = 0usize;
 
     // Pre-process all URLs and build arrays for batch insert
-    // This replaces the individual INSERT loop with data preparation
     let mut seen: HashSet<String> = HashSet::new();
+    let mut batch_source_ids: Vec<i64> = Vec::new();
     let mut batch_urls: Vec<String> = Vec::new();
     let mut batch_video_game_ids: Vec<Option<i64>> = Vec::new();
     let mut batch_sources: Vec<Option<String>> = Vec::new();
@@ -6799,58 +7393,120 @@ pub async fn ensure_vg_source_media_links_with_meta(
     let mut batch_sort_orders: Vec<i32> = Vec::new();
     let mut batch_metadata: Vec<Value> = Vec::new();
 
-    for (idx, (url, media_type_raw, role_raw, title_raw)) in urls.iter().enumerate() {
-        let trimmed = url.trim();
-        if trimmed.is_empty() {
-            continue;
-        }
-        let normalized = trimmed.to_string();
-        if !seen.insert(normalized.clone()) {
-            continue;
-        }
-        let media_type_candidate = media_type_raw
-            .as_ref()
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty());
-        let role_candidate = role_raw
-            .as_ref()
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty());
+    for entry in &entries {
+        let metadata_base = entry.meta.as_ref().filter(|v| !v.is_null()).cloned();
+        for (idx, (url, media_type_raw, role_raw, title_raw)) in entry.urls.iter().enumerate() {
+            let trimmed = url.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let normalized = trimmed.to_string();
+            if !seen.insert(normalized.clone()) {
+                continue;
+            }
+            if !host_policy.permits(&normalized) {
+                rejected_hosts += 1;
+                continue;
+            }
+            let media_type_candidate = media_type_raw
+                .as_ref()
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty());
+            let role_candidate = role_raw
+                .as_ref()
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty());
 
-        let (prio, group) = media_group_priority(media_type_candidate, role_candidate, &normalized);
-        let sort_order: i32 = prio.saturating_mul(1000) + (idx as i32);
+            let (prio, group) =
+                media_group_priority(media_type_candidate, role_candidate, &normalized);
+            let sort_order: i32 = prio.saturating_mul(1000) + (idx as i32);
 
-        let media_type = media_type_candidate
-            .map(|s| normalize_media_type_for_db(s, supports_background).to_ascii_lowercase());
+            let media_type = media_type_candidate
+                .map(|s| normalize_media_type_for_db(s, supports_background).to_ascii_lowercase());
 
-        let role = normalize_provider_media_role(group, role_candidate);
+            let role = normalize_provider_media_role(group, role_candidate);
 
-        let row_meta: Value = if !has_sort_order && !has_position {
-            let mut row_meta: Value = metadata_base.clone().unwrap_or_else(|| json!({}));
-            if let Value::Object(map) = &mut row_meta {
-                map.insert("gc_sort_order".to_string(), json!(sort_order));
-                map.insert("gc_media_group".to_string(), json!(group));
-            }
-            row_meta
-        } else {
-            metadata_base.clone().unwrap_or_else(|| json!({}))
-        };
+            let row_meta: Value = if !has_sort_order && !has_position {
+                let mut row_meta: Value = metadata_base.clone().unwrap_or_else(|| json!({}));
+                if let Value::Object(map) = &mut row_meta {
+                    map.insert("gc_sort_order".to_string(), json!(sort_order));
+                    map.insert("gc_media_group".to_string(), json!(group));
+                }
+                row_meta
+            } else {
+                metadata_base.clone().unwrap_or_else(|| json!({}))
+            };
 
-        let title = title_raw
-            .as_ref()
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string());
+            let title = title_raw
+                .as_ref()
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string());
+
+            // Accumulate values for batch insert
+            batch_source_ids.push(entry.video_game_source_id);
+            batch_urls.push(normalized);
+            batch_video_game_ids.push(entry.video_game_id);
+            batch_sources.push(Some(source_norm.to_string()));
+            batch_media_types.push(media_type);
+            batch_roles.push(role);
+            batch_titles.push(title);
+            batch_sort_orders.push(sort_order);
+            batch_metadata.push(row_meta);
+        }
+    }
+
+    if rejected_hosts > 0 {
+        crate::database_ops::media_filter::record_host_rejections(rejected_hosts);
+        debug!(
+            entries = entries.len(),
+            source,
+            rejected = rejected_hosts,
+            "media links rejected by host policy"
+        );
+    }
 
-        // Accumulate values for batch insert
-        batch_urls.push(normalized);
-        batch_video_game_ids.push(video_game_id);
-        batch_sources.push(Some(source_norm.to_string()));
-        batch_media_types.push(media_type);
-        batch_roles.push(role);
-        batch_titles.push(title);
-        batch_sort_orders.push(sort_order);
-        batch_metadata.push(row_meta);
+    // Optional HEAD check of URLs not yet in canonical_media (MEDIA_VALIDATE=1).
+    if let Some(validator) = crate::database_ops::media_validate::MediaValidator::from_env() {
+        if !batch_urls.is_empty() {
+            let known: HashSet<String> = sqlx::query_scalar(
+                "SELECT cm.url FROM canonical_media cm
+                 WHERE cm.url_hash IN (SELECT canonical_media_url_hash(u) FROM UNNEST($1::text[]) AS u)",
+            )
+            .persistent(false)
+            .bind(&batch_urls)
+            .fetch_all(&db.pool)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+            let fresh: Vec<String> = batch_urls
+                .iter()
+                .filter(|u| !known.contains(*u))
+                .cloned()
+                .collect();
+            let valid = validator.valid_urls(&fresh).await;
+            let keep: Vec<bool> = batch_urls
+                .iter()
+                .map(|u| known.contains(u) || valid.contains(u))
+                .collect();
+            let skipped = keep.iter().filter(|k| !**k).count();
+            if skipped > 0 {
+                debug!(
+                    entries = entries.len(),
+                    source, skipped, "media links skipped by validation"
+                );
+                retain_by_mask(&mut batch_source_ids, &keep);
+                retain_by_mask(&mut batch_urls, &keep);
+                retain_by_mask(&mut batch_video_game_ids, &keep);
+                retain_by_mask(&mut batch_sources, &keep);
+                retain_by_mask(&mut batch_media_types, &keep);
+                retain_by_mask(&mut batch_roles, &keep);
+                retain_by_mask(&mut batch_titles, &keep);
+                retain_by_mask(&mut batch_sort_orders, &keep);
+                retain_by_mask(&mut batch_metadata, &keep);
+            }
+        }
     }
 
     if batch_urls.is_empty() {
@@ -6858,69 +7514,74 @@ pub async fn ensure_vg_source_media_links_with_meta(
     }
 
     let count = batch_urls.len();
-
-    // Execute single batch INSERT using UNNEST to expand arrays
-    // UNNEST transforms arrays into rows, allowing us to insert all media links in one query
-    // The ON CONFLICT clause maintains idempotency - re-running is safe
-    if has_sort_order {
-        sqlx::query(
-            "INSERT INTO canonical_media
-                (url, url_hash, metadata)
-             SELECT
-                url,
-                canonical_media_url_hash(url),
-                jsonb_build_object(
-                    'video_game_source_id', $1::bigint,
-                    'video_game_id', video_game_id,
-                    'source', source,
-                    'media_type', media_type,
-                    'role', role,
-                    'title', title,
-                    'sort_order', sort_order
-                ) || COALESCE(metadata, '{}'::jsonb)
-             FROM UNNEST(
-                $2::bigint[],
-                $3::text[],
-                $4::text[],
-                $5::text[],
-                $6::text[],
-                $7::text[],
-                $8::int[],
-                $9::jsonb[]
-             ) AS t(video_game_id, url, source, media_type, role, title, sort_order, metadata)
-             ON CONFLICT (url_hash) DO UPDATE
-             SET metadata = canonical_media.metadata || EXCLUDED.metadata,
-                 updated_at = now()",
-        )
-        .persistent(false)
-        .bind(video_game_source_id)
-        .bind(&batch_video_game_ids)
-        .bind(&batch_urls)
-        .bind(&batch_sources)
-        .bind(&batch_media_types)
-        .bind(&batch_roles)
-        .bind(&batch_titles)
-        .bind(&batch_sort_orders)
-        .bind(&batch_metadata)
-        .execute(&db.pool)
-        .await?;
+    let order_key = if has_sort_order {
+        Some("sort_order")
     } else if has_position {
-        sqlx::query(
-            "INSERT INTO canonical_media
+        Some("position")
+    } else {
+        None
+    };
+    let sql = media_link_insert_sql(order_key);
+    let chunk_rows = crate::util::env::env_parse("MEDIA_LINK_BATCH_ROWS", 5000usize).max(1);
+
+    // UNNEST expands the column arrays into rows so each chunk is one statement; the
+    // ON CONFLICT clause keeps re-runs idempotent.
+    let mut start = 0;
+    while start < count {
+        let end = (start + chunk_rows).min(count);
+        sqlx::query(&sql)
+            .persistent(false)
+            .bind(&batch_source_ids[start..end])
+            .bind(&batch_video_game_ids[start..end])
+            .bind(&batch_urls[start..end])
+            .bind(&batch_sources[start..end])
+            .bind(&batch_media_types[start..end])
+            .bind(&batch_roles[start..end])
+            .bind(&batch_titles[start..end])
+            .bind(&batch_sort_orders[start..end])
+            .bind(&batch_metadata[start..end])
+            .execute(&db.pool)
+            .await?;
+        MEDIA_LINK_BATCHES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        start = end;
+    }
+
+    let mut games: Vec<i64> = batch_video_game_ids.iter().flatten().copied().collect();
+    games.sort_unstable();
+    games.dedup();
+    let policy = crate::database_ops::media_primary::PrimaryMediaPolicy::from_env();
+    if let Err(e) =
+        crate::database_ops::media_primary::recompute_primary_media_batch(db, &games, &policy).await
+    {
+        warn!(games = games.len(), error = %e, "primary media recompute failed");
+    }
+
+    Ok(count)
+}
+
+/// Multi-row canonical_media upsert over parallel arrays `$1..$9`. `order_key` names the
+/// metadata key for the sort order (`sort_order` / `position`), or `None` when the
+/// deployment keeps it in `gc_sort_order` instead.
+fn media_link_insert_sql(order_key: Option<&str>) -> String {
+    let order = order_key
+        .map(|key| format!(",\n                    '{key}', sort_order"))
+        .unwrap_or_default();
+    format!(
+        "INSERT INTO canonical_media
                 (url, url_hash, metadata)
              SELECT
                 url,
                 canonical_media_url_hash(url),
                 jsonb_build_object(
-                    'video_game_source_id', $1::bigint,
+                    'video_game_source_id', video_game_source_id,
                     'video_game_id', video_game_id,
                     'source', source,
                     'media_type', media_type,
                     'role', role,
-                    'title', title,
-                    'position', sort_order
-                ) || COALESCE(metadata, '{}'::jsonb)
+                    'title', title{order}
+                ) || COALESCE(metadata, '{{}}'::jsonb)
              FROM UNNEST(
+                $1::bigint[],
                 $2::bigint[],
                 $3::text[],
                 $4::text[],
@@ -6929,65 +7590,76 @@ pub async fn ensure_vg_source_media_links_with_meta(
                 $7::text[],
                 $8::int[],
                 $9::jsonb[]
-             ) AS t(video_game_id, url, source, media_type, role, title, sort_order, metadata)
-             ON CONFLICT (url_hash) DO UPDATE
-             SET metadata = canonical_media.metadata || EXCLUDED.metadata,
-                 updated_at = now()",
-        )
-        .persistent(false)
-        .bind(video_game_source_id)
-        .bind(&batch_video_game_ids)
-        .bind(&batch_urls)
-        .bind(&batch_sources)
-        .bind(&batch_media_types)
-        .bind(&batch_roles)
-        .bind(&batch_titles)
-        .bind(&batch_sort_orders)
-        .bind(&batch_metadata)
-        .execute(&db.pool)
-        .await?;
-    } else {
-        sqlx::query(
-            "INSERT INTO canonical_media
-                (url, url_hash, metadata)
-             SELECT
-                url,
-                canonical_media_url_hash(url),
-                jsonb_build_object(
-                    'video_game_source_id', $1::bigint,
-                    'video_game_id', video_game_id,
-                    'source', source,
-                    'media_type', media_type,
-                    'role', role,
-                    'title', title
-                ) || COALESCE(metadata, '{}'::jsonb)
-             FROM UNNEST(
-                $2::bigint[],
-                $3::text[],
-                $4::text[],
-                $5::text[],
-                $6::text[],
-                $7::text[],
-                $8::jsonb[]
-             ) AS t(video_game_id, url, source, media_type, role, title, metadata)
+             ) AS t(video_game_source_id, video_game_id, url, source, media_type, role, title, sort_order, metadata)
              ON CONFLICT (url_hash) DO UPDATE
              SET metadata = canonical_media.metadata || EXCLUDED.metadata,
-                 updated_at = now()",
-        )
-        .persistent(false)
-        .bind(video_game_source_id)
-        .bind(&batch_video_game_ids)
-        .bind(&batch_urls)
-        .bind(&batch_sources)
-        .bind(&batch_media_types)
-        .bind(&batch_roles)
-        .bind(&batch_titles)
-        .bind(&batch_metadata)
-        .execute(&db.pool)
-        .await?;
+                 updated_at = now()"
+    )
+}
+
+#[cfg(test)]
+mod media_link_batch_tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    #[test]
+    fn insert_sql_includes_order_key_only_when_the_schema_has_one() {
+        let sql = media_link_insert_sql(Some("position"));
+        assert!(sql.contains("'position', sort_order"));
+        assert!(sql.contains("'{}'::jsonb"));
+        assert!(!media_link_insert_sql(None).contains("', sort_order"));
     }
 
-    Ok(count)
+    async fn stored(db: &Db, prefix: &str) -> i64 {
+        sqlx::query_scalar("SELECT count(*) FROM canonical_media WHERE url LIKE $1")
+            .bind(format!("{prefix}/%"))
+            .fetch_one(&db.pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn page_media_is_written_in_one_batch_and_rerun_is_idempotent() {
+        let db = RollbackDb::connect().await;
+        let prefix = format!("https://media-batch-test.invalid/{}", Uuid::new_v4());
+        let image = |name: &str, role: &str| {
+            (
+                format!("{prefix}/{name}.jpg"),
+                Some("image".to_string()),
+                Some(role.to_string()),
+                None,
+            )
+        };
+        let page = vec![
+            MediaLinkEntry {
+                video_game_source_id: 9_000_000_001,
+                urls: vec![image("a-cover", "cover"), image("a-shot", "screenshot")],
+                ..Default::default()
+            },
+            MediaLinkEntry {
+                video_game_source_id: 9_000_000_002,
+                // The shared screenshot is kept once, for the first entry.
+                urls: vec![image("b-cover", "cover"), image("a-shot", "screenshot")],
+                ..Default::default()
+            },
+        ];
+
+        let before = media_link_batches();
+        let written = ensure_vg_source_media_links_batch(&db, &page, "psstore")
+            .await
+            .unwrap();
+        assert_eq!(written, 3);
+        assert_eq!(media_link_batches(), before + 1);
+        assert_eq!(stored(&db, &prefix).await, 3);
+
+        ensure_vg_source_media_links_batch(&db, &page, "psstore")
+            .await
+            .unwrap();
+        assert_eq!(stored(&db, &prefix).await, 3);
+
+        db.rollback().await;
+    }
 }
 
 // --------- Provider media links (simple version) ---------
@@ -7576,3 +8248,430 @@ pub async fn backfill_game_videos_canonical_media(db: &Db, batch_size: i64) -> R
     );
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn concurrent_region_unions_keep_every_code() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 8).await.unwrap();
+        let slug = format!("region-union-{}", std::process::id());
+        let product_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.products (slug, name) VALUES ($1, 'Region Union') RETURNING id",
+        )
+        .bind(&slug)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let title_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.video_game_titles (product_id, title)
+             VALUES ($1, 'Region Union') RETURNING id",
+        )
+        .bind(product_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let vg_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.video_games (title_id, platform_id, region_codes)
+             SELECT $1, id, ARRAY['US']::text[] FROM public.platforms ORDER BY id LIMIT 1
+             RETURNING id",
+        )
+        .bind(title_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+
+        let codes = ["gb", "de", "fr", "jp", "au", "br", "us", "de"];
+        let tasks: Vec<_> = codes
+            .into_iter()
+            .map(|code| {
+                let db = db.clone();
+                tokio::spawn(
+                    async move { union_video_game_region_codes(&db, vg_id, &[code]).await },
+                )
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        let stored: Vec<String> =
+            sqlx::query_scalar("SELECT region_codes FROM public.video_games WHERE id = $1")
+                .bind(vg_id)
+                .fetch_one(&db.pool)
+                .await
+                .unwrap();
+        sqlx::query("DELETE FROM public.products WHERE id = $1")
+            .bind(product_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        assert_eq!(stored, ["AU", "BR", "DE", "FR", "GB", "JP", "US"]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn external_id_match_merges_store_metadata() {
+        use crate::database_ops::igdb::external_games::{
+            external_links, link_external_games, IgdbExternalGame,
+        };
+        let db = RollbackDb::connect().await;
+        let uid = format!("{}", 8_800_000 + std::process::id());
+        let product_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.products (slug, name) VALUES ($1, 'External Merge') RETURNING id",
+        )
+        .bind(format!("external-merge-{uid}"))
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let vg_id: i64 = sqlx::query_scalar(
+            "WITH t AS (
+                 INSERT INTO public.video_game_titles (product_id, title)
+                 VALUES ($1, 'External Merge') RETURNING id
+             )
+             INSERT INTO public.video_games (title_id, platform_id)
+             SELECT t.id, p.id FROM t, (SELECT id FROM public.platforms ORDER BY id LIMIT 1) p
+             RETURNING id",
+        )
+        .bind(product_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let igdb_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.providers (slug, name) VALUES ('igdb', 'IGDB')
+             ON CONFLICT (slug) DO UPDATE SET name = providers.name RETURNING id",
+        )
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let igdb_item: i64 = sqlx::query_scalar(
+            "INSERT INTO public.provider_items (provider_id, external_id)
+             VALUES ($1, $2) RETURNING id",
+        )
+        .bind(igdb_id)
+        .bind(format!("test-merge-{uid}"))
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let links = external_links(&[IgdbExternalGame {
+            category: Some(1),
+            uid: Some(uid.clone()),
+        }]);
+        link_external_games(&db, igdb_item, Some(vg_id), &links)
+            .await
+            .unwrap();
+
+        let mut cache = ProviderEntityCache::new(db.clone());
+        let first = cache
+            .ensure_video_game_for_store_item(
+                &uid,
+                product_id,
+                "External Merge",
+                None,
+                Some(serde_json::json!({ "publisher": "Acme" })),
+                "steam-store",
+            )
+            .await;
+        // The second lookup is served from the cache even once the link is gone.
+        sqlx::query("DELETE FROM public.provider_items WHERE id = $1")
+            .bind(igdb_item)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        let second = cache
+            .ensure_video_game_for_store_item(
+                &uid,
+                product_id,
+                "External Merge",
+                None,
+                None,
+                "steam-store",
+            )
+            .await;
+        let publisher: Option<String> = sqlx::query_scalar(
+            "SELECT metadata->>'publisher' FROM public.video_games WHERE id = $1",
+        )
+        .bind(vg_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        db.rollback().await;
+
+        assert_eq!(first.unwrap(), vg_id);
+        assert_eq!(second.unwrap(), vg_id);
+        assert_eq!(publisher.as_deref(), Some("Acme"));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn concurrent_ensures_share_one_sellable_and_offer() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 8).await.unwrap();
+        assert!(
+            !php_compat_schema(&db).await.unwrap(),
+            "needs the native sellables/offers schema"
+        );
+        let product_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.products (slug, name) VALUES ($1, 'Concurrent Ensure') RETURNING id",
+        )
+        .bind(format!("concurrent-ensure-{}", std::process::id()))
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO public.video_game_titles (product_id, title) VALUES ($1, 'Concurrent Ensure')",
+        )
+        .bind(product_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        let retailer_id = ensure_retailer(&db, "Concurrent Test Store", None)
+            .await
+            .unwrap();
+
+        // Locales run in parallel and reach the same new product at once.
+        let sellables =
+            futures::future::join_all((0..8).map(|_| ensure_sellable(&db, "software", product_id)))
+                .await;
+        let sellable_id = *sellables[0].as_ref().unwrap();
+        let offers = futures::future::join_all(
+            (0..8).map(|_| ensure_offer(&db, sellable_id, retailer_id, Some("deluxe"))),
+        )
+        .await;
+        for sql in [
+            "DELETE FROM public.offers WHERE sellable_id = $1",
+            "DELETE FROM public.sellables WHERE id = $1",
+            "DELETE FROM public.video_game_titles WHERE product_id = $2",
+            "DELETE FROM public.products WHERE id = $2",
+        ] {
+            sqlx::query(sql)
+                .bind(sellable_id)
+                .bind(product_id)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+
+        let sellables: Vec<i64> = sellables.into_iter().map(Result::unwrap).collect();
+        let offers: Vec<i64> = offers.into_iter().map(Result::unwrap).collect();
+        assert!(
+            sellables.iter().all(|id| *id == sellable_id),
+            "{sellables:?}"
+        );
+        assert!(offers.iter().all(|id| *id == offers[0]), "{offers:?}");
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn genre_and_synopsis_columns_follow_source_priority() {
+        let db = RollbackDb::connect().await;
+        let product_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.products (slug, name) VALUES ($1, 'Priority Merge') RETURNING id",
+        )
+        .bind(format!("priority-merge-{}", std::process::id()))
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let vg_id: i64 = sqlx::query_scalar(
+            "WITH t AS (
+                 INSERT INTO public.video_game_titles (product_id, title)
+                 VALUES ($1, 'Priority Merge') RETURNING id
+             )
+             INSERT INTO public.video_games (title_id, platform_id)
+             SELECT t.id, p.id FROM t, (SELECT id FROM public.platforms ORDER BY id LIMIT 1) p
+             RETURNING id",
+        )
+        .bind(product_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let columns = || {
+            sqlx::query_as::<_, (Option<Vec<String>>, Option<String>)>(
+                "SELECT genres, synopsis FROM public.video_games WHERE id = $1",
+            )
+            .bind(vg_id)
+            .fetch_one(&db.pool)
+        };
+
+        // A lower-ranked source fills empty columns.
+        merge_video_game_metadata(
+            &db,
+            vg_id,
+            "steam",
+            json!({ "genres": ["Action", "Indie"], "synopsis": "A long Steam description." }),
+        )
+        .await
+        .unwrap();
+        let after_steam = columns().await.unwrap();
+        // IGDB outranks Steam and replaces both.
+        merge_video_game_metadata(
+            &db,
+            vg_id,
+            "igdb",
+            json!({ "genres": ["Shooter"], "synopsis": "IGDB summary." }),
+        )
+        .await
+        .unwrap();
+        let after_igdb = columns().await.unwrap();
+        // A later Steam or PS Store write no longer touches them.
+        merge_video_game_metadata(
+            &db,
+            vg_id,
+            "steam",
+            json!({ "genres": ["Action"], "synopsis": "An even longer Steam description." }),
+        )
+        .await
+        .unwrap();
+        merge_video_game_metadata(&db, vg_id, "ps-store", json!({ "genres": ["Action"] }))
+            .await
+            .unwrap();
+        let after_rerun = columns().await.unwrap();
+        db.rollback().await;
+
+        assert_eq!(
+            after_steam,
+            (
+                Some(vec!["Action".to_string(), "Indie".to_string()]),
+                Some("A long Steam description.".to_string())
+            )
+        );
+        let igdb = (
+            Some(vec!["Shooter".to_string()]),
+            Some("IGDB summary.".to_string()),
+        );
+        assert_eq!(after_igdb, igdb);
+        assert_eq!(after_rerun, igdb);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn ensure_currency_keeps_the_stored_minor_unit() {
+        let db = RollbackDb::connect().await;
+        let minor_unit = |id: i64| {
+            sqlx::query_scalar::<_, i16>("SELECT minor_unit FROM public.currencies WHERE id = $1")
+                .bind(id)
+                .fetch_one(&db.pool)
+        };
+
+        let id = ensure_currency(&db, "XTS", "Testing Code", 2)
+            .await
+            .unwrap();
+        assert_eq!(minor_unit(id).await.unwrap(), 2);
+        // An override arriving later does not rescale the existing row.
+        assert_eq!(
+            ensure_currency(&db, "XTS", "Testing Code", 0)
+                .await
+                .unwrap(),
+            id
+        );
+        assert_eq!(minor_unit(id).await.unwrap(), 2);
+
+        db.rollback().await;
+    }
+
+    /// Counts the statements sqlx runs (its `sqlx::query` events), schema probes included.
+    struct QueryCounter(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for QueryCounter {
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if event.metadata().target() == "sqlx::query" {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            }
+        }
+    }
+
+    async fn counting_queries<F: std::future::Future>(fut: F) -> (F::Output, usize) {
+        use tracing::instrument::WithSubscriber;
+        use tracing_subscriber::layer::SubscriberExt;
+        let count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let subscriber = tracing_subscriber::registry().with(QueryCounter(count.clone()));
+        let out = fut.with_subscriber(subscriber).await;
+        (out, count.load(std::sync::atomic::Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn batched_ensure_creates_a_page_in_a_few_statements() {
+        let db = RollbackDb::connect().await;
+        let platform_id: i64 =
+            sqlx::query_scalar("SELECT id FROM public.platforms ORDER BY id LIMIT 1")
+                .fetch_one(&db.pool)
+                .await
+                .unwrap();
+        let page = |tag: &str, n: usize| -> Vec<(String, String)> {
+            (0..n)
+                .map(|i| {
+                    (
+                        format!("batch-ensure-{}-{tag}-{i}", std::process::id()),
+                        format!("Batch Ensure {tag} {i}"),
+                    )
+                })
+                .collect()
+        };
+        // A repeated slug is resolved once.
+        let mut small = page("small", 5);
+        small.push((
+            small[0].0.clone(),
+            "Batch Ensure small 0 (again)".to_string(),
+        ));
+        let large = page("large", 40);
+
+        // Warm the pool and the schema probes, then count every statement.
+        ensure_products_batch(&db, "software", platform_id, &page("warm", 2))
+            .await
+            .unwrap();
+        let (created, created_queries) =
+            counting_queries(ensure_products_batch(&db, "software", platform_id, &small)).await;
+        let created = created.unwrap();
+        let (large_created, large_queries) =
+            counting_queries(ensure_products_batch(&db, "software", platform_id, &large)).await;
+        let large_created = large_created.unwrap();
+        let (rerun, rerun_queries) =
+            counting_queries(ensure_products_batch(&db, "software", platform_id, &small)).await;
+        let rerun = rerun.unwrap();
+        let linked: Vec<(i64, i64, i64)> = sqlx::query_as(
+            "SELECT p.id, t.id, vg.id
+             FROM public.products p
+             JOIN public.video_game_titles t ON t.product_id = p.id
+             JOIN public.video_games vg ON vg.title_id = t.id AND vg.platform_id = $2
+             WHERE p.slug = ANY($1)
+             ORDER BY p.id",
+        )
+        .bind(
+            small
+                .iter()
+                .map(|(slug, _)| slug.clone())
+                .collect::<Vec<_>>(),
+        )
+        .bind(platform_id)
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        db.rollback().await;
+
+        assert!(!created.fallback, "schema needs the per-product ensures");
+        assert_eq!(created.products.len(), 5);
+        assert_eq!(large_created.products.len(), 40);
+        // The same statements for 5 products as for 40.
+        assert_eq!(created_queries, large_queries);
+        assert!(created_queries <= 10, "{created_queries} statements");
+        // Nothing new the second time: the title and video_game inserts are skipped.
+        assert_eq!(rerun_queries, created_queries - 2);
+        assert_eq!(rerun.products, created.products);
+        let mut expected: Vec<(i64, i64, i64)> = created
+            .products
+            .values()
+            .map(|p| (p.product_id, p.title_id, p.video_game_id))
+            .collect();
+        expected.sort_unstable();
+        assert_eq!(linked, expected);
+    }
+}
@@ -67,6 +67,49 @@ fn env_usize(key: &str, default: usize, max: usize) -> usize {
         .unwrap_or(default)
 }
 
+/// Settings of one ITAD sync; [`ItadSyncOptions::from_env`] reads the `ITAD_*` variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItadSyncOptions {
+    /// `ITAD_API_KEY`.
+    pub api_key: Option<String>,
+    /// `ITAD_BASE_URL`, default the public API.
+    pub base_url: String,
+    /// `ITAD_TIMEOUT_SECS`, default 20.
+    pub timeout_secs: u64,
+    /// Deals country (`ITAD_COUNTRY`, default `US`).
+    pub country: String,
+    /// Deals fetched per run (`ITAD_DEALS_LIMIT`, default 50, at most 500).
+    pub deals_limit: u32,
+    /// Games whose overview and media are fetched (`ITAD_MAX_GAME_OVERVIEWS`, default 40,
+    /// at most 500).
+    pub max_game_overviews: usize,
+    /// Currency for deals without one (`ITAD_DEFAULT_CURRENCY`, default `USD`).
+    pub default_currency: String,
+    /// Name for a newly created country (`ITAD_COUNTRY_NAME`); `None` uses the code.
+    pub country_name: Option<String>,
+}
+
+impl ItadSyncOptions {
+    pub fn from_env() -> Self {
+        Self {
+            api_key: std::env::var("ITAD_API_KEY").ok().filter(|s| !s.is_empty()),
+            base_url: std::env::var("ITAD_BASE_URL")
+                .unwrap_or_else(|_| "https://api.isthereanydeal.com".into()),
+            timeout_secs: std::env::var("ITAD_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(20),
+            country: std::env::var("ITAD_COUNTRY").unwrap_or_else(|_| "US".into()),
+            // Keep this intentionally small by default (portfolio + quota friendly).
+            deals_limit: env_usize("ITAD_DEALS_LIMIT", 50, 500) as u32,
+            max_game_overviews: env_usize("ITAD_MAX_GAME_OVERVIEWS", 40, 500),
+            default_currency: std::env::var("ITAD_DEFAULT_CURRENCY")
+                .unwrap_or_else(|_| "USD".into()),
+            country_name: std::env::var("ITAD_COUNTRY_NAME").ok(),
+        }
+    }
+}
+
 fn price_to_minor_units(price: f64, minor_unit: i16) -> i64 {
     let scale = 10_f64.powi(minor_unit as i32);
     (price * scale).round() as i64
@@ -79,6 +122,15 @@ fn price_to_minor_units(price: f64, minor_unit: i16) -> i64 {
 /// - Be resilient to legacy/partial schemas by skipping optional linkage writes.
 /// - Prefer existing shared ingestion helpers for schema drift tolerance.
 pub async fn sync(db: &Db, api_key: Option<String>) -> Result<PostIngestSummary> {
+    let opts = ItadSyncOptions {
+        api_key,
+        ..ItadSyncOptions::from_env()
+    };
+    sync_with_options(db, opts).await
+}
+
+/// [`sync`] with every setting passed in rather than read from env.
+pub async fn sync_with_options(db: &Db, opts: ItadSyncOptions) -> Result<PostIngestSummary> {
     let mut summary = PostIngestSummary::default();
 
     let enabled = env_bool("ITAD_ENABLED", true);
@@ -146,17 +198,11 @@ pub async fn sync(db: &Db, api_key: Option<String>) -> Result<PostIngestSummary>
     }
 
     // Provider client config
-    let base_url =
-        std::env::var("ITAD_BASE_URL").unwrap_or_else(|_| "https://api.isthereanydeal.com".into());
-    let timeout_secs = std::env::var("ITAD_TIMEOUT_SECS")
-        .ok()
-        .and_then(|v| v.parse::<u64>().ok())
-        .unwrap_or(20);
-    let country_code = std::env::var("ITAD_COUNTRY").unwrap_or_else(|_| "US".into());
-
-    // Keep this intentionally small by default (portfolio + quota friendly).
-    let deals_limit: u32 = env_usize("ITAD_DEALS_LIMIT", 50, 500) as u32;
-    let max_game_overviews = env_usize("ITAD_MAX_GAME_OVERVIEWS", 40, 500);
+    let base_url = opts.base_url;
+    let timeout_secs = opts.timeout_secs;
+    let country_code = opts.country;
+    let deals_limit = opts.deals_limit.min(500);
+    let max_game_overviews = opts.max_game_overviews.min(500);
     let default_tax_inclusive = env_bool("ITAD_DEFAULT_TAX_INCLUSIVE", true);
     let minor_unit_default: i16 = std::env::var("ITAD_MINOR_UNIT_DEFAULT")
         .ok()
@@ -165,7 +211,7 @@ pub async fn sync(db: &Db, api_key: Option<String>) -> Result<PostIngestSummary>
 
     let provider = ItadProvider::new(Some(&base_url), Some(timeout_secs))
         .context("itad: build provider")?
-        .with_api_key(api_key);
+        .with_api_key(opts.api_key);
 
     // Fetch recent deals.
     info!(
@@ -213,9 +259,8 @@ pub async fn sync(db: &Db, api_key: Option<String>) -> Result<PostIngestSummary>
     // Ensure country/jurisdiction once.
     // Note: for a production-grade solution we'd have a proper ISO country registry.
     // For now we keep it stable and harmless (name defaults to the code).
-    let default_currency_code =
-        std::env::var("ITAD_DEFAULT_CURRENCY").unwrap_or_else(|_| "USD".into());
-    let country_name = std::env::var("ITAD_COUNTRY_NAME").unwrap_or_else(|_| country_code.clone());
+    let default_currency_code = opts.default_currency;
+    let country_name = opts.country_name.unwrap_or_else(|| country_code.clone());
     let fallback_currency_id = ensure_currency(
         db,
         &default_currency_code,
@@ -0,0 +1,194 @@
+//! Postgres advisory-lock leader election.
+//!
+//! Several schedulers (e.g. `ps_long_test` or the main service) may run at once; only the
+//! instance holding the session-level advisory lock should enqueue ticks. The lock lives on a
+//! dedicated pooled connection, so it is released automatically when the leader process dies
+//! and a standby can take over on its next `ensure_leader()` call.
+//!
+//! Requires a session-mode connection (PgBouncer transaction pooling does not preserve
+//! session advisory locks). Enabled via `ENABLE_LEADER_ELECTION=1`.
+use anyhow::Result;
+use sqlx::pool::PoolConnection;
+use sqlx::Postgres;
+use tracing::{info, warn};
+
+use crate::database_ops::db::Db;
+
+pub struct LeaderElection {
+    db: Db,
+    name: String,
+    key: i64,
+    conn: Option<PoolConnection<Postgres>>,
+}
+
+impl LeaderElection {
+    pub fn new(db: &Db, name: &str) -> Self {
+        Self {
+            db: db.clone(),
+            name: name.to_string(),
+            key: lock_key(name),
+            conn: None,
+        }
+    }
+
+    /// `ENABLE_LEADER_ELECTION` gate (default off).
+    pub fn enabled() -> bool {
+        crate::util::env::env_flag("ENABLE_LEADER_ELECTION", false)
+    }
+
+    pub fn is_leader(&self) -> bool {
+        self.conn.is_some()
+    }
+
+    /// Return whether this instance is the leader, trying to take the lock if it is not.
+    /// An existing leadership is re-verified so a dropped lock connection demotes us.
+    pub async fn ensure_leader(&mut self) -> Result<bool> {
+        if let Some(conn) = self.conn.as_mut() {
+            let alive = sqlx::query_scalar::<_, i32>("SELECT 1")
+                .fetch_one(&mut **conn)
+                .await
+                .is_ok();
+            if alive {
+                return Ok(true);
+            }
+            warn!(election = %self.name, "leader lock connection lost; stepping down");
+            if let Some(conn) = self.conn.take() {
+                conn.detach();
+            }
+        }
+
+        let mut conn = self.db.pool.acquire().await?;
+        let acquired: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1)")
+            .bind(self.key)
+            .fetch_one(&mut *conn)
+            .await?;
+        if acquired {
+            info!(election = %self.name, key = self.key, "acquired leadership");
+            self.conn = Some(conn);
+        }
+        Ok(acquired)
+    }
+
+    /// Voluntarily release leadership so a standby can take over.
+    pub async fn release(&mut self) -> Result<()> {
+        if let Some(mut conn) = self.conn.take() {
+            let _: bool = sqlx::query_scalar("SELECT pg_advisory_unlock($1)")
+                .bind(self.key)
+                .fetch_one(&mut *conn)
+                .await?;
+            info!(election = %self.name, "released leadership");
+        }
+        Ok(())
+    }
+
+    /// Block until leadership is acquired, polling every `poll`. Errors from a single check
+    /// (pool timeout, dropped connection) are logged and retried rather than returned, so a
+    /// standby keeps waiting through transient DB trouble.
+    pub async fn wait_for_leadership(&mut self, poll: std::time::Duration) {
+        let name = self.name.clone();
+        wait_until_leader(self, &name, poll).await;
+    }
+}
+
+/// A single leadership check; split out of [`LeaderElection`] so the standby loop can be
+/// exercised without Postgres.
+#[async_trait::async_trait]
+trait LeaderCheck: Send {
+    async fn check(&mut self) -> Result<bool>;
+}
+
+#[async_trait::async_trait]
+impl LeaderCheck for LeaderElection {
+    async fn check(&mut self) -> Result<bool> {
+        self.ensure_leader().await
+    }
+}
+
+async fn wait_until_leader<L: LeaderCheck>(lock: &mut L, name: &str, poll: std::time::Duration) {
+    let mut logged = false;
+    loop {
+        match lock.check().await {
+            Ok(true) => return,
+            Ok(false) => {
+                if !logged {
+                    info!(election = %name, "standing by; another instance holds leadership");
+                    logged = true;
+                }
+            }
+            Err(e) => {
+                warn!(election = %name, error = %e, "leader election check failed; retrying");
+            }
+        }
+        tokio::time::sleep(poll).await;
+    }
+}
+
+/// Stable 64-bit FNV-1a hash of the election name, used as the advisory lock key.
+fn lock_key(name: &str) -> i64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in name.as_bytes() {
+        hash ^= u64::from(*b);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_key_is_stable_and_distinct() {
+        assert_eq!(lock_key("ps_long_scheduler"), lock_key("ps_long_scheduler"));
+        assert_ne!(lock_key("ps_long_scheduler"), lock_key("main_scheduler"));
+    }
+
+    struct Scripted(Vec<Result<bool>>);
+
+    #[async_trait::async_trait]
+    impl LeaderCheck for Scripted {
+        async fn check(&mut self) -> Result<bool> {
+            self.0.remove(0)
+        }
+    }
+
+    #[tokio::test]
+    async fn wait_survives_a_failed_check() {
+        let mut lock = Scripted(vec![
+            Err(anyhow::anyhow!(
+                "pool timed out while waiting for an open connection"
+            )),
+            Ok(false),
+            Ok(true),
+        ]);
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            wait_until_leader(
+                &mut lock,
+                "leader_election_test",
+                std::time::Duration::from_millis(1),
+            ),
+        )
+        .await
+        .expect("wait should resolve once the lock is acquired");
+        assert!(lock.0.is_empty());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn standby_takes_over_after_release() {
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 4).await.unwrap();
+        let mut a = LeaderElection::new(&db, "leader_election_test");
+        let mut b = LeaderElection::new(&db, "leader_election_test");
+
+        assert!(a.ensure_leader().await.unwrap());
+        assert!(!b.ensure_leader().await.unwrap());
+        assert!(a.ensure_leader().await.unwrap());
+
+        a.release().await.unwrap();
+        assert!(b.ensure_leader().await.unwrap());
+        assert!(!a.ensure_leader().await.unwrap());
+        b.release().await.unwrap();
+    }
+}
@@ -0,0 +1,417 @@
+// Periodic data maintenance run from the cleanup loop.
+//
+// Orphaned offer_jurisdictions: rows left without any price history (e.g. a region
+// dropped mid-run) that only bloat the price joins.
+//
+// Missing media: provider_items that never got a provider_media_links row are stamped
+// `metadata.media_checked_at`, so they are reported once instead of on every scan.
+
+use anyhow::Result;
+use sqlx::Row;
+use tracing::{debug, info, instrument};
+
+use crate::database_ops::db::Db;
+use crate::database_ops::schema_caps::SchemaCaps;
+
+/// Tables that always hold price history for an offer_jurisdiction, whether or not the
+/// deployment declares the foreign key.
+const PRICE_TABLES: &[(&str, &str)] = &[
+    ("public.prices", "offer_jurisdiction_id"),
+    ("public.current_price", "offer_jurisdiction_id"),
+];
+
+#[derive(Debug, Clone)]
+pub struct OrphanCleanupOptions {
+    /// Only consider offer_jurisdictions whose offer was created at least this long ago,
+    /// so rows inserted just before their first price are left alone.
+    pub min_age_hours: i64,
+    pub limit: i64,
+    /// Delete the orphans; otherwise they are only reported.
+    pub delete: bool,
+}
+
+impl Default for OrphanCleanupOptions {
+    fn default() -> Self {
+        Self {
+            min_age_hours: 72,
+            limit: 1000,
+            delete: false,
+        }
+    }
+}
+
+impl OrphanCleanupOptions {
+    /// From `ORPHAN_OJ_MIN_AGE_HOURS`, `ORPHAN_OJ_LIMIT` and `ORPHAN_OJ_DELETE`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            min_age_hours: crate::util::env::env_parse(
+                "ORPHAN_OJ_MIN_AGE_HOURS",
+                defaults.min_age_hours,
+            ),
+            limit: crate::util::env::env_parse("ORPHAN_OJ_LIMIT", defaults.limit),
+            delete: crate::util::env::env_flag("ORPHAN_OJ_DELETE", defaults.delete),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OrphanCleanupReport {
+    /// Orphaned offer_jurisdiction ids found this run (flagged or deleted).
+    pub orphan_ids: Vec<i64>,
+    pub deleted: u64,
+}
+
+/// Find offer_jurisdictions with no price history whose offer is older than
+/// `min_age_hours`, and delete them when `opts.delete` is set.
+///
+/// Rows still referenced by any foreign key (alerts, price partitions, ...) are never
+/// touched, so `ON DELETE CASCADE` constraints cannot take user data with them. Skipped
+/// (empty report) on schemas without offer_jurisdictions/offers or any price table.
+#[instrument(skip(db))]
+pub async fn cleanup_orphaned_offer_jurisdictions(
+    db: &Db,
+    opts: &OrphanCleanupOptions,
+) -> Result<OrphanCleanupReport> {
+    let caps = SchemaCaps::global();
+    if !caps.table_visible(db, "public.offer_jurisdictions").await?
+        || !caps.table_visible(db, "public.offers").await?
+    {
+        debug!("maintenance: no offer_jurisdictions/offers tables; orphan cleanup skipped");
+        return Ok(OrphanCleanupReport::default());
+    }
+    let Some(referencing) = referencing_columns(db).await? else {
+        debug!("maintenance: no price tables; orphan cleanup skipped");
+        return Ok(OrphanCleanupReport::default());
+    };
+    let sql = orphan_select_sql(&referencing);
+    let orphan_ids: Vec<i64> = sqlx::query(&sql)
+        .persistent(false)
+        .bind(opts.min_age_hours.max(0))
+        .bind(opts.limit.max(1))
+        .fetch_all(&db.pool)
+        .await?
+        .into_iter()
+        .map(|r| r.try_get::<i64, _>("id"))
+        .collect::<Result<_, _>>()?;
+
+    let mut deleted = 0;
+    if opts.delete && !orphan_ids.is_empty() {
+        // Re-check the predicate at delete time in case a price landed meanwhile.
+        let sql = format!(
+            "DELETE FROM public.offer_jurisdictions oj WHERE oj.id = ANY($1) AND {}",
+            unreferenced_predicate(&referencing)
+        );
+        deleted = sqlx::query(&sql)
+            .persistent(false)
+            .bind(&orphan_ids)
+            .execute(&db.pool)
+            .await?
+            .rows_affected();
+    }
+
+    info!(
+        orphans = orphan_ids.len(),
+        deleted,
+        delete = opts.delete,
+        "maintenance: orphaned offer_jurisdictions"
+    );
+    Ok(OrphanCleanupReport {
+        orphan_ids,
+        deleted,
+    })
+}
+
+/// provider_items metadata key set once an item was found without media links.
+pub const MEDIA_CHECKED_KEY: &str = "media_checked_at";
+
+/// `$1` = batch size. Claims unchecked provider_items without media links the same way the
+/// backfill worker claims rows (`FOR UPDATE SKIP LOCKED`), so concurrent runs never
+/// process an item twice, and stamps them as checked. `updated_at` is left alone: the
+/// staleness ranking reads it as the last ingest, which this is not.
+const MISSING_MEDIA_SQL: &str = "
+    WITH cte AS (
+      SELECT pi.id
+      FROM provider_items pi
+      WHERE NOT EXISTS (SELECT 1 FROM provider_media_links l WHERE l.provider_item_id = pi.id)
+        AND NOT (COALESCE(pi.metadata, '{}'::jsonb) ? 'media_checked_at')
+      ORDER BY pi.id
+      FOR UPDATE OF pi SKIP LOCKED
+      LIMIT $1
+    )
+    UPDATE provider_items pi
+    SET metadata = COALESCE(pi.metadata, '{}'::jsonb)
+          || jsonb_build_object('media_checked_at', now())
+    FROM cte
+    WHERE pi.id = cte.id";
+
+/// Mark up to `limit` provider_items that have no media links as media-checked. Returns
+/// the number of items processed.
+#[instrument(skip(db))]
+pub async fn cleanup_missing_media(db: &Db, limit: i64) -> Result<u64> {
+    let processed = sqlx::query(MISSING_MEDIA_SQL)
+        .persistent(false)
+        .bind(limit.max(1))
+        .execute(&db.pool)
+        .await?
+        .rows_affected();
+    info!(
+        processed,
+        limit, "maintenance: provider_items without media"
+    );
+    Ok(processed)
+}
+
+/// `(table, column)` pairs pointing at offer_jurisdictions: the visible price tables plus
+/// every declared foreign key (partition-level copies excluded). `None` when no price
+/// table is visible, as every row would then look orphaned.
+async fn referencing_columns(db: &Db) -> Result<Option<Vec<(String, String)>>> {
+    let mut refs: Vec<(String, String)> = Vec::new();
+    for (table, column) in PRICE_TABLES {
+        if SchemaCaps::global().table_visible(db, table).await? {
+            refs.push((table.to_string(), column.to_string()));
+        }
+    }
+    if refs.is_empty() {
+        return Ok(None);
+    }
+
+    let rows = sqlx::query(
+        "SELECT format('%I.%I', n.nspname, c.relname) AS tbl, a.attname AS col
+         FROM pg_constraint k
+         JOIN pg_class c ON c.oid = k.conrelid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         JOIN pg_attribute a ON a.attrelid = k.conrelid AND a.attnum = k.conkey[1]
+         WHERE k.contype = 'f'
+           AND k.confrelid = 'public.offer_jurisdictions'::regclass
+           AND k.conparentid = 0
+           AND array_length(k.conkey, 1) = 1",
+    )
+    .persistent(false)
+    .fetch_all(&db.pool)
+    .await?;
+
+    for r in rows {
+        let pair: (String, String) = (r.try_get("tbl")?, r.try_get("col")?);
+        if !refs.contains(&pair) {
+            refs.push(pair);
+        }
+    }
+    Ok(Some(refs))
+}
+
+fn unreferenced_predicate(referencing: &[(String, String)]) -> String {
+    referencing
+        .iter()
+        .map(|(table, column)| {
+            format!("NOT EXISTS (SELECT 1 FROM {table} r WHERE r.\"{column}\" = oj.id)")
+        })
+        .collect::<Vec<_>>()
+        .join(" AND ")
+}
+
+/// `$1` = minimum offer age in hours, `$2` = limit.
+fn orphan_select_sql(referencing: &[(String, String)]) -> String {
+    format!(
+        "SELECT oj.id
+         FROM public.offer_jurisdictions oj
+         JOIN public.offers o ON o.id = oj.offer_id
+         WHERE o.created_at < now() - make_interval(hours => $1::int)
+           AND {}
+         ORDER BY oj.id
+         LIMIT $2",
+        unreferenced_predicate(referencing)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    fn price_refs() -> Vec<(String, String)> {
+        PRICE_TABLES
+            .iter()
+            .map(|(t, c)| (t.to_string(), c.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn select_excludes_every_referencing_table() {
+        let mut refs = price_refs();
+        refs.push((
+            "public.alerts".to_string(),
+            "offer_jurisdiction_id".to_string(),
+        ));
+        let sql = orphan_select_sql(&refs);
+        for table in ["public.prices", "public.current_price", "public.alerts"] {
+            assert!(sql.contains(&format!("NOT EXISTS (SELECT 1 FROM {table} r")));
+        }
+        assert_eq!(sql.matches("NOT EXISTS").count(), 3);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn items_without_media_are_checked_once() {
+        // One connection, so the temp tables below shadow the real ones for every query.
+        let db = RollbackDb::connect().await;
+        for sql in [
+            "CREATE TEMP TABLE provider_items (
+                 id bigint PRIMARY KEY,
+                 metadata jsonb,
+                 updated_at timestamptz
+             )",
+            "CREATE TEMP TABLE provider_media_links (provider_item_id bigint NOT NULL, url text)",
+            "INSERT INTO provider_items (id, metadata) VALUES
+                 (1, NULL), (2, '{\"title\": \"x\"}'), (3, '{}'), (4, NULL), (5, '{}')",
+            "INSERT INTO provider_media_links VALUES (2, 'https://img/cover.png'),
+                 (4, 'https://img/hero.png')",
+        ] {
+            sqlx::query(sql).execute(&db.pool).await.unwrap();
+        }
+
+        assert_eq!(cleanup_missing_media(&db, 1).await.unwrap(), 1);
+        assert_eq!(cleanup_missing_media(&db, 100).await.unwrap(), 2);
+        assert_eq!(cleanup_missing_media(&db, 100).await.unwrap(), 0);
+
+        let checked: Vec<(i64, bool)> = sqlx::query_as(&format!(
+            "SELECT id, COALESCE(metadata ? '{MEDIA_CHECKED_KEY}', false)
+             FROM provider_items ORDER BY id"
+        ))
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            checked,
+            vec![(1, true), (2, false), (3, true), (4, false), (5, true)]
+        );
+        // Checking for media is not a refresh: updated_at stays as the ingest left it.
+        let touched: i64 =
+            sqlx::query_scalar("SELECT count(*) FROM provider_items WHERE updated_at IS NOT NULL")
+                .fetch_one(&db.pool)
+                .await
+                .unwrap();
+        assert_eq!(touched, 0);
+        let title: Option<String> =
+            sqlx::query_scalar("SELECT metadata->>'title' FROM provider_items WHERE id = 2")
+                .fetch_one(&db.pool)
+                .await
+                .unwrap();
+        assert_eq!(title.as_deref(), Some("x"));
+
+        db.rollback().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn orphan_cleanup_is_a_no_op_without_offer_tables() {
+        let db = RollbackDb::connect().await;
+        if SchemaCaps::global()
+            .table_visible(&db, "public.offer_jurisdictions")
+            .await
+            .unwrap()
+        {
+            // Covered by orphan_is_flagged_and_removed_while_priced_row_survives.
+            return db.rollback().await;
+        }
+        let opts = OrphanCleanupOptions {
+            delete: true,
+            ..OrphanCleanupOptions::default()
+        };
+        let report = cleanup_orphaned_offer_jurisdictions(&db, &opts)
+            .await
+            .unwrap();
+        assert!(report.orphan_ids.is_empty());
+        assert_eq!(report.deleted, 0);
+
+        db.rollback().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn orphan_is_flagged_and_removed_while_priced_row_survives() {
+        let db = RollbackDb::connect().await;
+        if !SchemaCaps::global()
+            .table_visible(&db, "public.offers")
+            .await
+            .unwrap()
+        {
+            // Covered by orphan_cleanup_is_a_no_op_without_offer_tables.
+            return db.rollback().await;
+        }
+
+        // Reuse any existing offer/jurisdiction/currency and backdate a throwaway offer.
+        let row = sqlx::query(
+            "WITH o AS (
+                 INSERT INTO public.offers (sellable_id, retailer_id, sku, created_at)
+                 SELECT sellable_id, retailer_id, 'orphan-oj-test', now() - interval '30 days'
+                 FROM public.offers LIMIT 1
+                 RETURNING id
+             )
+             SELECT o.id AS offer_id,
+                    (SELECT id FROM public.jurisdictions ORDER BY id LIMIT 1) AS j1,
+                    (SELECT id FROM public.jurisdictions ORDER BY id OFFSET 1 LIMIT 1) AS j2,
+                    (SELECT id FROM public.currencies ORDER BY id LIMIT 1) AS currency_id
+             FROM o",
+        )
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let offer_id: i64 = row.get("offer_id");
+        let currency_id: i64 = row.get("currency_id");
+
+        let mut ids = Vec::new();
+        for j in [row.get::<i64, _>("j1"), row.get::<i64, _>("j2")] {
+            let id: i64 = sqlx::query_scalar(
+                "INSERT INTO public.offer_jurisdictions (offer_id, jurisdiction_id, currency_id)
+                 VALUES ($1, $2, $3) RETURNING id",
+            )
+            .bind(offer_id)
+            .bind(j)
+            .bind(currency_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+            ids.push(id);
+        }
+        let (orphan, priced) = (ids[0], ids[1]);
+        sqlx::query(
+            "INSERT INTO public.current_price (offer_jurisdiction_id, amount_minor, recorded_at)
+             VALUES ($1, 1999, now())",
+        )
+        .bind(priced)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        let flag = OrphanCleanupOptions {
+            min_age_hours: 24,
+            limit: 100_000,
+            delete: false,
+        };
+        let report = cleanup_orphaned_offer_jurisdictions(&db, &flag)
+            .await
+            .unwrap();
+        assert!(report.orphan_ids.contains(&orphan));
+        assert!(!report.orphan_ids.contains(&priced));
+        assert_eq!(report.deleted, 0);
+
+        let delete = OrphanCleanupOptions {
+            delete: true,
+            ..flag
+        };
+        cleanup_orphaned_offer_jurisdictions(&db, &delete)
+            .await
+            .unwrap();
+        let remaining: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM public.offer_jurisdictions WHERE offer_id = $1 ORDER BY id",
+        )
+        .bind(offer_id)
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        assert_eq!(remaining, vec![priced]);
+
+        db.rollback().await;
+    }
+}
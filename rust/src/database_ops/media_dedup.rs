@@ -0,0 +1,382 @@
+//! Cross-provider media deduplication per video game.
+//!
+//! A game ingested from several providers collects the same asset more than once, often
+//! as different size variants of one CDN image (RAWG `/media/resize/640/-/…`, IGDB
+//! `t_thumb` vs `t_cover_big`). Media are grouped by a canonical URL key; the largest
+//! copy of each group is kept and records every provider that supplied it in
+//! `metadata.providers`, the rest are marked `metadata.duplicate_of` rather than deleted.
+//! Processed rows carry `metadata.dedup_checked`, so a run only revisits games that
+//! gained new media since.
+
+use anyhow::Result;
+use serde_json::json;
+use sqlx::Row;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use tracing::{debug, info, instrument, warn};
+
+use crate::database_ops::db::Db;
+use crate::database_ops::media_primary::{
+    candidate_from_row, canonical_provider, recompute_primary_media_batch, MediaCandidate,
+    PrimaryMediaPolicy, MEDIA_COLUMNS,
+};
+
+#[derive(Debug, Clone)]
+pub struct MediaDedupOptions {
+    /// Games processed per run, most recently updated media first.
+    pub limit: i64,
+    /// Ignore query strings (resize/format parameters) when comparing URLs.
+    pub strip_query: bool,
+}
+
+impl Default for MediaDedupOptions {
+    fn default() -> Self {
+        Self {
+            limit: 200,
+            strip_query: true,
+        }
+    }
+}
+
+impl MediaDedupOptions {
+    /// From `MEDIA_DEDUP_LIMIT` and `MEDIA_DEDUP_STRIP_QUERY`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            limit: crate::util::env::env_parse("MEDIA_DEDUP_LIMIT", defaults.limit),
+            strip_query: crate::util::env::env_flag(
+                "MEDIA_DEDUP_STRIP_QUERY",
+                defaults.strip_query,
+            ),
+        }
+    }
+}
+
+/// One asset of the deduped set and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DedupedMedia {
+    pub media_id: i64,
+    pub url: String,
+    /// Canonical provider names that supplied a copy, sorted.
+    pub providers: Vec<String>,
+    /// Other copies of the same asset.
+    pub duplicate_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MediaDedupReport {
+    pub games: usize,
+    pub duplicates: usize,
+}
+
+/// Comparison key for a media URL: host without `www.`, path with known size variants
+/// folded (RAWG resize/crop prefixes, the IGDB `t_*` size directory), query optional.
+pub fn canonical_media_key(url: &str, strip_query: bool) -> String {
+    let Ok(parsed) = url::Url::parse(url.trim()) else {
+        return url.trim().to_ascii_lowercase();
+    };
+    let host = parsed
+        .host_str()
+        .unwrap_or_default()
+        .trim_start_matches("www.")
+        .to_ascii_lowercase();
+    let mut segments: Vec<&str> = parsed
+        .path_segments()
+        .map(|s| s.filter(|p| !p.is_empty()).collect())
+        .unwrap_or_default();
+    // RAWG: /media/resize/640/-/games/… and /media/crop/600/400/games/…
+    if segments.first() == Some(&"media")
+        && matches!(segments.get(1), Some(&"resize") | Some(&"crop"))
+        && segments.len() > 4
+    {
+        segments.drain(1..4);
+    }
+    // IGDB: /igdb/image/upload/t_cover_big/<id>.jpg; only that directory is a size.
+    if host == "images.igdb.com"
+        && segments.len() > 4
+        && segments[..3] == ["igdb", "image", "upload"]
+        && segments[3].starts_with("t_")
+    {
+        segments[3] = "t_original";
+    }
+    let mut key = format!("{host}/{}", segments.join("/"));
+    if !strip_query {
+        if let Some(query) = parsed.query().filter(|q| !q.is_empty()) {
+            key.push('?');
+            key.push_str(query);
+        }
+    }
+    key
+}
+
+/// Collapse `candidates` into one entry per canonical URL, in first-seen order. The
+/// largest copy is kept (unknown dimensions rank last, then the lower media id).
+pub fn dedupe(candidates: &[MediaCandidate], strip_query: bool) -> Vec<DedupedMedia> {
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut groups: Vec<Vec<&MediaCandidate>> = Vec::new();
+    for c in candidates {
+        let key = canonical_media_key(&c.url, strip_query);
+        let slot = *index.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[slot].push(c);
+    }
+
+    groups
+        .into_iter()
+        .map(|mut group| {
+            group.sort_by_key(|c| (Reverse(c.area()), c.media_id));
+            let mut providers: Vec<String> = group
+                .iter()
+                .filter_map(|c| c.source.as_deref())
+                .map(canonical_provider)
+                .filter(|p| !p.is_empty())
+                .collect();
+            providers.sort();
+            providers.dedup();
+            DedupedMedia {
+                media_id: group[0].media_id,
+                url: group[0].url.clone(),
+                providers,
+                duplicate_ids: group[1..].iter().map(|c| c.media_id).collect(),
+            }
+        })
+        .collect()
+}
+
+/// Dedupe the media of one game and persist the result: keepers get
+/// `metadata.providers` (and lose any stale `duplicate_of`), copies get
+/// `metadata.duplicate_of`. Returns the deduped set.
+#[instrument(skip(db, opts))]
+pub async fn dedupe_game_media(
+    db: &Db,
+    video_game_id: i64,
+    opts: &MediaDedupOptions,
+) -> Result<Vec<DedupedMedia>> {
+    let sql = format!(
+        "SELECT {MEDIA_COLUMNS}
+         FROM public.canonical_media cm
+         WHERE cm.metadata->>'video_game_id' = $1::bigint::text
+         ORDER BY cm.id"
+    );
+    let candidates = sqlx::query(&sql)
+        .persistent(false)
+        .bind(video_game_id)
+        .fetch_all(&db.pool)
+        .await?
+        .iter()
+        .map(candidate_from_row)
+        .collect::<Result<Vec<_>>>()?;
+
+    let deduped = dedupe(&candidates, opts.strip_query);
+    for media in deduped.iter().filter(|m| !m.duplicate_ids.is_empty()) {
+        sqlx::query(
+            "UPDATE public.canonical_media
+             SET metadata = (metadata - 'duplicate_of') || jsonb_build_object('providers', $2::jsonb),
+                 updated_at = now()
+             WHERE id = $1
+               AND (metadata ? 'duplicate_of'
+                    OR metadata->'providers' IS DISTINCT FROM $2::jsonb)",
+        )
+        .persistent(false)
+        .bind(media.media_id)
+        .bind(json!(media.providers))
+        .execute(&db.pool)
+        .await?;
+        sqlx::query(
+            "UPDATE public.canonical_media
+             SET metadata = metadata || jsonb_build_object('duplicate_of', $1::bigint),
+                 updated_at = now()
+             WHERE id = ANY($2)
+               AND metadata->>'duplicate_of' IS DISTINCT FROM $1::bigint::text",
+        )
+        .persistent(false)
+        .bind(media.media_id)
+        .bind(&media.duplicate_ids)
+        .execute(&db.pool)
+        .await?;
+    }
+
+    let checked: Vec<i64> = candidates.iter().map(|c| c.media_id).collect();
+    sqlx::query(
+        "UPDATE public.canonical_media
+         SET metadata = metadata || '{\"dedup_checked\": true}'::jsonb
+         WHERE id = ANY($1) AND NOT metadata ? 'dedup_checked'",
+    )
+    .persistent(false)
+    .bind(&checked)
+    .execute(&db.pool)
+    .await?;
+
+    debug!(
+        video_game_id,
+        candidates = candidates.len(),
+        deduped = deduped.len(),
+        "media_dedup: game processed"
+    );
+    Ok(deduped)
+}
+
+/// Dedupe games whose media came from more than one provider and that have media not yet
+/// checked, then re-pick their primary cover so it never points at a duplicate.
+#[instrument(skip(db))]
+pub async fn dedupe_multi_provider_media(
+    db: &Db,
+    opts: &MediaDedupOptions,
+) -> Result<MediaDedupReport> {
+    let game_ids: Vec<i64> = sqlx::query(
+        "SELECT (cm.metadata->>'video_game_id')::bigint AS video_game_id
+         FROM public.canonical_media cm
+         WHERE cm.metadata->>'video_game_id' ~ '^[0-9]{1,18}$'
+         GROUP BY 1
+         HAVING count(DISTINCT cm.metadata->>'source') > 1
+            AND bool_or(NOT cm.metadata ? 'dedup_checked')
+         ORDER BY max(cm.updated_at) DESC NULLS LAST
+         LIMIT $1",
+    )
+    .persistent(false)
+    .bind(opts.limit.max(1))
+    .fetch_all(&db.pool)
+    .await?
+    .into_iter()
+    .map(|r| r.try_get::<i64, _>("video_game_id"))
+    .collect::<Result<_, _>>()?;
+
+    let mut report = MediaDedupReport::default();
+    for video_game_id in &game_ids {
+        let deduped = dedupe_game_media(db, *video_game_id, opts).await?;
+        report.games += 1;
+        report.duplicates += deduped.iter().map(|m| m.duplicate_ids.len()).sum::<usize>();
+    }
+    let policy = PrimaryMediaPolicy::from_env();
+    if let Err(e) = recompute_primary_media_batch(db, &game_ids, &policy).await {
+        warn!(games = game_ids.len(), error = %e, "media_dedup: primary media recompute failed");
+    }
+
+    info!(
+        games = report.games,
+        duplicates = report.duplicates,
+        "media_dedup: multi-provider games deduped"
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn media(id: i64, url: &str, source: &str, size: Option<(i32, i32)>) -> MediaCandidate {
+        MediaCandidate {
+            media_id: id,
+            url: url.to_string(),
+            media_type: Some("image".to_string()),
+            role: Some("screenshot".to_string()),
+            source: Some(source.to_string()),
+            width: size.map(|s| s.0),
+            height: size.map(|s| s.1),
+        }
+    }
+
+    #[test]
+    fn size_variants_share_a_key() {
+        assert_eq!(
+            canonical_media_key(
+                "https://media.rawg.io/media/resize/640/-/games/abc/shot1.jpg",
+                true
+            ),
+            canonical_media_key("https://media.rawg.io/media/games/abc/shot1.jpg", true)
+        );
+        assert_eq!(
+            canonical_media_key(
+                "https://images.igdb.com/igdb/image/upload/t_thumb/co1abc.jpg",
+                true
+            ),
+            canonical_media_key(
+                "https://IMAGES.igdb.com/igdb/image/upload/t_cover_big/co1abc.jpg?x=1",
+                true
+            )
+        );
+        assert_ne!(
+            canonical_media_key("https://cdn.example.com/a.jpg?w=1", false),
+            canonical_media_key("https://cdn.example.com/a.jpg?w=2", false)
+        );
+    }
+
+    #[test]
+    fn t_prefixed_names_outside_igdb_size_segment_are_kept() {
+        assert_ne!(
+            canonical_media_key("https://cdn.example.com/art/t_cover.jpg", true),
+            canonical_media_key("https://cdn.example.com/art/t_hero.jpg", true)
+        );
+        assert_ne!(
+            canonical_media_key("https://cdn.example.com/t_big/shot.jpg", true),
+            canonical_media_key("https://cdn.example.com/t_small/shot.jpg", true)
+        );
+        // On IGDB itself the file name is never rewritten.
+        assert_ne!(
+            canonical_media_key(
+                "https://images.igdb.com/igdb/image/upload/t_thumb/t_a.jpg",
+                true
+            ),
+            canonical_media_key(
+                "https://images.igdb.com/igdb/image/upload/t_thumb/t_b.jpg",
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn overlapping_media_from_two_providers_collapse() {
+        let candidates = vec![
+            media(
+                1,
+                "https://media.rawg.io/media/resize/640/-/games/abc/shot1.jpg",
+                "rawg",
+                Some((640, 360)),
+            ),
+            media(
+                2,
+                "https://media.rawg.io/media/games/abc/shot2.jpg",
+                "rawg",
+                None,
+            ),
+            media(
+                3,
+                "https://media.rawg.io/media/games/abc/shot1.jpg",
+                "igdb",
+                Some((1920, 1080)),
+            ),
+            media(
+                4,
+                "https://images.igdb.com/igdb/image/upload/t_thumb/co1abc.jpg",
+                "igdb",
+                None,
+            ),
+            media(
+                5,
+                "https://images.igdb.com/igdb/image/upload/t_cover_big/co1abc.jpg",
+                "psn",
+                Some((264, 374)),
+            ),
+        ];
+
+        let deduped = dedupe(&candidates, true);
+        assert_eq!(deduped.len(), 3);
+
+        let shot1 = &deduped[0];
+        assert_eq!(shot1.media_id, 3, "largest copy is kept");
+        assert_eq!(shot1.duplicate_ids, vec![1]);
+        assert_eq!(shot1.providers, vec!["igdb", "rawg"]);
+
+        let shot2 = &deduped[1];
+        assert_eq!(shot2.media_id, 2);
+        assert!(shot2.duplicate_ids.is_empty());
+        assert_eq!(shot2.providers, vec!["rawg"]);
+
+        let cover = &deduped[2];
+        assert_eq!(cover.media_id, 5);
+        assert_eq!(cover.duplicate_ids, vec![4]);
+        assert_eq!(cover.providers, vec!["igdb", "psstore"]);
+    }
+}
@@ -9,6 +9,8 @@
 //! demonstrates the gold standard for media filtering.
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
 
 /// Media classification for images
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -285,6 +287,83 @@ pub fn should_include_screenshots() -> bool {
         .unwrap_or(false)
 }
 
+/// Host allow/block lists for media URLs (`MEDIA_HOST_ALLOWLIST`, `MEDIA_HOST_BLOCKLIST`).
+///
+/// Entries are comma-separated host names and match the host itself or any subdomain.
+/// The blocklist always wins; an empty allowlist permits every host.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MediaHostPolicy {
+    pub allow: Vec<String>,
+    pub block: Vec<String>,
+}
+
+static HOST_REJECTIONS: AtomicU64 = AtomicU64::new(0);
+
+impl MediaHostPolicy {
+    pub fn new(allow: &str, block: &str) -> Self {
+        let parse = |raw: &str| {
+            raw.split(',')
+                .map(|h| h.trim().trim_start_matches("*.").trim_start_matches('.'))
+                .map(|h| h.trim_end_matches('.').to_ascii_lowercase())
+                .filter(|h| !h.is_empty())
+                .collect::<Vec<_>>()
+        };
+        Self {
+            allow: parse(allow),
+            block: parse(block),
+        }
+    }
+
+    /// Read once per process; both lists unset means every host is permitted.
+    pub fn from_env() -> &'static Self {
+        static POLICY: OnceLock<MediaHostPolicy> = OnceLock::new();
+        POLICY.get_or_init(|| {
+            Self::new(
+                &std::env::var("MEDIA_HOST_ALLOWLIST").unwrap_or_default(),
+                &std::env::var("MEDIA_HOST_BLOCKLIST").unwrap_or_default(),
+            )
+        })
+    }
+
+    pub fn is_permissive(&self) -> bool {
+        self.allow.is_empty() && self.block.is_empty()
+    }
+
+    /// Whether media at `url` may be stored. URLs without a parseable host are rejected
+    /// once any list is configured.
+    pub fn permits(&self, url: &str) -> bool {
+        if self.is_permissive() {
+            return true;
+        }
+        let Some(host) = url::Url::parse(url.trim()).ok().and_then(|u| {
+            u.host_str()
+                .map(|h| h.trim_end_matches('.').to_ascii_lowercase())
+        }) else {
+            return false;
+        };
+        let matches = |entry: &String| {
+            host == *entry
+                || host
+                    .strip_suffix(entry.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        };
+        if self.block.iter().any(matches) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.iter().any(matches)
+    }
+}
+
+/// Record `n` media URLs rejected by [`MediaHostPolicy`].
+pub fn record_host_rejections(n: usize) {
+    HOST_REJECTIONS.fetch_add(n as u64, Ordering::Relaxed);
+}
+
+/// Media URLs rejected by the host policy since process start.
+pub fn host_rejections() -> u64 {
+    HOST_REJECTIONS.load(Ordering::Relaxed)
+}
+
 /// Statistics for media classification (for logging/debugging)
 #[derive(Debug, Default)]
 pub struct MediaStats {
@@ -418,6 +497,26 @@ mod tests {
         assert!(!ImageType::Logo.should_include(true)); // Always excluded
     }
 
+    #[test]
+    fn test_media_host_policy() {
+        let open = MediaHostPolicy::new("", "");
+        assert!(open.permits("https://anything.example/cover.jpg"));
+        assert!(open.permits("not a url"));
+
+        let policy =
+            MediaHostPolicy::new("playstation.net, *.steamstatic.com", "ads.playstation.net");
+        assert!(policy.permits("https://image.api.playstation.net/cdn/cover.png"));
+        assert!(policy.permits("https://cdn.cloudflare.steamstatic.com/a.jpg"));
+        assert!(!policy.permits("https://ads.playstation.net/banner.jpg"));
+        assert!(!policy.permits("https://evilplaystation.net/cover.png"));
+        assert!(!policy.permits("https://tracker.example.com/pixel.gif"));
+        assert!(!policy.permits("/relative/path.jpg"));
+
+        let block_only = MediaHostPolicy::new("", "tracker.example.com");
+        assert!(block_only.permits("https://cdn.example.com/a.jpg"));
+        assert!(!block_only.permits("https://TRACKER.example.com./a.jpg"));
+    }
+
     #[test]
     fn test_classify_video_from_url() {
         assert_eq!(
@@ -0,0 +1,795 @@
+//! Primary cover selection per video game.
+//!
+//! After media links are ingested, every image attached to a game is ranked by role
+//! (`MEDIA_PRIMARY_ROLE_PRIORITY`, default `cover,hero,artwork,screenshot`), then by
+//! resolution within a role, then by provider (`MEDIA_PRIMARY_PROVIDER_PRIORITY`); the
+//! winner is stored in `video_games.primary_media_id`. Games left without a cover can
+//! borrow one from another provider's copy of the same game (`MEDIA_BACKFILL_ENABLED`);
+//! games that found nothing are stamped `metadata.media_backfill_checked_at` and skipped
+//! until `MEDIA_BACKFILL_RECHECK_DAYS` have passed.
+
+use anyhow::Result;
+use sqlx::Row;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use tracing::{debug, instrument};
+
+use crate::database_ops::db::Db;
+use crate::database_ops::media_filter::classify_image_from_url;
+use crate::database_ops::schema_caps::SchemaCaps;
+use crate::normalization::title::{TitleKey, MIN_TITLE_SIMILARITY};
+
+pub const DEFAULT_ROLE_PRIORITY: &str = "cover,hero,artwork,screenshot";
+pub const DEFAULT_PROVIDER_PRIORITY: &str = "psstore,xbox,steam,igdb,giantbomb,rawg,nexarda";
+
+/// Media types that are never eligible as a cover.
+const VIDEO_MEDIA_TYPES: &[&str] = &["video", "trailer", "gameplay", "preview", "advertisement"];
+
+/// Ranking rules for the primary cover; earlier entries win.
+#[derive(Debug, Clone)]
+pub struct PrimaryMediaPolicy {
+    pub roles: Vec<String>,
+    pub providers: Vec<String>,
+}
+
+impl Default for PrimaryMediaPolicy {
+    fn default() -> Self {
+        Self {
+            roles: parse_list(DEFAULT_ROLE_PRIORITY),
+            providers: parse_list(DEFAULT_PROVIDER_PRIORITY),
+        }
+    }
+}
+
+impl PrimaryMediaPolicy {
+    pub fn from_env() -> Self {
+        let read = |key: &str, default: &str| {
+            let list = crate::util::env::env_opt(key)
+                .map(|raw| parse_list(&raw))
+                .unwrap_or_default();
+            if list.is_empty() {
+                parse_list(default)
+            } else {
+                list
+            }
+        };
+        Self {
+            roles: read("MEDIA_PRIMARY_ROLE_PRIORITY", DEFAULT_ROLE_PRIORITY),
+            providers: read("MEDIA_PRIMARY_PROVIDER_PRIORITY", DEFAULT_PROVIDER_PRIORITY),
+        }
+    }
+
+    /// Rank of a role; roles missing from the list sort after every listed one.
+    fn role_rank(&self, role: &str) -> usize {
+        self.roles
+            .iter()
+            .position(|r| r == role)
+            .unwrap_or(self.roles.len())
+    }
+
+    fn provider_rank(&self, source: Option<&str>) -> usize {
+        let source = source.map(canonical_provider);
+        self.providers
+            .iter()
+            .position(|p| source.as_deref() == Some(canonical_provider(p).as_str()))
+            .unwrap_or(self.providers.len())
+    }
+}
+
+/// One canonical_media row attached to a game.
+#[derive(Debug, Clone)]
+pub struct MediaCandidate {
+    pub media_id: i64,
+    pub url: String,
+    pub media_type: Option<String>,
+    pub role: Option<String>,
+    pub source: Option<String>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+}
+
+impl MediaCandidate {
+    /// Pixel count, 0 when the dimensions are unknown.
+    pub(crate) fn area(&self) -> i64 {
+        match (self.width, self.height) {
+            (Some(w), Some(h)) if w > 0 && h > 0 => i64::from(w) * i64::from(h),
+            _ => 0,
+        }
+    }
+
+    fn is_video(&self) -> bool {
+        self.media_type
+            .as_deref()
+            .map(|t| VIDEO_MEDIA_TYPES.contains(&t.trim().to_ascii_lowercase().as_str()))
+            .unwrap_or(false)
+    }
+
+    /// Logical role: the stored role when it names a known image kind, otherwise the
+    /// media_filter classification of the role/media_type/URL.
+    fn cover_role(&self, policy: &PrimaryMediaPolicy) -> String {
+        for raw in [self.role.as_deref(), self.media_type.as_deref()]
+            .into_iter()
+            .flatten()
+        {
+            let raw = raw.trim().to_ascii_lowercase();
+            if policy.roles.contains(&raw) {
+                return raw;
+            }
+        }
+        let hint = self.role.as_deref().or(self.media_type.as_deref());
+        classify_image_from_url(&self.url, hint)
+            .to_media_type()
+            .to_string()
+    }
+}
+
+/// Best cover among `candidates`, or `None` when only videos are attached. Within a role
+/// the larger image wins (unknown dimensions rank last), then the preferred provider;
+/// remaining ties go to the lower media id so the choice is stable across recomputes.
+pub fn pick_primary<'a>(
+    candidates: &'a [MediaCandidate],
+    policy: &PrimaryMediaPolicy,
+) -> Option<&'a MediaCandidate> {
+    candidates.iter().filter(|c| !c.is_video()).min_by_key(|c| {
+        (
+            policy.role_rank(&c.cover_role(policy)),
+            Reverse(c.area()),
+            policy.provider_rank(c.source.as_deref()),
+            c.media_id,
+        )
+    })
+}
+
+/// Re-rank the media of `video_game_id` and store the winner in
+/// `video_games.primary_media_id`. Returns the chosen media id; see
+/// [`recompute_primary_media_batch`].
+pub async fn recompute_primary_media(
+    db: &Db,
+    video_game_id: i64,
+    policy: &PrimaryMediaPolicy,
+) -> Result<Option<i64>> {
+    Ok(recompute_primary_media_batch(db, &[video_game_id], policy)
+        .await?
+        .remove(&video_game_id))
+}
+
+/// Re-rank the media of every game in `video_game_ids` with one candidate read and one
+/// UPDATE, returning the winner per game. A game left with only videos (or no media)
+/// keeps its current `primary_media_id` rather than losing it. No-op on databases
+/// without the column.
+#[instrument(skip(db, video_game_ids, policy), fields(games = video_game_ids.len()))]
+pub async fn recompute_primary_media_batch(
+    db: &Db,
+    video_game_ids: &[i64],
+    policy: &PrimaryMediaPolicy,
+) -> Result<HashMap<i64, i64>> {
+    if video_game_ids.is_empty() || !primary_media_column_present(db).await? {
+        return Ok(HashMap::new());
+    }
+
+    let keys: Vec<String> = video_game_ids.iter().map(i64::to_string).collect();
+    let sql = format!(
+        "SELECT {MEDIA_COLUMNS}, (cm.metadata->>'video_game_id')::bigint AS video_game_id
+         FROM public.canonical_media cm
+         WHERE cm.metadata->>'video_game_id' = ANY($1)
+           AND NOT cm.metadata ? 'duplicate_of'"
+    );
+    let mut by_game: HashMap<i64, Vec<MediaCandidate>> = HashMap::new();
+    for row in sqlx::query(&sql)
+        .persistent(false)
+        .bind(&keys)
+        .fetch_all(&db.pool)
+        .await?
+    {
+        by_game
+            .entry(row.try_get("video_game_id")?)
+            .or_default()
+            .push(candidate_from_row(&row)?);
+    }
+
+    let primaries: HashMap<i64, i64> = by_game
+        .iter()
+        .filter_map(|(vg_id, candidates)| {
+            Some((*vg_id, pick_primary(candidates, policy)?.media_id))
+        })
+        .collect();
+    let (ids, media_ids): (Vec<i64>, Vec<i64>) = primaries.iter().map(|(k, v)| (*k, *v)).unzip();
+    let updated = sqlx::query(
+        "UPDATE public.video_games vg
+         SET primary_media_id = p.media_id, updated_at = now()
+         FROM unnest($1::bigint[], $2::bigint[]) AS p(video_game_id, media_id)
+         WHERE vg.id = p.video_game_id AND vg.primary_media_id IS DISTINCT FROM p.media_id",
+    )
+    .persistent(false)
+    .bind(&ids)
+    .bind(&media_ids)
+    .execute(&db.pool)
+    .await?
+    .rows_affected();
+
+    debug!(
+        games = video_game_ids.len(),
+        with_media = by_game.len(),
+        updated,
+        "media_primary: recomputed"
+    );
+    Ok(primaries)
+}
+
+/// Cover backfill from secondary providers (`MEDIA_BACKFILL_*`).
+#[derive(Debug, Clone)]
+pub struct MediaBackfillOptions {
+    /// Providers whose media may be borrowed, in preference order.
+    pub providers: Vec<String>,
+    /// Games without a primary cover examined per run.
+    pub limit: i64,
+    pub min_title_similarity: f64,
+    /// Days before a game that found no cover is examined again.
+    pub recheck_days: i32,
+}
+
+impl MediaBackfillOptions {
+    /// From `MEDIA_BACKFILL_PROVIDERS` (default `rawg,igdb,giantbomb`),
+    /// `MEDIA_BACKFILL_LIMIT` (default 200), `MEDIA_BACKFILL_MIN_SIMILARITY` and
+    /// `MEDIA_BACKFILL_RECHECK_DAYS` (default 7).
+    pub fn from_env() -> Self {
+        let providers = crate::util::env::env_opt("MEDIA_BACKFILL_PROVIDERS")
+            .map(|raw| parse_list(&raw))
+            .filter(|list| !list.is_empty())
+            .unwrap_or_else(|| parse_list(DEFAULT_BACKFILL_PROVIDERS));
+        Self {
+            providers,
+            limit: crate::util::env::env_parse("MEDIA_BACKFILL_LIMIT", 200),
+            min_title_similarity: crate::util::env::env_parse(
+                "MEDIA_BACKFILL_MIN_SIMILARITY",
+                MIN_TITLE_SIMILARITY,
+            ),
+            recheck_days: crate::util::env::env_parse("MEDIA_BACKFILL_RECHECK_DAYS", 7),
+        }
+    }
+}
+
+/// video_games.metadata key stamped when a backfill pass found no cover.
+pub const BACKFILL_CHECKED_KEY: &str = "media_backfill_checked_at";
+
+pub const DEFAULT_BACKFILL_PROVIDERS: &str = "rawg,igdb,giantbomb";
+
+/// Media attached to another game, with that game's title for matching.
+#[derive(Debug, Clone)]
+pub struct MatchedMedia {
+    pub game_title: String,
+    pub media: MediaCandidate,
+}
+
+/// Best cover for a game titled `title` among media of other providers' games: only
+/// titles that pass the cross-provider title match and providers listed in
+/// `opts.providers` qualify, ranked like [`pick_primary`] with the backfill providers as
+/// the provider preference.
+pub fn pick_backfill<'a>(
+    title: &str,
+    candidates: &'a [MatchedMedia],
+    policy: &PrimaryMediaPolicy,
+    opts: &MediaBackfillOptions,
+) -> Option<&'a MediaCandidate> {
+    let target = TitleKey::new(title);
+    let allowed: Vec<String> = opts
+        .providers
+        .iter()
+        .map(|p| canonical_provider(p))
+        .collect();
+    let eligible: Vec<MediaCandidate> = candidates
+        .iter()
+        .filter(|m| {
+            m.media
+                .source
+                .as_deref()
+                .is_some_and(|s| allowed.contains(&canonical_provider(s)))
+        })
+        .filter(|m| target.similarity(&TitleKey::new(&m.game_title)) >= opts.min_title_similarity)
+        .map(|m| m.media.clone())
+        .collect();
+    let policy = PrimaryMediaPolicy {
+        roles: policy.roles.clone(),
+        providers: allowed,
+    };
+    let best = pick_primary(&eligible, &policy)?.media_id;
+    candidates
+        .iter()
+        .map(|m| &m.media)
+        .find(|m| m.media_id == best)
+}
+
+/// For games without a primary cover, borrow the best matching cover from another
+/// provider's copy of the same game and promote it. Games with no match are stamped so
+/// the next runs move on to others. Returns the number of games updated.
+#[instrument(skip(db, policy))]
+pub async fn backfill_missing_primary_media(
+    db: &Db,
+    policy: &PrimaryMediaPolicy,
+    opts: &MediaBackfillOptions,
+) -> Result<usize> {
+    if !primary_media_column_present(db).await? {
+        return Ok(0);
+    }
+
+    let targets = sqlx::query(&format!(
+        "SELECT vg.id, COALESCE(vg.display_title, vgt.title) AS title
+         FROM public.video_games vg
+         JOIN public.video_game_titles vgt ON vgt.id = vg.title_id
+         WHERE vg.primary_media_id IS NULL
+           AND COALESCE((vg.metadata->>'{BACKFILL_CHECKED_KEY}')::timestamptz, '-infinity')
+               <= now() - make_interval(days => $2)
+         ORDER BY vg.metadata->>'{BACKFILL_CHECKED_KEY}' NULLS FIRST,
+                  vg.updated_at DESC NULLS LAST, vg.id
+         LIMIT $1"
+    ))
+    .persistent(false)
+    .bind(opts.limit.max(1))
+    .bind(opts.recheck_days.max(0))
+    .fetch_all(&db.pool)
+    .await?;
+
+    let sql = format!(
+        "SELECT {MEDIA_COLUMNS}, COALESCE(ovg.display_title, ovgt.title) AS game_title
+         FROM public.video_game_titles ovgt
+         JOIN public.video_games ovg ON ovg.title_id = ovgt.id
+         JOIN public.canonical_media cm ON cm.metadata->>'video_game_id' = ovg.id::text
+         WHERE ovgt.normalized_title % public.normalize_game_title($2)
+           AND ovg.id <> $1
+           AND lower(cm.metadata->>'source') = ANY($3)
+           AND NOT cm.metadata ? 'duplicate_of'
+         LIMIT 500"
+    );
+    let mark_checked = format!(
+        "UPDATE public.video_games
+         SET metadata = COALESCE(metadata, '{{}}'::jsonb)
+               || jsonb_build_object('{BACKFILL_CHECKED_KEY}', now())
+         WHERE id = $1"
+    );
+    let mut promoted = 0;
+    for target in targets {
+        let video_game_id: i64 = target.try_get("id")?;
+        let title: String = target.try_get("title")?;
+        let candidates = sqlx::query(&sql)
+            .persistent(false)
+            .bind(video_game_id)
+            .bind(&title)
+            .bind(&opts.providers)
+            .fetch_all(&db.pool)
+            .await?
+            .iter()
+            .map(|r| -> Result<MatchedMedia> {
+                Ok(MatchedMedia {
+                    game_title: r.try_get("game_title")?,
+                    media: candidate_from_row(r)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let Some(media) = pick_backfill(&title, &candidates, policy, opts) else {
+            sqlx::query(&mark_checked)
+                .persistent(false)
+                .bind(video_game_id)
+                .execute(&db.pool)
+                .await?;
+            continue;
+        };
+        promoted += sqlx::query(
+            "UPDATE public.video_games SET primary_media_id = $2, updated_at = now()
+             WHERE id = $1 AND primary_media_id IS NULL",
+        )
+        .persistent(false)
+        .bind(video_game_id)
+        .bind(media.media_id)
+        .execute(&db.pool)
+        .await?
+        .rows_affected() as usize;
+        debug!(
+            video_game_id,
+            media_id = media.media_id,
+            source = ?media.source,
+            "media_primary: backfilled cover"
+        );
+    }
+    Ok(promoted)
+}
+
+/// canonical_media columns read into a [`MediaCandidate`] (table alias `cm`).
+pub(crate) const MEDIA_COLUMNS: &str = "cm.id, cm.url,
+    cm.metadata->>'media_type' AS media_type,
+    cm.metadata->>'role' AS role,
+    cm.metadata->>'source' AS source,
+    COALESCE(cm.width, CASE WHEN cm.metadata->>'width' ~ '^[0-9]{1,9}$'
+                            THEN (cm.metadata->>'width')::int END) AS width,
+    COALESCE(cm.height, CASE WHEN cm.metadata->>'height' ~ '^[0-9]{1,9}$'
+                             THEN (cm.metadata->>'height')::int END) AS height";
+
+pub(crate) fn candidate_from_row(r: &sqlx::postgres::PgRow) -> Result<MediaCandidate> {
+    Ok(MediaCandidate {
+        media_id: r.try_get("id")?,
+        url: r.try_get("url")?,
+        media_type: r.try_get("media_type")?,
+        role: r.try_get("role")?,
+        source: r.try_get("source")?,
+        width: r.try_get("width")?,
+        height: r.try_get("height")?,
+    })
+}
+
+pub(crate) async fn primary_media_column_present(db: &Db) -> Result<bool> {
+    SchemaCaps::global()
+        .column_visible(db, "public.video_games", "primary_media_id")
+        .await
+}
+
+fn parse_list(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|s| s.trim().to_ascii_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Provider aliases as written by the ingest paths (`psn` on older enum-typed DBs).
+pub(crate) fn canonical_provider(raw: &str) -> String {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "psn" | "playstation" | "playstation_store" | "ps_store" => "psstore".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    fn media(id: i64, url: &str, role: Option<&str>, source: &str) -> MediaCandidate {
+        MediaCandidate {
+            media_id: id,
+            url: url.to_string(),
+            media_type: Some("image".to_string()),
+            role: role.map(str::to_string),
+            source: Some(source.to_string()),
+            width: None,
+            height: None,
+        }
+    }
+
+    fn sized(candidate: MediaCandidate, width: i32, height: i32) -> MediaCandidate {
+        MediaCandidate {
+            width: Some(width),
+            height: Some(height),
+            ..candidate
+        }
+    }
+
+    #[test]
+    fn role_priority_beats_provider_preference() {
+        let policy = PrimaryMediaPolicy::default();
+        let candidates = vec![
+            media(
+                1,
+                "https://cdn.example.com/shot1.jpg",
+                Some("gallery"),
+                "psstore",
+            ),
+            media(
+                2,
+                "https://cdn.example.com/key.jpg",
+                Some("hero"),
+                "psstore",
+            ),
+            media(3, "https://cdn.example.com/a.jpg", Some("cover"), "rawg"),
+            MediaCandidate {
+                media_type: Some("trailer".to_string()),
+                ..media(4, "https://cdn.example.com/t.mp4", Some("cover"), "psstore")
+            },
+        ];
+        assert_eq!(pick_primary(&candidates, &policy).unwrap().media_id, 3);
+    }
+
+    #[test]
+    fn provider_preference_breaks_role_ties() {
+        let policy = PrimaryMediaPolicy::default();
+        let candidates = vec![
+            media(1, "https://cdn.example.com/boxart.jpg", None, "giantbomb"),
+            media(2, "https://cdn.example.com/img.jpg", Some("cover"), "psn"),
+        ];
+        assert_eq!(pick_primary(&candidates, &policy).unwrap().media_id, 2);
+    }
+
+    #[test]
+    fn custom_role_order_and_fallback_to_unlisted_roles() {
+        let policy = PrimaryMediaPolicy {
+            roles: parse_list("artwork, cover"),
+            providers: Vec::new(),
+        };
+        let candidates = vec![
+            media(1, "https://cdn.example.com/screenshot_1.jpg", None, "igdb"),
+            media(2, "https://cdn.example.com/cover.jpg", None, "igdb"),
+            media(3, "https://cdn.example.com/artwork.jpg", None, "igdb"),
+        ];
+        assert_eq!(pick_primary(&candidates, &policy).unwrap().media_id, 3);
+        assert_eq!(pick_primary(&candidates[..1], &policy).unwrap().media_id, 1);
+    }
+
+    #[test]
+    fn videos_only_yield_no_primary() {
+        let policy = PrimaryMediaPolicy::default();
+        let candidates = vec![MediaCandidate {
+            media_type: Some("video".to_string()),
+            ..media(1, "https://cdn.example.com/cover.mp4", None, "steam")
+        }];
+        assert!(pick_primary(&candidates, &policy).is_none());
+    }
+
+    #[test]
+    fn larger_cover_wins_within_role_before_provider() {
+        let policy = PrimaryMediaPolicy::default();
+        let candidates = vec![
+            sized(
+                media(1, "https://cdn.example.com/a.jpg", Some("cover"), "psstore"),
+                300,
+                400,
+            ),
+            sized(
+                media(2, "https://cdn.example.com/b.jpg", Some("cover"), "rawg"),
+                1200,
+                1600,
+            ),
+            media(3, "https://cdn.example.com/c.jpg", Some("cover"), "psstore"),
+            sized(
+                media(4, "https://cdn.example.com/d.jpg", Some("hero"), "psstore"),
+                3840,
+                2160,
+            ),
+        ];
+        assert_eq!(pick_primary(&candidates, &policy).unwrap().media_id, 2);
+
+        // Equal resolution falls back to provider preference.
+        let tied = vec![
+            sized(
+                media(1, "https://cdn.example.com/a.jpg", Some("cover"), "rawg"),
+                600,
+                800,
+            ),
+            sized(
+                media(2, "https://cdn.example.com/b.jpg", Some("cover"), "steam"),
+                600,
+                800,
+            ),
+        ];
+        assert_eq!(pick_primary(&tied, &policy).unwrap().media_id, 2);
+    }
+
+    #[test]
+    fn backfill_promotes_rawg_cover_for_matched_title() {
+        let policy = PrimaryMediaPolicy::default();
+        let opts = MediaBackfillOptions {
+            providers: parse_list(DEFAULT_BACKFILL_PROVIDERS),
+            limit: 10,
+            min_title_similarity: MIN_TITLE_SIMILARITY,
+            recheck_days: 7,
+        };
+        let matched = |title: &str, m: MediaCandidate| MatchedMedia {
+            game_title: title.to_string(),
+            media: m,
+        };
+        let candidates = vec![
+            matched(
+                "Hollow Knight: Silksong",
+                media(7, "https://media.rawg.io/cover.jpg", Some("cover"), "rawg"),
+            ),
+            matched(
+                "Hollow Knight",
+                media(8, "https://media.rawg.io/hk.jpg", Some("cover"), "rawg"),
+            ),
+            matched(
+                "Hollow Knight™: Silksong Standard Edition",
+                media(9, "https://cdn.example.com/x.jpg", Some("cover"), "steam"),
+            ),
+        ];
+        let picked = pick_backfill("Hollow Knight™: Silksong", &candidates, &policy, &opts);
+        assert_eq!(picked.unwrap().media_id, 7);
+
+        assert!(pick_backfill("Celeste", &candidates, &policy, &opts).is_none());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn backfill_skips_duplicates_and_checked_games() {
+        let db = RollbackDb::connect().await;
+        let pool = &db.pool;
+
+        let platform_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.platforms (code, name)
+             VALUES ('backfill-cover-test', 'Backfill Cover Test')
+             ON CONFLICT (name) DO UPDATE SET code = EXCLUDED.code RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        // Two copies of the same game under separate products; only `donor` has media.
+        let mut games = Vec::new();
+        for slug in ["backfill-cover-target", "backfill-cover-donor"] {
+            let vg_id: i64 = sqlx::query_scalar(
+                "WITH p AS (
+                   INSERT INTO public.products (slug, name) VALUES ($1, 'Backfill Cover Test')
+                   RETURNING id
+                 ), t AS (
+                   INSERT INTO public.video_game_titles (product_id, title, normalized_title)
+                   SELECT id, 'Backfill Cover Test',
+                          public.normalize_game_title('Backfill Cover Test')
+                   FROM p RETURNING id
+                 )
+                 INSERT INTO public.video_games (title_id, platform_id)
+                 SELECT id, $2 FROM t RETURNING id",
+            )
+            .bind(slug)
+            .bind(platform_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            games.push(vg_id);
+        }
+        let (target, donor) = (games[0], games[1]);
+        let media_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.canonical_media (url, url_hash, metadata)
+             VALUES ('https://media.rawg.io/backfill-cover.jpg', md5('backfill-cover-test'),
+                     jsonb_build_object('video_game_id', $1::text, 'source', 'rawg',
+                                        'role', 'cover', 'duplicate_of', 1))
+             RETURNING id",
+        )
+        .bind(donor)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+
+        let policy = PrimaryMediaPolicy::default();
+        let opts = MediaBackfillOptions {
+            providers: parse_list(DEFAULT_BACKFILL_PROVIDERS),
+            limit: 1000,
+            min_title_similarity: MIN_TITLE_SIMILARITY,
+            recheck_days: 7,
+        };
+        let primary = |id: i64| {
+            sqlx::query_scalar::<_, Option<i64>>(
+                "SELECT primary_media_id FROM public.video_games WHERE id = $1",
+            )
+            .bind(id)
+            .fetch_one(pool)
+        };
+
+        // A duplicate is never promoted; the miss is stamped.
+        backfill_missing_primary_media(&db, &policy, &opts)
+            .await
+            .unwrap();
+        assert_eq!(primary(target).await.unwrap(), None);
+        let checked: bool = sqlx::query_scalar(&format!(
+            "SELECT metadata ? '{BACKFILL_CHECKED_KEY}' FROM public.video_games WHERE id = $1"
+        ))
+        .bind(target)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        assert!(checked);
+
+        // Once the media is usable, the stamped game waits for the recheck window.
+        sqlx::query(
+            "UPDATE public.canonical_media SET metadata = metadata - 'duplicate_of' WHERE id = $1",
+        )
+        .bind(media_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        backfill_missing_primary_media(&db, &policy, &opts)
+            .await
+            .unwrap();
+        assert_eq!(primary(target).await.unwrap(), None);
+
+        let recheck_now = MediaBackfillOptions {
+            recheck_days: 0,
+            ..opts
+        };
+        backfill_missing_primary_media(&db, &policy, &recheck_now)
+            .await
+            .unwrap();
+        assert_eq!(primary(target).await.unwrap(), Some(media_id));
+
+        db.rollback().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn batch_recompute_picks_covers_and_keeps_existing_primaries() {
+        let db = RollbackDb::connect().await;
+        let pool = &db.pool;
+
+        let platform_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.platforms (code, name)
+             VALUES ('batch-primary-test', 'Batch Primary Test')
+             ON CONFLICT (name) DO UPDATE SET code = EXCLUDED.code RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let mut games = Vec::new();
+        for slug in ["batch-primary-covered", "batch-primary-videos-only"] {
+            let vg_id: i64 = sqlx::query_scalar(
+                "WITH p AS (
+                   INSERT INTO public.products (slug, name) VALUES ($1, $1) RETURNING id
+                 ), t AS (
+                   INSERT INTO public.video_game_titles (product_id, title)
+                   SELECT id, $1 FROM p RETURNING id
+                 )
+                 INSERT INTO public.video_games (title_id, platform_id)
+                 SELECT id, $2 FROM t RETURNING id",
+            )
+            .bind(slug)
+            .bind(platform_id)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            games.push(vg_id);
+        }
+        let (covered, videos_only) = (games[0], games[1]);
+        let mut media = Vec::new();
+        for (vg_id, url, media_type, role) in [
+            (
+                covered,
+                "https://cdn.example.com/batch-primary/cover.jpg",
+                "image",
+                "cover",
+            ),
+            (
+                covered,
+                "https://cdn.example.com/batch-primary/a.mp4",
+                "video",
+                "trailer",
+            ),
+            (
+                videos_only,
+                "https://cdn.example.com/batch-primary/b.mp4",
+                "video",
+                "trailer",
+            ),
+        ] {
+            let id: i64 = sqlx::query_scalar(
+                "INSERT INTO public.canonical_media (url, url_hash, metadata)
+                 VALUES ($2, md5($2),
+                         jsonb_build_object('video_game_id', $1::text, 'source', 'steam',
+                                            'media_type', $3::text, 'role', $4::text))
+                 RETURNING id",
+            )
+            .bind(vg_id)
+            .bind(url)
+            .bind(media_type)
+            .bind(role)
+            .fetch_one(pool)
+            .await
+            .unwrap();
+            media.push(id);
+        }
+        // The videos-only game already has a primary (e.g. from the backfill).
+        sqlx::query("UPDATE public.video_games SET primary_media_id = $2 WHERE id = $1")
+            .bind(videos_only)
+            .bind(media[0])
+            .execute(pool)
+            .await
+            .unwrap();
+
+        let picked = recompute_primary_media_batch(&db, &games, &PrimaryMediaPolicy::default())
+            .await
+            .unwrap();
+        assert_eq!(picked, HashMap::from([(covered, media[0])]));
+        let primaries: Vec<Option<i64>> = sqlx::query_scalar(
+            "SELECT primary_media_id FROM public.video_games WHERE id = ANY($1) ORDER BY id",
+        )
+        .bind(&games)
+        .fetch_all(pool)
+        .await
+        .unwrap();
+        assert_eq!(primaries, vec![Some(media[0]), Some(media[0])]);
+
+        db.rollback().await;
+    }
+}
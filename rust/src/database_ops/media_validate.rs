@@ -0,0 +1,268 @@
+//! Optional reachability check for newly ingested media URLs.
+//!
+//! With `MEDIA_VALIDATE=1`, every media URL not yet in canonical_media gets a HEAD
+//! request before it is stored; only 2xx responses with an image/video content-type are
+//! kept. Definitive answers are cached per process so re-ingesting the same catalogue
+//! doesn't re-probe CDNs; timeouts, connection errors and 5xx are retried next time.
+
+use futures::{stream, StreamExt};
+use reqwest::{header, Client};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tracing::debug;
+
+/// Cache entries kept before the map is cleared; bounds memory on long runs.
+const CACHE_CAPACITY: usize = 100_000;
+
+/// Outcome of one HEAD probe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Probe {
+    /// 2xx with an image/video content-type.
+    Valid,
+    /// 404/410, or 2xx with another content-type.
+    Invalid,
+    /// Timeout, connection error or any other status; not cached.
+    Transient,
+}
+
+static SKIPPED: AtomicU64 = AtomicU64::new(0);
+
+pub struct MediaValidator {
+    client: Client,
+    concurrency: usize,
+    cache: Mutex<HashMap<String, bool>>,
+}
+
+impl MediaValidator {
+    pub fn new(concurrency: usize, timeout: Duration) -> Self {
+        let client = Client::builder()
+            .timeout(timeout)
+            .redirect(reqwest::redirect::Policy::limited(5))
+            .build()
+            .expect("reqwest client");
+        Self {
+            client,
+            concurrency: concurrency.max(1),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Shared validator when `MEDIA_VALIDATE` is on, configured by
+    /// `MEDIA_VALIDATE_CONCURRENCY` (default 8) and `MEDIA_VALIDATE_TIMEOUT_SECS` (default 5).
+    pub fn from_env() -> Option<&'static Self> {
+        static VALIDATOR: OnceLock<Option<MediaValidator>> = OnceLock::new();
+        VALIDATOR
+            .get_or_init(|| {
+                crate::util::env::env_flag("MEDIA_VALIDATE", false).then(|| {
+                    Self::new(
+                        crate::util::env::env_parse("MEDIA_VALIDATE_CONCURRENCY", 8usize),
+                        Duration::from_secs(crate::util::env::env_parse(
+                            "MEDIA_VALIDATE_TIMEOUT_SECS",
+                            5u64,
+                        )),
+                    )
+                })
+            })
+            .as_ref()
+    }
+
+    /// The subset of `urls` that answered HEAD with 2xx and a media content-type.
+    pub async fn valid_urls(&self, urls: &[String]) -> HashSet<String> {
+        let mut valid = HashSet::new();
+        let mut pending = Vec::new();
+        {
+            let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            for url in urls {
+                match cache.get(url) {
+                    Some(true) => {
+                        valid.insert(url.clone());
+                    }
+                    Some(false) => {}
+                    None => pending.push(url.clone()),
+                }
+            }
+        }
+
+        let checked: Vec<(String, Probe)> = stream::iter(pending)
+            .map(|url| async move {
+                let probe = self.head(&url).await;
+                (url, probe)
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if cache.len() + checked.len() > CACHE_CAPACITY {
+            cache.clear();
+        }
+        for (url, probe) in checked {
+            match probe {
+                Probe::Valid => {
+                    valid.insert(url.clone());
+                    cache.insert(url, true);
+                }
+                Probe::Invalid => {
+                    cache.insert(url, false);
+                }
+                Probe::Transient => {}
+            }
+        }
+        drop(cache);
+
+        let skipped = urls.iter().filter(|u| !valid.contains(*u)).count();
+        if skipped > 0 {
+            SKIPPED.fetch_add(skipped as u64, Ordering::Relaxed);
+        }
+        valid
+    }
+
+    async fn head(&self, url: &str) -> Probe {
+        match self.client.head(url).send().await {
+            Ok(resp) => {
+                let content_type = resp
+                    .headers()
+                    .get(header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok());
+                let probe = classify(resp.status().as_u16(), content_type);
+                if probe != Probe::Valid {
+                    debug!(
+                        url,
+                        status = resp.status().as_u16(),
+                        content_type,
+                        ?probe,
+                        "media_validate: rejected"
+                    );
+                }
+                probe
+            }
+            Err(e) => {
+                debug!(url, error = %e, "media_validate: request failed");
+                Probe::Transient
+            }
+        }
+    }
+}
+
+/// Media URLs dropped by validation since process start.
+pub fn skipped_count() -> u64 {
+    SKIPPED.load(Ordering::Relaxed)
+}
+
+fn classify(status: u16, content_type: Option<&str>) -> Probe {
+    let media = content_type
+        .map(|ct| ct.trim().to_ascii_lowercase())
+        .is_some_and(|ct| ct.starts_with("image/") || ct.starts_with("video/"));
+    match status {
+        200..=299 if media => Probe::Valid,
+        200..=299 | 404 | 410 => Probe::Invalid,
+        _ => Probe::Transient,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal HTTP server: `/missing*` answers 404, `/page*` 200 text/html, `/flaky*` 503
+    /// on its first request, anything else 200 image/png. Returns the base URL and a
+    /// request counter.
+    async fn mock_cdn() -> (String, std::sync::Arc<AtomicU64>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let hits = std::sync::Arc::new(AtomicU64::new(0));
+        let counter = hits.clone();
+        let flaky_hits = std::sync::Arc::new(AtomicU64::new(0));
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+                counter.fetch_add(1, Ordering::Relaxed);
+                let flaky_hits = flaky_hits.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let n = socket.read(&mut buf).await.unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buf[..n]);
+                    let path = request.split_whitespace().nth(1).unwrap_or("/");
+                    let (status, content_type) = if path.starts_with("/missing") {
+                        ("404 Not Found", "text/plain")
+                    } else if path.starts_with("/page") {
+                        ("200 OK", "text/html")
+                    } else if path.starts_with("/flaky")
+                        && flaky_hits.fetch_add(1, Ordering::Relaxed) == 0
+                    {
+                        ("503 Service Unavailable", "text/plain")
+                    } else {
+                        ("200 OK", "image/png")
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\ncontent-type: {content_type}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (base, hits)
+    }
+
+    #[test]
+    fn acceptance_requires_2xx_and_media_type() {
+        assert_eq!(classify(200, Some("image/jpeg")), Probe::Valid);
+        assert_eq!(classify(206, Some("Video/MP4")), Probe::Valid);
+        assert_eq!(classify(404, Some("image/png")), Probe::Invalid);
+        assert_eq!(classify(410, None), Probe::Invalid);
+        assert_eq!(
+            classify(200, Some("text/html; charset=utf-8")),
+            Probe::Invalid
+        );
+        assert_eq!(classify(200, None), Probe::Invalid);
+        assert_eq!(classify(503, Some("image/png")), Probe::Transient);
+        assert_eq!(classify(429, None), Probe::Transient);
+    }
+
+    #[tokio::test]
+    async fn missing_and_non_media_urls_are_skipped_and_cached() {
+        let (base, hits) = mock_cdn().await;
+        let validator = MediaValidator::new(4, Duration::from_secs(5));
+        let urls = vec![
+            format!("{base}/cover.png"),
+            format!("{base}/missing.png"),
+            format!("{base}/page.html"),
+        ];
+
+        let skipped_before = skipped_count();
+        let valid = validator.valid_urls(&urls).await;
+        assert_eq!(valid, HashSet::from([urls[0].clone()]));
+        assert!(skipped_count() >= skipped_before + 2);
+        assert_eq!(hits.load(Ordering::Relaxed), 3);
+
+        // Second pass is served from the cache.
+        let again = validator.valid_urls(&urls).await;
+        assert_eq!(again, valid);
+        assert_eq!(hits.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried_on_the_next_pass() {
+        let (base, hits) = mock_cdn().await;
+        let validator = MediaValidator::new(4, Duration::from_secs(5));
+        let urls = vec![format!("{base}/flaky.png")];
+
+        // 503 first: skipped this time but not remembered.
+        assert!(validator.valid_urls(&urls).await.is_empty());
+        assert_eq!(hits.load(Ordering::Relaxed), 1);
+
+        let valid = validator.valid_urls(&urls).await;
+        assert_eq!(valid, HashSet::from([urls[0].clone()]));
+        assert_eq!(hits.load(Ordering::Relaxed), 2);
+
+        // The 200 is cached.
+        assert_eq!(validator.valid_urls(&urls).await, valid);
+        assert_eq!(hits.load(Ordering::Relaxed), 2);
+    }
+}
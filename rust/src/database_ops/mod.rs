@@ -1,22 +1,36 @@
 pub mod alerts;
 pub mod backfill;
+pub mod csv_prices;
 pub mod db;
 pub mod ensure_video_game_enhanced;
 pub mod ensure_video_game_for_product_enhanced;
 pub mod exchange;
+pub mod external_ratings;
 pub mod giantbomb;
 pub mod igdb;
 pub mod ingest_providers;
 pub mod itad;
+pub mod leader;
+pub mod maintenance;
+pub mod media_dedup;
 pub mod media_filter;
 pub mod media_map;
+pub mod media_primary;
+pub mod media_validate;
 pub mod nexarda;
+pub mod notify;
 pub mod platform_hardware;
+pub mod platforms_dedupe;
 pub mod playstation;
+pub mod provider_loop;
 pub mod rawg;
 pub mod schema_audit;
+pub mod schema_caps;
+pub mod schema_profile;
 pub mod search;
+pub mod staleness;
 pub mod steam;
+pub mod sync_watermark;
 pub mod tgdb;
 pub mod worker_manager;
 pub mod xbox;
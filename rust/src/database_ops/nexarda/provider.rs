@@ -1,3 +1,4 @@
+use crate::util::currency::minor_unit as currency_minor_unit;
 use anyhow::{anyhow, Result};
 use chrono::Utc;
 use reqwest::{header, Client};
@@ -26,15 +27,43 @@ pub struct NexardaProvider {
     dynamic_store_cache: Arc<Mutex<HashMap<String, StoreConfig>>>,
     /// Current call-scoped options cache used by dynamic store registration
     current_options: Arc<AsyncMutex<NexardaOptions>>, // shallow copy, not mutated except overrides read
+    /// Normalized store names allowed to auto-register (`NEXARDA_REGISTER_ALLOWLIST`);
+    /// `None` lets every store through.
+    register_allowlist: Option<HashSet<String>>,
+    /// Auto-registration decisions of the current `fetch_deals` run.
+    store_registrations: Arc<Mutex<Vec<StoreRegistration>>>,
     http: Client,
 }
 
+/// A store Nexarda reported that was not in the configured store map.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoreRegistration {
+    pub store_name: String,
+    pub normalized_name: String,
+    pub currency: String,
+    pub store_id: String,
+    pub region_code: String,
+    /// False when the allowlist held the store back for review.
+    pub registered: bool,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NexardaOptions {
     pub products: Vec<Product>,
     pub store_map: HashMap<String, HashMap<String, StoreConfig>>, // store_name_normalized -> currency -> config
     pub base_url: Option<String>,
     pub timeout: Option<u64>,
+    /// Retries after the first attempt of each price request. Network errors, timeouts, 429
+    /// and 5xx responses are retried. Defaults to `NEXARDA_MAX_RETRIES - 1`: that variable
+    /// has always counted total attempts (default 3), so it keeps its meaning.
+    pub max_retries: Option<u32>,
+    /// Base delay between retries, multiplied by the attempt number (default
+    /// `NEXARDA_BACKOFF_MS` or 200). A longer 429 `Retry-After` wins.
+    pub backoff_ms: Option<u64>,
+    /// Price requests per minute (default `NEXARDA_REQS_PER_MIN`); wins over `rps`.
+    pub reqs_per_min: Option<u32>,
+    /// Price requests per second (default `NEXARDA_RPS`). Unpaced when neither is set.
+    pub rps: Option<f32>,
     pub api_key: Option<String>,
     pub auto_register_stores: Option<bool>,
     pub default_regions: Vec<RegionDefinition>,
@@ -132,16 +161,64 @@ impl NexardaProvider {
             timeout_secs,
             dynamic_store_cache: Arc::new(Mutex::new(HashMap::new())),
             current_options: Arc::new(AsyncMutex::new(NexardaOptions::default())),
+            register_allowlist: crate::util::env::env_opt("NEXARDA_REGISTER_ALLOWLIST")
+                .map(|raw| parse_store_allowlist(&raw)),
+            store_registrations: Arc::new(Mutex::new(Vec::new())),
             http,
         })
     }
 
+    /// Only auto-register these stores (matched case-insensitively); others are recorded
+    /// as held back in [`Self::store_registrations`].
+    pub fn with_register_allowlist<I, S>(mut self, stores: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.register_allowlist = Some(
+            stores
+                .into_iter()
+                .map(|s| s.as_ref().trim().to_lowercase())
+                .collect(),
+        );
+        self
+    }
+
+    /// Stores registered or held back during the last `fetch_deals` run.
+    pub fn store_registrations(&self) -> Vec<StoreRegistration> {
+        self.store_registrations.lock().unwrap().clone()
+    }
+
+    fn record_store_registration(&self, registration: StoreRegistration) {
+        if registration.registered {
+            info!(
+                store = %registration.store_name,
+                currency = %registration.currency,
+                store_id = %registration.store_id,
+                "nexarda: auto-registered store"
+            );
+        } else {
+            warn!(
+                store = %registration.store_name,
+                currency = %registration.currency,
+                "nexarda: store not in NEXARDA_REGISTER_ALLOWLIST; held back for review"
+            );
+        }
+        let mut audit = self.store_registrations.lock().unwrap();
+        if !audit.iter().any(|r| {
+            r.normalized_name == registration.normalized_name && r.currency == registration.currency
+        }) {
+            audit.push(registration);
+        }
+    }
+
     pub async fn fetch_deals(&self, options: NexardaOptions) -> Result<DealsResponse> {
         // Save current options snapshot for dynamic store registration
         {
             let mut guard = self.current_options.lock().await;
             *guard = options.clone();
         }
+        self.store_registrations.lock().unwrap().clear();
 
         let products: Vec<Product> = options
             .products
@@ -172,7 +249,7 @@ impl NexardaProvider {
             .base_url
             .clone()
             .unwrap_or_else(|| self.base_url.clone());
-        let timeout = options.timeout.unwrap_or(self.timeout_secs);
+        let policy = RequestPolicy::from_options(&options, self.timeout_secs);
         let api_key = options.api_key.clone();
 
         let mut results: Vec<GameDeals> = Vec::new();
@@ -190,7 +267,7 @@ impl NexardaProvider {
                 let payload = self
                     .request_prices(
                         &base_url,
-                        timeout,
+                        &policy,
                         api_key.as_deref(),
                         &product.r#type,
                         &product.id,
@@ -232,6 +309,10 @@ impl NexardaProvider {
         meta.insert("provider".to_string(), json!("nexarda"));
         meta.insert("generated_at".to_string(), json!(Utc::now().to_rfc3339()));
         meta.insert("product_count".to_string(), json!(options.products.len()));
+        meta.insert(
+            "store_registrations".to_string(),
+            json!(self.store_registrations()),
+        );
 
         Ok(DealsResponse { results, meta })
     }
@@ -442,7 +523,20 @@ impl NexardaProvider {
         }
 
         let auto_register = options.auto_register_stores.unwrap_or(true);
-        if !normalized_name.is_empty() && auto_register {
+        let allowed = self
+            .register_allowlist
+            .as_ref()
+            .is_none_or(|allow| allow.contains(&normalized_name));
+        if !normalized_name.is_empty() && auto_register && !allowed {
+            self.record_store_registration(StoreRegistration {
+                store_name: store_name.to_string(),
+                normalized_name: normalized_name.clone(),
+                currency: currency_up.clone(),
+                store_id: region.store_id.clone(),
+                region_code: region.region_code.clone(),
+                registered: false,
+            });
+        } else if !normalized_name.is_empty() && auto_register {
             return self.register_dynamic_store(
                 store_name,
                 &normalized_name,
@@ -527,6 +621,14 @@ impl NexardaProvider {
             .lock()
             .unwrap()
             .insert(cache_key, cfg.clone());
+        self.record_store_registration(StoreRegistration {
+            store_name: store_name.to_string(),
+            normalized_name: normalized_name.to_string(),
+            currency: currency.to_string(),
+            store_id,
+            region_code,
+            registered: true,
+        });
 
         cfg
     }
@@ -534,7 +636,7 @@ impl NexardaProvider {
     async fn request_prices(
         &self,
         base_url: &str,
-        _timeout: u64,
+        policy: &RequestPolicy,
         api_key: Option<&str>,
         r#type: &str,
         id: &serde_json::Value,
@@ -571,42 +673,10 @@ impl NexardaProvider {
 
         let url = format!("{}/prices", base_url.trim_end_matches('/'));
 
-        // simple retry with env-configurable pacing and backoff
-        let mut attempt = 0;
-        let max_attempts: u32 = std::env::var("NEXARDA_MAX_RETRIES")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(3);
-        let backoff_ms_base: u64 = std::env::var("NEXARDA_BACKOFF_MS")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(200);
-        // pacing: prefer RPM over RPS
-        let pace_ms: Option<u64> = std::env::var("NEXARDA_REQS_PER_MIN")
-            .ok()
-            .and_then(|s| s.parse::<u32>().ok())
-            .and_then(|rpm| {
-                if rpm > 0 {
-                    Some(60_000u64 / (rpm as u64))
-                } else {
-                    None
-                }
-            })
-            .or_else(|| {
-                std::env::var("NEXARDA_RPS")
-                    .ok()
-                    .and_then(|s| s.parse::<f32>().ok())
-                    .and_then(|rps| {
-                        if rps > 0.0 {
-                            Some((1000.0 / rps) as u64)
-                        } else {
-                            None
-                        }
-                    })
-            });
+        let mut attempt: u32 = 0;
         loop {
             attempt += 1;
-            if let Some(ms) = pace_ms {
+            if let Some(ms) = policy.pace_ms {
                 sleep(Duration::from_millis(ms)).await;
             }
             let resp = self
@@ -614,9 +684,11 @@ impl NexardaProvider {
                 .get(&url)
                 .headers(headers.clone())
                 .query(&query)
+                .timeout(Duration::from_secs(policy.timeout_secs))
                 .send()
                 .await;
 
+            let mut sleep_ms = policy.backoff_ms.saturating_mul(attempt as u64);
             match resp {
                 Ok(r) if r.status().is_success() => {
                     let payload = r.json::<Value>().await?;
@@ -627,36 +699,50 @@ impl NexardaProvider {
                     }
                 }
                 Ok(r) => {
-                    if attempt >= max_attempts {
+                    let status = r.status();
+                    if !is_retryable_status(status) || attempt > policy.max_retries {
                         return Err(anyhow!(
                             "NEXARDA price request failed for ID [{}] ({}).",
                             id,
-                            r.status()
+                            status
                         ));
                     }
+                    if let Some(retry_after) = r
+                        .headers()
+                        .get(header::RETRY_AFTER)
+                        .and_then(|h| h.to_str().ok())
+                        .and_then(|s| s.trim().parse::<u64>().ok())
+                    {
+                        sleep_ms = sleep_ms.max(retry_after.saturating_mul(1000));
+                    }
+                    warn!(id = %id, %status, attempt, sleep_ms, "nexarda: retrying price request");
                 }
                 Err(e) => {
-                    if attempt >= max_attempts {
+                    if attempt > policy.max_retries {
                         return Err(anyhow!(
                             "NEXARDA price request error for ID [{}]: {}",
                             id,
                             e
                         ));
                     }
+                    warn!(id = %id, error = %e, attempt, sleep_ms, "nexarda: retrying price request");
                 }
             }
-            // linear backoff between attempts (can be adjusted to exponential later)
-            let sleep_ms = backoff_ms_base.saturating_mul(attempt as u64);
+            // linear backoff between attempts
             sleep(Duration::from_millis(sleep_ms)).await;
         }
     }
 
     /// Ingest fetched deals directly into DB using existing ensure_* + ingest_prices helpers.
     /// This keeps provider self-sufficient while allowing orchestration loop to call one method.
+    ///
+    /// With `NEXARDA_PRODUCTS_FROM_DB=1` the configured products are topped up with the
+    /// stalest Nexarda-linked products already in the DB (see [`Self::stale_products`]),
+    /// up to `NEXARDA_DB_BATCH_SIZE` (default 100) in total.
     pub async fn ingest_to_db(
         &self,
         db: &crate::database_ops::db::Db,
-        options: NexardaOptions,
+        mut options: NexardaOptions,
     ) -> Result<usize> {
         use crate::database_ops::db::PriceRow;
         use crate::database_ops::ingest_providers::{
@@ -665,6 +751,7 @@ impl NexardaProvider {
             ingest_prices, link_provider_offer, update_video_game_display_title_and_region,
             PostIngestSummary, ProviderEntityCache,
         };
+        use crate::normalization::display_title::TitleCandidate;
         use chrono::Utc;
 
         let provider_id =
@@ -672,7 +759,23 @@ impl NexardaProvider {
         let mut entity_cache = ProviderEntityCache::new(db.clone());
         let mut post_summary = PostIngestSummary::default();
 
+        if crate::util::env::env_flag("NEXARDA_PRODUCTS_FROM_DB", false) {
+            let batch = crate::util::env::env_parse("NEXARDA_DB_BATCH_SIZE", 100i64).max(1);
+            let room = batch - options.products.len() as i64;
+            if room > 0 {
+                let stale = Self::stale_products(db, room).await?;
+                info!(
+                    selected = stale.len(),
+                    "nexarda: products selected from DB by staleness"
+                );
+                merge_products(&mut options.products, stale);
+            }
+        }
+
         let resp = self.fetch_deals(options.clone()).await?; // cloning for potential reuse
+        if let Err(e) = record_store_registrations(db, &self.store_registrations()).await {
+            warn!(error = %e, "nexarda: failed to write store registration audit");
+        }
         if resp.results.is_empty() {
             info!(
                 product_count = options.products.len(),
@@ -711,11 +814,7 @@ impl NexardaProvider {
             for deal in gd.deals.iter() {
                 // ensure currency / country / jurisdiction
                 let cur_code = deal.currency.to_uppercase();
-                let minor_unit = match cur_code.as_str() {
-                    "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "HUF" => 0,
-                    "BHD" | "IQD" | "KWD" | "JOD" | "OMR" | "TND" => 3,
-                    _ => 2,
-                };
+                let minor_unit = currency_minor_unit(&cur_code);
                 let currency_id = ensure_currency(db, &cur_code, &cur_code, minor_unit).await?;
                 let country_id = ensure_country(
                     db,
@@ -741,13 +840,15 @@ impl NexardaProvider {
                     .ensure_offer_jurisdiction(offer_id, juris_id, currency_id)
                     .await?;
                 // provider item + linking (deal_id acts as external id)
+                let mut item_meta = serde_json::to_value(&deal.extras)?;
+                item_meta["nexarda_product"] = json!({
+                    "title": gd.game.title,
+                    "slug": gd.game.slug,
+                    "platform": gd.game.platform,
+                    "category": gd.game.category,
+                });
                 let video_game_source_id = entity_cache
-                    .ensure_provider_item(
-                        provider_id,
-                        &deal.deal_id,
-                        Some(serde_json::to_value(&deal.extras)?),
-                        true,
-                    )
+                    .ensure_provider_item(provider_id, &deal.deal_id, Some(item_meta), true)
                     .await?;
                 link_provider_offer(db, video_game_source_id, offer_id, Some(0.9)).await?;
                 last_video_game_source_id = Some(video_game_source_id);
@@ -800,9 +901,11 @@ impl NexardaProvider {
                 .iter()
                 .map(|d| d.region_code.to_ascii_uppercase())
                 .collect();
+            // NEXARDA lists games under their English titles.
+            let candidate = TitleCandidate::new(&gd.game.title, "nexarda", Some("en"));
             for rc in regions.iter() {
-                let _ =
-                    update_video_game_display_title_and_region(db, vg_id, &gd.game.title, rc).await;
+                let _ = update_video_game_display_title_and_region(db, vg_id, &candidate, Some(rc))
+                    .await;
             }
             // Media ingestion (cover/banner from metadata)
             if let Some(pid) = last_video_game_source_id {
@@ -861,6 +964,59 @@ impl NexardaProvider {
         Ok(total_prices)
     }
 
+    /// Up to `limit` products already ingested from Nexarda, least recently refreshed first.
+    /// Products are recovered from their deals' provider item ids
+    /// (`nexarda:<type>:<id>:<currency>:<store>`); a product counts as refreshed when any
+    /// of its deals was.
+    pub async fn stale_products(
+        db: &crate::database_ops::db::Db,
+        limit: i64,
+    ) -> Result<Vec<Product>> {
+        use sqlx::Row;
+
+        let provider_id = crate::database_ops::ingest_providers::ensure_provider(
+            db,
+            "nexarda",
+            "pricing_catalog",
+            Some(NEXARDA_PROVIDER_KEY),
+        )
+        .await?;
+        let rows = sqlx::query(
+            "SELECT split_part(pi.external_id, ':', 2) AS product_type,
+                    split_part(pi.external_id, ':', 3) AS product_id,
+                    max(pi.metadata->'nexarda_product'->>'title') AS title,
+                    max(pi.metadata->'nexarda_product'->>'slug') AS slug,
+                    max(pi.metadata->'nexarda_product'->>'platform') AS platform,
+                    max(pi.metadata->'nexarda_product'->>'category') AS category,
+                    max(COALESCE(pi.updated_at, pi.created_at)) AS refreshed_at
+             FROM public.provider_items pi
+             WHERE pi.provider_id = $1 AND pi.external_id LIKE 'nexarda:%:%:%'
+             GROUP BY 1, 2
+             ORDER BY refreshed_at ASC NULLS FIRST, 1, 2
+             LIMIT $2",
+        )
+        .persistent(false)
+        .bind(provider_id)
+        .bind(limit.max(0))
+        .fetch_all(&db.pool)
+        .await?;
+
+        rows.iter()
+            .map(|r| -> Result<Product> {
+                let raw_id: String = r.try_get("product_id")?;
+                Ok(Product {
+                    id: product_id_value(&raw_id),
+                    r#type: r.try_get("product_type")?,
+                    title: r.try_get("title")?,
+                    slug: r.try_get("slug")?,
+                    platform: r.try_get("platform")?,
+                    category: r.try_get("category")?,
+                    regions: Vec::new(),
+                })
+            })
+            .collect()
+    }
+
     /// Ingest a pre-fetched Nexarda catalogue JSON file (one-time seed or batch).
     /// Env fallbacks:
     /// - NEXARDA_CATALOGUE_PATH (preferred)
@@ -1052,7 +1208,7 @@ impl NexardaProvider {
                 if price_f <= 0.0 {
                     continue;
                 }
-                let minor_unit = currency_minor_unit_cat(&ccy);
+                let minor_unit = currency_minor_unit(&ccy);
                 let currency_id = ensure_currency(db, &ccy, &ccy, minor_unit).await?;
                 let cc2 = currency_to_country_code_cat(&ccy);
                 let country_id = ensure_country(db, &cc2, &cc2, currency_id).await?;
@@ -1111,6 +1267,134 @@ struct RegionConfig {
     store_id: String,
 }
 
+/// Product a deal id (`nexarda:<type>:<id>:<currency>:<store>`) was fetched for, as used
+/// by targeted refresh jobs.
+pub fn product_from_deal_id(deal_id: &str) -> Option<Product> {
+    let mut parts = deal_id.split(':');
+    if parts.next()? != "nexarda" {
+        return None;
+    }
+    let r#type = parts.next().filter(|t| !t.is_empty())?.to_string();
+    let id = parts.next().filter(|id| !id.is_empty())?;
+    Some(Product {
+        id: product_id_value(id),
+        r#type,
+        ..Default::default()
+    })
+}
+
+/// Product id as it was sent to Nexarda: deal ids embed it JSON-encoded, so string ids
+/// keep their quotes and numeric ids are bare.
+fn product_id_value(raw: &str) -> Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| json!(raw))
+}
+
+/// Append `extra` products not already in `products` (same type and id).
+fn merge_products(products: &mut Vec<Product>, extra: Vec<Product>) {
+    let mut seen: HashSet<(String, String)> = products
+        .iter()
+        .map(|p| (p.r#type.clone(), p.id.to_string()))
+        .collect();
+    products.extend(
+        extra
+            .into_iter()
+            .filter(|p| seen.insert((p.r#type.clone(), p.id.to_string()))),
+    );
+}
+
+/// Comma-separated store names, normalized like store-map keys.
+fn parse_store_allowlist(raw: &str) -> HashSet<String> {
+    raw.split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Append this run's registration decisions to `nexarda_store_registrations` (migration
+/// 0563) under one run id. Skipped when the table is absent.
+async fn record_store_registrations(
+    db: &crate::database_ops::db::Db,
+    registrations: &[StoreRegistration],
+) -> Result<()> {
+    if registrations.is_empty() {
+        return Ok(());
+    }
+    let present: bool =
+        sqlx::query_scalar("SELECT to_regclass('public.nexarda_store_registrations') IS NOT NULL")
+            .persistent(false)
+            .fetch_one(&db.pool)
+            .await?;
+    if !present {
+        return Ok(());
+    }
+    let run_id = uuid::Uuid::new_v4();
+    let mut qb = sqlx::QueryBuilder::new(
+        "INSERT INTO public.nexarda_store_registrations
+           (run_id, store_name, normalized_name, currency, store_id, region_code, registered) ",
+    );
+    qb.push_values(registrations, |mut b, r| {
+        b.push_bind(run_id)
+            .push_bind(&r.store_name)
+            .push_bind(&r.normalized_name)
+            .push_bind(&r.currency)
+            .push_bind(&r.store_id)
+            .push_bind(&r.region_code)
+            .push_bind(r.registered);
+    });
+    qb.build().persistent(false).execute(&db.pool).await?;
+    Ok(())
+}
+
+/// Timeout and retry settings for Nexarda HTTP calls, from [`NexardaOptions`] with env
+/// fallbacks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RequestPolicy {
+    timeout_secs: u64,
+    max_retries: u32,
+    backoff_ms: u64,
+    /// Delay before each price request.
+    pace_ms: Option<u64>,
+}
+
+impl RequestPolicy {
+    fn from_options(options: &NexardaOptions, default_timeout_secs: u64) -> Self {
+        Self {
+            timeout_secs: options.timeout.unwrap_or(default_timeout_secs),
+            max_retries: options.max_retries.unwrap_or_else(|| {
+                // NEXARDA_MAX_RETRIES counts attempts, first one included.
+                crate::util::env::env_parse::<u32>("NEXARDA_MAX_RETRIES", 3).saturating_sub(1)
+            }),
+            backoff_ms: options
+                .backoff_ms
+                .unwrap_or_else(|| crate::util::env::env_parse("NEXARDA_BACKOFF_MS", 200)),
+            pace_ms: pace_ms(
+                options
+                    .reqs_per_min
+                    .or_else(|| crate::util::env::env_parse_opt("NEXARDA_REQS_PER_MIN")),
+                options
+                    .rps
+                    .or_else(|| crate::util::env::env_parse_opt("NEXARDA_RPS")),
+            ),
+        }
+    }
+}
+
+/// Delay between requests for a rate; per-minute wins over per-second, zero means unset.
+fn pace_ms(reqs_per_min: Option<u32>, rps: Option<f32>) -> Option<u64> {
+    match (reqs_per_min.filter(|&n| n > 0), rps.filter(|&r| r > 0.0)) {
+        (Some(rpm), _) => Some(60_000 / rpm as u64),
+        (None, Some(rps)) => Some((1000.0 / rps) as u64),
+        (None, None) => None,
+    }
+}
+
+/// Throttling, request timeouts and server errors are transient; other failures are not.
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        || status.is_server_error()
+}
+
 fn normalize_store_map(
     input: &HashMap<String, HashMap<String, StoreConfig>>,
 ) -> HashMap<String, HashMap<String, StoreConfig>> {
@@ -1208,14 +1492,6 @@ fn extract_price_f64_cat(v: &serde_json::Value) -> Option<f64> {
     None
 }
 
-fn currency_minor_unit_cat(code: &str) -> i16 {
-    match code {
-        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "HUF" => 0,
-        "BHD" | "IQD" | "KWD" | "JOD" | "OMR" | "TND" => 3,
-        _ => 2,
-    }
-}
-
 fn currency_to_country_code_cat(code: &str) -> String {
     (match code {
         "USD" => "US",
@@ -1254,6 +1530,179 @@ fn currency_to_country_code_cat(code: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal Nexarda stand-in answering the first `failures` requests with `status`, then
+    /// a successful prices payload. Returns the base URL and a request counter.
+    async fn flaky_nexarda(status: &'static str, failures: u32) -> (String, Arc<AtomicU32>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicU32::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 1024];
+                    let _ = socket.read(&mut buf).await;
+                    let (status, body) = if n < failures {
+                        (status, "{}")
+                    } else {
+                        (
+                            "200 OK",
+                            r#"{"success":true,"info":{},"prices":{"list":[]}}"#,
+                        )
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nretry-after: 0\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+        (base, hits)
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried() {
+        let provider = NexardaProvider::new(None, Some(5)).unwrap();
+        let policy = RequestPolicy {
+            timeout_secs: 5,
+            max_retries: 2,
+            backoff_ms: 1,
+            pace_ms: None,
+        };
+
+        let (base, hits) = flaky_nexarda("429 Too Many Requests", 2).await;
+        let payload = provider
+            .request_prices(&base, &policy, None, "game", &json!(1), "USD")
+            .await
+            .unwrap();
+        assert_eq!(payload["success"], json!(true));
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+        // Retries are bounded...
+        let (base, hits) = flaky_nexarda("503 Service Unavailable", 5).await;
+        assert!(provider
+            .request_prices(&base, &policy, None, "game", &json!(1), "USD")
+            .await
+            .is_err());
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+
+        // ...and permanent failures are not retried at all.
+        let (base, hits) = flaky_nexarda("404 Not Found", 1).await;
+        assert!(provider
+            .request_prices(&base, &policy, None, "game", &json!(1), "USD")
+            .await
+            .is_err());
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn allowlist_holds_back_unknown_stores() {
+        let provider = NexardaProvider::new(None, None)
+            .unwrap()
+            .with_register_allowlist(parse_store_allowlist(" Steam ,GOG"));
+        let region = RegionConfig {
+            currency: "USD".into(),
+            region_code: "US".into(),
+            store_id: "nexarda_usd".into(),
+        };
+        let options = NexardaOptions {
+            auto_register_stores: Some(true),
+            ..Default::default()
+        };
+        let mut store_map = HashMap::new();
+
+        let cfg = provider.resolve_store_config("Stearn", &region, &mut store_map, "usd", &options);
+        assert_eq!(
+            cfg.store_id, "nexarda_usd",
+            "unknown store falls back to the region"
+        );
+        let cfg = provider.resolve_store_config("Steam", &region, &mut store_map, "usd", &options);
+        assert_eq!(cfg.store_id, "nexarda_steam_usd");
+
+        assert!(!store_map.contains_key("stearn"));
+        assert!(store_map.contains_key("steam"));
+        let audit = provider.store_registrations();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[0].store_name, "Stearn");
+        assert!(!audit[0].registered);
+        assert_eq!(audit[1].normalized_name, "steam");
+        assert!(audit[1].registered);
+    }
+
+    #[test]
+    fn product_ids_round_trip_through_deal_ids() {
+        assert_eq!(product_id_value("1234"), json!(1234));
+        assert_eq!(product_id_value("\"abc\""), json!("abc"));
+        assert_eq!(product_id_value("abc"), json!("abc"));
+        let product = product_from_deal_id("nexarda:game:1234:usd:steam").unwrap();
+        assert_eq!((product.r#type.as_str(), product.id), ("game", json!(1234)));
+        assert!(product_from_deal_id("steam:123").is_none());
+
+        let mut products = vec![Product {
+            id: json!(1),
+            r#type: "game".into(),
+            ..Default::default()
+        }];
+        merge_products(
+            &mut products,
+            vec![
+                Product {
+                    id: json!(1),
+                    r#type: "game".into(),
+                    ..Default::default()
+                },
+                Product {
+                    id: json!(2),
+                    r#type: "game".into(),
+                    ..Default::default()
+                },
+            ],
+        );
+        assert_eq!(products.len(), 2);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn stale_linked_products_are_selected_first() {
+        let db = crate::test_support::RollbackDb::connect().await;
+        let provider_id = crate::database_ops::ingest_providers::ensure_provider(
+            &db,
+            "nexarda",
+            "pricing_catalog",
+            Some(NEXARDA_PROVIDER_KEY),
+        )
+        .await
+        .unwrap();
+        // Fresh everywhere else so the seeded rows are the stalest.
+        sqlx::query(
+            "INSERT INTO public.provider_items (provider_id, external_id, metadata, updated_at)
+             VALUES
+               ($1, 'nexarda:game:990001:usd:steam', '{\"nexarda_product\":{\"title\":\"Old\"}}', '2000-01-01'),
+               ($1, 'nexarda:game:990001:eur:steam', '{}', '2000-01-03'),
+               ($1, 'nexarda:game:\"stale-b\":usd:gog', '{}', '2000-01-02')",
+        )
+        .bind(provider_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        let selected = NexardaProvider::stale_products(&db, 2).await.unwrap();
+        db.rollback().await;
+
+        let ids: Vec<Value> = selected.iter().map(|p| p.id.clone()).collect();
+        assert_eq!(ids, vec![json!("stale-b"), json!(990001)]);
+        assert_eq!(selected[1].title.as_deref(), Some("Old"));
+        assert_eq!(selected[1].r#type, "game");
+    }
 
     #[test]
     fn slugify_basic() {
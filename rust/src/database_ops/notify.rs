@@ -0,0 +1,150 @@
+//! Coalescing of `pg_notify` events emitted while ingesting.
+//!
+//! A large seed run writes hundreds of pages and used to notify once per page, which can
+//! swamp LISTEN consumers. `NOTIFY_COALESCE` picks how page events are folded:
+//!
+//! - `off` / `immediate` / `0` (default): one notification per page, as before;
+//! - `run` / `on` / `1`: a single summary notification at the end of the run;
+//! - `N` (an integer above 1): one summary per `N` pages, plus the remainder at the end.
+//!
+//! Payloads keep the `{"count": n}` shape; coalesced ones add `"pages"`.
+
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use tracing::warn;
+
+use crate::database_ops::db::Db;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NotifyCoalesce {
+    #[default]
+    Immediate,
+    PerRun,
+    EveryPages(u32),
+}
+
+impl NotifyCoalesce {
+    pub fn parse(raw: &str) -> Self {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "0" | "off" | "false" | "no" | "immediate" => Self::Immediate,
+            "1" | "on" | "true" | "yes" | "run" => Self::PerRun,
+            other => match other.parse::<u32>() {
+                Ok(pages) => Self::EveryPages(pages),
+                Err(_) => {
+                    warn!(
+                        value = raw,
+                        "NOTIFY_COALESCE: unrecognized value; notifying immediately"
+                    );
+                    Self::Immediate
+                }
+            },
+        }
+    }
+
+    pub fn from_env() -> Self {
+        crate::util::env::env_opt("NOTIFY_COALESCE")
+            .map(|raw| Self::parse(&raw))
+            .unwrap_or_default()
+    }
+}
+
+/// Buffers page events for one channel during a run. Shared between concurrently seeded
+/// locales; the lock is only held while counting, never while notifying.
+#[derive(Debug)]
+pub struct NotifyBatcher {
+    channel: &'static str,
+    mode: NotifyCoalesce,
+    pending: Mutex<(u64, u32)>,
+}
+
+impl NotifyBatcher {
+    pub fn new(channel: &'static str, mode: NotifyCoalesce) -> Self {
+        Self {
+            channel,
+            mode,
+            pending: Mutex::new((0, 0)),
+        }
+    }
+
+    /// Count one page of `count` written rows; returns the payload due now, if any.
+    pub fn push(&self, count: u64) -> Option<Value> {
+        let every = match self.mode {
+            NotifyCoalesce::Immediate => return Some(json!({ "count": count })),
+            NotifyCoalesce::PerRun => None,
+            NotifyCoalesce::EveryPages(pages) => Some(pages),
+        };
+        let mut pending = self.pending.lock().unwrap_or_else(|p| p.into_inner());
+        pending.0 += count;
+        pending.1 += 1;
+        if every.is_some_and(|pages| pending.1 >= pages) {
+            Some(Self::summary(std::mem::take(&mut *pending)))
+        } else {
+            None
+        }
+    }
+
+    /// The summary of pages not yet notified; call once the run is done.
+    pub fn take(&self) -> Option<Value> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|p| p.into_inner()));
+        (pending.1 > 0).then(|| Self::summary(pending))
+    }
+
+    /// [`push`](Self::push) and send whatever is due. Best-effort: failures are logged.
+    pub async fn record(&self, db: &Db, count: u64) {
+        if let Some(payload) = self.push(count) {
+            self.send(db, &payload).await;
+        }
+    }
+
+    /// [`take`](Self::take) and send the remaining summary.
+    pub async fn flush(&self, db: &Db) {
+        if let Some(payload) = self.take() {
+            self.send(db, &payload).await;
+        }
+    }
+
+    fn summary((count, pages): (u64, u32)) -> Value {
+        json!({ "count": count, "pages": pages })
+    }
+
+    async fn send(&self, db: &Db, payload: &Value) {
+        if let Err(e) = sqlx::query("SELECT pg_notify($1, $2)")
+            .persistent(false)
+            .bind(self.channel)
+            .bind(payload.to_string())
+            .execute(&db.pool)
+            .await
+        {
+            warn!(channel = self.channel, error = %e, "pg_notify failed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesced_pages_emit_one_summary() {
+        let per_run = NotifyBatcher::new("ratings_upsert", NotifyCoalesce::parse("run"));
+        let due: Vec<Value> = (1..=5).filter_map(|n| per_run.push(n)).collect();
+        assert!(due.is_empty());
+        assert_eq!(per_run.take(), Some(json!({ "count": 15, "pages": 5 })));
+        assert_eq!(per_run.take(), None);
+
+        let every_two = NotifyBatcher::new("ratings_upsert", NotifyCoalesce::parse("2"));
+        let due: Vec<Value> = (1..=5).filter_map(|n| every_two.push(n)).collect();
+        assert_eq!(
+            due,
+            vec![
+                json!({ "count": 3, "pages": 2 }),
+                json!({ "count": 7, "pages": 2 })
+            ]
+        );
+        assert_eq!(every_two.take(), Some(json!({ "count": 5, "pages": 1 })));
+
+        let immediate = NotifyBatcher::new("ratings_upsert", NotifyCoalesce::default());
+        assert_eq!(immediate.push(4), Some(json!({ "count": 4 })));
+        assert_eq!(immediate.take(), None);
+    }
+}
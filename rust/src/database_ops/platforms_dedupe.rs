@@ -0,0 +1,570 @@
+//! Merging of duplicate `platforms` rows.
+//!
+//! Platforms whose code (or name, when there is no code) normalizes to the same slug are
+//! one platform: "PS5", "ps5" and "PS-5" all become `ps5`. Each group keeps the row with
+//! the most `video_games`, or the lowest id on a tie. Every foreign key pointing at a
+//! loser is repointed to the kept row, the merge is recorded in `platforms_dedupe_map`
+//! (when present, for `platforms_restore`), and the losers are deleted. A dry run only
+//! reports the merges it would make.
+//!
+//! Repointing must not break the unique keys of the referencing tables. A title listed on
+//! both duplicates has one `video_games` row per platform; these are folded into the row on
+//! the kept platform first, with their own references moved across. A referencing row that
+//! would still collide with another on a plain unique key (`game_consoles.platform_id`,
+//! the `platform_hardware_map` key) duplicates that row and is dropped.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::Row;
+use tracing::{info, warn};
+
+use crate::database_ops::db::Db;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlatformRow {
+    pub id: i64,
+    pub name: String,
+    pub code: Option<String>,
+    /// `video_games` rows pointing at this platform.
+    pub video_games: i64,
+}
+
+/// One group of duplicates folded into `keep_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlatformMerge {
+    pub slug: String,
+    pub keep_id: i64,
+    pub keep_name: String,
+    pub merged_ids: Vec<i64>,
+    pub merged_names: Vec<String>,
+    /// `video_games` rows moved to `keep_id` (would be moved, in a dry run).
+    pub video_games_repointed: i64,
+}
+
+/// A merge left undone because repointing or deleting failed (e.g. a unique key on a
+/// referencing table); its rows are untouched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SkippedMerge {
+    pub slug: String,
+    pub keep_id: i64,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DedupeReport {
+    pub dry_run: bool,
+    pub merges: Vec<PlatformMerge>,
+    pub skipped: Vec<SkippedMerge>,
+    /// Platform rows deleted (would be deleted, in a dry run).
+    pub rows_removed: u64,
+}
+
+/// Lowercase ASCII alphanumerics of `raw`; the key duplicates are grouped by.
+pub fn normalize_slug(raw: &str) -> String {
+    raw.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Group `rows` by normalized slug and pick the row each duplicate group keeps. Groups
+/// are ordered by kept id; merged ids ascend.
+pub fn plan_merges(rows: Vec<PlatformRow>) -> Vec<PlatformMerge> {
+    let mut groups: BTreeMap<String, Vec<PlatformRow>> = BTreeMap::new();
+    for row in rows {
+        let slug = normalize_slug(row.code.as_deref().unwrap_or(&row.name));
+        if !slug.is_empty() {
+            groups.entry(slug).or_default().push(row);
+        }
+    }
+
+    let mut merges: Vec<PlatformMerge> = groups
+        .into_iter()
+        .filter(|(_, rows)| rows.len() > 1)
+        .map(|(slug, mut rows)| {
+            rows.sort_by_key(|p| (std::cmp::Reverse(p.video_games), p.id));
+            let keep = rows.remove(0);
+            rows.sort_by_key(|p| p.id);
+            PlatformMerge {
+                slug,
+                keep_id: keep.id,
+                keep_name: keep.name,
+                merged_ids: rows.iter().map(|p| p.id).collect(),
+                video_games_repointed: rows.iter().map(|p| p.video_games).sum(),
+                merged_names: rows.into_iter().map(|p| p.name).collect(),
+            }
+        })
+        .collect();
+    merges.sort_by_key(|m| m.keep_id);
+    merges
+}
+
+/// Find and merge duplicate platforms; with `dry_run` nothing is written. Each merge runs
+/// in its own transaction, so a failing group is skipped without undoing the others.
+pub async fn run(db: &Db, dry_run: bool) -> Result<DedupeReport> {
+    let merges = plan_merges(load_platforms(db).await?);
+    let mut report = DedupeReport {
+        dry_run,
+        ..Default::default()
+    };
+    if dry_run {
+        report.rows_removed = merges.iter().map(|m| m.merged_ids.len() as u64).sum();
+        report.merges = merges;
+        info!(
+            merges = report.merges.len(),
+            rows_would_remove = report.rows_removed,
+            "platforms dedupe: dry run (no changes applied)"
+        );
+        return Ok(report);
+    }
+
+    let targets = MergeTargets {
+        platform_refs: referencing_columns(db).await?,
+        video_game_refs: foreign_keys_to(db, "video_games").await?,
+        titled_video_games: has_columns(db, "video_games", &["title_id", "edition"]).await?,
+    };
+    let record_map: bool =
+        sqlx::query_scalar("SELECT to_regclass('platforms_dedupe_map') IS NOT NULL")
+            .persistent(false)
+            .fetch_one(&db.pool)
+            .await?;
+    for merge in merges {
+        match apply_merge(db, &merge, &targets, record_map).await {
+            Ok(removed) => {
+                info!(
+                    slug = %merge.slug,
+                    keep_id = merge.keep_id,
+                    merged = ?merge.merged_ids,
+                    "platforms dedupe: merged"
+                );
+                report.rows_removed += removed;
+                report.merges.push(merge);
+            }
+            Err(e) => {
+                warn!(slug = %merge.slug, keep_id = merge.keep_id, error = %e, "platforms dedupe: merge skipped");
+                report.skipped.push(SkippedMerge {
+                    slug: merge.slug,
+                    keep_id: merge.keep_id,
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+    info!(
+        merges = report.merges.len(),
+        skipped = report.skipped.len(),
+        rows_removed = report.rows_removed,
+        "platforms dedupe complete"
+    );
+    Ok(report)
+}
+
+async fn load_platforms(db: &Db) -> Result<Vec<PlatformRow>> {
+    let has_video_games: bool = sqlx::query_scalar("SELECT to_regclass('video_games') IS NOT NULL")
+        .persistent(false)
+        .fetch_one(&db.pool)
+        .await?;
+    let sql = if has_video_games {
+        "SELECT p.id, p.name, p.code,
+                (SELECT count(*) FROM video_games vg WHERE vg.platform_id = p.id) AS video_games
+         FROM platforms p"
+    } else {
+        "SELECT p.id, p.name, p.code, 0::bigint AS video_games FROM platforms p"
+    };
+    let rows = sqlx::query(sql)
+        .persistent(false)
+        .fetch_all(&db.pool)
+        .await?;
+    rows.into_iter()
+        .map(|r| {
+            Ok(PlatformRow {
+                id: r.try_get("id")?,
+                name: r.try_get("name")?,
+                code: r.try_get("code")?,
+                video_games: r.try_get("video_games")?,
+            })
+        })
+        .collect()
+}
+
+/// What a merge has to rewrite, looked up once per run.
+struct MergeTargets {
+    /// Columns pointing at platforms.
+    platform_refs: Vec<(String, String)>,
+    /// Columns pointing at video_games, moved when colliding rows are folded together.
+    video_game_refs: Vec<(String, String)>,
+    /// Whether video_games carries the `(title_id, platform_id, edition)` key.
+    titled_video_games: bool,
+}
+
+/// `(table, column)` pairs pointing at platforms: every declared single-column foreign key
+/// (partition-level copies excluded) plus `video_games.platform_id`, declared or not.
+async fn referencing_columns(db: &Db) -> Result<Vec<(String, String)>> {
+    let mut columns = foreign_keys_to(db, "platforms").await?;
+    let video_games: Option<String> = sqlx::query_scalar(
+        "SELECT format('%I.%I', n.nspname, c.relname)
+         FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE c.oid = to_regclass('video_games')",
+    )
+    .persistent(false)
+    .fetch_optional(&db.pool)
+    .await?;
+    if let Some(table) = video_games {
+        let column = (table, "platform_id".to_string());
+        if !columns.contains(&column) {
+            columns.push(column);
+            columns.sort();
+        }
+    }
+    Ok(columns)
+}
+
+/// `(table, column)` pairs of every declared single-column foreign key to `target`,
+/// partition-level copies excluded.
+async fn foreign_keys_to(db: &Db, target: &str) -> Result<Vec<(String, String)>> {
+    let rows = sqlx::query(
+        "SELECT format('%I.%I', n.nspname, c.relname) AS tbl, a.attname::text AS col
+         FROM pg_constraint k
+         JOIN pg_class c ON c.oid = k.conrelid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         JOIN pg_attribute a ON a.attrelid = k.conrelid AND a.attnum = k.conkey[1]
+         WHERE k.contype = 'f'
+           AND k.confrelid = to_regclass($1)
+           AND k.conparentid = 0
+           AND array_length(k.conkey, 1) = 1
+         ORDER BY 1, 2",
+    )
+    .persistent(false)
+    .bind(target)
+    .fetch_all(&db.pool)
+    .await?;
+    rows.into_iter()
+        .map(|r| Ok((r.try_get("tbl")?, r.try_get("col")?)))
+        .collect()
+}
+
+async fn has_columns(db: &Db, table: &str, columns: &[&str]) -> Result<bool> {
+    let found: i64 = sqlx::query_scalar(
+        "SELECT count(*) FROM pg_attribute
+         WHERE attrelid = to_regclass($1) AND attname = ANY($2) AND NOT attisdropped",
+    )
+    .persistent(false)
+    .bind(table)
+    .bind(columns)
+    .fetch_one(&db.pool)
+    .await?;
+    Ok(found == columns.len() as i64)
+}
+
+/// Repoint, record and delete one group atomically; returns the platform rows deleted.
+async fn apply_merge(
+    db: &Db,
+    merge: &PlatformMerge,
+    targets: &MergeTargets,
+    record_map: bool,
+) -> Result<u64> {
+    let mut tx = db.pool.begin().await?;
+    if targets.titled_video_games {
+        let folded = fold_colliding_video_games(&mut tx, merge, &targets.video_game_refs).await?;
+        if folded > 0 {
+            info!(slug = %merge.slug, folded, "platforms dedupe: folded shared-title video_games");
+        }
+    }
+    for (table, column) in &targets.platform_refs {
+        repoint(&mut tx, table, column, &merge.merged_ids, merge.keep_id).await?;
+    }
+    if record_map {
+        sqlx::query(
+            "INSERT INTO platforms_dedupe_map (dupe_id, canonical_id)
+             SELECT unnest($2::bigint[]), $1
+             ON CONFLICT (dupe_id) DO UPDATE
+                 SET canonical_id = EXCLUDED.canonical_id, deduped_at = now()",
+        )
+        .persistent(false)
+        .bind(merge.keep_id)
+        .bind(&merge.merged_ids)
+        .execute(&mut *tx)
+        .await?;
+    }
+    let deleted = sqlx::query("DELETE FROM platforms WHERE id = ANY($1)")
+        .persistent(false)
+        .bind(&merge.merged_ids)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    tx.commit().await?;
+    Ok(deleted)
+}
+
+/// Fold the group's video_games that share a title and edition into one row, preferring
+/// the one already on the kept platform, so repointing `platform_id` cannot collide.
+/// Returns the video_games rows deleted.
+async fn fold_colliding_video_games(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    merge: &PlatformMerge,
+    video_game_refs: &[(String, String)],
+) -> Result<u64> {
+    let platform_ids: Vec<i64> = std::iter::once(merge.keep_id)
+        .chain(merge.merged_ids.iter().copied())
+        .collect();
+    let pairs: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT winner, id FROM (
+             SELECT id, first_value(id) OVER (
+                 PARTITION BY title_id, COALESCE(edition, '')
+                 ORDER BY platform_id = $1 DESC, id
+             ) AS winner
+             FROM video_games
+             WHERE platform_id = ANY($2) AND title_id IS NOT NULL
+         ) vg
+         WHERE id <> winner
+         ORDER BY winner, id",
+    )
+    .persistent(false)
+    .bind(merge.keep_id)
+    .bind(&platform_ids)
+    .fetch_all(&mut **tx)
+    .await?;
+    let mut losers_by_winner: BTreeMap<i64, Vec<i64>> = BTreeMap::new();
+    for (winner, loser) in pairs {
+        losers_by_winner.entry(winner).or_default().push(loser);
+    }
+
+    let mut deleted = 0;
+    for (winner, losers) in losers_by_winner {
+        for (table, column) in video_game_refs {
+            repoint(tx, table, column, &losers, winner).await?;
+        }
+        deleted += sqlx::query("DELETE FROM video_games WHERE id = ANY($1)")
+            .persistent(false)
+            .bind(&losers)
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+    }
+    Ok(deleted)
+}
+
+/// Point `table.column` at `to` instead of any of `from`. Rows that would then duplicate
+/// another on a plain (non-partial, column-only) unique key through `column` are dropped
+/// first; among colliding repointed rows, the first one physically stored is kept.
+async fn repoint(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    table: &str,
+    column: &str,
+    from: &[i64],
+    to: i64,
+) -> Result<()> {
+    let unique_keys: Vec<Vec<String>> = sqlx::query_scalar(
+        "SELECT array_agg(a.attname::text ORDER BY k.ord)
+         FROM pg_index i
+         CROSS JOIN LATERAL unnest(i.indkey::int2[]) WITH ORDINALITY AS k(attnum, ord)
+         JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = k.attnum
+         WHERE i.indrelid = to_regclass($1)
+           AND i.indisunique
+           AND i.indpred IS NULL
+           AND i.indexprs IS NULL
+         GROUP BY i.indexrelid
+         HAVING bool_or(a.attname = $2)",
+    )
+    .persistent(false)
+    .bind(table)
+    .bind(column)
+    .fetch_all(&mut **tx)
+    .await?;
+    for key in unique_keys {
+        let same_key: String = key
+            .iter()
+            .filter(|c| c.as_str() != column)
+            .map(|c| format!(" AND k.\"{c}\" = t.\"{c}\""))
+            .collect();
+        sqlx::query(&format!(
+            "DELETE FROM {table} t
+             WHERE t.\"{column}\" = ANY($2)
+               AND EXISTS (
+                   SELECT 1 FROM {table} k
+                   WHERE (k.\"{column}\" = $1 OR (k.\"{column}\" = ANY($2) AND k.ctid < t.ctid)){same_key}
+               )"
+        ))
+        .persistent(false)
+        .bind(to)
+        .bind(from)
+        .execute(&mut **tx)
+        .await?;
+    }
+    sqlx::query(&format!(
+        "UPDATE {table} SET \"{column}\" = $1 WHERE \"{column}\" = ANY($2)"
+    ))
+    .persistent(false)
+    .bind(to)
+    .bind(from)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    fn platform(id: i64, name: &str, code: Option<&str>, video_games: i64) -> PlatformRow {
+        PlatformRow {
+            id,
+            name: name.to_string(),
+            code: code.map(str::to_string),
+            video_games,
+        }
+    }
+
+    #[test]
+    fn dry_run_report_lists_each_merge() {
+        let merges = plan_merges(vec![
+            platform(1, "PlayStation 5", Some("PS5"), 2),
+            platform(2, "Xbox Series X", None, 0),
+            platform(3, "ps5", Some("ps5"), 9),
+            platform(4, "PS-5", Some("PS-5"), 0),
+            platform(5, "XBOX SERIES X", None, 0),
+            platform(6, "Switch", Some("switch"), 4),
+            platform(7, "???", None, 0),
+            platform(8, "---", None, 0),
+        ]);
+        let report = DedupeReport {
+            dry_run: true,
+            rows_removed: 3,
+            merges,
+            skipped: Vec::new(),
+        };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "dry_run": true,
+                "merges": [
+                    {
+                        // Tie on video_games: the lowest id is kept.
+                        "slug": "xboxseriesx",
+                        "keep_id": 2,
+                        "keep_name": "Xbox Series X",
+                        "merged_ids": [5],
+                        "merged_names": ["XBOX SERIES X"],
+                        "video_games_repointed": 0
+                    },
+                    {
+                        "slug": "ps5",
+                        "keep_id": 3,
+                        "keep_name": "ps5",
+                        "merged_ids": [1, 4],
+                        "merged_names": ["PlayStation 5", "PS-5"],
+                        "video_games_repointed": 2
+                    }
+                ],
+                "skipped": [],
+                "rows_removed": 3
+            })
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn duplicates_are_repointed_then_removed() {
+        // One connection, so the temp tables below shadow the real ones for every query.
+        let db = RollbackDb::connect().await;
+        for sql in [
+            "CREATE TEMP TABLE platforms (id bigint PRIMARY KEY, name text NOT NULL, code text)",
+            "CREATE TEMP TABLE video_games (
+                 id bigint PRIMARY KEY,
+                 platform_id bigint REFERENCES platforms(id),
+                 title_id bigint,
+                 edition text
+             )",
+            "CREATE UNIQUE INDEX ON video_games (title_id, platform_id) WHERE edition IS NULL",
+            "CREATE UNIQUE INDEX ON video_games (title_id, platform_id, COALESCE(edition, ''))",
+            "CREATE TEMP TABLE video_game_ratings_by_locale (
+                 video_game_id bigint REFERENCES video_games(id),
+                 locale text,
+                 UNIQUE (video_game_id, locale)
+             )",
+            "CREATE TEMP TABLE game_consoles (
+                 id bigint PRIMARY KEY,
+                 platform_id bigint UNIQUE REFERENCES platforms(id)
+             )",
+            "CREATE TEMP TABLE platform_hardware_map (
+                 platform_id bigint PRIMARY KEY REFERENCES platforms(id),
+                 hardware_product_id bigint
+             )",
+            "CREATE TEMP TABLE platforms_dedupe_map (
+                 dupe_id bigint PRIMARY KEY,
+                 canonical_id bigint NOT NULL,
+                 deduped_at timestamptz NOT NULL DEFAULT now()
+             )",
+            "INSERT INTO platforms VALUES
+                 (1, 'PlayStation 5', 'PS5'), (2, 'ps5', 'ps5'), (3, 'Switch', 'switch')",
+            // Title 100 is listed on both duplicates; the Switch copy is another platform.
+            "INSERT INTO video_games VALUES
+                 (10, 1, 100, NULL), (11, 2, 100, NULL), (12, 2, 101, NULL),
+                 (13, 3, 100, NULL), (14, 1, 102, 'Deluxe'), (15, 2, NULL, NULL)",
+            "INSERT INTO video_game_ratings_by_locale VALUES
+                 (10, 'en-US'), (10, 'en-GB'), (11, 'en-US')",
+            "INSERT INTO game_consoles VALUES (20, 1)",
+            "INSERT INTO platform_hardware_map VALUES (1, 500), (2, 501)",
+        ] {
+            sqlx::query(sql).execute(&db.pool).await.unwrap();
+        }
+
+        let dry = run(&db, true).await.unwrap();
+        assert_eq!(dry.rows_removed, 1);
+        assert_eq!(dry.merges[0].keep_id, 2);
+        assert_eq!(dry.merges[0].merged_ids, vec![1]);
+        let platforms: i64 = sqlx::query_scalar("SELECT count(*) FROM platforms")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(platforms, 3);
+
+        let applied = run(&db, false).await.unwrap();
+        assert_eq!(applied.merges, dry.merges);
+        assert_eq!(applied.rows_removed, 1);
+        assert!(applied.skipped.is_empty());
+        let rows: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT id, platform_id FROM video_games
+             UNION ALL SELECT id, platform_id FROM game_consoles
+             ORDER BY 1",
+        )
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        // 10 shared its title with 11 and was folded into it.
+        assert_eq!(
+            rows,
+            vec![(11, 2), (12, 2), (13, 3), (14, 2), (15, 2), (20, 2)]
+        );
+        let ratings: Vec<(i64, String)> = sqlx::query_as(
+            "SELECT video_game_id, locale FROM video_game_ratings_by_locale ORDER BY 1, 2",
+        )
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        assert_eq!(ratings, vec![(11, "en-GB".into()), (11, "en-US".into())]);
+        let hardware: Vec<(i64, i64)> = sqlx::query_as("SELECT * FROM platform_hardware_map")
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(hardware, vec![(2, 501)]);
+        let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM platforms ORDER BY id")
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![2, 3]);
+        let mapped: Vec<(i64, i64)> =
+            sqlx::query_as("SELECT dupe_id, canonical_id FROM platforms_dedupe_map")
+                .fetch_all(&db.pool)
+                .await
+                .unwrap();
+        assert_eq!(mapped, vec![(1, 2)]);
+        assert!(run(&db, false).await.unwrap().merges.is_empty());
+
+        db.rollback().await;
+    }
+}
@@ -0,0 +1,167 @@
+//! JSON-lines audit trail of PS Store price rows (`PS_PRICE_AUDIT_PATH`).
+//!
+//! `psstore_seed_pipeline` appends one line per price row before handing the batch to
+//! `ingest_prices`, so stored prices can be reconciled against PlayStation's invoices. The
+//! file rotates daily: `PS_PRICE_AUDIT_PATH=audit/prices.jsonl` writes
+//! `audit/prices_20250301.jsonl`, `audit/prices_20250302.jsonl`, ...
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDate, Utc};
+use serde_json::{json, Value};
+use tracing::warn;
+
+use crate::database_ops::db::PriceRow;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceAudit {
+    base: PathBuf,
+}
+
+impl PriceAudit {
+    pub fn new(base: impl Into<PathBuf>) -> Self {
+        Self { base: base.into() }
+    }
+
+    /// The audit configured by `PS_PRICE_AUDIT_PATH`; `None` when unset.
+    pub fn from_env() -> Option<Self> {
+        crate::util::env::env_opt("PS_PRICE_AUDIT_PATH").map(Self::new)
+    }
+
+    /// The file for `date`: the configured path with `_YYYYMMDD` before its extension.
+    pub fn path_for(&self, date: NaiveDate) -> PathBuf {
+        let stem = self
+            .base
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "psstore_price_audit".into());
+        let name = match self.base.extension() {
+            Some(ext) => format!("{stem}_{}.{}", date.format("%Y%m%d"), ext.to_string_lossy()),
+            None => format!("{stem}_{}", date.format("%Y%m%d")),
+        };
+        self.base.with_file_name(name)
+    }
+
+    /// Append one line per row to today's file. Failures are logged and otherwise
+    /// ignored; the audit never stops an ingest.
+    pub async fn append(&self, rows: &[PriceRow], currency_code: &str) {
+        if rows.is_empty() {
+            return;
+        }
+        let path = self.path_for(Utc::now().date_naive());
+        let count = rows.len();
+        let buf = match encode_lines(rows, currency_code) {
+            Ok(buf) => buf,
+            Err(err) => {
+                warn!(rows = count, error = %err, "psstore price audit encode failed");
+                return;
+            }
+        };
+        let written = {
+            let path = path.clone();
+            tokio::task::spawn_blocking(move || append_lines(&path, &buf)).await
+        };
+        match written {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => {
+                warn!(path = %path.display(), rows = count, error = %err, "psstore price audit write failed");
+            }
+            Err(err) => {
+                warn!(path = %path.display(), rows = count, error = %err, "psstore price audit task failed");
+            }
+        }
+    }
+}
+
+fn encode_lines(rows: &[PriceRow], currency_code: &str) -> serde_json::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    for row in rows {
+        serde_json::to_writer(&mut buf, &audit_line(row, currency_code))?;
+        buf.push(b'\n');
+    }
+    Ok(buf)
+}
+
+/// Blocking; runs on the blocking pool so the seeding tasks keep their worker threads.
+fn append_lines(path: &Path, buf: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    // One write per batch keeps lines from concurrently seeded locales whole.
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(buf)
+}
+
+fn audit_line(row: &PriceRow, currency_code: &str) -> Value {
+    json!({
+        "offer_jurisdiction_id": row.offer_jurisdiction_id,
+        "amount_minor": row.amount_minor,
+        "currency_code": row.currency.as_deref().unwrap_or(currency_code),
+        "locale": row.meta.get("locale").cloned().unwrap_or(Value::Null),
+        "kind": row.meta.get("kind").cloned().unwrap_or(Value::Null),
+        "recorded_at": row.recorded_at.to_rfc3339(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(kind: &str, amount_minor: i64) -> PriceRow {
+        PriceRow {
+            offer_jurisdiction_id: 42,
+            video_game_source_id: None,
+            recorded_at: Utc::now(),
+            amount_minor,
+            tax_inclusive: true,
+            fx_minor_per_unit: None,
+            btc_sats_per_unit: None,
+            meta: json!({"src": "psstore", "kind": kind, "locale": "en-GB"}),
+            video_game_id: Some(7),
+            currency: None,
+            country_code: Some("en-GB".into()),
+            retailer: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn rows_append_to_the_daily_file() {
+        let dir = std::env::temp_dir().join(format!("psstore_audit_{}", std::process::id()));
+        let audit = PriceAudit::new(dir.join("prices.jsonl"));
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        assert_eq!(audit.path_for(date), dir.join("prices_20250301.jsonl"));
+
+        audit
+            .append(&[row("base", 6999), row("discount", 3499)], "GBP")
+            .await;
+        audit.append(&[row("base", 6999)], "GBP").await;
+        let path = audit.path_for(Utc::now().date_naive());
+        let written = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let lines: Vec<Value> = written
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1]["kind"], "discount");
+        assert_eq!(lines[1]["amount_minor"], 3499);
+        assert_eq!(lines[1]["currency_code"], "GBP");
+        assert_eq!(lines[1]["locale"], "en-GB");
+        assert_eq!(lines[1]["offer_jurisdiction_id"], 42);
+        assert!(lines[1]["recorded_at"].as_str().is_some());
+
+        // An unwritable path (a directory under a regular file) only warns.
+        let blocker =
+            std::env::temp_dir().join(format!("psstore_audit_file_{}", std::process::id()));
+        std::fs::write(&blocker, b"").unwrap();
+        PriceAudit::new(blocker.join("prices.jsonl"))
+            .append(&[row("base", 1)], "GBP")
+            .await;
+        let _ = std::fs::remove_file(&blocker);
+    }
+}
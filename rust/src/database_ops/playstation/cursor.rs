@@ -0,0 +1,174 @@
+//! Resumable paging for the PS Store seed pipeline (`psstore_ingest_cursor`).
+//!
+//! After each category page's batch commits, the pipeline records the page per
+//! `(locale, category_id)`. The next run resumes after it when the cursor is fresher than
+//! `PS_CURSOR_TTL_SECS`; a finished walk clears its cursor so the following tick starts
+//! fresh. `PS_RESUME=0` ignores stored cursors.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::database_ops::db::Db;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorOptions {
+    pub resume: bool,
+    pub ttl: Duration,
+}
+
+impl Default for CursorOptions {
+    fn default() -> Self {
+        Self {
+            resume: true,
+            ttl: Duration::hours(6),
+        }
+    }
+}
+
+impl CursorOptions {
+    /// From `PS_RESUME` and `PS_CURSOR_TTL_SECS`.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            resume: crate::util::env::env_flag("PS_RESUME", defaults.resume),
+            ttl: Duration::seconds(crate::util::env::env_parse(
+                "PS_CURSOR_TTL_SECS",
+                defaults.ttl.num_seconds(),
+            )),
+        }
+    }
+}
+
+/// First page to fetch: the page after a stored cursor younger than the TTL, otherwise
+/// `start_page`.
+pub fn resume_page(
+    stored: Option<(i32, DateTime<Utc>)>,
+    start_page: u32,
+    opts: &CursorOptions,
+    now: DateTime<Utc>,
+) -> u32 {
+    match stored {
+        Some((page, at)) if opts.resume && now - at < opts.ttl => {
+            u32::try_from(page).map_or(start_page, |p| start_page.max(p.saturating_add(1)))
+        }
+        _ => start_page,
+    }
+}
+
+async fn cursor_present(db: &Db) -> Result<bool> {
+    Ok(
+        sqlx::query_scalar("SELECT to_regclass('public.psstore_ingest_cursor') IS NOT NULL")
+            .persistent(false)
+            .fetch_one(&db.pool)
+            .await?,
+    )
+}
+
+/// The stored cursor for `locale` / `category_id`: last processed page and when.
+pub async fn load_cursor(
+    db: &Db,
+    locale: &str,
+    category_id: &str,
+) -> Result<Option<(i32, DateTime<Utc>)>> {
+    if !cursor_present(db).await? {
+        return Ok(None);
+    }
+    Ok(sqlx::query_as(
+        "SELECT page, updated_at FROM public.psstore_ingest_cursor
+         WHERE locale = $1 AND category_id = $2",
+    )
+    .persistent(false)
+    .bind(locale)
+    .bind(category_id)
+    .fetch_optional(&db.pool)
+    .await?)
+}
+
+/// Record `page` as the last fully-processed page.
+pub async fn save_cursor(db: &Db, locale: &str, category_id: &str, page: u32) -> Result<()> {
+    if !cursor_present(db).await? {
+        return Ok(());
+    }
+    sqlx::query(
+        "INSERT INTO public.psstore_ingest_cursor (locale, category_id, page)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (locale, category_id) DO UPDATE
+           SET page = EXCLUDED.page, updated_at = now()",
+    )
+    .persistent(false)
+    .bind(locale)
+    .bind(category_id)
+    .bind(i32::try_from(page).unwrap_or(i32::MAX))
+    .execute(&db.pool)
+    .await?;
+    Ok(())
+}
+
+/// Forget the cursor once a category walk completes.
+pub async fn clear_cursor(db: &Db, locale: &str, category_id: &str) -> Result<()> {
+    if !cursor_present(db).await? {
+        return Ok(());
+    }
+    sqlx::query("DELETE FROM public.psstore_ingest_cursor WHERE locale = $1 AND category_id = $2")
+        .persistent(false)
+        .bind(locale)
+        .bind(category_id)
+        .execute(&db.pool)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    #[test]
+    fn resume_respects_ttl_and_opt_out() {
+        let now = Utc::now();
+        let opts = CursorOptions::default();
+        assert_eq!(resume_page(None, 0, &opts, now), 0);
+        assert_eq!(
+            resume_page(Some((3, now - Duration::minutes(5))), 0, &opts, now),
+            4
+        );
+        assert_eq!(
+            resume_page(Some((3, now - Duration::hours(7))), 0, &opts, now),
+            0,
+            "stale cursor starts fresh"
+        );
+        let no_resume = CursorOptions {
+            resume: false,
+            ..opts
+        };
+        assert_eq!(
+            resume_page(Some((3, now)), 0, &no_resume, now),
+            0,
+            "PS_RESUME=0"
+        );
+        assert_eq!(resume_page(Some((3, now)), 10, &opts, now), 10);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn interrupted_walk_resumes_at_next_page() {
+        let db = RollbackDb::connect().await;
+        let (locale, category) = ("en-us", "cursor-test-category");
+        let opts = CursorOptions::default();
+
+        // First run is interrupted after committing pages 0..=3.
+        for page in 0..=3 {
+            save_cursor(&db, locale, category, page).await.unwrap();
+        }
+        let stored = load_cursor(&db, locale, category).await.unwrap();
+        let resumed = resume_page(stored, 0, &opts, Utc::now());
+
+        clear_cursor(&db, locale, category).await.unwrap();
+        let cleared = load_cursor(&db, locale, category).await.unwrap();
+
+        assert_eq!(resumed, 4);
+        assert_eq!(cleared, None);
+
+        db.rollback().await;
+    }
+}
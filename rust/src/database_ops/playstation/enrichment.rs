@@ -0,0 +1,215 @@
+//! Per-product enrichment markers for the PS Store seed pipeline (`psstore_enrichment`).
+//!
+//! Detail and star-rating requests are the bulk of a seed run. After each category page's
+//! writes, the pipeline stamps the products whose detail payload it fetched, per
+//! `(locale, product_id)`. With `PS_ENRICH_MAX_AGE_HOURS` set, a later run skips both
+//! requests for products an earlier run stamped within that many hours, so an interrupted
+//! run picks up enrichment where it stopped. Skipped listings still get their price rows,
+//! and feed the run's rating/genre aggregates from what earlier runs stored
+//! ([`stored_enrichment`]). Unset or 0 disables skipping.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+
+use crate::database_ops::db::Db;
+use crate::database_ops::schema_caps::SchemaCaps;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EnrichOptions {
+    /// Skip products enriched within this long; `None` fetches everything.
+    pub max_age: Option<Duration>,
+}
+
+impl EnrichOptions {
+    /// From `PS_ENRICH_MAX_AGE_HOURS`.
+    pub fn from_env() -> Self {
+        let hours = crate::util::env::env_parse::<i64>("PS_ENRICH_MAX_AGE_HOURS", 0);
+        Self {
+            max_age: (hours > 0).then(|| Duration::hours(hours)),
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.max_age.is_some()
+    }
+}
+
+/// Whether a product stamped at `enriched_at` can skip enrichment in the run started at
+/// `run_started`. Only stamps from earlier runs count, so a product listed under both
+/// categories is still fetched for each listing of the run that first enriches it.
+pub fn is_fresh(
+    enriched_at: DateTime<Utc>,
+    run_started: DateTime<Utc>,
+    opts: &EnrichOptions,
+) -> bool {
+    opts.max_age
+        .is_some_and(|max_age| enriched_at < run_started && run_started - enriched_at < max_age)
+}
+
+async fn markers_present(db: &Db) -> Result<bool> {
+    SchemaCaps::global()
+        .table_visible(db, "psstore_enrichment")
+        .await
+}
+
+/// What an earlier run stored for a video_game a listing skipped enrichment for.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StoredEnrichment {
+    /// The locale's star rating (`video_game_ratings_by_locale`).
+    pub rating: Option<(f32, i64)>,
+    /// The PS Store genre union from the metadata, else the `genres` column.
+    pub genres: Vec<String>,
+}
+
+/// Stored ratings in `locale` and genres for `video_game_ids`, so skipped listings keep
+/// contributing to the run's aggregates instead of overwriting them with partial ones.
+pub async fn stored_enrichment(
+    db: &Db,
+    locale: &str,
+    video_game_ids: &[i64],
+) -> Result<HashMap<i64, StoredEnrichment>> {
+    let mut stored: HashMap<i64, StoredEnrichment> = HashMap::new();
+    if video_game_ids.is_empty() {
+        return Ok(stored);
+    }
+    let genres: Vec<(i64, Option<Vec<String>>)> = sqlx::query_as(
+        "SELECT id,
+                CASE WHEN jsonb_typeof(metadata::jsonb->'genres_union') = 'array'
+                     THEN ARRAY(SELECT jsonb_array_elements_text(metadata::jsonb->'genres_union'))
+                     ELSE genres END
+         FROM public.video_games WHERE id = ANY($1)",
+    )
+    .persistent(false)
+    .bind(video_game_ids)
+    .fetch_all(&db.pool)
+    .await?;
+    for (vg_id, genres) in genres {
+        stored.entry(vg_id).or_default().genres = genres.unwrap_or_default();
+    }
+    if SchemaCaps::global()
+        .table_visible(db, "video_game_ratings_by_locale")
+        .await?
+    {
+        let ratings: Vec<(i64, f32, i64)> = sqlx::query_as(
+            "SELECT video_game_id, average_rating::float4, rating_count::int8
+             FROM public.video_game_ratings_by_locale
+             WHERE locale = $1 AND video_game_id = ANY($2)
+               AND average_rating IS NOT NULL AND rating_count IS NOT NULL",
+        )
+        .persistent(false)
+        .bind(locale)
+        .bind(video_game_ids)
+        .fetch_all(&db.pool)
+        .await?;
+        for (vg_id, avg, cnt) in ratings {
+            stored.entry(vg_id).or_default().rating = Some((avg, cnt));
+        }
+    }
+    Ok(stored)
+}
+
+/// The subset of `product_ids` in `locale` that [`is_fresh`] lets skip enrichment.
+pub async fn recently_enriched(
+    db: &Db,
+    locale: &str,
+    product_ids: &[String],
+    opts: &EnrichOptions,
+    run_started: DateTime<Utc>,
+) -> Result<HashSet<String>> {
+    if !opts.enabled() || product_ids.is_empty() || !markers_present(db).await? {
+        return Ok(HashSet::new());
+    }
+    let stamps: Vec<(String, DateTime<Utc>)> = sqlx::query_as(
+        "SELECT product_id, enriched_at FROM public.psstore_enrichment
+         WHERE locale = $1 AND product_id = ANY($2)",
+    )
+    .persistent(false)
+    .bind(locale)
+    .bind(product_ids)
+    .fetch_all(&db.pool)
+    .await?;
+    Ok(stamps
+        .into_iter()
+        .filter(|(_, at)| is_fresh(*at, run_started, opts))
+        .map(|(product_id, _)| product_id)
+        .collect())
+}
+
+/// Stamp `product_ids` in `locale` as enriched now.
+pub async fn mark_enriched(db: &Db, locale: &str, product_ids: &[String]) -> Result<()> {
+    if product_ids.is_empty() || !markers_present(db).await? {
+        return Ok(());
+    }
+    sqlx::query(
+        "INSERT INTO public.psstore_enrichment (locale, product_id)
+         SELECT $1, unnest($2::text[])
+         ON CONFLICT (locale, product_id) DO UPDATE SET enriched_at = now()",
+    )
+    .persistent(false)
+    .bind(locale)
+    .bind(product_ids)
+    .execute(&db.pool)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    #[test]
+    fn only_earlier_runs_within_max_age_are_fresh() {
+        let started = Utc::now();
+        let opts = EnrichOptions {
+            max_age: Some(Duration::hours(24)),
+        };
+        assert!(is_fresh(started - Duration::hours(2), started, &opts));
+        assert!(
+            !is_fresh(started - Duration::hours(25), started, &opts),
+            "stale marker is re-enriched"
+        );
+        assert!(
+            !is_fresh(started + Duration::seconds(5), started, &opts),
+            "stamped by this run"
+        );
+        assert!(
+            !is_fresh(
+                started - Duration::hours(2),
+                started,
+                &EnrichOptions::default()
+            ),
+            "disabled"
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn marked_products_are_skipped_by_the_next_run() {
+        let db = RollbackDb::connect().await;
+        let locale = "enrich-test";
+        let ids = vec![
+            "UP0000-ENRICH0001_00".to_string(),
+            "UP0000-ENRICH0002_00".to_string(),
+        ];
+        let opts = EnrichOptions {
+            max_age: Some(Duration::hours(1)),
+        };
+
+        mark_enriched(&db, locale, &ids[..1]).await.unwrap();
+        let same_run =
+            recently_enriched(&db, locale, &ids, &opts, Utc::now() - Duration::minutes(1))
+                .await
+                .unwrap();
+        let next_run =
+            recently_enriched(&db, locale, &ids, &opts, Utc::now() + Duration::seconds(1))
+                .await
+                .unwrap();
+
+        db.rollback().await;
+        assert!(same_run.is_empty());
+        assert_eq!(next_run, HashSet::from([ids[0].clone()]));
+    }
+}
@@ -0,0 +1,167 @@
+//! Operator include/exclude lists for the PS Store seed pipeline.
+//!
+//! `PS_EXCLUDE_PRODUCTS` / `PS_EXCLUDE_CONCEPTS` take comma-separated ids to skip;
+//! `PS_INCLUDE_PRODUCTS` restricts a run to just those products (targeted refresh); each
+//! category stops paging once all of them have been listed.
+//! Each also reads `<KEY>_FILE`, a file with one id per line (commas also accepted, `#`
+//! starts a comment) for large sets. Exclusions win over inclusions.
+
+use psstore_client::PsProductSummary;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Default)]
+pub struct ProductFilter {
+    exclude_products: HashSet<String>,
+    exclude_concepts: HashSet<String>,
+    /// `None` processes every product; `Some` only the listed ones.
+    include_products: Option<HashSet<String>>,
+}
+
+impl ProductFilter {
+    pub fn from_env() -> Self {
+        Self {
+            exclude_products: ids_from_env("PS_EXCLUDE_PRODUCTS"),
+            exclude_concepts: ids_from_env("PS_EXCLUDE_CONCEPTS"),
+            include_products: Some(ids_from_env("PS_INCLUDE_PRODUCTS"))
+                .filter(|ids| !ids.is_empty()),
+        }
+    }
+
+    pub fn with_excluded(products: &[&str], concepts: &[&str]) -> Self {
+        Self {
+            exclude_products: products.iter().map(|s| s.trim().to_string()).collect(),
+            exclude_concepts: concepts.iter().map(|s| s.trim().to_string()).collect(),
+            include_products: None,
+        }
+    }
+
+    pub fn include_only(mut self, products: &[&str]) -> Self {
+        self.include_products = Some(products.iter().map(|s| s.trim().to_string()).collect());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exclude_products.is_empty()
+            && self.exclude_concepts.is_empty()
+            && self.include_products.is_none()
+    }
+
+    /// Included products, when the run is restricted to a known set.
+    pub fn included_products(&self) -> Option<&HashSet<String>> {
+        self.include_products.as_ref()
+    }
+
+    pub fn is_excluded(&self, product_id: Option<&str>, concept_id: Option<&str>) -> bool {
+        product_id.is_some_and(|p| self.exclude_products.contains(p.trim()))
+            || concept_id.is_some_and(|c| self.exclude_concepts.contains(c.trim()))
+    }
+
+    /// Not excluded and, when an include list is set, listed in it. Items without a
+    /// productId never match an include list.
+    pub fn allows(&self, product_id: Option<&str>, concept_id: Option<&str>) -> bool {
+        if self.is_excluded(product_id, concept_id) {
+            return false;
+        }
+        match &self.include_products {
+            Some(included) => product_id.is_some_and(|p| included.contains(p.trim())),
+            None => true,
+        }
+    }
+
+    /// Drop disallowed items from a grid page before any detail fetch or DB write.
+    /// Returns how many were skipped.
+    pub fn retain_allowed(&self, items: &mut Vec<PsProductSummary>) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let before = items.len();
+        items.retain(|it| self.allows(it.product_id.as_deref(), it.concept_id.as_deref()));
+        before - items.len()
+    }
+}
+
+/// Ids from `KEY` plus the file named by `KEY_FILE`. An unreadable file is logged and
+/// ignored so a bad path never aborts a seed run.
+fn ids_from_env(key: &str) -> HashSet<String> {
+    let mut ids = parse_ids(&crate::util::env::env_opt(key).unwrap_or_default());
+    if let Some(path) = crate::util::env::env_opt(&format!("{key}_FILE")) {
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => ids.extend(parse_ids(&contents)),
+            Err(e) => tracing::warn!(%path, error = %e, "psstore: cannot read {key}_FILE"),
+        }
+    }
+    ids
+}
+
+fn parse_ids(raw: &str) -> HashSet<String> {
+    raw.lines()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .flat_map(|line| line.split(','))
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(product_id: Option<&str>, concept_id: Option<&str>) -> PsProductSummary {
+        serde_json::from_value(serde_json::json!({
+            "product_id": product_id,
+            "concept_id": concept_id,
+            "name": "Game",
+            "release_date": null,
+            "base_price_minor": null,
+            "discounted_price_minor": null,
+            "is_free": null,
+            "media_urls": [],
+            "media_image_urls": [],
+            "media_video_urls": [],
+            "media_images": [],
+            "media_videos": [],
+            "genres": [],
+            "average_rating": null,
+            "rating_count": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_lists_and_files_with_comments() {
+        let ids = parse_ids("UP0001-CUSA1_00-A, UP0002-CUSA2_00-B\n# known-bad\nEP0003-CUSA3_00-C # crashes detail\n\n");
+        assert_eq!(ids.len(), 3);
+        assert!(ids.contains("EP0003-CUSA3_00-C"));
+    }
+
+    #[test]
+    fn excluded_products_are_dropped_from_the_page() {
+        let filter = ProductFilter::with_excluded(&["UP0001-CUSA1_00-A"], &["10009"]);
+        let mut page = vec![
+            summary(Some("UP0001-CUSA1_00-A"), Some("10001")),
+            summary(Some("UP0002-CUSA2_00-B"), Some("10009")),
+            summary(Some("UP0003-CUSA3_00-C"), None),
+            summary(None, None),
+        ];
+        assert_eq!(filter.retain_allowed(&mut page), 2);
+        let kept: Vec<_> = page.iter().map(|it| it.product_id.clone()).collect();
+        assert_eq!(kept, vec![Some("UP0003-CUSA3_00-C".to_string()), None]);
+        assert!(filter.is_excluded(None, Some("10009")));
+    }
+
+    #[test]
+    fn include_list_keeps_only_listed_products() {
+        let filter = ProductFilter::with_excluded(&[], &["10009"])
+            .include_only(&["UP0001-CUSA1_00-A", "UP0002-CUSA2_00-B"]);
+        let mut page = vec![
+            summary(Some("UP0001-CUSA1_00-A"), Some("10001")),
+            summary(Some("UP0002-CUSA2_00-B"), Some("10009")),
+            summary(Some("UP0003-CUSA3_00-C"), Some("10003")),
+            summary(None, Some("10001")),
+        ];
+        assert_eq!(filter.retain_allowed(&mut page), 3);
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].product_id.as_deref(), Some("UP0001-CUSA1_00-A"));
+    }
+}
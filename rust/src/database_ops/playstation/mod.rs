@@ -1,7 +1,11 @@
+pub mod audit;
+pub mod cursor;
 pub mod dump_categories;
 pub mod dump_detail;
 pub mod dump_prices;
+pub mod enrichment;
 pub mod export_products;
+pub mod filter;
 pub mod genre_scan;
 pub mod ingest_demo;
 pub mod prices;
@@ -10,15 +10,16 @@ use std::{
 use tracing::{info, warn};
 
 use crate::database_ops::db::{CurrentPriceRow, Db, PriceRow};
+use crate::database_ops::external_ratings::{upsert_external_rating, RatingSource};
 use crate::database_ops::ingest_providers::{
     edition_hint_from_title_or_metadata, ensure_country, ensure_currency, ensure_game_provider,
     ensure_national_jurisdiction, ensure_platform, ensure_provider, ensure_retailer,
     ensure_video_game_source, ingest_run_finish, ingest_run_start, link_provider_offer,
-    php_compat_schema, update_video_game_display_title_and_region, update_video_game_genres,
-    update_video_game_release_date_if_null, update_video_game_synopsis_prefer_longer,
-    PostIngestSummary, ProviderEntityCache,
+    merge_video_game_metadata, php_compat_schema, update_video_game_display_title_and_region,
+    update_video_game_release_date_if_null, PostIngestSummary, ProviderEntityCache,
 };
 use crate::database_ops::media_map::MediaMap;
+use crate::normalization::display_title::TitleCandidate;
 use psstore_client::{PsConfig, PsProductSummary, PsStoreClient};
 
 const PS_STORE_PROVIDER_KEY: &str = "ps-store";
@@ -438,7 +439,63 @@ pub struct IngestSummary {
     pub current_upserts: usize,
 }
 
+/// Settings of one prices run; [`PsPricesOptions::from_env`] reads the `PS_*` variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PsPricesOptions {
+    /// Store locales such as `en-us` (`PS_STORE_REGIONS`, default `en-us`).
+    pub regions: Vec<String>,
+    /// Grid pages per category (`PS_MAX_PAGES`, default 1).
+    pub pages: u32,
+    /// `PS_PAGE_SIZE`, default 100.
+    pub page_size: u32,
+    /// `PS4_CATEGORY` / `PS5_CATEGORY`.
+    pub cat_ps4: String,
+    pub cat_ps5: String,
+    /// Client requests per second per locale (`PS_STORE_RPS`, default 3).
+    pub rps: u32,
+    /// Attempts per request, first one included (`PS_STORE_MAX_RETRIES`, default 5).
+    pub max_retries: u32,
+    /// `PS_STORE_BACKOFF_MS`, default 1500.
+    pub backoff_ms: u64,
+    /// categoryGridRetrieve hash; `None` leaves the client's env/dynamic lookup.
+    pub grid_hash: Option<String>,
+}
+
+impl PsPricesOptions {
+    pub fn from_env() -> Self {
+        use crate::util::env::env_parse_opt as parse;
+        Self {
+            regions: parse_regions(
+                &env::var("PS_STORE_REGIONS").unwrap_or_else(|_| "en-us".into()),
+            ),
+            pages: parse("PS_MAX_PAGES").unwrap_or(1),
+            page_size: parse("PS_PAGE_SIZE").unwrap_or(100),
+            cat_ps4: env::var("PS4_CATEGORY")
+                .unwrap_or_else(|_| "44d8bb20-653e-431e-8ad0-c0a365f68d2f".into()),
+            cat_ps5: env::var("PS5_CATEGORY")
+                .unwrap_or_else(|_| "4cbf39e2-5749-4970-ba81-93a489e4570c".into()),
+            rps: parse("PS_STORE_RPS").unwrap_or(3),
+            max_retries: parse("PS_STORE_MAX_RETRIES").unwrap_or(5),
+            backoff_ms: parse("PS_STORE_BACKOFF_MS").unwrap_or(1500),
+            grid_hash: None,
+        }
+    }
+}
+
+/// Comma- or space-separated locales, lowercased.
+pub fn parse_regions(raw: &str) -> Vec<String> {
+    raw.split([',', ' '])
+        .filter(|s| !s.is_empty())
+        .map(|s| s.trim().to_lowercase())
+        .collect()
+}
+
 pub async fn run_from_env() -> Result<()> {
+    run_with_options(PsPricesOptions::from_env()).await
+}
+
+/// [`run_from_env`] with the run settings passed in; the database still comes from env.
+pub async fn run_with_options(opts: PsPricesOptions) -> Result<()> {
     dotenv::dotenv().ok();
     // Pre-flight: ensure minimal config and log snapshot
     preflight_check(
@@ -468,31 +525,18 @@ pub async fn run_from_env() -> Result<()> {
         .and_then(|s| s.parse().ok())
         .unwrap_or(2025);
     println!("remember: restricting to releases between {year_min}-{year_max} inclusive\n");
-    let regions_raw = env::var("PS_STORE_REGIONS").unwrap_or_else(|_| "en-us".into());
-    let regions: Vec<String> = regions_raw
-        .split(|c: char| (c == ',' || c == ' '))
-        .filter(|s| !s.is_empty())
-        .map(|s| s.trim().to_lowercase())
-        .collect();
+    let regions = &opts.regions;
     if regions.is_empty() {
         eprintln!("No regions specified via PS_STORE_REGIONS; aborting");
         return Ok(());
     }
-    let pages: u32 = env::var("PS_MAX_PAGES")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(1);
-    let page_size: u32 = env::var("PS_PAGE_SIZE")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(100);
-    let cat_ps4 =
-        env::var("PS4_CATEGORY").unwrap_or_else(|_| "44d8bb20-653e-431e-8ad0-c0a365f68d2f".into());
-    let cat_ps5 =
-        env::var("PS5_CATEGORY").unwrap_or_else(|_| "4cbf39e2-5749-4970-ba81-93a489e4570c".into());
-    let sha = env::var("PSSTORE_SHA256")
-        .or_else(|_| env::var("PS_HASH"))
-        .unwrap_or_else(|_| {
+    let (pages, page_size) = (opts.pages, opts.page_size);
+    let sha = opts
+        .grid_hash
+        .clone()
+        .or_else(|| env::var("PSSTORE_SHA256").ok())
+        .or_else(|| env::var("PS_HASH").ok())
+        .unwrap_or_else(|| {
             "9845afc0dbaab4965f6563fffc703f588c8e76792000e8610843b8d3ee9c4c09".into()
         });
     info!(sha256 = %sha, "psstore ingest using persisted query hash override");
@@ -514,7 +558,7 @@ pub async fn run_from_env() -> Result<()> {
     )
     .await?;
 
-    let summary_res = ingest_prices(&db_url, &regions, pages, page_size, &cat_ps4, &cat_ps5).await;
+    let summary_res = ingest_prices_with_options(&db_url, &opts).await;
     let (status, items_processed, prices_written) = match &summary_res {
         Ok(s) => ("ok", s.items as i64, s.price_points as i64),
         Err(_) => ("error", 0, 0),
@@ -542,6 +586,24 @@ pub async fn ingest_prices(
     cat_ps4: &str,
     cat_ps5: &str,
 ) -> Result<IngestSummary> {
+    let opts = PsPricesOptions {
+        regions: regions.to_vec(),
+        pages,
+        page_size,
+        cat_ps4: cat_ps4.to_string(),
+        cat_ps5: cat_ps5.to_string(),
+        ..PsPricesOptions::from_env()
+    };
+    ingest_prices_with_options(db_url, &opts).await
+}
+
+pub async fn ingest_prices_with_options(
+    db_url: &str,
+    opts: &PsPricesOptions,
+) -> Result<IngestSummary> {
+    let regions = opts.regions.as_slice();
+    let (pages, page_size) = (opts.pages, opts.page_size);
+    let (cat_ps4, cat_ps5) = (opts.cat_ps4.as_str(), opts.cat_ps5.as_str());
     // Never run migrations in ingest worker
     let db = Db::connect_no_migrate(db_url, 10).await?;
     // Ensure we are writing to public.* explicitly for this session
@@ -643,23 +705,12 @@ pub async fn ingest_prices(
             ensure_national_jurisdiction(&db, country_id).await?
         };
 
-        let rps_env: u32 = env::var("PS_STORE_RPS")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(3);
-        let max_retries_env: u32 = env::var("PS_STORE_MAX_RETRIES")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(5);
-        let backoff_ms_env: u64 = env::var("PS_STORE_BACKOFF_MS")
-            .ok()
-            .and_then(|v| v.parse().ok())
-            .unwrap_or(1500);
         let cfg = PsConfig {
             locales: vec![loc.clone()],
-            rps: rps_env,
-            retry_attempts: max_retries_env,
-            retry_base_delay_ms: backoff_ms_env,
+            rps: opts.rps,
+            retry_attempts: opts.max_retries,
+            retry_base_delay_ms: opts.backoff_ms,
+            grid_hash: opts.grid_hash.clone(),
             ..PsConfig::default()
         };
 
@@ -1339,7 +1390,8 @@ impl ProductIngestor {
                 // Laravel schema: use product_id directly
                 let video_game_id = self
                     .entity_cache
-                    .ensure_video_game_for_product_laravel(
+                    .ensure_video_game_for_store_item(
+                        &external_product_id,
                         product_id,
                         &title_name,
                         Some(&product_slug),
@@ -1380,7 +1432,8 @@ impl ProductIngestor {
                 // Laravel schema: use product_id directly
                 let video_game_id = self
                     .entity_cache
-                    .ensure_video_game_for_product_laravel(
+                    .ensure_video_game_for_store_item(
+                        &external_product_id,
                         product_id,
                         &title_name,
                         Some(&product_slug),
@@ -1403,7 +1456,8 @@ impl ProductIngestor {
             // Laravel schema: use product_id directly
             let video_game_id = self
                 .entity_cache
-                .ensure_video_game_for_product_laravel(
+                .ensure_video_game_for_store_item(
+                    &external_product_id,
                     product_id,
                     &title_name,
                     Some(&product_slug),
@@ -1432,7 +1486,13 @@ impl ProductIngestor {
             summary.genres.clone()
         };
         if !genres.is_empty() {
-            let _ = update_video_game_genres(&self.db, video_game_id, &genres).await;
+            let _ = merge_video_game_metadata(
+                &self.db,
+                video_game_id,
+                PS_STORE_PROVIDER_KEY,
+                json!({ "genres": genres }),
+            )
+            .await;
         }
 
         if let Some(pid) = video_game_source_id {
@@ -1440,6 +1500,14 @@ impl ProductIngestor {
         }
 
         if let Some((avg, cnt)) = extract_rating_from_detail(detail_node) {
+            let _ = upsert_external_rating(
+                &self.db,
+                video_game_id,
+                RatingSource::PlayStation,
+                f64::from(avg),
+                Some(cnt),
+            )
+            .await;
             if ratings_conflict_supported(&self.db).await.unwrap_or(false) {
                 let _ = sqlx
                     ::query(
@@ -1478,14 +1546,20 @@ impl ProductIngestor {
         }
 
         if let Some(desc) = extract_synopsis_from_detail(detail_node) {
-            let _ = update_video_game_synopsis_prefer_longer(&self.db, video_game_id, &desc).await;
+            let _ = merge_video_game_metadata(
+                &self.db,
+                video_game_id,
+                PS_STORE_PROVIDER_KEY,
+                json!({ "synopsis": desc }),
+            )
+            .await;
         }
 
         let _ = update_video_game_display_title_and_region(
             &self.db,
             video_game_id,
-            &title_name,
-            &ctx.region_code,
+            &TitleCandidate::new(&title_name, PS_STORE_PROVIDER_KEY, Some(&ctx.locale)),
+            Some(&ctx.region_code),
         )
         .await;
 
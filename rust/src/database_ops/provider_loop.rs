@@ -0,0 +1,359 @@
+//! Shared scaffolding for the long-running provider loops of the service binary.
+//!
+//! [`run_provider_loop`] owns the drift-free ticker, the `<NAME>_RUN_ON_START` gate,
+//! shutdown handling, error logging and per-loop counters, so a provider only implements
+//! [`IngestProvider::tick`]. Every loop of the service (including the ones not built on
+//! [`IngestProvider`]) reports its runs to a shared [`ProviderMetricsRegistry`], which
+//! `/api/metrics` serves, and can be woken early through its [`ProviderWakes`] channel
+//! (`POST /api/providers/{name}/run`).
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tokio::sync::broadcast;
+use tracing::{error, info};
+
+use crate::database_ops::db::Db;
+
+/// What one provider tick did.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ProviderTickSummary {
+    /// Items (products, deals, prices, ...) the tick processed.
+    pub items: usize,
+}
+
+/// Counters kept by [`run_provider_loop`] and returned when it stops.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ProviderLoopMetrics {
+    pub ticks: u64,
+    pub failures: u64,
+    pub last_items: usize,
+    pub last_run_ms: u64,
+    pub last_error: Option<String>,
+}
+
+/// Run counters of one provider loop as served by `/api/metrics`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct ProviderMetrics {
+    /// Successful runs.
+    pub runs: u64,
+    /// Failed runs.
+    pub failures: u64,
+    pub last_run_ms: u64,
+    pub last_error: Option<String>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// Expiry of the provider's OAuth token, for providers that hold one (IGDB).
+    pub token_expires_at: Option<DateTime<Utc>>,
+}
+
+impl ProviderMetrics {
+    pub fn record_success(&mut self, elapsed: Duration) {
+        self.runs += 1;
+        self.last_run_ms = elapsed.as_millis() as u64;
+        self.last_error = None;
+        self.last_success_at = Some(Utc::now());
+    }
+
+    pub fn record_failure(&mut self, elapsed: Duration, error: &str) {
+        self.failures += 1;
+        self.last_run_ms = elapsed.as_millis() as u64;
+        self.last_error = Some(error.to_string());
+    }
+}
+
+/// Provider name to [`ProviderMetrics`], shared by every loop and the HTTP API. Locks are
+/// held only for a single update or snapshot.
+#[derive(Debug, Clone, Default)]
+pub struct ProviderMetricsRegistry(Arc<Mutex<BTreeMap<String, ProviderMetrics>>>);
+
+impl ProviderMetricsRegistry {
+    pub fn record_success(&self, provider: &str, elapsed: Duration) {
+        self.update(provider, |m| m.record_success(elapsed));
+    }
+
+    pub fn record_failure(&self, provider: &str, elapsed: Duration, error: &str) {
+        self.update(provider, |m| m.record_failure(elapsed, error));
+    }
+
+    pub fn record_token_expiry(&self, provider: &str, at: DateTime<Utc>) {
+        self.update(provider, |m| m.token_expires_at = Some(at));
+    }
+
+    /// Record a run from its outcome.
+    pub fn record<T, E: std::fmt::Display>(
+        &self,
+        provider: &str,
+        elapsed: Duration,
+        result: &std::result::Result<T, E>,
+    ) {
+        match result {
+            Ok(_) => self.record_success(provider, elapsed),
+            Err(e) => self.record_failure(provider, elapsed, &e.to_string()),
+        }
+    }
+
+    pub fn get(&self, provider: &str) -> Option<ProviderMetrics> {
+        self.lock().get(provider).cloned()
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<String, ProviderMetrics> {
+        self.lock().clone()
+    }
+
+    fn update(&self, provider: &str, f: impl FnOnce(&mut ProviderMetrics)) {
+        f(self.lock().entry(provider.to_string()).or_default());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, ProviderMetrics>> {
+        // A panic mid-update leaves plain counters behind; keep serving them.
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// One broadcast wake channel per scheduled provider. Signalling a channel makes that
+/// provider's loop run now instead of at its next interval.
+#[derive(Debug, Clone, Default)]
+pub struct ProviderWakes(Arc<BTreeMap<String, broadcast::Sender<()>>>);
+
+impl ProviderWakes {
+    pub fn new<S: AsRef<str>>(providers: &[S]) -> Self {
+        let channels = providers
+            .iter()
+            .map(|name| (name.as_ref().to_string(), broadcast::channel(16).0))
+            .collect();
+        Self(Arc::new(channels))
+    }
+
+    pub fn sender(&self, provider: &str) -> Option<broadcast::Sender<()>> {
+        self.0.get(provider).cloned()
+    }
+
+    /// `None` when `provider` has no channel; such a loop only runs on its interval.
+    pub fn subscribe(&self, provider: &str) -> Option<broadcast::Receiver<()>> {
+        self.0.get(provider).map(broadcast::Sender::subscribe)
+    }
+
+    /// Signal `provider`'s loop; false when no such provider is registered.
+    pub fn wake(&self, provider: &str) -> bool {
+        match self.0.get(provider) {
+            Some(tx) => {
+                let _ = tx.send(());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Wait until `provider` should run again: at once when wakes arrived during the last run
+/// (coalesced into a single run), otherwise at the next tick or wake. Returns false when
+/// `shutdown_rx` fires first.
+pub async fn wait_for_next_run(
+    provider: &str,
+    ticker: &mut tokio::time::Interval,
+    wake_rx: &mut Option<broadcast::Receiver<()>>,
+    shutdown_rx: &mut broadcast::Receiver<()>,
+) -> bool {
+    if let Some(rx) = wake_rx.as_mut() {
+        let mut wakes = 0u32;
+        while matches!(
+            rx.try_recv(),
+            Ok(()) | Err(broadcast::error::TryRecvError::Lagged(_))
+        ) {
+            wakes = wakes.saturating_add(1);
+        }
+        if wakes > 0 {
+            info!(
+                provider,
+                wakes, "coalesced wake(s) received; running again immediately"
+            );
+            return true;
+        }
+    }
+    tokio::select! {
+        _ = ticker.tick() => {
+            info!(provider, "next tick");
+        }
+        _ = recv_wake(wake_rx) => {
+            info!(provider, "wake signal received");
+        }
+        _ = shutdown_rx.recv() => {
+            info!(provider, "shutdown");
+            return false;
+        }
+    }
+    true
+}
+
+/// Resolves on the next wake; never when there is no (or no longer a) wake channel.
+async fn recv_wake(wake_rx: &mut Option<broadcast::Receiver<()>>) {
+    loop {
+        let Some(rx) = wake_rx.as_mut() else {
+            return std::future::pending().await;
+        };
+        match rx.recv().await {
+            Ok(()) | Err(broadcast::error::RecvError::Lagged(_)) => return,
+            Err(broadcast::error::RecvError::Closed) => *wake_rx = None,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait IngestProvider: Send + Sync {
+    /// Lowercase loop name; also the `<NAME>_RUN_ON_START` env prefix, uppercased.
+    fn name(&self) -> &str;
+    async fn tick(&self, db: &Db) -> Result<ProviderTickSummary>;
+    fn interval(&self) -> Duration;
+}
+
+/// Tick `provider` every [`IngestProvider::interval`], or earlier when its channel in
+/// `wakes` is signalled, until `shutdown_rx` fires. The first tick runs immediately unless
+/// `<NAME>_RUN_ON_START=0`. A failed tick is logged and counted (also in `registry`); the
+/// loop carries on with the next interval.
+pub async fn run_provider_loop<P: IngestProvider + ?Sized>(
+    provider: &P,
+    db: &Db,
+    registry: &ProviderMetricsRegistry,
+    wakes: &ProviderWakes,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> ProviderLoopMetrics {
+    let name = provider.name();
+    let mut metrics = ProviderLoopMetrics::default();
+    let mut wake_rx = wakes.subscribe(name);
+    let mut ticker = tokio::time::interval(provider.interval());
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    ticker.tick().await;
+
+    let run_on_start = format!("{}_RUN_ON_START", name.to_ascii_uppercase());
+    if !crate::util::env::env_flag(&run_on_start, true) {
+        info!(
+            provider = name,
+            "run-on-start disabled; waiting for first interval"
+        );
+        if !wait_for_next_run(name, &mut ticker, &mut wake_rx, &mut shutdown_rx).await {
+            return metrics;
+        }
+    }
+
+    loop {
+        info!(provider = name, "tick");
+        let started = Instant::now();
+        metrics.ticks += 1;
+        let result = provider.tick(db).await;
+        registry.record(name, started.elapsed(), &result);
+        match result {
+            Ok(summary) => {
+                metrics.last_items = summary.items;
+                metrics.last_error = None;
+            }
+            Err(e) => {
+                metrics.failures += 1;
+                metrics.last_error = Some(e.to_string());
+                error!(provider = name, error = %e, failures = metrics.failures, "provider tick failed");
+            }
+        }
+        metrics.last_run_ms = started.elapsed().as_millis() as u64;
+
+        if !wait_for_next_run(name, &mut ticker, &mut wake_rx, &mut shutdown_rx).await {
+            break;
+        }
+    }
+    metrics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    struct Flaky {
+        interval: Duration,
+        calls: AtomicU64,
+    }
+
+    #[async_trait::async_trait]
+    impl IngestProvider for Flaky {
+        fn name(&self) -> &str {
+            "flaky_test_loop"
+        }
+        async fn tick(&self, _db: &Db) -> Result<ProviderTickSummary> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) % 2 == 0 {
+                anyhow::bail!("upstream unavailable");
+            }
+            Ok(ProviderTickSummary { items: 3 })
+        }
+        fn interval(&self) -> Duration {
+            self.interval
+        }
+    }
+
+    fn lazy_db() -> Db {
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://loop@127.0.0.1:1/none")
+            .unwrap();
+        Db { pool }
+    }
+
+    #[tokio::test]
+    async fn loop_counts_failures_and_stops_on_shutdown() {
+        let db = lazy_db();
+        let provider = Flaky {
+            interval: Duration::from_millis(10),
+            calls: AtomicU64::new(0),
+        };
+        let (tx, rx) = broadcast::channel(1);
+        let stop = async {
+            tokio::time::sleep(Duration::from_millis(55)).await;
+            tx.send(()).unwrap();
+        };
+        let registry = ProviderMetricsRegistry::default();
+        let wakes = ProviderWakes::default();
+        let (metrics, ()) = tokio::join!(
+            run_provider_loop(&provider, &db, &registry, &wakes, rx),
+            stop
+        );
+
+        let ticks = provider.calls.load(Ordering::SeqCst);
+        assert!(ticks >= 3, "ticks={ticks}");
+        assert_eq!(metrics.ticks, ticks);
+        assert_eq!(metrics.failures, ticks.div_ceil(2));
+        assert_eq!(metrics.last_error.is_some(), ticks % 2 == 1);
+        let recorded = registry.get("flaky_test_loop").unwrap();
+        assert_eq!(recorded.failures, metrics.failures);
+        assert_eq!(recorded.runs, ticks - metrics.failures);
+        assert_eq!(recorded.last_error, metrics.last_error);
+        assert!(recorded.last_success_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn shutdown_interrupts_a_long_interval() {
+        let db = lazy_db();
+        let provider = Flaky {
+            interval: Duration::from_secs(3600),
+            calls: AtomicU64::new(1),
+        };
+        let (tx, rx) = broadcast::channel(1);
+        tx.send(()).unwrap();
+        let metrics = tokio::time::timeout(
+            Duration::from_secs(5),
+            run_provider_loop(
+                &provider,
+                &db,
+                &ProviderMetricsRegistry::default(),
+                &ProviderWakes::default(),
+                rx,
+            ),
+        )
+        .await
+        .expect("loop should stop on shutdown");
+
+        assert_eq!(metrics.ticks, 1);
+        assert_eq!(metrics.failures, 0);
+        assert_eq!(metrics.last_items, 3);
+    }
+}
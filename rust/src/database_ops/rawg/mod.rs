@@ -7,32 +7,19 @@ use std::time::Duration;
 use tracing::{debug, info, warn};
 
 use crate::database_ops::db::Db;
+use crate::database_ops::external_ratings::{upsert_external_rating, RatingSource};
 use crate::database_ops::ingest_providers::{
     ensure_vg_source_media_links_with_meta, extract_normalized_rating_from_payload,
     upsert_game_media_batch,
 };
+use crate::database_ops::schema_caps::SchemaCaps;
 
 async fn table_exists(db: &Db, table: &str) -> Result<bool> {
-    let exists: Option<bool> = sqlx::query_scalar(
-        "SELECT TRUE FROM information_schema.tables WHERE table_schema = ANY (current_schemas(true)) AND table_name = $1 LIMIT 1",
-    )
-    .persistent(false)
-    .bind(table)
-    .fetch_optional(&db.pool)
-    .await?;
-    Ok(exists.unwrap_or(false))
+    SchemaCaps::global().table_visible(db, table).await
 }
 
 async fn column_exists(db: &Db, table: &str, column: &str) -> Result<bool> {
-    let exists: Option<bool> = sqlx::query_scalar(
-        "SELECT TRUE FROM information_schema.columns WHERE table_schema = ANY (current_schemas(true)) AND table_name = $1 AND column_name = $2 LIMIT 1",
-    )
-    .persistent(false)
-    .bind(table)
-    .bind(column)
-    .fetch_optional(&db.pool)
-    .await?;
-    Ok(exists.unwrap_or(false))
+    SchemaCaps::global().column_visible(db, table, column).await
 }
 
 #[derive(Debug, Deserialize)]
@@ -332,13 +319,65 @@ async fn persist_rawg_media(
     }
 }
 
+/// Settings of one RAWG sync; [`RawgSyncOptions::from_env`] reads the `RAWG_*` variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawgSyncOptions {
+    /// `None` reads `RAWG_API_KEY`.
+    pub api_key: Option<String>,
+    /// `range`, `top_monthly`, `top_genres` or `all` (`RAWG_MODE`, default `range`).
+    pub mode: String,
+    /// Release years of a `range` run, inclusive (`RAWG_YEAR_MIN`/`RAWG_YEAR_MAX`, default
+    /// 2015..=2025).
+    pub year_min: i32,
+    pub year_max: i32,
+    /// Games per page of a `range` run (`RAWG_PAGE_SIZE`, default 40).
+    pub page_size: u32,
+    /// Fetch each game's detail in a `range` run (`RAWG_FETCH_DETAILS`).
+    pub fetch_details: bool,
+    /// `RAWG_REQS_PER_MIN`; unset paces range runs at 60 and top lists at 30.
+    pub reqs_per_min: Option<u64>,
+    /// Fixed pause between requests, overriding the rate (`RAWG_SLEEP_MS_OVERRIDE`).
+    pub sleep_ms: Option<u64>,
+}
+
+impl RawgSyncOptions {
+    pub fn from_env() -> Self {
+        use crate::util::env::env_parse_opt as parse;
+        Self {
+            api_key: None,
+            mode: std::env::var("RAWG_MODE").unwrap_or_else(|_| "range".to_string()),
+            year_min: parse("RAWG_YEAR_MIN").unwrap_or(2015),
+            year_max: parse("RAWG_YEAR_MAX").unwrap_or(2025),
+            page_size: parse("RAWG_PAGE_SIZE").unwrap_or(40),
+            fetch_details: std::env::var("RAWG_FETCH_DETAILS")
+                .ok()
+                .map(|s| s == "1" || s.eq_ignore_ascii_case("true"))
+                .unwrap_or(false),
+            reqs_per_min: parse("RAWG_REQS_PER_MIN"),
+            sleep_ms: parse("RAWG_SLEEP_MS_OVERRIDE"),
+        }
+    }
+
+    fn key(&self) -> String {
+        self.api_key
+            .clone()
+            .or_else(|| std::env::var("RAWG_API_KEY").ok())
+            .unwrap_or_default()
+    }
+
+    /// Pause before each request, given the rate used when none is configured.
+    fn sleep_ms(&self, default_reqs_per_min: u64) -> u64 {
+        if let Some(ms) = self.sleep_ms {
+            return ms;
+        }
+        let rpm = self.reqs_per_min.unwrap_or(default_reqs_per_min);
+        60_000u64.checked_div(rpm).map_or(0, |ms| ms.max(1))
+    }
+}
+
 /// Ingest RAWG games within [year_min, year_max] descending, respecting simple RPM pacing.
-pub async fn ingest_range(
-    db: &Db,
-    api_key: Option<String>,
-    year_min: i32,
-    year_max: i32,
-) -> Result<()> {
+pub async fn ingest_range(db: &Db, opts: &RawgSyncOptions) -> Result<()> {
+    let (year_min, year_max) = (opts.year_min, opts.year_max);
     use crate::database_ops::ingest_providers::{
         ensure_platform, ensure_product_named, ensure_provider, ensure_provider_item,
         ensure_retailer, ensure_software_row, ensure_video_game, ensure_video_game_for_product,
@@ -349,17 +388,8 @@ pub async fn ingest_range(
 
     let provider_id = ensure_provider(db, "rawg", "catalog", Some("rawg")).await?;
     let client = reqwest::Client::new();
-    let key = api_key
-        .or_else(|| std::env::var("RAWG_API_KEY").ok())
-        .unwrap_or_default();
-    let rpm: u64 = std::env::var("RAWG_REQS_PER_MIN")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(60);
-    let page_size: u32 = std::env::var("RAWG_PAGE_SIZE")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(40);
+    let key = opts.key();
+    let page_size = opts.page_size;
     let max_pages: u32 = std::env::var("RAWG_MAX_PAGES")
         .ok()
         .and_then(|s| s.parse().ok())
@@ -368,12 +398,9 @@ pub async fn ingest_range(
         .ok()
         .and_then(|s| s.parse().ok())
         .unwrap_or(0);
-    let fetch_details: bool = std::env::var("RAWG_FETCH_DETAILS")
-        .ok()
-        .map(|s| (s == "1" || s.eq_ignore_ascii_case("true")))
-        .unwrap_or(false);
+    let fetch_details = opts.fetch_details;
     let max_screenshots = max_screenshots_per_game();
-    let sleep_ms = if rpm > 0 { (60_000u64 / rpm).max(1) } else { 0 };
+    let sleep_ms = opts.sleep_ms(60);
 
     let provider_items_exist = table_exists(db, "provider_items").await.unwrap_or(false);
     let video_games_catalog_schema = column_exists(db, "video_games", "title_id")
@@ -417,7 +444,7 @@ pub async fn ingest_range(
         provider_id,
         year_min,
         year_max,
-        rpm,
+        sleep_ms,
         page_size,
         max_pages,
         max_items,
@@ -601,6 +628,18 @@ pub async fn ingest_range(
                         }
                     }
                 }
+                if let Some(score) = detail_data.as_ref().and_then(|d| d.metacritic) {
+                    for vg in &video_game_ids {
+                        let _ = upsert_external_rating(
+                            db,
+                            *vg,
+                            RatingSource::Metacritic,
+                            f64::from(score),
+                            None,
+                        )
+                        .await;
+                    }
+                }
 
                 let mut provider_meta = Map::new();
                 provider_meta.insert("rawg_id".into(), json!(row.id));
@@ -733,6 +772,7 @@ pub async fn ingest_range(
                     let _ = merge_video_game_metadata(
                         db,
                         vg_id,
+                        "rawg",
                         json!({
                             "sources": { "rawg": provider_item_meta.clone() },
                             "rawg_numeric": row.id,
@@ -790,7 +830,7 @@ pub async fn ingest_range(
 /// Fetch and ingest top N games from a specific date range with ordering
 async fn ingest_top_games(
     db: &Db,
-    api_key: &str,
+    opts: &RawgSyncOptions,
     date_start: &str,
     date_end: &str,
     genre: Option<&str>,
@@ -808,11 +848,8 @@ async fn ingest_top_games(
 
     let provider_id = ensure_provider(db, "rawg", "catalog", Some("rawg")).await?;
     let client = reqwest::Client::new();
-    let rpm: u64 = std::env::var("RAWG_REQS_PER_MIN")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(30);
-    let sleep_ms = if rpm > 0 { (60_000u64 / rpm).max(1) } else { 0 };
+    let key = opts.key();
+    let sleep_ms = opts.sleep_ms(30);
     let max_screenshots = max_screenshots_per_game();
 
     let provider_items_exist = table_exists(db, "provider_items").await.unwrap_or(false);
@@ -853,7 +890,7 @@ async fn ingest_top_games(
 
     let mut url = format!(
         "https://api.rawg.io/api/games?dates={},{}&ordering={}&page_size={}&key={}",
-        date_start, date_end, ordering, limit, api_key
+        date_start, date_end, ordering, limit, key
     );
     if let Some(g) = genre {
         url.push_str(&format!("&genres={}", g));
@@ -951,7 +988,7 @@ async fn ingest_top_games(
         };
 
         // Always fetch details for top games to get retailer info
-        let detail_data = fetch_game_detail(&client, row.id, api_key, sleep_ms).await?;
+        let detail_data = fetch_game_detail(&client, row.id, &key, sleep_ms).await?;
 
         let platform_entries = row
             .platforms
@@ -1009,6 +1046,18 @@ async fn ingest_top_games(
                 }
             }
         }
+        if let Some(score) = detail_data.as_ref().and_then(|d| d.metacritic) {
+            for vg in &video_game_ids {
+                let _ = upsert_external_rating(
+                    db,
+                    *vg,
+                    RatingSource::Metacritic,
+                    f64::from(score),
+                    None,
+                )
+                .await;
+            }
+        }
 
         let mut provider_meta = Map::new();
         provider_meta.insert("rawg_id".into(), json!(row.id));
@@ -1137,6 +1186,7 @@ async fn ingest_top_games(
             let _ = merge_video_game_metadata(
                 db,
                 vg_id,
+                "rawg",
                 json!({
                     "sources": { "rawg": provider_item_meta.clone() },
                     "rawg_numeric": row.id,
@@ -1204,11 +1254,7 @@ async fn ingest_top_games(
 }
 
 /// Fetch top games of the month
-pub async fn ingest_top_monthly(db: &Db, api_key: Option<String>) -> Result<u64> {
-    let key = api_key
-        .or_else(|| std::env::var("RAWG_API_KEY").ok())
-        .unwrap_or_default();
-
+pub async fn ingest_top_monthly(db: &Db, opts: &RawgSyncOptions) -> Result<u64> {
     // Get current month date range
     let now = chrono::Utc::now().naive_utc().date();
     let start_of_month = chrono::NaiveDate::from_ymd_opt(now.year(), now.month(), 1)
@@ -1240,15 +1286,11 @@ pub async fn ingest_top_monthly(db: &Db, api_key: Option<String>) -> Result<u64>
         date_start,
         date_end, limit, "RAWG ingesting top games of the month"
     );
-    ingest_top_games(db, &key, &date_start, &date_end, None, "-rating", limit).await
+    ingest_top_games(db, opts, &date_start, &date_end, None, "-rating", limit).await
 }
 
 /// Fetch top games by genre
-pub async fn ingest_top_by_genre(db: &Db, api_key: Option<String>, genre: &str) -> Result<u64> {
-    let key = api_key
-        .or_else(|| std::env::var("RAWG_API_KEY").ok())
-        .unwrap_or_default();
-
+pub async fn ingest_top_by_genre(db: &Db, opts: &RawgSyncOptions, genre: &str) -> Result<u64> {
     // Use 2025 as the year range
     let date_start = "2025-01-01";
     let date_end = "2025-12-31";
@@ -1266,7 +1308,7 @@ pub async fn ingest_top_by_genre(db: &Db, api_key: Option<String>, genre: &str)
     info!(genre, limit, "RAWG ingesting top games by genre");
     ingest_top_games(
         db,
-        &key,
+        opts,
         date_start,
         date_end,
         Some(genre),
@@ -1278,6 +1320,15 @@ pub async fn ingest_top_by_genre(db: &Db, api_key: Option<String>, genre: &str)
 
 /// Minimal sync shim
 pub async fn sync(db: &Db, api_key: Option<String>) -> Result<()> {
+    let opts = RawgSyncOptions {
+        api_key,
+        ..RawgSyncOptions::from_env()
+    };
+    sync_with_options(db, &opts).await
+}
+
+/// [`sync`] with every setting passed in rather than read from env.
+pub async fn sync_with_options(db: &Db, opts: &RawgSyncOptions) -> Result<()> {
     // Check for required schema tables (legacy-safe)
     let required_tables = [
         "platforms",
@@ -1304,11 +1355,9 @@ pub async fn sync(db: &Db, api_key: Option<String>) -> Result<()> {
         return Ok(());
     }
 
-    let mode = std::env::var("RAWG_MODE").unwrap_or_else(|_| "range".to_string());
-
-    match mode.as_str() {
+    match opts.mode.as_str() {
         "top_monthly" => {
-            ingest_top_monthly(db, api_key).await?;
+            ingest_top_monthly(db, opts).await?;
             Ok(())
         }
         "top_genres" => {
@@ -1327,7 +1376,7 @@ pub async fn sync(db: &Db, api_key: Option<String>) -> Result<()> {
                 .unwrap_or_else(|| vec!["4".into(), "15".into(), "2".into()]);
 
             for genre_id in genre_ids {
-                match ingest_top_by_genre(db, api_key.clone(), &genre_id).await {
+                match ingest_top_by_genre(db, opts, &genre_id).await {
                     Ok(count) => {
                         info!(genre = %genre_id, count, "ingested top games");
                     }
@@ -1340,7 +1389,7 @@ pub async fn sync(db: &Db, api_key: Option<String>) -> Result<()> {
         }
         "all" => {
             // Run all modes
-            ingest_top_monthly(db, api_key.clone()).await?;
+            ingest_top_monthly(db, opts).await?;
 
             let genre_ids: Vec<String> = std::env::var("RAWG_TOP_GENRES")
                 .ok()
@@ -1354,7 +1403,7 @@ pub async fn sync(db: &Db, api_key: Option<String>) -> Result<()> {
                 .unwrap_or_else(|| vec!["4".into(), "15".into(), "2".into()]);
 
             for genre_id in genre_ids {
-                match ingest_top_by_genre(db, api_key.clone(), &genre_id).await {
+                match ingest_top_by_genre(db, opts, &genre_id).await {
                     Ok(count) => {
                         info!(genre = %genre_id, count, "ingested top games");
                     }
@@ -1367,15 +1416,7 @@ pub async fn sync(db: &Db, api_key: Option<String>) -> Result<()> {
         }
         _ => {
             // Default range mode
-            let y_min: i32 = std::env::var("RAWG_YEAR_MIN")
-                .ok()
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(2015);
-            let y_max: i32 = std::env::var("RAWG_YEAR_MAX")
-                .ok()
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(2025);
-            ingest_range(db, api_key, y_min, y_max).await
+            ingest_range(db, opts).await
         }
     }
 }
@@ -0,0 +1,384 @@
+//! Process-wide cache of schema capability probes.
+//!
+//! Seed and sync pipelines check the same handful of tables and columns on every run
+//! (`to_regclass` / `pg_attribute` lookups). [`SchemaCaps::global`] answers each probe from
+//! the database once and then from memory. Entries are dropped by
+//! [`SchemaCaps::invalidate`] (the migration runner calls it after applying migrations) or,
+//! when `SCHEMA_CAPS_TTL_SECS` is set, once they are older than that many seconds. Negative
+//! answers also expire after `SCHEMA_CAPS_NEGATIVE_TTL_SECS` (default 60), so a table created
+//! while the service runs is picked up without a restart. Failed probes are never cached.
+//!
+//! [`SchemaCaps::check`] verifies a whole list of [`SchemaRequirement`]s at once, for
+//! pipelines that must not start writing into a partial schema.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tracing::info;
+
+use crate::database_ops::db::Db;
+
+/// Tables the ingest pipelines require; probed once when the service starts.
+pub const INGEST_TABLES: &[&str] = &[
+    "platforms",
+    "providers",
+    "provider_items",
+    "video_game_sources",
+    "video_game_titles",
+    "video_games",
+];
+
+/// One thing a pipeline needs from the schema before it may write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaRequirement {
+    Table(&'static str),
+    /// At least one of the tables, for writes that fall back from one to the other.
+    AnyTable(&'static [&'static str]),
+    /// A unique, non-partial index on exactly `columns`, i.e. a valid ON CONFLICT target.
+    UniqueKey {
+        table: &'static str,
+        columns: &'static [&'static str],
+    },
+}
+
+/// Requirements [`SchemaCaps::check`] found unmet.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaGaps {
+    pub missing_tables: Vec<String>,
+    /// `table(col, ...)` per missing unique key.
+    pub missing_unique_keys: Vec<String>,
+}
+
+impl SchemaGaps {
+    pub fn is_empty(&self) -> bool {
+        self.missing_tables.is_empty() && self.missing_unique_keys.is_empty()
+    }
+}
+
+impl std::fmt::Display for SchemaGaps {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if !self.missing_tables.is_empty() {
+            parts.push(format!(
+                "missing tables: {}",
+                self.missing_tables.join(", ")
+            ));
+        }
+        if !self.missing_unique_keys.is_empty() {
+            parts.push(format!(
+                "missing unique keys: {}",
+                self.missing_unique_keys.join(", ")
+            ));
+        }
+        f.write_str(&parts.join("; "))
+    }
+}
+
+/// `(table, column)`; `column` is `None` for a table visibility probe.
+type CapKey = (String, Option<String>);
+
+/// How long a "not there" answer is trusted by default.
+const DEFAULT_NEGATIVE_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+pub struct SchemaCaps {
+    ttl: Option<Duration>,
+    negative_ttl: Duration,
+    entries: Mutex<HashMap<CapKey, (bool, Instant)>>,
+}
+
+impl SchemaCaps {
+    /// Cache whose entries expire after `ttl` (`None`: never); negative entries expire after
+    /// [`DEFAULT_NEGATIVE_TTL`] at most.
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl,
+            negative_ttl: DEFAULT_NEGATIVE_TTL,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_negative_ttl(mut self, negative_ttl: Duration) -> Self {
+        self.negative_ttl = negative_ttl;
+        self
+    }
+
+    /// The shared cache; its TTL is read from `SCHEMA_CAPS_TTL_SECS` on first use (unset or
+    /// 0: entries live until invalidated) and the negative one from
+    /// `SCHEMA_CAPS_NEGATIVE_TTL_SECS`.
+    pub fn global() -> &'static SchemaCaps {
+        static CAPS: OnceLock<SchemaCaps> = OnceLock::new();
+        CAPS.get_or_init(|| {
+            let ttl = crate::util::env::env_parse::<u64>("SCHEMA_CAPS_TTL_SECS", 0);
+            let negative_ttl = crate::util::env::env_parse::<u64>(
+                "SCHEMA_CAPS_NEGATIVE_TTL_SECS",
+                DEFAULT_NEGATIVE_TTL.as_secs(),
+            );
+            Self::new((ttl > 0).then(|| Duration::from_secs(ttl)))
+                .with_negative_ttl(Duration::from_secs(negative_ttl))
+        })
+    }
+
+    /// Whether `table` resolves through the connection's search_path.
+    pub async fn table_visible(&self, db: &Db, table: &str) -> Result<bool> {
+        self.cached((table.to_string(), None), || query_table_visible(db, table))
+            .await
+    }
+
+    /// Whether the search_path-visible `table` has a live `column`.
+    pub async fn column_visible(&self, db: &Db, table: &str, column: &str) -> Result<bool> {
+        self.cached((table.to_string(), Some(column.to_string())), || {
+            query_column_visible(db, table, column)
+        })
+        .await
+    }
+
+    /// Warm the cache for `tables` (typically at startup); returns the ones not visible.
+    pub async fn probe<'a>(&self, db: &Db, tables: &[&'a str]) -> Result<Vec<&'a str>> {
+        let mut missing = Vec::new();
+        for &table in tables {
+            if !self.table_visible(db, table).await? {
+                missing.push(table);
+            }
+        }
+        info!(
+            probed = tables.len(),
+            missing = ?missing,
+            "schema capabilities cached"
+        );
+        Ok(missing)
+    }
+
+    /// Every unmet entry of `requirements`. A unique key on a missing table is reported as
+    /// the missing table, and an unmet [`SchemaRequirement::AnyTable`] as `a|b`. Key
+    /// lookups are not cached.
+    pub async fn check(&self, db: &Db, requirements: &[SchemaRequirement]) -> Result<SchemaGaps> {
+        let mut gaps = SchemaGaps::default();
+        for requirement in requirements {
+            let (table, columns) = match *requirement {
+                SchemaRequirement::Table(table) => (table, None),
+                SchemaRequirement::AnyTable(tables) => {
+                    let mut any = false;
+                    for table in tables {
+                        if self.table_visible(db, table).await? {
+                            any = true;
+                            break;
+                        }
+                    }
+                    if !any {
+                        gaps.missing_tables.push(tables.join("|"));
+                    }
+                    continue;
+                }
+                SchemaRequirement::UniqueKey { table, columns } => (table, Some(columns)),
+            };
+            if !self.table_visible(db, table).await? {
+                if !gaps.missing_tables.iter().any(|t| t == table) {
+                    gaps.missing_tables.push(table.to_string());
+                }
+                continue;
+            }
+            if let Some(columns) = columns {
+                if !unique_key_exists(db, table, columns).await? {
+                    gaps.missing_unique_keys
+                        .push(format!("{table}({})", columns.join(", ")));
+                }
+            }
+        }
+        Ok(gaps)
+    }
+
+    /// Forget every cached answer; the next lookup of each probe queries the database.
+    pub fn invalidate(&self) {
+        self.lock().clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    async fn cached<F, Fut>(&self, key: CapKey, probe: F) -> Result<bool>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<bool>>,
+    {
+        if let Some(&(value, at)) = self.lock().get(&key) {
+            let ttl = if value {
+                self.ttl
+            } else {
+                Some(self.negative_ttl.min(self.ttl.unwrap_or(Duration::MAX)))
+            };
+            if ttl.is_none_or(|ttl| at.elapsed() < ttl) {
+                return Ok(value);
+            }
+        }
+        let value = probe().await?;
+        self.lock().insert(key, (value, Instant::now()));
+        Ok(value)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<CapKey, (bool, Instant)>> {
+        self.entries.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+/// Whether `table` (as resolved by search_path) has a unique, non-partial index on exactly
+/// `columns`, in any order.
+pub async fn unique_key_exists(db: &Db, table: &str, columns: &[&str]) -> Result<bool> {
+    let present: bool = sqlx::query_scalar(
+        "SELECT EXISTS (
+             SELECT 1 FROM pg_index i
+             WHERE i.indrelid = to_regclass($1)
+               AND i.indisunique
+               AND i.indpred IS NULL
+               AND i.indnkeyatts = cardinality($2::text[])
+               AND (
+                   SELECT array_agg(a.attname::text ORDER BY a.attname::text)
+                   FROM pg_attribute a
+                   WHERE a.attrelid = i.indrelid AND a.attnum = ANY(i.indkey)
+               ) = (SELECT array_agg(c ORDER BY c) FROM unnest($2::text[]) AS c)
+         )",
+    )
+    .persistent(false)
+    .bind(table)
+    .bind(columns)
+    .fetch_one(&db.pool)
+    .await?;
+    Ok(present)
+}
+
+async fn query_table_visible(db: &Db, table: &str) -> Result<bool> {
+    let visible: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
+        .persistent(false)
+        .bind(table)
+        .fetch_one(&db.pool)
+        .await?;
+    Ok(visible)
+}
+
+async fn query_column_visible(db: &Db, table: &str, column: &str) -> Result<bool> {
+    let exists: Option<bool> = sqlx::query_scalar(
+        "SELECT TRUE FROM pg_attribute
+         WHERE attrelid = to_regclass($1) AND attname = $2
+           AND attnum > 0 AND NOT attisdropped
+         LIMIT 1",
+    )
+    .persistent(false)
+    .bind(table)
+    .bind(column)
+    .fetch_optional(&db.pool)
+    .await?;
+    Ok(exists.unwrap_or(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    async fn counted(queries: &AtomicU32, answer: bool) -> Result<bool> {
+        queries.fetch_add(1, Ordering::SeqCst);
+        Ok(answer)
+    }
+
+    async fn unreachable_db() -> Result<bool> {
+        anyhow::bail!("connection reset")
+    }
+
+    #[tokio::test]
+    async fn probes_are_cached_until_invalidated() {
+        let caps = SchemaCaps::new(None);
+        let queries = AtomicU32::new(0);
+        let probe = |answer: bool| {
+            let queries = &queries;
+            move || counted(queries, answer)
+        };
+        let key = || ("provider_items".to_string(), None);
+
+        assert!(caps.cached(key(), probe(true)).await.unwrap());
+        assert!(caps.cached(key(), probe(false)).await.unwrap());
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+
+        // Failures are not cached.
+        let column = || ("video_games".to_string(), Some("title_id".to_string()));
+        assert!(caps.cached(column(), unreachable_db).await.is_err());
+        assert_eq!(caps.len(), 1);
+
+        caps.invalidate();
+        assert!(caps.is_empty());
+        assert!(!caps.cached(key(), probe(false)).await.unwrap());
+        assert!(caps.cached(column(), probe(true)).await.unwrap());
+        assert_eq!(queries.load(Ordering::SeqCst), 3);
+
+        // A warm entry answers without touching the (unreachable) database.
+        let db = Db {
+            pool: sqlx::postgres::PgPoolOptions::new()
+                .connect_lazy("postgres://caps@127.0.0.1:1/none")
+                .unwrap(),
+        };
+        assert!(!caps.table_visible(&db, "provider_items").await.unwrap());
+        assert!(caps
+            .column_visible(&db, "video_games", "title_id")
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_probed_again() {
+        let caps = SchemaCaps::new(Some(Duration::from_millis(20)));
+        let queries = AtomicU32::new(0);
+        let key = || ("video_game_titles".to_string(), None);
+        assert!(caps
+            .cached(key(), || counted(&queries, true))
+            .await
+            .unwrap());
+        assert!(caps
+            .cached(key(), || counted(&queries, false))
+            .await
+            .unwrap());
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(!caps
+            .cached(key(), || counted(&queries, false))
+            .await
+            .unwrap());
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn missing_tables_are_probed_again_after_the_negative_ttl() {
+        let caps = SchemaCaps::new(None).with_negative_ttl(Duration::from_millis(20));
+        let queries = AtomicU32::new(0);
+        let missing = || ("offer_jurisdictions".to_string(), None);
+        let present = || ("video_games".to_string(), None);
+        assert!(!caps
+            .cached(missing(), || counted(&queries, false))
+            .await
+            .unwrap());
+        assert!(caps
+            .cached(present(), || counted(&queries, true))
+            .await
+            .unwrap());
+        assert!(!caps
+            .cached(missing(), || counted(&queries, true))
+            .await
+            .unwrap());
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        // The table was created meanwhile; positive entries still never expire.
+        assert!(caps
+            .cached(missing(), || counted(&queries, true))
+            .await
+            .unwrap());
+        assert!(caps
+            .cached(present(), || counted(&queries, false))
+            .await
+            .unwrap());
+        assert_eq!(queries.load(Ordering::SeqCst), 3);
+    }
+}
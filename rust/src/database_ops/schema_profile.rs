@@ -0,0 +1,181 @@
+//! Which schema family the target database follows.
+//!
+//! Two shapes are supported: the service's own (`Native`: `retailers`,
+//! `offer_jurisdictions`, `currencies.minor_unit`, ...) and the Laravel app's
+//! (`PhpCompat`: `game_retailers`, `sku_regions` + `region_prices`,
+//! `currencies.decimals`).
+//! The profile is detected once per process from which tables are visible, or forced with
+//! `SCHEMA_PROFILE=native|php-compat` (`auto`, the default, detects). Code that needs a
+//! table or column whose name depends on the family asks the profile instead of probing.
+
+use anyhow::Result;
+use tokio::sync::OnceCell;
+use tracing::{info, warn};
+
+use crate::database_ops::db::Db;
+use crate::database_ops::schema_caps::SchemaCaps;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemaProfile {
+    #[default]
+    Native,
+    PhpCompat,
+}
+
+static PROFILE: OnceCell<SchemaProfile> = OnceCell::const_new();
+
+impl SchemaProfile {
+    /// `SCHEMA_PROFILE` value; `None` means detect.
+    pub fn parse_override(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "native" => Some(Self::Native),
+            "php-compat" | "php" | "laravel" => Some(Self::PhpCompat),
+            "" | "auto" => None,
+            _ => {
+                warn!(value = raw, "SCHEMA_PROFILE: unrecognized value; detecting");
+                None
+            }
+        }
+    }
+
+    /// Profile of a schema in which `visible(table)` tells whether a table resolves.
+    pub fn from_visible_tables(visible: impl Fn(&str) -> bool) -> Self {
+        if visible("sku_regions") && visible("region_prices") {
+            Self::PhpCompat
+        } else {
+            Self::Native
+        }
+    }
+
+    /// Probe the database; see [`from_visible_tables`](Self::from_visible_tables).
+    pub async fn detect(db: &Db) -> Result<Self> {
+        let caps = SchemaCaps::global();
+        let mut visible = std::collections::HashSet::new();
+        for table in ["sku_regions", "region_prices", "countries", "jurisdictions"] {
+            if caps.table_visible(db, table).await? {
+                visible.insert(table);
+            }
+        }
+        let profile = Self::from_visible_tables(|t| visible.contains(t));
+        if profile == Self::PhpCompat
+            && !(visible.contains("countries") && visible.contains("jurisdictions"))
+        {
+            // Supporting tables are optional in legacy-only environments; keep compat on so
+            // callers can take a best-effort path against sku_regions alone.
+            warn!(
+                countries = visible.contains("countries"),
+                jurisdictions = visible.contains("jurisdictions"),
+                "php compat: supporting tables partially missing; sku_regions/region_prices are present, but jurisdictions/countries may be absent. We will treat sku_regions.id as the canonical 'jurisdiction' identifier when possible. If a caller only has a country/jurisdiction id and needs region_code mapping, set GC_ALLOW_COUNTRY_ONLY_JURISDICTIONS=1 to allow treating jurisdiction_id as country_id (best-effort)."
+            );
+        }
+        Ok(profile)
+    }
+
+    /// The process-wide profile: `SCHEMA_PROFILE` if set, else detected on first call.
+    pub async fn current(db: &Db) -> Result<Self> {
+        let profile = PROFILE
+            .get_or_try_init(|| async {
+                let forced = crate::util::env::env_opt("SCHEMA_PROFILE")
+                    .and_then(|raw| Self::parse_override(&raw));
+                let profile = match forced {
+                    Some(profile) => profile,
+                    None => Self::detect(db).await?,
+                };
+                info!(?profile, forced = forced.is_some(), "schema profile");
+                Ok::<Self, anyhow::Error>(profile)
+            })
+            .await?;
+        Ok(*profile)
+    }
+
+    pub fn is_php_compat(self) -> bool {
+        self == Self::PhpCompat
+    }
+
+    /// Retailer tables in lookup order: this profile's own first, the other as a fallback.
+    pub fn retailer_tables(self) -> [&'static str; 2] {
+        match self {
+            Self::Native => ["retailers", "game_retailers"],
+            Self::PhpCompat => ["game_retailers", "retailers"],
+        }
+    }
+
+    /// `SELECT` of the display key of retailer `$1` in `table` (one of
+    /// [`retailer_tables`](Self::retailer_tables)).
+    pub fn retailer_key_sql(table: &str) -> String {
+        let key = if table == "game_retailers" {
+            "COALESCE(retailer_key, slug, name, 'retailer')"
+        } else {
+            "COALESCE(slug, name, 'retailer')"
+        };
+        format!("SELECT {key} FROM {table} WHERE id=$1")
+    }
+
+    /// `currencies` columns holding the minor-unit digits, preferred first.
+    pub fn currency_minor_unit_columns(self) -> [&'static str; 2] {
+        match self {
+            Self::Native => ["minor_unit", "decimals"],
+            Self::PhpCompat => ["decimals", "minor_unit"],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NATIVE: &[&str] = &[
+        "retailers",
+        "offers",
+        "offer_jurisdictions",
+        "jurisdictions",
+        "currencies",
+    ];
+    const LARAVEL: &[&str] = &[
+        "game_retailers",
+        "game_providers",
+        "sku_regions",
+        "region_prices",
+        "currencies",
+        "countries",
+    ];
+
+    #[test]
+    fn detects_each_profile_and_routes_names() {
+        let native = SchemaProfile::from_visible_tables(|t| NATIVE.contains(&t));
+        let laravel = SchemaProfile::from_visible_tables(|t| LARAVEL.contains(&t));
+        assert_eq!(native, SchemaProfile::Native);
+        assert_eq!(laravel, SchemaProfile::PhpCompat);
+        // sku_regions alone is not the Laravel pricing schema.
+        assert_eq!(
+            SchemaProfile::from_visible_tables(|t| t == "sku_regions"),
+            SchemaProfile::Native
+        );
+
+        let retailers = native.retailer_tables()[0];
+        assert_eq!(
+            SchemaProfile::retailer_key_sql(retailers),
+            "SELECT COALESCE(slug, name, 'retailer') FROM retailers WHERE id=$1"
+        );
+        let retailers = laravel.retailer_tables()[0];
+        assert_eq!(
+            SchemaProfile::retailer_key_sql(retailers),
+            "SELECT COALESCE(retailer_key, slug, name, 'retailer') FROM game_retailers WHERE id=$1"
+        );
+        assert_eq!(laravel.currency_minor_unit_columns()[0], "decimals");
+    }
+
+    #[test]
+    fn override_parses_or_falls_back_to_detection() {
+        assert_eq!(
+            SchemaProfile::parse_override("php_compat"),
+            Some(SchemaProfile::PhpCompat)
+        );
+        assert_eq!(
+            SchemaProfile::parse_override(" Native "),
+            Some(SchemaProfile::Native)
+        );
+        assert_eq!(SchemaProfile::parse_override("auto"), None);
+        assert_eq!(SchemaProfile::parse_override("mysql"), None);
+    }
+}
@@ -0,0 +1,228 @@
+//! Staleness-ranked refresh selection.
+//!
+//! Instead of re-scanning whole catalogues on a fixed interval, pick the provider items that
+//! were refreshed longest ago (never-refreshed first) with a per-provider budget, and turn
+//! them into one targeted refresh job per provider. An item's last refresh is the later of
+//! `provider_items.last_seen_at` (when the column exists) and `updated_at`, which
+//! `ensure_provider_item` stamps whenever an ingest creates or re-links the item.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::Row;
+use tracing::{debug, instrument};
+
+use crate::database_ops::db::Db;
+use crate::database_ops::schema_caps::SchemaCaps;
+
+/// Provider slugs with a targeted refresh job (see `ingest_worker`'s `refresh` task).
+pub const DEFAULT_REFRESH_PROVIDERS: &str = "ps-store,nexarda";
+
+#[derive(Debug, Clone)]
+pub struct StalenessOptions {
+    /// Items selected per provider and tick.
+    pub budget_per_provider: i64,
+    /// Provider slugs to consider; empty means all providers.
+    pub providers: Vec<String>,
+}
+
+impl Default for StalenessOptions {
+    fn default() -> Self {
+        Self {
+            budget_per_provider: 50,
+            providers: split_providers(DEFAULT_REFRESH_PROVIDERS),
+        }
+    }
+}
+
+impl StalenessOptions {
+    /// From `STALE_REFRESH_BUDGET` and `STALE_REFRESH_PROVIDERS` (comma-separated slugs).
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            budget_per_provider: crate::util::env::env_parse(
+                "STALE_REFRESH_BUDGET",
+                defaults.budget_per_provider,
+            ),
+            providers: crate::util::env::env_opt("STALE_REFRESH_PROVIDERS")
+                .map(|raw| split_providers(&raw))
+                .unwrap_or(defaults.providers),
+        }
+    }
+}
+
+fn split_providers(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|s| s.trim().to_ascii_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StaleItem {
+    pub provider_item_id: i64,
+    pub provider_slug: String,
+    pub external_id: String,
+    /// `None` for items never refreshed since they were created.
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+}
+
+/// Items of one provider to refresh together, stalest first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RefreshBatch {
+    pub provider_slug: String,
+    pub external_ids: Vec<String>,
+    pub provider_item_ids: Vec<i64>,
+}
+
+/// Up to `budget_per_provider` items of each provider, ordered stalest first overall.
+#[instrument(skip(db))]
+pub async fn stalest_provider_items(db: &Db, opts: &StalenessOptions) -> Result<Vec<StaleItem>> {
+    let refreshed_at = if SchemaCaps::global()
+        .column_visible(db, "public.provider_items", "last_seen_at")
+        .await?
+    {
+        "GREATEST(pi.last_seen_at, pi.updated_at)"
+    } else {
+        "pi.updated_at"
+    };
+    let providers: Option<&[String]> =
+        (!opts.providers.is_empty()).then_some(opts.providers.as_slice());
+    let sql = format!(
+        "SELECT provider_item_id, provider_slug, external_id, last_refreshed_at
+         FROM (
+           SELECT pi.id AS provider_item_id, p.slug AS provider_slug, pi.external_id,
+                  {refreshed_at} AS last_refreshed_at,
+                  row_number() OVER (
+                    PARTITION BY pi.provider_id
+                    ORDER BY {refreshed_at} ASC NULLS FIRST, pi.id
+                  ) AS rank
+           FROM public.provider_items pi
+           JOIN public.providers p ON p.id = pi.provider_id
+           WHERE pi.external_id IS NOT NULL
+             AND ($2::text[] IS NULL OR lower(p.slug) = ANY($2))
+         ) ranked
+         WHERE rank <= $1
+         ORDER BY last_refreshed_at ASC NULLS FIRST, provider_slug, provider_item_id"
+    );
+    let items = sqlx::query(&sql)
+        .persistent(false)
+        .bind(opts.budget_per_provider.max(0))
+        .bind(providers)
+        .fetch_all(&db.pool)
+        .await?
+        .iter()
+        .map(|r| -> Result<StaleItem> {
+            Ok(StaleItem {
+                provider_item_id: r.try_get("provider_item_id")?,
+                provider_slug: r.try_get("provider_slug")?,
+                external_id: r.try_get("external_id")?,
+                last_refreshed_at: r.try_get("last_refreshed_at")?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    debug!(items = items.len(), "staleness: provider items selected");
+    Ok(items)
+}
+
+/// Group `items` into one batch per provider, the provider with the stalest item first.
+pub fn refresh_batches(items: &[StaleItem]) -> Vec<RefreshBatch> {
+    let mut batches: Vec<RefreshBatch> = Vec::new();
+    for item in items {
+        let batch = match batches
+            .iter_mut()
+            .position(|b| b.provider_slug == item.provider_slug)
+        {
+            Some(i) => &mut batches[i],
+            None => {
+                batches.push(RefreshBatch {
+                    provider_slug: item.provider_slug.clone(),
+                    external_ids: Vec::new(),
+                    provider_item_ids: Vec::new(),
+                });
+                batches.last_mut().unwrap()
+            }
+        };
+        batch.external_ids.push(item.external_id.clone());
+        batch.provider_item_ids.push(item.provider_item_id);
+    }
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+
+    fn item(id: i64, provider: &str) -> StaleItem {
+        StaleItem {
+            provider_item_id: id,
+            provider_slug: provider.to_string(),
+            external_id: format!("ext-{id}"),
+            last_refreshed_at: None,
+        }
+    }
+
+    #[test]
+    fn batches_follow_staleness_order() {
+        let batches =
+            refresh_batches(&[item(3, "nexarda"), item(1, "ps-store"), item(2, "nexarda")]);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].provider_slug, "nexarda");
+        assert_eq!(batches[0].external_ids, vec!["ext-3", "ext-2"]);
+        assert_eq!(batches[1].provider_item_ids, vec![1]);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn stalest_items_are_selected_first() {
+        let db = RollbackDb::connect().await;
+        let provider_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.providers (slug, name) VALUES ('staleness-test', 'Staleness Test')
+             ON CONFLICT (slug) DO UPDATE SET name = EXCLUDED.name RETURNING id",
+        )
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO public.provider_items (provider_id, external_id, updated_at)
+             VALUES ($1, 'fresh', now()),
+                    ($1, 'week-old', now() - interval '7 days'),
+                    ($1, 'never', NULL),
+                    ($1, 'month-old', now() - interval '30 days')",
+        )
+        .bind(provider_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        let opts = StalenessOptions {
+            budget_per_provider: 3,
+            providers: vec!["staleness-test".to_string()],
+        };
+        let selected = stalest_provider_items(&db, &opts).await.unwrap();
+        // An ingest re-linking the stalest items moves them to the back of the queue.
+        for external_id in ["never", "month-old"] {
+            crate::database_ops::ingest_providers::ensure_provider_item(
+                &db,
+                provider_id,
+                external_id,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        let after_relink = stalest_provider_items(&db, &opts).await.unwrap();
+        db.rollback().await;
+
+        let ids: Vec<&str> = selected.iter().map(|i| i.external_id.as_str()).collect();
+        assert_eq!(ids, vec!["never", "month-old", "week-old"]);
+        assert!(selected[0].last_refreshed_at.is_none());
+        let ids: Vec<&str> = after_relink
+            .iter()
+            .map(|i| i.external_id.as_str())
+            .collect();
+        assert_eq!(ids[0], "week-old");
+        assert!(after_relink.iter().all(|i| i.last_refreshed_at.is_some()));
+    }
+}
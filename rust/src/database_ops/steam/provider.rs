@@ -1,11 +1,14 @@
 use crate::currency_for_country;
 use crate::database_ops::db::{Db, PriceRow};
+use crate::database_ops::external_ratings::{upsert_external_rating, RatingSource};
 use crate::database_ops::ingest_providers::{
     ensure_country, ensure_currency, ensure_national_jurisdiction, ensure_platform,
     ensure_provider, ensure_retailer, ensure_vg_source_media_links_with_meta, ingest_prices,
-    link_provider_offer, update_video_game_display_title_and_region, upsert_game_media,
-    PostIngestSummary, ProviderEntityCache,
+    link_provider_offer, merge_video_game_metadata, update_video_game_display_title_and_region,
+    upsert_game_media, PostIngestSummary, ProviderEntityCache,
 };
+use crate::normalization::display_title::TitleCandidate;
+use crate::util::currency::minor_unit as currency_minor_unit;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use futures::{stream::FuturesUnordered, StreamExt};
@@ -111,6 +114,24 @@ struct Metacritic {
 struct Recommendations {
     total: Option<i64>,
 }
+
+/// `query_summary` of the store's appreviews endpoint.
+#[derive(Debug, Deserialize)]
+struct ReviewSummary {
+    #[serde(default)]
+    total_positive: Option<i64>,
+    #[serde(default)]
+    total_reviews: Option<i64>,
+}
+
+impl ReviewSummary {
+    /// Percent positive and review count, for `RatingSource::Steam`; `None` without reviews.
+    fn rating(&self) -> Option<(f64, i64)> {
+        let total = self.total_reviews.filter(|t| *t > 0)?;
+        let positive = self.total_positive?.clamp(0, total);
+        Some((positive as f64 * 100.0 / total as f64, total))
+    }
+}
 #[derive(Debug, Deserialize)]
 struct GenreEntry {
     #[allow(dead_code)]
@@ -314,6 +335,27 @@ fn push_bundle_price_row(
     }
 }
 
+/// Per-run Steam settings a caller may override; the rest is read from env by the run.
+#[derive(Debug, Clone)]
+pub struct SteamRunOptions {
+    /// Only re-price known items lacking a recent price (`STEAM_BACKFILL=1`).
+    pub backfill: bool,
+    /// What counts as recent for backfill (`STEAM_RECENT_MISSING_DAYS`, default 30).
+    pub recent_missing_days: i64,
+}
+
+impl SteamRunOptions {
+    pub fn from_env() -> Self {
+        Self {
+            backfill: std::env::var("STEAM_BACKFILL").ok().as_deref() == Some("1"),
+            recent_missing_days: std::env::var("STEAM_RECENT_MISSING_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(30),
+        }
+    }
+}
+
 impl SteamProvider {
     pub fn new() -> Self {
         // Add a sane default timeout to avoid indefinite hangs on slow Steam endpoints.
@@ -330,6 +372,11 @@ impl SteamProvider {
     }
 
     pub async fn run_from_env(db: &Db) -> Result<()> {
+        Self::run_with_options(db, SteamRunOptions::from_env()).await
+    }
+
+    /// [`Self::run_from_env`] with the per-run settings passed in.
+    pub async fn run_with_options(db: &Db, opts: SteamRunOptions) -> Result<()> {
         // DEBUG instrumentation: capture per-app decision traces when STEAM_DEBUG=1
         let debug_enabled = std::env::var("STEAM_DEBUG").ok().as_deref() == Some("1");
         #[derive(Serialize)]
@@ -345,7 +392,7 @@ impl SteamProvider {
             reason: Option<String>,
         }
         let mut debug_traces: Vec<AppDebugTrace> = Vec::new();
-        let backfill = std::env::var("STEAM_BACKFILL").ok().as_deref() == Some("1");
+        let backfill = opts.backfill;
         let mut app_ids: Vec<String> = std::env::var("STEAM_APP_IDS")
             .unwrap_or_else(|_| "".into())
             .split(',')
@@ -564,10 +611,7 @@ impl SteamProvider {
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(8);
-        let recent_days: i64 = std::env::var("STEAM_RECENT_MISSING_DAYS")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(30);
+        let recent_days = opts.recent_missing_days;
         let per_request_timeout = std::env::var("STEAM_REQUEST_TIMEOUT_SECS")
             .ok()
             .and_then(|s| s.parse::<u64>().ok())
@@ -760,7 +804,8 @@ impl SteamProvider {
                                 .await?;
                             // Laravel schema: use product_id directly
                             let vg_id = entity_cache
-                                .ensure_video_game_for_product_laravel(
+                                .ensure_video_game_for_store_item(
+                                    &id,
                                     product_id,
                                     &name,
                                     Some(&slug),
@@ -948,18 +993,57 @@ impl SteamProvider {
                                                 .collect()
                                         });
                                     let synopsis = select_synopsis(&full);
-                                    let synopsis_ref = synopsis.as_deref();
                                     let _ = sqlx
                                         ::query(
-                                            "UPDATE public.video_games SET average_rating = COALESCE($1, average_rating), rating_count = COALESCE($2, rating_count), rating_updated_at = CASE WHEN $1 IS NOT NULL OR $2 IS NOT NULL THEN now() ELSE rating_updated_at END, genres = CASE WHEN $3::text[] IS NOT NULL AND array_length($3,1) > 0 THEN $3 ELSE genres END, synopsis = CASE WHEN $4::text IS NOT NULL AND (synopsis IS NULL OR length(synopsis) < length($4)) THEN $4 ELSE synopsis END WHERE id = $5"
+                                            "UPDATE public.video_games SET average_rating = COALESCE($1, average_rating), rating_count = COALESCE($2, rating_count), rating_updated_at = CASE WHEN $1 IS NOT NULL OR $2 IS NOT NULL THEN now() ELSE rating_updated_at END WHERE id = $3"
                                         )
                                         .persistent(false)
                                         .bind(avg_rating)
                                         .bind(rating_count)
-                                        .bind(genres.as_ref())
-                                        .bind(synopsis_ref)
                                         .bind(vg_id)
                                         .execute(&db.pool).await;
+                                    // Genres and synopsis go through the prioritized merge so
+                                    // IGDB's values are not overwritten by Steam's.
+                                    let mut content = serde_json::Map::new();
+                                    if let Some(genres) = genres.filter(|g| !g.is_empty()) {
+                                        content.insert("genres".into(), json!(genres));
+                                    }
+                                    if let Some(synopsis) = synopsis {
+                                        content.insert("synopsis".into(), json!(synopsis));
+                                    }
+                                    if !content.is_empty() {
+                                        let _ = merge_video_game_metadata(
+                                            db,
+                                            vg_id,
+                                            "steam",
+                                            Value::Object(content),
+                                        )
+                                        .await;
+                                    }
+                                    if let Some(score) = avg_rating {
+                                        let _ = upsert_external_rating(
+                                            db,
+                                            vg_id,
+                                            RatingSource::Metacritic,
+                                            f64::from(score),
+                                            None,
+                                        )
+                                        .await;
+                                    }
+                                    if let Some((percent, reviews)) =
+                                        fetch_review_summary(&client, &id)
+                                            .await
+                                            .and_then(|s| s.rating())
+                                    {
+                                        let _ = upsert_external_rating(
+                                            db,
+                                            vg_id,
+                                            RatingSource::Steam,
+                                            percent,
+                                            Some(reviews),
+                                        )
+                                        .await;
+                                    }
                                 }
                             }
                             if fetch_media && media_scope.should_fetch_in_region(region_idx) {
@@ -1050,9 +1134,13 @@ impl SteamProvider {
                                 }
                             }
                             // Ensure display_title and aggregate region_codes
-                            let _ =
-                                update_video_game_display_title_and_region(db, vg_id, &name, &cc)
-                                    .await;
+                            let _ = update_video_game_display_title_and_region(
+                                db,
+                                vg_id,
+                                &TitleCandidate::new(&name, "steam", Some(&language)),
+                                Some(&cc),
+                            )
+                            .await;
                             processed_apps += 1;
                             if processed_apps % 25 == 0 {
                                 info!(country = %cc, processed_apps, total_apps=app_ids.len(), region_price_rows, elapsed_secs = (Utc::now()-region_start).num_seconds(), "region progress");
@@ -1639,15 +1727,7 @@ fn slugify(s: &str) -> String {
         .to_string()
 }
 
-fn currency_minor_unit(code: &str) -> i16 {
-    match code.to_ascii_uppercase().as_str() {
-        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "HUF" => 0,
-        "BHD" | "IQD" | "KWD" | "JOD" | "OMR" | "TND" => 3,
-        _ => 2,
-    }
-}
-
-fn load_steam_regions() -> Vec<(String, String)> {
+pub(crate) fn load_steam_regions() -> Vec<(String, String)> {
     // returns Vec<(country_code, currency_code)>; env STEAM_REGIONS override: "US:USD,GB:GBP,DE:EUR"
     if let Ok(s) = std::env::var("STEAM_REGIONS") {
         let mut out = Vec::new();
@@ -1820,6 +1900,23 @@ async fn fetch_full_details(client: &Client, appid: &str) -> Result<Option<FullA
     Ok(None)
 }
 
+/// User review totals across all languages and purchase types.
+async fn fetch_review_summary(client: &Client, appid: &str) -> Option<ReviewSummary> {
+    let url = format!("https://store.steampowered.com/appreviews/{}", appid);
+    let v = get_with_backoff_json(
+        client,
+        &url,
+        &[
+            ("json", "1"),
+            ("language", "all"),
+            ("purchase_type", "all"),
+            ("num_per_page", "0"),
+        ],
+    )
+    .await?;
+    serde_json::from_value(v.get("query_summary")?.clone()).ok()
+}
+
 // -------- Media extraction (screenshots, covers, trailers) --------
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -2010,6 +2107,26 @@ async fn fetch_media_urls(
     Ok(out)
 }
 
+#[cfg(test)]
+mod tests_reviews {
+    use super::ReviewSummary;
+
+    #[test]
+    fn review_summary_becomes_percent_positive() {
+        let summary: ReviewSummary = serde_json::from_str(
+            r#"{"num_reviews":0,"review_score":8,"total_positive":11620,"total_negative":742,"total_reviews":12362}"#,
+        )
+        .unwrap();
+        let (percent, count) = summary.rating().unwrap();
+        assert!((percent - 94.0).abs() < 0.1, "{percent}");
+        assert_eq!(count, 12_362);
+
+        let empty: ReviewSummary =
+            serde_json::from_str(r#"{"total_positive":0,"total_reviews":0}"#).unwrap();
+        assert_eq!(empty.rating(), None);
+    }
+}
+
 #[cfg(test)]
 mod tests_url_norm {
     #[test]
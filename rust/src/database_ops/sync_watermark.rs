@@ -0,0 +1,103 @@
+//! Per-provider incremental sync watermarks (`provider_sync_watermarks`).
+//!
+//! A watermark is the highest source-side modification time already ingested for one
+//! provider and dataset. Watermarks only move forward, and both functions degrade to
+//! "no watermark" on databases without the table so callers fall back to a full backfill.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::database_ops::db::Db;
+
+async fn watermarks_present(db: &Db) -> Result<bool> {
+    Ok(
+        sqlx::query_scalar("SELECT to_regclass('public.provider_sync_watermarks') IS NOT NULL")
+            .persistent(false)
+            .fetch_one(&db.pool)
+            .await?,
+    )
+}
+
+/// The stored watermark for `provider_id` / `sync_kind`, if any.
+pub async fn load_watermark(
+    db: &Db,
+    provider_id: i64,
+    sync_kind: &str,
+) -> Result<Option<DateTime<Utc>>> {
+    if !watermarks_present(db).await? {
+        return Ok(None);
+    }
+    Ok(sqlx::query_scalar(
+        "SELECT watermark FROM public.provider_sync_watermarks
+         WHERE provider_id = $1 AND sync_kind = $2",
+    )
+    .persistent(false)
+    .bind(provider_id)
+    .bind(sync_kind)
+    .fetch_optional(&db.pool)
+    .await?)
+}
+
+/// Advance the watermark to `watermark` unless the stored one is already later. Returns
+/// `false` when the table does not exist.
+pub async fn advance_watermark(
+    db: &Db,
+    provider_id: i64,
+    sync_kind: &str,
+    watermark: DateTime<Utc>,
+) -> Result<bool> {
+    if !watermarks_present(db).await? {
+        return Ok(false);
+    }
+    sqlx::query(
+        "INSERT INTO public.provider_sync_watermarks (provider_id, sync_kind, watermark)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (provider_id, sync_kind) DO UPDATE
+           SET watermark = GREATEST(provider_sync_watermarks.watermark, EXCLUDED.watermark),
+               updated_at = now()",
+    )
+    .persistent(false)
+    .bind(provider_id)
+    .bind(sync_kind)
+    .bind(watermark)
+    .execute(&db.pool)
+    .await?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::RollbackDb;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn watermark_only_moves_forward() {
+        let db = RollbackDb::connect().await;
+        let provider_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.providers (slug, name) VALUES ('watermark-test', 'Watermark Test')
+             ON CONFLICT (slug) DO UPDATE SET name = EXCLUDED.name RETURNING id",
+        )
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let t1 = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let t2 = Utc.timestamp_opt(1_700_100_000, 0).unwrap();
+
+        assert_eq!(
+            load_watermark(&db, provider_id, "test").await.unwrap(),
+            None
+        );
+        assert!(advance_watermark(&db, provider_id, "test", t2)
+            .await
+            .unwrap());
+        advance_watermark(&db, provider_id, "test", t1)
+            .await
+            .unwrap();
+        let stored = load_watermark(&db, provider_id, "test").await.unwrap();
+
+        db.rollback().await;
+        assert_eq!(stored, Some(t2));
+    }
+}
@@ -1,10 +1,11 @@
 use anyhow::Result;
 use serde::Deserialize;
 use serde_json::json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tracing::{info, warn};
 
 use crate::database_ops::db::Db;
+use crate::normalization::{canonical_genre, canonical_platform_tgdb};
 
 async fn table_exists(db: &Db, name: &str) -> bool {
     sqlx::query_scalar::<_, bool>(
@@ -34,12 +35,171 @@ fn parse_year(date: &str) -> Option<i32> {
     date.get(0..4)?.parse().ok()
 }
 
-fn classify_tgdb_role(t: &str) -> &'static str {
-    match t.to_ascii_lowercase().as_str() {
+/// Map a TGDB image `type` (and boxart `side`) to a `media_type` role: front boxart is the
+/// cover, back boxart and fanart are artwork, banners are hero images.
+fn classify_tgdb_role(t: &str, side: Option<&str>) -> &'static str {
+    match t.trim().to_ascii_lowercase().as_str() {
+        "boxart" if side.is_some_and(|s| s.eq_ignore_ascii_case("back")) => "artwork",
         "boxart" => "cover",
-        "fanart" => "banner",
-        "screenshot" => "screenshot",
-        _ => "image",
+        "fanart" => "artwork",
+        "banner" => "hero",
+        "screenshot" | "titlescreen" => "screenshot",
+        "clearlogo" => "logo",
+        _ => "artwork",
+    }
+}
+
+/// One TGDB image resolved to an absolute URL and role.
+#[derive(Debug, Clone, PartialEq)]
+struct TgdbMedia {
+    url: String,
+    role: &'static str,
+    filename: String,
+    kind: Option<String>,
+    position: usize,
+}
+
+/// Media for game `gid` from a `/Games/Images` response, in upstream order.
+fn tgdb_game_media(images: GameImages, gid: u64) -> Vec<TgdbMedia> {
+    let Some(data) = images.data else {
+        return Vec::new();
+    };
+    let (Some(base), Some(mut map)) = (
+        data.base_url.or(images.base_url).and_then(|b| b.original),
+        data.images,
+    ) else {
+        return Vec::new();
+    };
+    map.remove(&gid.to_string())
+        .unwrap_or_default()
+        .into_iter()
+        .enumerate()
+        .filter_map(|(position, im)| {
+            let filename = im.filename?;
+            Some(TgdbMedia {
+                url: format!("{}{}", base, filename),
+                role: classify_tgdb_role(im.r#type.as_deref().unwrap_or(""), im.side.as_deref()),
+                filename,
+                kind: im.r#type,
+                position,
+            })
+        })
+        .collect()
+}
+
+/// Canonical genres for TGDB genre ids, deduplicated in upstream order. Ids without a name
+/// or a canonical mapping are logged once per run (tracked in `unmapped`) and dropped.
+fn canonical_tgdb_genres(
+    ids: &[u32],
+    names: &HashMap<u32, String>,
+    unmapped: &mut HashSet<String>,
+) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for id in ids {
+        let name = names.get(id).cloned().unwrap_or_else(|| format!("#{id}"));
+        match canonical_genre(&name) {
+            Some(genre) if !out.iter().any(|g| g == genre) => out.push(genre.to_string()),
+            Some(_) => {}
+            None => {
+                if unmapped.insert(name.clone()) {
+                    warn!(genre_id = id, genre = %name, "TGDB: unmapped genre; not stored");
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Default pacing with an API key (`TGDB_REQS_PER_MIN`).
+const DEFAULT_REQS_PER_MIN: u64 = 60;
+/// Default pacing without a key (`TGDB_ANON_REQS_PER_MIN`); anonymous quotas are tiny.
+const DEFAULT_ANON_REQS_PER_MIN: u64 = 6;
+/// Anonymous calls per run after which we warn that the quota is likely spent
+/// (`TGDB_ANON_QUOTA`).
+const DEFAULT_ANON_QUOTA: u64 = 100;
+
+/// Request pacing for one ingest run. Anonymous runs use the stricter of
+/// `TGDB_ANON_REQS_PER_MIN` and `TGDB_REQS_PER_MIN`, count calls against `TGDB_ANON_QUOTA`
+/// and warn once the quota is likely exceeded.
+#[derive(Debug, Clone, PartialEq)]
+struct TgdbRateLimit {
+    reqs_per_min: u64,
+    anonymous: bool,
+    anon_quota: u64,
+    calls: u64,
+    quota_warned: bool,
+}
+
+impl TgdbRateLimit {
+    fn from_env(has_key: bool, reqs_per_min: Option<u64>) -> Self {
+        let parse = |key: &str| std::env::var(key).ok().and_then(|s| s.trim().parse().ok());
+        Self::resolve(
+            has_key,
+            reqs_per_min.or_else(|| parse("TGDB_REQS_PER_MIN")),
+            parse("TGDB_ANON_REQS_PER_MIN"),
+            parse("TGDB_ANON_QUOTA"),
+        )
+    }
+
+    fn resolve(
+        has_key: bool,
+        reqs_per_min: Option<u64>,
+        anon_reqs_per_min: Option<u64>,
+        anon_quota: Option<u64>,
+    ) -> Self {
+        let keyed = reqs_per_min.unwrap_or(DEFAULT_REQS_PER_MIN);
+        let reqs_per_min = if has_key {
+            keyed
+        } else {
+            let anon = anon_reqs_per_min
+                .unwrap_or(DEFAULT_ANON_REQS_PER_MIN)
+                .max(1);
+            if keyed > 0 {
+                anon.min(keyed)
+            } else {
+                anon
+            }
+        };
+        Self {
+            reqs_per_min,
+            anonymous: !has_key,
+            anon_quota: anon_quota.unwrap_or(DEFAULT_ANON_QUOTA),
+            calls: 0,
+            quota_warned: false,
+        }
+    }
+
+    fn interval(&self) -> Option<std::time::Duration> {
+        (self.reqs_per_min > 0)
+            .then(|| std::time::Duration::from_millis((60_000 / self.reqs_per_min).max(1)))
+    }
+
+    /// Sleep one interval before the next call and count it.
+    async fn wait(&mut self) {
+        if let Some(interval) = self.interval() {
+            tokio::time::sleep(interval).await;
+        }
+        self.calls += 1;
+        if self.anonymous
+            && !self.quota_warned
+            && self.anon_quota > 0
+            && self.calls > self.anon_quota
+        {
+            self.quota_warned = true;
+            warn!(
+                calls = self.calls,
+                quota = self.anon_quota,
+                "TGDB: anonymous call count exceeds TGDB_ANON_QUOTA; quota is likely exhausted (set TGDB_API_KEY)"
+            );
+        }
+    }
+
+    /// Warn when an anonymous call is refused in a way that signals an exhausted quota.
+    fn note_status(&mut self, status: reqwest::StatusCode) {
+        if self.anonymous && !self.quota_warned && matches!(status.as_u16(), 403 | 429) {
+            self.quota_warned = true;
+            warn!(%status, calls = self.calls, "TGDB: anonymous quota likely exceeded (set TGDB_API_KEY)");
+        }
     }
 }
 
@@ -63,6 +223,22 @@ struct TgdbPlatform {
     name: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct TgdbGenres {
+    data: Option<TgdbGenresData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TgdbGenresData {
+    genres: Option<HashMap<String, TgdbGenre>>, // keyed by genre id string
+}
+
+#[derive(Debug, Deserialize)]
+struct TgdbGenre {
+    id: Option<u32>,
+    name: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 struct VideoGame {
     data: Option<VideoGameData>,
@@ -85,11 +261,12 @@ struct VideoGameEntry {
     #[serde(rename = "game_title")]
     game_title: Option<String>,
     release_date: Option<String>,
+    genres: Option<Vec<u32>>, // requested via `fields=genres`
 }
 
 #[derive(Debug, Deserialize)]
 struct GameImages {
-    base_url: Option<GameImagesBaseUrl>,
+    base_url: Option<GameImagesBaseUrl>, // legacy placement; current API nests it under `data`
     data: Option<GameImagesData>,
 }
 
@@ -100,6 +277,7 @@ struct GameImagesBaseUrl {
 
 #[derive(Debug, Deserialize)]
 struct GameImagesData {
+    base_url: Option<GameImagesBaseUrl>,
     images: Option<HashMap<String, Vec<GameImageEntry>>>, // keyed by game id
 }
 
@@ -108,6 +286,34 @@ struct GameImageEntry {
     filename: Option<String>,
     #[serde(rename = "type")]
     r#type: Option<String>, // mapped via classify_tgdb_role
+    side: Option<String>, // "front" / "back" for boxart
+}
+
+/// Settings of one TGDB sync; [`TgdbSyncOptions::from_env`] reads the `TGDB_*` variables.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TgdbSyncOptions {
+    /// `None` reads `TGDB_API_KEY`; without a key the run is anonymous.
+    pub api_key: Option<String>,
+    /// Release years kept, inclusive (`TGDB_YEAR_MIN`/`TGDB_YEAR_MAX`, default 2015..=2025).
+    pub year_min: i32,
+    pub year_max: i32,
+    /// Games per page (`TGDB_PAGE_SIZE`, default 50).
+    pub page_size: u32,
+    /// Keyed pacing; `None` reads `TGDB_REQS_PER_MIN`.
+    pub reqs_per_min: Option<u64>,
+}
+
+impl TgdbSyncOptions {
+    pub fn from_env() -> Self {
+        use crate::util::env::env_parse_opt as parse;
+        Self {
+            api_key: None,
+            year_min: parse("TGDB_YEAR_MIN").unwrap_or(2015),
+            year_max: parse("TGDB_YEAR_MAX").unwrap_or(2025),
+            page_size: parse("TGDB_PAGE_SIZE").unwrap_or(50),
+            reqs_per_min: None,
+        }
+    }
 }
 
 /// Ingest TGDB by platform, filtering to [year_min, year_max] descending; mirrors game entries and media.
@@ -117,10 +323,22 @@ pub async fn ingest_range(
     year_min: i32,
     year_max: i32,
 ) -> Result<()> {
+    let opts = TgdbSyncOptions {
+        api_key,
+        year_min,
+        year_max,
+        ..TgdbSyncOptions::from_env()
+    };
+    sync_with_options(db, &opts).await
+}
+
+/// [`ingest_range`] with every setting passed in rather than read from env.
+pub async fn sync_with_options(db: &Db, opts: &TgdbSyncOptions) -> Result<()> {
+    let (year_min, year_max) = (opts.year_min, opts.year_max);
     use crate::database_ops::ingest_providers::{
         ensure_platform, ensure_product_named, ensure_provider, ensure_provider_item,
         ensure_software_row, ensure_vg_source_media_links_with_meta, ensure_video_game,
-        ensure_video_game_title, upsert_game_media_batch,
+        ensure_video_game_title, update_video_game_genres_if_empty, upsert_game_media_batch,
     };
     let provider_id: Option<i64> = if table_exists(db, "provider_items").await
         && table_exists(db, "video_game_sources").await
@@ -135,28 +353,29 @@ pub async fn ingest_range(
     let can_write_provider_media_links =
         provider_id.is_some() && table_exists(db, "vg_source_media_links").await;
     let client = reqwest::Client::new();
-    let key = api_key
+    let key = opts
+        .api_key
+        .clone()
         .or_else(|| std::env::var("TGDB_API_KEY").ok())
         .unwrap_or_default();
-    let rpm: u64 = std::env::var("TGDB_REQS_PER_MIN")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(60);
-    let sleep_ms = if rpm > 0 { (60_000u64 / rpm).max(1) } else { 0 };
-    let page_size: u32 = std::env::var("TGDB_PAGE_SIZE")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(50);
+    let mut limit = TgdbRateLimit::from_env(!key.is_empty(), opts.reqs_per_min);
+    if limit.anonymous {
+        warn!(
+            reqs_per_min = limit.reqs_per_min,
+            quota = limit.anon_quota,
+            "TGDB: no API key; running anonymously at a reduced rate"
+        );
+    }
+    let page_size = opts.page_size;
 
     // fetch platforms
     let mut purl = "https://api.thegamesdb.net/Platforms".to_string();
     if !key.is_empty() {
         purl.push_str(&format!("?apikey={}", key));
     }
-    if sleep_ms > 0 {
-        tokio::time::sleep(std::time::Duration::from_millis(sleep_ms)).await;
-    }
+    limit.wait().await;
     let presp = client.get(&purl).send().await?;
+    limit.note_status(presp.status());
     if !presp.status().is_success() {
         warn!(status=?presp.status(), "TGDB platforms failed");
         return Ok(());
@@ -168,6 +387,34 @@ pub async fn ingest_range(
     let mut platforms: Vec<TgdbPlatform> = map.into_values().collect();
     platforms.sort_by_key(|p| p.id.unwrap_or(0));
 
+    // genre id -> TGDB name, mapped to canonical genres per game
+    let mut gurl = "https://api.thegamesdb.net/Genres".to_string();
+    if !key.is_empty() {
+        gurl.push_str(&format!("?apikey={}", key));
+    }
+    limit.wait().await;
+    let genre_names: HashMap<u32, String> = match client.get(&gurl).send().await {
+        Ok(resp) if resp.status().is_success() => resp
+            .json::<TgdbGenres>()
+            .await?
+            .data
+            .and_then(|d| d.genres)
+            .unwrap_or_default()
+            .into_values()
+            .filter_map(|g| Some((g.id?, g.name?)))
+            .collect(),
+        Ok(resp) => {
+            limit.note_status(resp.status());
+            warn!(status=?resp.status(), "TGDB genres failed; games will be ingested without genres");
+            HashMap::new()
+        }
+        Err(err) => {
+            warn!(error=%err, "TGDB genres failed; games will be ingested without genres");
+            HashMap::new()
+        }
+    };
+    let mut unmapped_genres: HashSet<String> = HashSet::new();
+
     for plat in platforms {
         let pid = match plat.id {
             Some(v) => v,
@@ -175,15 +422,22 @@ pub async fn ingest_range(
                 continue;
             }
         };
-        let pname = plat.name.unwrap_or_else(|| format!("Platform {}", pid));
-        let pslug = slugify(&pname);
+        let (pname, pslug) = match canonical_platform_tgdb(pid) {
+            Some((name, code)) => (name.to_string(), code.to_string()),
+            None => {
+                let name = plat.name.unwrap_or_else(|| format!("Platform {}", pid));
+                warn!(tgdb_platform_id = pid, platform = %name, "TGDB: unmapped platform; using TGDB name");
+                let slug = slugify(&name);
+                (name, slug)
+            }
+        };
         let platform_id = ensure_platform(db, &pname, Some(&pslug)).await?;
 
         // page through games filtered by platform; then filter by year and process
         let mut page: u64 = 1;
         loop {
             let mut gurl = format!(
-                "https://api.thegamesdb.net/Games?filter[platform]={}&page={}&limit={}",
+                "https://api.thegamesdb.net/Games?filter[platform]={}&page={}&limit={}&fields=genres",
                 pid, page, page_size
             );
             if !key.is_empty() {
@@ -194,10 +448,9 @@ pub async fn ingest_range(
                     key
                 );
             }
-            if sleep_ms > 0 {
-                tokio::time::sleep(std::time::Duration::from_millis(sleep_ms)).await;
-            }
+            limit.wait().await;
             let gresp = client.get(&gurl).send().await?;
+            limit.note_status(gresp.status());
             if !gresp.status().is_success() {
                 warn!(status=?gresp.status(), pid, page, "TGDB games page failed");
                 break;
@@ -250,6 +503,14 @@ pub async fn ingest_range(
                 ensure_software_row(db, product_id).await?;
                 let title_id = ensure_video_game_title(db, product_id, &title, Some(&slug)).await?;
                 let vg_id = ensure_video_game(db, title_id, platform_id, None).await?;
+                let genres = canonical_tgdb_genres(
+                    g.genres.as_deref().unwrap_or_default(),
+                    &genre_names,
+                    &mut unmapped_genres,
+                );
+                if !genres.is_empty() {
+                    update_video_game_genres_if_empty(db, vg_id, &genres).await?;
+                }
                 let pi: Option<i64> = if let Some(pid) = provider_id {
                     match ensure_provider_item(db, pid, &format!("tgdb:{}", gid), None).await {
                         Ok(id) => Some(id),
@@ -267,109 +528,94 @@ pub async fn ingest_range(
                 if !key.is_empty() {
                     iurl.push_str(&format!("&apikey={}", key));
                 }
-                if sleep_ms > 0 {
-                    tokio::time::sleep(std::time::Duration::from_millis(sleep_ms)).await;
-                }
+                limit.wait().await;
                 let iresp = client.get(&iurl).send().await?;
+                limit.note_status(iresp.status());
                 if iresp.status().is_success() {
                     let imgs: GameImages = iresp.json().await?;
-                    if let (Some(base), Some(data)) =
-                        (imgs.base_url.and_then(|b| b.original), imgs.data)
+                    let media = tgdb_game_media(imgs, gid);
+                    let canonical_media_rows: Vec<(
+                        i64,
+                        String,
+                        String,
+                        String,
+                        serde_json::Value,
+                    )> = media
+                        .iter()
+                        .map(|m| {
+                            let provider_data = json!({
+                                "source": "tgdb",
+                                "role": m.role,
+                                "media_class": "image",
+                                "tgdb_game_id": gid,
+                                "filename": m.filename,
+                                "platform_id": platform_id,
+                                "release_date": g.release_date,
+                                "type": m.kind,
+                                "position": m.position,
+                            });
+                            (
+                                vg_id,
+                                format!("tgdb:{}:{}", gid, m.filename),
+                                m.role.to_string(),
+                                m.url.clone(),
+                                provider_data,
+                            )
+                        })
+                        .collect();
+                    if !canonical_media_rows.is_empty() {
+                        let borrowed: Vec<_> = canonical_media_rows
+                            .iter()
+                            .map(|(vg, external_id, media_type, url, pdata)| {
+                                (
+                                    *vg,
+                                    "tgdb",
+                                    external_id.as_str(),
+                                    media_type.as_str(),
+                                    url.as_str(),
+                                    pdata,
+                                )
+                            })
+                            .collect();
+                        if let Err(err) = upsert_game_media_batch(db, &borrowed).await {
+                            warn!(
+                                game_id = gid,
+                                error = %err,
+                                "TGDB: failed to upsert canonical media"
+                            );
+                        }
+                    }
+
+                    if let (true, Some(video_game_source_id)) = (can_write_provider_media_links, pi)
                     {
-                        if let Some(map) = data.images {
-                            if let Some(entries) = map.get(&gid.to_string()) {
-                                let mut media_links: Vec<(
-                                    String,
-                                    Option<String>,
-                                    Option<String>,
-                                    Option<String>,
-                                )> = Vec::new();
-                                let mut canonical_media_rows: Vec<(
-                                    i64,
-                                    String,
-                                    String,
-                                    String,
-                                    String,
-                                    serde_json::Value,
-                                )> = Vec::new();
-                                for (idx, im) in entries.iter().enumerate() {
-                                    if let Some(fname) = &im.filename {
-                                        let url = format!("{}{}", base, fname);
-                                        let role = classify_tgdb_role(
-                                            im.r#type.as_deref().unwrap_or("image"),
-                                        )
-                                        .to_string();
-                                        media_links.push((
-                                            url.clone(),
-                                            Some("image".into()),
-                                            Some(role.clone()),
-                                            Some(title.clone()),
-                                        ));
-                                        let provider_data = json!({
-                                            "source": "tgdb",
-                                            "role": role,
-                                            "media_class": "image",
-                                            "tgdb_game_id": gid,
-                                            "filename": fname,
-                                            "platform_id": platform_id,
-                                            "release_date": g.release_date,
-                                            "type": im.r#type,
-                                            "position": idx,
-                                        });
-                                        canonical_media_rows.push((
-                                            vg_id,
-                                            "tgdb".to_string(),
-                                            format!("tgdb:{}:{}", gid, fname),
-                                            role.clone(),
-                                            url,
-                                            provider_data,
-                                        ));
-                                    }
-                                }
-
-                                if !canonical_media_rows.is_empty() {
-                                    let borrowed: Vec<_> = canonical_media_rows
-                                        .iter()
-                                        .map(|(vg, source, external_id, media_type, url, pdata)| {
-                                            (
-                                                *vg,
-                                                source.as_str(),
-                                                external_id.as_str(),
-                                                media_type.as_str(),
-                                                url.as_str(),
-                                                pdata,
-                                            )
-                                        })
-                                        .collect();
-                                    if let Err(err) = upsert_game_media_batch(db, &borrowed).await {
-                                        warn!(
-                                            game_id = gid,
-                                            error = %err,
-                                            "TGDB: failed to upsert canonical media"
-                                        );
-                                    }
-                                }
-
-                                if can_write_provider_media_links {
-                                    if let Some(video_game_source_id) = pi {
-                                        if !media_links.is_empty() {
-                                            let meta = json!({
-                                                "platform_id": platform_id,
-                                                "year": g.release_date,
-                                                "source": "tgdb",
-                                            });
-                                            let _ = ensure_vg_source_media_links_with_meta(
-                                                db,
-                                                video_game_source_id,
-                                                None,
-                                                &media_links,
-                                                "tgdb",
-                                                Some(meta),
-                                            )
-                                            .await;
-                                        }
-                                    }
-                                }
+                        let media_links: Vec<_> = media
+                            .iter()
+                            .map(|m| {
+                                (
+                                    m.url.clone(),
+                                    Some("image".to_string()),
+                                    Some(m.role.to_string()),
+                                    Some(title.clone()),
+                                )
+                            })
+                            .collect();
+                        if !media_links.is_empty() {
+                            let meta = json!({
+                                "platform_id": platform_id,
+                                "year": g.release_date,
+                                "source": "tgdb",
+                            });
+                            if let Err(err) = ensure_vg_source_media_links_with_meta(
+                                db,
+                                video_game_source_id,
+                                Some(vg_id),
+                                &media_links,
+                                "tgdb",
+                                Some(meta),
+                            )
+                            .await
+                            {
+                                warn!(game_id = gid, error = %err, "TGDB: failed to write media links");
                             }
                         }
                     }
@@ -392,13 +638,94 @@ pub async fn ingest_range(
 
 /// Minimal sync shim
 pub async fn sync(db: &Db, api_key: Option<String>) -> Result<()> {
-    let y_min: i32 = std::env::var("TGDB_YEAR_MIN")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(2015);
-    let y_max: i32 = std::env::var("TGDB_YEAR_MAX")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(2025);
-    ingest_range(db, api_key, y_min, y_max).await
+    let opts = TgdbSyncOptions {
+        api_key,
+        ..TgdbSyncOptions::from_env()
+    };
+    sync_with_options(db, &opts).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn game_images_fixture_maps_roles() {
+        let images: GameImages = serde_json::from_str(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/tgdb_game_images.json"
+        )))
+        .unwrap();
+        let media = tgdb_game_media(images, 1018);
+        let roles: Vec<(&str, &str)> = media
+            .iter()
+            .map(|m| (m.filename.as_str(), m.role))
+            .collect();
+        assert_eq!(
+            roles,
+            vec![
+                ("boxart/front/1018-1.jpg", "cover"),
+                ("boxart/back/1018-1.jpg", "artwork"),
+                ("fanart/1018-1.jpg", "artwork"),
+                ("graphical/1018-g.jpg", "hero"),
+                ("screenshots/1018-1.jpg", "screenshot"),
+                ("clearlogo/1018.png", "logo"),
+            ]
+        );
+        assert_eq!(
+            media[0].url,
+            "https://cdn.thegamesdb.net/images/original/boxart/front/1018-1.jpg"
+        );
+        assert_eq!(
+            media[5].position, 6,
+            "entries without a filename are skipped"
+        );
+    }
+
+    #[test]
+    fn anonymous_runs_use_reduced_rate() {
+        let keyed = TgdbRateLimit::resolve(true, Some(120), None, None);
+        assert_eq!(keyed.reqs_per_min, 120);
+        assert!(!keyed.anonymous);
+
+        let anon = TgdbRateLimit::resolve(false, Some(120), None, None);
+        assert!(anon.anonymous);
+        assert_eq!(anon.reqs_per_min, DEFAULT_ANON_REQS_PER_MIN);
+        assert_eq!(anon.interval(), Some(std::time::Duration::from_secs(10)));
+
+        // An explicit anonymous rate is still capped by the keyed rate.
+        assert_eq!(
+            TgdbRateLimit::resolve(false, Some(4), Some(30), None).reqs_per_min,
+            4
+        );
+        assert_eq!(
+            TgdbRateLimit::resolve(false, None, Some(0), None).reqs_per_min,
+            1
+        );
+    }
+
+    #[test]
+    fn genres_map_to_canonical_names() {
+        let names: HashMap<u32, String> = [
+            (1, "Action"),
+            (4, "Role-Playing"),
+            (8, "Shooter"),
+            (11, "Sports"),
+            (15, "Platform"),
+            (26, "Virtual Console"),
+        ]
+        .into_iter()
+        .map(|(id, name)| (id, name.to_string()))
+        .collect();
+        let mut unmapped = HashSet::new();
+        let genres = canonical_tgdb_genres(&[15, 4, 8, 26, 1, 8, 99], &names, &mut unmapped);
+        assert_eq!(
+            genres,
+            vec!["Platformer", "Role-Playing", "Shooter", "Action"]
+        );
+        assert_eq!(
+            unmapped,
+            HashSet::from(["Virtual Console".to_string(), "#99".to_string()])
+        );
+    }
 }
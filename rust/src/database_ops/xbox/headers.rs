@@ -236,16 +236,16 @@ mod tests {
 
     #[test]
     fn test_xbox_config_from_env() {
-        std::env::set_var("XBOX_CLIENT_ID", "test-client-id");
-        std::env::set_var("XBOX_SANDBOX_ID", "RETAIL.0");
+        let mut env = crate::test_support::TestEnv::enter_blocking();
+        env.set(&[
+            ("XBOX_CLIENT_ID", "test-client-id"),
+            ("XBOX_SANDBOX_ID", "RETAIL.0"),
+        ]);
 
         let config = XboxLiveConfig::from_env();
 
         assert_eq!(config.client_id.as_deref(), Some("test-client-id"));
         assert_eq!(config.sandbox_id.as_deref(), Some("RETAIL.0"));
-
-        std::env::remove_var("XBOX_CLIENT_ID");
-        std::env::remove_var("XBOX_SANDBOX_ID");
     }
 
     #[test]
@@ -13,6 +13,7 @@ use crate::database_ops::media_filter::{
     classify_image_from_url, classify_video_from_url, filter_images, filter_videos,
     should_include_screenshots, MediaStats,
 };
+use crate::util::currency::minor_unit as currency_minor_unit;
 use anyhow::{anyhow, Context, Result};
 use chrono::{NaiveDate, NaiveDateTime, Utc};
 use reqwest::Client;
@@ -43,6 +44,7 @@ pub struct XboxProvider {
     auth: Option<XboxAuth>,
     correlation_vector: Arc<Mutex<CorrelationVector>>,
     xbox_config: XboxLiveConfig,
+    policy: XboxRequestPolicy,
 }
 
 #[derive(Clone)]
@@ -73,10 +75,12 @@ impl XboxAuth {
 
 #[derive(Debug, Clone)]
 pub struct XboxOptions {
-    pub market: String,   // e.g., "US"
-    pub language: String, // e.g., "en-US"
+    pub market: String,           // e.g., "US"
+    pub language: String,         // e.g., "en-US"
     pub product_ids: Vec<String>, // list of bigIds
-                          // Note: MS-CV is now auto-generated via CorrelationVector
+    // Note: MS-CV is now auto-generated via CorrelationVector
+    /// Only fetch and dump the catalog, writing nothing (`XBOX_DRY_RUN=1`).
+    pub dry_run: bool,
 }
 
 impl Default for XboxOptions {
@@ -85,10 +89,53 @@ impl Default for XboxOptions {
             market: std::env::var("XBOX_MARKET").unwrap_or_else(|_| "US".to_string()),
             language: std::env::var("XBOX_LANGUAGE").unwrap_or_else(|_| "en".to_string()),
             product_ids: load_product_ids_from_env(),
+            dry_run: std::env::var("XBOX_DRY_RUN").ok().as_deref() == Some("1"),
         }
     }
 }
 
+/// How the provider paces and retries Display Catalog requests.
+#[derive(Debug, Clone)]
+pub struct XboxRequestPolicy {
+    /// Pause before each request: `XBOX_REQS_PER_MIN`, else `XBOX_RPS`; none by default.
+    pub pace_ms: Option<u64>,
+    /// `XBOX_MAX_RETRIES`, default 3.
+    pub max_retries: u32,
+    /// `XBOX_BACKOFF_MS`, default 1000.
+    pub backoff_ms: u64,
+    /// Pause after a 429 without Retry-After (`XBOX_CHUNK_SLEEP_MS`); `backoff_ms` if unset.
+    pub chunk_sleep_ms: Option<u64>,
+    /// Product ids per request (`XBOX_CHUNK_SIZE`, default 30).
+    pub chunk_size: usize,
+    /// MS-CV header of the category, collection and browse calls (`XBOX_MS_CV`).
+    pub ms_cv: String,
+}
+
+impl XboxRequestPolicy {
+    pub fn from_env() -> Self {
+        let parse = |key: &str| std::env::var(key).ok().and_then(|s| s.parse::<u64>().ok());
+        Self {
+            pace_ms: Self::pace_ms(
+                parse("XBOX_REQS_PER_MIN"),
+                std::env::var("XBOX_RPS").ok().and_then(|s| s.parse().ok()),
+            ),
+            max_retries: parse("XBOX_MAX_RETRIES").map_or(3, |v| v as u32),
+            backoff_ms: parse("XBOX_BACKOFF_MS").unwrap_or(1000),
+            chunk_sleep_ms: parse("XBOX_CHUNK_SLEEP_MS"),
+            chunk_size: parse("XBOX_CHUNK_SIZE").map_or(30, |v| v as usize),
+            ms_cv: std::env::var("XBOX_MS_CV").unwrap_or_else(|_| "DGU1mcuYo0WMMp+".to_string()),
+        }
+    }
+
+    /// Pause implied by a requests-per-minute or (failing that) requests-per-second rate.
+    pub fn pace_ms(reqs_per_min: Option<u64>, rps: Option<f32>) -> Option<u64> {
+        if let Some(rpm) = reqs_per_min.filter(|v| *v > 0) {
+            return Some(60_000 / rpm);
+        }
+        rps.filter(|v| *v > 0.0).map(|rps| (1000.0 / rps) as u64)
+    }
+}
+
 impl XboxProvider {
     pub fn new() -> Result<Self> {
         let client = Client::new();
@@ -100,9 +147,16 @@ impl XboxProvider {
             auth: Some(auth),
             correlation_vector,
             xbox_config,
+            policy: XboxRequestPolicy::from_env(),
         })
     }
 
+    /// This provider pacing and retrying requests per `policy`.
+    pub fn with_policy(mut self, policy: XboxRequestPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
     async fn build_request(
         &self,
         url: &str,
@@ -170,37 +224,6 @@ impl XboxProvider {
         }
     }
 
-    fn pace_ms() -> Option<u64> {
-        // Prefer RPM over RPS; fall back to None (no pacing)
-        if let Ok(v) = std::env::var("XBOX_REQS_PER_MIN") {
-            if let Ok(rpm) = v.parse::<u32>() {
-                if rpm > 0 {
-                    return Some(60_000u64 / (rpm as u64));
-                }
-            }
-        }
-        if let Ok(v) = std::env::var("XBOX_RPS") {
-            if let Ok(rps) = v.parse::<f32>() {
-                if rps > 0.0 {
-                    return Some((1000.0 / rps) as u64);
-                }
-            }
-        }
-        None
-    }
-
-    fn retry_policy() -> (u32, u64) {
-        let max_retries: u32 = std::env::var("XBOX_MAX_RETRIES")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(3);
-        let backoff_ms: u64 = std::env::var("XBOX_BACKOFF_MS")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(1000);
-        (max_retries, backoff_ms)
-    }
-
     fn toplist_debug_enabled() -> bool {
         std::env::var("XBOX_TOPLIST_DEBUG")
             .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
@@ -303,11 +326,7 @@ impl XboxProvider {
 
         let mut total_products = 0usize;
         let mut had_error = false;
-        let chunk_size: usize = std::env::var("XBOX_CHUNK_SIZE")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(30)
-            .max(1);
+        let chunk_size = self.policy.chunk_size.max(1);
         for (chunk_idx, chunk) in opts.product_ids.chunks(chunk_size).enumerate() {
             let url = format!(
                 "https://displaycatalog.mp.microsoft.com/v7.0/products?bigIds={}&market={}&languages={}",
@@ -315,8 +334,8 @@ impl XboxProvider {
                 opts.market,
                 opts.language
             );
-            let (max_retries, backoff_ms) = Self::retry_policy();
-            let pace_ms = Self::pace_ms();
+            let (max_retries, backoff_ms) = (self.policy.max_retries, self.policy.backoff_ms);
+            let pace_ms = self.policy.pace_ms;
             // do request with retry policy
             let mut tries: u32 = 0;
             let resp = loop {
@@ -328,10 +347,7 @@ impl XboxProvider {
                 match request.send().await {
                     Ok(r) => {
                         if r.status().as_u16() == 429 {
-                            let mut sleep_ms: u64 = std::env::var("XBOX_CHUNK_SLEEP_MS")
-                                .ok()
-                                .and_then(|s| s.parse().ok())
-                                .unwrap_or(backoff_ms);
+                            let mut sleep_ms = self.policy.chunk_sleep_ms.unwrap_or(backoff_ms);
                             if let Some(ra) = r
                                 .headers()
                                 .get("Retry-After")
@@ -444,7 +460,7 @@ impl XboxProvider {
     }
 
     async fn fetch_categories(&self, market: &str, language: &str) -> Result<Value> {
-        let ms_cv = std::env::var("XBOX_MS_CV").unwrap_or_else(|_| "DGU1mcuYo0WMMp+".to_string());
+        let ms_cv = self.policy.ms_cv.clone();
         let urls = vec![
             format!(
                 "https://displaycatalog.mp.microsoft.com/v7.0/categories?market={}&languages={}&categoryType=Games&deviceFamily=Windows.Xbox",
@@ -542,7 +558,7 @@ impl XboxProvider {
     ) -> Result<Value> {
         let language =
             std::env::var("XBOX_CATEGORY_LANGUAGE").unwrap_or_else(|_| "en-us".to_string());
-        let ms_cv = std::env::var("XBOX_MS_CV").unwrap_or_else(|_| "DGU1mcuYo0WMMp+".to_string());
+        let ms_cv = self.policy.ms_cv.clone();
         let url = format!(
             "https://displaycatalog.mp.microsoft.com/v7.0/products?market={}&languages={}&categoryId={}&deviceFamily=Windows.Xbox&productFamilyNames=Games&orderBy=rank&top={}&skipItems=0",
             market, language, category_id, top
@@ -571,7 +587,7 @@ impl XboxProvider {
     async fn fetch_collection(&self, market: &str, collection: &str, top: usize) -> Result<Value> {
         let language =
             std::env::var("XBOX_CATEGORY_LANGUAGE").unwrap_or_else(|_| "en-us".to_string());
-        let ms_cv = std::env::var("XBOX_MS_CV").unwrap_or_else(|_| "DGU1mcuYo0WMMp+".to_string());
+        let ms_cv = self.policy.ms_cv.clone();
         let debug = Self::toplist_debug_enabled();
         let mut attempts: Vec<String> = Vec::new();
 
@@ -835,7 +851,7 @@ impl XboxProvider {
         let mut product_ids = Vec::new();
         let language =
             std::env::var("XBOX_BROWSE_LANGUAGE").unwrap_or_else(|_| "en-us".to_string());
-        let ms_cv = std::env::var("XBOX_MS_CV").unwrap_or_else(|_| "DGU1mcuYo0WMMp+".to_string());
+        let ms_cv = self.policy.ms_cv.clone();
 
         let mut page = 0;
         let mut skip = 0;
@@ -865,8 +881,7 @@ impl XboxProvider {
                 req = req.bearer_auth(token);
             }
 
-            let pace_ms = Self::pace_ms();
-            if let Some(ms) = pace_ms {
+            if let Some(ms) = self.policy.pace_ms {
                 tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
             }
 
@@ -1025,11 +1040,7 @@ impl XboxProvider {
         let mut total_products = 0usize;
         let mut prices_written: i64 = 0;
         let mut had_error = false;
-        let chunk_size: usize = std::env::var("XBOX_CHUNK_SIZE")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(30)
-            .max(1);
+        let chunk_size = self.policy.chunk_size.max(1);
         for (chunk_idx, chunk) in opts.product_ids.chunks(chunk_size).enumerate() {
             let url = format!(
                 "https://displaycatalog.mp.microsoft.com/v7.0/products?bigIds={}&market={}&languages={}",
@@ -1037,8 +1048,8 @@ impl XboxProvider {
                 opts.market,
                 opts.language
             );
-            let (max_retries, backoff_ms) = Self::retry_policy();
-            let pace_ms = Self::pace_ms();
+            let (max_retries, backoff_ms) = (self.policy.max_retries, self.policy.backoff_ms);
+            let pace_ms = self.policy.pace_ms;
             let mut tries: u32 = 0;
             let resp = loop {
                 tries += 1;
@@ -1049,10 +1060,7 @@ impl XboxProvider {
                 match request.send().await {
                     Ok(r) => {
                         if r.status().as_u16() == 429 {
-                            let mut sleep_ms: u64 = std::env::var("XBOX_CHUNK_SLEEP_MS")
-                                .ok()
-                                .and_then(|s| s.parse().ok())
-                                .unwrap_or(backoff_ms);
+                            let mut sleep_ms = self.policy.chunk_sleep_ms.unwrap_or(backoff_ms);
                             if let Some(ra) = r
                                 .headers()
                                 .get("Retry-After")
@@ -1723,7 +1731,7 @@ impl XboxProvider {
         opts.product_ids = product_ids;
 
         // Dry-run option: skip database writes and only dump JSON if XBOX_DRY_RUN=1
-        if std::env::var("XBOX_DRY_RUN").ok().as_deref() == Some("1") {
+        if opts.dry_run {
             let _ = self.run_dump_only(opts).await?;
         } else {
             let _ = self.run_once(db, opts).await?;
@@ -1821,14 +1829,6 @@ fn parse_collections_env() -> Vec<String> {
     cols
 }
 
-fn currency_minor_unit(code: &str) -> i16 {
-    match code.to_ascii_uppercase().as_str() {
-        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "HUF" => 0,
-        "BHD" | "IQD" | "KWD" | "JOD" | "OMR" | "TND" => 3,
-        _ => 2,
-    }
-}
-
 fn currency_for_market(market: &str) -> (&'static str, &'static str) {
     match market.to_ascii_uppercase().as_str() {
         "US" | "CA" | "AU" | "NZ" => ("USD", "US Dollar"),
@@ -1958,7 +1958,11 @@ pub async fn run_from_env(db: &Db) -> Result<()> {
 }
 
 pub async fn run_with_provider(db: &Db, prov: XboxProvider) -> Result<()> {
-    let mut opts = XboxOptions::default();
+    run_with_options(db, prov, XboxOptions::default()).await
+}
+
+/// [`run_with_provider`] starting from `opts`; discovered products are added to its ids.
+pub async fn run_with_options(db: &Db, prov: XboxProvider, mut opts: XboxOptions) -> Result<()> {
     let mut product_ids: Vec<String> = opts.product_ids.clone();
 
     // Toplist harvesting (existing behavior)
@@ -2050,7 +2054,7 @@ pub async fn run_with_provider(db: &Db, prov: XboxProvider) -> Result<()> {
     opts.product_ids = product_ids;
 
     // Dry-run option: skip database writes and only dump JSON if XBOX_DRY_RUN=1
-    if std::env::var("XBOX_DRY_RUN").ok().as_deref() == Some("1") {
+    if opts.dry_run {
         let _ = prov.run_dump_only(opts).await?;
     } else {
         let _ = prov.run_once(db, opts).await?;
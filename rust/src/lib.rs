@@ -7,6 +7,9 @@ pub mod orchestrator;
 
 pub mod util;
 
+#[cfg(test)]
+mod test_support;
+
 pub use actix_web::http::header;
 
 // PS Store seeding pipeline (library function, not a bin)
@@ -17,17 +20,22 @@ use serde::Serialize;
 use serde_json::{json, Value};
 
 use database_ops::db::{Db, PriceRow};
+use database_ops::external_ratings::{upsert_external_rating, RatingSource};
 use database_ops::ingest_providers::{
     ensure_country, ensure_currency, ensure_national_jurisdiction, ensure_offer,
-    ensure_offer_jurisdiction, ensure_platform, ensure_product_named, ensure_provider,
-    ensure_provider_item, ensure_retailer, ensure_sellable, ensure_software_row,
-    ensure_vg_source_media_links_with_meta, ensure_video_game, ensure_video_game_title,
-    ingest_prices, link_provider_offer, merge_video_game_metadata,
-    update_video_game_display_title_and_region, update_video_game_genres,
-    update_video_game_genres_if_empty, update_video_game_global_rating_if_null,
-    update_video_game_synopsis_prefer_longer, PostIngestSummary,
+    ensure_offer_jurisdiction, ensure_platform, ensure_product_named, ensure_products_batch,
+    ensure_provider, ensure_provider_item, ensure_retailer, ensure_sellable, ensure_software_row,
+    ensure_vg_source_media_links_batch, ensure_video_game, ensure_video_game_title, ingest_prices,
+    link_provider_offer, merge_provider_item_metadata, merge_video_game_metadata,
+    update_video_game_display_title_and_region, update_video_game_genres_if_empty,
+    update_video_game_global_rating_if_null, MediaLinkEntry, MediaUrl, PostIngestSummary,
 };
 use database_ops::playstation::prices::parse_pricing_minor;
+use database_ops::schema_caps::{SchemaCaps, SchemaRequirement};
+use database_ops::schema_profile::SchemaProfile;
+use normalization::display_title::TitleCandidate;
+use util::currency::minor_unit as currency_minor_unit;
+use util::lru::LruCache;
 // collections used later in function scope; kept minimal here
 
 use psstore_client::PsMedia;
@@ -40,29 +48,179 @@ struct LocaleContext {
     currency_code: String,
 }
 
-pub async fn psstore_seed_pipeline(db: &Db) -> Result<PostIngestSummary> {
-    async fn visible_table_exists(db: &Db, table: &str) -> Result<bool> {
-        let visible: bool = sqlx::query_scalar("SELECT to_regclass($1) IS NOT NULL")
-            .persistent(false)
-            .bind(table)
-            .fetch_one(&db.pool)
-            .await?;
-        Ok(visible)
+/// Row ids resolved by `psstore_seed_pipeline`, shared by the concurrently seeded locales.
+/// Each map holds at most `PS_CACHE_MAX_ENTRIES` entries when set; an evicted id is
+/// simply ensured again.
+#[derive(Default)]
+struct SeedCaches {
+    provider_items: LruCache<String, i64>, // product_id -> video_game_source_id
+    sellables: LruCache<i64, i64>,         // video_game_title_id -> sellable_id
+    offers: LruCache<(i64, Option<&'static str>), i64>, // (sellable_id, edition sku) -> offer_id
+    offer_jurisdictions: LruCache<(i64, i64), i64>, // (offer_id,jurisdiction_id) -> offer_jurisdiction_id
+}
+
+impl SeedCaches {
+    fn bounded(max_entries: Option<usize>) -> Self {
+        Self {
+            provider_items: LruCache::new(max_entries),
+            sellables: LruCache::new(max_entries),
+            offers: LruCache::new(max_entries),
+            offer_jurisdictions: LruCache::new(max_entries),
+        }
+    }
+
+    fn evictions(&self) -> u64 {
+        self.provider_items.evictions()
+            + self.sellables.evictions()
+            + self.offers.evictions()
+            + self.offer_jurisdictions.evictions()
+    }
+}
+
+#[derive(Default)]
+struct SharedSeedCaches(std::sync::Mutex<SeedCaches>);
+
+impl SharedSeedCaches {
+    fn bounded(max_entries: Option<usize>) -> Self {
+        Self(std::sync::Mutex::new(SeedCaches::bounded(max_entries)))
+    }
+
+    /// Run `f` under the lock; never held across an await.
+    fn with<R>(&self, f: impl FnOnce(&mut SeedCaches) -> R) -> R {
+        f(&mut self.0.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+/// What one locale contributes to a seed run, merged after every locale finishes.
+struct LocaleSeedOutput {
+    summary: PostIngestSummary,
+    ladders: Vec<PriceLadderSnapshot>,
+    excluded_items: usize,
+    /// Listings whose detail/rating fetch was skipped as recently enriched.
+    enrich_skipped: usize,
+    /// Latency of the per-item ensure step; `None` when nothing was ensured.
+    ensure_latency: Option<crate::util::latency::LatencySummary>,
+    stages: StageTimings,
+}
+
+/// Coarse pipeline stages timed per locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    /// Category grid pages.
+    List,
+    /// Per-item detail + star rating requests.
+    Detail,
+    /// Product/title/video game/offer rows for products not seen yet.
+    Ensure,
+    PriceWrite,
+    RatingUpsert,
+}
+
+impl Stage {
+    const ALL: [Stage; 5] = [
+        Stage::List,
+        Stage::Detail,
+        Stage::Ensure,
+        Stage::PriceWrite,
+        Stage::RatingUpsert,
+    ];
+
+    fn key(self) -> &'static str {
+        match self {
+            Stage::List => "list_ms",
+            Stage::Detail => "detail_ms",
+            Stage::Ensure => "ensure_ms",
+            Stage::PriceWrite => "price_write_ms",
+            Stage::RatingUpsert => "rating_upsert_ms",
+        }
+    }
+}
+
+/// Wall-clock time one locale spent in each [`Stage`]; whatever falls outside them
+/// (concept lookups, media, cursors) is reported as `other_ms`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct StageTimings {
+    spent: [std::time::Duration; Stage::ALL.len()],
+    total: std::time::Duration,
+}
+
+impl StageTimings {
+    fn add(&mut self, stage: Stage, elapsed: std::time::Duration) {
+        self.spent[stage as usize] += elapsed;
     }
 
+    /// Await `fut`, charging its wall time to `stage`.
+    async fn time<T>(&mut self, stage: Stage, fut: impl std::future::Future<Output = T>) -> T {
+        let t0 = std::time::Instant::now();
+        let out = fut.await;
+        self.add(stage, t0.elapsed());
+        out
+    }
+
+    fn spent(&self, stage: Stage) -> std::time::Duration {
+        self.spent[stage as usize]
+    }
+
+    fn other(&self) -> std::time::Duration {
+        self.total
+            .saturating_sub(self.spent.iter().sum::<std::time::Duration>())
+    }
+
+    fn to_json(self) -> Value {
+        let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+        let mut out = serde_json::Map::new();
+        for stage in Stage::ALL {
+            out.insert(stage.key().to_string(), json!(ms(self.spent(stage))));
+        }
+        out.insert("other_ms".to_string(), json!(ms(self.other())));
+        out.insert("total_ms".to_string(), json!(ms(self.total)));
+        Value::Object(out)
+    }
+}
+
+pub async fn psstore_seed_pipeline(db: &Db) -> Result<PostIngestSummary> {
+    let product_filter = database_ops::playstation::filter::ProductFilter::from_env();
+    psstore_seed_pipeline_with_filter(db, product_filter).await
+}
+
+/// [`psstore_seed_pipeline`] restricted by `product_filter` instead of the `PS_EXCLUDE_*` /
+/// `PS_INCLUDE_PRODUCTS` lists; targeted refreshes pass their products this way.
+pub async fn psstore_seed_pipeline_with_filter(
+    db: &Db,
+    product_filter: database_ops::playstation::filter::ProductFilter,
+) -> Result<PostIngestSummary> {
     // Config via env
     // Centralized dotenv & env helpers
     crate::util::env::init_env();
-    let regions = load_regions();
+    // PS_DRY_RUN=1: fetch and extract as usual but issue no SQL at all; ids stand in as 0
+    // and only the metrics export is written.
+    let dry_run = crate::util::env::env_flag("PS_DRY_RUN", false);
+    if dry_run {
+        println!("[psstore] dry run: database writes disabled");
+    }
+    // Dry runs and targeted refreshes poll every region and leave the cadence untouched.
+    let follow_cadence = !dry_run && product_filter.included_products().is_none();
+    let regions = if follow_cadence {
+        due_regions(&load_regions())
+    } else {
+        load_regions()
+    };
     if regions.is_empty() {
         return Ok(PostIngestSummary::default());
     }
 
+    // PS_REQUIRE_FULL_SCHEMA=1: refuse to run at all unless every table and ON CONFLICT
+    // key the pipeline writes to is present, rather than skipping or failing mid-run.
+    if !dry_run && crate::util::env::env_flag("PS_REQUIRE_FULL_SCHEMA", false) {
+        let requirements = psstore_schema_requirements(SchemaProfile::current(db).await?);
+        require_full_schema(db, SchemaCaps::global(), &requirements).await?;
+    }
+
     // php-compat: if the target database doesn't have the tables the PS store pipeline
     // requires, skip gracefully instead of failing hard.
     //
     // NOTE: we intentionally check *visibility* via search_path resolution because all
-    // queries in this binary are unqualified.
+    // queries in this binary are unqualified. Answers are cached for the process.
     let required_tables = [
         "platforms",
         "providers",
@@ -72,9 +230,15 @@ pub async fn psstore_seed_pipeline(db: &Db) -> Result<PostIngestSummary> {
         "video_games",
     ];
     let mut missing: Vec<&str> = Vec::new();
-    for t in required_tables {
-        if !visible_table_exists(db, t).await.unwrap_or(false) {
-            missing.push(t);
+    if !dry_run {
+        for t in required_tables {
+            if !SchemaCaps::global()
+                .table_visible(db, t)
+                .await
+                .unwrap_or(false)
+            {
+                missing.push(t);
+            }
         }
     }
     if !missing.is_empty() {
@@ -92,6 +256,8 @@ pub async fn psstore_seed_pipeline(db: &Db) -> Result<PostIngestSummary> {
     let year_min: i32 = env_parse("YEAR_MIN", 2020);
     let year_max: i32 = env_parse("YEAR_MAX", 2025);
     println!("remember: restricting to releases between {year_min}-{year_max} inclusive\n");
+    // Drop out-of-window grid items before their rating/detail/pricing fetches.
+    let year_prefilter = env_flag("PS_YEAR_PREFILTER", true);
     // Prefer PSSTORE_SHA256 if provided, else fall back to PS_HASH, else default
     let _ps_hash = env_opt("PSSTORE_SHA256")
         .or_else(|| env_opt("PS_HASH"))
@@ -108,17 +274,26 @@ pub async fn psstore_seed_pipeline(db: &Db) -> Result<PostIngestSummary> {
     let page_size: u32 = env_parse("PS_PAGE_SIZE", 100u32);
     let total_pages: u32 = env_parse("PS_TOTAL_PAGES", 500u32);
     let start_page: u32 = env_parse("PS_PAGE_START", 0u32);
+    let cursor_opts = database_ops::playstation::cursor::CursorOptions::from_env();
     let backfill_mode: bool = env_flag("PS_BACKFILL", true);
     // Deprecated: PS_CUTOFF_YEAR; superseded by YEAR_MIN/YEAR_MAX
     let _cutoff_year: i32 = env_parse("PS_CUTOFF_YEAR", year_min);
 
     // Ensure base static entities
-    let ps5_platform_id = ensure_platform(db, "PS5", Some("ps5")).await?;
-    let ps4_platform_id = ensure_platform(db, "PS4", Some("ps4")).await?;
-    let provider_id =
-        ensure_provider(db, "playstation_store", "storefront", Some("ps-store")).await?;
-    let retailer_id = ensure_retailer(db, "PlayStation", Some("playstation")).await?;
-    let mut post_summary = PostIngestSummary::default();
+    let (ps5_platform_id, ps4_platform_id, provider_id, retailer_id) = if dry_run {
+        (0, 0, 0, 0)
+    } else {
+        (
+            ensure_platform(db, "PS5", Some("ps5")).await?,
+            ensure_platform(db, "PS4", Some("ps4")).await?,
+            ensure_provider(db, "playstation_store", "storefront", Some("ps-store")).await?,
+            ensure_retailer(db, "PlayStation", Some("playstation")).await?,
+        )
+    };
+    let mut post_summary = PostIngestSummary {
+        dry_run,
+        ..PostIngestSummary::default()
+    };
 
     // Pre-create per-locale jurisdiction and cache currency_id to avoid repeated lookups later
     let mut locale_ctx: std::collections::HashMap<String, LocaleContext> =
@@ -127,9 +302,16 @@ pub async fn psstore_seed_pipeline(db: &Db) -> Result<PostIngestSummary> {
         let code2 = loc.split('-').nth(1).unwrap_or("us").to_uppercase();
         let (cur_code, cur_name) = currency_for_country(&code2);
         let mu = currency_minor_unit(cur_code);
-        let currency_id = ensure_currency(db, cur_code, cur_name, mu).await?;
-        let country_id = ensure_country(db, &code2, &code2, currency_id).await?;
-        let juris_id = ensure_national_jurisdiction(db, country_id).await?;
+        let (currency_id, juris_id) = if dry_run {
+            (0, 0)
+        } else {
+            let currency_id = ensure_currency(db, cur_code, cur_name, mu).await?;
+            let country_id = ensure_country(db, &code2, &code2, currency_id).await?;
+            (
+                currency_id,
+                ensure_national_jurisdiction(db, country_id).await?,
+            )
+        };
 
         // Cache both juris_id and currency_id per locale for later use
         locale_ctx.insert(
@@ -142,572 +324,790 @@ pub async fn psstore_seed_pipeline(db: &Db) -> Result<PostIngestSummary> {
         );
     }
 
-    // Aggregation maps across all locales
-    // (HashMap, HashSet already imported above)
-    struct GlobalAgg {
-        genres: std::collections::HashSet<String>,
-        rating_sum: f64,
-        rating_count: i64,
-        vg_id: i64,
-    }
-    let mut global_aggs: std::collections::HashMap<String, GlobalAgg> =
-        std::collections::HashMap::new(); // product_key -> agg
-    let mut provider_item_cache: std::collections::HashMap<String, i64> =
-        std::collections::HashMap::new(); // product_id -> video_game_source_id
-    let mut sellable_cache: std::collections::HashMap<i64, i64> = std::collections::HashMap::new(); // video_game_title_id -> sellable_id
-    let mut offer_cache: std::collections::HashMap<i64, i64> = std::collections::HashMap::new(); // sellable_id -> offer_id
-    let mut offer_juris_cache: std::collections::HashMap<(i64, i64), i64> =
-        std::collections::HashMap::new(); // (offer_id,jurisdiction_id) -> offer_jurisdiction_id
+    // product_key -> agg, shared by every locale (PS_AGG_SHARDS lock shards)
+    let agg_map = GlobalAggMap::new(env_parse("PS_AGG_SHARDS", 16usize));
+    let key_strategy = ProductKeyStrategy::from_env();
+    // Targeted refreshes (PS_INCLUDE_PRODUCTS) walk for specific products; they neither
+    // resume from nor move the catalogue walk's page cursor.
+    let use_cursor = product_filter.included_products().is_none() && !dry_run;
+    // PS_ENRICH_MAX_AGE_HOURS: skip detail/rating fetches for products an earlier run
+    // enriched within that window. Targeted refreshes always fetch.
+    let enrich_opts = database_ops::playstation::enrichment::EnrichOptions::from_env();
+    let use_enrich_markers =
+        enrich_opts.enabled() && product_filter.included_products().is_none() && !dry_run;
+    let run_started = Utc::now();
+    let mut excluded_items: usize = 0;
+    let mut enrich_skipped: usize = 0;
     let mut price_ladder_snapshots: Vec<PriceLadderSnapshot> = Vec::new();
 
-    for locale in &regions {
-        // HashSet already imported at module scope
-        use std::time::Instant;
-        let mut concept_id_cache: std::collections::HashMap<String, Option<String>> =
-            std::collections::HashMap::new();
-        let mut concept_price_cache: std::collections::HashMap<String, (Option<i64>, Option<i64>)> =
-            std::collections::HashMap::new();
-        let mut processed_products: std::collections::HashSet<String> =
-            std::collections::HashSet::new();
-        let mut ensure_durations: Vec<std::time::Duration> = Vec::new();
-        let cfg = PsConfig {
-            locales: vec![locale.clone()],
-            rps: rps_per_locale,
-            retry_attempts,
-            retry_base_delay_ms: retry_base_ms,
-            ..PsConfig::default()
-        };
-        let client = PsStoreClient::new(cfg);
-        if let Some(ctx) = locale_ctx.get(locale).cloned() {
-            for cat_id in [&cat_ps5, &cat_ps4] {
-                match fetch_price_buckets(&client, locale, cat_id).await {
-                    Ok(buckets) if !buckets.is_empty() => {
-                        log_price_buckets(locale, cat_id, &buckets);
-                        price_ladder_snapshots.push(PriceLadderSnapshot {
-                            locale: locale.clone(),
-                            category_id: (*cat_id).clone(),
-                            currency_code: ctx.currency_code.clone(),
-                            buckets,
-                        });
-                    }
-                    Ok(_) => {}
-                    Err(err) => {
-                        tracing::warn!(locale=%locale, category=%cat_id, error=%err, "psstore price ladder capture failed");
+    // Locales are seeded concurrently (PS_LOCALE_CONCURRENCY, default 2); each has its own
+    // client and rate limiter. Row-id caches and aggregates are shared (aggregates resolve
+    // to the region-order result whichever locale finishes first); ladders and summary
+    // counts are collected per locale and merged in region order below.
+    let locale_concurrency: usize = env_parse("PS_LOCALE_CONCURRENCY", 2usize).max(1);
+    // PS_STAGE_TIMERS=1: log each locale's time per stage and add it to the metrics export.
+    let stage_timers = crate::util::env::env_flag("PS_STAGE_TIMERS", false);
+    // PS_CACHE_MAX_ENTRIES: LRU bound for each per-run lookup cache (unset/0: unbounded).
+    // The global aggregates are results rather than a cache and are never evicted.
+    let cache_max_entries = crate::util::env::env_parse_opt::<usize>("PS_CACHE_MAX_ENTRIES");
+    let caches = SharedSeedCaches::bounded(cache_max_entries);
+    // PS_PRICE_AUDIT_PATH: JSON-lines record of every price row, written before ingest.
+    let price_audit = database_ops::playstation::audit::PriceAudit::from_env();
+    let price_audit = price_audit.as_ref();
+    // NOTIFY_COALESCE: per-page `ratings_upsert` notifications, or summaries per run/N pages.
+    let ratings_notify = database_ops::notify::NotifyBatcher::new(
+        "ratings_upsert",
+        database_ops::notify::NotifyCoalesce::from_env(),
+    );
+    // Without a unique (video_game_id, locale) index ON CONFLICT cannot be used; such
+    // php-compat schemas take the slower update-then-insert path.
+    let ratings_unique_key = dry_run || locale_ratings_have_unique_key(db).await.unwrap_or(true);
+    if !ratings_unique_key {
+        tracing::warn!(
+            "psstore_seed_pipeline: video_game_ratings_by_locale has no unique (video_game_id, locale) key; upserting ratings row by row (degraded)"
+        );
+    }
+    let (cat_ps4, cat_ps5, locale_ctx, product_filter, caches, ratings_notify) = (
+        &cat_ps4,
+        &cat_ps5,
+        &locale_ctx,
+        &product_filter,
+        &caches,
+        &ratings_notify,
+    );
+    let locale_outputs: Result<Vec<(usize, LocaleSeedOutput)>> = {
+        use futures::stream::{StreamExt, TryStreamExt};
+        let agg_map = &agg_map;
+        // Owned locales: a closure over borrowed ones is not general enough to be `Send`,
+        // which spawning the pipeline (ingest worker refresh jobs) requires.
+        futures::stream::iter(regions.iter().cloned().enumerate())
+            .map(|(locale_idx, locale)| async move {
+                use std::time::Instant;
+                let locale = &locale;
+                let mut post_summary = PostIngestSummary::default();
+                let mut excluded_items: usize = 0;
+                let mut enrich_skipped: usize = 0;
+                let mut price_ladder_snapshots: Vec<PriceLadderSnapshot> = Vec::new();
+                let mut concept_id_cache: LruCache<String, Option<String>> =
+                    LruCache::new(cache_max_entries);
+                let mut concept_price_cache: LruCache<String, (Option<i64>, Option<i64>)> =
+                    LruCache::new(cache_max_entries);
+                // product_key -> (product, title, video_game, sellable, offer) ids. A cross-buy
+                // title listed under both categories reuses the rows made on its first listing.
+                let mut processed_products: LruCache<String, (i64, i64, i64, i64, i64)> =
+                    LruCache::new(cache_max_entries);
+                let mut ensure_durations: Vec<std::time::Duration> = Vec::new();
+                let locale_started = Instant::now();
+                let mut stages = StageTimings::default();
+                let cfg = PsConfig {
+                    locales: vec![locale.clone()],
+                    rps: rps_per_locale,
+                    retry_attempts,
+                    retry_base_delay_ms: retry_base_ms,
+                    ..PsConfig::default()
+                };
+                let client = PsStoreClient::new(cfg);
+                if let Some(ctx) = locale_ctx.get(locale).cloned() {
+                    for cat_id in [cat_ps5, cat_ps4] {
+                        match fetch_price_buckets(&client, locale, cat_id).await {
+                            Ok(buckets) if !buckets.is_empty() => {
+                                log_price_buckets(locale, cat_id, &buckets);
+                                price_ladder_snapshots.push(PriceLadderSnapshot {
+                                    locale: locale.clone(),
+                                    category_id: (*cat_id).clone(),
+                                    currency_code: ctx.currency_code.clone(),
+                                    buckets,
+                                });
+                            }
+                            Ok(_) => {}
+                            Err(err) => {
+                                tracing::warn!(locale=%locale, category=%cat_id, error=%err, "psstore price ladder capture failed");
+                            }
+                        }
                     }
                 }
-            }
-        }
-        for (cat_id, platform_id) in [(&cat_ps5, ps5_platform_id), (&cat_ps4, ps4_platform_id)] {
-            let mut page = start_page;
-            let mut stop_due_to_year = false;
-            while page < start_page + total_pages && !stop_due_to_year {
-                let offset = page * page_size;
-                // Descending by release date to walk backwards in time; enforce YEAR_MIN..=YEAR_MAX
-                // Use productReleaseDate (PlayStation API expects this key); using releaseDate can cause ES shard errors
-                let list = client
-                    .category_grid_retrieve_sorted(
-                        locale,
-                        cat_id,
-                        page_size,
-                        offset,
-                        "productReleaseDate",
-                        false,
-                    )
-                    .await
-                    .unwrap_or_default();
-                if list.is_empty() {
-                    page += 1;
-                    continue;
-                }
-
-                // Collect rows for this page and write in batch
-                let mut price_rows: Vec<PriceRow> = Vec::with_capacity(list.len() * 2);
-
-                // Rating + detail fetch with semaphore to bound concurrency; maintain original order mapping
-                use futures::stream::{FuturesUnordered, StreamExt};
-                use futures::Future;
-                use std::pin::Pin;
-                use tokio::sync::Semaphore;
-                let rating_sem = std::sync::Arc::new(Semaphore::new(
-                    std::env::var("PS_RATING_CONCURRENCY")
-                        .ok()
-                        .and_then(|s| s.parse().ok())
-                        .unwrap_or(4),
-                ));
-                let items = list; // rename for reuse
-                let mut tasks: FuturesUnordered<
-                    Pin<
-                        Box<
-                            dyn Future<Output = (usize, Option<(f32, i64)>, serde_json::Value)>
-                                + Send,
-                        >,
-                    >,
-                > = FuturesUnordered::new();
-                for (idx, it) in items.iter().enumerate() {
-                    let fut: Pin<
-                        Box<
-                            dyn Future<Output = (usize, Option<(f32, i64)>, serde_json::Value)>
-                                + Send,
-                        >,
-                    > = if let Some(pid) = &it.product_id {
-                        let client_cloned = client.clone();
-                        let locale_cloned = locale.clone();
-                        let pid_cloned = pid.clone();
-                        let sem = rating_sem.clone();
-                        Box::pin(async move {
-                            let _permit = sem.acquire().await.ok();
-                            let rating = client_cloned
-                                .product_star_rating(&locale_cloned, &pid_cloned)
-                                .await
-                                .ok()
-                                .flatten();
-                            let detail = client_cloned
-                                .product_detail_raw(&locale_cloned, &pid_cloned)
-                                .await
-                                .unwrap_or_else(|_| serde_json::Value::Null);
-                            (idx, rating, detail)
-                        })
+                for (cat_id, platform_id, platform_label) in [
+                    (cat_ps5, ps5_platform_id, "PS5"),
+                    (cat_ps4, ps4_platform_id, "PS4"),
+                ] {
+                    use database_ops::playstation::cursor::{
+                        clear_cursor, load_cursor, resume_page, save_cursor,
+                    };
+                    use database_ops::playstation::enrichment::{
+                        mark_enriched, recently_enriched, stored_enrichment, StoredEnrichment,
+                    };
+                    let stored_cursor = if use_cursor {
+                        load_cursor(db, locale, cat_id).await?
                     } else {
-                        Box::pin(async move { (idx, None, serde_json::Value::Null) })
+                        None
                     };
-                    tasks.push(fut);
-                }
-
-                // Collect ratings + details into vectors indexed by original item order
-                let mut ratings: Vec<Option<(f32, i64)>> = vec![None; items.len()];
-                let mut details: Vec<serde_json::Value> =
-                    vec![serde_json::Value::Null; items.len()];
-                while let Some((idx, r, detail)) = tasks.next().await {
-                    if idx < ratings.len() {
-                        ratings[idx] = r;
-                        details[idx] = detail;
+                    let mut page = resume_page(stored_cursor, start_page, &cursor_opts, Utc::now());
+                    if page != start_page {
+                        tracing::info!(locale=%locale, category=%cat_id, page, "psstore resuming from stored page cursor");
                     }
-                }
+                    let mut stop_due_to_year = false;
+                    // PS_INCLUDE_PRODUCTS: included products not yet listed in this category;
+                    // paging stops once every one of them has been seen.
+                    let mut include_pending = product_filter.included_products().cloned();
+                    while page < start_page + total_pages
+                        && !stop_due_to_year
+                        && include_pending.as_ref().is_none_or(|pending| !pending.is_empty())
+                    {
+                        let offset = page * page_size;
+                        // Descending by release date to walk backwards in time; enforce YEAR_MIN..=YEAR_MAX
+                        // Use productReleaseDate (PlayStation API expects this key); using releaseDate can cause ES shard errors
+                        let mut list = stages
+                            .time(
+                                Stage::List,
+                                client.category_grid_retrieve_sorted(
+                                    locale,
+                                    cat_id,
+                                    page_size,
+                                    offset,
+                                    "productReleaseDate",
+                                    false,
+                                ),
+                            )
+                            .await
+                            .unwrap_or_default();
+                        // Filtering can empty whole pages (PS_INCLUDE_PRODUCTS), so take the
+                        // YEAR_MIN stop from the unfiltered page; the page itself is still processed.
+                        if list
+                            .iter()
+                            .filter_map(grid_release_year)
+                            .any(|year| year < year_min)
+                        {
+                            stop_due_to_year = true;
+                        }
+                        if let Some(pending) = include_pending.as_mut() {
+                            for pid in list.iter().filter_map(|it| it.product_id.as_deref()) {
+                                pending.remove(pid.trim());
+                            }
+                        }
+                        // Operator include/exclude lists apply before any detail fetch or DB write.
+                        excluded_items += product_filter.retain_allowed(&mut list);
+                        if year_prefilter {
+                            let dropped = retain_in_year_window(&mut list, year_min, year_max);
+                            if dropped > 0 {
+                                tracing::debug!(locale=%locale, category=%cat_id, page, dropped, "psstore: skipped out-of-window items before detail fetch");
+                            }
+                        }
+                        if list.is_empty() {
+                            if use_cursor {
+                                save_cursor(db, locale, cat_id, page).await?;
+                            }
+                            page += 1;
+                            continue;
+                        }
 
-                // Collect batch media rows for this page (will flush once)
-                let mut rating_rows: Vec<(i64, String, f32, i64)> = Vec::new();
-                for (idx, it) in items.into_iter().enumerate() {
-                    let mut it = it;
-                    let product_id_for_lookup = it.product_id.clone();
-                    let mut concept_id = it.concept_id.clone();
-                    if concept_id.is_none() {
-                        if let Some(pid) = product_id_for_lookup.as_ref() {
-                            if let Some(cached) = concept_id_cache.get(pid) {
-                                concept_id = cached.clone();
-                            } else {
-                                let fetched = match client
-                                    .concept_by_product_id_raw(locale, pid)
-                                    .await
-                                {
-                                    Ok(payload) => extract_concept_id_from_response(&payload),
-                                    Err(err) => {
-                                        tracing::warn!(locale=%locale, product_id=%pid, error=%err, "psstore concept lookup failed");
-                                        None
-                                    }
+                        // Collect rows for this page and write in batch
+                        let mut price_rows: Vec<PriceRow> = Vec::with_capacity(list.len() * 2);
+
+                        // Rating + detail fetch; at most PS_RATING_CONCURRENCY requests (and
+                        // futures) in flight, results kept in the page's item order.
+                        let items = list; // rename for reuse
+                        let rating_concurrency =
+                            crate::util::env::env_parse("PS_RATING_CONCURRENCY", 4usize);
+                        // Products enriched recently by an earlier run skip both requests.
+                        let skip_enrich = if use_enrich_markers {
+                            let product_ids: Vec<String> =
+                                items.iter().filter_map(|it| it.product_id.clone()).collect();
+                            recently_enriched(db, locale, &product_ids, &enrich_opts, run_started)
+                                .await?
+                        } else {
+                            std::collections::HashSet::new()
+                        };
+                        let detail_started = Instant::now();
+                        let fetched = fetch_in_order(items.len(), rating_concurrency, |idx| {
+                            let client = client.clone();
+                            let locale = locale.clone();
+                            let pid = items[idx]
+                                .product_id
+                                .clone()
+                                .filter(|pid| !skip_enrich.contains(pid));
+                            async move {
+                                let Some(pid) = pid else {
+                                    return (None, serde_json::Value::Null);
                                 };
-                                concept_id_cache.insert(pid.clone(), fetched.clone());
-                                concept_id = fetched;
+                                let rating = client
+                                    .product_star_rating(&locale, &pid)
+                                    .await
+                                    .ok()
+                                    .flatten();
+                                let detail = client
+                                    .product_detail_raw(&locale, &pid)
+                                    .await
+                                    .unwrap_or(serde_json::Value::Null);
+                                (rating, detail)
+                            }
+                        })
+                        .await;
+                        stages.add(Stage::Detail, detail_started.elapsed());
+                        let (ratings, details): (Vec<Option<(f32, i64)>>, Vec<serde_json::Value>) =
+                            fetched.into_iter().unzip();
+                        // The grid may omit conceptId; resolve it before the prewarm so both it
+                        // and the per-item exclusion re-check see the final ids.
+                        let mut items = items;
+                        for it in items.iter_mut().filter(|it| it.concept_id.is_none()) {
+                            let Some(pid) = it.product_id.clone() else {
+                                continue;
+                            };
+                            if let Some(cached) = concept_id_cache.get(&pid) {
+                                it.concept_id = cached.clone();
+                                continue;
                             }
+                            let fetched = match client.concept_by_product_id_raw(locale, &pid).await {
+                                Ok(payload) => extract_concept_id_from_response(&payload),
+                                Err(err) => {
+                                    tracing::warn!(locale=%locale, product_id=%pid, error=%err, "psstore concept lookup failed");
+                                    None
+                                }
+                            };
+                            concept_id_cache.insert(pid, fetched.clone());
+                            it.concept_id = fetched;
                         }
-                    }
-                    if it.concept_id.is_none() {
-                        it.concept_id = concept_id.clone();
-                    }
-                    if let Some(concept_id_value) = concept_id.clone() {
-                        let (base_minor, discount_minor) = if let Some(cached) =
-                            concept_price_cache.get(&concept_id_value)
-                        {
-                            *cached
+
+                        // Collect batch media rows for this page (will flush once)
+                        let mut rating_rows: Vec<(i64, String, f32, i64)> = Vec::new();
+                        let mut media_links: Vec<MediaLinkEntry> = Vec::new();
+                        // Product ids whose detail was fetched and written on this page.
+                        let mut enriched: Vec<String> = Vec::new();
+                        // (product_key, video_game, platforms) of listings that skipped enrichment.
+                        let mut skipped: Vec<(String, i64, Vec<String>)> = Vec::new();
+                        // Resolve the page's new products, titles and video_games in a few
+                        // multi-row statements; the per-item ensure below only covers misses.
+                        let prewarmed = if dry_run {
+                            std::collections::HashMap::new()
                         } else {
-                            match client.concept_pricing_raw(locale, &concept_id_value).await {
-                                Ok(payload) => {
-                                    let parsed = parse_pricing_minor(&payload);
-                                    concept_price_cache.insert(concept_id_value.clone(), parsed);
-                                    parsed
-                                }
+                            let page_products: Vec<(String, String)> = items
+                                .iter()
+                                .filter(|it| {
+                                    product_filter.allows(it.product_id.as_deref(), it.concept_id.as_deref())
+                                        && grid_release_year(it)
+                                            .is_none_or(|year| (year_min..=year_max).contains(&year))
+                                })
+                                .filter_map(|it| {
+                                    let title = base_title(it.name.as_deref().unwrap_or("unknown")).to_string();
+                                    let slug = normalize_title(&title);
+                                    let key = key_strategy.key(it.concept_id.as_deref(), it.product_id.as_deref(), &slug);
+                                    processed_products.get(&key).is_none().then_some((slug, title))
+                                })
+                                .collect();
+                            let t0 = Instant::now();
+                            let batch = ensure_products_batch(db, "software", platform_id, &page_products).await;
+                            stages.add(Stage::Ensure, t0.elapsed());
+                            match batch {
+                                Ok(batch) => batch.products,
                                 Err(err) => {
-                                    tracing::warn!(locale=%locale, concept_id=%concept_id_value, error=%err, "psstore concept pricing fetch failed");
-                                    concept_price_cache
-                                        .insert(concept_id_value.clone(), (None, None));
-                                    (None, None)
+                                    tracing::warn!(locale=%locale, category=%cat_id, page, error=%err, "psstore batched ensure failed; ensuring per product");
+                                    std::collections::HashMap::new()
                                 }
                             }
                         };
-                        if let Some(b) = base_minor.filter(|v| *v > 0) {
-                            it.base_price_minor = Some(b);
-                        }
-                        if let Some(d) = discount_minor.filter(|v| *v > 0) {
-                            it.discounted_price_minor = Some(d);
-                        }
-                    } else if product_id_for_lookup.is_some() {
-                        tracing::debug!(locale=%locale, product_id=?product_id_for_lookup, "psstore conceptId unavailable after lookup");
-                    }
+                        for (idx, it) in items.into_iter().enumerate() {
+                            let mut it = it;
+                            let product_id_for_lookup = it.product_id.clone();
+                            let concept_id = it.concept_id.clone();
+                            // Re-check exclusions against the resolved conceptId.
+                            if !product_filter.allows(it.product_id.as_deref(), concept_id.as_deref()) {
+                                excluded_items += 1;
+                                continue;
+                            }
+                            if let Some(concept_id_value) = concept_id.clone() {
+                                let (base_minor, discount_minor) = if let Some(cached) =
+                                    concept_price_cache.get(&concept_id_value)
+                                {
+                                    *cached
+                                } else {
+                                    match client.concept_pricing_raw(locale, &concept_id_value).await {
+                                        Ok(payload) => {
+                                            let parsed = parse_pricing_minor(&payload);
+                                            concept_price_cache.insert(concept_id_value.clone(), parsed);
+                                            parsed
+                                        }
+                                        Err(err) => {
+                                            tracing::warn!(locale=%locale, concept_id=%concept_id_value, error=%err, "psstore concept pricing fetch failed");
+                                            concept_price_cache
+                                                .insert(concept_id_value.clone(), (None, None));
+                                            (None, None)
+                                        }
+                                    }
+                                };
+                                if let Some(b) = base_minor.filter(|v| *v > 0) {
+                                    it.base_price_minor = Some(b);
+                                }
+                                if let Some(d) = discount_minor.filter(|v| *v > 0) {
+                                    it.discounted_price_minor = Some(d);
+                                }
+                            } else if product_id_for_lookup.is_some() {
+                                tracing::debug!(locale=%locale, product_id=?product_id_for_lookup, "psstore conceptId unavailable after lookup");
+                            }
 
-                    // Title + slug
-                    let title = it.name.clone().unwrap_or_else(|| "unknown".to_string());
-                    let slug = normalize_title(&title);
+                            // Title + slug
+                            // Editions share the base game's product hierarchy; the tier is kept on
+                            // the source metadata and price rows so comparisons can tell them apart.
+                            let listed_title = it.name.clone().unwrap_or_else(|| "unknown".to_string());
+                            let edition = details
+                                .get(idx)
+                                .and_then(extract_edition)
+                                .or_else(|| edition_in_text(&listed_title).map(|(tier, _)| tier));
+                            let title = base_title(&listed_title).to_string();
+                            let slug = normalize_title(&title);
+                            // Each non-standard edition gets its own offer (sku = tier), so its
+                            // prices land on their own offer_jurisdiction.
+                            let offer_sku = edition.filter(|tier| *tier != "standard");
+
+                            // Check release year for window (parse first 4 digits if present)
+                            if let Some(release_year) = grid_release_year(&it) {
+                                // Skip items newer than YEAR_MAX to keep the window tight
+                                if release_year > year_max {
+                                    continue;
+                                }
+                                // Stop once we've crossed below YEAR_MIN (we are in descending order)
+                                if release_year < year_min {
+                                    stop_due_to_year = true;
+                                    break;
+                                }
+                            }
 
-                    // Check release year for window (parse first 4 digits if present)
-                    if let Some(release_year) = it
-                        .release_date
-                        .as_ref()
-                        .and_then(|d| d.get(0..4))
-                        .and_then(|y| y.parse::<i32>().ok())
-                    {
-                        // Skip items newer than YEAR_MAX to keep the window tight
-                        if release_year > year_max {
-                            continue;
-                        }
-                        // Stop once we've crossed below YEAR_MIN (we are in descending order)
-                        if release_year < year_min {
-                            stop_due_to_year = true;
-                            break;
-                        }
-                    }
+                            // Ensure product hierarchy only once per product across locales
+                            let product_key =
+                                key_strategy.key(it.concept_id.as_deref(), it.product_id.as_deref(), &slug);
+                            let offer_key = match offer_sku {
+                                Some(sku) => format!("{product_key}#{sku}"),
+                                None => product_key.clone(),
+                            };
+                            post_summary.extraction.products_seen += 1;
+                            let (_product_id, _title_id, _vg_id, _sellable_id, offer_id) = if dry_run {
+                                (0, 0, 0, 0, 0)
+                            } else if let Some(&ids) = processed_products.get(&offer_key) {
+                                ids
+                            } else {
+                                let t0 = Instant::now();
+                                let (product_id, title_id, _vg_id) = match prewarmed.get(&slug) {
+                                    Some(p) => (p.product_id, p.title_id, p.video_game_id),
+                                    None => {
+                                        let product_id =
+                                            ensure_product_named(db, "software", &slug, &title).await?;
+                                        ensure_software_row(db, product_id).await?;
+                                        let title_id =
+                                            ensure_video_game_title(db, product_id, &title, Some(&slug)).await?;
+                                        let vg_id = ensure_video_game(db, title_id, platform_id, None).await?;
+                                        (product_id, title_id, vg_id)
+                                    }
+                                };
+                                let cached_sellable =
+                                    caches.with(|c| c.sellables.get(&title_id).copied());
+                                let sellable_id = match cached_sellable {
+                                    Some(sid) => sid,
+                                    None => {
+                                        let sid = ensure_sellable(db, "software", product_id).await?;
+                                        caches.with(|c| c.sellables.insert(title_id, sid));
+                                        sid
+                                    }
+                                };
+                                let cached_offer =
+                                    caches.with(|c| c.offers.get(&(sellable_id, offer_sku)).copied());
+                                let offer_id = match cached_offer {
+                                    Some(oid) => oid,
+                                    None => {
+                                        let oid = ensure_offer(db, sellable_id, retailer_id, offer_sku).await?;
+                                        caches.with(|c| c.offers.insert((sellable_id, offer_sku), oid));
+                                        oid
+                                    }
+                                };
+                                let ids = (product_id, title_id, _vg_id, sellable_id, offer_id);
+                                processed_products.insert(offer_key, ids);
+                                ensure_durations.push(t0.elapsed());
+                                stages.add(Stage::Ensure, t0.elapsed());
+                                ids
+                            };
 
-                    // Ensure product hierarchy only once per product across locales
-                    let product_key = it.product_id.clone().unwrap_or_else(|| slug.clone());
-                    let (_product_id, _title_id, _vg_id, _sellable_id, offer_id) =
-                        if !processed_products.contains(&product_key) {
-                            let t0 = Instant::now();
-                            let product_id =
-                                ensure_product_named(db, "software", &slug, &title).await?;
-                            ensure_software_row(db, product_id).await?;
-                            let title_id =
-                                ensure_video_game_title(db, product_id, &title, Some(&slug))
-                                    .await?;
-                            let _vg_id = ensure_video_game(db, title_id, platform_id, None).await?;
-                            let sellable_id = match sellable_cache.get(&title_id) {
-                                Some(&sid) => sid,
+                            // Map locale to offer jurisdiction and cached currency_id
+                            let locale_meta = locale_ctx
+                                .get(locale)
+                                .expect("jurisdiction & currency for locale");
+                            let juris_id = locale_meta.jurisdiction_id;
+                            let currency_id = locale_meta.currency_id;
+                            let cached_oj = caches.with(|c| {
+                                c.offer_jurisdictions.get(&(offer_id, juris_id)).copied()
+                            });
+                            let oj_id = match cached_oj {
+                                _ if dry_run => 0,
+                                Some(cached) => cached,
                                 None => {
-                                    let sid = ensure_sellable(db, "software", product_id).await?;
-                                    sellable_cache.insert(title_id, sid);
-                                    sid
+                                    let new_id =
+                                        ensure_offer_jurisdiction(db, offer_id, juris_id, currency_id)
+                                            .await?;
+                                    caches.with(|c| {
+                                        c.offer_jurisdictions.insert((offer_id, juris_id), new_id)
+                                    });
+                                    new_id
                                 }
                             };
-                            let offer_id = match offer_cache.get(&sellable_id) {
-                                Some(&oid) => oid,
-                                None => {
-                                    let oid =
-                                        ensure_offer(db, sellable_id, retailer_id, None).await?;
-                                    offer_cache.insert(sellable_id, oid);
-                                    oid
+                            if !dry_run {
+                                post_summary.offer_jurisdiction_ids.insert(oj_id);
+                            }
+
+                            // Extract genres from detail payload if available (metGetProductById response)
+                            let mut genres: Vec<String> = Vec::new();
+                            let mut platforms: Vec<String> = vec![platform_label.to_string()];
+                            let mut detail_media_sets: Option<(Vec<PsMedia>, Vec<PsMedia>)> = None;
+                            if let Some(detail_obj) = details.get(idx) {
+                                genres = extract_genres(detail_obj);
+                                platforms.extend(extract_platforms(detail_obj));
+                                post_summary.extraction.genres_extracted += genres.len();
+                                if !genres.is_empty() {
+                                    tracing::debug!(video_game_id=_vg_id, %slug, genres=?genres, "psstore genres extracted");
                                 }
-                            };
-                            processed_products.insert(product_key.clone());
-                            ensure_durations.push(t0.elapsed());
-                            (product_id, title_id, _vg_id, sellable_id, offer_id)
-                        } else {
-                            // Lookup existing rows cheaply
-                            let row = sqlx
-                            ::query(
-                                "SELECT p.id as product_id, vgt.id as title_id, vg.id as vg_id, s.id as sellable_id, o.id as offer_id FROM products p JOIN video_game_titles vgt ON vgt.video_game_id=p.id JOIN video_games vg ON vg.title_id=vgt.id JOIN sellables s ON s.software_title_id=vgt.id JOIN offers o ON o.sellable_id=s.id WHERE vgt.normalized_title=$1 LIMIT 1"
-                            )
-                            .persistent(false)
-                            .bind(&slug)
-                            .fetch_optional(&db.pool).await?;
-                            use sqlx::Row;
-                            if let Some(r) = row {
-                                (
-                                    r.get::<i64, _>("product_id"),
-                                    r.get::<i64, _>("title_id"),
-                                    r.get::<i64, _>("vg_id"),
-                                    r.get::<i64, _>("sellable_id"),
-                                    r.get::<i64, _>("offer_id"),
-                                )
-                            } else {
-                                // Fallback: run ensures (rare)
-                                let product_id =
-                                    ensure_product_named(db, "software", &slug, &title).await?;
-                                ensure_software_row(db, product_id).await?;
-                                let title_id =
-                                    ensure_video_game_title(db, product_id, &title, Some(&slug))
-                                        .await?;
-                                let _vg_id =
-                                    ensure_video_game(db, title_id, platform_id, None).await?;
-                                let sellable_id =
-                                    ensure_sellable(db, "software", product_id).await?;
-                                let offer_id =
-                                    ensure_offer(db, sellable_id, retailer_id, None).await?;
-                                (product_id, title_id, _vg_id, sellable_id, offer_id)
+                                if let Some(syn) = extract_synopsis(detail_obj) {
+                                    post_summary
+                                        .extraction
+                                        .synopsis_lengths
+                                        .push(syn.chars().count());
+                                    if !dry_run {
+                                        let _ = merge_video_game_metadata(db, _vg_id, "ps-store", json!({ "synopsis": syn }))
+                                            .await;
+                                    }
+                                }
+                                // Backfill: also set display_title and union region code, mirroring prices ingest behavior
+                                if !dry_run {
+                                    let _ = update_video_game_display_title_and_region(
+                                        db,
+                                        _vg_id,
+                                        &TitleCandidate::new(&title, "ps-store", Some(locale)),
+                                        Some(locale.split('-').nth(1).unwrap_or("us")),
+                                    )
+                                    .await;
+                                }
+                                detail_media_sets = extract_detail_media(detail_obj);
                             }
-                        };
 
-                    // Map locale to offer jurisdiction and cached currency_id
-                    let locale_meta = locale_ctx
-                        .get(locale)
-                        .expect("jurisdiction & currency for locale");
-                    let juris_id = locale_meta.jurisdiction_id;
-                    let currency_id = locale_meta.currency_id;
-                    let oj_id = match offer_juris_cache.get(&(offer_id, juris_id)) {
-                        Some(&cached) => cached,
-                        None => {
-                            let new_id =
-                                ensure_offer_jurisdiction(db, offer_id, juris_id, currency_id)
-                                    .await?;
-                            offer_juris_cache.insert((offer_id, juris_id), new_id);
-                            new_id
-                        }
-                    };
-                    post_summary.offer_jurisdiction_ids.insert(oj_id);
-
-                    // Extract genres from detail payload if available (metGetProductById response)
-                    let mut genres: Vec<String> = Vec::new();
-                    let mut detail_media_sets: Option<(Vec<PsMedia>, Vec<PsMedia>)> = None;
-                    if let Some(detail_obj) = details.get(idx) {
-                        genres = extract_genres(detail_obj);
-                        if !genres.is_empty() {
-                            tracing::debug!(video_game_id=_vg_id, %slug, genres=?genres, "psstore genres extracted");
-                        }
-                        if let Some(syn) = extract_synopsis(detail_obj) {
-                            let _ =
-                                update_video_game_synopsis_prefer_longer(db, _vg_id, &syn).await;
-                        }
-                        // Backfill: also set display_title and union region code, mirroring prices ingest behavior
-                        let _ = update_video_game_display_title_and_region(
-                            db,
-                            _vg_id,
-                            &title,
-                            locale.split('-').nth(1).unwrap_or("us"),
-                        )
-                        .await;
-                        detail_media_sets = extract_detail_media(detail_obj);
-                    }
+                            // Backfill: write genres array directly on video_games if present
+                            if !genres.is_empty() && !dry_run {
+                                let _ = merge_video_game_metadata(db, _vg_id, "ps-store", json!({ "genres": genres }))
+                                    .await;
+                            }
 
-                    // Backfill: write genres array directly on video_games if present
-                    if !genres.is_empty() {
-                        let _ = update_video_game_genres(db, _vg_id, &genres).await;
-                    }
+                            // Provider mapping (if we have an external id)
+                            let mut video_game_source_id: Option<i64> = None;
+                            if let Some(ext) = &it.product_id {
+                                if !dry_run {
+                                    let cached_pid =
+                                        caches.with(|c| c.provider_items.get(ext).copied());
+                                    let pid = if let Some(cached) = cached_pid {
+                                        cached
+                                    } else {
+                                        let pid = ensure_provider_item(db, provider_id, ext, None).await?;
+                                        caches.with(|c| c.provider_items.insert(ext.clone(), pid));
+                                        pid
+                                    };
+                                    post_summary.record_provider_item(pid);
+                                    video_game_source_id = Some(pid);
+                                    link_provider_offer(db, pid, offer_id, Some(0.9)).await?;
+                                    if let Some(edition) = edition {
+                                        merge_provider_item_metadata(
+                                            db,
+                                            pid,
+                                            json!({ "edition": edition }),
+                                        )
+                                        .await?;
+                                    }
+                                }
+                                // Media links (images + videos) with refined role classification & meta
+                                let mut urls: Vec<MediaUrl> = Vec::new();
+                                // Attempt to use detailed media (higher fidelity) if present
+                                let push_summary_media = |targets: &mut Vec<MediaUrl>| {
+                                    for u in &it.media_image_urls {
+                                        targets.push((
+                                            u.clone(),
+                                            Some("image".into()),
+                                            Some("screenshot".into()),
+                                            it.name.clone(),
+                                        ));
+                                    }
+                                    for u in &it.media_video_urls {
+                                        targets.push((
+                                            u.clone(),
+                                            Some("video".into()),
+                                            Some("trailer".into()),
+                                            it.name.clone(),
+                                        ));
+                                    }
+                                };
+                                if let Some((ref detail_images, ref detail_videos)) = detail_media_sets {
+                                    let mut detail_added = false;
+                                    for m in detail_images {
+                                        if let Some(url) = m.url.as_deref() {
+                                            detail_added = true;
+                                            let role_raw = m.role.as_deref().unwrap_or("");
+                                            let classified = classify_image_role(role_raw)
+                                                .unwrap_or_else(|| {
+                                                    if !role_raw.trim().is_empty() {
+                                                        record_unknown_image_role(role_raw);
+                                                    }
+                                                    "screenshot"
+                                                })
+                                                .to_string();
+                                            urls.push((
+                                                url.to_string(),
+                                                Some("image".into()),
+                                                Some(classified),
+                                                it.name.clone(),
+                                            ));
+                                        }
+                                    }
+                                    for m in detail_videos {
+                                        if let Some(url) = m.url.as_deref() {
+                                            detail_added = true;
+                                            let role_raw = m.role.as_deref().unwrap_or("");
+                                            urls.push((
+                                                url.to_string(),
+                                                Some("video".into()),
+                                                Some(
+                                                    classify_video_role(role_raw, url, m.duration_secs)
+                                                        .to_string(),
+                                                ),
+                                                it.name.clone(),
+                                            ));
+                                        }
+                                    }
+                                    if !detail_added {
+                                        push_summary_media(&mut urls);
+                                    }
+                                } else {
+                                    push_summary_media(&mut urls);
+                                }
+                                let images = urls
+                                    .iter()
+                                    .filter(|(_, kind, _, _)| kind.as_deref() == Some("image"))
+                                    .count();
+                                post_summary.extraction.media_images += images;
+                                post_summary.extraction.media_videos += urls.len() - images;
+                                if !urls.is_empty() && !dry_run {
+                                    let meta = serde_json::json!({
+                                        "locale": locale,
+                                        "platform_id": platform_id,
+                                        "genres": genres,
+                                    });
+                                    media_links.push(MediaLinkEntry {
+                                        video_game_source_id: video_game_source_id.unwrap(),
+                                        video_game_id: Some(_vg_id),
+                                        urls,
+                                        meta: Some(meta),
+                                    });
+                                }
+                            }
 
-                    // Provider mapping (if we have an external id)
-                    let mut video_game_source_id: Option<i64> = None;
-                    if let Some(ext) = &it.product_id {
-                        let pid = if let Some(cached) = provider_item_cache.get(ext) {
-                            *cached
-                        } else {
-                            let pid = ensure_provider_item(db, provider_id, ext, None).await?;
-                            provider_item_cache.insert(ext.clone(), pid);
-                            pid
-                        };
-                        post_summary.record_provider_item(pid);
-                        video_game_source_id = Some(pid);
-                        link_provider_offer(db, pid, offer_id, Some(0.9)).await?;
-                        // Media links (images + videos) with refined role classification & meta
-                        let mut urls: Vec<(
-                            String,
-                            Option<String>,
-                            Option<String>,
-                            Option<String>,
-                        )> = Vec::new();
-                        // Attempt to use detailed media (higher fidelity) if present
-                        let push_summary_media = |targets: &mut Vec<(
-                            String,
-                            Option<String>,
-                            Option<String>,
-                            Option<String>,
-                        )>| {
-                            for u in &it.media_image_urls {
-                                targets.push((
-                                    u.clone(),
-                                    Some("image".into()),
-                                    Some("screenshot".into()),
-                                    it.name.clone(),
-                                ));
+                            // Prices: only enqueue when not in backfill mode
+                            if !backfill_mode {
+                                let now = Utc::now();
+                                if let Some(base) = it.base_price_minor {
+                                    if base > 0 {
+                                        price_rows.push(PriceRow {
+                                            offer_jurisdiction_id: oj_id,
+                                            video_game_source_id,
+                                            recorded_at: now,
+                                            amount_minor: base,
+                                            tax_inclusive: true,
+                                            fx_minor_per_unit: None,
+                                            btc_sats_per_unit: None,
+                                            meta: json!({"src":"psstore","kind":"base","locale":locale,"edition":edition}),
+                                            video_game_id: Some(_vg_id),
+                                            currency: None,
+                                            country_code: Some(locale.clone()),
+                                            retailer: None,
+                                        });
+                                    }
+                                }
+                                if let Some(discount) = it.discounted_price_minor {
+                                    if discount > 0 {
+                                        price_rows.push(PriceRow {
+                                            offer_jurisdiction_id: oj_id,
+                                            video_game_source_id,
+                                            recorded_at: now,
+                                            amount_minor: discount,
+                                            tax_inclusive: true,
+                                            fx_minor_per_unit: None,
+                                            btc_sats_per_unit: None,
+                                            meta: json!({"src":"psstore","kind":"discount","locale":locale,"edition":edition}),
+                                            video_game_id: Some(_vg_id),
+                                            currency: None,
+                                            country_code: Some(locale.clone()),
+                                            retailer: None,
+                                        });
+                                    }
+                                }
                             }
-                            for u in &it.media_video_urls {
-                                targets.push((
-                                    u.clone(),
-                                    Some("video".into()),
-                                    Some("trailer".into()),
-                                    it.name.clone(),
-                                ));
+
+                            // Per-locale rating row
+                            let rating = ratings.get(idx).cloned().flatten();
+                            if let Some((avg, cnt)) = rating {
+                                rating_rows.push((_vg_id, locale.clone(), avg, cnt));
                             }
-                        };
-                        if let Some((ref detail_images, ref detail_videos)) = detail_media_sets {
-                            let mut detail_added = false;
-                            for m in detail_images {
-                                if let Some(url) = m.url.as_deref() {
-                                    detail_added = true;
-                                    let role_raw = m.role.as_deref().unwrap_or("");
-                                    let classified = classify_image_role(role_raw)
-                                        .unwrap_or("screenshot")
-                                        .to_string();
-                                    urls.push((
-                                        url.to_string(),
-                                        Some("image".into()),
-                                        Some(classified),
-                                        it.name.clone(),
-                                    ));
+                            // A skipped listing fetched no rating or genres; it aggregates what
+                            // earlier runs stored instead, once the page's items are done.
+                            match it.product_id {
+                                Some(ref pid) if skip_enrich.contains(pid) => {
+                                    enrich_skipped += 1;
+                                    skipped.push((product_key, _vg_id, platforms));
+                                    continue;
                                 }
-                            }
-                            for m in detail_videos {
-                                if let Some(url) = m.url.as_deref() {
-                                    detail_added = true;
-                                    let role_raw = m.role.as_deref().unwrap_or("");
-                                    urls.push((
-                                        url.to_string(),
-                                        Some("video".into()),
-                                        Some(classify_video_role(role_raw).to_string()),
-                                        it.name.clone(),
-                                    ));
+                                Some(ref pid) if details.get(idx).is_some_and(|d| !d.is_null()) => {
+                                    enriched.push(pid.clone());
                                 }
+                                _ => {}
                             }
-                            if !detail_added {
-                                push_summary_media(&mut urls);
+                            // Global aggregation (genres are aggregated even if the rating is missing)
+                            agg_map.merge_rating(&product_key, locale_idx, locale, _vg_id, rating);
+                            agg_map.merge_genres(
+                                &product_key,
+                                locale_idx,
+                                locale,
+                                _vg_id,
+                                &genres,
+                                &platforms,
+                            );
+                        }
+                        if !skipped.is_empty() {
+                            let vg_ids: Vec<i64> = skipped.iter().map(|(_, vg_id, _)| *vg_id).collect();
+                            let mut stored = stored_enrichment(db, locale, &vg_ids).await?;
+                            for (product_key, vg_id, platforms) in skipped {
+                                let StoredEnrichment { rating, genres } =
+                                    stored.remove(&vg_id).unwrap_or_default();
+                                agg_map.merge_rating(&product_key, locale_idx, locale, vg_id, rating);
+                                agg_map.merge_genres(
+                                    &product_key,
+                                    locale_idx,
+                                    locale,
+                                    vg_id,
+                                    &genres,
+                                    &platforms,
+                                );
                             }
-                        } else {
-                            push_summary_media(&mut urls);
                         }
-                        if !urls.is_empty() {
-                            let meta = serde_json::json!({
-                                "locale": locale,
-                                "platform_id": platform_id,
-                                "genres": genres,
-                            });
-                            let _ = ensure_vg_source_media_links_with_meta(
-                                db,
-                                video_game_source_id.unwrap(),
-                                Some(_vg_id),
-                                &urls,
-                                "psstore",
-                                Some(meta),
-                            )
-                            .await?;
+
+                        if !media_links.is_empty() {
+                            ensure_vg_source_media_links_batch(db, &media_links, "psstore").await?;
                         }
-                    }
 
-                    // Prices: only enqueue when not in backfill mode
-                    if !backfill_mode {
-                        let now = Utc::now();
-                        if let Some(base) = it.base_price_minor {
-                            if base > 0 {
-                                price_rows.push(PriceRow {
-                                    offer_jurisdiction_id: oj_id,
-                                    video_game_source_id,
-                                    recorded_at: now,
-                                    amount_minor: base,
-                                    tax_inclusive: true,
-                                    fx_minor_per_unit: None,
-                                    btc_sats_per_unit: None,
-                                    meta: json!({"src":"psstore","kind":"base","locale":locale}),
-                                    video_game_id: Some(_vg_id),
-                                    currency: None,
-                                    country_code: Some(locale.clone()),
-                                    retailer: None,
-                                });
-                            }
+                        if !rating_rows.is_empty() && !dry_run {
+                            stages
+                                .time(
+                                    Stage::RatingUpsert,
+                                    upsert_locale_ratings(db, &rating_rows, ratings_unique_key),
+                                )
+                                .await?;
+                            // Realtime notify (optional)
+                            ratings_notify.record(db, rating_rows.len() as u64).await;
                         }
-                        if let Some(discount) = it.discounted_price_minor {
-                            if discount > 0 {
-                                price_rows.push(PriceRow {
-                                    offer_jurisdiction_id: oj_id,
-                                    video_game_source_id,
-                                    recorded_at: now,
-                                    amount_minor: discount,
-                                    tax_inclusive: true,
-                                    fx_minor_per_unit: None,
-                                    btc_sats_per_unit: None,
-                                    meta: json!({"src":"psstore","kind":"discount","locale":locale}),
-                                    video_game_id: Some(_vg_id),
-                                    currency: None,
-                                    country_code: Some(locale.clone()),
-                                    retailer: None,
-                                });
+
+                        if !backfill_mode && !dry_run && !price_rows.is_empty() {
+                            if let Some(audit) = price_audit {
+                                let currency_code = locale_ctx
+                                    .get(locale)
+                                    .map_or("", |ctx| ctx.currency_code.as_str());
+                                audit.append(&price_rows, currency_code).await;
                             }
+                            let batch_len = price_rows.len();
+                            let ingest_result = stages
+                                .time(Stage::PriceWrite, ingest_prices(db, price_rows))
+                                .await?;
+                            post_summary.record_batch(batch_len, &ingest_result);
                         }
-                    }
 
-                    // Per-locale rating row
-                    if let Some((avg, cnt)) = ratings.get(idx).cloned().flatten() {
-                        rating_rows.push((_vg_id, locale.clone(), avg, cnt));
-                        // Global aggregation
-                        let entry = global_aggs.entry(product_key.clone()).or_insert(GlobalAgg {
-                            genres: std::collections::HashSet::new(),
-                            rating_sum: 0.0,
-                            rating_count: 0,
-                            vg_id: _vg_id,
-                        });
-                        entry.rating_sum += (avg as f64) * (cnt as f64);
-                        entry.rating_count += cnt;
-                        for g in &genres {
-                            entry.genres.insert(g.clone());
+                        if use_enrich_markers {
+                            mark_enriched(db, locale, &enriched).await?;
                         }
-                    } else {
-                        // Still aggregate genres even if rating missing
-                        let entry = global_aggs.entry(product_key.clone()).or_insert(GlobalAgg {
-                            genres: std::collections::HashSet::new(),
-                            rating_sum: 0.0,
-                            rating_count: 0,
-                            vg_id: _vg_id,
-                        });
-                        for g in &genres {
-                            entry.genres.insert(g.clone());
+                        if use_cursor {
+                            save_cursor(db, locale, cat_id, page).await?;
                         }
+                        page += 1;
                     }
-                }
-
-                if !rating_rows.is_empty() {
-                    // Bulk upsert ratings_by_locale
-                    use sqlx::QueryBuilder;
-                    let mut qb = QueryBuilder::new(
-                        "INSERT INTO video_game_ratings_by_locale (video_game_id, locale, average_rating, rating_count, rating_updated_at) VALUES ",
-                    );
-                    let mut sep = qb.separated(", ");
-                    for (vg_id, loc, avg, cnt) in &rating_rows {
-                        sep.push("(")
-                            .push_bind(vg_id)
-                            .push(", ")
-                            .push_bind(loc)
-                            .push(", ")
-                            .push_bind(avg)
-                            .push(", ")
-                            .push_bind(cnt)
-                            .push(", now())");
+                    if use_cursor {
+                        clear_cursor(db, locale, cat_id).await?;
                     }
-                    qb.push(
-                        " ON CONFLICT (video_game_id, locale) DO UPDATE SET average_rating=EXCLUDED.average_rating, rating_count=EXCLUDED.rating_count, rating_updated_at=now()"
+                }
+                let ensure_latency =
+                    crate::util::latency::LatencySummary::from_durations(&ensure_durations);
+                if let Some(l) = &ensure_latency {
+                    println!(
+                        "[psstore] ensure metrics locale={locale} count={} avg_ms={:.2} p50_ms={:.2} p95_ms={:.2} p99_ms={:.2}",
+                        l.count, l.avg_ms, l.p50_ms, l.p95_ms, l.p99_ms
                     );
-                    qb.build().execute(&db.pool).await?;
-                    // Realtime notify (optional)
-                    let _ = sqlx::query("SELECT pg_notify('ratings_upsert', $1)")
-                        .persistent(false)
-                        .bind(format!("{{\"count\":{}}}", rating_rows.len()))
-                        .execute(&db.pool)
-                        .await;
                 }
-
-                if !backfill_mode {
-                    if !price_rows.is_empty() {
-                        let batch_len = price_rows.len();
-                        let ingest_result = ingest_prices(db, price_rows).await?;
-                        post_summary.record_batch(batch_len, &ingest_result);
-                    }
+                stages.total = locale_started.elapsed();
+                if stage_timers {
+                    println!("[psstore] stage timings locale={locale} {}", stages.to_json());
                 }
-
-                page += 1;
-            }
+                let stats = client.stats();
+                println!(
+                    "[psstore] client metrics locale={locale} requests={} bytes_in={} cache_hits={}",
+                    stats.requests, stats.bytes_in, stats.cache_hits
+                );
+                if follow_cadence {
+                    mark_region_polled(locale);
+                }
+                Ok::<_, anyhow::Error>((
+                    locale_idx,
+                    LocaleSeedOutput {
+                        summary: post_summary,
+                        ladders: price_ladder_snapshots,
+                        excluded_items,
+                        enrich_skipped,
+                        ensure_latency,
+                        stages,
+                    },
+                ))
+            })
+            .buffer_unordered(locale_concurrency)
+            .try_collect()
+            .await
+    };
+    // Ratings already written by the other locales are announced even when one failed.
+    ratings_notify.flush(db).await;
+    let mut locale_outputs = locale_outputs?;
+    if let Some(max_entries) = cache_max_entries {
+        println!(
+            "[psstore] shared caches bounded max_entries={max_entries} evictions={}",
+            caches.with(|c| c.evictions())
+        );
+    }
+    locale_outputs.sort_by_key(|(locale_idx, _)| *locale_idx);
+    // locale -> ensure latency, exported with the metrics snapshot.
+    let mut ensure_metrics = std::collections::BTreeMap::new();
+    let mut stage_timings = serde_json::Map::new();
+    for (locale_idx, output) in locale_outputs {
+        post_summary.absorb(output.summary);
+        excluded_items += output.excluded_items;
+        enrich_skipped += output.enrich_skipped;
+        price_ladder_snapshots.extend(output.ladders);
+        if let Some(latency) = output.ensure_latency {
+            ensure_metrics.insert(regions[locale_idx].clone(), latency);
         }
-        if !ensure_durations.is_empty() {
-            let total = ensure_durations.len();
-            let sum: std::time::Duration = ensure_durations
-                .iter()
-                .copied()
-                .reduce(|a, b| a + b)
-                .unwrap();
-            let avg_ms = (sum.as_secs_f64() * 1000.0) / (total as f64);
-            let mut sorted = ensure_durations.clone();
-            sorted.sort();
-            let p95 = sorted
-                .get(((total as f64) * 0.95).floor() as usize)
-                .unwrap_or(sorted.last().unwrap());
-            println!(
-                "[psstore] ensure metrics locale={locale} count={total} avg_ms={avg_ms:.2} p95_ms={:.2}",
-                p95.as_secs_f64() * 1000.0
-            );
+        if stage_timers {
+            stage_timings.insert(regions[locale_idx].clone(), output.stages.to_json());
+        }
+    }
+    // PS_ENSURE_METRICS_NOTIFY=1: also publish them on `psstore_ensure_metrics`.
+    if !dry_run
+        && !ensure_metrics.is_empty()
+        && crate::util::env::env_flag("PS_ENSURE_METRICS_NOTIFY", false)
+    {
+        let payload = serde_json::json!({ "ensure_metrics": &ensure_metrics });
+        if let Err(e) = sqlx::query("SELECT pg_notify($1, $2)")
+            .persistent(false)
+            .bind("psstore_ensure_metrics")
+            .bind(payload.to_string())
+            .execute(&db.pool)
+            .await
+        {
+            tracing::warn!(error = %e, "psstore ensure metrics pg_notify failed");
         }
     }
+    let global_aggs = agg_map.into_aggs();
 
-    if !price_ladder_snapshots.is_empty() {
+    if !price_ladder_snapshots.is_empty() && !dry_run {
         let ladder_export = PriceLadderExport {
             generated_at: chrono::Utc::now().to_rfc3339(),
             ladders: price_ladder_snapshots,
@@ -735,41 +1135,57 @@ pub async fn psstore_seed_pipeline(db: &Db) -> Result<PostIngestSummary> {
     }
 
     // Persist aggregated metadata per product
-    for (_key, agg) in &global_aggs {
-        let genres_vec: Vec<String> = agg.genres.iter().cloned().collect();
-        let genres_json = serde_json::Value::from(genres_vec.clone());
-        let genres_array = if genres_vec.is_empty() {
-            None
-        } else {
-            Some(genres_vec)
-        };
-        let global_avg = if agg.rating_count > 0 {
-            Some(agg.rating_sum / (agg.rating_count as f64))
-        } else {
-            None
-        };
-        let global_count = if agg.rating_count > 0 {
-            Some(agg.rating_count)
-        } else {
-            None
-        };
-        let patch = serde_json::json!({
-            "genres_union": genres_json,
-            "rating_global": global_avg,
-            "rating_count_global": agg.rating_count,
-        });
-        merge_video_game_metadata(db, agg.vg_id, patch).await?;
-        if let Some(ref g) = genres_array {
-            let _ = update_video_game_genres_if_empty(db, agg.vg_id, g).await;
+    if !dry_run {
+        for agg in global_aggs.values() {
+            let genres_vec: Vec<String> = agg.genres.iter().cloned().collect();
+            let genres_json = serde_json::Value::from(genres_vec.clone());
+            let genres_array = if genres_vec.is_empty() {
+                None
+            } else {
+                Some(genres_vec)
+            };
+            let global_avg = if agg.rating_count > 0 {
+                Some(agg.rating_sum / (agg.rating_count as f64))
+            } else {
+                None
+            };
+            let global_count = if agg.rating_count > 0 {
+                Some(agg.rating_count)
+            } else {
+                None
+            };
+            let platforms: Vec<&String> = agg.platforms.iter().collect();
+            let patch = serde_json::json!({
+                "genres_union": genres_json,
+                "platforms": platforms,
+                "rating_global": global_avg,
+                "rating_count_global": agg.rating_count,
+            });
+            merge_video_game_metadata(db, agg.vg_id, "ps-store", patch).await?;
+            if let (Some(avg), Some(count)) = (global_avg, global_count) {
+                let _ = upsert_external_rating(
+                    db,
+                    agg.vg_id,
+                    RatingSource::PlayStation,
+                    avg,
+                    Some(count),
+                )
+                .await;
+            }
+            if let Some(ref g) = genres_array {
+                let _ = update_video_game_genres_if_empty(db, agg.vg_id, g).await;
+            }
+            let _ =
+                update_video_game_global_rating_if_null(db, agg.vg_id, global_avg, global_count)
+                    .await;
         }
-        let _ =
-            update_video_game_global_rating_if_null(db, agg.vg_id, global_avg, global_count).await;
     }
 
     // Persist a PS Store-derived monthly toplist based on aggregated star ratings.
     // This is the missing bridge that lets Laravel Spotlight consume PS Store ratings
     // via `provider_toplists`/`provider_toplist_items` just like RAWG/IGDB.
-    {
+    // Listings skipped as recently enriched rank on their stored ratings.
+    if !dry_run {
         use chrono::Datelike;
         use database_ops::ingest_providers::{
             replace_provider_toplist_items, upsert_provider_toplist,
@@ -883,6 +1299,10 @@ pub async fn psstore_seed_pipeline(db: &Db) -> Result<PostIngestSummary> {
         .collect();
     let snapshot = serde_json::json!({
         "generated_at": chrono::Utc::now().to_rfc3339(),
+        "dry_run": dry_run,
+        "extraction": post_summary.extraction,
+        "ensure_metrics": ensure_metrics,
+        "stage_timings": stage_timings,
         "products": metrics
     });
     let metrics_path = format!(
@@ -921,9 +1341,11 @@ pub async fn psstore_seed_pipeline(db: &Db) -> Result<PostIngestSummary> {
     // RLS adaptation note: if Supabase RLS is enabled, ensure service role key is used; this pipeline relies on unrestricted access for bulk ingestion.
     // Optionally: set SUPABASE_SERVICE_ROLE env and verify connection role.
 
-    post_summary.verify(db, provider_id).await?;
-    eprintln!("INFO: psstore seed pipeline summary - provider_id={}, price_rows={}, provider_items={}, offer_jurisdictions={}",
-             provider_id, post_summary.total_price_rows_written, post_summary.video_game_source_ids.len(), post_summary.offer_jurisdiction_ids.len());
+    if !dry_run {
+        post_summary.verify(db, provider_id).await?;
+    }
+    eprintln!("INFO: psstore seed pipeline summary - provider_id={}, price_rows={}, provider_items={}, offer_jurisdictions={}, excluded_items={}, enrich_skipped={}",
+             provider_id, post_summary.total_price_rows_written, post_summary.video_game_source_ids.len(), post_summary.offer_jurisdiction_ids.len(), excluded_items, enrich_skipped);
 
     Ok(post_summary)
 }
@@ -935,11 +1357,88 @@ fn normalize_title(s: &str) -> String {
         .to_string()
 }
 
-fn load_regions() -> Vec<String> {
-    // Normalize to IETF-style locale tags: language (lowercase) + '-' + region (uppercase)
-    // Examples: "en-US", "en-GB", "de-DE", "ja-JP". Underscores are accepted and converted to '-'.
-    let raw = std::env::var("PS_STORE_REGIONS").unwrap_or_else(|_| "en-us en-gb de-de".into());
-    raw.split(|c: char| (c == ',' || c == ' '))
+/// Release year from a grid item's `release_date` (first four digits).
+fn grid_release_year(it: &psstore_client::PsProductSummary) -> Option<i32> {
+    it.release_date
+        .as_ref()
+        .and_then(|d| d.get(0..4))
+        .and_then(|y| y.parse::<i32>().ok())
+}
+
+/// Drop the grid items the per-item year check would skip: every item newer than
+/// `year_max`, and everything from the first item older than `year_min` on (the walk stops
+/// there). Items without a release date are kept. Returns how many were dropped.
+fn retain_in_year_window(
+    list: &mut Vec<psstore_client::PsProductSummary>,
+    year_min: i32,
+    year_max: i32,
+) -> usize {
+    let before = list.len();
+    if let Some(stop) = list
+        .iter()
+        .position(|it| grid_release_year(it).is_some_and(|year| year < year_min))
+    {
+        list.truncate(stop);
+    }
+    list.retain(|it| grid_release_year(it).is_none_or(|year| year <= year_max));
+    before - list.len()
+}
+
+/// `fetch(0..len)` with at most `limit` of its futures alive at once (each is only created
+/// when a slot frees up, so a large page cannot queue all of them), results in index order.
+async fn fetch_in_order<T, F, Fut>(len: usize, limit: usize, mut fetch: F) -> Vec<T>
+where
+    F: FnMut(usize) -> Fut,
+    Fut: std::future::Future<Output = T>,
+{
+    use futures::stream::StreamExt;
+    let mut out: Vec<(usize, T)> = futures::stream::iter(0..len)
+        .map(|idx| {
+            let fut = fetch(idx);
+            async move { (idx, fut.await) }
+        })
+        .buffer_unordered(limit.max(1))
+        .collect()
+        .await;
+    out.sort_by_key(|(idx, _)| *idx);
+    out.into_iter().map(|(_, v)| v).collect()
+}
+
+fn region_polls() -> std::sync::MutexGuard<'static, crate::util::cadence::RegionPollState> {
+    static POLLS: std::sync::OnceLock<std::sync::Mutex<crate::util::cadence::RegionPollState>> =
+        std::sync::OnceLock::new();
+    POLLS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Regions due this tick under `PS_REGION_CADENCE` (e.g. `US=1,GB=1,JP=1,*=4`); each
+/// pipeline run counts as one tick. A region only counts as polled once
+/// [`mark_region_polled`] records its locale finishing.
+fn due_regions(regions: &[String]) -> Vec<String> {
+    let cadence = crate::util::cadence::RegionCadence::from_env("PS_REGION_CADENCE");
+    let due = region_polls().due(&cadence, regions);
+    if due.len() < regions.len() {
+        let skipped: Vec<&String> = regions.iter().filter(|r| !due.contains(r)).collect();
+        tracing::info!(
+            ?due,
+            ?skipped,
+            "psstore: region cadence skipped regions this tick"
+        );
+    }
+    due
+}
+
+fn mark_region_polled(region: &str) {
+    region_polls().mark_polled(region);
+}
+
+fn load_regions() -> Vec<String> {
+    // Normalize to IETF-style locale tags: language (lowercase) + '-' + region (uppercase)
+    // Examples: "en-US", "en-GB", "de-DE", "ja-JP". Underscores are accepted and converted to '-'.
+    let raw = std::env::var("PS_STORE_REGIONS").unwrap_or_else(|_| "en-us en-gb de-de".into());
+    raw.split(|c: char| (c == ',' || c == ' '))
         .filter(|s| !s.is_empty())
         .map(|s| s.trim())
         .map(|tok| {
@@ -958,14 +1457,6 @@ fn load_regions() -> Vec<String> {
         .collect()
 }
 
-fn currency_minor_unit(code: &str) -> i16 {
-    match code.to_ascii_uppercase().as_str() {
-        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "HUF" => 0,
-        "BHD" | "IQD" | "KWD" | "JOD" | "OMR" | "TND" => 3,
-        _ => 2,
-    }
-}
-
 pub(crate) fn currency_for_country(code2: &str) -> (&'static str, &'static str) {
     match code2 {
         "US" => ("USD", "US Dollar"),
@@ -1103,33 +1594,464 @@ fn extract_concept_id_from_response(v: &Value) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Whether `video_game_ratings_by_locale` (as resolved by search_path) has a unique,
+/// non-partial index on exactly (video_game_id, locale), i.e. a valid ON CONFLICT target.
+async fn locale_ratings_have_unique_key(db: &Db) -> Result<bool> {
+    database_ops::schema_caps::unique_key_exists(
+        db,
+        "video_game_ratings_by_locale",
+        &["video_game_id", "locale"],
+    )
+    .await
+}
+
+/// Everything `psstore_seed_pipeline` writes to under either schema profile, verified
+/// before the first write when `PS_REQUIRE_FULL_SCHEMA=1` (see
+/// [`psstore_schema_requirements`]). `software` is not listed: it was dropped in 0535 and
+/// `ensure_software_row` skips it when absent.
+pub const PSSTORE_FULL_SCHEMA: &[SchemaRequirement] = &[
+    SchemaRequirement::Table("platforms"),
+    SchemaRequirement::Table("providers"),
+    SchemaRequirement::Table("provider_items"),
+    SchemaRequirement::Table("products"),
+    SchemaRequirement::Table("video_game_sources"),
+    SchemaRequirement::Table("video_game_titles"),
+    SchemaRequirement::Table("video_games"),
+    SchemaRequirement::Table("currencies"),
+    // ensure_retailer / ensure_national_jurisdiction use whichever the profile prefers.
+    SchemaRequirement::AnyTable(&["retailers", "game_retailers"]),
+    SchemaRequirement::AnyTable(&["jurisdictions", "countries"]),
+    SchemaRequirement::Table("sku_regions"),
+    SchemaRequirement::Table("region_prices"),
+    SchemaRequirement::Table("vg_source_media_links"),
+    SchemaRequirement::UniqueKey {
+        table: "products",
+        columns: &["slug"],
+    },
+    SchemaRequirement::UniqueKey {
+        table: "currencies",
+        columns: &["code"],
+    },
+    SchemaRequirement::UniqueKey {
+        table: "game_media",
+        columns: &["video_game_id", "source", "external_id"],
+    },
+    SchemaRequirement::UniqueKey {
+        table: "video_game_ratings_by_locale",
+        columns: &["video_game_id", "locale"],
+    },
+];
+
+/// Written only under the native profile; php-compat synthesizes sellable and offer ids
+/// and keeps prices in sku_regions.
+pub const PSSTORE_NATIVE_SCHEMA: &[SchemaRequirement] = &[
+    SchemaRequirement::Table("retailers"),
+    SchemaRequirement::Table("jurisdictions"),
+    SchemaRequirement::Table("sellables"),
+    SchemaRequirement::Table("offers"),
+    SchemaRequirement::Table("offer_jurisdictions"),
+    SchemaRequirement::UniqueKey {
+        table: "retailers",
+        columns: &["slug"],
+    },
+    SchemaRequirement::UniqueKey {
+        table: "offer_jurisdictions",
+        columns: &["offer_id", "jurisdiction_id"],
+    },
+];
+
+/// The requirements for a run under `profile`.
+pub fn psstore_schema_requirements(profile: SchemaProfile) -> Vec<SchemaRequirement> {
+    let mut requirements = PSSTORE_FULL_SCHEMA.to_vec();
+    if !profile.is_php_compat() {
+        requirements.extend_from_slice(PSSTORE_NATIVE_SCHEMA);
+    }
+    requirements
+}
+
+/// Fail with every gap in `requirements` instead of letting a partial schema be written
+/// halfway.
+async fn require_full_schema(
+    db: &Db,
+    caps: &SchemaCaps,
+    requirements: &[SchemaRequirement],
+) -> Result<()> {
+    let gaps = caps.check(db, requirements).await?;
+    if gaps.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "psstore_seed_pipeline: PS_REQUIRE_FULL_SCHEMA=1 and the schema is incomplete ({gaps}); nothing was written"
+    ))
+}
+
+/// One row per `(video_game_id, locale)`, keeping the most-reviewed one. A page lists a
+/// game once per category and edition, and a multi-row `ON CONFLICT DO UPDATE` cannot
+/// touch the same row twice.
+fn dedupe_rating_rows(rows: &[(i64, String, f32, i64)]) -> Vec<(i64, String, f32, i64)> {
+    let mut best: std::collections::HashMap<(i64, &str), usize> = std::collections::HashMap::new();
+    for (i, (vg_id, locale, _, count)) in rows.iter().enumerate() {
+        best.entry((*vg_id, locale.as_str()))
+            .and_modify(|kept| {
+                if *count > rows[*kept].3 {
+                    *kept = i;
+                }
+            })
+            .or_insert(i);
+    }
+    let mut kept: Vec<usize> = best.into_values().collect();
+    kept.sort_unstable();
+    kept.into_iter().map(|i| rows[i].clone()).collect()
+}
+
+/// Upsert one page of (video_game_id, locale, average, count) rating rows. With a unique
+/// key this is a single ON CONFLICT insert; without one each row is updated in place or
+/// inserted when absent, inside one transaction.
+async fn upsert_locale_ratings(
+    db: &Db,
+    rows: &[(i64, String, f32, i64)],
+    unique_key: bool,
+) -> Result<()> {
+    let rows = dedupe_rating_rows(rows);
+    let rows = rows.as_slice();
+    if unique_key {
+        let mut qb = sqlx::QueryBuilder::new(
+            "INSERT INTO video_game_ratings_by_locale (video_game_id, locale, average_rating, rating_count, rating_updated_at) VALUES ",
+        );
+        let mut sep = qb.separated(", ");
+        for (vg_id, loc, avg, cnt) in rows {
+            sep.push("(")
+                .push_bind(vg_id)
+                .push(", ")
+                .push_bind(loc)
+                .push(", ")
+                .push_bind(avg)
+                .push(", ")
+                .push_bind(cnt)
+                .push(", now())");
+        }
+        qb.push(
+            " ON CONFLICT (video_game_id, locale) DO UPDATE SET average_rating=EXCLUDED.average_rating, rating_count=EXCLUDED.rating_count, rating_updated_at=now()"
+        );
+        qb.build().execute(&db.pool).await?;
+        return Ok(());
+    }
+
+    let mut tx = db.pool.begin().await?;
+    for (vg_id, loc, avg, cnt) in rows {
+        let updated = sqlx::query(
+            "UPDATE video_game_ratings_by_locale SET average_rating=$3, rating_count=$4, rating_updated_at=now() WHERE video_game_id=$1 AND locale=$2",
+        )
+        .persistent(false)
+        .bind(vg_id)
+        .bind(loc)
+        .bind(avg)
+        .bind(cnt)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if updated == 0 {
+            sqlx::query(
+                "INSERT INTO video_game_ratings_by_locale (video_game_id, locale, average_rating, rating_count, rating_updated_at) VALUES ($1, $2, $3, $4, now())",
+            )
+            .persistent(false)
+            .bind(vg_id)
+            .bind(loc)
+            .bind(avg)
+            .bind(cnt)
+            .execute(&mut *tx)
+            .await?;
+        }
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Ratings and genres of one game merged across every locale it was seen in.
+#[derive(Debug, PartialEq)]
+struct GlobalAgg {
+    genres: std::collections::HashSet<String>,
+    /// Normalized platform labels ("PS4", "PS5") the product is listed or playable on.
+    platforms: std::collections::BTreeSet<String>,
+    /// Locales whose rating is already folded in; a cross-buy title listed under both
+    /// the PS4 and PS5 categories carries the same rating twice.
+    rated_locales: std::collections::HashSet<String>,
+    rating_sum: f64,
+    rating_count: i64,
+    vg_id: i64,
+}
+
+impl GlobalAgg {
+    /// Fold one locale's rating (average, count), genres and platforms into the bucket
+    /// for `key`; the first locale seen supplies the video_game the aggregate is written
+    /// to. A locale's rating is only counted once per product.
+    fn add(
+        aggs: &mut std::collections::HashMap<String, GlobalAgg>,
+        key: &str,
+        locale: &str,
+        vg_id: i64,
+        rating: Option<(f32, i64)>,
+        genres: &[String],
+        platforms: &[String],
+    ) {
+        let entry = aggs.entry(key.to_string()).or_insert_with(|| GlobalAgg {
+            genres: std::collections::HashSet::new(),
+            platforms: std::collections::BTreeSet::new(),
+            rated_locales: std::collections::HashSet::new(),
+            rating_sum: 0.0,
+            rating_count: 0,
+            vg_id,
+        });
+        if let Some((avg, cnt)) = rating {
+            if entry.rated_locales.insert(locale.to_string()) {
+                entry.rating_sum += (avg as f64) * (cnt as f64);
+                entry.rating_count += cnt;
+            }
+        }
+        entry.genres.extend(genres.iter().cloned());
+        entry.platforms.extend(platforms.iter().cloned());
+    }
+
+    /// Fold another locale's aggregates into `aggs`; a bucket already present keeps its
+    /// video_game, and a locale's rating is still only counted once.
+    fn merge_into(
+        aggs: &mut std::collections::HashMap<String, GlobalAgg>,
+        other: std::collections::HashMap<String, GlobalAgg>,
+    ) {
+        use std::collections::hash_map::Entry;
+        for (key, agg) in other {
+            match aggs.entry(key) {
+                Entry::Vacant(slot) => {
+                    slot.insert(agg);
+                }
+                Entry::Occupied(mut slot) => {
+                    let entry = slot.get_mut();
+                    if entry.rated_locales.is_disjoint(&agg.rated_locales) {
+                        entry.rating_sum += agg.rating_sum;
+                        entry.rating_count += agg.rating_count;
+                    }
+                    entry.rated_locales.extend(agg.rated_locales);
+                    entry.genres.extend(agg.genres);
+                    entry.platforms.extend(agg.platforms);
+                }
+            }
+        }
+    }
+}
+
+/// One [`GlobalAggMap`] shard: buckets, and the locale index each bucket's video_game
+/// came from.
+type GlobalAggShard = (
+    std::collections::HashMap<String, GlobalAgg>,
+    std::collections::HashMap<String, usize>,
+);
+
+/// [`GlobalAgg`]s that concurrently seeded locales merge into. Keys are spread over
+/// `shards` independently locked maps, so locales only contend when they touch the same
+/// shard. The video_game of a bucket comes from the lowest locale index that listed it,
+/// as in the sequential region-order merge, whatever order the locales run in.
+struct GlobalAggMap {
+    shards: Vec<std::sync::Mutex<GlobalAggShard>>,
+    hasher: std::collections::hash_map::RandomState,
+}
+
+impl GlobalAggMap {
+    fn new(shards: usize) -> Self {
+        Self {
+            shards: (0..shards.max(1)).map(|_| Default::default()).collect(),
+            hasher: Default::default(),
+        }
+    }
+
+    /// Fold one listing's rating into `key`; see [`GlobalAgg::add`].
+    fn merge_rating(
+        &self,
+        key: &str,
+        locale_idx: usize,
+        locale: &str,
+        vg_id: i64,
+        rating: Option<(f32, i64)>,
+    ) {
+        self.merge(key, locale_idx, locale, vg_id, rating, &[], &[]);
+    }
+
+    /// Fold one listing's genres and platforms into `key`.
+    fn merge_genres(
+        &self,
+        key: &str,
+        locale_idx: usize,
+        locale: &str,
+        vg_id: i64,
+        genres: &[String],
+        platforms: &[String],
+    ) {
+        self.merge(key, locale_idx, locale, vg_id, None, genres, platforms);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn merge(
+        &self,
+        key: &str,
+        locale_idx: usize,
+        locale: &str,
+        vg_id: i64,
+        rating: Option<(f32, i64)>,
+        genres: &[String],
+        platforms: &[String],
+    ) {
+        use std::hash::BuildHasher;
+        let shard = (self.hasher.hash_one(key) % self.shards.len() as u64) as usize;
+        let mut shard = self.shards[shard].lock().unwrap_or_else(|e| e.into_inner());
+        let (aggs, origins) = &mut *shard;
+        GlobalAgg::add(aggs, key, locale, vg_id, rating, genres, platforms);
+        let origin = origins.entry(key.to_string()).or_insert(locale_idx);
+        if locale_idx < *origin {
+            *origin = locale_idx;
+            if let Some(agg) = aggs.get_mut(key) {
+                agg.vg_id = vg_id;
+            }
+        }
+    }
+
+    /// Consolidate the shards into one map once every locale is done.
+    fn into_aggs(self) -> std::collections::HashMap<String, GlobalAgg> {
+        let mut aggs = std::collections::HashMap::new();
+        for shard in self.shards {
+            let (shard_aggs, _) = shard.into_inner().unwrap_or_else(|e| e.into_inner());
+            GlobalAgg::merge_into(&mut aggs, shard_aggs);
+        }
+        aggs
+    }
+}
+
+/// How PS Store items are keyed for cross-locale dedupe and rating/genre aggregation
+/// (`PS_PRODUCT_KEY_STRATEGY`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProductKeyStrategy {
+    /// conceptId (shared by every locale's SKU of a game), else productId, else the
+    /// title slug.
+    Concept,
+    /// productId (region-specific), else the title slug. Legacy behavior.
+    ProductId,
+}
+
+impl ProductKeyStrategy {
+    fn from_env() -> Self {
+        match std::env::var("PS_PRODUCT_KEY_STRATEGY")
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "product" | "product_id" | "productid" => Self::ProductId,
+            _ => Self::Concept,
+        }
+    }
+
+    /// Key kinds are prefixed so an id can never collide with a slug.
+    fn key(self, concept_id: Option<&str>, product_id: Option<&str>, slug: &str) -> String {
+        fn present(v: Option<&str>) -> Option<&str> {
+            v.map(str::trim).filter(|v| !v.is_empty())
+        }
+        let concept = match self {
+            Self::Concept => present(concept_id),
+            Self::ProductId => None,
+        };
+        if let Some(c) = concept {
+            format!("concept:{c}")
+        } else if let Some(p) = present(product_id) {
+            format!("product:{p}")
+        } else {
+            format!("slug:{slug}")
+        }
+    }
+}
+
 // --- Media & Genre Helpers ---
-fn classify_video_role(role: &str) -> &'static str {
-    match role.to_ascii_uppercase().as_str() {
-        "TRAILER" => "trailer",
-        "PREVIEW" => "preview",
-        "GAMEPLAY" => "gameplay",
-        "CINEMATIC" | "CUTSCENE" => "cinematic",
-        "TEASER" => "teaser",
-        _ => "gameplay",
+/// Videos shorter than this with no role or URL signal are treated as teasers.
+const TEASER_MAX_SECS: f64 = 30.0;
+
+fn classify_video_role(role: &str, url: &str, duration_secs: Option<f64>) -> &'static str {
+    match role.trim().to_ascii_uppercase().as_str() {
+        "TRAILER" => return "trailer",
+        "PREVIEW" => return "preview",
+        "GAMEPLAY" => return "gameplay",
+        "CINEMATIC" | "CUTSCENE" => return "cinematic",
+        "TEASER" => return "teaser",
+        _ => {}
+    }
+    // No usable role: PlayStation often omits it, but the asset path or length hints.
+    let path = url.to_ascii_lowercase();
+    if path.contains("trailer") {
+        "trailer"
+    } else if path.contains("teaser") {
+        "teaser"
+    } else if path.contains("gameplay") {
+        "gameplay"
+    } else if duration_secs.is_some_and(|secs| secs < TEASER_MAX_SECS) {
+        "teaser"
+    } else {
+        "gameplay"
     }
 }
 
 fn classify_image_role(role: &str) -> Option<&'static str> {
-    match role.to_ascii_uppercase().as_str() {
-        "HERO" => Some("hero"),
-        "COVER" | "GAMEHUB_COVER_ART" => Some("cover"),
-        "LOGO" => Some("logo"),
-        "SCREENSHOT" => Some("screenshot"),
+    // Variants carry numeric suffixes ("GAMEHUB_COVER_ART_2"); classify on the stem.
+    let upper = role.trim().to_ascii_uppercase();
+    let stem = upper.trim_end_matches(|c: char| c.is_ascii_digit() || c == '_');
+    match stem {
+        "HERO" | "HERO_CHARACTER" => return Some("hero"),
+        "COVER" | "GAMEHUB_COVER_ART" | "PACKSHOT" | "BOXART" | "BOX_ART" => return Some("cover"),
+        "LOGO" | "LOGO_IMAGE" | "STORE_DISPLAY_CLASSIFICATION" => return Some("logo"),
+        "SCREENSHOT" | "GAMEPLAY_SCREENSHOT" => return Some("screenshot"),
         // High-level art variants normalize to artwork for enum compatibility
         "BACKGROUND"
         | "PORTRAIT_BANNER"
         | "FOUR_BY_THREE_BANNER"
         | "SIXTEEN_BY_NINE_BANNER"
         | "EDITION_KEY_ART"
-        | "MASTER" => Some("artwork"),
-        _ => None,
+        | "MASTER" => return Some("artwork"),
+        _ => {}
     }
+    // Newer role strings compose the same vocabulary ("GAMEHUB_HERO_BANNER",
+    // "EDITION_PACKSHOT_ART"); fall back to the tokens they contain.
+    let tokens: Vec<&str> = stem.split('_').collect();
+    let has = |t: &str| tokens.contains(&t);
+    if has("COVER") || has("PACKSHOT") || has("BOXART") {
+        Some("cover")
+    } else if has("HERO") {
+        Some("hero")
+    } else if has("LOGO") || has("ICON") || has("CLASSIFICATION") {
+        Some("logo")
+    } else if has("SCREENSHOT") {
+        Some("screenshot")
+    } else if has("BANNER") || has("BACKGROUND") || has("ART") || has("WALLPAPER") {
+        Some("artwork")
+    } else {
+        None
+    }
+}
+
+/// Image role strings [`classify_image_role`] could not map, with sighting counts.
+fn unknown_image_roles() -> &'static std::sync::Mutex<std::collections::HashMap<String, u64>> {
+    static ROLES: std::sync::OnceLock<std::sync::Mutex<std::collections::HashMap<String, u64>>> =
+        std::sync::OnceLock::new();
+    ROLES.get_or_init(Default::default)
+}
+
+/// Count an unmapped image role; the first sighting of each role is logged so it can be
+/// added to the mapping. Returns how often the role has been seen.
+fn record_unknown_image_role(role: &str) -> u64 {
+    let key = role.trim().to_ascii_uppercase();
+    let mut roles = unknown_image_roles()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let count = roles.entry(key.clone()).or_insert(0);
+    *count += 1;
+    if *count == 1 {
+        tracing::warn!(role = %key, distinct_unknown = roles.len(), "psstore: unknown image role, stored as screenshot");
+    }
+    roles.get(&key).copied().unwrap_or(0)
 }
 
 fn detail_product_node<'a>(detail: &'a serde_json::Value) -> Option<&'a serde_json::Value> {
@@ -1203,6 +2125,129 @@ fn collect_genre_strings(v: &serde_json::Value, out: &mut Vec<String>) {
     }
 }
 
+/// Edition tier (`standard`, `deluxe`, `ultimate`, `gold`) of a PS product detail payload,
+/// from its `edition` node (`type` such as `STANDARD_EDITION`, then `name`) or else the
+/// product name. `None` when the payload names no known tier.
+fn extract_edition(detail: &serde_json::Value) -> Option<&'static str> {
+    let prod = detail_product_node(detail)?;
+    let edition = prod.get("edition");
+    fn field<'a>(node: Option<&'a serde_json::Value>, key: &str) -> Option<&'a str> {
+        node.and_then(|n| n.get(key)).and_then(|v| v.as_str())
+    }
+    if let Some(kind) = field(edition, "type") {
+        let tier = kind
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .find_map(|token| edition_tier(&token.to_ascii_lowercase()));
+        if tier.is_some() {
+            return tier;
+        }
+    }
+    [field(edition, "name"), field(Some(prod), "name")]
+        .into_iter()
+        .flatten()
+        .find_map(|text| edition_in_text(text).map(|(tier, _)| tier))
+}
+
+fn edition_tier(word: &str) -> Option<&'static str> {
+    match word {
+        "standard" => Some("standard"),
+        "deluxe" => Some("deluxe"),
+        "ultimate" => Some("ultimate"),
+        "gold" => Some("gold"),
+        _ => None,
+    }
+}
+
+/// Tier named by an edition phrase in `text` ("Gold Edition", "Digital Deluxe", "Deluxe
+/// Edition") and the byte offset where the phrase starts. A tier word on its own, as in
+/// "Ultimate Marvel vs. Capcom 3", is not an edition.
+fn edition_in_text(text: &str) -> Option<(&'static str, usize)> {
+    let lower = text.to_ascii_lowercase();
+    // (byte offset, word); offsets index `text` too since ASCII lowercasing keeps lengths.
+    let mut words: Vec<(usize, &str)> = Vec::new();
+    let mut word_start: Option<usize> = None;
+    for (i, c) in lower.char_indices() {
+        if c.is_alphanumeric() {
+            word_start.get_or_insert(i);
+        } else if let Some(start) = word_start.take() {
+            words.push((start, &lower[start..i]));
+        }
+    }
+    if let Some(start) = word_start {
+        words.push((start, &lower[start..]));
+    }
+    words.iter().enumerate().find_map(|(i, (start, word))| {
+        let tier = edition_tier(word)?;
+        let next = words.get(i + 1).map(|(_, w)| *w);
+        let prev = i.checked_sub(1).map(|p| words[p]);
+        match (prev, next) {
+            (Some((prev_start, "digital")), _) => Some((tier, prev_start)),
+            (_, Some("edition")) => Some((tier, *start)),
+            _ => None,
+        }
+    })
+}
+
+/// `title` without a trailing edition phrase and its separator, so "God of War Ragnarök:
+/// Digital Deluxe Edition" and "God of War Ragnarök" share one product.
+fn base_title(title: &str) -> &str {
+    let Some((_, start)) = edition_in_text(title) else {
+        return title;
+    };
+    let base = title[..start].trim_end_matches(|c: char| {
+        c.is_whitespace() || matches!(c, ':' | '-' | '–' | '—' | '|' | '(')
+    });
+    if base.is_empty() {
+        title
+    } else {
+        base
+    }
+}
+
+/// Normalized platforms ("PS4", "PS5", "PSVR2", ...) from the detail payload's
+/// `platforms` / `playablePlatform` arrays, sorted and deduplicated. Entries may be plain
+/// strings ("PS5", "PlayStation®4") or objects with a `name`/`type`.
+fn extract_platforms(detail: &serde_json::Value) -> Vec<String> {
+    let mut platforms: std::collections::BTreeSet<&'static str> = std::collections::BTreeSet::new();
+    let Some(prod) = detail_product_node(detail) else {
+        return Vec::new();
+    };
+    let nodes = [Some(prod), prod.get("concept")];
+    for node in nodes.into_iter().flatten() {
+        for key in ["platforms", "playablePlatform", "playablePlatforms"] {
+            let Some(arr) = node.get(key).and_then(|v| v.as_array()) else {
+                continue;
+            };
+            for entry in arr {
+                let raw = entry
+                    .as_str()
+                    .or_else(|| entry.get("name").and_then(|v| v.as_str()))
+                    .or_else(|| entry.get("type").and_then(|v| v.as_str()));
+                if let Some(platform) = raw.and_then(normalize_ps_platform) {
+                    platforms.insert(platform);
+                }
+            }
+        }
+    }
+    platforms.into_iter().map(str::to_string).collect()
+}
+
+fn normalize_ps_platform(raw: &str) -> Option<&'static str> {
+    let key: String = raw
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_uppercase();
+    let key = key.strip_prefix("PLAYSTATION").unwrap_or(&key);
+    match key.strip_prefix("PS").unwrap_or(key) {
+        "4" => Some("PS4"),
+        "5" => Some("PS5"),
+        "VR" => Some("PSVR"),
+        "VR2" => Some("PSVR2"),
+        _ => None,
+    }
+}
+
 fn extract_detail_media(detail: &serde_json::Value) -> Option<(Vec<PsMedia>, Vec<PsMedia>)> {
     let Some(prod) = detail_product_node(detail) else {
         return None;
@@ -1249,6 +2294,11 @@ fn append_media_entries(
             media_type: media_type.clone(),
             role: role.clone(),
             url: Some(url.to_string()),
+            duration_secs: psstore_client::media_duration_secs(entry).or_else(|| {
+                entry
+                    .get("media")
+                    .and_then(psstore_client::media_duration_secs)
+            }),
         };
         let is_image = typename
             .as_deref()
@@ -1424,3 +2474,980 @@ fn extract_synopsis(detail: &serde_json::Value) -> Option<String> {
     }
     best
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{RollbackDb, TestEnv};
+
+    #[test]
+    fn playstation_image_roles_with_suffixes_and_variants() {
+        assert_eq!(classify_image_role("GAMEHUB_COVER_ART_2"), Some("cover"));
+        assert_eq!(classify_image_role("gamehub_cover_art"), Some("cover"));
+        assert_eq!(classify_image_role("PACKSHOT"), Some("cover"));
+        assert_eq!(
+            classify_image_role("STORE_DISPLAY_CLASSIFICATION"),
+            Some("logo")
+        );
+        assert_eq!(classify_image_role("EDITION_KEY_ART_3"), Some("artwork"));
+        assert_eq!(classify_image_role("GAMEHUB_HERO_BANNER"), Some("hero"));
+        assert_eq!(classify_image_role("SCREENSHOT_10"), Some("screenshot"));
+        assert_eq!(classify_image_role("BACKGROUND_LAYER_ART"), Some("artwork"));
+    }
+
+    #[test]
+    fn concept_key_merges_locales_with_and_without_product_id() {
+        let strategy = ProductKeyStrategy::Concept;
+        let us = strategy.key(
+            Some("10002694"),
+            Some("UP1004-CUSA03041_00-REDEMPTIONFULL02"),
+            "red dead redemption 2",
+        );
+        let gb = strategy.key(Some("10002694"), None, "red dead redemption 2");
+        let jp = strategy.key(
+            Some("10002694"),
+            Some("JP0102-CUSA08519_00-REDEMPTIONFULL02"),
+            "red dead redemption 2",
+        );
+        let mut buckets = std::collections::HashSet::new();
+        buckets.extend([us.clone(), gb, jp]);
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(us, "concept:10002694");
+
+        // Without a conceptId fall back to productId, then the slug.
+        assert_eq!(
+            strategy.key(None, Some("UP1004-CUSA03041_00-X"), "rdr2"),
+            "product:UP1004-CUSA03041_00-X"
+        );
+        assert_eq!(strategy.key(Some(" "), None, "rdr2"), "slug:rdr2");
+
+        // The legacy productId strategy splits the same game across locales.
+        let legacy = ProductKeyStrategy::ProductId;
+        assert_ne!(
+            legacy.key(None, Some("UP1004-CUSA03041_00-X"), "rdr2"),
+            legacy.key(None, None, "rdr2")
+        );
+    }
+
+    #[test]
+    fn ratings_of_one_concept_aggregate_across_regions() {
+        let strategy = ProductKeyStrategy::Concept;
+        let mut aggs = std::collections::HashMap::new();
+        let action = ["Action".to_string()];
+        let us = strategy.key(Some("10002694"), Some("UP1004-CUSA03041_00-RDR2"), "rdr2");
+        let gb = strategy.key(Some("10002694"), Some("EP1004-CUSA03099_00-RDR2"), "rdr2");
+        let ps4 = ["PS4".to_string()];
+        let ps5 = ["PS5".to_string()];
+        GlobalAgg::add(&mut aggs, &us, "en-us", 11, Some((4.0, 300)), &action, &ps5);
+        GlobalAgg::add(
+            &mut aggs,
+            &gb,
+            "en-gb",
+            12,
+            Some((5.0, 100)),
+            &["Adventure".to_string()],
+            &ps5,
+        );
+        GlobalAgg::add(&mut aggs, &gb, "en-gb", 12, None, &action, &ps5);
+        // The same listing again under the PS4 category: platforms merge, the rating
+        // is not counted twice.
+        GlobalAgg::add(&mut aggs, &us, "en-us", 11, Some((4.0, 300)), &action, &ps4);
+
+        assert_eq!(aggs.len(), 1);
+        let agg = &aggs[&us];
+        assert_eq!(agg.vg_id, 11);
+        assert_eq!(agg.rating_count, 400);
+        assert!((agg.rating_sum / agg.rating_count as f64 - 4.25).abs() < 1e-9);
+        assert_eq!(agg.genres.len(), 2);
+        assert_eq!(agg.platforms.iter().collect::<Vec<_>>(), vec!["PS4", "PS5"]);
+    }
+
+    #[test]
+    fn cross_gen_detail_lists_both_platforms() {
+        let detail = json!({
+            "data": {
+                "productRetrieve": {
+                    "name": "Horizon Forbidden West",
+                    "platforms": ["PS5", "PS4"],
+                    "concept": { "playablePlatform": ["PlayStation®4", "PS5™"] }
+                }
+            }
+        });
+        assert_eq!(extract_platforms(&detail), vec!["PS4", "PS5"]);
+
+        let vr = json!({
+            "data": {
+                "metGetProductById": {
+                    "playablePlatform": [{ "name": "PS VR2" }, { "type": "PS5" }, "PC"]
+                }
+            }
+        });
+        assert_eq!(extract_platforms(&vr), vec!["PS5", "PSVR2"]);
+        assert!(extract_platforms(&json!({ "data": {} })).is_empty());
+    }
+
+    #[test]
+    fn video_role_heuristics_without_role() {
+        let cdn = "https://gs2-sec.ww.prod.dl.playstation.net/gs2-sec/appkgo/prod/CUSA1/1";
+        assert_eq!(
+            classify_video_role("TRAILER", &format!("{cdn}/clip.mp4"), Some(10.0)),
+            "trailer"
+        );
+        assert_eq!(
+            classify_video_role("", &format!("{cdn}/launch_trailer.mp4"), Some(12.0)),
+            "trailer"
+        );
+        assert_eq!(
+            classify_video_role("", &format!("{cdn}/Teaser_EN.mp4"), None),
+            "teaser"
+        );
+        assert_eq!(
+            classify_video_role("UNKNOWN", &format!("{cdn}/clip.mp4"), Some(15.0)),
+            "teaser"
+        );
+        assert_eq!(
+            classify_video_role("", &format!("{cdn}/clip.mp4"), Some(95.0)),
+            "gameplay"
+        );
+        assert_eq!(
+            classify_video_role("", &format!("{cdn}/clip.mp4"), None),
+            "gameplay"
+        );
+    }
+
+    #[test]
+    fn unmapped_image_roles_are_counted_distinctly() {
+        assert_eq!(classify_image_role("PROMO_TILE_SQUARE"), None);
+        assert_eq!(classify_image_role(""), None);
+        assert_eq!(record_unknown_image_role("promo_tile_square"), 1);
+        assert_eq!(record_unknown_image_role("PROMO_TILE_SQUARE"), 2);
+        assert_eq!(record_unknown_image_role("UNSEEN_ROLE_KIND"), 1);
+    }
+
+    #[test]
+    fn rating_rows_keep_one_row_per_game_and_locale() {
+        let rows = vec![
+            (1, "en-us".to_string(), 4.5, 120),
+            (2, "en-us".to_string(), 4.0, 10),
+            (1, "en-us".to_string(), 4.7, 3_000),
+            (1, "en-gb".to_string(), 4.6, 40),
+            (2, "en-us".to_string(), 3.9, 8),
+        ];
+        assert_eq!(
+            dedupe_rating_rows(&rows),
+            vec![
+                (2, "en-us".to_string(), 4.0, 10),
+                (1, "en-us".to_string(), 4.7, 3_000),
+                (1, "en-gb".to_string(), 4.6, 40),
+            ]
+        );
+    }
+
+    #[test]
+    fn editions_from_ps_detail_payloads() {
+        let detail = |product: Value| json!({ "data": { "metGetProductById": product } });
+        let named = |name: &str| detail(json!({ "name": name }));
+
+        assert_eq!(
+            extract_edition(&detail(json!({
+                "name": "God of War Ragnarök",
+                "edition": { "name": "Standard Edition", "type": "STANDARD_EDITION" },
+            }))),
+            Some("standard")
+        );
+        assert_eq!(
+            extract_edition(&detail(json!({
+                "name": "Marvel's Spider-Man 2",
+                "edition": { "name": "Digital Deluxe Edition", "type": "PREMIUM_EDITION" },
+            }))),
+            Some("deluxe")
+        );
+        assert_eq!(
+            extract_edition(&named("God of War Ragnarök: Digital Deluxe Edition")),
+            Some("deluxe")
+        );
+        assert_eq!(
+            extract_edition(&named("EA SPORTS FC™ 24 Ultimate Edition")),
+            Some("ultimate")
+        );
+        assert_eq!(
+            extract_edition(&named("Far Cry® 6 Gold Edition")),
+            Some("gold")
+        );
+        assert_eq!(
+            extract_edition(&named("Hogwarts Legacy Digital Deluxe")),
+            Some("deluxe")
+        );
+        assert_eq!(
+            extract_edition(&named("Ultimate Marvel vs. Capcom 3")),
+            None
+        );
+        assert_eq!(extract_edition(&named("Golden Axe")), None);
+        assert_eq!(extract_edition(&Value::Null), None);
+
+        assert_eq!(
+            base_title("God of War Ragnarök: Digital Deluxe Edition"),
+            "God of War Ragnarök"
+        );
+        assert_eq!(base_title("Far Cry® 6 – Gold Edition"), "Far Cry® 6");
+        assert_eq!(
+            base_title("Ultimate Marvel vs. Capcom 3"),
+            "Ultimate Marvel vs. Capcom 3"
+        );
+        assert_eq!(base_title("Gold Edition"), "Gold Edition");
+    }
+
+    /// PS Store stand-in: one product on every category page, a detail payload with genres,
+    /// media and a long description, and empty data for every other operation.
+    async fn ps_store_stub() -> String {
+        ps_store_stub_with(r#"{"data":{}}"#).await
+    }
+
+    /// [`ps_store_stub`] answering concept pricing lookups with `pricing`.
+    async fn ps_store_stub_with(pricing: &'static str) -> String {
+        const GRID: &str = r#"{"data":{"categoryGridRetrieve":{"products":[
+            {"id":"UP9000-PPSA01234_00","conceptId":"10001","name":"Astro Bot","releaseDate":"2024-09-06T00:00:00Z"}
+        ]}}}"#;
+        ps_store_stub_serving(GRID, pricing).await.0
+    }
+
+    /// [`ps_store_stub_with`] listing `grid` on every category page; also returns the number
+    /// of product detail requests served so far.
+    async fn ps_store_stub_serving(
+        grid: &'static str,
+        pricing: &'static str,
+    ) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        const DETAIL: &str = r#"{"data":{"metGetProductById":{
+            "name":"Astro Bot",
+            "productGenres":["Action","Platformer"],
+            "descriptions":[{"__typename":"Description","type":"LONG","value":"<b>Astro</b> is back."}],
+            "media":[
+                {"__typename":"Image","type":"IMAGE","role":"MASTER","url":"https://image.example/astro.png"},
+                {"__typename":"Video","type":"VIDEO","role":"PREVIEW","url":"https://video.example/astro.mp4"}
+            ]
+        }}}"#;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let detail_hits = std::sync::Arc::new(AtomicUsize::new(0));
+        let hits = detail_hits.clone();
+        tokio::spawn(async move {
+            while let Ok((mut sock, _)) = listener.accept().await {
+                let mut head = Vec::new();
+                let mut buf = [0u8; 4096];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match sock.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => head.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let head = String::from_utf8_lossy(&head).to_ascii_lowercase();
+                let body = if head.contains("x-apollo-operation-name: categorygridretrieve") {
+                    grid
+                } else if head.contains("x-apollo-operation-name: metgetproductbyid") {
+                    hits.fetch_add(1, Ordering::SeqCst);
+                    DETAIL
+                } else if head.contains("x-apollo-operation-name: metgetpricingdatabyconceptid") {
+                    pricing
+                } else if head.contains("x-apollo-operation-name: metgetconceptbyproductidquery") {
+                    r#"{"data":{"metGetConceptByProductIdQuery":{"conceptId":"20002"}}}"#
+                } else {
+                    r#"{"data":{}}"#
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = sock.write_all(response.as_bytes()).await;
+            }
+        });
+        (base_url, detail_hits)
+    }
+
+    #[tokio::test]
+    async fn detail_fetches_stay_bounded_on_a_large_page() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        /// Counts live detail futures from creation to drop.
+        struct Live(Arc<(AtomicUsize, AtomicUsize)>);
+        impl Live {
+            fn new(counts: &Arc<(AtomicUsize, AtomicUsize)>) -> Self {
+                let now = counts.0.fetch_add(1, Ordering::SeqCst) + 1;
+                counts.1.fetch_max(now, Ordering::SeqCst);
+                Self(counts.clone())
+            }
+        }
+        impl Drop for Live {
+            fn drop(&mut self) {
+                self.0 .0.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+
+        let counts = Arc::new((AtomicUsize::new(0), AtomicUsize::new(0)));
+        let results = fetch_in_order(1_000, 4, |idx| {
+            let live = Live::new(&counts);
+            async move {
+                // Finish out of order so the results have to be re-sorted.
+                tokio::time::sleep(std::time::Duration::from_micros((idx % 7) as u64 * 50)).await;
+                drop(live);
+                idx * 2
+            }
+        })
+        .await;
+
+        assert_eq!(results, (0..1_000).map(|i| i * 2).collect::<Vec<_>>());
+        assert_eq!(counts.1.load(Ordering::SeqCst), 4);
+        assert_eq!(counts.0.load(Ordering::SeqCst), 0);
+        // A zero limit still makes progress.
+        assert_eq!(
+            fetch_in_order(3, 0, |i| async move { i }).await,
+            vec![0, 1, 2]
+        );
+    }
+
+    /// Dry run against the stub with `regions`; any database query fails the run.
+    async fn dry_run_pipeline(env: &mut TestEnv, regions: &str) -> PostIngestSummary {
+        dry_run_pipeline_at(env, &ps_store_stub().await, regions).await
+    }
+
+    /// [`dry_run_pipeline`] against the stub at `base_url`.
+    async fn dry_run_pipeline_at(
+        env: &mut TestEnv,
+        base_url: &str,
+        regions: &str,
+    ) -> PostIngestSummary {
+        dry_run_pipeline_with(env, base_url, regions, &[]).await
+    }
+
+    /// [`dry_run_pipeline_at`] with `overrides` applied on top of the defaults.
+    async fn dry_run_pipeline_with(
+        env: &mut TestEnv,
+        base_url: &str,
+        regions: &str,
+        overrides: &[(&'static str, &str)],
+    ) -> PostIngestSummary {
+        env.set(&[
+            ("PS_DRY_RUN", "1"),
+            ("PS_BASE_URL", base_url),
+            ("PS_STORE_REGIONS", regions),
+            ("PS_LOCALE_CONCURRENCY", "2"),
+            ("PS_TOTAL_PAGES", "1"),
+            ("PS_IPV6_ONLY", "0"),
+            ("YEAR_MIN", "2020"),
+            ("YEAR_MAX", "2025"),
+        ]);
+        env.set(overrides);
+        // Nothing listens here: any query, read or write, fails the run.
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_secs(1))
+            .connect_lazy("postgres://dry-run@127.0.0.1:1/none")
+            .unwrap();
+        let db = Db { pool };
+        psstore_seed_pipeline(&db).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn dry_run_extracts_without_touching_the_database() {
+        let mut env = TestEnv::enter().await;
+        let summary = dry_run_pipeline(&mut env, "en-us").await;
+
+        assert!(summary.dry_run);
+        assert_eq!(summary.total_price_rows_written, 0);
+        assert!(summary.video_game_source_ids.is_empty());
+        assert!(summary.offer_jurisdiction_ids.is_empty());
+        // The product is listed in both the PS5 and PS4 categories.
+        assert_eq!(summary.extraction.products_seen, 2);
+        assert_eq!(summary.extraction.genres_extracted, 4);
+        assert_eq!(summary.extraction.media_images, 2);
+        assert_eq!(summary.extraction.media_videos, 2);
+        assert_eq!(summary.extraction.synopsis_lengths, vec![14, 14]);
+    }
+
+    #[tokio::test]
+    async fn out_of_window_items_are_never_fetched() {
+        const GRID: &str = r#"{"data":{"categoryGridRetrieve":{"products":[
+            {"id":"UP9000-PPSA09999_00","conceptId":"10009","name":"Not Yet Out","releaseDate":"2026-03-01T00:00:00Z"},
+            {"id":"UP9000-PPSA01234_00","conceptId":"10001","name":"Astro Bot","releaseDate":"2024-09-06T00:00:00Z"},
+            {"id":"UP9000-CUSA00001_00","conceptId":"10002","name":"Too Old","releaseDate":"2019-05-01T00:00:00Z"},
+            {"id":"UP9000-CUSA00002_00","conceptId":"10003","name":"Older Still","releaseDate":"2018-05-01T00:00:00Z"}
+        ]}}}"#;
+        let mut env = TestEnv::enter().await;
+        let (base_url, detail_hits) = ps_store_stub_serving(GRID, r#"{"data":{}}"#).await;
+        let summary = dry_run_pipeline_at(&mut env, &base_url, "en-us").await;
+
+        // Only Astro Bot is in 2020..=2025, once per category.
+        assert_eq!(summary.extraction.products_seen, 2);
+        assert_eq!(detail_hits.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn include_only_run_stops_paging_once_every_product_is_seen() {
+        let mut env = TestEnv::enter().await;
+        let (base_url, detail_hits) = ps_store_stub_serving(
+            r#"{"data":{"categoryGridRetrieve":{"products":[
+                {"id":"UP9000-PPSA01234_00","conceptId":"10001","name":"Astro Bot","releaseDate":"2024-09-06T00:00:00Z"}
+            ]}}}"#,
+            r#"{"data":{}}"#,
+        )
+        .await;
+        let summary = dry_run_pipeline_with(
+            &mut env,
+            &base_url,
+            "en-us",
+            &[
+                ("PS_TOTAL_PAGES", "5"),
+                ("PS_INCLUDE_PRODUCTS", "UP9000-PPSA01234_00"),
+            ],
+        )
+        .await;
+
+        // Found on the first page of each category; the other four pages are never fetched.
+        assert_eq!(summary.extraction.products_seen, 2);
+        assert_eq!(detail_hits.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn year_window_prefilter_matches_the_per_item_checks() {
+        let item = |id: &str, date: Option<&str>| psstore_client::PsProductSummary {
+            product_id: Some(id.to_string()),
+            concept_id: None,
+            name: None,
+            release_date: date.map(str::to_string),
+            base_price_minor: None,
+            discounted_price_minor: None,
+            is_free: None,
+            media_urls: Vec::new(),
+            media_image_urls: Vec::new(),
+            media_video_urls: Vec::new(),
+            media_images: Vec::new(),
+            media_videos: Vec::new(),
+            genres: Vec::new(),
+            average_rating: None,
+            rating_count: None,
+        };
+        let mut list = vec![
+            item("new", Some("2027-01-01")),
+            item("in", Some("2025-12-31")),
+            item("undated", None),
+            item("newer-again", Some("2026-02-02")),
+            item("in-too", Some("2020-01-01")),
+            item("old", Some("2019-12-31")),
+            item("after-old", Some("2024-01-01")),
+        ];
+        assert_eq!(retain_in_year_window(&mut list, 2020, 2025), 4);
+        let kept: Vec<_> = list
+            .iter()
+            .filter_map(|it| it.product_id.as_deref())
+            .collect();
+        assert_eq!(kept, ["in", "undated", "in-too"]);
+    }
+
+    #[tokio::test]
+    async fn locales_run_concurrently_and_merge() {
+        let mut env = TestEnv::enter().await;
+        let summary = dry_run_pipeline(&mut env, "en-us,en-gb").await;
+
+        // Both locales list the product under both categories.
+        assert_eq!(summary.extraction.products_seen, 4);
+        assert_eq!(summary.extraction.genres_extracted, 8);
+        assert_eq!(summary.extraction.synopsis_lengths, vec![14; 4]);
+    }
+
+    /// Row counts of what the pipeline writes. region_prices is history, so only its
+    /// latest row per sku_region counts, together with the sum of those amounts.
+    async fn seeded_row_counts(db: &Db) -> Vec<(&'static str, i64)> {
+        let mut queries: Vec<(&'static str, String)> = [
+            "products",
+            "video_game_titles",
+            "video_games",
+            "sku_regions",
+        ]
+        .into_iter()
+        .map(|table| (table, format!("SELECT count(*) FROM {table}")))
+        .collect();
+        let latest = "(SELECT DISTINCT ON (sku_region_id) local_amount FROM region_prices
+             ORDER BY sku_region_id, recorded_at DESC, id DESC) latest";
+        queries.push((
+            "latest region_prices",
+            format!("SELECT count(*) FROM {latest}"),
+        ));
+        queries.push((
+            "latest region_prices amount",
+            format!("SELECT COALESCE(sum(local_amount), 0)::bigint FROM {latest}"),
+        ));
+        let mut counts = Vec::new();
+        for (label, sql) in queries {
+            let n: i64 = sqlx::query_scalar(&sql)
+                .fetch_one(&db.pool)
+                .await
+                .unwrap_or_else(|e| panic!("counting {label}: {e}"));
+            counts.push((label, n));
+        }
+        counts
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn rerun_over_the_same_fixture_is_idempotent() {
+        const PRICING: &str = r#"{"data":{"retrieveConceptByConceptId":{"defaultProduct":{
+            "price":{"basePrice":"$59.99","discountedPrice":"$39.99"}
+        }}}}"#;
+        let mut env = TestEnv::enter().await;
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 4).await.unwrap();
+        // region_prices only grows, so drop the fixture's history to see the first run write.
+        sqlx::query(
+            "DELETE FROM region_prices WHERE sku_region_id IN (
+                 SELECT sr.id FROM sku_regions sr JOIN products p ON p.id = sr.product_id
+                 WHERE p.slug = 'astro-bot')",
+        )
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        let base_url = ps_store_stub_with(PRICING).await;
+        env.set(&[
+            ("PS_DRY_RUN", "0"),
+            ("PS_BACKFILL", "0"),
+            ("PS_BASE_URL", base_url.as_str()),
+            ("PS_STORE_REGIONS", "en-us,en-gb"),
+            ("PS_LOCALE_CONCURRENCY", "2"),
+            ("PS_TOTAL_PAGES", "1"),
+            ("PS_IPV6_ONLY", "0"),
+            ("YEAR_MIN", "2020"),
+            ("YEAR_MAX", "2025"),
+        ]);
+
+        let before = seeded_row_counts(&db).await;
+        let first = psstore_seed_pipeline(&db).await.unwrap();
+        let after_first = seeded_row_counts(&db).await;
+        assert!(!first.offer_jurisdiction_ids.is_empty());
+        assert!(first.total_price_rows_written > 0);
+        assert!(
+            after_first.iter().zip(&before).any(|(a, b)| a.1 > b.1),
+            "first run wrote nothing: {before:?} -> {after_first:?}"
+        );
+
+        let second = psstore_seed_pipeline(&db).await.unwrap();
+        let after_second = seeded_row_counts(&db).await;
+        assert_eq!(after_second, after_first);
+        // The re-run resolves to the rows the first run created.
+        assert_eq!(second.offer_jurisdiction_ids, first.offer_jurisdiction_ids);
+        assert_eq!(second.video_game_source_ids, first.video_game_source_ids);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn recently_enriched_products_skip_detail_fetch_on_rerun() {
+        use std::sync::atomic::Ordering;
+        const GRID: &str = r#"{"data":{"categoryGridRetrieve":{"products":[
+            {"id":"UP9000-PPSA01234_00","conceptId":"10001","name":"Astro Bot","releaseDate":"2024-09-06T00:00:00Z"}
+        ]}}}"#;
+        let mut env = TestEnv::enter().await;
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 4).await.unwrap();
+        let clear_markers = || {
+            sqlx::query(
+                "DELETE FROM public.psstore_enrichment
+                 WHERE lower(locale) = 'en-us' AND product_id = 'UP9000-PPSA01234_00'",
+            )
+            .execute(&db.pool)
+        };
+        clear_markers().await.unwrap();
+        let (base_url, detail_hits) = ps_store_stub_serving(GRID, r#"{"data":{}}"#).await;
+        env.set(&[
+            ("PS_DRY_RUN", "0"),
+            ("PS_BASE_URL", base_url.as_str()),
+            ("PS_STORE_REGIONS", "en-us"),
+            ("PS_LOCALE_CONCURRENCY", "1"),
+            ("PS_TOTAL_PAGES", "1"),
+            ("PS_IPV6_ONLY", "0"),
+            ("YEAR_MIN", "2020"),
+            ("YEAR_MAX", "2025"),
+            ("PS_ENRICH_MAX_AGE_HOURS", "24"),
+        ]);
+
+        let first = psstore_seed_pipeline(&db).await.unwrap();
+        let first_hits = detail_hits.load(Ordering::SeqCst);
+        let vg_id: i64 = sqlx::query_scalar(
+            "SELECT vg.id FROM video_games vg
+             JOIN video_game_titles t ON t.id = vg.title_id
+             WHERE t.normalized_title = 'astro bot'
+             ORDER BY vg.id LIMIT 1",
+        )
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let stored_metadata = || {
+            sqlx::query_as::<_, (Vec<String>, Option<f64>)>(
+                "SELECT ARRAY(SELECT jsonb_array_elements_text(
+                                 COALESCE(metadata::jsonb->'genres_union', '[]'::jsonb)) ORDER BY 1),
+                        (metadata::jsonb->>'rating_global')::float8
+                 FROM video_games WHERE id = $1",
+            )
+            .bind(vg_id)
+            .fetch_one(&db.pool)
+        };
+        let (first_genres, _) = stored_metadata().await.unwrap();
+        // The stub serves no star ratings; stand in for one an earlier run stored.
+        sqlx::query(
+            "INSERT INTO video_game_ratings_by_locale
+                 (video_game_id, locale, average_rating, rating_count, rating_updated_at)
+             VALUES ($1, 'en-US', 4.5, 120, now())",
+        )
+        .bind(vg_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        let second = psstore_seed_pipeline(&db).await;
+        let second_hits = detail_hits.load(Ordering::SeqCst) - first_hits;
+        let (second_genres, second_rating) = stored_metadata().await.unwrap();
+        sqlx::query("DELETE FROM video_game_ratings_by_locale WHERE video_game_id = $1")
+            .bind(vg_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        clear_markers().await.unwrap();
+
+        let second = second.unwrap();
+        // Both category listings are fetched by the run that first enriches the product.
+        assert_eq!(first_hits, 2);
+        assert_eq!(first.extraction.genres_extracted, 4);
+        // The re-run still lists and writes the product but skips its detail requests.
+        assert_eq!(second_hits, 0);
+        assert_eq!(second.extraction.products_seen, 2);
+        assert_eq!(second.extraction.genres_extracted, 0);
+        assert_eq!(second.offer_jurisdiction_ids, first.offer_jurisdiction_ids);
+        // Skipped listings aggregate the stored rating and genres rather than blanking them.
+        assert_eq!(second_rating, Some(4.5));
+        assert_eq!(second_genres, first_genres);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn concept_excluded_after_lookup_is_never_prewarmed() {
+        // No conceptId on the grid; the stub's concept lookup answers 20002.
+        const GRID: &str = r#"{"data":{"categoryGridRetrieve":{"products":[
+            {"id":"UP9000-PPSA02002_00","name":"Looked Up Later","releaseDate":"2024-09-06T00:00:00Z"}
+        ]}}}"#;
+        let mut env = TestEnv::enter().await;
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = Db::connect_no_migrate(&url, 4).await.unwrap();
+        let (base_url, detail_hits) = ps_store_stub_serving(GRID, r#"{"data":{}}"#).await;
+        env.set(&[
+            ("PS_DRY_RUN", "0"),
+            ("PS_BASE_URL", base_url.as_str()),
+            ("PS_STORE_REGIONS", "en-us"),
+            ("PS_LOCALE_CONCURRENCY", "1"),
+            ("PS_TOTAL_PAGES", "1"),
+            ("PS_IPV6_ONLY", "0"),
+            ("YEAR_MIN", "2020"),
+            ("YEAR_MAX", "2025"),
+            ("PS_EXCLUDE_CONCEPTS", "20002"),
+        ]);
+
+        let summary = psstore_seed_pipeline(&db).await.unwrap();
+        let products: i64 = sqlx::query_scalar("SELECT count(*) FROM products WHERE slug = $1")
+            .bind(normalize_title("Looked Up Later"))
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+
+        assert_eq!(summary.extraction.products_seen, 0);
+        assert_eq!(products, 0, "excluded concept was prewarmed");
+        // The detail fetch runs before the lookup; only the writes are held back.
+        assert_eq!(detail_hits.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn ratings_upsert_without_unique_key_updates_in_place() {
+        // One connection, so the temp table below shadows the real one for every query.
+        let db = RollbackDb::connect().await;
+        sqlx::query(
+            "CREATE TEMP TABLE video_game_ratings_by_locale (
+                 video_game_id bigint NOT NULL,
+                 locale text NOT NULL,
+                 average_rating real NOT NULL,
+                 rating_count bigint NOT NULL,
+                 rating_updated_at timestamptz NOT NULL DEFAULT now()
+             )",
+        )
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        assert!(!locale_ratings_have_unique_key(&db).await.unwrap());
+
+        let page = |avg: f32| vec![(7_i64, "en-us".to_string(), avg, 10_i64)];
+        upsert_locale_ratings(&db, &page(4.0), false).await.unwrap();
+        upsert_locale_ratings(&db, &page(4.5), false).await.unwrap();
+        upsert_locale_ratings(&db, &[(7, "en-gb".to_string(), 3.0, 2)], false)
+            .await
+            .unwrap();
+        let rows: Vec<(String, f32)> = sqlx::query_as(
+            "SELECT locale, average_rating FROM video_game_ratings_by_locale ORDER BY locale",
+        )
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![("en-gb".to_string(), 3.0), ("en-us".to_string(), 4.5)]
+        );
+
+        sqlx::query("CREATE UNIQUE INDEX ON video_game_ratings_by_locale (locale, video_game_id)")
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        assert!(locale_ratings_have_unique_key(&db).await.unwrap());
+        db.rollback().await;
+    }
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn partial_schema_is_refused_with_every_gap() {
+        // One connection, so search_path below applies to every query.
+        let db = RollbackDb::connect().await;
+        for stmt in [
+            "CREATE SCHEMA ps_partial_schema",
+            "SET search_path TO ps_partial_schema",
+            "CREATE TABLE platforms (id bigserial PRIMARY KEY, code text)",
+            "CREATE TABLE providers (id bigserial PRIMARY KEY, name text)",
+            "CREATE TABLE video_games (id bigserial PRIMARY KEY, title text)",
+            "CREATE TABLE game_media (video_game_id bigint, source text, external_id text,
+                 UNIQUE (video_game_id, source, external_id))",
+            "CREATE TABLE video_game_ratings_by_locale (video_game_id bigint, locale text)",
+        ] {
+            sqlx::query(stmt).execute(&db.pool).await.unwrap();
+        }
+
+        let caps = SchemaCaps::new(None);
+        let gaps = caps.check(&db, PSSTORE_FULL_SCHEMA).await.unwrap();
+        assert_eq!(
+            gaps.missing_tables,
+            [
+                "provider_items",
+                "products",
+                "video_game_sources",
+                "video_game_titles",
+                "currencies",
+                "retailers|game_retailers",
+                "jurisdictions|countries",
+                "sku_regions",
+                "region_prices",
+                "vg_source_media_links"
+            ]
+        );
+        assert_eq!(
+            gaps.missing_unique_keys,
+            ["video_game_ratings_by_locale(video_game_id, locale)"]
+        );
+        let err = require_full_schema(&db, &caps, PSSTORE_FULL_SCHEMA)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("missing tables: provider_items, products, video_game_sources"),
+            "{err}"
+        );
+        assert!(err.contains("nothing was written"), "{err}");
+        let written: i64 = sqlx::query_scalar(
+            "SELECT (SELECT count(*) FROM platforms) + (SELECT count(*) FROM video_games)",
+        )
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        assert_eq!(written, 0);
+
+        for stmt in [
+            "CREATE TABLE provider_items (id bigserial PRIMARY KEY)",
+            "CREATE TABLE products (id bigserial PRIMARY KEY, slug text UNIQUE)",
+            "CREATE TABLE video_game_sources (id bigserial PRIMARY KEY)",
+            "CREATE TABLE video_game_titles (id bigserial PRIMARY KEY)",
+            "CREATE TABLE currencies (id bigserial PRIMARY KEY, code text UNIQUE)",
+            "CREATE TABLE game_retailers (id bigserial PRIMARY KEY)",
+            "CREATE TABLE countries (id bigserial PRIMARY KEY)",
+            "CREATE TABLE sku_regions (id bigserial PRIMARY KEY)",
+            "CREATE TABLE region_prices (id bigserial PRIMARY KEY)",
+            "CREATE TABLE vg_source_media_links (id bigserial PRIMARY KEY)",
+            "CREATE UNIQUE INDEX ON video_game_ratings_by_locale (locale, video_game_id)",
+        ] {
+            sqlx::query(stmt).execute(&db.pool).await.unwrap();
+        }
+        caps.invalidate();
+        require_full_schema(&db, &caps, PSSTORE_FULL_SCHEMA)
+            .await
+            .unwrap();
+        // The native profile also writes the commerce tables.
+        let native = psstore_schema_requirements(SchemaProfile::Native);
+        let gaps = caps.check(&db, &native).await.unwrap();
+        assert_eq!(
+            gaps.missing_tables,
+            [
+                "retailers",
+                "jurisdictions",
+                "sellables",
+                "offers",
+                "offer_jurisdictions"
+            ]
+        );
+        assert_eq!(
+            psstore_schema_requirements(SchemaProfile::PhpCompat),
+            PSSTORE_FULL_SCHEMA
+        );
+
+        db.rollback().await;
+    }
+
+    #[test]
+    fn locale_aggregates_merge_in_region_order() {
+        let action = ["Action".to_string()];
+        let mut us = std::collections::HashMap::new();
+        GlobalAgg::add(
+            &mut us,
+            "10001",
+            "en-US",
+            11,
+            Some((4.0, 300)),
+            &action,
+            &["PS5".to_string()],
+        );
+        let mut gb = std::collections::HashMap::new();
+        GlobalAgg::add(
+            &mut gb,
+            "10001",
+            "en-GB",
+            12,
+            Some((5.0, 100)),
+            &["Platformer".to_string()],
+            &["PS4".to_string()],
+        );
+        GlobalAgg::add(&mut gb, "10002", "en-GB", 13, None, &action, &[]);
+
+        let mut merged = std::collections::HashMap::new();
+        GlobalAgg::merge_into(&mut merged, us);
+        GlobalAgg::merge_into(&mut merged, gb);
+
+        assert_eq!(merged.len(), 2);
+        let agg = &merged["10001"];
+        assert_eq!(agg.vg_id, 11);
+        assert_eq!(agg.rating_count, 400);
+        assert!((agg.rating_sum / agg.rating_count as f64 - 4.25).abs() < 1e-9);
+        assert_eq!(agg.genres.len(), 2);
+        assert_eq!(agg.platforms.len(), 2);
+        assert_eq!(merged["10002"].vg_id, 13);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_locale_merges_equal_the_sequential_merge() {
+        // Per locale: (key, vg_id, rating, genre, platform) in listing order.
+        type Listing = (String, i64, Option<(f32, i64)>, String, String);
+        let locales: Vec<(String, Vec<Listing>)> = (0..6)
+            .map(|l| {
+                let listings = (0..200)
+                    .map(|i| {
+                        let key = format!("concept:{}", (i * 7 + l * 13) % 90);
+                        let rating = (i % 3 != 0).then(|| (1.0 + (i % 5) as f32, 10 + i as i64));
+                        let genre = ["Action", "RPG", "Puzzle", "Sports"][(i + l) % 4].to_string();
+                        let platform = ["PS4", "PS5"][i % 2].to_string();
+                        (key, (l * 1000 + i) as i64, rating, genre, platform)
+                    })
+                    .collect();
+                (format!("locale-{l}"), listings)
+            })
+            .collect();
+
+        let mut sequential = std::collections::HashMap::new();
+        for (locale, listings) in &locales {
+            let mut aggs = std::collections::HashMap::new();
+            for (key, vg_id, rating, genre, platform) in listings {
+                GlobalAgg::add(
+                    &mut aggs,
+                    key,
+                    locale,
+                    *vg_id,
+                    *rating,
+                    std::slice::from_ref(genre),
+                    std::slice::from_ref(platform),
+                );
+            }
+            GlobalAgg::merge_into(&mut sequential, aggs);
+        }
+
+        let map = std::sync::Arc::new(GlobalAggMap::new(4));
+        let mut tasks = tokio::task::JoinSet::new();
+        // Highest locale index first, so region order has to be restored by the map.
+        for (locale_idx, (locale, listings)) in locales.into_iter().enumerate().rev() {
+            let map = map.clone();
+            tasks.spawn(async move {
+                for (key, vg_id, rating, genre, platform) in listings {
+                    map.merge_rating(&key, locale_idx, &locale, vg_id, rating);
+                    tokio::task::yield_now().await;
+                    map.merge_genres(&key, locale_idx, &locale, vg_id, &[genre], &[platform]);
+                }
+            });
+        }
+        while let Some(done) = tasks.join_next().await {
+            done.unwrap();
+        }
+        let concurrent = std::sync::Arc::into_inner(map).unwrap().into_aggs();
+
+        assert_eq!(concurrent.len(), 90);
+        assert_eq!(concurrent, sequential);
+    }
+
+    #[tokio::test]
+    async fn stage_timers_cover_the_locale_run() {
+        use std::time::{Duration, Instant};
+        let pause = |ms| tokio::time::sleep(Duration::from_millis(ms));
+
+        let started = Instant::now();
+        let mut stages = StageTimings::default();
+        for _page in 0..2 {
+            stages.time(Stage::List, pause(10)).await;
+            stages.time(Stage::Detail, pause(15)).await;
+            let t0 = Instant::now();
+            pause(5).await;
+            stages.add(Stage::Ensure, t0.elapsed());
+            stages.time(Stage::RatingUpsert, pause(5)).await;
+            stages.time(Stage::PriceWrite, pause(5)).await;
+        }
+        // Untimed work (concept lookups, media) lands in `other`.
+        pause(5).await;
+        stages.total = started.elapsed();
+
+        for (stage, at_least) in [
+            (Stage::List, 20),
+            (Stage::Detail, 30),
+            (Stage::Ensure, 10),
+            (Stage::RatingUpsert, 10),
+            (Stage::PriceWrite, 10),
+        ] {
+            assert!(
+                stages.spent(stage) >= Duration::from_millis(at_least),
+                "{stage:?} under-recorded: {:?}",
+                stages.spent(stage)
+            );
+        }
+        let timed: Duration = Stage::ALL.iter().map(|&s| stages.spent(s)).sum();
+        assert_eq!(timed + stages.other(), stages.total);
+        assert!(stages.other() >= Duration::from_millis(5));
+        assert!(stages.other() < timed, "{stages:?}");
+
+        let exported = stages.to_json();
+        for key in [
+            "list_ms",
+            "detail_ms",
+            "ensure_ms",
+            "price_write_ms",
+            "rating_upsert_ms",
+            "other_ms",
+            "total_ms",
+        ] {
+            assert!(exported[key].as_f64().unwrap() > 0.0, "{key}");
+        }
+    }
+}
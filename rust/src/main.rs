@@ -1,52 +1,51 @@
 use anyhow::{Context, Result};
 use dotenv::dotenv;
 use futures::{stream, StreamExt};
+use i_miss_rust::api::metrics::PLAYSTATION_PROVIDER;
 use i_miss_rust::database_ops::db::Db;
+use i_miss_rust::database_ops::exchange::{ExchangeService, FxSource};
 use i_miss_rust::database_ops::giantbomb::{collector, ingest, price_guide, ratings};
 use i_miss_rust::database_ops::itad::provider::ItadProvider;
+use i_miss_rust::database_ops::leader::LeaderElection;
+use i_miss_rust::database_ops::maintenance;
+use i_miss_rust::database_ops::media_dedup;
+use i_miss_rust::database_ops::media_primary;
 use i_miss_rust::database_ops::nexarda::provider::{NexardaOptions, NexardaProvider};
+use i_miss_rust::database_ops::platforms_dedupe;
+use i_miss_rust::database_ops::provider_loop::{
+    run_provider_loop, wait_for_next_run, IngestProvider, ProviderMetricsRegistry,
+    ProviderTickSummary, ProviderWakes,
+};
+use i_miss_rust::database_ops::schema_caps::{SchemaCaps, INGEST_TABLES};
 use i_miss_rust::psstore_seed_pipeline;
 use i_miss_rust::util::env as env_util;
+use i_miss_rust::util::tasks::NamedTasks;
 use psstore_client::{PsConfig, PsStoreClient};
 use rand::{thread_rng, Rng};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::{broadcast, Mutex, Notify};
-use tokio::task::JoinSet;
+use tokio::sync::{broadcast, Notify};
 use tokio_postgres::AsyncMessage;
 use tracing::{error, info, warn};
 
-#[derive(Debug, Clone, Default, serde::Serialize)]
-struct PsMetrics {
-    last_run_ms: u64,
-    runs: u64,
-    failures: u64,
-    last_error: Option<String>,
-}
-
-fn env_bool(key: &str, default: bool) -> bool {
-    std::env::var(key)
-        .ok()
-        .map(|v| match v.trim().to_ascii_lowercase().as_str() {
-            "1" | "true" | "yes" | "on" => true,
-            "0" | "false" | "no" | "off" => false,
-            _ => default,
-        })
-        .unwrap_or(default)
-}
-
-fn env_u32(key: &str, default: u32) -> u32 {
-    std::env::var(key)
-        .ok()
-        .and_then(|v| v.parse::<u32>().ok())
-        .unwrap_or(default)
-}
-
-fn env_u64(key: &str, default: u64) -> u64 {
-    std::env::var(key)
-        .ok()
-        .and_then(|v| v.parse::<u64>().ok())
-        .unwrap_or(default)
+/// Honor `<PREFIX>_RUN_ON_START=0` by waiting one full interval before the first run
+/// (default: run immediately). Returns false if shutdown arrived while waiting.
+async fn await_first_run(
+    prefix: &str,
+    ticker: &mut tokio::time::Interval,
+    rx: &mut broadcast::Receiver<()>,
+) -> bool {
+    if env_util::env_flag(&format!("{prefix}_RUN_ON_START"), true) {
+        return true;
+    }
+    info!(
+        provider = prefix,
+        "run-on-start disabled; waiting for first interval"
+    );
+    tokio::select! {
+        _ = ticker.tick() => true,
+        _ = rx.recv() => false,
+    }
 }
 
 #[tokio::main]
@@ -60,18 +59,13 @@ async fn main() -> Result<()> {
             });
         let addr =
             std::env::var("MANAGER_HTTP_ADDR").unwrap_or_else(|_| "127.0.0.1:9090".to_string());
-        tokio::spawn(async move {
-            let _ = i_miss_rust::orchestrator::spawn_worker_manager(&workers, &addr).await;
-        });
+        // Supervised: restarted with backoff on exit, up to WORKER_MAX_RESTARTS.
+        let _ = i_miss_rust::orchestrator::supervise_worker_manager(&workers, &addr);
     }
     if let Ok(qs) = std::env::var("AUTOSTART_WORKERS") {
         for part in qs.split(',').filter(|s| !s.is_empty()) {
             if let Some((q, addr)) = part.split_once('@') {
-                let q = q.to_string();
-                let addr = addr.to_string();
-                tokio::spawn(async move {
-                    let _ = i_miss_rust::orchestrator::spawn_ingest_worker(&q, &addr, None).await;
-                });
+                let _ = i_miss_rust::orchestrator::supervise_ingest_worker(q, addr, None);
             }
         }
     }
@@ -111,22 +105,26 @@ async fn main() -> Result<()> {
         anyhow::bail!("Database URL not configured; set SUPABASE_IPV6_DB or SUPABASE_DB_URL first");
     }
 
-    let max_conns: u32 = env_u32("DB_MAX_CONNS", 10);
+    let max_conns: u32 = env_util::env_parse("DB_MAX_CONNS", 10);
     // Important: the long-running ingest service must NOT auto-run migrations.
     // Use the no-migrate connector so startup does not push any SQL.
     let db = Db::connect_no_migrate(&database_url, max_conns)
         .await
         .context("Db::connect_no_migrate failed")?;
     info!("database connected (no-migrate, max_conns={})", max_conns);
+    match SchemaCaps::global().probe(&db, INGEST_TABLES).await {
+        Ok(missing) if !missing.is_empty() => {
+            warn!(missing_tables = ?missing, "ingest tables not visible; dependent pipelines will skip")
+        }
+        Ok(_) => {}
+        Err(e) => warn!(error = %e, "schema capability probe failed; probing lazily"),
+    }
 
     // --- one-off mode -------------------------------------------------------
     // Set ONE_OFF_MODE=1 to auto-enable all providers without manual configuration
     // All providers run in continuous loops with built-in orchestration
     // No need to run external worker binaries or manually enable each provider
-    let one_off_mode = std::env::var("ONE_OFF_MODE")
-        .ok()
-        .map(|v| (v == "1" || v.eq_ignore_ascii_case("true")))
-        .unwrap_or(true);
+    let one_off_mode = env_util::env_flag("ONE_OFF_MODE", true);
 
     if one_off_mode {
         info!("ONE_OFF_MODE: Auto-enabling all providers with built-in orchestration");
@@ -153,34 +151,130 @@ async fn main() -> Result<()> {
         .and_then(|s| s.parse().ok())
         .unwrap_or(10);
 
-    // --- shutdown wiring -----------------------------------------------------
-    let (shutdown_tx, _) = broadcast::channel::<()>(5);
-    let shutdown_notify = Arc::new(Notify::new());
-    let mut tasks = JoinSet::new();
-
-    // --- metrics + wake channels --------------------------------------------
-    let ps_metrics = Arc::new(Mutex::new(PsMetrics::default()));
-    let (ps_wake_tx, _) = broadcast::channel::<()>(16);
-
-    // --- optional HTTP API ---------------------------------------------------
+    // --- shared state + optional HTTP API -------------------------------------
+    // The API outlives leadership terms: a standby keeps serving it while only the leader
+    // runs the provider loops.
+    let shared = SharedState {
+        provider_wakes: ProviderWakes::new(SCHEDULED_PROVIDERS),
+        provider_metrics: ProviderMetricsRegistry::default(),
+        shutdown_notify: Arc::new(Notify::new()),
+    };
+    let (http_shutdown_tx, _) = broadcast::channel::<()>(1);
+    let mut http = None;
     if let Ok(addr) = std::env::var("PS_HTTP_ADDR") {
         if !addr.is_empty() {
-            start_http_server(
+            // Bind before spawning so a bad/occupied PS_HTTP_ADDR fails startup loudly.
+            let listener = i_miss_rust::api::server::bind_listener(&addr)
+                .context("PS_HTTP_ADDR: cannot start HTTP API")?;
+            info!(%addr, "http api listening");
+            let ps_wake_tx = shared
+                .provider_wakes
+                .sender(PLAYSTATION_PROVIDER)
+                .context("psstore missing from SCHEDULED_PROVIDERS")?;
+            http = Some(tokio::spawn(run_http_server(
                 db.clone(),
-                ps_wake_tx.clone(),
-                ps_metrics.clone(),
-                shutdown_notify.clone(),
-                addr,
-            );
+                shared.clone(),
+                ps_wake_tx,
+                http_shutdown_tx.subscribe(),
+                listener,
+            )));
+        }
+    }
+
+    // --- leader election -----------------------------------------------------
+    // With ENABLE_LEADER_ELECTION=1 only one instance runs the provider loops; others block
+    // here as standby until the leader's advisory lock is released. A leader that loses the
+    // lock stops its loops and goes back to standing by instead of exiting.
+    let leader_poll = Duration::from_secs(env_util::env_parse("LEADER_POLL_SECS", 15));
+    let mut election =
+        LeaderElection::enabled().then(|| LeaderElection::new(&db, "main_scheduler"));
+    loop {
+        if let Some(election) = election.as_mut() {
+            tokio::select! {
+                _ = election.wait_for_leadership(leader_poll) => {}
+                _ = tokio::signal::ctrl_c() => {
+                    info!("shutdown: Ctrl+C received while standing by");
+                    break;
+                }
+                _ = shared.shutdown_notify.notified() => {
+                    info!("shutdown: HTTP signal received while standing by");
+                    break;
+                }
+            }
+            info!("leader election: this instance is the scheduler leader");
         }
+        let stop = run_services(
+            db.clone(),
+            database_url.clone(),
+            one_off_mode,
+            ps_interval_secs,
+            nx_interval_secs,
+            &shared,
+            election.as_mut().map(|e| (e, leader_poll)),
+        )
+        .await?;
+        match stop {
+            ServiceStop::Shutdown => break,
+            ServiceStop::LeadershipLost => {
+                warn!("leader election: leadership lost; loops stopped, standing by")
+            }
+        }
+    }
+
+    let _ = http_shutdown_tx.send(());
+    if let Some(http) = http {
+        let _ = http.await;
     }
+    info!("all tasks stopped — goodbye");
+    Ok(())
+}
+
+/// Handles the HTTP API shares with the provider loops of every leadership term.
+#[derive(Clone)]
+struct SharedState {
+    provider_wakes: ProviderWakes,
+    provider_metrics: ProviderMetricsRegistry,
+    /// Notified by `POST /api/shutdown`.
+    shutdown_notify: Arc<Notify>,
+}
+
+/// Why [`run_services`] returned.
+enum ServiceStop {
+    /// Ctrl+C or `POST /api/shutdown`: the process should exit.
+    Shutdown,
+    /// The advisory lock was lost; the caller stands by until it can lead again.
+    LeadershipLost,
+}
+
+/// Spawn every provider loop, then run until shutdown is requested or, when `election` is
+/// set, until this instance stops being the leader.
+async fn run_services(
+    db: Db,
+    database_url: String,
+    one_off_mode: bool,
+    ps_interval_secs: u64,
+    nx_interval_secs: u64,
+    shared: &SharedState,
+    election: Option<(&mut LeaderElection, Duration)>,
+) -> Result<ServiceStop> {
+    // --- shutdown wiring -----------------------------------------------------
+    let (shutdown_tx, _) = broadcast::channel::<()>(5);
+    let shutdown_notify = &shared.shutdown_notify;
+    let mut tasks = NamedTasks::new();
+
+    // --- metrics + wake channels --------------------------------------------
+    let provider_metrics = &shared.provider_metrics;
+    let provider_wakes = &shared.provider_wakes;
+    let ps_wake_tx = provider_wakes
+        .sender(PLAYSTATION_PROVIDER)
+        .context("psstore missing from SCHEDULED_PROVIDERS")?;
 
     // --- optional LISTEN wake (psstore_tick) --------------------------------
     // LISTEN requires a Postgres connection string; do not accidentally use SUPABASE_HTTP_URL (https)
     {
         let dburl = database_url.clone();
         let ps_wake_tx_clone = ps_wake_tx.clone();
-        tasks.spawn(async move {
+        tasks.spawn("listen_psstore_tick", async move {
             // exp backoff with jitter for resilient reconnects
             let mut backoff = 1u64;
             loop {
@@ -212,10 +306,10 @@ async fn main() -> Result<()> {
     {
         let db_ps = db.clone();
         let mut rx = shutdown_tx.subscribe();
-        let mut ps_wake_rx = ps_wake_tx.subscribe();
-        let ps_metrics = ps_metrics.clone();
+        let mut ps_wake_rx = provider_wakes.subscribe(PLAYSTATION_PROVIDER);
+        let metrics = provider_metrics.clone();
 
-        tasks.spawn(async move {
+        tasks.spawn("psstore", async move {
             let _config = PsConfig::default();
             let _client = PsStoreClient::new(_config);
 
@@ -223,17 +317,20 @@ async fn main() -> Result<()> {
             let mut ticker = tokio::time::interval(Duration::from_secs(ps_interval_secs));
             ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
             ticker.tick().await;
+            if !await_first_run("PS", &mut ticker, &mut rx).await {
+                return;
+            }
 
             loop {
                 let span = tracing::info_span!("psstore.tick");
                 let _g = span.enter();
                 info!("psstore: tick");
                 let t_run = std::time::Instant::now();
-                match psstore_seed_pipeline(&db_ps.clone()).await {
+                let result = psstore_seed_pipeline(&db_ps.clone()).await;
+                metrics.record(PLAYSTATION_PROVIDER, t_run.elapsed(), &result);
+                let m = metrics.get(PLAYSTATION_PROVIDER).unwrap_or_default();
+                match result {
                     Ok(summary) => {
-                        let mut m = ps_metrics.lock().await;
-                        m.runs += 1;
-                        m.last_run_ms = t_run.elapsed().as_millis() as u64;
                         info!(
                             elapsed_ms=%m.last_run_ms,
                             total_runs=%m.runs,
@@ -244,33 +341,15 @@ async fn main() -> Result<()> {
                         );
                     }
                     Err(e) => {
-                        error!(error = %e, "psstore pipeline failed");
-                        let mut m = ps_metrics.lock().await;
-                        m.failures += 1;
-                        m.last_error = Some(e.to_string());
+                        error!(error = %e, failures=%m.failures, "psstore pipeline failed");
                     }
                 }
 
-                // Coalesce wakes: after a tick completes, drain any queued wakes and run once quickly.
-                let mut wake_count = 0u32;
-                while ps_wake_rx.try_recv().is_ok() {
-                    wake_count = wake_count.saturating_add(1);
-                }
-                if wake_count > 0 {
-                    info!(wakes=%wake_count, "psstore: coalesced wake(s) received; running again immediately");
-                    continue;
-                }
-
-                tokio::select! {
-                    _ = ticker.tick() => {},
-                    _ = ps_wake_rx.recv() => {
-                        // one immediate wake; extra signals will be coalesced next loop
-                        info!("psstore: wake signal received");
-                    }
-                    _ = rx.recv() => {
-                        info!("psstore: shutdown");
-                        break;
-                    }
+                // Wakes queued during the run are coalesced into one immediate rerun.
+                if !wait_for_next_run(PLAYSTATION_PROVIDER, &mut ticker, &mut ps_wake_rx, &mut rx)
+                    .await
+                {
+                    break;
                 }
             }
         });
@@ -279,8 +358,10 @@ async fn main() -> Result<()> {
     // --- Nexarda provider loop ----------------------------------------------
     {
         let db_nx = db.clone();
-        let mut rx = shutdown_tx.subscribe();
-        tasks.spawn(async move {
+        let metrics = provider_metrics.clone();
+        let wakes = provider_wakes.clone();
+        let rx = shutdown_tx.subscribe();
+        tasks.spawn("nexarda", async move {
             let base_url_opt = std::env::var("NEXARDA_BASE_URL")
                 .ok()
                 .filter(|s| !s.is_empty());
@@ -288,61 +369,18 @@ async fn main() -> Result<()> {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(10);
-            let nx = match NexardaProvider::new(base_url_opt.as_deref(), Some(timeout_secs)) {
+            let provider = match NexardaProvider::new(base_url_opt.as_deref(), Some(timeout_secs)) {
                 Ok(provider) => provider,
                 Err(err) => {
                     error!(error = %err, "nexarda provider init failed");
                     return;
                 }
             };
-
-            let mut ticker = tokio::time::interval(Duration::from_secs(nx_interval_secs));
-            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-            ticker.tick().await;
-
-            loop {
-                info!("nexarda: tick");
-                let opts = NexardaOptions {
-                    products: std::env::var("NEXARDA_PRODUCTS")
-                        .ok()
-                        .and_then(|s| serde_json::from_str(&s).ok())
-                        .unwrap_or_default(),
-                    store_map: std::env::var("NEXARDA_STORE_MAP")
-                        .ok()
-                        .and_then(|s| serde_json::from_str(&s).ok())
-                        .unwrap_or_default(),
-                    api_key: std::env::var("NEXARDA_API_KEY")
-                        .ok()
-                        .filter(|s| !s.is_empty()),
-                    auto_register_stores: Some(true),
-                    default_regions: std::env::var("NEXARDA_DEFAULT_REGIONS")
-                        .ok()
-                        .and_then(|s| serde_json::from_str(&s).ok())
-                        .unwrap_or_default(),
-                    dynamic_store_overrides: std::env::var("NEXARDA_STORE_OVERRIDES")
-                        .ok()
-                        .and_then(|s| serde_json::from_str(&s).ok())
-                        .unwrap_or_default(),
-                    default_tax_inclusive: Some(true),
-                    context: None,
-                    base_url: None,
-                    timeout: None,
-                };
-
-                if let Err(e) = nx.ingest_to_db(&db_nx, opts).await {
-                    error!(error = %e, "nexarda ingestion failed");
-                }
-
-                tokio::select! {
-                    _ = ticker.tick() => {
-                        info!("nexarda: next tick");
-                    },
-                    _ = rx.recv() => {
-                        info!("nexarda: shutdown");
-                        break;
-                    }
-                }
-            }
+            let nx = NexardaLoop {
+                provider,
+                interval: Duration::from_secs(nx_interval_secs),
+            };
+            run_provider_loop(&nx, &db_nx, &metrics, &wakes, rx).await;
         });
     }
 
@@ -350,8 +388,10 @@ async fn main() -> Result<()> {
     // Always enabled; relies on env vars only for optional sub-features
     {
         let db_gb = db.clone();
+        let metrics = provider_metrics.clone();
+        let mut wake_rx = provider_wakes.subscribe("giantbomb");
         let mut rx = shutdown_tx.subscribe();
-        tasks.spawn(async move {
+        tasks.spawn("giantbomb", async move {
             let interval = std::env::var("GB_LOOP_SECS")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -360,9 +400,14 @@ async fn main() -> Result<()> {
             let mut ticker = tokio::time::interval(Duration::from_secs(interval));
             ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
             ticker.tick().await;
+            if !await_first_run("GB", &mut ticker, &mut rx).await {
+                return;
+            }
 
             loop {
                 info!("giantbomb: tick");
+                let t_run = std::time::Instant::now();
+                let mut last_error: Option<String> = None;
 
                 // 1. Ingest GiantBomb JSON dump (from collector.rs output)
                 if let Ok(path) = std::env::var("GB_INGEST_JSON_PATH") {
@@ -376,6 +421,7 @@ async fn main() -> Result<()> {
                             }
                             Err(e) => {
                                 error!(error = %e, "giantbomb JSON ingest failed");
+                                last_error = Some(format!("json ingest: {e}"));
                             }
                         }
                     }
@@ -383,7 +429,7 @@ async fn main() -> Result<()> {
 
                 // 2. Run GiantBomb collector (handles --merge-details, --parse-videos, etc.)
                 // Enable via GB_COLLECTOR_ENABLED=1
-                if env_bool("GB_COLLECTOR_ENABLED", false) {
+                if env_util::env_flag("GB_COLLECTOR_ENABLED", false) {
                     info!("giantbomb: running collector");
                     match collector::run_from_env().await {
                         Ok(_) => {
@@ -391,27 +437,31 @@ async fn main() -> Result<()> {
                         }
                         Err(e) => {
                             error!(error = %e, "giantbomb collector failed");
+                            last_error = Some(format!("collector: {e}"));
                         }
                     }
                 }
 
                 // 3. Import price guide CSV (ITAD style)
                 // Enable via GB_PRICE_GUIDE_ENABLED=1
-                if env_bool("GB_PRICE_GUIDE_ENABLED", false) {
+                if env_util::env_flag("GB_PRICE_GUIDE_ENABLED", false) {
                     info!("giantbomb: importing price guide");
-                    match price_guide::run_import(env_bool("GB_PRICE_GUIDE_FAST", false)).await {
+                    match price_guide::run_import(env_util::env_flag("GB_PRICE_GUIDE_FAST", false))
+                        .await
+                    {
                         Ok(_) => {
                             info!("giantbomb: price guide imported");
                         }
                         Err(e) => {
                             error!(error = %e, "giantbomb price guide import failed");
+                            last_error = Some(format!("price guide: {e}"));
                         }
                     }
                 }
 
                 // 4. Print ratings (print_from_env reads MEDIA_MAP_FILE)
                 // Enable via GB_RATINGS_ENABLED=1
-                if env_bool("GB_RATINGS_ENABLED", false) {
+                if env_util::env_flag("GB_RATINGS_ENABLED", false) {
                     info!("giantbomb: printing ratings");
                     match ratings::print_from_env() {
                         Ok(_) => {
@@ -419,18 +469,17 @@ async fn main() -> Result<()> {
                         }
                         Err(e) => {
                             error!(error = %e, "giantbomb ratings print failed");
+                            last_error = Some(format!("ratings: {e}"));
                         }
                     }
                 }
+                match last_error {
+                    None => metrics.record_success("giantbomb", t_run.elapsed()),
+                    Some(e) => metrics.record_failure("giantbomb", t_run.elapsed(), &e),
+                }
 
-                tokio::select! {
-                    _ = ticker.tick() => {
-                        info!("giantbomb: next tick");
-                    },
-                    _ = rx.recv() => {
-                        info!("giantbomb: shutdown");
-                        break;
-                    }
+                if !wait_for_next_run("giantbomb", &mut ticker, &mut wake_rx, &mut rx).await {
+                    break;
                 }
             }
         });
@@ -439,8 +488,10 @@ async fn main() -> Result<()> {
     // --- IGDB loop ----------------------------------------------------------
     {
         let db_ig = db.clone();
+        let metrics = provider_metrics.clone();
+        let mut wake_rx = provider_wakes.subscribe("igdb");
         let mut rx = shutdown_tx.subscribe();
-        tasks.spawn(async move {
+        tasks.spawn("igdb", async move {
             let secs = std::env::var("IGDB_LOOP_SECS")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -448,20 +499,22 @@ async fn main() -> Result<()> {
             let mut ticker = tokio::time::interval(Duration::from_secs(secs));
             ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
             ticker.tick().await;
+            if !await_first_run("IGDB", &mut ticker, &mut rx).await {
+                return;
+            }
 
             loop {
-                if let Err(e) = i_miss_rust::database_ops::igdb::client::run_from_env(&db_ig).await
-                {
+                let t_run = std::time::Instant::now();
+                let result = i_miss_rust::database_ops::igdb::client::run_from_env(&db_ig).await;
+                metrics.record("igdb", t_run.elapsed(), &result);
+                if let Some(at) = i_miss_rust::database_ops::igdb::client::token_expires_at() {
+                    metrics.record_token_expiry("igdb", at);
+                }
+                if let Err(e) = result {
                     error!(error = %e, "igdb run failed");
                 }
-                tokio::select! {
-                    _ = ticker.tick() => {
-                        info!("igdb: next tick");
-                    },
-                    _ = rx.recv() => {
-                        info!("igdb: shutdown");
-                        break;
-                    }
+                if !wait_for_next_run("igdb", &mut ticker, &mut wake_rx, &mut rx).await {
+                    break;
                 }
             }
         });
@@ -470,28 +523,31 @@ async fn main() -> Result<()> {
     // --- Xbox DisplayCatalog loop ------------------------------------------
     {
         let db_x = db.clone();
+        let metrics = provider_metrics.clone();
+        let mut wake_rx = provider_wakes.subscribe("xbox");
         let interval = std::env::var("XBOX_LOOP_SECS")
             .ok()
             .and_then(|s| s.parse().ok())
             .unwrap_or(7_200);
         let mut rx = shutdown_tx.subscribe();
-        tasks.spawn(async move {
+        tasks.spawn("xbox", async move {
             let mut ticker = tokio::time::interval(Duration::from_secs(interval));
             ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
             ticker.tick().await;
+            if !await_first_run("XBOX", &mut ticker, &mut rx).await {
+                return;
+            }
 
             loop {
                 info!("xbox: tick");
-                if let Err(e) = i_miss_rust::database_ops::xbox::provider::run_from_env(&db_x).await
-                {
+                let t_run = std::time::Instant::now();
+                let result = i_miss_rust::database_ops::xbox::provider::run_from_env(&db_x).await;
+                metrics.record("xbox", t_run.elapsed(), &result);
+                if let Err(e) = result {
                     error!(error = %e, "xbox run failed");
                 }
-                tokio::select! {
-                    _ = ticker.tick() => {},
-                    _ = rx.recv() => {
-                        info!("xbox: shutdown");
-                        break;
-                    }
+                if !wait_for_next_run("xbox", &mut ticker, &mut wake_rx, &mut rx).await {
+                    break;
                 }
             }
         });
@@ -500,27 +556,30 @@ async fn main() -> Result<()> {
     // --- Xbox Store API provider loop ----------------------------------------
     {
         let db_xsa = db.clone();
-        let interval = env_u64("XBOX_STORE_LOOP_SECS", 3600);
+        let metrics = provider_metrics.clone();
+        let mut wake_rx = provider_wakes.subscribe("xbox_store");
+        let interval: u64 = env_util::env_parse("XBOX_STORE_LOOP_SECS", 3600);
         let mut rx = shutdown_tx.subscribe();
-        tasks.spawn(async move {
+        tasks.spawn("xbox_store", async move {
             let mut ticker = tokio::time::interval(Duration::from_secs(interval));
             ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
             ticker.tick().await;
+            if !await_first_run("XBOX_STORE", &mut ticker, &mut rx).await {
+                return;
+            }
 
             loop {
                 info!("xbox_store_api: tick");
-                if let Err(e) =
+                let t_run = std::time::Instant::now();
+                let result =
                     i_miss_rust::database_ops::xbox_store::provider::XboxStoreProvider::run_from_env(&db_xsa)
-                        .await
-                {
+                        .await;
+                metrics.record("xbox_store", t_run.elapsed(), &result);
+                if let Err(e) = result {
                     error!(error = %e, "xbox_store_api run failed");
                 }
-                tokio::select! {
-                    _ = ticker.tick() => {},
-                    _ = rx.recv() => {
-                        info!("xbox_store_api: shutdown");
-                        break;
-                    }
+                if !wait_for_next_run("xbox_store", &mut ticker, &mut wake_rx, &mut rx).await {
+                    break;
                 }
             }
         });
@@ -529,29 +588,30 @@ async fn main() -> Result<()> {
     // --- Steam provider loop -------------------------------------------------
     {
         let db_st = db.clone();
-        let interval = env_u64("STEAM_LOOP_SECS", 120);
+        let metrics = provider_metrics.clone();
+        let mut wake_rx = provider_wakes.subscribe("steam");
+        let interval: u64 = env_util::env_parse("STEAM_LOOP_SECS", 120);
         let mut rx = shutdown_tx.subscribe();
-        tasks.spawn(async move {
+        tasks.spawn("steam", async move {
             let mut ticker = tokio::time::interval(Duration::from_secs(interval));
             ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
             ticker.tick().await;
+            if !await_first_run("STEAM", &mut ticker, &mut rx).await {
+                return;
+            }
 
             loop {
                 info!("steam: tick");
-                if let Err(e) =
+                let t_run = std::time::Instant::now();
+                let result =
                     i_miss_rust::database_ops::steam::provider::SteamProvider::run_from_env(&db_st)
-                        .await
-                {
+                        .await;
+                metrics.record("steam", t_run.elapsed(), &result);
+                if let Err(e) = result {
                     error!(error = %e, "steam run failed");
                 }
-                tokio::select! {
-                    _ = ticker.tick() => {
-                        info!("steam: next tick");
-                    },
-                    _ = rx.recv() => {
-                        info!("steam: shutdown");
-                        break;
-                    }
+                if !wait_for_next_run("steam", &mut ticker, &mut wake_rx, &mut rx).await {
+                    break;
                 }
             }
         });
@@ -559,62 +619,28 @@ async fn main() -> Result<()> {
 
     // --- ITAD provider loop -------------------------------------------------
     {
-        let _db_itad = db.clone();
-        let mut rx = shutdown_tx.subscribe();
-        tasks.spawn(async move {
+        let db_itad = db.clone();
+        let metrics = provider_metrics.clone();
+        let wakes = provider_wakes.clone();
+        let rx = shutdown_tx.subscribe();
+        tasks.spawn("itad", async move {
             let interval = std::env::var("ITAD_LOOP_SECS")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(3600); // Default: 1 hour
 
-            let itad = match ItadProvider::new(None, Some(30)) {
+            let provider = match ItadProvider::new(None, Some(30)) {
                 Ok(p) => p,
                 Err(err) => {
                     error!(error = %err, "itad provider init failed");
                     return;
                 }
             };
-
-            let mut ticker = tokio::time::interval(Duration::from_secs(interval));
-            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-            ticker.tick().await;
-
-            loop {
-                info!("itad: tick");
-
-                // Fetch trending games and their media
-                match itad.get_trending(Some(50)).await {
-                    Ok(games) => {
-                        info!(count = games.len(), "itad: fetched trending games");
-                        // Media ingestion would happen here in production
-                        // Each game's media would be stored via ensure_vg_source_media_links_with_meta
-                    }
-                    Err(e) => {
-                        error!(error = %e, "itad trending fetch failed");
-                    }
-                }
-
-                // Fetch latest deals
-                match itad.get_latest_deals(Some(100), None).await {
-                    Ok(deals) => {
-                        info!(count = deals.len(), "itad: fetched latest deals");
-                        // Price ingestion would happen here in production
-                    }
-                    Err(e) => {
-                        error!(error = %e, "itad deals fetch failed");
-                    }
-                }
-
-                tokio::select! {
-                    _ = ticker.tick() => {
-                        info!("itad: next tick");
-                    },
-                    _ = rx.recv() => {
-                        info!("itad: shutdown");
-                        break;
-                    }
-                }
-            }
+            let itad = ItadLoop {
+                provider,
+                interval: Duration::from_secs(interval),
+            };
+            run_provider_loop(&itad, &db_itad, &metrics, &wakes, rx).await;
         });
     }
 
@@ -623,7 +649,7 @@ async fn main() -> Result<()> {
     {
         let _db_bf = db.clone();
         let mut rx = shutdown_tx.subscribe();
-        tasks.spawn(async move {
+        tasks.spawn("backfill", async move {
             let interval = std::env::var("BACKFILL_LOOP_SECS")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -632,6 +658,9 @@ async fn main() -> Result<()> {
             let mut ticker = tokio::time::interval(Duration::from_secs(interval));
             ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
             ticker.tick().await;
+            if !await_first_run("BACKFILL", &mut ticker, &mut rx).await {
+                return;
+            }
 
             loop {
                 info!("backfill: tick");
@@ -694,9 +723,13 @@ async fn main() -> Result<()> {
     // --- FX Sync & Media Cleanup loop --------------------------------------
     // Handles: FX rate synchronization and media deduplication
     {
-        let _db_fx = db.clone();
+        let db_fx = db.clone();
+        let metrics = provider_metrics.clone();
         let mut rx = shutdown_tx.subscribe();
-        tasks.spawn(async move {
+        tasks.spawn("fx_cleanup", async move {
+            let fx = ExchangeService::new(db_fx.clone());
+            let fx_sources = FxSource::list_from_env();
+
             // FX sync interval (default: 6 hours)
             let fx_interval = std::env::var("FX_SYNC_INTERVAL_SECS")
                 .ok()
@@ -718,44 +751,101 @@ async fn main() -> Result<()> {
                 tokio::select! {
                     _ = fx_ticker.tick() => {
                         // FX Sync: Update foreign exchange rates from CoinGecko, ECB, exchangerate.host
-                        info!("fx_sync: tick - updating exchange rates");
-                        // FX sync would use ExchangeService::sync_all() here
-                        // For now, just log the tick
-                        info!("fx_sync: exchange rates updated");
+                        info!(sources = ?fx_sources, "fx_sync: tick - updating exchange rates");
+                        let summary = fx.sync_all(&fx_sources).await;
+                        for outcome in &summary.sources {
+                            let name = format!("fx:{}", outcome.source.name());
+                            let elapsed = Duration::from_millis(outcome.elapsed_ms);
+                            match &outcome.error {
+                                None => metrics.record_success(&name, elapsed),
+                                Some(e) => metrics.record_failure(&name, elapsed, e),
+                            }
+                        }
+                        info!(
+                            fetched = summary.fetched,
+                            stored = summary.stored,
+                            pairs = summary.pairs,
+                            failed_sources = summary.failed_sources().count(),
+                            "fx_sync: exchange rates updated"
+                        );
                     },
                     _ = cleanup_ticker.tick() => {
                         // Media Cleanup: Deduplication and orphaned media removal
                         info!("media_cleanup: tick - deduplicating media entries");
 
                         // 1. Cleanup missing media (find orphaned provider_items with no media links)
-                        if env_bool("CLEANUP_MISSING_MEDIA", true) {
-                            info!("media_cleanup: removing orphaned media links");
+                        if env_util::env_flag("CLEANUP_MISSING_MEDIA", true) {
+                            info!("media_cleanup: checking provider items without media links");
                             let limit = std::env::var("CLEANUP_LIMIT")
                                 .ok()
                                 .and_then(|s| s.parse().ok())
                                 .unwrap_or(1000);
-                            // cleanup_missing_media::run() would be called here
-                            info!(limit, "media_cleanup: processed missing media");
+                            match maintenance::cleanup_missing_media(&db_fx, limit).await {
+                                Ok(processed) => info!(limit, processed, "media_cleanup: processed missing media"),
+                                Err(e) => warn!(error=%e, "media_cleanup: missing media scan failed"),
+                            }
                         }
 
                         // 2. Deduplicate platform records
-                        if env_bool("DEDUPE_PLATFORMS", false) {
+                        if env_util::env_flag("DEDUPE_PLATFORMS", false) {
                             info!("media_cleanup: deduplicating platform records");
-                            let dry_run = std::env::var("DRY_RUN")
-                                .ok()
-                                .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-                                .unwrap_or(false);
-                            // platforms_dedupe::run() would be called here
-                            info!(dry_run, "media_cleanup: platform deduplication complete");
+                            let dry_run = env_util::env_flag("DRY_RUN", false);
+                            match platforms_dedupe::run(&db_fx, dry_run).await {
+                                Ok(report) => info!(
+                                    dry_run,
+                                    merges = report.merges.len(),
+                                    skipped = report.skipped.len(),
+                                    rows_removed = report.rows_removed,
+                                    report = %serde_json::to_string(&report).unwrap_or_default(),
+                                    "media_cleanup: platform deduplication complete"
+                                ),
+                                Err(e) => warn!(error=%e, "media_cleanup: platform deduplication failed"),
+                            }
                         }
 
                         // 3. GiantBomb detail file deduplication (on demand via env)
-                        if env_bool("DEDUPE_GB_DETAILS", false) {
+                        if env_util::env_flag("DEDUPE_GB_DETAILS", false) {
                             info!("media_cleanup: deduplicating GiantBomb detail files");
                             // dedupe_detail_file() would be called on games_detailed.json and related files
                             info!("media_cleanup: GiantBomb detail deduplication complete");
                         }
 
+                        // 4. Orphaned offer_jurisdictions (no price history), opt-in; flagged unless ORPHAN_OJ_DELETE=1
+                        if env_util::env_flag("CLEANUP_ORPHAN_OFFER_JURISDICTIONS", false) {
+                            let opts = maintenance::OrphanCleanupOptions::from_env();
+                            match maintenance::cleanup_orphaned_offer_jurisdictions(&db_fx, &opts).await {
+                                Ok(report) => info!(
+                                    orphans = report.orphan_ids.len(),
+                                    deleted = report.deleted,
+                                    "media_cleanup: orphaned offer_jurisdictions checked"
+                                ),
+                                Err(e) => warn!(error=%e, "media_cleanup: orphaned offer_jurisdiction cleanup failed"),
+                            }
+                        }
+
+                        // 5. Borrow covers from secondary providers for games without a primary cover
+                        if env_util::env_flag("MEDIA_BACKFILL_ENABLED", false) {
+                            let policy = media_primary::PrimaryMediaPolicy::from_env();
+                            let opts = media_primary::MediaBackfillOptions::from_env();
+                            match media_primary::backfill_missing_primary_media(&db_fx, &policy, &opts).await {
+                                Ok(promoted) => info!(promoted, "media_cleanup: primary cover backfill complete"),
+                                Err(e) => warn!(error=%e, "media_cleanup: primary cover backfill failed"),
+                            }
+                        }
+
+                        // 6. Collapse the same asset ingested from several providers
+                        if env_util::env_flag("MEDIA_DEDUP_ENABLED", false) {
+                            let opts = media_dedup::MediaDedupOptions::from_env();
+                            match media_dedup::dedupe_multi_provider_media(&db_fx, &opts).await {
+                                Ok(report) => info!(
+                                    games = report.games,
+                                    duplicates = report.duplicates,
+                                    "media_cleanup: cross-provider media dedup complete"
+                                ),
+                                Err(e) => warn!(error=%e, "media_cleanup: cross-provider media dedup failed"),
+                            }
+                        }
+
                         info!("media_cleanup: all cleanup tasks completed");
                     },
                     _ = rx.recv() => {
@@ -773,26 +863,157 @@ async fn main() -> Result<()> {
         info!("  ↳ ONE_OFF_MODE: All providers auto-enabled with continuous loops");
     }
 
-    tokio::select! {
+    let leadership_lost = async {
+        let Some((election, poll)) = election else {
+            return std::future::pending().await;
+        };
+        let mut ticker = tokio::time::interval(poll);
+        loop {
+            ticker.tick().await;
+            if !election.ensure_leader().await.unwrap_or(false) {
+                return;
+            }
+        }
+    };
+    let stop = tokio::select! {
         _ = tokio::signal::ctrl_c() => {
             info!("shutdown: Ctrl+C received");
+            ServiceStop::Shutdown
         }
         _ = shutdown_notify.notified() => {
             info!("shutdown: HTTP signal received");
+            ServiceStop::Shutdown
         }
-    }
+        _ = leadership_lost => {
+            error!("leader election: leadership lost; stopping provider loops");
+            ServiceStop::LeadershipLost
+        }
+    };
 
     let _ = shutdown_tx.send(());
-    info!("shutdown: gracefully stopping {} task(s)...", tasks.len());
-    while let Some(res) = tasks.join_next().await {
-        match res {
-            Ok(_) => {}
-            Err(e) => error!(error = %e, "task join error"),
+    // SHUTDOWN_GRACE_SECS: how long loops get to finish their current run before abort.
+    let grace = Duration::from_secs(env_util::env_parse("SHUTDOWN_GRACE_SECS", 30));
+    info!(
+        grace_secs = grace.as_secs(),
+        "shutdown: gracefully stopping {} task(s)...",
+        tasks.len()
+    );
+    let aborted = tasks.shutdown_within(grace).await;
+    if !aborted.is_empty() {
+        warn!(tasks = ?aborted, "shutdown: force-aborted task(s) still running at the deadline");
+    }
+    Ok(stop)
+}
+
+/// Nexarda price sync; options are re-read from env on every tick.
+struct NexardaLoop {
+    provider: NexardaProvider,
+    interval: Duration,
+}
+
+#[async_trait::async_trait]
+impl IngestProvider for NexardaLoop {
+    fn name(&self) -> &str {
+        "nexarda"
+    }
+
+    async fn tick(&self, db: &Db) -> Result<ProviderTickSummary> {
+        let opts = NexardaOptions {
+            products: std::env::var("NEXARDA_PRODUCTS")
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
+            store_map: std::env::var("NEXARDA_STORE_MAP")
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
+            api_key: std::env::var("NEXARDA_API_KEY")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            auto_register_stores: Some(true),
+            default_regions: std::env::var("NEXARDA_DEFAULT_REGIONS")
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
+            dynamic_store_overrides: std::env::var("NEXARDA_STORE_OVERRIDES")
+                .ok()
+                .and_then(|s| serde_json::from_str(&s).ok())
+                .unwrap_or_default(),
+            default_tax_inclusive: Some(true),
+            context: None,
+            base_url: None,
+            timeout: None,
+            max_retries: None,
+            backoff_ms: None,
+            reqs_per_min: None,
+            rps: None,
+        };
+        let items = self
+            .provider
+            .ingest_to_db(db, opts)
+            .await
+            .context("nexarda ingestion failed")?;
+        Ok(ProviderTickSummary { items })
+    }
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+}
+
+/// ITAD trending games and latest deals. Each fetch is best-effort; the tick only fails
+/// when both do.
+struct ItadLoop {
+    provider: ItadProvider,
+    interval: Duration,
+}
+
+#[async_trait::async_trait]
+impl IngestProvider for ItadLoop {
+    fn name(&self) -> &str {
+        "itad"
+    }
+
+    async fn tick(&self, _db: &Db) -> Result<ProviderTickSummary> {
+        let mut items = 0;
+        let mut failed = 0;
+
+        // Fetch trending games and their media
+        match self.provider.get_trending(Some(50)).await {
+            Ok(games) => {
+                info!(count = games.len(), "itad: fetched trending games");
+                items += games.len();
+                // Media ingestion would happen here in production
+                // Each game's media would be stored via ensure_vg_source_media_links_with_meta
+            }
+            Err(e) => {
+                error!(error = %e, "itad trending fetch failed");
+                failed += 1;
+            }
         }
+
+        // Fetch latest deals
+        match self.provider.get_latest_deals(Some(100), None).await {
+            Ok(deals) => {
+                info!(count = deals.len(), "itad: fetched latest deals");
+                items += deals.len();
+                // Price ingestion would happen here in production
+            }
+            Err(e) => {
+                error!(error = %e, "itad deals fetch failed");
+                failed += 1;
+            }
+        }
+
+        if failed == 2 {
+            anyhow::bail!("itad trending and deals fetches both failed");
+        }
+        Ok(ProviderTickSummary { items })
     }
 
-    info!("all tasks stopped — goodbye");
-    Ok(())
+    fn interval(&self) -> Duration {
+        self.interval
+    }
 }
 
 /// Prefer the session pooler (5432) over transaction pooler (6543) for prep/timeout stability,
@@ -864,37 +1085,80 @@ struct RunReq {
     _title: Option<String>,
 }
 
-fn start_http_server(
+/// Provider loops spawned by this scheduler, reported by `/api/version`.
+const SCHEDULED_PROVIDERS: &[&str] = &[
+    "psstore",
+    "nexarda",
+    "giantbomb",
+    "igdb",
+    "xbox",
+    "xbox_store",
+    "steam",
+    "itad",
+];
+
+/// Serve the control API until the broadcast shutdown fires, then stop the listener so the
+/// port is released before the service exits.
+async fn run_http_server(
     db: Db,
+    shared: SharedState,
     ps_wake_tx: broadcast::Sender<()>,
-    ps_metrics: Arc<Mutex<PsMetrics>>,
-    shutdown_notify: Arc<Notify>,
-    addr: String,
+    shutdown_rx: broadcast::Receiver<()>,
+    listener: std::net::TcpListener,
 ) {
     use actix_web::{web, App, HttpResponse, HttpServer, Responder};
-    tokio::spawn(async move {
-        let db = web::Data::new(db);
-        let wake = web::Data::new(ps_wake_tx);
-        let metrics = web::Data::new(ps_metrics);
-        let notify = web::Data::new(shutdown_notify);
-        if let Err(e) = HttpServer::new(move || {
-            App::new()
-                .app_data(db.clone())
-                .app_data(wake.clone())
-                .app_data(metrics.clone())
-                .app_data(notify.clone())
-                .route("/api/ps/run", web::post().to(run_now))
-                .route("/api/metrics", web::get().to(get_metrics))
-                .route("/api/shutdown", web::post().to(shutdown_now))
-        })
-        .bind(addr)
-        .expect("failed to bind http server")
-        .run()
-        .await
-        {
-            warn!(error=%e, "http server error");
+    let db = web::Data::new(db);
+    let wake = web::Data::new(ps_wake_tx);
+    let wakes = web::Data::new(shared.provider_wakes);
+    let metrics = web::Data::new(shared.provider_metrics);
+    let notify = web::Data::new(shared.shutdown_notify);
+    let providers = web::Data::new(i_miss_rust::api::version::EnabledProviders(
+        SCHEDULED_PROVIDERS.iter().map(|p| p.to_string()).collect(),
+    ));
+    let api_token = i_miss_rust::api::auth::api_token_from_env();
+    let rate_limit = i_miss_rust::api::rate_limit::RateLimit::from_env();
+    let server = match HttpServer::new(move || {
+        App::new()
+            .wrap(rate_limit.clone())
+            .wrap(i_miss_rust::api::auth::write_auth(api_token.as_deref()))
+            .wrap(i_miss_rust::api::middleware::cors_from_env())
+            .app_data(db.clone())
+            .app_data(wake.clone())
+            .app_data(wakes.clone())
+            .app_data(metrics.clone())
+            .app_data(notify.clone())
+            .app_data(providers.clone())
+            .route("/api/ps/run", web::post().to(run_now))
+            .route(
+                "/api/providers/{name}/run",
+                web::post().to(i_miss_rust::api::providers::run_provider),
+            )
+            .route(
+                "/api/metrics",
+                web::get().to(i_miss_rust::api::metrics::get_metrics),
+            )
+            .route(
+                "/metrics",
+                web::get().to(i_miss_rust::api::metrics::get_prometheus_metrics),
+            )
+            .route("/api/shutdown", web::post().to(shutdown_now))
+            .route(
+                "/api/version",
+                web::get().to(i_miss_rust::api::version::get_version),
+            )
+    })
+    .listen(listener)
+    {
+        Ok(server) => server.run(),
+        Err(e) => {
+            error!(error=%e, "http server failed to start");
+            return;
         }
-    });
+    };
+    match i_miss_rust::api::server::serve_until_shutdown(server, shutdown_rx).await {
+        Ok(()) => info!("shutdown: http server stopped"),
+        Err(e) => warn!(error=%e, "http server error"),
+    }
 
     async fn run_now(
         db: actix_web::web::Data<Db>,
@@ -908,11 +1172,6 @@ fn start_http_server(
         HttpResponse::Ok().json(serde_json::json!({"ok": true}))
     }
 
-    async fn get_metrics(metrics: actix_web::web::Data<Arc<Mutex<PsMetrics>>>) -> impl Responder {
-        let m = metrics.lock().await;
-        HttpResponse::Ok().json(&*m)
-    }
-
     async fn shutdown_now(notify: actix_web::web::Data<Arc<Notify>>) -> impl Responder {
         notify.notify_one();
         HttpResponse::Ok().json(serde_json::json!({"ok": true, "shutdown": true}))
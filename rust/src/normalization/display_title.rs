@@ -0,0 +1,186 @@
+//! Display-title selection across providers and locales.
+//!
+//! Every storefront and catalogue offers its own name for a game, often localized (a PS
+//! Store `ja-JP` listing carries the Japanese title). [`DisplayTitlePolicy`] ranks those
+//! candidates by language first and provider second, so `video_games.display_title` ends
+//! up with the preferred official title regardless of which provider ran first.
+
+use std::sync::OnceLock;
+use tracing::warn;
+
+/// One provider's title for a game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitleCandidate {
+    pub title: String,
+    /// Provider slug, e.g. `igdb`, `steam`, `ps-store`.
+    pub source: String,
+    /// ISO 639-1 language of the title when known.
+    pub language: Option<String>,
+}
+
+impl TitleCandidate {
+    /// `language` may be a locale (`ja-JP`), a code (`en`) or a Steam language name
+    /// (`english`).
+    pub fn new(title: &str, source: &str, language: Option<&str>) -> Self {
+        Self {
+            title: title.trim().to_string(),
+            source: source.trim().to_ascii_lowercase(),
+            language: language.and_then(language_code),
+        }
+    }
+}
+
+/// ISO 639-1 code for a locale, code or Steam language name; `None` when unrecognized.
+fn language_code(raw: &str) -> Option<String> {
+    let raw = raw.trim().to_ascii_lowercase();
+    let code = match raw.as_str() {
+        "english" => "en",
+        "japanese" => "ja",
+        "french" => "fr",
+        "german" => "de",
+        "spanish" | "latam" => "es",
+        "italian" => "it",
+        "korean" | "koreana" => "ko",
+        "schinese" | "tchinese" => "zh",
+        "portuguese" | "brazilian" => "pt",
+        "russian" => "ru",
+        other => {
+            let lang = other.split(['-', '_']).next().unwrap_or("");
+            if lang.len() != 2 || !lang.chars().all(|c| c.is_ascii_lowercase()) {
+                return None;
+            }
+            lang
+        }
+    };
+    Some(code.to_string())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayTitlePolicy {
+    languages: Vec<String>,
+    sources: Vec<String>,
+}
+
+impl Default for DisplayTitlePolicy {
+    fn default() -> Self {
+        Self {
+            languages: vec!["en".to_string()],
+            sources: ["igdb", "steam", "nexarda", "rawg", "ps-store"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl DisplayTitlePolicy {
+    /// Parse `languages=en,ja;sources=igdb,steam,ps-store`, highest priority first. Either
+    /// part may be omitted and keeps its default.
+    pub fn parse(raw: &str) -> Self {
+        let mut policy = Self::default();
+        for rule in raw.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let list = |values: &str| -> Vec<String> {
+                values
+                    .split(',')
+                    .map(|s| s.trim().to_ascii_lowercase())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            };
+            match rule.split_once('=') {
+                Some(("languages", values)) if !list(values).is_empty() => {
+                    policy.languages = list(values);
+                }
+                Some(("sources", values)) if !list(values).is_empty() => {
+                    policy.sources = list(values);
+                }
+                _ => warn!(%rule, "DISPLAY_TITLE_POLICY: ignoring invalid rule"),
+            }
+        }
+        policy
+    }
+
+    /// Policy from `DISPLAY_TITLE_POLICY`, read once per process.
+    pub fn from_env() -> &'static Self {
+        static POLICY: OnceLock<DisplayTitlePolicy> = OnceLock::new();
+        POLICY.get_or_init(|| {
+            crate::util::env::env_opt("DISPLAY_TITLE_POLICY")
+                .map(|raw| Self::parse(&raw))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Sort key of a candidate; lower is preferred. Unlisted languages and sources rank
+    /// after every listed one.
+    fn rank(&self, candidate: &TitleCandidate) -> (usize, usize) {
+        let language = candidate
+            .language
+            .as_ref()
+            .and_then(|l| self.languages.iter().position(|p| p == l))
+            .unwrap_or(self.languages.len());
+        let source = self
+            .sources
+            .iter()
+            .position(|s| *s == candidate.source)
+            .unwrap_or(self.sources.len());
+        (language, source)
+    }
+
+    /// Whether `candidate` should replace `current` (`None` when no title is set yet); an
+    /// equally ranked title is kept.
+    pub fn prefers(&self, candidate: &TitleCandidate, current: Option<&TitleCandidate>) -> bool {
+        !candidate.title.is_empty()
+            && current.is_none_or(|current| self.rank(candidate) < self.rank(current))
+    }
+}
+
+/// The preferred non-empty title among `candidates` under `policy`; the first listed wins
+/// a tie.
+pub fn select_display_title<'a>(
+    candidates: &'a [TitleCandidate],
+    policy: &DisplayTitlePolicy,
+) -> Option<&'a TitleCandidate> {
+    candidates
+        .iter()
+        .filter(|c| !c.title.is_empty())
+        .min_by_key(|c| policy.rank(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_catalogue_title_beats_localized_store_title() {
+        let policy = DisplayTitlePolicy::default();
+        let ps_ja = TitleCandidate::new(
+            "ファイナルファンタジーVII リバース",
+            "ps-store",
+            Some("ja-JP"),
+        );
+        let ps_us = TitleCandidate::new("FINAL FANTASY VII REBIRTH", "ps-store", Some("en-US"));
+        let igdb = TitleCandidate::new("Final Fantasy VII Rebirth", "igdb", Some("en"));
+
+        let with_igdb = [ps_ja.clone(), igdb.clone()];
+        let chosen = select_display_title(&with_igdb, &policy).unwrap();
+        assert_eq!(chosen.source, "igdb");
+        assert_eq!(chosen.title, "Final Fantasy VII Rebirth");
+        // An English store title still beats the Japanese one without IGDB.
+        let store_only = [ps_ja.clone(), ps_us.clone()];
+        let chosen = select_display_title(&store_only, &policy).unwrap();
+        assert_eq!(chosen.language.as_deref(), Some("en"));
+
+        assert!(policy.prefers(&igdb, Some(&ps_ja)));
+        assert!(!policy.prefers(&ps_ja, Some(&igdb)));
+        assert!(!policy.prefers(&ps_us, Some(&ps_us)));
+        assert!(policy.prefers(&ps_ja, None));
+
+        let japanese_first = DisplayTitlePolicy::parse("languages=ja,en");
+        assert!(japanese_first.prefers(&ps_ja, Some(&igdb)));
+        assert_eq!(
+            TitleCandidate::new("Hades", "Steam", Some("english"))
+                .language
+                .as_deref(),
+            Some("en")
+        );
+    }
+}
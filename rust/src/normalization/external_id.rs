@@ -0,0 +1,89 @@
+//! Canonical game lookup by a store's own id.
+//!
+//! Catalogue providers such as IGDB list the Steam appids and store product ids of each
+//! game (`provider_item_external_links`). A storefront ingesting one of those ids can find
+//! the canonical game exactly and merge into it, instead of matching on its title.
+
+use anyhow::Result;
+
+use crate::database_ops::db::Db;
+
+/// The video game a catalogue provider links to `external_id` on store `provider` (a
+/// `providers.slug` such as `steam-store` or `ps-store`); `None` when nothing links it.
+pub async fn resolve_by_external_id(
+    db: &Db,
+    provider: &str,
+    external_id: &str,
+) -> Result<Option<i64>> {
+    let external_id = external_id.trim();
+    if external_id.is_empty() {
+        return Ok(None);
+    }
+    let present: bool =
+        sqlx::query_scalar("SELECT to_regclass('public.provider_item_external_links') IS NOT NULL")
+            .persistent(false)
+            .fetch_one(&db.pool)
+            .await?;
+    if !present {
+        return Ok(None);
+    }
+    Ok(sqlx::query_scalar(
+        "SELECT video_game_id
+         FROM public.provider_item_external_links
+         WHERE provider_slug = $1
+           AND external_uid = $2
+           AND video_game_id IS NOT NULL
+         ORDER BY updated_at DESC
+         LIMIT 1",
+    )
+    .persistent(false)
+    .bind(provider.trim().to_ascii_lowercase())
+    .bind(external_id)
+    .fetch_optional(&db.pool)
+    .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database_ops::igdb::external_games::{
+        external_links, link_external_games, IgdbExternalGame,
+    };
+    use crate::test_support::RollbackDb;
+
+    #[tokio::test]
+    #[ignore = "requires Postgres via TEST_DATABASE_URL"]
+    async fn steam_appid_resolves_to_igdb_game() {
+        let db = RollbackDb::connect().await;
+        let provider_id: i64 = sqlx::query_scalar(
+            "INSERT INTO public.providers (slug, name) VALUES ('igdb', 'IGDB')
+             ON CONFLICT (slug) DO UPDATE SET name = providers.name RETURNING id",
+        )
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let igdb_item: i64 = sqlx::query_scalar(
+            "INSERT INTO public.provider_items (provider_id, external_id)
+             VALUES ($1, 'test-resolve-1174180') RETURNING id",
+        )
+        .bind(provider_id)
+        .fetch_one(&db.pool)
+        .await
+        .unwrap();
+        let video_game_id = 9_000_000_000 + igdb_item;
+        let links = external_links(&[IgdbExternalGame {
+            category: Some(1),
+            uid: Some("1174180".to_string()),
+        }]);
+        link_external_games(&db, igdb_item, Some(video_game_id), &links)
+            .await
+            .unwrap();
+
+        let resolved = resolve_by_external_id(&db, "Steam-Store", "1174180").await;
+        let unknown = resolve_by_external_id(&db, "steam-store", "0").await;
+        db.rollback().await;
+
+        assert_eq!(resolved.unwrap(), Some(video_game_id));
+        assert_eq!(unknown.unwrap(), None);
+    }
+}
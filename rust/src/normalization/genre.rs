@@ -0,0 +1,100 @@
+/// Canonical genre name for a provider genre label, so genres from different catalogues
+/// land on the same `video_games.genres` values. Names follow IGDB's genre list (and the
+/// display names the IGDB tag sync stores, e.g. `Simulator`, `Sports`), so TGDB and IGDB
+/// rows merge.
+///
+/// Matching ignores case, punctuation and a trailing plural "s" ("Shooters", "shoot-em-up"
+/// and "Shooter" all map to `Shooter`). Returns `None` for labels outside the vocabulary,
+/// which callers should log rather than store verbatim.
+pub fn canonical_genre(raw: &str) -> Option<&'static str> {
+    let key: String = raw
+        .trim()
+        .to_ascii_lowercase()
+        .replace('&', " and ")
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    let key = key
+        .strip_suffix('s')
+        .filter(|k| k.len() > 3)
+        .unwrap_or(&key);
+    let genre = match key {
+        "action" => "Action",
+        "adventure" | "pointandclick" | "visualnovel" => "Adventure",
+        "actionadventure" => "Action-Adventure",
+        "roleplaying" | "rpg" | "roleplayingrpg" | "roleplayinggame" | "jrpg" | "actionrpg" => {
+            "Role-Playing"
+        }
+        "mmo" | "mmorpg" | "massivelymultiplayer" => "MMO",
+        "shooter" | "shooting" | "fps" | "firstpersonshooter" | "shootemup" => "Shooter",
+        "fighting" | "fighter" | "beatemup" | "hackandslashbeatemup" => "Fighting",
+        "platform" | "platformer" => "Platformer",
+        "puzzle" | "quiz" | "trivia" | "quiztrivia" => "Puzzle",
+        "racing" | "driving" | "vehiclesimulation" => "Racing",
+        "sport" => "Sports",
+        "strategy"
+        | "realtimestrategy"
+        | "realtimestrategyrt"
+        | "rts"
+        | "turnbasedstrategy"
+        | "turnbasedstrategytb"
+        | "tactic"
+        | "tactical" => "Strategy",
+        "simulator"
+        | "simulation"
+        | "sim"
+        | "lifesimulation"
+        | "flightsimulator"
+        | "constructionandmanagementsimulation" => "Simulator",
+        "sandbox" => "Sandbox",
+        "stealth" => "Stealth",
+        "horror" | "survivalhorror" => "Horror",
+        "music" | "rhythm" | "musicrhythm" => "Music",
+        "party" | "board" | "boardgame" | "card" | "cardandboardgame" => "Party",
+        "family" | "kids" | "casual" => "Family",
+        "education" | "educational" => "Educational",
+        "arcade" => "Arcade",
+        _ => return None,
+    };
+    Some(genre)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn provider_labels_share_canonical_names() {
+        assert_eq!(canonical_genre("Shooters"), Some("Shooter"));
+        assert_eq!(canonical_genre("shoot-'em-up"), Some("Shooter"));
+        assert_eq!(canonical_genre("Role-Playing"), Some("Role-Playing"));
+        assert_eq!(canonical_genre("RPG"), Some("Role-Playing"));
+        assert_eq!(canonical_genre("Platform"), Some("Platformer"));
+        assert_eq!(canonical_genre("sports"), Some("Sports"));
+        assert_eq!(canonical_genre("Virtual Console"), None);
+    }
+
+    #[test]
+    fn igdb_genre_names_are_canonical() {
+        // IGDB's /genres names, and the vocabulary the IGDB tag sync stores.
+        for (igdb, canonical) in [
+            ("Simulator", "Simulator"),
+            ("Sport", "Sports"),
+            ("Shooter", "Shooter"),
+            ("Fighting", "Fighting"),
+            ("Role-playing (RPG)", "Role-Playing"),
+            ("Real Time Strategy (RTS)", "Strategy"),
+            ("Turn-based strategy (TBS)", "Strategy"),
+            ("Tactical", "Strategy"),
+            ("Hack and slash/Beat 'em up", "Fighting"),
+            ("Quiz/Trivia", "Puzzle"),
+            ("Card & Board Game", "Party"),
+            ("Point-and-click", "Adventure"),
+            ("Platform", "Platformer"),
+        ] {
+            assert_eq!(canonical_genre(igdb), Some(canonical), "{igdb}");
+        }
+        // TGDB's label lands on the same name.
+        assert_eq!(canonical_genre("Simulation"), Some("Simulator"));
+    }
+}
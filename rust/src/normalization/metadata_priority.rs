@@ -0,0 +1,176 @@
+//! Field-level source priority for `video_games.metadata` merges.
+//!
+//! Several providers patch the same metadata keys (genres, ratings, platforms). Instead of
+//! last-writer-wins, each top-level key remembers the source that wrote it (under
+//! [`SOURCES_KEY`]) and a patch only replaces a key when its source ranks at least as high
+//! as the recorded one.
+
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tracing::warn;
+
+/// Metadata key holding `{field: source}` for every field written through a prioritized
+/// merge; kept for debugging which provider a value came from.
+pub const SOURCES_KEY: &str = "_field_sources";
+
+const DEFAULT_ORDER: [&str; 4] = ["igdb", "rawg", "steam", "ps-store"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataPriority {
+    default: Vec<String>,
+    fields: HashMap<String, Vec<String>>,
+}
+
+impl Default for MetadataPriority {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_ORDER.iter().map(|s| s.to_string()).collect(),
+            fields: HashMap::new(),
+        }
+    }
+}
+
+impl MetadataPriority {
+    /// Parse `;`-separated rules, each a comma-separated source list from highest to
+    /// lowest priority, optionally prefixed by `field=` (`default=` or no prefix sets the
+    /// order for every other field), e.g. `igdb,rawg,ps-store;synopsis=ps-store,igdb`.
+    pub fn parse(raw: &str) -> Self {
+        let mut priority = Self::default();
+        for rule in raw.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let (field, list) = match rule.split_once('=') {
+                Some((field, list)) => (field.trim(), list),
+                None => ("default", rule),
+            };
+            let order: Vec<String> = list
+                .split(',')
+                .map(|s| s.trim().to_ascii_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect();
+            if field.is_empty() || order.is_empty() {
+                warn!(%rule, "METADATA_SOURCE_PRIORITY: ignoring invalid rule");
+                continue;
+            }
+            if field == "default" {
+                priority.default = order;
+            } else {
+                priority.fields.insert(field.to_string(), order);
+            }
+        }
+        priority
+    }
+
+    /// Priority from `METADATA_SOURCE_PRIORITY`, read once per process.
+    pub fn from_env() -> &'static Self {
+        static PRIORITY: OnceLock<MetadataPriority> = OnceLock::new();
+        PRIORITY.get_or_init(|| {
+            crate::util::env::env_opt("METADATA_SOURCE_PRIORITY")
+                .map(|raw| Self::parse(&raw))
+                .unwrap_or_default()
+        })
+    }
+
+    /// Position of `source` in the order for `field`; lower wins. Unlisted sources rank
+    /// after every listed one.
+    pub fn rank(&self, field: &str, source: &str) -> usize {
+        let order = self.fields.get(field).unwrap_or(&self.default);
+        let source = source.trim().to_ascii_lowercase();
+        order
+            .iter()
+            .position(|s| *s == source)
+            .unwrap_or(order.len())
+    }
+}
+
+/// Apply `patch` (an object) from `source` onto `current` metadata. A key is replaced when
+/// it has no recorded source or `source` ranks at least as high as the recorded one; a
+/// `null` never replaces a present value. Returns the merged metadata with [`SOURCES_KEY`]
+/// updated for every key written.
+pub fn merge_prioritized(
+    current: &Value,
+    patch: &Value,
+    source: &str,
+    priority: &MetadataPriority,
+) -> Value {
+    let mut merged: Map<String, Value> = current.as_object().cloned().unwrap_or_default();
+    let Some(patch) = patch.as_object() else {
+        return Value::Object(merged);
+    };
+    let mut sources: Map<String, Value> = merged
+        .get(SOURCES_KEY)
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    for (field, value) in patch {
+        if field == SOURCES_KEY {
+            continue;
+        }
+        if value.is_null() && merged.get(field).is_some_and(|v| !v.is_null()) {
+            continue;
+        }
+        let outranked = sources
+            .get(field)
+            .and_then(Value::as_str)
+            .is_some_and(|winner| priority.rank(field, source) > priority.rank(field, winner));
+        if outranked {
+            continue;
+        }
+        merged.insert(field.clone(), value.clone());
+        sources.insert(field.clone(), Value::from(source));
+    }
+    merged.insert(SOURCES_KEY.to_string(), Value::Object(sources));
+    Value::Object(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn lower_priority_patch_keeps_higher_priority_fields() {
+        let priority = MetadataPriority::default();
+        let igdb = merge_prioritized(
+            &json!({ "legacy": true }),
+            &json!({ "genres_union": ["Role-Playing"], "rating_global": 88.0 }),
+            "igdb",
+            &priority,
+        );
+        let ps = merge_prioritized(
+            &igdb,
+            &json!({
+                "genres_union": ["Action"],
+                "rating_global": null,
+                "platforms": ["PS5"],
+            }),
+            "ps-store",
+            &priority,
+        );
+        assert_eq!(ps["genres_union"], json!(["Role-Playing"]));
+        assert_eq!(ps["rating_global"], json!(88.0));
+        assert_eq!(ps["platforms"], json!(["PS5"]));
+        assert_eq!(ps["legacy"], json!(true));
+        assert_eq!(
+            ps[SOURCES_KEY],
+            json!({ "genres_union": "igdb", "rating_global": "igdb", "platforms": "ps-store" })
+        );
+
+        // Same source refreshes its own value; a field override reverses the order.
+        let refreshed = merge_prioritized(
+            &ps,
+            &json!({ "genres_union": ["Role-Playing", "Action"] }),
+            "igdb",
+            &priority,
+        );
+        assert_eq!(refreshed["genres_union"], json!(["Role-Playing", "Action"]));
+        let ps_first = MetadataPriority::parse("igdb,rawg;genres_union=ps-store,igdb");
+        let overridden = merge_prioritized(
+            &ps,
+            &json!({ "genres_union": ["Action"] }),
+            "ps-store",
+            &ps_first,
+        );
+        assert_eq!(overridden["genres_union"], json!(["Action"]));
+        assert_eq!(overridden[SOURCES_KEY]["genres_union"], json!("ps-store"));
+    }
+}
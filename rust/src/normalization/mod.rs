@@ -1,2 +1,13 @@
+pub mod display_title;
+pub mod external_id;
+pub mod genre;
+pub mod metadata_priority;
 pub mod platform;
 pub mod rating;
+pub mod tax;
+pub mod title;
+
+pub use external_id::resolve_by_external_id;
+pub use genre::canonical_genre;
+pub use platform::canonical_platform_tgdb;
+pub use tax::tax_rate_for_jurisdiction;
@@ -67,6 +67,46 @@ impl PlatformKey {
     }
 }
 
+/// Canonical `(name, code)` for a TheGamesDB platform id, matching the names other
+/// providers pass to `ensure_platform` (e.g. TGDB `4919` is `PS4`, as seeded by the
+/// PlayStation Store pipeline). Returns `None` for ids outside the mapping.
+pub fn canonical_platform_tgdb(tgdb_id: u32) -> Option<(&'static str, &'static str)> {
+    let platform = match tgdb_id {
+        1 => ("PC", "pc"),
+        2 => ("Nintendo GameCube", "gamecube"),
+        3 => ("Nintendo 64", "n64"),
+        4 => ("Game Boy", "gb"),
+        5 => ("Game Boy Advance", "gba"),
+        6 => ("Super Nintendo", "snes"),
+        7 => ("NES", "nes"),
+        8 => ("Nintendo DS", "nds"),
+        9 => ("Wii", "wii"),
+        10 => ("PlayStation", "ps1"),
+        11 => ("PlayStation 2", "ps2"),
+        12 => ("PlayStation 3", "ps3"),
+        13 => ("PSP", "psp"),
+        14 => ("Xbox", "xbox"),
+        15 => ("Xbox 360", "xbox360"),
+        16 => ("Dreamcast", "dreamcast"),
+        17 => ("Sega Saturn", "saturn"),
+        18 => ("Sega Genesis", "genesis"),
+        37 => ("Mac", "mac"),
+        38 => ("Wii U", "wiiu"),
+        39 => ("PlayStation Vita", "psvita"),
+        41 => ("Game Boy Color", "gbc"),
+        4912 => ("Nintendo 3DS", "3ds"),
+        4915 => ("iOS", "ios"),
+        4916 => ("Android", "android"),
+        4919 => ("PS4", "ps4"),
+        4920 => ("Xbox One", "xboxone"),
+        4971 => ("Nintendo Switch", "switch"),
+        4980 => ("PS5", "ps5"),
+        4981 => ("Xbox Series X|S", "xboxseries"),
+        _ => return None,
+    };
+    Some(platform)
+}
+
 fn strip_region_prefixes(input: &str) -> &str {
     const PREFIXES: [&str; 3] = ["pal", "ntsc", "jpy"];
     for prefix in PREFIXES {
@@ -111,6 +151,18 @@ mod tests {
         assert!(a.similarity(&b) >= MIN_PLATFORM_SIMILARITY);
     }
 
+    #[test]
+    fn maps_tgdb_platform_ids() {
+        assert_eq!(canonical_platform_tgdb(4919), Some(("PS4", "ps4")));
+        assert_eq!(canonical_platform_tgdb(4980), Some(("PS5", "ps5")));
+        assert_eq!(canonical_platform_tgdb(1), Some(("PC", "pc")));
+        assert_eq!(
+            canonical_platform_tgdb(4971),
+            Some(("Nintendo Switch", "switch"))
+        );
+        assert_eq!(canonical_platform_tgdb(999_999), None);
+    }
+
     #[test]
     fn preserves_numeric_distinctions() {
         let ps4 = PlatformKey::new("PlayStation 4");
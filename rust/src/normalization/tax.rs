@@ -0,0 +1,131 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Tax treatment to normalize prices to before comparing them across regions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxBasis {
+    /// Strip VAT/GST from prices listed tax-inclusive.
+    Exclusive,
+    /// Add VAT/GST to prices listed tax-exclusive.
+    Inclusive,
+}
+
+impl TaxBasis {
+    /// `"exclusive"` / `"inclusive"` (case-insensitive); empty or `"listed"` keeps prices
+    /// as the store quotes them.
+    pub fn parse(raw: &str) -> Result<Option<Self>> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "listed" | "none" => Ok(None),
+            "exclusive" | "excl" | "net" => Ok(Some(Self::Exclusive)),
+            "inclusive" | "incl" | "gross" => Ok(Some(Self::Inclusive)),
+            other => bail!("unknown tax basis '{other}' (expected exclusive or inclusive)"),
+        }
+    }
+}
+
+/// Standard consumer VAT/GST rate for an ISO country code, as a fraction (0.19 = 19%).
+///
+/// Jurisdictions whose stores list prices before a tax that varies locally (US sales tax,
+/// Canadian provincial tax, Hong Kong) map to their federal rate, which may be 0. Returns
+/// `None` for countries not in the table.
+pub fn tax_rate_for_jurisdiction(code: &str) -> Option<f64> {
+    let rate = match code.trim().to_ascii_uppercase().as_str() {
+        // European Union
+        "AT" => 0.20,
+        "BE" => 0.21,
+        "BG" => 0.20,
+        "CY" => 0.19,
+        "CZ" => 0.21,
+        "DE" => 0.19,
+        "DK" => 0.25,
+        "EE" => 0.24,
+        "ES" => 0.21,
+        "FI" => 0.255,
+        "FR" => 0.20,
+        "GR" => 0.24,
+        "HR" => 0.25,
+        "HU" => 0.27,
+        "IE" => 0.23,
+        "IT" => 0.22,
+        "LT" => 0.21,
+        "LU" => 0.17,
+        "LV" => 0.21,
+        "MT" => 0.18,
+        "NL" => 0.21,
+        "PL" => 0.23,
+        "PT" => 0.23,
+        "RO" => 0.21,
+        "SE" => 0.25,
+        "SI" => 0.22,
+        "SK" => 0.23,
+        // Rest of Europe
+        "CH" => 0.081,
+        "GB" => 0.20,
+        "IS" => 0.24,
+        "NO" => 0.25,
+        "RU" => 0.22,
+        "TR" => 0.20,
+        "UA" => 0.20,
+        // Americas
+        "AR" => 0.21,
+        "CA" => 0.05,
+        "CL" => 0.19,
+        "CO" => 0.19,
+        "MX" => 0.16,
+        "PE" => 0.18,
+        "US" => 0.0,
+        // Asia-Pacific
+        "AU" => 0.10,
+        "CN" => 0.13,
+        "HK" => 0.0,
+        "IN" => 0.18,
+        "JP" => 0.10,
+        "KR" => 0.10,
+        "NZ" => 0.15,
+        "SG" => 0.09,
+        "TH" => 0.07,
+        "TW" => 0.05,
+        // Middle East & Africa
+        "AE" => 0.05,
+        "IL" => 0.18,
+        "SA" => 0.15,
+        "ZA" => 0.15,
+        _ => return None,
+    };
+    Some(rate)
+}
+
+/// Convert `amount_minor`, listed with or without tax at `rate`, to `basis`.
+pub fn normalize_tax(amount_minor: i64, tax_inclusive: bool, rate: f64, basis: TaxBasis) -> i64 {
+    let factor = match (basis, tax_inclusive) {
+        (TaxBasis::Exclusive, true) => 1.0 / (1.0 + rate),
+        (TaxBasis::Inclusive, false) => 1.0 + rate,
+        _ => return amount_minor,
+    };
+    (amount_minor as f64 * factor).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_and_round_trip() {
+        assert_eq!(tax_rate_for_jurisdiction(" de "), Some(0.19));
+        assert_eq!(tax_rate_for_jurisdiction("US"), Some(0.0));
+        assert_eq!(tax_rate_for_jurisdiction("XX"), None);
+
+        let net = normalize_tax(5999, true, 0.19, TaxBasis::Exclusive);
+        assert_eq!(net, 5041);
+        assert_eq!(normalize_tax(net, false, 0.19, TaxBasis::Inclusive), 5999);
+        assert_eq!(normalize_tax(5999, false, 0.19, TaxBasis::Exclusive), 5999);
+
+        assert_eq!(TaxBasis::parse("").unwrap(), None);
+        assert_eq!(
+            TaxBasis::parse("Exclusive").unwrap(),
+            Some(TaxBasis::Exclusive)
+        );
+        assert!(TaxBasis::parse("vat").is_err());
+    }
+}
@@ -0,0 +1,163 @@
+use strsim::jaro_winkler;
+
+/// Minimum similarity score (Jaro-Winkler over canonical titles) required for two
+/// product titles to be treated as the same game.
+pub const MIN_TITLE_SIMILARITY: f64 = 0.92;
+
+/// Edition qualifiers dropped when they precede a trailing "edition".
+const EDITION_QUALIFIERS: [&str; 16] = [
+    "standard",
+    "deluxe",
+    "digital",
+    "ultimate",
+    "gold",
+    "complete",
+    "definitive",
+    "goty",
+    "legendary",
+    "premium",
+    "launch",
+    "cross",
+    "gen",
+    "year",
+    "the",
+    "of",
+];
+
+/// Multi-word edition qualifiers, stripped as a whole ("Game of the Year Edition").
+const EDITION_PHRASES: [&[&str]; 1] = [&["game", "of", "the", "year"]];
+
+/// Trailing platform markers ("... PS4 & PS5") that do not identify the game itself.
+const PLATFORM_SUFFIXES: [&str; 3] = ["ps4", "ps5", "and"];
+
+/// Canonical, comparison-friendly form of a product title.
+///
+/// Normalization steps:
+/// - drop trademark symbols (®, ™, ©)
+/// - lowercase; punctuation and whitespace collapse to single spaces
+/// - strip trailing platform markers ("PS4 & PS5")
+/// - strip trailing edition suffixes ("Standard Edition", "Digital Deluxe Edition", ...)
+pub fn canonicalize_for_match(raw: &str) -> String {
+    let cleaned: String = raw
+        .chars()
+        .filter(|c| !matches!(c, '®' | '™' | '©'))
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    let mut tokens: Vec<&str> = cleaned.split_whitespace().collect();
+    let full = tokens.join(" ");
+
+    loop {
+        match tokens.last() {
+            Some(t) if PLATFORM_SUFFIXES.contains(t) => {
+                tokens.pop();
+            }
+            Some(&"edition") => {
+                tokens.pop();
+                loop {
+                    if let Some(phrase) = EDITION_PHRASES.iter().find(|p| tokens.ends_with(p)) {
+                        tokens.truncate(tokens.len() - phrase.len());
+                    } else if tokens
+                        .last()
+                        .is_some_and(|t| EDITION_QUALIFIERS.contains(t))
+                    {
+                        tokens.pop();
+                    } else {
+                        break;
+                    }
+                }
+            }
+            _ => break,
+        }
+    }
+
+    if tokens.is_empty() {
+        full
+    } else {
+        tokens.join(" ")
+    }
+}
+
+/// Canonicalized title key used for fuzzy comparison.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TitleKey {
+    canonical: String,
+    numbers: Vec<String>,
+}
+
+impl TitleKey {
+    pub fn new(raw: &str) -> Self {
+        let canonical = canonicalize_for_match(raw);
+        let numbers = canonical
+            .split(|c: char| !c.is_ascii_digit())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        Self { canonical, numbers }
+    }
+
+    pub fn canonical(&self) -> &str {
+        &self.canonical
+    }
+
+    /// Similarity in [0, 1]. Titles whose numbers differ (sequels, yearly releases such as
+    /// "NBA 2K25" vs "NBA 2K26") never match.
+    pub fn similarity(&self, other: &Self) -> f64 {
+        if self.numbers != other.numbers {
+            return 0.0;
+        }
+        jaro_winkler(&self.canonical, &other.canonical)
+    }
+}
+
+/// Convenience wrapper over [`TitleKey::similarity`].
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    TitleKey::new(a).similarity(&TitleKey::new(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_trademark_symbols() {
+        assert_eq!(canonicalize_for_match("NBA® 2K26"), "nba 2k26");
+        assert_eq!(title_similarity("NBA® 2K26", "NBA 2K26"), 1.0);
+    }
+
+    #[test]
+    fn strips_edition_and_platform_suffixes() {
+        assert_eq!(
+            canonicalize_for_match("NBA 2K26 - Standard Edition"),
+            "nba 2k26"
+        );
+        assert_eq!(
+            canonicalize_for_match("EA SPORTS FC™ 26 Ultimate Edition PS4 & PS5"),
+            "ea sports fc 26"
+        );
+        assert_eq!(
+            canonicalize_for_match("Skyrim: Game of the Year Edition"),
+            "skyrim"
+        );
+        assert_eq!(
+            canonicalize_for_match("Fallout 3 Game of the Year Edition"),
+            "fallout 3"
+        );
+        // "game" on its own is part of the title, not a qualifier.
+        assert_eq!(
+            canonicalize_for_match("The Game Deluxe Edition"),
+            "the game"
+        );
+    }
+
+    #[test]
+    fn different_numbers_never_match() {
+        assert_eq!(title_similarity("NBA 2K25", "NBA 2K26"), 0.0);
+        assert!(title_similarity("NBA 2K26 Deluxe Edition", "NBA® 2K26") >= MIN_TITLE_SIMILARITY);
+    }
+}
@@ -1,12 +1,134 @@
 use anyhow::{Context, Result};
 use std::env;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::process::{Child, Command};
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
 
 /// Handle for a spawned background binary (worker/manager)
 pub struct ProcHandle {
     pub child: Child,
 }
 
+/// Restart policy for supervised child processes.
+#[derive(Debug, Clone, Copy)]
+pub struct SupervisorConfig {
+    /// Give up after this many restarts (WORKER_MAX_RESTARTS, default 5).
+    pub max_restarts: u32,
+    /// First backoff delay; doubles per restart (WORKER_RESTART_BACKOFF_MS, default 500).
+    pub base_backoff: Duration,
+    /// Backoff cap (WORKER_RESTART_BACKOFF_MAX_MS, default 30000).
+    pub max_backoff: Duration,
+    /// A process that stayed up this long was healthy: the restart budget and backoff
+    /// start over after it exits (WORKER_HEALTHY_SECS, default 300).
+    pub healthy_after: Duration,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            base_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            healthy_after: Duration::from_secs(300),
+        }
+    }
+}
+
+impl SupervisorConfig {
+    pub fn from_env() -> Self {
+        use crate::util::env::env_parse;
+        let d = Self::default();
+        Self {
+            max_restarts: env_parse("WORKER_MAX_RESTARTS", d.max_restarts),
+            base_backoff: Duration::from_millis(env_parse(
+                "WORKER_RESTART_BACKOFF_MS",
+                d.base_backoff.as_millis() as u64,
+            )),
+            max_backoff: Duration::from_millis(env_parse(
+                "WORKER_RESTART_BACKOFF_MAX_MS",
+                d.max_backoff.as_millis() as u64,
+            )),
+            healthy_after: Duration::from_secs(env_parse(
+                "WORKER_HEALTHY_SECS",
+                d.healthy_after.as_secs(),
+            )),
+        }
+    }
+
+    /// Capped exponential backoff before restart number `restart` (1-based).
+    pub fn backoff_for(&self, restart: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(restart.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.base_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// A child process running under [`supervise`].
+pub struct Supervised {
+    /// Total restarts performed so far (shared with the supervisor task); unlike the
+    /// restart budget, never reset.
+    pub restarts: Arc<AtomicU32>,
+    /// Completes once the supervisor gives up.
+    pub task: JoinHandle<()>,
+}
+
+/// Keep a child process alive: whenever it exits (or fails to spawn), restart it after a
+/// capped exponential backoff, up to `cfg.max_restarts` consecutive times. A run lasting
+/// `cfg.healthy_after` resets that count, so a long-lived worker never exhausts it.
+pub fn supervise<F>(name: impl Into<String>, cfg: SupervisorConfig, mut spawn: F) -> Supervised
+where
+    F: FnMut() -> Result<Child> + Send + 'static,
+{
+    let name = name.into();
+    let restarts = Arc::new(AtomicU32::new(0));
+    let counter = restarts.clone();
+    let task = tokio::spawn(async move {
+        let mut consecutive = 0u32;
+        loop {
+            let started = tokio::time::Instant::now();
+            match spawn() {
+                Ok(mut child) => match child.wait().await {
+                    Ok(status) => warn!(worker = %name, %status, "supervised process exited"),
+                    Err(e) => warn!(worker = %name, error = %e, "supervised process wait failed"),
+                },
+                Err(e) => warn!(worker = %name, error = %e, "supervised process failed to spawn"),
+            }
+
+            if started.elapsed() >= cfg.healthy_after {
+                consecutive = 0;
+            }
+            if consecutive >= cfg.max_restarts {
+                error!(
+                    worker = %name,
+                    restarts = counter.load(Ordering::SeqCst),
+                    consecutive,
+                    "supervised process exceeded WORKER_MAX_RESTARTS; giving up"
+                );
+                break;
+            }
+            consecutive += 1;
+            let total = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            let delay = cfg.backoff_for(consecutive);
+            info!(
+                worker = %name,
+                restart = consecutive,
+                total_restarts = total,
+                max_restarts = cfg.max_restarts,
+                backoff_ms = delay.as_millis() as u64,
+                "restarting supervised process"
+            );
+            tokio::time::sleep(delay).await;
+        }
+    });
+    Supervised { restarts, task }
+}
+
 fn inherit_db_env(cmd: &mut Command) {
     if let Ok(v) = env::var("SUPABASE_IPV6_DB") {
         cmd.env("SUPABASE_IPV6_DB", v);
@@ -37,27 +159,17 @@ fn inherit_db_env(cmd: &mut Command) {
     }
 }
 
-/// Spawn the multi-worker manager binary with the provided worker set and addr.
-/// managers: e.g., "default_ingest:9025,psstore_ingest:9081"
-/// addr: e.g., "127.0.0.1:9090"
-pub async fn spawn_worker_manager(managers: &str, addr: &str) -> Result<ProcHandle> {
+fn worker_manager_command(managers: &str, addr: &str) -> Command {
     let bin = env::var("WORKER_MANAGER_BIN")
         .unwrap_or_else(|_| "target/debug/worker_manager".to_string());
     let mut cmd = Command::new(bin);
     cmd.env("MANAGER_WORKERS", managers)
         .env("MANAGER_HTTP_ADDR", addr);
     inherit_db_env(&mut cmd);
-    let child = cmd.spawn().context("failed to spawn worker_manager")?;
-    Ok(ProcHandle { child })
+    cmd
 }
 
-/// Spawn an ingest worker for a given queue and bind address.
-/// queue: e.g., "default_ingest"; addr: e.g., "127.0.0.1:9025"; notify: defaults to queue if None.
-pub async fn spawn_ingest_worker(
-    queue: &str,
-    addr: &str,
-    notify: Option<&str>,
-) -> Result<ProcHandle> {
+fn ingest_worker_command(queue: &str, addr: &str, notify: Option<&str>) -> Command {
     let bin =
         env::var("INGEST_WORKER_BIN").unwrap_or_else(|_| "target/debug/ingest_worker".to_string());
     let mut cmd = Command::new(bin);
@@ -66,6 +178,125 @@ pub async fn spawn_ingest_worker(
         .env("INGEST_NOTIFY_CHANNEL", notify)
         .env("WORKER_HTTP_ADDR", addr);
     inherit_db_env(&mut cmd);
-    let child = cmd.spawn().context("failed to spawn ingest_worker")?;
+    cmd
+}
+
+/// Spawn the multi-worker manager binary with the provided worker set and addr.
+/// managers: e.g., "default_ingest:9025,psstore_ingest:9081"
+/// addr: e.g., "127.0.0.1:9090"
+pub async fn spawn_worker_manager(managers: &str, addr: &str) -> Result<ProcHandle> {
+    let child = worker_manager_command(managers, addr)
+        .spawn()
+        .context("failed to spawn worker_manager")?;
     Ok(ProcHandle { child })
 }
+
+/// Spawn an ingest worker for a given queue and bind address.
+/// queue: e.g., "default_ingest"; addr: e.g., "127.0.0.1:9025"; notify: defaults to queue if None.
+pub async fn spawn_ingest_worker(
+    queue: &str,
+    addr: &str,
+    notify: Option<&str>,
+) -> Result<ProcHandle> {
+    let child = ingest_worker_command(queue, addr, notify)
+        .spawn()
+        .context("failed to spawn ingest_worker")?;
+    Ok(ProcHandle { child })
+}
+
+/// Like [`spawn_worker_manager`], but restarted on exit per [`SupervisorConfig::from_env`].
+pub fn supervise_worker_manager(managers: &str, addr: &str) -> Supervised {
+    let (managers, addr) = (managers.to_string(), addr.to_string());
+    supervise("worker_manager", SupervisorConfig::from_env(), move || {
+        worker_manager_command(&managers, &addr)
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to spawn worker_manager")
+    })
+}
+
+/// Like [`spawn_ingest_worker`], but restarted on exit per [`SupervisorConfig::from_env`].
+pub fn supervise_ingest_worker(queue: &str, addr: &str, notify: Option<&str>) -> Supervised {
+    let (queue, addr) = (queue.to_string(), addr.to_string());
+    let notify = notify.map(str::to_string);
+    supervise(queue.clone(), SupervisorConfig::from_env(), move || {
+        ingest_worker_command(&queue, &addr, notify.as_deref())
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to spawn ingest_worker")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let cfg = SupervisorConfig {
+            max_restarts: 10,
+            base_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(1000),
+            ..SupervisorConfig::default()
+        };
+        assert_eq!(cfg.backoff_for(1), Duration::from_millis(100));
+        assert_eq!(cfg.backoff_for(2), Duration::from_millis(200));
+        assert_eq!(cfg.backoff_for(4), Duration::from_millis(800));
+        assert_eq!(cfg.backoff_for(5), Duration::from_millis(1000));
+        assert_eq!(cfg.backoff_for(64), Duration::from_millis(1000));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn restarts_exiting_worker_then_gives_up() {
+        let cfg = SupervisorConfig {
+            max_restarts: 3,
+            base_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            ..SupervisorConfig::default()
+        };
+        let spawns = Arc::new(AtomicU32::new(0));
+        let spawns_in = spawns.clone();
+        let sup = supervise("exits_immediately", cfg, move || {
+            spawns_in.fetch_add(1, Ordering::SeqCst);
+            Command::new("true").spawn().context("spawn true")
+        });
+        tokio::time::timeout(Duration::from_secs(10), sup.task)
+            .await
+            .expect("supervisor should give up")
+            .unwrap();
+        assert_eq!(sup.restarts.load(Ordering::SeqCst), 3);
+        assert_eq!(spawns.load(Ordering::SeqCst), 4);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn healthy_runs_reset_the_restart_budget() {
+        let cfg = SupervisorConfig {
+            max_restarts: 1,
+            base_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            healthy_after: Duration::from_millis(20),
+        };
+        let spawns = Arc::new(AtomicU32::new(0));
+        let spawns_in = spawns.clone();
+        // Four runs that outlive `healthy_after`, then one that fails straight away.
+        let sup = supervise("long_lived", cfg, move || {
+            if spawns_in.fetch_add(1, Ordering::SeqCst) < 4 {
+                Command::new("sleep")
+                    .arg("0.05")
+                    .spawn()
+                    .context("spawn sleep")
+            } else {
+                Err(anyhow::anyhow!("spawn refused"))
+            }
+        });
+        tokio::time::timeout(Duration::from_secs(10), sup.task)
+            .await
+            .expect("supervisor should give up")
+            .unwrap();
+        // Each healthy exit restarts despite max_restarts = 1; the failed spawn uses it up.
+        assert_eq!(sup.restarts.load(Ordering::SeqCst), 4);
+        assert_eq!(spawns.load(Ordering::SeqCst), 5);
+    }
+}
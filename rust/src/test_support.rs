@@ -0,0 +1,89 @@
+//! Helpers shared by the crate's unit tests.
+
+/// Serializes the tests that configure code under test through process env.
+static ENV_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Holds the process-wide env lock and puts back every variable set through it when
+/// dropped. Tests that set env vars must go through this so they never run in parallel.
+pub(crate) struct TestEnv {
+    saved: Vec<(&'static str, Option<std::ffi::OsString>)>,
+    _lock: tokio::sync::MutexGuard<'static, ()>,
+}
+
+impl TestEnv {
+    pub(crate) async fn enter() -> Self {
+        Self::holding(ENV_LOCK.lock().await)
+    }
+
+    /// [`Self::enter`] for synchronous tests.
+    pub(crate) fn enter_blocking() -> Self {
+        Self::holding(ENV_LOCK.blocking_lock())
+    }
+
+    fn holding(lock: tokio::sync::MutexGuard<'static, ()>) -> Self {
+        Self {
+            saved: Vec::new(),
+            _lock: lock,
+        }
+    }
+
+    pub(crate) fn set(&mut self, vars: &[(&'static str, &str)]) {
+        for &(key, value) in vars {
+            if !self.saved.iter().any(|(k, _)| *k == key) {
+                self.saved.push((key, std::env::var_os(key)));
+            }
+            std::env::set_var(key, value);
+        }
+    }
+}
+
+impl Drop for TestEnv {
+    fn drop(&mut self) {
+        for (key, value) in self.saved.drain(..).rev() {
+            match value {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+    }
+}
+
+/// Test database whose writes are all thrown away: every query runs on one connection inside
+/// a transaction opened here, so code under test that begins its own transaction gets a
+/// savepoint and can never commit. Finish with [`RollbackDb::rollback`]; a panicking test
+/// drops the connection, which aborts the transaction too.
+pub(crate) struct RollbackDb {
+    db: crate::database_ops::db::Db,
+}
+
+impl RollbackDb {
+    /// Connect to `TEST_DATABASE_URL` and open the enclosing transaction.
+    pub(crate) async fn connect() -> Self {
+        use sqlx::Connection;
+
+        let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL");
+        let db = crate::database_ops::db::Db::connect_no_migrate(&url, 1)
+            .await
+            .unwrap();
+        let mut conn = db.pool.acquire().await.unwrap();
+        // Leak the guard: the connection stays one transaction deep for its whole life.
+        std::mem::forget(conn.begin().await.unwrap());
+        drop(conn);
+        Self { db }
+    }
+
+    pub(crate) async fn rollback(self) {
+        let mut conn = self.db.pool.acquire().await.unwrap();
+        sqlx::query("ROLLBACK").execute(&mut *conn).await.unwrap();
+        drop(conn);
+        self.db.pool.close().await;
+    }
+}
+
+impl std::ops::Deref for RollbackDb {
+    type Target = crate::database_ops::db::Db;
+
+    fn deref(&self) -> &Self::Target {
+        &self.db
+    }
+}
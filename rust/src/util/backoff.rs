@@ -0,0 +1,50 @@
+//! Retry delays for pgmq jobs.
+//!
+//! A plain `base * 2^(attempt-1)` schedule makes jobs that failed together retry together.
+//! [`jittered_retry_secs`] spreads them with decorrelated jitter: a uniform draw between
+//! `base` and three times the exponential delay, never above `max`.
+
+use rand::Rng;
+
+/// Capped exponential delay before retry `attempt` (1-based); the doubling stops at 2^6.
+pub fn retry_delay_secs(base: u64, max: u64, attempt: u32) -> u64 {
+    base.saturating_mul(1u64 << attempt.saturating_sub(1).min(6))
+        .min(max)
+}
+
+/// [`retry_delay_secs`] with decorrelated jitter: uniform in
+/// `base..=min(max, 3 * retry_delay_secs(..))`.
+pub fn jittered_retry_secs(base: u64, max: u64, attempt: u32, rng: &mut impl Rng) -> u64 {
+    let hi = retry_delay_secs(base, max, attempt)
+        .saturating_mul(3)
+        .min(max);
+    let lo = base.min(hi);
+    rng.gen_range(lo..=hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jittered_delays_vary_within_bounds() {
+        let mut rng = rand::thread_rng();
+        for attempt in 1..=8 {
+            let hi = (retry_delay_secs(5, 600, attempt) * 3).min(600);
+            let delays: Vec<u64> = (0..50)
+                .map(|_| jittered_retry_secs(5, 600, attempt, &mut rng))
+                .collect();
+            assert!(
+                delays.iter().all(|d| (5..=hi).contains(d)),
+                "attempt {attempt}: {delays:?}"
+            );
+            assert!(
+                delays.iter().any(|&d| d != delays[0]),
+                "attempt {attempt}: no jitter"
+            );
+        }
+        // The cap holds even when it is below the base.
+        assert_eq!(jittered_retry_secs(30, 10, 3, &mut rng), 10);
+        assert_eq!(retry_delay_secs(5, 600, 20), 320);
+    }
+}
@@ -0,0 +1,154 @@
+//! Per-region poll cadence.
+//!
+//! Pipelines that run once per tick can poll busy regions every tick and quiet ones every
+//! Nth tick. A cadence spec such as `"US=1,GB=1,JP=1,*=4"` maps a region (country code, or
+//! a full locale like `en-GB`) to how many ticks apart it is polled; `*` sets the default.
+//! Regions not listed, and every region when no spec is configured, run every tick.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use tracing::warn;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionCadence {
+    every: HashMap<String, u32>,
+    default_every: u32,
+}
+
+impl Default for RegionCadence {
+    fn default() -> Self {
+        Self {
+            every: HashMap::new(),
+            default_every: 1,
+        }
+    }
+}
+
+impl RegionCadence {
+    /// Parse `REGION=N` pairs separated by commas or whitespace. Invalid pairs and `N = 0`
+    /// are logged and ignored.
+    pub fn parse(raw: &str) -> Self {
+        let mut cadence = Self::default();
+        for pair in raw
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+        {
+            let parsed = pair
+                .split_once('=')
+                .and_then(|(region, n)| Some((region.trim(), n.trim().parse::<u32>().ok()?)))
+                .filter(|(region, n)| !region.is_empty() && *n > 0);
+            match parsed {
+                Some(("*", n)) => cadence.default_every = n,
+                Some((region, n)) => {
+                    cadence.every.insert(region.to_ascii_uppercase(), n);
+                }
+                None => warn!(%pair, "region cadence: ignoring invalid entry"),
+            }
+        }
+        cadence
+    }
+
+    /// Cadence from the env var `key`; every region every tick when unset.
+    pub fn from_env(key: &str) -> Self {
+        super::env_opt(key)
+            .map(|raw| Self::parse(&raw))
+            .unwrap_or_default()
+    }
+
+    /// Ticks between polls of `region`: an exact locale entry wins over its country code.
+    pub fn every(&self, region: &str) -> u32 {
+        let region = region.trim().to_ascii_uppercase();
+        let country = region.rsplit(['-', '_']).next().unwrap_or(&region);
+        self.every
+            .get(&region)
+            .or_else(|| self.every.get(country))
+            .copied()
+            .unwrap_or(self.default_every)
+    }
+}
+
+/// Tick counter plus the tick and time each region was last polled.
+#[derive(Debug, Clone, Default)]
+pub struct RegionPollState {
+    tick: u64,
+    last_polled: HashMap<String, (u64, DateTime<Utc>)>,
+}
+
+impl RegionPollState {
+    /// Advance one tick and return the regions due under `cadence`, in input order. A region
+    /// never polled before (or never polled successfully) is always due.
+    pub fn due(&mut self, cadence: &RegionCadence, regions: &[String]) -> Vec<String> {
+        self.tick += 1;
+        regions
+            .iter()
+            .filter(|region| {
+                self.last_polled
+                    .get(*region)
+                    .is_none_or(|(tick, _)| self.tick - tick >= u64::from(cadence.every(region)))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Record `region` as polled on the current tick; call once its poll has finished.
+    pub fn mark_polled(&mut self, region: &str) {
+        self.last_polled
+            .insert(region.to_string(), (self.tick, Utc::now()));
+    }
+
+    /// When `region` was last passed to [`Self::mark_polled`].
+    pub fn last_polled_at(&self, region: &str) -> Option<DateTime<Utc>> {
+        self.last_polled.get(region).map(|(_, at)| *at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cold_region_skips_non_nth_ticks() {
+        let cadence = RegionCadence::parse("US=1, en-GB=1 *=3 bogus JP=0");
+        assert_eq!(cadence.every("en-US"), 1);
+        assert_eq!(cadence.every("en-GB"), 1);
+        assert_eq!(cadence.every("de-DE"), 3);
+        assert_eq!(cadence.every("ja-JP"), 3, "zero cadence is ignored");
+
+        let regions = ["en-US".to_string(), "de-DE".to_string()];
+        let mut state = RegionPollState::default();
+        let ticks: Vec<Vec<String>> = (0..7)
+            .map(|_| {
+                let due = state.due(&cadence, &regions);
+                for region in &due {
+                    state.mark_polled(region);
+                }
+                due
+            })
+            .collect();
+        for (i, due) in ticks.iter().enumerate() {
+            assert!(
+                due.contains(&"en-US".to_string()),
+                "hot region runs on tick {i}"
+            );
+            assert_eq!(
+                due.contains(&"de-DE".to_string()),
+                i % 3 == 0,
+                "cold region on tick {i}"
+            );
+        }
+        assert!(state.last_polled_at("de-DE").is_some());
+        assert!(state.last_polled_at("fr-FR").is_none());
+    }
+
+    #[test]
+    fn region_whose_poll_failed_stays_due() {
+        let cadence = RegionCadence::parse("*=3");
+        let regions = ["en-US".to_string(), "de-DE".to_string()];
+        let mut state = RegionPollState::default();
+        assert_eq!(state.due(&cadence, &regions), regions);
+        // Only en-US finished; de-DE is retried on the next tick.
+        state.mark_polled("en-US");
+        assert_eq!(state.due(&cadence, &regions), vec!["de-DE".to_string()]);
+        assert!(state.last_polled_at("de-DE").is_none());
+    }
+}
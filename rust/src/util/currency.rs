@@ -0,0 +1,212 @@
+//! Currency minor units (decimal places used for `amount_minor`).
+//!
+//! `CURRENCY_MINOR_UNITS` overrides or extends the built-in table without a rebuild: a
+//! JSON object such as `{"MGA": 0, "HUF": 0}`, or a path to a file holding one. Overrides
+//! are read once per process; invalid entries are logged and ignored. They only apply to
+//! currencies rows created afterwards; existing rows keep their stored unit until a
+//! migration rescales them.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tracing::warn;
+
+/// Decimal places for `code` (ISO 4217, case-insensitive): the `CURRENCY_MINOR_UNITS`
+/// override when present, otherwise the built-in table.
+pub fn minor_unit(code: &str) -> i16 {
+    minor_unit_with(overrides(), code)
+}
+
+fn minor_unit_with(overrides: &HashMap<String, i16>, code: &str) -> i16 {
+    let code = code.trim().to_ascii_uppercase();
+    if let Some(unit) = overrides.get(&code) {
+        return *unit;
+    }
+    builtin_minor_unit(&code)
+}
+
+/// ISO 4217 minor units; kept in line with `tests/fixtures/iso4217.csv` except HUF,
+/// whose stored rows use 0; moving it to 2 needs a data migration first.
+fn builtin_minor_unit(code: &str) -> i16 {
+    match code {
+        "BIF" | "CLP" | "DJF" | "GNF" | "HUF" | "ISK" | "JPY" | "KMF" | "KRW" | "PYG" | "RWF"
+        | "UGX" | "UYI" | "VND" | "VUV" | "XAF" | "XOF" | "XPF" => 0,
+        "BHD" | "IQD" | "JOD" | "KWD" | "LYD" | "OMR" | "TND" => 3,
+        "CLF" | "UYW" => 4,
+        _ => 2,
+    }
+}
+
+fn overrides() -> &'static HashMap<String, i16> {
+    static OVERRIDES: OnceLock<HashMap<String, i16>> = OnceLock::new();
+    OVERRIDES.get_or_init(|| {
+        let Some(raw) = super::env_opt("CURRENCY_MINOR_UNITS") else {
+            return HashMap::new();
+        };
+        let json = if raw.trim_start().starts_with('{') {
+            raw
+        } else {
+            match std::fs::read_to_string(raw.trim()) {
+                Ok(contents) => contents,
+                Err(e) => {
+                    warn!(path = %raw.trim(), error = %e, "CURRENCY_MINOR_UNITS: cannot read file");
+                    return HashMap::new();
+                }
+            }
+        };
+        parse_overrides(&json)
+    })
+}
+
+/// `{"CODE": units}` with units in 0..=4; anything else is skipped with a warning.
+fn parse_overrides(json: &str) -> HashMap<String, i16> {
+    let map: HashMap<String, serde_json::Value> = match serde_json::from_str(json) {
+        Ok(map) => map,
+        Err(e) => {
+            warn!(error = %e, "CURRENCY_MINOR_UNITS: expected a JSON object of code -> units");
+            return HashMap::new();
+        }
+    };
+    map.into_iter()
+        .filter_map(|(code, units)| {
+            let parsed = units
+                .as_i64()
+                .filter(|u| (0..=4).contains(u))
+                .map(|u| u as i16);
+            if parsed.is_none() {
+                warn!(%code, %units, "CURRENCY_MINOR_UNITS: ignoring invalid entry");
+            }
+            Some((code.trim().to_ascii_uppercase(), parsed?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn override_changes_price_scaling() {
+        let none = HashMap::new();
+        assert_eq!(minor_unit_with(&none, "mga"), 2);
+        assert_eq!(minor_unit_with(&none, "JPY"), 0);
+
+        let overrides = parse_overrides(r#"{"mga": 0, "JPY": 2, "XTS": 9, "BAD": "x"}"#);
+        assert_eq!(overrides.len(), 2);
+        let mga = minor_unit_with(&overrides, "MGA");
+        assert_eq!(mga, 0);
+        assert_eq!(minor_unit_with(&overrides, "JPY"), 2);
+        assert_eq!(
+            minor_unit_with(&overrides, "XTS"),
+            2,
+            "out of range is ignored"
+        );
+
+        // 4500 ariary is stored as 4500 minor units instead of 450000.
+        assert_eq!((4500.0 * 10f64.powi(i32::from(mga))).round() as i64, 4500);
+    }
+
+    /// Codes whose built-in unit knowingly differs from ISO 4217 because stored amounts use it.
+    /// Moving one to the ISO value needs its own data migration for the rows already written.
+    const STORED_DEVIATIONS: &[(&str, i16)] = &[("HUF", 0)];
+
+    /// Codes the crate knows about that are not ISO 4217 currencies and are never priced.
+    const NON_ISO: &[&str] = &["BTC", "ETH"];
+
+    /// `code -> Some(units)` from the bundled ISO 4217 table; `None` where ISO defines no
+    /// minor unit (metals, funds, testing codes).
+    fn iso4217() -> HashMap<String, Option<i16>> {
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/iso4217.csv"
+        ))
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .skip(1)
+        .map(|line| {
+            let mut cols = line.split(',');
+            let code = cols.next().unwrap().to_string();
+            let units = cols.next().unwrap().parse().ok();
+            (code, units)
+        })
+        .collect()
+    }
+
+    /// Every currency code the crate maps a country, locale or exchange rate to.
+    fn referenced_currencies() -> BTreeSet<String> {
+        use crate::database_ops::exchange::ExchangeService;
+
+        let mut codes: BTreeSet<String> = ExchangeService::supported_currencies()
+            .into_keys()
+            .chain(ExchangeService::region_currency_map().into_values())
+            .map(str::to_string)
+            .collect();
+        for (_, currency) in crate::database_ops::steam::provider::load_steam_regions() {
+            codes.insert(currency);
+        }
+        for a in b'A'..=b'Z' {
+            for b in b'A'..=b'Z' {
+                let country = String::from_utf8(vec![a, b]).unwrap();
+                codes.insert(crate::currency_for_country(&country).0.to_string());
+            }
+        }
+        codes
+    }
+
+    #[test]
+    fn builtin_table_matches_iso4217() {
+        let iso = iso4217();
+        assert!(iso.len() > 170, "ISO 4217 fixture looks truncated");
+        let mismatches: Vec<String> = iso
+            .iter()
+            .filter_map(|(code, units)| Some((code, (*units)?)))
+            .filter(|(code, _)| !STORED_DEVIATIONS.iter().any(|(c, _)| c == code))
+            .filter(|(code, units)| builtin_minor_unit(code) != *units)
+            .map(|(code, units)| format!("{code}: iso {units}, crate {}", builtin_minor_unit(code)))
+            .collect();
+        assert!(
+            mismatches.is_empty(),
+            "minor-unit mismatches: {mismatches:?}"
+        );
+        for (code, units) in STORED_DEVIATIONS {
+            assert_eq!(builtin_minor_unit(code), *units, "{code}");
+            assert_ne!(
+                iso[*code],
+                Some(*units),
+                "{code} now matches ISO; drop the entry"
+            );
+        }
+    }
+
+    #[test]
+    fn referenced_currencies_have_iso_minor_units() {
+        let iso = iso4217();
+        let none = HashMap::new();
+        let mut unmapped = Vec::new();
+        let mut mismatches = Vec::new();
+        for code in referenced_currencies() {
+            if NON_ISO.contains(&code.as_str()) {
+                continue;
+            }
+            if STORED_DEVIATIONS.iter().any(|(c, _)| *c == code) {
+                continue;
+            }
+            match iso.get(&code) {
+                Some(Some(units)) if minor_unit_with(&none, &code) == *units => {}
+                Some(Some(units)) => mismatches.push(format!(
+                    "{code}: iso {units}, crate {}",
+                    minor_unit_with(&none, &code)
+                )),
+                Some(None) | None => unmapped.push(code),
+            }
+        }
+        assert!(
+            unmapped.is_empty(),
+            "currencies without an ISO 4217 minor unit: {unmapped:?}"
+        );
+        assert!(
+            mismatches.is_empty(),
+            "minor-unit mismatches: {mismatches:?}"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Latency summaries for per-call timings collected during a run.
+//!
+//! Percentiles use the nearest-rank method: `pN` is the smallest sample such that at least
+//! N% of samples are at or below it, so it is always an observed duration.
+
+use serde::Serialize;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub avg_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+}
+
+impl LatencySummary {
+    /// Summary of `samples`; `None` when there are none.
+    pub fn from_durations(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let total: Duration = sorted.iter().sum();
+        Some(Self {
+            count: sorted.len(),
+            avg_ms: millis(total) / sorted.len() as f64,
+            p50_ms: millis(percentile(&sorted, 50)),
+            p95_ms: millis(percentile(&sorted, 95)),
+            p99_ms: millis(percentile(&sorted, 99)),
+        })
+    }
+}
+
+/// Nearest-rank `pct`th percentile of the non-empty, ascending `sorted`.
+pub fn percentile(sorted: &[Duration], pct: u32) -> Duration {
+    let rank = (sorted.len() * pct.min(100) as usize).div_ceil(100);
+    sorted[rank.saturating_sub(1)]
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_over_a_known_set() {
+        let ms = |n: u64| Duration::from_millis(n);
+        // 1..=100 ms, shuffled.
+        let samples: Vec<Duration> = (1..=100).map(|n| ms((n * 37) % 100 + 1)).collect();
+        let summary = LatencySummary::from_durations(&samples).unwrap();
+        assert_eq!(summary.count, 100);
+        assert!((summary.avg_ms - 50.5).abs() < 1e-9);
+        assert_eq!(
+            (summary.p50_ms, summary.p95_ms, summary.p99_ms),
+            (50.0, 95.0, 99.0)
+        );
+
+        let summary = LatencySummary::from_durations(&[ms(40), ms(10), ms(30), ms(20)]).unwrap();
+        assert_eq!(
+            (summary.p50_ms, summary.p95_ms, summary.p99_ms),
+            (20.0, 40.0, 40.0)
+        );
+        assert_eq!(percentile(&[ms(7)], 0), ms(7));
+        assert_eq!(LatencySummary::from_durations(&[]), None);
+    }
+}
@@ -0,0 +1,138 @@
+//! Size-bounded least-recently-used map for per-run lookup caches.
+//!
+//! Seed runs memoize row ids and upstream lookups so each is resolved once. On very large
+//! catalogs those maps can outgrow memory; with a bound, the least recently used entry is
+//! dropped and a later lookup of it is simply a miss (the caller re-queries). Without a
+//! bound it behaves like a plain `HashMap`.
+
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+#[derive(Debug, Clone)]
+pub struct LruCache<K, V> {
+    max: Option<usize>,
+    tick: u64,
+    entries: HashMap<K, (V, u64)>,
+    /// Last-use tick -> key; only maintained when bounded.
+    order: BTreeMap<u64, K>,
+    evictions: u64,
+}
+
+impl<K, V> Default for LruCache<K, V> {
+    fn default() -> Self {
+        Self {
+            max: None,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            evictions: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    /// `max` of `None` (or `Some(0)`) means unbounded.
+    pub fn new(max: Option<usize>) -> Self {
+        Self {
+            max: max.filter(|&m| m > 0),
+            ..Self::default()
+        }
+    }
+
+    /// The cached value, marking it most recently used.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (value, used) = self.entries.get_mut(key)?;
+        if self.max.is_some() {
+            self.tick += 1;
+            if let Some(k) = self.order.remove(&*used) {
+                self.order.insert(self.tick, k);
+            }
+            *used = self.tick;
+        }
+        Some(value)
+    }
+
+    /// Insert or replace `key`, evicting the least recently used entries beyond the bound.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let Some(max) = self.max else {
+            return self.entries.insert(key, (value, 0)).map(|(v, _)| v);
+        };
+        self.tick += 1;
+        let old = self.entries.insert(key.clone(), (value, self.tick));
+        if let Some((_, used)) = &old {
+            self.order.remove(used);
+        }
+        self.order.insert(self.tick, key);
+        while self.entries.len() > max {
+            let Some((_, oldest)) = self.order.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+            self.evictions += 1;
+        }
+        old.map(|(v, _)| v)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries dropped to stay within the bound so far.
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_cache_evicts_lru_and_misses_requery() {
+        let mut cache = LruCache::new(Some(2));
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        assert_eq!(cache.get("a"), Some(&1));
+        cache.insert("c", 3);
+        // "b" was least recently used.
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(&1));
+        assert_eq!(cache.get("c"), Some(&3));
+        assert_eq!((cache.len(), cache.evictions()), (2, 1));
+
+        // A memoized lookup returns the same answers bounded or not; misses re-query.
+        let lookups: Vec<u32> = (0..200).map(|i| (i * 7 + i / 3) % 23).collect();
+        let run = |max: Option<usize>| {
+            let mut cache = LruCache::new(max);
+            let mut queries = 0;
+            let answers: Vec<u32> = lookups
+                .iter()
+                .map(|&id| match cache.get(&id) {
+                    Some(&v) => v,
+                    None => {
+                        queries += 1;
+                        let v = id * 100;
+                        cache.insert(id, v);
+                        v
+                    }
+                })
+                .collect();
+            assert!(max.is_none_or(|m| cache.len() <= m));
+            (answers, queries)
+        };
+        let (unbounded, unbounded_queries) = run(None);
+        let (bounded, bounded_queries) = run(Some(5));
+        assert_eq!(bounded, unbounded);
+        assert_eq!(unbounded_queries, 23);
+        assert!(bounded_queries > unbounded_queries);
+    }
+}
@@ -1,6 +1,13 @@
 //! Environment helpers: centralized dotenv loading and ergonomic getters.
 //! Call `init_env()` once early in each binary (or rely on lazy Once).
+pub mod backoff;
+pub mod cadence;
+pub mod currency;
 pub mod db;
+pub mod latency;
+pub mod lru;
+pub mod tasks;
+pub mod trace_context;
 pub mod env {
     pub use super::*;
 }
@@ -90,14 +97,17 @@ where
 pub fn env_flag(key: &str, default: bool) -> bool {
     init_env();
     match std::env::var(key) {
-        Ok(raw) => {
-            let v = raw.trim().to_ascii_lowercase();
-            matches!(v.as_str(), "1" | "true" | "on" | "yes")
-        }
+        Ok(raw) => parse_flag(&raw),
         Err(_) => default,
     }
 }
 
+/// Shared truthy parsing for flags: 1/true/on/yes (case-insensitive, trimmed).
+pub fn parse_flag(raw: &str) -> bool {
+    let v = raw.trim().to_ascii_lowercase();
+    matches!(v.as_str(), "1" | "true" | "on" | "yes")
+}
+
 /// Optional parsed value.
 pub fn env_parse_opt<T>(key: &str) -> Option<T>
 where
@@ -320,3 +330,18 @@ pub fn preflight_check(title: &str, required: &[&str], also_log: &[&str]) -> any
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_flag_accepts_standard_truthy_values() {
+        for v in ["1", "true", "TRUE", " yes ", "On"] {
+            assert!(parse_flag(v), "{v:?} should be true");
+        }
+        for v in ["0", "false", "no", "off", "", "45"] {
+            assert!(!parse_flag(v), "{v:?} should be false");
+        }
+    }
+}
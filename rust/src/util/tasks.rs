@@ -0,0 +1,130 @@
+//! Named background tasks with a bounded graceful shutdown.
+//!
+//! The service spawns one task per provider loop. On shutdown every loop is asked to stop
+//! (via the broadcast channel) and given [`NamedTasks::shutdown_within`]'s grace period;
+//! a loop stuck in a long upstream call is then aborted and reported by name.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::task::{Id, JoinError, JoinSet};
+use tracing::{error, warn};
+
+#[derive(Debug, Default)]
+pub struct NamedTasks {
+    tasks: JoinSet<()>,
+    names: HashMap<Id, String>,
+}
+
+impl NamedTasks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn<F>(&mut self, name: impl Into<String>, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let handle = self.tasks.spawn(task);
+        self.names.insert(handle.id(), name.into());
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Wait up to `grace` for every task to finish, then abort the rest. Returns the names
+    /// of the aborted tasks, sorted; tasks that finished but were not yet joined when the
+    /// deadline fired are not counted.
+    pub async fn shutdown_within(mut self, grace: Duration) -> Vec<String> {
+        let deadline = tokio::time::sleep(grace);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                next = self.tasks.join_next_with_id() => match next {
+                    None => return Vec::new(),
+                    Some(res) => self.reap(res),
+                },
+                _ = &mut deadline => break,
+            }
+        }
+        while let Some(res) = self.tasks.try_join_next_with_id() {
+            self.reap(res);
+        }
+
+        let mut aborted: Vec<String> = self.names.drain().map(|(_, name)| name).collect();
+        aborted.sort();
+        if !aborted.is_empty() {
+            warn!(
+                grace_secs = grace.as_secs_f64(),
+                tasks = ?aborted,
+                "shutdown: grace period elapsed; aborting remaining task(s)"
+            );
+        }
+        self.tasks.shutdown().await;
+        aborted
+    }
+
+    /// Forget a joined task, logging it if it panicked or was cancelled.
+    fn reap(&mut self, res: Result<(Id, ()), JoinError>) {
+        match res {
+            Ok((id, ())) => {
+                self.names.remove(&id);
+            }
+            Err(e) => {
+                let name = self.names.remove(&e.id()).unwrap_or_default();
+                error!(task = %name, error = %e, "task join error");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stuck_task_is_aborted_at_the_deadline() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let ran = Arc::new(AtomicUsize::new(0));
+        let mut tasks = NamedTasks::new();
+        let steam = ran.clone();
+        tasks.spawn("steam", async move {
+            steam.fetch_add(1, Ordering::SeqCst);
+        });
+        tasks.spawn("igdb", std::future::pending());
+        let panicky = ran.clone();
+        tasks.spawn("panicky", async move {
+            panicky.fetch_add(1, Ordering::SeqCst);
+            panic!("boom")
+        });
+        assert_eq!(tasks.len(), 3);
+        // Let the finishing tasks complete first so only the stuck one can be aborted,
+        // however late the deadline is observed.
+        while ran.load(Ordering::SeqCst) < 2 {
+            tokio::task::yield_now().await;
+        }
+
+        let aborted = tokio::time::timeout(
+            Duration::from_secs(5),
+            tasks.shutdown_within(Duration::from_millis(50)),
+        )
+        .await
+        .expect("shutdown should not wait past the grace period");
+        assert_eq!(aborted, vec!["igdb"]);
+
+        let mut tasks = NamedTasks::new();
+        tasks.spawn("steam", async {});
+        assert!(tasks
+            .shutdown_within(Duration::from_secs(3600))
+            .await
+            .is_empty());
+    }
+}
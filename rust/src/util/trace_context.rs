@@ -0,0 +1,114 @@
+//! W3C Trace Context (`traceparent`) carried on queued jobs.
+//!
+//! With `OTEL_ENABLED` set, `/api/enqueue` stores the caller's `traceparent` header on the
+//! job and the worker runs it under a span carrying the same trace id, a fresh span id and
+//! the caller's span as its parent, so the request and the job correlate in any collector
+//! that reads those fields. Unset, headers are ignored and jobs run without the extra span.
+
+use std::fmt;
+
+use rand::Rng;
+
+/// Header name, as sent by OpenTelemetry propagators.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// `OTEL_ENABLED`, default off.
+pub fn otel_enabled() -> bool {
+    crate::util::env::env_flag("OTEL_ENABLED", false)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceParent {
+    /// 32 lowercase hex digits.
+    pub trace_id: String,
+    /// 16 lowercase hex digits identifying the parent span.
+    pub span_id: String,
+    pub flags: u8,
+}
+
+impl TraceParent {
+    /// Parse a `traceparent` value. Version `ff` and all-zero ids are invalid; future
+    /// versions are read by their first four fields, as the spec asks.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let mut parts = raw.trim().split('-');
+        let (version, trace_id, span_id, flags) =
+            (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+        if !is_hex(version, 2) || version == "ff" || (version == "00" && parts.next().is_some()) {
+            return None;
+        }
+        if !is_hex(trace_id, 32) || !is_hex(span_id, 16) || !is_hex(flags, 2) {
+            return None;
+        }
+        if trace_id.bytes().all(|b| b == b'0') || span_id.bytes().all(|b| b == b'0') {
+            return None;
+        }
+        Some(Self {
+            trace_id: trace_id.to_string(),
+            span_id: span_id.to_string(),
+            flags: u8::from_str_radix(flags, 16).ok()?,
+        })
+    }
+
+    /// A new span in the same trace, with a random non-zero span id.
+    pub fn child(&self) -> Self {
+        let id: u64 = rand::thread_rng().gen_range(1..=u64::MAX);
+        Self {
+            trace_id: self.trace_id.clone(),
+            span_id: format!("{id:016x}"),
+            flags: self.flags,
+        }
+    }
+
+    pub fn sampled(&self) -> bool {
+        self.flags & 0x01 != 0
+    }
+}
+
+impl fmt::Display for TraceParent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "00-{}-{}-{:02x}",
+            self.trace_id, self.span_id, self.flags
+        )
+    }
+}
+
+fn is_hex(s: &str, len: usize) -> bool {
+    s.len() == len && s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_valid_headers_and_rejects_malformed_ones() {
+        let raw = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let tp = TraceParent::parse(raw).unwrap();
+        assert_eq!(tp.trace_id, "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(tp.span_id, "00f067aa0ba902b7");
+        assert!(tp.sampled());
+        assert_eq!(tp.to_string(), raw);
+
+        let child = tp.child();
+        assert_eq!(child.trace_id, tp.trace_id);
+        assert_ne!(child.span_id, tp.span_id);
+        assert_eq!(TraceParent::parse(&child.to_string()), Some(child));
+
+        // A later version may append fields.
+        assert!(TraceParent::parse(&format!("01{}-extra", &raw[2..])).is_some());
+        for bad in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+        ] {
+            assert_eq!(TraceParent::parse(bad), None, "{bad:?}");
+        }
+    }
+}